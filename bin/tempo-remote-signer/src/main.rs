@@ -0,0 +1,130 @@
+//! A remote signer holding a validator's consensus keys.
+//!
+//! Listens for connections from the validator's node and signs on its behalf,
+//! refusing to sign conflicting simplex votes or messages the node never signs.
+use std::{net::SocketAddr, path::PathBuf};
+
+use clap::Parser as _;
+use commonware_codec::DecodeExt as _;
+use commonware_cryptography::ed25519::PublicKey;
+use commonware_runtime::{Metrics as _, Runner as _};
+use eyre::WrapErr as _;
+use tempo_commonware_node_config::{NAMESPACE, Passphrase, SigningKey, SigningShare};
+use tempo_remote_signer::ServerConfig;
+use tracing_subscriber::EnvFilter;
+
+/// Holds a validator's consensus keys and signs on behalf of its node.
+#[derive(Debug, clap::Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// The socket address to listen on for the node.
+    #[arg(long, default_value = "127.0.0.1:8100")]
+    listen_address: SocketAddr,
+
+    /// The file containing the ed25519 key the signer authenticates itself
+    /// with to the node.
+    #[arg(long, value_name = "FILE")]
+    identity_key: PathBuf,
+
+    /// The hex-encoded ed25519 public key of a node allowed to connect. Can be
+    /// passed multiple times.
+    #[arg(long = "allowed-client", value_name = "PUBLIC_KEY", value_parser = parse_public_key, required = true)]
+    allowed_clients: Vec<PublicKey>,
+
+    /// The file containing the validator's ed25519 signing key.
+    #[arg(long, value_name = "FILE")]
+    signing_key: PathBuf,
+
+    /// The file containing the validator's share of the bls12-381 threshold
+    /// signing key.
+    #[arg(long, value_name = "FILE")]
+    signing_share: Option<PathBuf>,
+
+    /// The file containing the passphrase to decrypt the keys if they are
+    /// stored in keystores.
+    #[arg(long, value_name = "FILE")]
+    keystore_passphrase_file: Option<PathBuf>,
+
+    /// The directory to persist signed votes in.
+    #[arg(long, value_name = "DIR")]
+    storage_directory: PathBuf,
+
+    /// The number of worker threads of the runtime.
+    #[arg(long, default_value_t = 2)]
+    worker_threads: usize,
+}
+
+fn main() -> eyre::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    let args = Args::parse();
+
+    let passphrase = args
+        .keystore_passphrase_file
+        .as_ref()
+        .map(|path| {
+            Passphrase::read_from_file(path).wrap_err_with(|| {
+                format!(
+                    "failed reading keystore passphrase from file `{}`",
+                    path.display()
+                )
+            })
+        })
+        .transpose()?;
+    let identity = SigningKey::read_from_file_or_keystore(&args.identity_key, passphrase.as_ref())
+        .wrap_err_with(|| {
+            format!(
+                "failed reading identity key from file `{}`",
+                args.identity_key.display()
+            )
+        })?;
+    let signing_key =
+        SigningKey::read_from_file_or_keystore(&args.signing_key, passphrase.as_ref())
+            .wrap_err_with(|| {
+                format!(
+                    "failed reading signing key from file `{}`",
+                    args.signing_key.display()
+                )
+            })?;
+    let share = args
+        .signing_share
+        .as_ref()
+        .map(|path| {
+            SigningShare::read_from_file_or_keystore(path, passphrase.as_ref()).wrap_err_with(
+                || {
+                    format!(
+                        "failed reading signing share from file `{}`",
+                        path.display()
+                    )
+                },
+            )
+        })
+        .transpose()?;
+
+    let config = ServerConfig {
+        listen_address: args.listen_address,
+        identity: identity.into_inner(),
+        allowed_clients: args.allowed_clients,
+        signing_key: signing_key.into_inner(),
+        share: share.map(SigningShare::into_inner),
+        namespace: NAMESPACE.to_vec(),
+        partition: "remote_signer".into(),
+    };
+
+    let runtime_config = commonware_runtime::tokio::Config::default()
+        .with_tcp_nodelay(Some(true))
+        .with_worker_threads(args.worker_threads)
+        .with_storage_directory(args.storage_directory);
+    commonware_runtime::tokio::Runner::new(runtime_config).start(async move |context| {
+        tempo_remote_signer::run(context.with_label("remote_signer"), config)
+            .await
+            .wrap_err("remote signer failed")
+    })
+}
+
+fn parse_public_key(hex: &str) -> eyre::Result<PublicKey> {
+    let bytes = const_hex::decode(hex).wrap_err("failed decoding hex")?;
+    PublicKey::decode(&bytes[..]).wrap_err("failed decoding ed25519 public key")
+}
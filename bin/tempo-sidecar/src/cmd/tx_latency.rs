@@ -1,12 +1,13 @@
 use crate::monitor::prometheus_metrics;
 use alloy::{
-    primitives::B256,
+    network::TransactionResponse,
+    primitives::{Address, B256},
     providers::{Provider, ProviderBuilder, WsConnect},
 };
 use clap::Parser;
 use eyre::{Context, Result};
 use futures::StreamExt;
-use metrics::{describe_gauge, describe_histogram, gauge, histogram};
+use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
 use metrics_exporter_prometheus::PrometheusBuilder;
 use poem::{EndpointExt, Route, Server, get, listener::TcpListener};
 use reqwest::Url;
@@ -67,6 +68,7 @@ impl TransactionLatencyMonitor {
 
         let mut block_subscription = provider
             .subscribe_full_blocks()
+            .full()
             .channel_size(1000)
             .into_stream()
             .await
@@ -96,20 +98,26 @@ impl TransactionLatencyMonitor {
                 },
                 maybe_block = block_subscription.next() => {
                     if let Some(Ok(block)) = maybe_block {
-                         self.on_mined_block(block.header.inner.into_consensus(), block.transactions.hashes().collect());
+                         let mined_txs = block.transactions.txns().map(|tx| (tx.tx_hash(), tx.from())).collect();
+                         self.on_mined_block(block.header.inner.into_consensus(), mined_txs);
                     }
                 }
             }
         }
     }
 
-    fn on_mined_block(&mut self, header: TempoHeader, mined_txs: HashSet<B256>) {
+    /// Handles a mined block with its transaction hashes and senders in block order.
+    fn on_mined_block(&mut self, header: TempoHeader, mined_txs: Vec<(B256, Address)>) {
         gauge!("tempo_tx_latency_pending_observed").set(self.pending.len() as f64);
+        Self::record_sender_fairness(&mined_txs);
         if self.pending.is_empty() {
             return;
         }
+        self.record_arrival_order(&mined_txs);
+
+        let mined_hashes: HashSet<B256> = mined_txs.iter().map(|(hash, _)| *hash).collect();
         self.pending.retain(|hash, seen_at| {
-            if mined_txs.contains(hash) {
+            if mined_hashes.contains(hash) {
                 let latency_secs =
                     Self::latency_seconds(*seen_at, header.timestamp_millis() as u128);
                 histogram!("tempo_tx_landing_latency_seconds").record(latency_secs);
@@ -133,6 +141,62 @@ impl TransactionLatencyMonitor {
         }
     }
 
+    /// Records how evenly the transactions of a block are spread across their senders.
+    fn record_sender_fairness(mined_txs: &[(B256, Address)]) {
+        // System transactions are sent from the zero address.
+        let mut per_sender = HashMap::<Address, u64>::new();
+        for (_, sender) in mined_txs.iter().filter(|(_, sender)| !sender.is_zero()) {
+            *per_sender.entry(*sender).or_default() += 1;
+        }
+        let total = per_sender.values().sum::<u64>();
+        if total == 0 {
+            return;
+        }
+        let max_share =
+            per_sender.values().max().copied().unwrap_or_default() as f64 / total as f64;
+
+        gauge!("tempo_tx_latency_block_senders").set(per_sender.len() as f64);
+        gauge!("tempo_tx_latency_top_sender_share").set(max_share);
+        gauge!("tempo_tx_latency_sender_fairness_index")
+            .set(Self::jain_index(per_sender.values().copied()));
+    }
+
+    /// Records how many observed transactions of a block were included after a transaction that
+    /// was observed later than them.
+    fn record_arrival_order(&self, mined_txs: &[(B256, Address)]) {
+        let mut latest_seen = 0;
+        let mut observed = 0u64;
+        let mut overtaken = 0u64;
+        for seen_at in mined_txs
+            .iter()
+            .filter_map(|(hash, _)| self.pending.get(hash))
+        {
+            observed += 1;
+            if *seen_at < latest_seen {
+                overtaken += 1;
+            }
+            latest_seen = latest_seen.max(*seen_at);
+        }
+
+        if observed > 0 {
+            counter!("tempo_tx_latency_overtaken_total").increment(overtaken);
+            gauge!("tempo_tx_latency_overtaken_ratio").set(overtaken as f64 / observed as f64);
+        }
+    }
+
+    /// Jain's fairness index of the given per-sender transaction counts, `1.0` if all senders
+    /// got the same number of transactions included.
+    fn jain_index(counts: impl Iterator<Item = u64>) -> f64 {
+        let (sum, sum_squares, n) = counts.fold((0f64, 0f64, 0f64), |(sum, sq, n), count| {
+            let count = count as f64;
+            (sum + count, sq + count * count, n + 1.0)
+        });
+        if sum_squares == 0.0 {
+            return 1.0;
+        }
+        sum * sum / (n * sum_squares)
+    }
+
     fn now_millis() -> u128 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -164,6 +228,26 @@ impl TxLatencyArgs {
             "tempo_tx_latency_pending_observed",
             "Number of observed pending transactions awaiting inclusion"
         );
+        describe_gauge!(
+            "tempo_tx_latency_block_senders",
+            "Number of distinct senders in the latest block"
+        );
+        describe_gauge!(
+            "tempo_tx_latency_top_sender_share",
+            "Share of the latest block's transactions sent by its most frequent sender"
+        );
+        describe_gauge!(
+            "tempo_tx_latency_sender_fairness_index",
+            "Jain's fairness index of the transactions per sender in the latest block"
+        );
+        describe_counter!(
+            "tempo_tx_latency_overtaken_total",
+            "Observed transactions included after a transaction that was observed later"
+        );
+        describe_gauge!(
+            "tempo_tx_latency_overtaken_ratio",
+            "Share of the latest block's observed transactions that were overtaken by a later one"
+        );
 
         let app = Route::new().at(
             "/metrics",
@@ -0,0 +1,597 @@
+//! Follow mode: imports finalized blocks from an upstream RPC node.
+//!
+//! The upstream node is not trusted. Every block is only imported after its finalization
+//! certificate was verified against the group public key of its epoch, starting from the DKG
+//! outcome in the local genesis header and following epoch transitions through the DKG outcomes
+//! in the boundary headers.
+
+use std::{sync::Arc, time::Duration};
+
+use alloy::{
+    eips::{BlockHashOrNumber, BlockNumberOrTag},
+    primitives::Bytes,
+    providers::{DynProvider, Provider as _, ProviderBuilder},
+    rpc::types::{Block as RpcBlock, Transaction},
+};
+use eyre::{OptionExt as _, WrapErr as _, bail, ensure};
+use reth_ethereum::{
+    chainspec::EthChainSpec as _,
+    node::core::primitives::{BlockHeader as _, SealedBlock},
+    rpc::types::engine::{ForkchoiceState, PayloadStatusEnum},
+    storage::BlockNumReader as _,
+};
+use reth_node_builder::EngineApiMessageVersion;
+use tempo_alloy::TempoNetwork;
+use tempo_chainspec::spec::TempoChainSpec;
+use tempo_light_client::LightClient;
+use tempo_node::{TempoFullNode, rpc::ConsensusFinalization};
+use tempo_payload_types::TempoExecutionData;
+use tempo_primitives::{Block, TempoHeader, TempoTxEnvelope};
+use tracing::{info, warn};
+
+/// How often the upstream node is polled for new finalized blocks.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Returns a light client trusting the validator group of the genesis of `spec`.
+pub(crate) fn genesis_light_client(spec: &TempoChainSpec) -> eyre::Result<LightClient> {
+    let epoch_length = spec
+        .info
+        .epoch_length()
+        .ok_or_eyre("the chainspec does not define an epoch length")?;
+    match LightClient::from_genesis(epoch_length, spec.genesis_header()) {
+        Ok(light_client) => Ok(light_client),
+        // Chains started before Allegretto define the initial group in the chainspec.
+        Err(_) => {
+            let public = spec
+                .info
+                .public_polynomial()
+                .clone()
+                .ok_or_eyre(
+                    "neither the genesis header nor the chainspec define the initial validator group",
+                )?
+                .into_inner();
+            Ok(LightClient::from_public_polynomial(
+                epoch_length,
+                0,
+                &public,
+            ))
+        }
+    }
+}
+
+/// Follows the chain of the node at `url`, importing blocks into `node` once they are proven
+/// final.
+///
+/// Only returns on errors that cannot be recovered from by retrying. Blocks that are not
+/// finalized yet or that fail verification are not imported, and the connection to the
+/// upstream node is re-established whenever a request fails.
+pub(crate) async fn run(node: TempoFullNode, url: String) -> eyre::Result<()> {
+    let light_client = genesis_light_client(&node.chain_spec())?;
+    let next = node
+        .provider
+        .best_block_number()
+        .wrap_err("failed reading local head")?
+        + 1;
+
+    info!(%url, "following upstream node");
+    Follower::new(
+        RpcUpstream::new(url),
+        EngineImporter { node },
+        light_client,
+        next,
+    )
+    .run()
+    .await
+}
+
+/// A source of blocks and their finalization certificates.
+pub(crate) trait Upstream {
+    /// Returns the encoded finalization certificate of block `height`, or `None` if the block is
+    /// not finalized yet.
+    async fn finalization(&mut self, height: u64) -> eyre::Result<Option<Bytes>>;
+
+    /// Returns block `height`, or `None` if the upstream does not know it.
+    async fn block(&mut self, height: u64) -> eyre::Result<Option<Block>>;
+}
+
+/// A destination of verified finalized blocks.
+pub(crate) trait Importer {
+    /// Imports `block` and makes it the head of the chain.
+    async fn import(&mut self, block: SealedBlock<Block>) -> eyre::Result<()>;
+}
+
+/// Imports verified blocks from an [`Upstream`] into an [`Importer`].
+pub(crate) struct Follower<U, I> {
+    upstream: U,
+    importer: I,
+    light_client: LightClient,
+    /// The height of the next block to import.
+    next: u64,
+    poll_interval: Duration,
+}
+
+impl<U: Upstream, I: Importer> Follower<U, I> {
+    pub(crate) fn new(upstream: U, importer: I, light_client: LightClient, next: u64) -> Self {
+        Self {
+            upstream,
+            importer,
+            light_client,
+            next,
+            poll_interval: POLL_INTERVAL,
+        }
+    }
+
+    /// Follows the upstream forever.
+    pub(crate) async fn run(mut self) -> eyre::Result<()> {
+        self.sync_epochs().await?;
+        info!(
+            next = self.next,
+            epoch = self.light_client.epoch(),
+            "verified validator set of the local head"
+        );
+
+        loop {
+            if !self.step().await? {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        }
+    }
+
+    /// Walks the epoch boundaries of the locally known chain to arrive at the validator set that
+    /// finalizes the next block.
+    async fn sync_epochs(&mut self) -> eyre::Result<()> {
+        while self.light_client.epoch_boundary() < self.next {
+            let boundary = self.light_client.epoch_boundary();
+            loop {
+                match self.verify_block(boundary).await {
+                    Ok(Some(_)) => break,
+                    Ok(None) => {
+                        bail!("upstream node has no finalization for boundary block `{boundary}`")
+                    }
+                    Err(err) => warn!(%err, boundary, "failed verifying epoch boundary; retrying"),
+                }
+                tokio::time::sleep(self.poll_interval).await;
+            }
+            info!(
+                epoch = self.light_client.epoch(),
+                "verified epoch transition"
+            );
+        }
+        Ok(())
+    }
+
+    /// Tries to import the next block.
+    ///
+    /// Returns whether a block was imported. Failures of the upstream are logged and retried on
+    /// the next step; only failures of the importer are returned.
+    async fn step(&mut self) -> eyre::Result<bool> {
+        let epoch = self.light_client.epoch();
+        let block = match self.verify_block(self.next).await {
+            Ok(Some(block)) => block,
+            // Not finalized yet.
+            Ok(None) => return Ok(false),
+            Err(err) => {
+                warn!(%err, height = self.next, "refusing to import block from upstream node");
+                return Ok(false);
+            }
+        };
+        if self.light_client.epoch() != epoch {
+            info!(
+                epoch = self.light_client.epoch(),
+                "verified epoch transition"
+            );
+        }
+
+        self.importer.import(block).await?;
+        self.next += 1;
+        Ok(true)
+    }
+
+    /// Fetches block `height` and its finalization certificate from the upstream and verifies
+    /// them.
+    ///
+    /// Returns `None` if the block is not finalized yet. Errors are failures of the upstream, which
+    /// both [`Self::step`] and [`Self::sync_epochs`] retry.
+    async fn verify_block(&mut self, height: u64) -> eyre::Result<Option<SealedBlock<Block>>> {
+        let Some(certificate) = self
+            .upstream
+            .finalization(height)
+            .await
+            .wrap_err("failed fetching finalization certificate")?
+        else {
+            return Ok(None);
+        };
+
+        let block = self
+            .upstream
+            .block(height)
+            .await
+            .wrap_err("failed fetching block")?
+            .ok_or_eyre("upstream node returned a finalization but no block")?;
+
+        // The certificate only proves that the header was finalized, not that it is at `height`.
+        ensure!(
+            block.header.number() == height,
+            "upstream node returned block `{}` for height `{height}`",
+            block.header.number(),
+        );
+
+        let finalized = self
+            .light_client
+            .verify_header(block.header.clone(), &certificate)
+            .wrap_err("failed verifying finalization certificate")?;
+
+        // The execution layer checks the body against the roots committed to by the certified
+        // header.
+        Ok(Some(SealedBlock::new_unchecked(block, finalized.hash)))
+    }
+}
+
+/// An [`Upstream`] served by the RPC of another Tempo node.
+///
+/// Connects lazily and drops the connection after every failed request, so that the follower
+/// reconnects if the upstream node restarts or the connection is lost.
+pub(crate) struct RpcUpstream {
+    url: String,
+    provider: Option<DynProvider<TempoNetwork>>,
+}
+
+impl RpcUpstream {
+    pub(crate) fn new(url: String) -> Self {
+        Self {
+            url,
+            provider: None,
+        }
+    }
+
+    async fn provider(&mut self) -> eyre::Result<&DynProvider<TempoNetwork>> {
+        if self.provider.is_none() {
+            let provider = ProviderBuilder::new_with_network::<TempoNetwork>()
+                .connect(&self.url)
+                .await
+                .wrap_err_with(|| format!("failed connecting to upstream node `{}`", self.url))?
+                .erased();
+            info!(url = %self.url, "connected to upstream node");
+            self.provider = Some(provider);
+        }
+        Ok(self.provider.as_ref().expect("connected above"))
+    }
+}
+
+impl Upstream for RpcUpstream {
+    async fn finalization(&mut self, height: u64) -> eyre::Result<Option<Bytes>> {
+        let result = self
+            .provider()
+            .await?
+            .raw_request::<_, Option<ConsensusFinalization>>(
+                "consensus_getFinalization".into(),
+                (BlockHashOrNumber::Number(height),),
+            )
+            .await;
+        if result.is_err() {
+            self.provider = None;
+        }
+        Ok(result?.map(|finalization| finalization.certificate))
+    }
+
+    async fn block(&mut self, height: u64) -> eyre::Result<Option<Block>> {
+        let result = self
+            .provider()
+            .await?
+            .raw_request::<_, Option<RpcBlock<Transaction<TempoTxEnvelope>, TempoHeader>>>(
+                "eth_getBlockByNumber".into(),
+                (BlockNumberOrTag::Number(height), true),
+            )
+            .await;
+        if result.is_err() {
+            self.provider = None;
+        }
+        Ok(result?.map(|block| {
+            block
+                .into_consensus_block()
+                .map_transactions(|tx| tx.into_inner())
+        }))
+    }
+}
+
+/// An [`Importer`] submitting blocks to the engine API of the local node.
+struct EngineImporter {
+    node: TempoFullNode,
+}
+
+impl Importer for EngineImporter {
+    async fn import(&mut self, block: SealedBlock<Block>) -> eyre::Result<()> {
+        let engine = &self.node.add_ons_handle.beacon_engine_handle;
+        let number = block.number();
+        let hash = block.hash();
+
+        let status = engine
+            .new_payload(TempoExecutionData {
+                block: Arc::new(block),
+                validator_set: None,
+                decrypted_transactions: None,
+            })
+            .await
+            .wrap_err("failed sending `new payload` message to execution layer")?;
+        match status.status {
+            PayloadStatusEnum::Valid | PayloadStatusEnum::Accepted => {}
+            PayloadStatusEnum::Invalid { validation_error } => {
+                bail!(
+                    "finalized block `{number}` was rejected by the execution layer: {validation_error}"
+                )
+            }
+            PayloadStatusEnum::Syncing => {
+                bail!("execution layer is syncing while importing finalized block `{number}`")
+            }
+        }
+
+        // Every imported block is final, so it serves as head, safe, and finalized block.
+        let forkchoice = ForkchoiceState {
+            head_block_hash: hash,
+            safe_block_hash: hash,
+            finalized_block_hash: hash,
+        };
+        let response = engine
+            .fork_choice_updated(forkchoice, None, EngineApiMessageVersion::V3)
+            .await
+            .wrap_err("failed requesting execution layer to update forkchoice state")?;
+        ensure!(
+            !response.is_invalid(),
+            "execution layer rejected forkchoice update to block `{number}`: {}",
+            response.payload_status,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::B256;
+    use commonware_codec::Encode as _;
+    use std::{collections::HashMap, sync::Mutex};
+    use tempo_light_client::test_utils::TestGroup;
+
+    const EPOCH_LENGTH: u64 = 4;
+
+    /// An upstream serving blocks from memory, failing every request while disconnected.
+    #[derive(Clone, Default)]
+    struct MockUpstream(Arc<Mutex<MockChain>>);
+
+    #[derive(Default)]
+    struct MockChain {
+        blocks: HashMap<u64, SealedBlock<Block>>,
+        certificates: HashMap<u64, Bytes>,
+        disconnected: bool,
+    }
+
+    impl MockUpstream {
+        /// Adds block `number` on top of the previous one and returns its hash.
+        fn add_block(&self, number: u64, extra_data: Bytes) -> B256 {
+            let mut chain = self.0.lock().unwrap();
+            let mut header = TempoHeader::default();
+            header.inner.number = number;
+            header.inner.extra_data = extra_data;
+            if let Some(parent) = number.checked_sub(1).and_then(|n| chain.blocks.get(&n)) {
+                header.inner.parent_hash = parent.header.hash_slow();
+            }
+            let hash = header.hash_slow();
+            chain
+                .blocks
+                .insert(number, Block::new(header, Default::default()));
+            hash
+        }
+
+        fn finalize(&self, number: u64, certificate: Vec<u8>) {
+            self.0
+                .lock()
+                .unwrap()
+                .certificates
+                .insert(number, certificate.into());
+        }
+
+        fn set_disconnected(&self, disconnected: bool) {
+            self.0.lock().unwrap().disconnected = disconnected;
+        }
+
+        /// Serves block `number` and its certificate when asked for block `height`.
+        fn serve_as(&self, height: u64, number: u64) {
+            let mut chain = self.0.lock().unwrap();
+            let block = chain.blocks[&number].clone();
+            let certificate = chain.certificates[&number].clone();
+            chain.blocks.insert(height, block);
+            chain.certificates.insert(height, certificate);
+        }
+    }
+
+    impl Upstream for MockUpstream {
+        async fn finalization(&mut self, height: u64) -> eyre::Result<Option<Bytes>> {
+            let chain = self.0.lock().unwrap();
+            ensure!(!chain.disconnected, "connection lost");
+            Ok(chain.certificates.get(&height).cloned())
+        }
+
+        async fn block(&mut self, height: u64) -> eyre::Result<Option<Block>> {
+            let chain = self.0.lock().unwrap();
+            ensure!(!chain.disconnected, "connection lost");
+            Ok(chain
+                .blocks
+                .get(&height)
+                .map(|block| block.clone().into_block()))
+        }
+    }
+
+    /// An importer recording the imported blocks, failing every import while broken.
+    #[derive(Clone, Default)]
+    struct MockImporter(Arc<Mutex<(Vec<SealedBlock<Block>>, bool)>>);
+
+    impl MockImporter {
+        fn imported(&self) -> Vec<u64> {
+            self.0
+                .lock()
+                .unwrap()
+                .0
+                .iter()
+                .map(|b| b.number())
+                .collect()
+        }
+
+        fn set_broken(&self, broken: bool) {
+            self.0.lock().unwrap().1 = broken;
+        }
+    }
+
+    impl Importer for MockImporter {
+        async fn import(&mut self, block: SealedBlock<Block>) -> eyre::Result<()> {
+            let mut state = self.0.lock().unwrap();
+            ensure!(!state.1, "execution layer is syncing");
+            state.0.push(block);
+            Ok(())
+        }
+    }
+
+    fn follower(
+        group: &TestGroup,
+        upstream: &MockUpstream,
+        importer: &MockImporter,
+    ) -> Follower<MockUpstream, MockImporter> {
+        Follower::new(
+            upstream.clone(),
+            importer.clone(),
+            group.light_client(EPOCH_LENGTH),
+            1,
+        )
+    }
+
+    #[tokio::test]
+    async fn follows_finalized_blocks_across_epochs() {
+        let group = TestGroup::new(0, 0);
+        let next_group = TestGroup::new(1, 1);
+        let upstream = MockUpstream::default();
+        let importer = MockImporter::default();
+        let mut follower = follower(&group, &upstream, &importer);
+
+        let boundary = follower.light_client.epoch_boundary();
+        for number in 1..=boundary + 2 {
+            let (signer, extra_data) = if number < boundary {
+                (&group, Bytes::new())
+            } else if number == boundary {
+                (&group, next_group.outcome().encode().to_vec().into())
+            } else {
+                (&next_group, Bytes::new())
+            };
+            let hash = upstream.add_block(number, extra_data);
+            upstream.finalize(number, signer.finalize(number, hash));
+        }
+        for _ in 1..=boundary + 2 {
+            assert!(follower.step().await.unwrap());
+        }
+        assert_eq!(importer.imported(), (1..=boundary + 2).collect::<Vec<_>>());
+        assert_eq!(follower.light_client.epoch(), 1);
+
+        // Blocks are only imported once they are finalized by the current group
+        let number = boundary + 3;
+        let hash = upstream.add_block(number, Bytes::new());
+        assert!(!follower.step().await.unwrap());
+
+        upstream.finalize(number, group.finalize(number, hash));
+        assert!(!follower.step().await.unwrap());
+
+        upstream.finalize(number, next_group.finalize(number, hash));
+        assert!(follower.step().await.unwrap());
+        assert_eq!(importer.imported().last(), Some(&number));
+    }
+
+    #[tokio::test]
+    async fn resumes_after_losing_the_upstream() {
+        let group = TestGroup::new(0, 0);
+        let upstream = MockUpstream::default();
+        let importer = MockImporter::default();
+        let mut follower = follower(&group, &upstream, &importer);
+
+        for number in 1..=2 {
+            let hash = upstream.add_block(number, Bytes::new());
+            upstream.finalize(number, group.finalize(number, hash));
+        }
+        assert!(follower.step().await.unwrap());
+        assert!(follower.step().await.unwrap());
+
+        // The upstream keeps finalizing blocks while the follower cannot reach it
+        upstream.set_disconnected(true);
+        for number in 3..=4 {
+            let hash = upstream.add_block(number, Bytes::new());
+            upstream.finalize(number, group.finalize(number, hash));
+        }
+        for _ in 0..3 {
+            assert!(!follower.step().await.unwrap());
+        }
+
+        // Once reachable again, the follower continues exactly where it stopped
+        upstream.set_disconnected(false);
+        while follower.step().await.unwrap() {}
+        assert_eq!(importer.imported(), vec![1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn rejects_finalized_blocks_at_the_wrong_height() {
+        let group = TestGroup::new(0, 0);
+        let upstream = MockUpstream::default();
+        let importer = MockImporter::default();
+        let mut follower = follower(&group, &upstream, &importer);
+
+        for number in 1..=2 {
+            let hash = upstream.add_block(number, Bytes::new());
+            upstream.finalize(number, group.finalize(number, hash));
+        }
+        assert!(follower.step().await.unwrap());
+
+        // Block 1 really was finalized, but it is not block 2
+        let block_2 = upstream.0.lock().unwrap().blocks[&2].clone();
+        upstream.serve_as(2, 1);
+        assert!(!follower.step().await.unwrap());
+        assert_eq!(follower.next, 2);
+        assert_eq!(importer.imported(), vec![1]);
+
+        upstream.0.lock().unwrap().blocks.insert(2, block_2.clone());
+        upstream.finalize(2, group.finalize(2, block_2.hash()));
+        assert!(follower.step().await.unwrap());
+        assert_eq!(importer.imported(), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn importer_failures_do_not_skip_blocks() {
+        let group = TestGroup::new(0, 0);
+        let upstream = MockUpstream::default();
+        let importer = MockImporter::default();
+        let mut follower = follower(&group, &upstream, &importer);
+
+        let hash = upstream.add_block(1, Bytes::new());
+        upstream.finalize(1, group.finalize(1, hash));
+
+        importer.set_broken(true);
+        assert!(follower.step().await.is_err());
+        assert_eq!(follower.next, 1);
+
+        importer.set_broken(false);
+        assert!(follower.step().await.unwrap());
+        assert_eq!(importer.imported(), vec![1]);
+        assert_eq!(follower.next, 2);
+    }
+
+    #[tokio::test]
+    async fn rpc_upstream_reconnects_after_failed_requests() {
+        // Nothing listens on the port once the listener is dropped
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut upstream = RpcUpstream::new(format!("http://127.0.0.1:{port}"));
+
+        // Failed requests drop the connection, so the next request reconnects
+        assert!(upstream.finalization(1).await.is_err());
+        assert!(upstream.provider.is_none());
+        assert!(upstream.block(1).await.is_err());
+        assert!(upstream.provider.is_none());
+    }
+}
@@ -16,6 +16,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 mod defaults;
+mod follow;
+mod snapshot;
 
 use clap::Parser;
 use commonware_runtime::{Metrics, Runner};
@@ -27,7 +29,7 @@ use reth_ethereum::{
     evm::revm::primitives::B256,
 };
 use reth_ethereum_cli as _;
-use reth_node_builder::{NodeHandle, WithLaunchContext};
+use reth_node_builder::NodeHandle;
 use std::{sync::Arc, thread};
 use tempo_chainspec::spec::{TempoChainSpec, TempoChainSpecParser};
 use tempo_commonware_node::run_consensus_stack;
@@ -37,14 +39,18 @@ use tempo_faucet::{
     args::FaucetArgs,
     faucet::{TempoFaucetExt, TempoFaucetExtApiServer},
 };
-use tempo_node::{TempoFullNode, TempoNodeArgs, node::TempoNode};
+use tempo_node::{
+    PrebuildHandle, TempoFullNode, TempoNodeArgs, node::TempoNode, rpc::ConsensusHandle,
+};
+use tempo_snapshot::Checkpoint;
 use tokio::sync::oneshot;
 use tracing::{info, info_span};
 
 // TODO: migrate this to tempo_node eventually.
 #[derive(Debug, Clone, PartialEq, Eq, clap::Args)]
 struct TempoArgs {
-    /// Follow this specific RPC node for block hashes
+    /// Follow this specific RPC node, importing only blocks whose finalization certificates
+    /// verify against the validator set of their epoch
     #[arg(
         long,
         value_name = "URL",
@@ -53,6 +59,19 @@ struct TempoArgs {
     )]
     pub follow: Option<String>,
 
+    /// Start a new node from the state snapshot at this path or URL, e.g. one published by
+    /// another node through `admin_exportSnapshot`. The snapshot is verified against the
+    /// validators of genesis before its state is imported. Allegretto must have been active for
+    /// the whole epoch of the snapshot
+    #[arg(long = "snapshot.import", value_name = "PATH|URL")]
+    pub snapshot_import: Option<String>,
+
+    /// Start a new node from the latest state snapshot exported by one of these peers, given as
+    /// comma-separated RPC URLs. Peers are tried in order after `--snapshot.import`, until one
+    /// serves a snapshot that passes verification
+    #[arg(long = "snapshot.peers", value_name = "URL", value_delimiter = ',')]
+    pub snapshot_peers: Vec<String>,
+
     #[command(flatten)]
     pub consensus: tempo_commonware_node::Args,
 
@@ -113,11 +132,19 @@ fn main() -> eyre::Result<()> {
     let is_node = matches!(cli.command, Commands::Node(_));
 
     let (args_and_node_handle_tx, args_and_node_handle_rx) =
-        oneshot::channel::<(TempoFullNode, TempoArgs)>();
+        oneshot::channel::<(TempoFullNode, TempoArgs, Option<Checkpoint>)>();
     let (consensus_dead_tx, mut consensus_dead_rx) = oneshot::channel();
 
     let shutdown_token = tokio_util::sync::CancellationToken::new();
 
+    // Filled in by the consensus stack once it's running to serve the `consensus_` RPC namespace.
+    let consensus_rpc = ConsensusHandle::default();
+    let consensus_rpc_clone = consensus_rpc.clone();
+
+    // Used by the consensus stack to announce the parents of the blocks it is going to propose.
+    let prebuild = PrebuildHandle::default();
+    let prebuild_clone = prebuild.clone();
+
     let shutdown_token_clone = shutdown_token.clone();
     let consensus_handle = thread::spawn(move || {
         // Exit early if we are not executing `tempo node` command.
@@ -125,7 +152,7 @@ fn main() -> eyre::Result<()> {
             return Ok(());
         }
 
-        let (node, args) = args_and_node_handle_rx.blocking_recv().wrap_err(
+        let (node, args, checkpoint) = args_and_node_handle_rx.blocking_recv().wrap_err(
             "channel closed before consensus-relevant command line args \
                 and a handle to the execution node could be received",
         )?;
@@ -168,7 +195,14 @@ fn main() -> eyre::Result<()> {
                     args.consensus.metrics_address,
                 )
                 .fuse();
-                let consensus_stack = run_consensus_stack(&ctx, args.consensus, node);
+                let consensus_stack = run_consensus_stack(
+                    &ctx,
+                    args.consensus,
+                    node,
+                    consensus_rpc_clone,
+                    prebuild_clone,
+                    checkpoint,
+                );
                 tokio::pin!(consensus_stack);
                 loop {
                     tokio::select!(
@@ -242,18 +276,35 @@ fn main() -> eyre::Result<()> {
             None
         };
 
+        let snapshot_sources = args
+            .snapshot_import
+            .iter()
+            .cloned()
+            .map(snapshot::Source::Location)
+            .chain(
+                args.snapshot_peers
+                    .iter()
+                    .cloned()
+                    .map(snapshot::Source::Peer),
+            )
+            .collect::<Vec<_>>();
+        let checkpoint = if snapshot_sources.is_empty() {
+            None
+        } else {
+            snapshot::import(builder.db().clone(), builder.config(), &snapshot_sources)
+                .await
+                .wrap_err("failed importing snapshot")?
+        };
+
         let NodeHandle {
             node,
             node_exit_future,
         } = builder
-            .node(TempoNode::new(&args.node_args, validator_key))
-            .apply(|mut builder: WithLaunchContext<_>| {
-                if let Some(follow_url) = &args.follow {
-                    builder.config_mut().debug.rpc_consensus_url = Some(follow_url.clone());
-                }
-
-                builder
-            })
+            .node(
+                TempoNode::new(&args.node_args, validator_key)
+                    .with_consensus_handle(consensus_rpc)
+                    .with_prebuild_handle(prebuild),
+            )
             .extend_rpc_modules(move |ctx| {
                 if faucet_args.enabled {
                     let ext = TempoFaucetExt::new(
@@ -271,7 +322,11 @@ fn main() -> eyre::Result<()> {
             .await
             .wrap_err("failed launching execution node")?;
 
-        let _ = args_and_node_handle_tx.send((node, args));
+        let follow = match args.follow.clone() {
+            Some(url) => follow::run(node.clone(), url).boxed(),
+            None => futures::future::pending().boxed(),
+        };
+        let _ = args_and_node_handle_tx.send((node, args, checkpoint));
 
         // TODO: emit these inside a span
         tokio::select! {
@@ -281,6 +336,11 @@ fn main() -> eyre::Result<()> {
             _ = &mut consensus_dead_rx => {
                 tracing::info!("consensus node exited");
             }
+            ret = follow => {
+                if let Err(err) = ret {
+                    tracing::error!(%err, "following upstream node failed");
+                }
+            }
             _ = tokio::signal::ctrl_c() => {
                 tracing::info!("received shutdown signal");
             }
@@ -0,0 +1,267 @@
+//! Snapshot sync: starts a new node from a state snapshot at a finalized epoch boundary.
+//!
+//! The snapshot is not trusted. Its boundary headers are verified starting from the validator
+//! group of the local genesis, following the epoch transitions through the DKG outcomes in the
+//! headers, and its state is only imported if it hashes to the state root of the last boundary
+//! header. Afterwards, the node executes the blocks following the boundary like any other node.
+//!
+//! Snapshots are written with `admin_exportSnapshot`, and are either fetched from a path or URL,
+//! or downloaded from peers serving their latest export through `consensus_getSnapshotChunk`.
+//!
+//! The node starting from a snapshot runs the post-allegretto DKG only, so the allegretto hardfork
+//! must have been active for the whole epoch of the snapshot. On chains that activated allegretto
+//! after genesis, only snapshots from the epoch after the activation on can be imported.
+
+use std::{
+    fmt,
+    fs::File,
+    io::{BufReader, Write as _},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alloy::{
+    providers::{Provider as _, ProviderBuilder},
+    transports::http::reqwest,
+};
+use eyre::{OptionExt as _, WrapErr as _, bail, ensure};
+use reth_config::config::EtlConfig;
+use reth_db::DatabaseEnv;
+use reth_ethereum::provider::{ProviderFactory, providers::StaticFileProvider};
+use reth_node_builder::{NodeConfig, NodeTypesWithDBAdapter};
+use tempo_alloy::TempoNetwork;
+use tempo_chainspec::{hardfork::TempoHardforks as _, spec::TempoChainSpec};
+use tempo_node::{
+    node::TempoNode,
+    rpc::{MAX_SNAPSHOT_CHUNK_SIZE, SnapshotChunk},
+};
+use tempo_snapshot::{
+    Checkpoint,
+    import::{Imported, import_state},
+};
+use tracing::{info, warn};
+
+use crate::follow;
+
+/// Upper bound on the size of a snapshot downloaded from a peer.
+const MAX_PEER_SNAPSHOT_SIZE: u64 = 512 * 1024 * 1024 * 1024;
+
+/// How many times more chunks than needed at [`MAX_SNAPSHOT_CHUNK_SIZE`] a peer may serve its
+/// snapshot in.
+const PEER_SNAPSHOT_CHUNKS_SLACK: u64 = 16;
+
+/// Where a snapshot is obtained from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Source {
+    /// A path or an http(s) URL.
+    Location(String),
+    /// The RPC URL of a peer serving its latest exported snapshot.
+    Peer(String),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Location(location) => f.write_str(location),
+            Self::Peer(url) => write!(f, "peer `{url}`"),
+        }
+    }
+}
+
+/// Imports a state snapshot into an empty database, trying `sources` in order until one of them
+/// provides a snapshot that passes verification.
+///
+/// Returns the verified checkpoint the consensus engine starts from, or `None` if the database
+/// already holds blocks past the checkpoint and the snapshot was ignored.
+pub(crate) async fn import(
+    db: Arc<DatabaseEnv>,
+    config: &NodeConfig<TempoChainSpec>,
+    sources: &[Source],
+) -> eyre::Result<Option<Checkpoint>> {
+    let spec = config.chain.clone();
+    let data_dir = config.datadir().data_dir().to_path_buf();
+
+    let mut verified = None;
+    for source in sources {
+        match fetch_verified(&spec, source, &data_dir).await {
+            Ok(snapshot) => {
+                verified = Some(snapshot);
+                break;
+            }
+            Err(err) => warn!(%source, err = %format!("{err:#}"), "failed obtaining snapshot"),
+        }
+    }
+    let Some((checkpoint, reader)) = verified else {
+        bail!(
+            "none of the {} snapshot sources provided a valid snapshot",
+            sources.len()
+        );
+    };
+
+    let factory = ProviderFactory::<NodeTypesWithDBAdapter<TempoNode, _>>::new(
+        db,
+        spec,
+        StaticFileProvider::read_write(config.datadir().static_files())
+            .wrap_err("failed opening static files")?,
+    )
+    .wrap_err("failed creating provider factory")?;
+    let etl_config = EtlConfig::new(
+        Some(EtlConfig::from_datadir(&data_dir)),
+        EtlConfig::default_file_size(),
+    );
+    let imported = tokio::task::spawn_blocking({
+        let checkpoint = checkpoint.clone();
+        move || import_state(&factory, &checkpoint, reader, etl_config)
+    })
+    .await
+    .wrap_err("snapshot import task panicked")??;
+
+    Ok((!matches!(imported, Imported::DatabaseNotEmpty { .. })).then_some(checkpoint))
+}
+
+/// Fetches the snapshot of `source` and verifies its header against the genesis of `spec`.
+///
+/// Returns the verified checkpoint and the snapshot positioned at its state.
+async fn fetch_verified(
+    spec: &TempoChainSpec,
+    source: &Source,
+    data_dir: &Path,
+) -> eyre::Result<(Checkpoint, BufReader<File>)> {
+    let path = match source {
+        Source::Location(location) => fetch(location, data_dir).await?,
+        Source::Peer(url) => download_from_peer(url, data_dir).await?,
+    };
+    let mut reader = BufReader::new(
+        File::open(&path).wrap_err_with(|| format!("failed opening `{}`", path.display()))?,
+    );
+
+    let header = tempo_snapshot::read_header(&mut reader).wrap_err("failed reading snapshot")?;
+    let mut light_client = follow::genesis_light_client(spec)?;
+    let checkpoint = header
+        .verify(&mut light_client)
+        .wrap_err("snapshot failed verification")?;
+    ensure!(
+        spec.is_allegretto_active_at_timestamp(
+            checkpoint.ceremony_start_timestamp(spec.genesis().timestamp)
+        ),
+        "snapshot of epoch `{}` was taken before the allegretto hardfork was active for a whole \
+        epoch; only later snapshots can be imported",
+        checkpoint.finalized.epoch,
+    );
+    info!(
+        height = checkpoint.height(),
+        hash = %checkpoint.hash(),
+        epoch = checkpoint.finalized.epoch,
+        %source,
+        "verified snapshot checkpoint",
+    );
+
+    Ok((checkpoint, reader))
+}
+
+/// Returns the path of the snapshot at `source`, downloading it into `data_dir` if it is a URL.
+async fn fetch(source: &str, data_dir: &Path) -> eyre::Result<PathBuf> {
+    if !(source.starts_with("http://") || source.starts_with("https://")) {
+        return Ok(PathBuf::from(source));
+    }
+
+    let path = data_dir.join("snapshot.jsonl");
+    info!(url = source, path = %path.display(), "downloading snapshot");
+
+    let mut response = reqwest::get(source)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .wrap_err_with(|| format!("failed requesting snapshot from `{source}`"))?;
+    let mut file =
+        File::create(&path).wrap_err_with(|| format!("failed creating `{}`", path.display()))?;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .wrap_err("failed downloading snapshot")?
+    {
+        file.write_all(&chunk)
+            .wrap_err_with(|| format!("failed writing `{}`", path.display()))?;
+    }
+    file.sync_all()
+        .wrap_err_with(|| format!("failed writing `{}`", path.display()))?;
+
+    Ok(path)
+}
+
+/// Downloads the latest snapshot exported by the peer at `url` into `data_dir`, chunk by chunk.
+///
+/// Fails if the peer replaces its snapshot during the download.
+async fn download_from_peer(url: &str, data_dir: &Path) -> eyre::Result<PathBuf> {
+    let path = data_dir.join("snapshot.jsonl");
+    info!(url, path = %path.display(), "downloading snapshot from peer");
+
+    let provider = ProviderBuilder::new_with_network::<TempoNetwork>()
+        .connect(url)
+        .await
+        .wrap_err_with(|| format!("failed connecting to peer `{url}`"))?;
+    let mut file =
+        File::create(&path).wrap_err_with(|| format!("failed creating `{}`", path.display()))?;
+
+    let mut first: Option<SnapshotChunk> = None;
+    let mut offset = 0;
+    let mut chunks = 0u64;
+    loop {
+        let chunk = provider
+            .raw_request::<_, Option<SnapshotChunk>>("consensus_getSnapshotChunk".into(), (offset,))
+            .await
+            .wrap_err("failed requesting snapshot chunk")?
+            .ok_or_eyre("peer did not export a snapshot")?;
+        chunks += 1;
+        ensure!(
+            chunk.size <= MAX_PEER_SNAPSHOT_SIZE,
+            "peer declared a snapshot of `{}` bytes, more than the `{MAX_PEER_SNAPSHOT_SIZE}` \
+            bytes accepted",
+            chunk.size,
+        );
+        let max_chunks =
+            PEER_SNAPSHOT_CHUNKS_SLACK * chunk.size.div_ceil(MAX_SNAPSHOT_CHUNK_SIZE as u64) + 1;
+        ensure!(
+            chunks <= max_chunks,
+            "peer served its snapshot of `{}` bytes in more than `{max_chunks}` chunks",
+            chunk.size,
+        );
+        let first = first.get_or_insert_with(|| chunk.clone());
+        ensure!(
+            chunk.hash == first.hash && chunk.size == first.size,
+            "peer replaced its snapshot of block `{}` during the download",
+            first.number,
+        );
+        ensure!(
+            chunk.offset == offset,
+            "peer returned chunk at offset `{}`, requested `{offset}`",
+            chunk.offset,
+        );
+        ensure!(
+            !chunk.data.is_empty() || offset == chunk.size,
+            "peer returned an empty chunk at offset `{offset}` of `{}` bytes",
+            chunk.size,
+        );
+        ensure!(
+            chunk.data.len() <= MAX_SNAPSHOT_CHUNK_SIZE,
+            "peer returned a chunk of `{}` bytes, more than the `{MAX_SNAPSHOT_CHUNK_SIZE}` bytes \
+            of a chunk",
+            chunk.data.len(),
+        );
+        ensure!(
+            chunk.data.len() as u64 <= chunk.size - offset,
+            "peer returned more than the `{}` bytes of its snapshot",
+            chunk.size,
+        );
+
+        file.write_all(&chunk.data)
+            .wrap_err_with(|| format!("failed writing `{}`", path.display()))?;
+        offset += chunk.data.len() as u64;
+        if offset == chunk.size {
+            break;
+        }
+    }
+    file.sync_all()
+        .wrap_err_with(|| format!("failed writing `{}`", path.display()))?;
+
+    Ok(path)
+}
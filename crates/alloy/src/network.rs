@@ -141,6 +141,8 @@ impl TransactionBuilder<TempoNetwork> for TempoTransactionRequest {
         match ty {
             TempoTxType::FeeToken => self.complete_fee_token(),
             TempoTxType::AA => self.complete_aa(),
+            // Encrypted transactions are built by encrypting a signed transaction.
+            TempoTxType::Encrypted => Err(vec!["ciphertext"]),
             TempoTxType::Legacy
             | TempoTxType::Eip2930
             | TempoTxType::Eip1559
@@ -181,6 +183,7 @@ impl TransactionBuilder<TempoNetwork> for TempoTransactionRequest {
             TempoTxType::FeeToken => {
                 Some(TempoTxType::FeeToken).filter(|_| self.can_build_fee_token())
             }
+            TempoTxType::Encrypted => None,
             TempoTxType::Legacy
             | TempoTxType::Eip2930
             | TempoTxType::Eip1559
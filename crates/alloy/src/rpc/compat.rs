@@ -35,6 +35,10 @@ impl TryIntoSimTx<TempoTxEnvelope> for TempoTransactionRequest {
 
                 Ok(tx.into_signed(signature).into())
             }
+            TempoTxType::Encrypted => Err(ValueError::new(
+                self,
+                "Encrypted transactions can't be built from a transaction request.",
+            )),
             TempoTxType::Legacy
             | TempoTxType::Eip2930
             | TempoTxType::Eip1559
@@ -156,11 +160,14 @@ fn create_mock_tempo_signature(
     key_data: Option<&Bytes>,
 ) -> TempoSignature {
     use tempo_primitives::transaction::tt_signature::{
-        P256SignatureWithPreHash, PrimitiveSignature, TempoSignature, WebAuthnSignature,
+        Ed25519Signature, P256SignatureWithPreHash, PrimitiveSignature, TempoSignature,
+        WebAuthnSignature,
     };
 
     match key_type {
-        SignatureType::Secp256k1 => {
+        // The co-signers of a multisig are not known from the key type, estimate a single
+        // secp256k1 co-signer
+        SignatureType::Secp256k1 | SignatureType::Multisig => {
             // Create a dummy secp256k1 signature (65 bytes)
             TempoSignature::Primitive(PrimitiveSignature::Secp256k1(Signature::new(
                 alloy_primitives::U256::ZERO,
@@ -232,6 +239,13 @@ fn create_mock_tempo_signature(
                 pub_key_y: alloy_primitives::B256::ZERO,
             }))
         }
+        SignatureType::Ed25519 => {
+            // Create a dummy Ed25519 signature
+            TempoSignature::Primitive(PrimitiveSignature::Ed25519(Ed25519Signature {
+                public_key: alloy_primitives::B256::ZERO,
+                signature: alloy_primitives::B512::ZERO,
+            }))
+        }
     }
 }
 
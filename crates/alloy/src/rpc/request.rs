@@ -6,7 +6,7 @@ use alloy_provider::Provider;
 use alloy_rpc_types_eth::{TransactionRequest, TransactionTrait};
 use serde::{Deserialize, Serialize};
 use tempo_primitives::{
-    AASigned, SignatureType, TempoTransaction, TempoTxEnvelope, TxFeeToken,
+    AASigned, SignatureType, TempoTransaction, TempoTxEnvelope, TxEncrypted, TxFeeToken,
     transaction::{Call, TempoSignedAuthorization, TempoTypedTransaction},
 };
 
@@ -227,6 +227,7 @@ impl From<TempoTxEnvelope> for TempoTransactionRequest {
             TempoTxEnvelope::Eip7702(tx) => tx.into(),
             TempoTxEnvelope::FeeToken(tx) => tx.into(),
             TempoTxEnvelope::AA(tx) => tx.into(),
+            TempoTxEnvelope::Encrypted(tx) => tx.into(),
         }
     }
 }
@@ -265,6 +266,12 @@ impl FeeToken for TxEip2930 {
     }
 }
 
+impl FeeToken for TxEncrypted {
+    fn fee_token(&self) -> Option<Address> {
+        None
+    }
+}
+
 impl FeeToken for TxLegacy {
     fn fee_token(&self) -> Option<Address> {
         None
@@ -347,6 +354,10 @@ impl From<TempoTypedTransaction> for TempoTransactionRequest {
                 ..Default::default()
             },
             TempoTypedTransaction::AA(tx) => tx.into(),
+            TempoTypedTransaction::Encrypted(tx) => Self {
+                inner: TransactionRequest::from_transaction(tx),
+                ..Default::default()
+            },
         }
     }
 }
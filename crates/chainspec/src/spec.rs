@@ -15,10 +15,17 @@ use reth_ethereum::evm::primitives::eth::spec::EthExecutorSpec;
 use reth_network_peers::NodeRecord;
 use std::sync::{Arc, LazyLock};
 use tempo_commonware_node_config::{Peers, PublicPolynomial};
-use tempo_primitives::TempoHeader;
+use tempo_primitives::{LaneFees, TempoHeader};
 
+/// Base fee of Tempo blocks before Allegro-Moderato, and the lower bound of the base fee of both
+/// lanes afterwards.
 pub const TEMPO_BASE_FEE: u64 = 10_000_000_000;
 
+/// Upper bound of the payment lane base fee.
+///
+/// Keeps the base fee of a 50,000 gas TIP-20 transfer under $0.001.
+pub const TEMPO_MAX_PAYMENT_BASE_FEE: u64 = 18_000_000_000;
+
 /// Tempo genesis info extracted from genesis extra_fields
 #[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -43,6 +50,15 @@ pub struct TempoGenesisInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     epoch_length: Option<u64>,
 
+    /// The share of players, in basis points, whose acks each DKG dealing
+    /// must carry since Allegro-Moderato.
+    ///
+    /// Dealings reveal the shares of the players that did not ack them, so
+    /// this bounds how much of a dealing becomes public. It never drops below
+    /// the `2f + 1` players needed to recover the group key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dkg_threshold_bps: Option<u32>,
+
     /// The public polynomial all nodes are to use at genesis.
     #[serde(skip_serializing_if = "Option::is_none")]
     public_polynomial: Option<PublicPolynomial>,
@@ -66,6 +82,10 @@ impl TempoGenesisInfo {
         self.epoch_length
     }
 
+    pub fn dkg_threshold_bps(&self) -> Option<u32> {
+        self.dkg_threshold_bps
+    }
+
     pub fn public_polynomial(&self) -> &Option<PublicPolynomial> {
         &self.public_polynomial
     }
@@ -159,10 +179,46 @@ impl TempoChainSpec {
                 timestamp_millis_part: inner.timestamp * 1000,
                 shared_gas_limit: 0,
                 inner,
+                lane_fees: None,
             }),
             info,
         }
     }
+
+    /// Returns the base fee of the payment lane of the block after `parent`, or `None` if the
+    /// lanes don't have separate base fees at `target_timestamp`.
+    ///
+    /// Like the general lane base fee returned by [`EthChainSpec::next_block_base_fee`], it follows
+    /// the EIP-1559 update rule, targeting half of the [payment gas
+    /// limit](TempoHeader::payment_gas_limit).
+    pub fn next_block_payment_base_fee(
+        &self,
+        parent: &TempoHeader,
+        target_timestamp: u64,
+    ) -> Option<u64> {
+        if !self.is_allegro_moderato_active_at_timestamp(target_timestamp) {
+            return None;
+        }
+
+        let Some(LaneFees {
+            payment_base_fee_per_gas,
+            payment_gas_used,
+            ..
+        }) = parent.lane_fees
+        else {
+            // First block with separate lanes
+            return Some(TEMPO_BASE_FEE);
+        };
+
+        let base_fee = self
+            .base_fee_params_at_timestamp(target_timestamp)
+            .next_block_base_fee(
+                payment_gas_used,
+                parent.payment_gas_limit(),
+                payment_base_fee_per_gas,
+            );
+        Some(base_fee.clamp(TEMPO_BASE_FEE, TEMPO_MAX_PAYMENT_BASE_FEE))
+    }
 }
 
 // Required by reth's e2e-test-utils for integration tests.
@@ -175,6 +231,7 @@ impl From<ChainSpec> for TempoChainSpec {
                 timestamp_millis_part: inner.timestamp * 1000,
                 inner,
                 shared_gas_limit: 0,
+                lane_fees: None,
             }),
             info: TempoGenesisInfo::default(),
         }
@@ -260,8 +317,30 @@ impl EthChainSpec for TempoChainSpec {
         self.inner.get_final_paris_total_difficulty()
     }
 
-    fn next_block_base_fee(&self, _parent: &TempoHeader, _target_timestamp: u64) -> Option<u64> {
-        Some(TEMPO_BASE_FEE)
+    /// Returns the base fee of the general lane of the block after `parent`.
+    ///
+    /// The base fee is fixed to [`TEMPO_BASE_FEE`] before Allegro-Moderato. Afterwards it follows
+    /// the EIP-1559 update rule, targeting half of the general gas limit.
+    fn next_block_base_fee(&self, parent: &TempoHeader, target_timestamp: u64) -> Option<u64> {
+        if !self.is_allegro_moderato_active_at_timestamp(target_timestamp) {
+            return Some(TEMPO_BASE_FEE);
+        }
+
+        let (
+            Some(LaneFees {
+                general_gas_used, ..
+            }),
+            Some(base_fee),
+        ) = (parent.lane_fees, parent.inner.base_fee_per_gas)
+        else {
+            // First block with separate lanes
+            return Some(TEMPO_BASE_FEE);
+        };
+
+        let base_fee = self
+            .base_fee_params_at_timestamp(target_timestamp)
+            .next_block_base_fee(general_gas_used, parent.general_gas_limit, base_fee);
+        Some(base_fee.max(TEMPO_BASE_FEE))
     }
 }
 
@@ -285,10 +364,12 @@ impl TempoHardforks for TempoChainSpec {
 
 #[cfg(test)]
 mod tests {
+    use super::{TEMPO_BASE_FEE, TEMPO_MAX_PAYMENT_BASE_FEE};
     use crate::hardfork::{TempoHardfork, TempoHardforks};
-    use reth_chainspec::{EthereumHardfork, ForkCondition, Hardforks};
+    use reth_chainspec::{EthChainSpec, EthereumHardfork, ForkCondition, Hardforks};
     use reth_cli::chainspec::ChainSpecParser as _;
     use serde_json::json;
+    use tempo_primitives::{LaneFees, TempoHeader};
 
     #[test]
     fn can_load_testnet() {
@@ -367,6 +448,7 @@ mod tests {
                 "moderatoTime": 2000,
                 "allegrettoTime": 3000,
                 "allegroModeratoTime": 4000,
+                "dkgThresholdBps": 8000,
             },
             "alloc": {}
         });
@@ -375,6 +457,7 @@ mod tests {
             serde_json::from_value(genesis_json).expect("genesis should be valid");
 
         let chainspec = super::TempoChainSpec::from_genesis(genesis);
+        assert_eq!(chainspec.info.dkg_threshold_bps(), Some(8000));
 
         // Test Adagio activation
         let activation = chainspec.fork(TempoHardfork::Adagio);
@@ -646,4 +729,112 @@ mod tests {
             "Should return AllegroModerato after its activation time"
         );
     }
+
+    fn lanes_parent(general_gas_used: u64, payment_gas_used: u64) -> TempoHeader {
+        let mut parent = TempoHeader {
+            general_gas_limit: 225_000_000,
+            shared_gas_limit: 50_000_000,
+            lane_fees: Some(LaneFees {
+                payment_base_fee_per_gas: TEMPO_BASE_FEE,
+                general_gas_used,
+                payment_gas_used,
+            }),
+            ..Default::default()
+        };
+        parent.inner.gas_limit = 500_000_000;
+        // Subblocks are full, which doesn't count towards either lane
+        parent.inner.gas_used = general_gas_used + payment_gas_used + parent.shared_gas_limit;
+        parent.inner.base_fee_per_gas = Some(TEMPO_BASE_FEE);
+        parent
+    }
+
+    #[test]
+    fn test_base_fee_is_fixed_before_allegro_moderato() {
+        let chainspec = super::TempoChainSpecParser::parse("testnet")
+            .expect("the testnet chainspec must always be well formed");
+
+        let parent = lanes_parent(225_000_000, 225_000_000);
+        assert_eq!(
+            chainspec.next_block_base_fee(&parent, 1),
+            Some(TEMPO_BASE_FEE)
+        );
+        assert_eq!(chainspec.next_block_payment_base_fee(&parent, 1), None);
+    }
+
+    #[test]
+    fn test_lane_base_fees_start_at_tempo_base_fee() {
+        let chainspec = super::TempoChainSpecParser::parse("dev")
+            .expect("the dev chainspec must always be well formed");
+
+        let mut parent = lanes_parent(225_000_000, 225_000_000);
+        parent.lane_fees = None;
+        assert_eq!(
+            chainspec.next_block_base_fee(&parent, 1),
+            Some(TEMPO_BASE_FEE)
+        );
+        assert_eq!(
+            chainspec.next_block_payment_base_fee(&parent, 1),
+            Some(TEMPO_BASE_FEE)
+        );
+    }
+
+    #[test]
+    fn test_lane_base_fees_follow_their_lane_usage() {
+        let chainspec = super::TempoChainSpecParser::parse("dev")
+            .expect("the dev chainspec must always be well formed");
+
+        // A full general lane raises its base fee by 12.5% without affecting the payment lane
+        let parent = lanes_parent(225_000_000, 0);
+        assert_eq!(
+            chainspec.next_block_base_fee(&parent, 1),
+            Some(11_250_000_000)
+        );
+        assert_eq!(
+            chainspec.next_block_payment_base_fee(&parent, 1),
+            Some(TEMPO_BASE_FEE)
+        );
+
+        // And vice versa
+        let parent = lanes_parent(0, 225_000_000);
+        assert_eq!(
+            chainspec.next_block_base_fee(&parent, 1),
+            Some(TEMPO_BASE_FEE)
+        );
+        assert_eq!(
+            chainspec.next_block_payment_base_fee(&parent, 1),
+            Some(11_250_000_000)
+        );
+
+        // Usage at the target keeps the base fees
+        let mut parent = lanes_parent(112_500_000, 112_500_000);
+        parent.inner.base_fee_per_gas = Some(12_000_000_000);
+        parent.lane_fees.as_mut().unwrap().payment_base_fee_per_gas = 12_000_000_000;
+        assert_eq!(
+            chainspec.next_block_base_fee(&parent, 1),
+            Some(12_000_000_000)
+        );
+        assert_eq!(
+            chainspec.next_block_payment_base_fee(&parent, 1),
+            Some(12_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_payment_base_fee_is_bounded() {
+        let chainspec = super::TempoChainSpecParser::parse("dev")
+            .expect("the dev chainspec must always be well formed");
+
+        let mut parent = lanes_parent(0, 225_000_000);
+        parent.lane_fees.as_mut().unwrap().payment_base_fee_per_gas = 17_000_000_000;
+        assert_eq!(
+            chainspec.next_block_payment_base_fee(&parent, 1),
+            Some(TEMPO_MAX_PAYMENT_BASE_FEE)
+        );
+
+        let parent = lanes_parent(0, 0);
+        assert_eq!(
+            chainspec.next_block_payment_base_fee(&parent, 1),
+            Some(TEMPO_BASE_FEE)
+        );
+    }
 }
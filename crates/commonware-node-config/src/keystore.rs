@@ -0,0 +1,332 @@
+//! Passphrase-protected keystore files for consensus key material.
+//!
+//! A keystore is a JSON document holding a secret encrypted with
+//! XChaCha20-Poly1305 under a key derived from a passphrase using scrypt.
+//! The kind of the secret is authenticated as additional data, so that a
+//! keystore holding a BLS share cannot be mistaken for one holding a signing
+//! key.
+
+use std::path::Path;
+
+use chacha20poly1305::{
+    Key, XChaCha20Poly1305, XNonce,
+    aead::{Aead as _, KeyInit as _, Payload},
+};
+use rand::RngCore as _;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// The keystore format version written by this crate.
+const VERSION: u32 = 1;
+
+/// The scrypt cost parameter `log2(N)` used for new keystores.
+pub const DEFAULT_SCRYPT_LOG_N: u8 = 17;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// The highest scrypt cost parameter `log2(N)` accepted.
+///
+/// Deriving a key takes `128 * r * 2^log_n` bytes of memory, so the cost read
+/// from a keystore must be bounded.
+const MAX_SCRYPT_LOG_N: u8 = 20;
+
+const SALT_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 24;
+const KEY_LENGTH: usize = 32;
+
+/// The name of the only supported cipher.
+const CIPHER: &str = "xchacha20poly1305";
+
+/// The kind of secret stored in a keystore.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyKind {
+    /// An ed25519 signing key.
+    Ed25519SigningKey,
+    /// A share of a bls12-381 threshold signing key.
+    Bls12381Share,
+}
+
+impl KeyKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ed25519SigningKey => "ed25519-signing-key",
+            Self::Bls12381Share => "bls12381-share",
+        }
+    }
+}
+
+/// A passphrase protecting a keystore.
+///
+/// The passphrase is zeroed from memory when dropped.
+#[derive(Clone, PartialEq, Eq, derive_more::Debug)]
+pub struct Passphrase(#[debug(skip)] Zeroizing<String>);
+
+impl Passphrase {
+    pub fn new(passphrase: String) -> Self {
+        Self(Zeroizing::new(passphrase))
+    }
+
+    /// Reads the passphrase from the first line of the file at `path`.
+    ///
+    /// A trailing newline is not considered part of the passphrase.
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self, KeystoreError> {
+        let contents =
+            Zeroizing::new(std::fs::read_to_string(path).map_err(KeystoreErrorKind::Read)?);
+        let passphrase = contents.lines().next().unwrap_or_default();
+        Ok(Self::new(passphrase.to_string()))
+    }
+
+    /// Reads the passphrase from the environment variable `name`.
+    pub fn from_env(name: &str) -> Result<Self, KeystoreError> {
+        let passphrase = std::env::var(name).map_err(|source| KeystoreErrorKind::Env {
+            name: name.to_string(),
+            source,
+        })?;
+        Ok(Self::new(passphrase))
+    }
+
+    /// Derives a symmetric key from the passphrase and `salt` using scrypt with
+    /// cost parameter `log2(N)` set to `log_n`.
+    pub fn derive_key(
+        &self,
+        salt: &[u8],
+        log_n: u8,
+    ) -> Result<Zeroizing<[u8; KEY_LENGTH]>, KeystoreError> {
+        Scrypt {
+            log_n,
+            r: SCRYPT_R,
+            p: SCRYPT_P,
+            salt: salt.to_vec(),
+        }
+        .derive_key(self)
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+/// A secret encrypted under a passphrase.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Keystore {
+    version: u32,
+    kind: KeyKind,
+    kdf: Scrypt,
+    cipher: Cipher,
+    #[serde(with = "const_hex::serde")]
+    ciphertext: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Scrypt {
+    log_n: u8,
+    r: u32,
+    p: u32,
+    #[serde(with = "const_hex::serde")]
+    salt: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Cipher {
+    name: String,
+    #[serde(with = "const_hex::serde")]
+    nonce: Vec<u8>,
+}
+
+impl Keystore {
+    /// Encrypts `secret` of `kind` under `passphrase`.
+    pub fn encrypt(
+        kind: KeyKind,
+        secret: &[u8],
+        passphrase: &Passphrase,
+    ) -> Result<Self, KeystoreError> {
+        Self::encrypt_with_cost(kind, secret, passphrase, DEFAULT_SCRYPT_LOG_N)
+    }
+
+    /// Like [`Keystore::encrypt`], but with the scrypt cost parameter `log2(N)` set to `log_n`.
+    pub fn encrypt_with_cost(
+        kind: KeyKind,
+        secret: &[u8],
+        passphrase: &Passphrase,
+        log_n: u8,
+    ) -> Result<Self, KeystoreError> {
+        let mut rng = rand::thread_rng();
+        let mut salt = vec![0u8; SALT_LENGTH];
+        rng.fill_bytes(&mut salt);
+        let mut nonce = vec![0u8; NONCE_LENGTH];
+        rng.fill_bytes(&mut nonce);
+
+        let kdf = Scrypt {
+            log_n,
+            r: SCRYPT_R,
+            p: SCRYPT_P,
+            salt,
+        };
+        let key = kdf.derive_key(passphrase)?;
+        let ciphertext = XChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: secret,
+                    aad: kind.as_str().as_bytes(),
+                },
+            )
+            .map_err(|_| KeystoreErrorKind::Encrypt)?;
+
+        Ok(Self {
+            version: VERSION,
+            kind,
+            kdf,
+            cipher: Cipher {
+                name: CIPHER.to_string(),
+                nonce,
+            },
+            ciphertext,
+        })
+    }
+
+    /// Decrypts the secret of `kind` stored in this keystore.
+    pub fn decrypt(
+        &self,
+        kind: KeyKind,
+        passphrase: &Passphrase,
+    ) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+        if self.version != VERSION {
+            return Err(KeystoreErrorKind::UnsupportedVersion(self.version).into());
+        }
+        if self.kind != kind {
+            return Err(KeystoreErrorKind::KindMismatch {
+                expected: kind,
+                got: self.kind,
+            }
+            .into());
+        }
+        if self.cipher.name != CIPHER {
+            return Err(KeystoreErrorKind::UnsupportedCipher(self.cipher.name.clone()).into());
+        }
+        if self.cipher.nonce.len() != NONCE_LENGTH {
+            return Err(KeystoreErrorKind::NonceLength(self.cipher.nonce.len()).into());
+        }
+
+        let key = self.kdf.derive_key(passphrase)?;
+        let secret = XChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+            .decrypt(
+                XNonce::from_slice(&self.cipher.nonce),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: kind.as_str().as_bytes(),
+                },
+            )
+            .map_err(|_| KeystoreErrorKind::Decrypt)?;
+        Ok(Zeroizing::new(secret))
+    }
+
+    /// Returns the kind of secret stored in this keystore.
+    pub fn kind(&self) -> KeyKind {
+        self.kind
+    }
+
+    /// Returns if `contents` look like a keystore rather than a hex-encoded plaintext key.
+    pub fn is_keystore(contents: &str) -> bool {
+        contents.trim_start().starts_with('{')
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, KeystoreError> {
+        Ok(serde_json::from_str(json).map_err(KeystoreErrorKind::Json)?)
+    }
+
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self, KeystoreError> {
+        let json = std::fs::read_to_string(path).map_err(KeystoreErrorKind::Read)?;
+        Self::from_json(&json)
+    }
+
+    /// Writes the keystore to `path`, readable only by the current user on unix.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), KeystoreError> {
+        let json = serde_json::to_string_pretty(self).map_err(KeystoreErrorKind::Json)?;
+        write_private_file(path.as_ref(), json.as_bytes()).map_err(KeystoreErrorKind::Write)?;
+        Ok(())
+    }
+}
+
+impl Scrypt {
+    fn derive_key(
+        &self,
+        passphrase: &Passphrase,
+    ) -> Result<Zeroizing<[u8; KEY_LENGTH]>, KeystoreError> {
+        if self.log_n > MAX_SCRYPT_LOG_N || self.r > SCRYPT_R || self.p > SCRYPT_P {
+            return Err(KeystoreErrorKind::KdfCost {
+                log_n: self.log_n,
+                r: self.r,
+                p: self.p,
+            }
+            .into());
+        }
+        let params = scrypt::Params::new(self.log_n, self.r, self.p, KEY_LENGTH)
+            .map_err(|_| KeystoreErrorKind::KdfParams)?;
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        scrypt::scrypt(
+            passphrase.as_bytes(),
+            &self.salt,
+            &params,
+            key.as_mut_slice(),
+        )
+        .map_err(|_| KeystoreErrorKind::KdfParams)?;
+        Ok(key)
+    }
+}
+
+fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write as _;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct KeystoreError {
+    #[from]
+    inner: KeystoreErrorKind,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum KeystoreErrorKind {
+    #[error("failed decrypting keystore; the passphrase is likely wrong")]
+    Decrypt,
+    #[error("failed encrypting secret")]
+    Encrypt,
+    #[error("failed reading passphrase from environment variable `{name}`")]
+    Env {
+        name: String,
+        #[source]
+        source: std::env::VarError,
+    },
+    #[error(
+        "scrypt parameters log_n={log_n}, r={r}, p={p} exceed the maximum of \
+        log_n={MAX_SCRYPT_LOG_N}, r={SCRYPT_R}, p={SCRYPT_P}"
+    )]
+    KdfCost { log_n: u8, r: u32, p: u32 },
+    #[error("invalid scrypt parameters")]
+    KdfParams,
+    #[error("keystore holds a `{}`, but a `{}` was expected", got.as_str(), expected.as_str())]
+    KindMismatch { expected: KeyKind, got: KeyKind },
+    #[error("failed (de)serializing keystore as json")]
+    Json(#[source] serde_json::Error),
+    #[error("keystore nonce must be {NONCE_LENGTH} bytes, but was {0} bytes")]
+    NonceLength(usize),
+    #[error("failed reading file")]
+    Read(#[source] std::io::Error),
+    #[error("unsupported keystore cipher `{0}`")]
+    UnsupportedCipher(String),
+    #[error("unsupported keystore version `{0}`")]
+    UnsupportedVersion(u32),
+    #[error("failed writing to file")]
+    Write(#[source] std::io::Error),
+}
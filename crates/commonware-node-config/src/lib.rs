@@ -23,10 +23,17 @@ use serde::{
     Serialize,
     ser::{SerializeMap as _, Serializer}, // codespell:ignore ser
 };
+use zeroize::Zeroizing;
 
+pub mod keystore;
 #[cfg(test)]
 mod tests;
 
+pub use keystore::{KeyKind, Keystore, KeystoreError, Passphrase};
+
+/// Namespace that validators sign consensus messages under.
+pub const NAMESPACE: &[u8] = b"TEMPO";
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Peers {
     inner: OrderedAssociated<PublicKey, SocketAddr>,
@@ -195,12 +202,49 @@ impl SigningKey {
         Self::try_from_hex(&hex)
     }
 
+    /// Reads a signing key from `path`, holding either a hex-encoded key or a keystore.
+    ///
+    /// `passphrase` is required to decrypt a keystore.
+    pub fn read_from_file_or_keystore<P: AsRef<Path>>(
+        path: P,
+        passphrase: Option<&Passphrase>,
+    ) -> Result<Self, SigningKeyError> {
+        let contents =
+            Zeroizing::new(std::fs::read_to_string(path).map_err(SigningKeyErrorKind::Read)?);
+        if !Keystore::is_keystore(&contents) {
+            return Self::try_from_hex(&contents);
+        }
+        let passphrase = passphrase.ok_or(SigningKeyErrorKind::MissingPassphrase)?;
+        let keystore = Keystore::from_json(&contents).map_err(SigningKeyErrorKind::Keystore)?;
+        Self::try_from_keystore(&keystore, passphrase)
+    }
+
     pub fn try_from_hex(hex: &str) -> Result<Self, SigningKeyError> {
-        let bytes = const_hex::decode(hex).map_err(SigningKeyErrorKind::Hex)?;
+        let bytes = Zeroizing::new(const_hex::decode(hex).map_err(SigningKeyErrorKind::Hex)?);
         let inner = PrivateKey::decode(&bytes[..]).map_err(SigningKeyErrorKind::Parse)?;
         Ok(Self { inner })
     }
 
+    pub fn try_from_keystore(
+        keystore: &Keystore,
+        passphrase: &Passphrase,
+    ) -> Result<Self, SigningKeyError> {
+        let bytes = keystore
+            .decrypt(KeyKind::Ed25519SigningKey, passphrase)
+            .map_err(SigningKeyErrorKind::Keystore)?;
+        let inner = PrivateKey::decode(&bytes[..]).map_err(SigningKeyErrorKind::Parse)?;
+        Ok(Self { inner })
+    }
+
+    /// Encrypts the signing key under `passphrase`.
+    pub fn to_keystore(&self, passphrase: &Passphrase) -> Result<Keystore, SigningKeyError> {
+        let bytes = Zeroizing::new(self.inner.encode().to_vec());
+        Ok(
+            Keystore::encrypt(KeyKind::Ed25519SigningKey, &bytes, passphrase)
+                .map_err(SigningKeyErrorKind::Keystore)?,
+        )
+    }
+
     pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), SigningKeyError> {
         std::fs::write(path, self.to_string()).map_err(SigningKeyErrorKind::Write)?;
         Ok(())
@@ -234,6 +278,10 @@ pub struct SigningKeyError {
 enum SigningKeyErrorKind {
     #[error("failed decoding file contents as hex-encoded bytes")]
     Hex(#[source] const_hex::FromHexError),
+    #[error("failed reading signing key from keystore")]
+    Keystore(#[source] KeystoreError),
+    #[error("signing key is stored in a keystore, but no passphrase was provided")]
+    MissingPassphrase,
     #[error("failed parsing hex-decoded bytes as ed25519 private key")]
     Parse(#[source] commonware_codec::Error),
     #[error("failed reading file")]
@@ -257,12 +305,49 @@ impl SigningShare {
         Self::try_from_hex(&hex)
     }
 
+    /// Reads a signing share from `path`, holding either a hex-encoded share or a keystore.
+    ///
+    /// `passphrase` is required to decrypt a keystore.
+    pub fn read_from_file_or_keystore<P: AsRef<Path>>(
+        path: P,
+        passphrase: Option<&Passphrase>,
+    ) -> Result<Self, SigningShareError> {
+        let contents =
+            Zeroizing::new(std::fs::read_to_string(path).map_err(SigningShareErrorKind::Read)?);
+        if !Keystore::is_keystore(&contents) {
+            return Self::try_from_hex(&contents);
+        }
+        let passphrase = passphrase.ok_or(SigningShareErrorKind::MissingPassphrase)?;
+        let keystore = Keystore::from_json(&contents).map_err(SigningShareErrorKind::Keystore)?;
+        Self::try_from_keystore(&keystore, passphrase)
+    }
+
     pub fn try_from_hex(hex: &str) -> Result<Self, SigningShareError> {
-        let bytes = const_hex::decode(hex).map_err(SigningShareErrorKind::Hex)?;
+        let bytes = Zeroizing::new(const_hex::decode(hex).map_err(SigningShareErrorKind::Hex)?);
+        let inner = Share::decode(&bytes[..]).map_err(SigningShareErrorKind::Parse)?;
+        Ok(Self { inner })
+    }
+
+    pub fn try_from_keystore(
+        keystore: &Keystore,
+        passphrase: &Passphrase,
+    ) -> Result<Self, SigningShareError> {
+        let bytes = keystore
+            .decrypt(KeyKind::Bls12381Share, passphrase)
+            .map_err(SigningShareErrorKind::Keystore)?;
         let inner = Share::decode(&bytes[..]).map_err(SigningShareErrorKind::Parse)?;
         Ok(Self { inner })
     }
 
+    /// Encrypts the signing share under `passphrase`.
+    pub fn to_keystore(&self, passphrase: &Passphrase) -> Result<Keystore, SigningShareError> {
+        let bytes = Zeroizing::new(self.inner.encode().to_vec());
+        Ok(
+            Keystore::encrypt(KeyKind::Bls12381Share, &bytes, passphrase)
+                .map_err(SigningShareErrorKind::Keystore)?,
+        )
+    }
+
     pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), SigningShareError> {
         std::fs::write(path, self.to_string()).map_err(SigningShareErrorKind::Write)?;
         Ok(())
@@ -292,6 +377,10 @@ pub struct SigningShareError {
 enum SigningShareErrorKind {
     #[error("failed decoding file contents as hex-encoded bytes")]
     Hex(#[source] const_hex::FromHexError),
+    #[error("failed reading signing share from keystore")]
+    Keystore(#[source] KeystoreError),
+    #[error("signing share is stored in a keystore, but no passphrase was provided")]
+    MissingPassphrase,
     #[error("failed parsing hex-decoded bytes as bls12381 private share")]
     Parse(#[source] commonware_codec::Error),
     #[error("failed reading file")]
@@ -1,12 +1,13 @@
 use std::net::SocketAddr;
 
+use commonware_codec::Encode as _;
 use commonware_cryptography::{
     PrivateKeyExt as _, Signer as _, bls12381::primitives::variant::MinSig, ed25519::PrivateKey,
 };
 use commonware_utils::set::OrderedAssociated;
 use rand::SeedableRng as _;
 
-use crate::{Peers, PublicPolynomial, SigningKey, SigningShare};
+use crate::{KeyKind, Keystore, Passphrase, Peers, PublicPolynomial, SigningKey, SigningShare};
 
 const PEERS: &str = r#"{
 "0x945fadcd1ea3bac97c86c2acbc539fce43219552d24aaa3188c3afc1df4d50a7": "127.0.0.1:8000",
@@ -95,6 +96,124 @@ fn signing_share_roundtrip() {
     );
 }
 
+/// A cheap scrypt cost so that tests don't take seconds per keystore.
+const TEST_SCRYPT_LOG_N: u8 = 4;
+
+#[test]
+fn signing_key_keystore_roundtrip() {
+    let signing_key: SigningKey = PrivateKey::from_seed(42).into();
+    let passphrase = Passphrase::new("correct horse battery staple".to_string());
+    let keystore = Keystore::encrypt_with_cost(
+        KeyKind::Ed25519SigningKey,
+        &signing_key.clone().into_inner().encode(),
+        &passphrase,
+        TEST_SCRYPT_LOG_N,
+    )
+    .unwrap();
+
+    let json = serde_json::to_string(&keystore).unwrap();
+    assert!(Keystore::is_keystore(&json));
+    let keystore = Keystore::from_json(&json).unwrap();
+
+    assert_eq!(
+        signing_key,
+        SigningKey::try_from_keystore(&keystore, &passphrase).unwrap(),
+    );
+    assert!(
+        SigningKey::try_from_keystore(&keystore, &Passphrase::new("wrong".to_string())).is_err()
+    );
+}
+
+#[test]
+fn signing_share_keystore_roundtrip() {
+    let quorum = commonware_utils::quorum(1_u32);
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let (_, mut shares) = commonware_cryptography::bls12381::dkg::ops::generate_shares::<_, MinSig>(
+        &mut rng, None, 1, quorum,
+    );
+    let signing_share: SigningShare = shares.remove(0).into();
+    let passphrase = Passphrase::new("correct horse battery staple".to_string());
+    let keystore = Keystore::encrypt_with_cost(
+        KeyKind::Bls12381Share,
+        &signing_share.clone().into_inner().encode(),
+        &passphrase,
+        TEST_SCRYPT_LOG_N,
+    )
+    .unwrap();
+
+    assert_eq!(
+        signing_share,
+        SigningShare::try_from_keystore(&keystore, &passphrase).unwrap(),
+    );
+}
+
+#[test]
+fn keystore_kind_is_checked() {
+    let passphrase = Passphrase::new("passphrase".to_string());
+    let keystore = Keystore::encrypt_with_cost(
+        KeyKind::Bls12381Share,
+        &PrivateKey::from_seed(42).encode(),
+        &passphrase,
+        TEST_SCRYPT_LOG_N,
+    )
+    .unwrap();
+    assert!(SigningKey::try_from_keystore(&keystore, &passphrase).is_err());
+}
+
+#[test]
+fn keystores_with_excessive_scrypt_cost_are_rejected() {
+    let signing_key: SigningKey = PrivateKey::from_seed(42).into();
+    let passphrase = Passphrase::new("correct horse battery staple".to_string());
+    let keystore = Keystore::encrypt_with_cost(
+        KeyKind::Ed25519SigningKey,
+        &signing_key.into_inner().encode(),
+        &passphrase,
+        TEST_SCRYPT_LOG_N,
+    )
+    .unwrap();
+
+    // A tampered keystore must not make the node allocate 2^63 * 1KiB of memory
+    let mut json = serde_json::to_value(&keystore).unwrap();
+    json["kdf"]["logN"] = 63.into();
+    let keystore: Keystore = serde_json::from_value(json.clone()).unwrap();
+    let err = keystore
+        .decrypt(KeyKind::Ed25519SigningKey, &passphrase)
+        .unwrap_err();
+    assert!(err.to_string().contains("exceed the maximum"), "{err}");
+
+    json["kdf"]["logN"] = TEST_SCRYPT_LOG_N.into();
+    json["kdf"]["r"] = 1_000_000.into();
+    let keystore: Keystore = serde_json::from_value(json).unwrap();
+    assert!(SigningKey::try_from_keystore(&keystore, &passphrase).is_err());
+}
+
+#[test]
+fn passphrase_derives_keys_per_salt() {
+    let passphrase = Passphrase::new("correct horse battery staple".to_string());
+    let key = passphrase.derive_key(&[1; 32], TEST_SCRYPT_LOG_N).unwrap();
+    assert_eq!(
+        key,
+        passphrase.derive_key(&[1; 32], TEST_SCRYPT_LOG_N).unwrap()
+    );
+    assert_ne!(
+        key,
+        passphrase.derive_key(&[2; 32], TEST_SCRYPT_LOG_N).unwrap()
+    );
+    assert_ne!(
+        key,
+        Passphrase::new("wrong".to_string())
+            .derive_key(&[1; 32], TEST_SCRYPT_LOG_N)
+            .unwrap()
+    );
+    assert!(passphrase.derive_key(&[1; 32], 63).is_err());
+}
+
+#[test]
+fn plaintext_keys_are_not_keystores() {
+    assert!(!Keystore::is_keystore(SIGNING_KEY));
+    assert!(!Keystore::is_keystore(SIGNING_SHARE));
+}
+
 #[track_caller]
 fn assert_public_polynomial_roundtrip(nodes: u32) {
     let quorum = commonware_utils::quorum(nodes);
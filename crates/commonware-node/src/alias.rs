@@ -1,13 +1,17 @@
 //! A collection of aliases for frequently used (primarily commonware) types.
 
 pub(crate) mod marshal {
-    use commonware_consensus::{marshal, simplex::signing_scheme::bls12381_threshold::Scheme};
-    use commonware_cryptography::{bls12381::primitives::variant::MinSig, ed25519::PublicKey};
+    use commonware_consensus::{marshal, simplex::types};
 
-    use crate::consensus::block::Block;
+    use crate::{
+        consensus::{Digest, block::Block},
+        epoch::ConsensusScheme,
+    };
 
     pub(crate) type Actor<TContext> =
-        marshal::Actor<TContext, Block, crate::epoch::SchemeProvider, Scheme<PublicKey, MinSig>>;
+        marshal::Actor<TContext, Block, crate::epoch::SchemeProvider, ConsensusScheme>;
 
-    pub(crate) type Mailbox = marshal::Mailbox<Scheme<PublicKey, MinSig>, Block>;
+    pub(crate) type Mailbox = marshal::Mailbox<ConsensusScheme, Block>;
+
+    pub(crate) type Finalization = types::Finalization<ConsensusScheme, Digest>;
 }
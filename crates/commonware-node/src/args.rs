@@ -1,9 +1,11 @@
 //! Command line arguments for configuring the consensus layer of a tempo node.
 use std::{net::SocketAddr, path::PathBuf, sync::OnceLock};
 
+use commonware_codec::DecodeExt as _;
 use commonware_cryptography::ed25519::PublicKey;
-use eyre::Context;
-use tempo_commonware_node_config::SigningKey;
+use eyre::{Context, OptionExt as _};
+use tempo_commonware_node_config::{Passphrase, SigningKey, SigningShare};
+use tempo_remote_signer::ClientConfig;
 
 const DEFAULT_MAX_MESSAGE_SIZE_BYTES: usize = reth_consensus_common::validation::MAX_RLP_BLOCK_SIZE;
 
@@ -13,13 +15,76 @@ pub struct Args {
     /// The file containing the ed25519 signing key for p2p communication.
     #[arg(
         long = "consensus.signing-key",
-        required_unless_present_any = ["follow", "dev"],
+        required_unless_present_any = ["follow", "dev", "remote_signer"],
     )]
     signing_key: Option<PathBuf>,
 
+    /// The socket address of a remote signer holding the ed25519 signing key.
+    /// Replaces `--consensus.signing-key`.
+    ///
+    /// The bls12-381 share is still read from `--consensus.signing-share`.
+    #[arg(
+        long = "consensus.remote-signer",
+        conflicts_with = "signing_key",
+        requires_all = [
+            "remote_signer_identity",
+            "remote_signer_client_key",
+            "validator_public_key",
+        ],
+    )]
+    remote_signer: Option<SocketAddr>,
+
+    /// The hex-encoded ed25519 public key the remote signer authenticates
+    /// itself with.
+    #[arg(
+        long = "consensus.remote-signer-identity",
+        value_name = "PUBLIC_KEY",
+        value_parser = parse_public_key,
+    )]
+    remote_signer_identity: Option<PublicKey>,
+
+    /// The file containing the ed25519 key this node authenticates itself
+    /// with to the remote signer.
+    #[arg(long = "consensus.remote-signer-client-key", value_name = "FILE")]
+    remote_signer_client_key: Option<PathBuf>,
+
+    /// The hex-encoded ed25519 public key of the validator. Startup fails if
+    /// the remote signer holds a different key.
+    #[arg(
+        long = "consensus.validator-public-key",
+        value_name = "PUBLIC_KEY",
+        value_parser = parse_public_key,
+        requires = "remote_signer",
+    )]
+    validator_public_key: Option<PublicKey>,
+
+    /// The amount of time to wait for the remote signer to respond.
+    #[arg(long = "consensus.remote-signer-timeout", default_value = "2s")]
+    remote_signer_timeout: jiff::SignedDuration,
+
     /// The file containing a share of the bls12-381 threshold signing key.
     #[arg(long = "consensus.signing-share")]
-    pub signing_share: Option<PathBuf>,
+    signing_share: Option<PathBuf>,
+
+    /// The file containing the passphrase to decrypt the signing key and share
+    /// if they are stored in keystores.
+    ///
+    /// Setting a passphrase also encrypts the DKG ceremony state persisted
+    /// to disk.
+    #[arg(
+        long = "consensus.keystore-passphrase-file",
+        value_name = "FILE",
+        conflicts_with = "keystore_passphrase_env"
+    )]
+    keystore_passphrase_file: Option<PathBuf>,
+
+    /// The name of the environment variable containing the passphrase to
+    /// decrypt the signing key and share if they are stored in keystores.
+    ///
+    /// Setting a passphrase also encrypts the DKG ceremony state persisted
+    /// to disk.
+    #[arg(long = "consensus.keystore-passphrase-env", value_name = "VAR")]
+    keystore_passphrase_env: Option<String>,
 
     /// The socket address that will be bound to listen for consensus communication from
     /// other nodes.
@@ -127,9 +192,27 @@ pub struct Args {
     #[arg(long = "consensus.subblock-broadcast-interval", default_value = "50ms")]
     pub subblock_broadcast_interval: jiff::SignedDuration,
 
+    /// A partner whose subblock transactions pay their fees to its own
+    /// recipient instead of `consensus.fee-recipient`, given as
+    /// `<SENDER>=<FEE_RECIPIENT>`. Can be repeated.
+    ///
+    /// Only takes effect once subblocks carry a fee recipient per transaction
+    /// (from Allegro-Moderato on).
+    #[arg(
+        long = "consensus.subblock-partner",
+        value_name = "SENDER=FEE_RECIPIENT",
+        value_parser = parse_subblock_partner,
+    )]
+    pub subblock_partners: Vec<(alloy_primitives::Address, alloy_primitives::Address)>,
+
     /// Cache for the signing key loaded from CLI-provided file.
     #[clap(skip)]
     loaded_signing_key: OnceLock<Option<SigningKey>>,
+
+    /// Cache for the keystore passphrase read from the CLI-provided file or
+    /// environment variable.
+    #[clap(skip)]
+    loaded_keystore_passphrase: OnceLock<Option<Passphrase>>,
 }
 
 impl Args {
@@ -143,12 +226,13 @@ impl Args {
             .signing_key
             .as_ref()
             .map(|path| {
-                SigningKey::read_from_file(path).wrap_err_with(|| {
-                    format!(
-                        "failed reading private ed25519 signing key share from file `{}`",
-                        path.display()
-                    )
-                })
+                SigningKey::read_from_file_or_keystore(path, self.keystore_passphrase()?.as_ref())
+                    .wrap_err_with(|| {
+                        format!(
+                            "failed reading private ed25519 signing key share from file `{}`",
+                            path.display()
+                        )
+                    })
             })
             .transpose()?;
 
@@ -157,10 +241,110 @@ impl Args {
         Ok(signing_key)
     }
 
-    /// Returns the public key derived from the configured signing key, if any.
+    /// Returns the bls12-381 signing share loaded from the specified file.
+    pub(crate) fn signing_share(&self) -> eyre::Result<Option<SigningShare>> {
+        self.signing_share
+            .as_ref()
+            .map(|path| {
+                SigningShare::read_from_file_or_keystore(path, self.keystore_passphrase()?.as_ref())
+                    .wrap_err_with(|| {
+                        format!(
+                            "failed reading private bls12-381 key share from file `{}`",
+                            path.display()
+                        )
+                    })
+            })
+            .transpose()
+    }
+
+    /// Returns the keystore passphrase read from the configured file or
+    /// environment variable, if any.
+    pub(crate) fn keystore_passphrase(&self) -> eyre::Result<Option<Passphrase>> {
+        if let Some(passphrase) = self.loaded_keystore_passphrase.get() {
+            return Ok(passphrase.clone());
+        }
+
+        let passphrase = if let Some(path) = &self.keystore_passphrase_file {
+            Passphrase::read_from_file(path)
+                .map(Some)
+                .wrap_err_with(|| {
+                    format!(
+                        "failed reading keystore passphrase from file `{}`",
+                        path.display()
+                    )
+                })?
+        } else if let Some(name) = &self.keystore_passphrase_env {
+            Passphrase::from_env(name)
+                .map(Some)
+                .wrap_err("failed reading keystore passphrase from environment")?
+        } else {
+            None
+        };
+
+        let _ = self.loaded_keystore_passphrase.set(passphrase.clone());
+
+        Ok(passphrase)
+    }
+
+    /// Returns the configuration to connect to the remote signer, if any.
+    pub(crate) fn remote_signer(&self) -> eyre::Result<Option<ClientConfig>> {
+        let Some(address) = self.remote_signer else {
+            return Ok(None);
+        };
+        let identity = self
+            .remote_signer_client_key
+            .as_ref()
+            .ok_or_eyre("required option `consensus.remote-signer-client-key` not set")?;
+        let identity =
+            SigningKey::read_from_file_or_keystore(identity, self.keystore_passphrase()?.as_ref())
+                .wrap_err_with(|| {
+                    format!(
+                        "failed reading remote signer client key from file `{}`",
+                        identity.display()
+                    )
+                })?;
+        Ok(Some(ClientConfig {
+            address,
+            identity: identity.into_inner(),
+            signer_identity: self
+                .remote_signer_identity
+                .clone()
+                .ok_or_eyre("required option `consensus.remote-signer-identity` not set")?,
+            timeout: self.remote_signer_timeout.try_into().wrap_err(
+                "failed converting argument remote-signer-timeout to regular \
+                duration; was it negative or chosen too large",
+            )?,
+        }))
+    }
+
+    /// Returns the public key derived from the configured signing key, or
+    /// the validator public key if the signing key is held by a remote
+    /// signer.
     pub fn public_key(&self) -> eyre::Result<Option<PublicKey>> {
+        if self.remote_signer.is_some() {
+            return Ok(self.validator_public_key.clone());
+        }
         Ok(self
             .signing_key()?
             .map(|signing_key| signing_key.public_key()))
     }
 }
+
+fn parse_subblock_partner(
+    partner: &str,
+) -> eyre::Result<(alloy_primitives::Address, alloy_primitives::Address)> {
+    let (sender, fee_recipient) = partner
+        .split_once('=')
+        .ok_or_eyre("expected `<SENDER>=<FEE_RECIPIENT>`")?;
+    Ok((
+        sender.parse().wrap_err("failed parsing sender address")?,
+        fee_recipient
+            .parse()
+            .wrap_err("failed parsing fee recipient address")?,
+    ))
+}
+
+fn parse_public_key(hex: &str) -> eyre::Result<PublicKey> {
+    let bytes = const_hex::decode(hex).wrap_err("failed decoding hex")?;
+    PublicKey::decode(&bytes[..]).wrap_err("failed decoding ed25519 public key")
+}
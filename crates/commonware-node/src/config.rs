@@ -23,6 +23,7 @@ pub(crate) const MARSHAL_CHANNEL_IDENT: commonware_p2p::Channel = 4;
 pub(crate) const DKG_CHANNEL_IDENT: commonware_p2p::Channel = 5;
 pub(crate) const BOUNDARY_CERT_CHANNEL_IDENT: commonware_p2p::Channel = 6;
 pub(crate) const SUBBLOCKS_CHANNEL_IDENT: commonware_p2p::Channel = 7;
+pub(crate) const DECRYPTION_CHANNEL_IDENT: commonware_p2p::Channel = 8;
 
 pub(crate) const NUMBER_CONCURRENT_FETCHES: usize = 4;
 
@@ -32,6 +33,8 @@ pub(crate) const BROADCASTER_LIMIT: Quota =
     Quota::per_second(NonZeroU32::new(8).expect("value is not zero"));
 pub(crate) const BOUNDARY_CERT_LIMIT: Quota =
     Quota::per_second(NonZeroU32::new(1).expect("value is not zero"));
+pub(crate) const DECRYPTION_LIMIT: Quota =
+    Quota::per_second(NonZeroU32::new(128).expect("value is not zero"));
 pub(crate) const DKG_LIMIT: Quota =
     Quota::per_second(NonZeroU32::new(128).expect("value is not zero"));
 pub(crate) const MARSHAL_LIMIT: Quota =
@@ -45,7 +48,7 @@ pub(crate) const RESOLVER_LIMIT: Quota =
 pub(crate) const SUBBLOCKS_LIMIT: Quota =
     Quota::per_second(NonZeroU32::new(128).expect("value is not zero"));
 
-pub(crate) const NAMESPACE: &[u8] = b"TEMPO";
+pub(crate) use tempo_commonware_node_config::NAMESPACE;
 
 /// The number of peer sets that will be active in the lookup p2p network.
 pub(crate) const PEERSETS_TO_TRACK: usize = 3;
@@ -12,7 +12,7 @@
 
 use std::{sync::Arc, time::Duration};
 
-use alloy_consensus::BlockHeader;
+use alloy_consensus::{BlockHeader, Transaction as _};
 use alloy_primitives::{B256, Bytes};
 use alloy_rpc_types_engine::PayloadId;
 use commonware_codec::{DecodeExt as _, Encode as _};
@@ -37,15 +37,18 @@ use futures::{
 };
 use rand::{CryptoRng, Rng};
 use reth_node_builder::ConsensusEngineHandle;
-use reth_primitives_traits::SealedBlock;
+use reth_primitives_traits::{Recovered, SealedBlock};
 use tempo_dkg_onchain_artifacts::PublicOutcome;
-use tempo_node::{TempoExecutionData, TempoFullNode, TempoPayloadTypes};
+use tempo_node::{PrebuildHandle, TempoExecutionData, TempoFullNode, TempoPayloadTypes};
 
 use reth_provider::BlockReader as _;
 use tokio::sync::RwLock;
 use tracing::{Level, debug, error, error_span, info, instrument, warn};
 
-use tempo_payload_types::TempoPayloadBuilderAttributes;
+use tempo_payload_types::{DecryptedTransactions, TempoPayloadBuilderAttributes};
+use tempo_primitives::{
+    ENCRYPTED_TX_ADDRESS, TempoTxEnvelope, transaction::encrypted::decode_decryption_keys,
+};
 
 use super::{
     Mailbox, executor,
@@ -54,6 +57,7 @@ use super::{
 };
 use crate::{
     consensus::{Digest, block::Block},
+    decryption,
     epoch::SchemeProvider,
     subblocks,
 };
@@ -103,8 +107,10 @@ where
 
                 execution_node: config.execution_node,
                 subblocks: config.subblocks,
+                decryption: config.decryption,
 
                 scheme_provider: config.scheme_provider,
+                prebuild: config.prebuild,
 
                 state: Uninit(()),
             },
@@ -211,7 +217,9 @@ struct Inner<TState> {
     genesis_block: Arc<Block>,
     execution_node: TempoFullNode,
     subblocks: subblocks::Mailbox,
+    decryption: decryption::Mailbox,
     scheme_provider: SchemeProvider,
+    prebuild: PrebuildHandle,
 
     state: TState,
 }
@@ -364,6 +372,8 @@ impl Inner<Init> {
             &proposal,
             parent_digest,
             &self.scheme_provider,
+            // Our own proposal includes the decrypted transactions of its parent.
+            None,
         )
         .await
         .wrap_err("failed verifying block against execution layer")?;
@@ -488,6 +498,7 @@ impl Inner<Init> {
             // It is safe to not verify the parent of the parent because this block is already notarized.
             parent.parent_digest(),
             &self.scheme_provider,
+            None,
         )
         .await
         .wrap_err("failed verifying block against execution layer")?
@@ -553,7 +564,24 @@ impl Inner<Init> {
             }
         };
 
-        let attrs = TempoPayloadBuilderAttributes::new(
+        let decrypted = self
+            .decrypt_parent_transactions(&parent)
+            .await
+            .wrap_err("failed decrypting encrypted transactions of parent block")?;
+
+        // Propose for the timestamp our payload was prebuilt for when the parent was notarized, so
+        // that the prebuilt transactions are not executed again.
+        let now = context.current().epoch_millis();
+        let timestamp_millis = self
+            .prebuild
+            .timestamp_millis(parent.block_hash())
+            .filter(|timestamp| {
+                *timestamp > parent.header().timestamp_millis() && *timestamp <= now
+            })
+            .unwrap_or(now);
+
+        let dropped_subblocks = self.subblocks.clone();
+        let mut attrs = TempoPayloadBuilderAttributes::new(
             // XXX: derives the payload ID from the parent so that
             // overlong payload builds will eventually succeed on the
             // next iteration: if all other nodes take equally as long,
@@ -564,14 +592,20 @@ impl Inner<Init> {
             payload_id_from_block_hash(&parent.block_hash()),
             parent.block_hash(),
             self.fee_recipient,
-            context.current().epoch_millis(),
+            timestamp_millis,
             extra_data,
             move || {
                 self.subblocks
                     .get_subblocks(parent.block_hash())
                     .unwrap_or_default()
             },
-        );
+        )
+        .with_dropped_subblock_reporter(move |subblock, reason| {
+            dropped_subblocks.report_dropped_subblock(subblock, reason)
+        });
+        if let Some(decrypted) = decrypted {
+            attrs = attrs.with_decrypted_transactions(decrypted);
+        }
 
         let interrupt_handle = attrs.interrupt_handle().clone();
 
@@ -669,6 +703,22 @@ impl Inner<Init> {
             return Ok((block, false));
         }
 
+        let decrypted_transactions = match verify_decrypted_transactions(
+            &block,
+            &parent,
+            &self.scheme_provider,
+            round.epoch(),
+        ) {
+            Ok(decrypted_transactions) => decrypted_transactions,
+            Err(reason) => {
+                warn!(
+                    %reason,
+                    "decrypted transactions could not be verified; failing block",
+                );
+                return Ok((block, false));
+            }
+        };
+
         if let Err(error) = self
             .state
             .executor_mailbox
@@ -693,12 +743,54 @@ impl Inner<Init> {
             &block,
             parent_digest,
             &self.scheme_provider,
+            decrypted_transactions,
         )
         .await
         .wrap_err("failed verifying block against execution layer")?;
 
         Ok((block, is_good))
     }
+
+    /// Decrypts the encrypted transactions of `parent` to include them at the start of the
+    /// proposal.
+    ///
+    /// Returns `None` if the parent has no encrypted transactions. Otherwise waits for the parent
+    /// to be finalized and its decryption keys to be recovered, since the proposal must include
+    /// them. Consensus gives up on the proposal if that takes too long.
+    async fn decrypt_parent_transactions(
+        &self,
+        parent: &Block,
+    ) -> eyre::Result<Option<DecryptedTransactions>> {
+        let encrypted = decryption::encrypted_transactions(&parent.body().transactions)
+            .wrap_err("failed reading encrypted transactions of parent block")?;
+        if encrypted.is_empty() {
+            return Ok(None);
+        }
+
+        debug!(
+            ciphertexts = encrypted.len(),
+            "waiting for decryption keys of parent block",
+        );
+        let keys = self
+            .decryption
+            .get_keys(parent.block_hash())
+            .await
+            .ok_or_eyre("decryption service went away")?;
+
+        let transactions = decryption::decrypt_transactions(&encrypted, &keys);
+
+        info!(
+            ciphertexts = encrypted.len(),
+            decrypted = transactions.len(),
+            "decrypted encrypted transactions of parent block; will include in payload builder \
+            attributes",
+        );
+
+        Ok(Some(DecryptedTransactions {
+            keys: keys.iter().map(tempo_encryption::encode_key).collect(),
+            transactions,
+        }))
+    }
 }
 
 impl Inner<Uninit> {
@@ -739,7 +831,9 @@ impl Inner<Uninit> {
                 _executor_handle: AbortOnDrop(executor_handle).into(),
             },
             subblocks: self.subblocks,
+            decryption: self.decryption,
             scheme_provider: self.scheme_provider,
+            prebuild: self.prebuild,
         };
 
         Ok(initialized)
@@ -792,6 +886,7 @@ async fn verify_block<TContext: Pacer>(
     block: &Block,
     parent_digest: Digest,
     scheme_provider: &SchemeProvider,
+    decrypted_transactions: Option<Vec<Recovered<TempoTxEnvelope>>>,
 ) -> eyre::Result<bool> {
     use alloy_rpc_types_engine::PayloadStatusEnum;
 
@@ -819,6 +914,7 @@ async fn verify_block<TContext: Pacer>(
                 .map(|p| B256::from_slice(p))
                 .collect(),
         ),
+        decrypted_transactions,
     };
     let payload_status = engine
         .new_payload(execution_data)
@@ -896,7 +992,7 @@ async fn verify_header_extra_data(
 
         ensure!(
             dkg_manager
-                .verify_intermediate_dealings(dealing)
+                .verify_intermediate_dealings(dealing, block.timestamp())
                 .await
                 .wrap_err("failed request to verify DKG dealing")?,
             "signature of intermediate DKG outcome could not be verified",
@@ -906,6 +1002,69 @@ async fn verify_header_extra_data(
     Ok(())
 }
 
+/// Verifies the decryption keys of the encrypted transactions in `parent` released in `block`.
+///
+/// If the parent has encrypted transactions, the block must release exactly one valid key per
+/// encrypted transaction. Returns the transactions these keys decrypt to, whose inclusion is
+/// enforced by the block executor: they must immediately follow the keys in the order of their
+/// ciphertexts, and may only be left out if they don't fit into the block or are invalid.
+#[instrument(skip_all, err(Display))]
+fn verify_decrypted_transactions(
+    block: &Block,
+    parent: &Block,
+    scheme_provider: &SchemeProvider,
+    epoch: Epoch,
+) -> eyre::Result<Option<Vec<Recovered<TempoTxEnvelope>>>> {
+    let keys_tx = block
+        .body()
+        .transactions
+        .iter()
+        .find(|tx| tx.is_system_tx() && tx.to() == Some(ENCRYPTED_TX_ADDRESS));
+
+    let encrypted = decryption::encrypted_transactions(&parent.body().transactions)
+        .wrap_err("failed reading encrypted transactions of parent block")?;
+    let Some(keys_tx) = keys_tx else {
+        ensure!(
+            encrypted.is_empty(),
+            "parent has `{}` encrypted transactions, but the block does not release their \
+            decryption keys",
+            encrypted.len(),
+        );
+        return Ok(None);
+    };
+    ensure!(
+        !encrypted.is_empty(),
+        "block contains decryption keys, but its parent has no encrypted transactions",
+    );
+
+    let keys = decode_decryption_keys(keys_tx.input(), block.height())
+        .ok_or_eyre("invalid decryption keys system transaction")?;
+    ensure!(
+        keys.len() == encrypted.len(),
+        "block contains `{}` decryption keys, but its parent has `{}` encrypted transactions",
+        keys.len(),
+        encrypted.len(),
+    );
+
+    let scheme = scheme_provider
+        .scheme(epoch)
+        .ok_or_eyre("cannot determine group public key in the current epoch")?;
+    let keys = encrypted
+        .iter()
+        .zip(&keys)
+        .map(|((target, _), key)| {
+            let key = tempo_encryption::decode_key(key).wrap_err("invalid decryption key")?;
+            ensure!(
+                tempo_encryption::verify_key(scheme.identity(), *target, &key),
+                "decryption key does not match target `{target}`",
+            );
+            Ok(key)
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    Ok(Some(decryption::decrypt_transactions(&encrypted, &keys)))
+}
+
 /// Constructs a [`PayloadId`] from the first 8 bytes of `block_hash`.
 fn payload_id_from_block_hash(block_hash: &B256) -> PayloadId {
     PayloadId::new(
@@ -374,6 +374,7 @@ where
                 block: Arc::new(block),
                 // can be omitted for finalized blocks
                 validator_set: None,
+                decrypted_transactions: None,
             })
             .pace(&self.context, Duration::from_millis(20))
             .await
@@ -421,6 +422,7 @@ where
                     block: Arc::new(block.into_inner()),
                     // can be omitted for finalized blocks
                     validator_set: None,
+                    decrypted_transactions: None,
                 })
                 .pace(&self.context, Duration::from_millis(20))
                 .await
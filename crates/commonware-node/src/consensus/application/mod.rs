@@ -6,7 +6,7 @@ use commonware_runtime::{Metrics, Pacer, Spawner, Storage};
 
 use eyre::WrapErr as _;
 use rand::{CryptoRng, Rng};
-use tempo_node::TempoFullNode;
+use tempo_node::{PrebuildHandle, TempoFullNode};
 
 mod executor;
 
@@ -16,7 +16,7 @@ mod ingress;
 pub(super) use actor::Actor;
 pub(crate) use ingress::Mailbox;
 
-use crate::{epoch::SchemeProvider, subblocks};
+use crate::{decryption, epoch::SchemeProvider, subblocks};
 
 pub(super) async fn init<TContext>(
     config: Config<TContext>,
@@ -51,6 +51,10 @@ pub(super) struct Config<TContext> {
     /// A handle to the subblocks service to get subblocks for proposals.
     pub(crate) subblocks: subblocks::Mailbox,
 
+    /// A handle to the decryption service to get the decryption keys of the parent's encrypted
+    /// transactions for proposals.
+    pub(crate) decryption: decryption::Mailbox,
+
     /// The minimum amount of time to wait before resolving a new payload from the builder
     pub(super) new_payload_wait_time: Duration,
 
@@ -61,4 +65,7 @@ pub(super) struct Config<TContext> {
 
     /// The scheme provider to use for the application.
     pub(crate) scheme_provider: SchemeProvider,
+
+    /// Handle to the payload prebuilds, whose timestamps are used for our proposals.
+    pub(crate) prebuild: PrebuildHandle,
 }
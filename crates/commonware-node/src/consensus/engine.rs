@@ -3,17 +3,17 @@
 //! [`alto`]: https://github.com/commonwarexyx/alto
 
 use std::{
+    collections::HashMap,
     net::SocketAddr,
     num::{NonZeroU64, NonZeroUsize},
     time::Duration,
 };
 
 use commonware_broadcast::buffered;
-use commonware_consensus::{Reporters, marshal};
+use commonware_codec::DecodeExt as _;
+use commonware_consensus::{Reporter as _, Reporters, marshal, simplex::types::Activity};
 use commonware_cryptography::{
-    Signer as _,
-    bls12381::primitives::group::Share,
-    ed25519::{PrivateKey, PublicKey},
+    Signer as _, bls12381::primitives::group::Share, ed25519::PublicKey,
 };
 use commonware_p2p::{Blocker, Receiver, Sender};
 use commonware_runtime::{
@@ -24,14 +24,19 @@ use commonware_utils::set::OrderedAssociated;
 use eyre::{OptionExt as _, WrapErr as _};
 use futures::future::try_join_all;
 use rand::{CryptoRng, Rng};
-use tempo_node::TempoFullNode;
-use tracing::info;
+use tempo_commonware_node_config::Passphrase;
+use tempo_node::{PrebuildHandle, TempoFullNode, rpc::ConsensusHandle};
+use tempo_snapshot::Checkpoint;
+use tracing::{info, warn};
 
 use crate::{
     config::{BLOCKS_FREEZER_TABLE_INITIAL_SIZE_BYTES, MARSHAL_LIMIT},
-    consensus::application,
-    dkg,
+    consensus::{application, prebuild::PrebuildReporter, rpc, status::StatusReporter},
+    decryption, dkg,
     epoch::{self, SchemeProvider},
+    evidence,
+    peers::PeerActivity,
+    signer::ConsensusSigner,
     subblocks,
 };
 
@@ -68,12 +73,25 @@ pub struct Builder<TBlocker, TContext, TPeerManager> {
 
     pub execution_node: Option<TempoFullNode>,
 
+    /// Handle through which the parents of the payloads we are going to
+    /// propose are announced to the payload builder ahead of time.
+    pub prebuild: PrebuildHandle,
+
     pub blocker: TBlocker,
     pub peer_manager: TPeerManager,
 
     pub partition_prefix: String,
-    pub signer: PrivateKey,
+    pub signer: ConsensusSigner,
     pub share: Option<Share>,
+
+    /// Encrypts the DKG ceremony state persisted to disk under a key derived
+    /// from this passphrase, if set.
+    pub keystore_passphrase: Option<Passphrase>,
+
+    /// The finalized epoch boundary a state snapshot was imported at, if the
+    /// node was started from one.
+    pub checkpoint: Option<Checkpoint>,
+
     pub mailbox_size: usize,
     pub deque_size: usize,
 
@@ -86,6 +104,7 @@ pub struct Builder<TBlocker, TContext, TPeerManager> {
     pub new_payload_wait_time: Duration,
     pub time_to_build_subblock: Duration,
     pub subblock_broadcast_interval: Duration,
+    pub subblock_partners: HashMap<alloy_primitives::Address, alloy_primitives::Address>,
 }
 
 impl<TBlocker, TContext, TPeerManager> Builder<TBlocker, TContext, TPeerManager>
@@ -190,17 +209,40 @@ where
         )
         .await;
 
+        if let Some(checkpoint) = &self.checkpoint {
+            seed_marshal_from_checkpoint(marshal_mailbox.clone(), checkpoint)
+                .await
+                .wrap_err("failed seeding marshal with the snapshot checkpoint")?;
+        }
+
+        let status = StatusReporter::new(scheme_provider.clone());
+
+        let (evidence, evidence_mailbox) = evidence::init(
+            self.context.with_label("evidence"),
+            evidence::Config {
+                partition_prefix: format!("{}_evidence", self.partition_prefix),
+                scheme_provider: scheme_provider.clone(),
+            },
+        )
+        .await;
+
         let subblocks = subblocks::Actor::new(subblocks::Config {
             context: self.context.clone(),
             signer: self.signer.clone(),
             scheme_provider: scheme_provider.clone(),
             node: execution_node.clone(),
             fee_recipient: self.fee_recipient,
+            partners: self.subblock_partners,
             time_to_build_subblock: self.time_to_build_subblock,
             subblock_broadcast_interval: self.subblock_broadcast_interval,
             epoch_length,
         });
 
+        let decryption = decryption::Actor::new(decryption::Config {
+            scheme_provider: scheme_provider.clone(),
+            node: execution_node.clone(),
+        });
+
         let (application, application_mailbox) = application::init(super::application::Config {
             context: self.context.with_label("application"),
             // TODO: pass in from the outside,
@@ -210,12 +252,21 @@ where
             execution_node: execution_node.clone(),
             new_payload_wait_time: self.new_payload_wait_time,
             subblocks: subblocks.mailbox(),
+            decryption: decryption.mailbox(),
             scheme_provider: scheme_provider.clone(),
             epoch_length,
+            prebuild: self.prebuild.clone(),
         })
         .await
         .wrap_err("failed initializing application actor")?;
 
+        let prebuild = PrebuildReporter::new(
+            self.signer.public_key(),
+            self.fee_recipient,
+            scheme_provider.clone(),
+            self.prebuild.clone(),
+        );
+
         let (epoch_manager, epoch_manager_mailbox) = epoch::manager::init(
             epoch::manager::Config {
                 application: application_mailbox.clone(),
@@ -226,8 +277,13 @@ where
                 time_to_propose: self.time_to_propose,
                 mailbox_size: self.mailbox_size,
                 subblocks: subblocks.mailbox(),
+                decryption: decryption.mailbox(),
                 marshal: marshal_mailbox.clone(),
                 scheme_provider: scheme_provider.clone(),
+                remote_signer: self.signer.remote().cloned(),
+                status: status.clone(),
+                evidence: evidence_mailbox.clone(),
+                prebuild,
                 time_to_collect_notarizations: self.time_to_collect_notarizations,
                 time_to_retry_nullify_broadcast: self.time_to_retry_nullify_broadcast,
                 partition_prefix: format!("{}_epoch_manager", self.partition_prefix),
@@ -242,12 +298,14 @@ where
             dkg::manager::Config {
                 epoch_manager: epoch_manager_mailbox,
                 epoch_length,
-                execution_node,
+                execution_node: execution_node.clone(),
                 initial_share: self.share.clone(),
                 mailbox_size: self.mailbox_size,
-                marshal: marshal_mailbox,
+                marshal: marshal_mailbox.clone(),
                 namespace: crate::config::NAMESPACE.to_vec(),
                 me: self.signer.clone(),
+                state_passphrase: self.keystore_passphrase.clone(),
+                checkpoint: self.checkpoint.clone(),
                 partition_prefix: format!("{}_dkg_manager", self.partition_prefix),
                 peer_manager: self.peer_manager.clone(),
             },
@@ -255,6 +313,20 @@ where
         .await
         .wrap_err("failed initializing dkg manager")?;
 
+        let peer_activity = PeerActivity::default();
+
+        let rpc = rpc::ConsensusRpc::new(
+            marshal_mailbox,
+            dkg_manager_mailbox.clone(),
+            peer_activity.clone(),
+            evidence_mailbox,
+            subblocks.mailbox(),
+            scheme_provider,
+            status,
+            execution_node,
+            epoch_length,
+        );
+
         Ok(Engine {
             context: ContextCell::new(self.context),
 
@@ -273,6 +345,14 @@ where
             epoch_manager,
 
             subblocks,
+
+            decryption,
+
+            evidence,
+
+            peer_activity,
+
+            rpc,
         })
     }
 }
@@ -318,6 +398,18 @@ where
     epoch_manager: epoch::manager::Actor<TBlocker, TContext>,
 
     subblocks: subblocks::Actor<TContext>,
+
+    /// Releases the decryption keys of encrypted transactions in notarized blocks.
+    decryption: decryption::Actor,
+
+    /// Records conflicting messages signed by validators.
+    evidence: evidence::Actor<TContext>,
+
+    /// Records which peers messages are received from.
+    peer_activity: PeerActivity,
+
+    /// Serves consensus state to the execution node's RPC server.
+    rpc: rpc::ConsensusRpc,
 }
 
 impl<TBlocker, TContext, TPeerManager> Engine<TBlocker, TContext, TPeerManager>
@@ -337,6 +429,14 @@ where
             Peers = OrderedAssociated<PublicKey, SocketAddr>,
         >,
 {
+    /// Serves this engine's consensus state through the `consensus_` RPC namespace of the
+    /// execution node.
+    pub fn install_rpc(&self, handle: &ConsensusHandle) {
+        if !handle.install(self.rpc.clone()) {
+            warn!("consensus RPC provider was already installed; ignoring");
+        }
+    }
+
     #[expect(
         clippy::too_many_arguments,
         reason = "following commonware's style of writing"
@@ -375,6 +475,10 @@ where
             impl Sender<PublicKey = PublicKey>,
             impl Receiver<PublicKey = PublicKey>,
         ),
+        decryption_channel: (
+            impl Sender<PublicKey = PublicKey>,
+            impl Receiver<PublicKey = PublicKey>,
+        ),
     ) -> Handle<eyre::Result<()>> {
         spawn_cell!(
             self.context,
@@ -387,6 +491,7 @@ where
                 dkg_channel,
                 boundary_certificates_channel,
                 subblocks_channel,
+                decryption_channel,
             )
             .await
         )
@@ -430,7 +535,21 @@ where
             impl Sender<PublicKey = PublicKey>,
             impl Receiver<PublicKey = PublicKey>,
         ),
+        decryption_channel: (
+            impl Sender<PublicKey = PublicKey>,
+            impl Receiver<PublicKey = PublicKey>,
+        ),
     ) -> eyre::Result<()> {
+        let pending_channel = self.peer_activity.track(pending_channel);
+        let recovered_channel = self.peer_activity.track(recovered_channel);
+        let resolver_channel = self.peer_activity.track(resolver_channel);
+        let broadcast_channel = self.peer_activity.track(broadcast_channel);
+        let marshal_channel = self.peer_activity.track(marshal_channel);
+        let dkg_channel = self.peer_activity.track(dkg_channel);
+        let boundary_certificates_channel = self.peer_activity.track(boundary_certificates_channel);
+        let subblocks_channel = self.peer_activity.track(subblocks_channel);
+        let decryption_channel = self.peer_activity.track(decryption_channel);
+
         let broadcast = self.broadcast.start(broadcast_channel);
         let resolver =
             marshal::resolver::p2p::init(&self.context, self.resolver_config, marshal_channel);
@@ -454,8 +573,14 @@ where
             .context
             .spawn(|_| self.subblocks.run(subblocks_channel));
 
+        let decryption = self
+            .context
+            .spawn(|_| self.decryption.run(decryption_channel));
+
         let dkg_manager = self.dkg_manager.start(dkg_channel);
 
+        let evidence = self.evidence.start();
+
         try_join_all(vec![
             application,
             broadcast,
@@ -463,6 +588,8 @@ where
             marshal,
             dkg_manager,
             subblocks,
+            decryption,
+            evidence,
         ])
         .await
         .map(|_| ())
@@ -471,3 +598,26 @@ where
         .wrap_err("one of the consensus engine's actors failed")
     }
 }
+
+/// Makes marshal start at the block of `checkpoint` instead of genesis.
+///
+/// The checkpoint's finalization is reported to marshal so that it fetches
+/// the boundary block from peers, and all heights up to and including the
+/// boundary are marked as processed: their state was imported from the
+/// snapshot and they are never backfilled.
+async fn seed_marshal_from_checkpoint(
+    mut marshal: crate::alias::marshal::Mailbox,
+    checkpoint: &Checkpoint,
+) -> eyre::Result<()> {
+    let finalization = crate::alias::marshal::Finalization::decode(checkpoint.certificate.as_ref())
+        .wrap_err("failed decoding finalization certificate of checkpoint")?;
+    info!(
+        height = checkpoint.height(),
+        hash = %checkpoint.hash(),
+        epoch = finalization.proposal.round.epoch(),
+        "starting marshal from snapshot checkpoint",
+    );
+    marshal.report(Activity::Finalization(finalization)).await;
+    marshal.set_floor(checkpoint.height()).await;
+    Ok(())
+}
@@ -4,6 +4,9 @@ pub(crate) mod application;
 pub(crate) mod block;
 pub(crate) mod digest;
 pub(crate) mod engine;
+pub(crate) mod prebuild;
+pub(crate) mod rpc;
+pub(crate) mod status;
 
 pub use digest::Digest;
 
@@ -0,0 +1,67 @@
+//! Announces the parent of the next block to the payload builder when we lead its view.
+
+use alloy_primitives::Address;
+use commonware_consensus::{Reporter, simplex::types::Activity, types::Round};
+use commonware_cryptography::ed25519::PublicKey;
+use tempo_node::PrebuildHandle;
+use tracing::debug;
+
+use crate::{
+    consensus::{Digest, status},
+    epoch::{ConsensusScheme, SchemeProvider},
+};
+
+/// Starts pre-building a payload on top of every notarized block if we are the
+/// leader of the view after it.
+///
+/// The leader of a view is determined by the seed of the certificate of the
+/// view before it, so it is known as soon as that view is notarized. This gives
+/// the payload builder a head start of the time it takes to enter the next view
+/// and to request the payload.
+#[derive(Clone)]
+pub(crate) struct PrebuildReporter {
+    me: PublicKey,
+    fee_recipient: Address,
+    scheme_provider: SchemeProvider,
+    handle: PrebuildHandle,
+}
+
+impl PrebuildReporter {
+    pub(crate) fn new(
+        me: PublicKey,
+        fee_recipient: Address,
+        scheme_provider: SchemeProvider,
+        handle: PrebuildHandle,
+    ) -> Self {
+        Self {
+            me,
+            fee_recipient,
+            scheme_provider,
+            handle,
+        }
+    }
+}
+
+impl Reporter for PrebuildReporter {
+    type Activity = Activity<ConsensusScheme, Digest>;
+
+    async fn report(&mut self, activity: Self::Activity) {
+        let Activity::Notarization(notarization) = activity else {
+            return;
+        };
+
+        let round = notarization.proposal.round;
+        let next = Round::new(round.epoch(), round.view() + 1);
+        if status::leader(
+            &self.scheme_provider,
+            next,
+            Some((round, &notarization.certificate)),
+        )
+        .is_some_and(|leader| leader == self.me)
+        {
+            let parent = notarization.proposal.payload.0;
+            debug!(%parent, ?next, "leading next view; pre-building payload on notarized block");
+            self.handle.prebuild(parent, self.fee_recipient);
+        }
+    }
+}
@@ -0,0 +1,378 @@
+//! Serves consensus state to the execution node's `consensus_` RPC namespace.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Read as _, Seek as _, SeekFrom},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use alloy_consensus::BlockHeader as _;
+use alloy_eips::BlockHashOrNumber;
+use alloy_primitives::B256;
+use commonware_codec::{DecodeExt as _, Encode as _};
+use commonware_consensus::{
+    marshal::SchemeProvider as _, simplex::signing_scheme::Scheme as _, types::Epoch, utils,
+};
+use commonware_cryptography::bls12381::primitives::{poly, variant::MinSig};
+use eyre::{OptionExt as _, WrapErr as _, ensure};
+use parking_lot::Mutex;
+use reth_provider::{BlockIdReader as _, DatabaseProviderFactory as _, StateProviderFactory as _};
+use tempo_dkg_onchain_artifacts::PublicOutcome;
+use tempo_node::{
+    TempoFullNode,
+    rpc::{
+        ConsensusEvidence, ConsensusFinalization, ConsensusPeer, ConsensusStateProvider,
+        ConsensusStatus, SnapshotChunk, SnapshotInfo, SubblockTransactionStatus,
+    },
+};
+use tempo_snapshot::{Boundary, SNAPSHOT_VERSION, SnapshotHeader};
+use tracing::info;
+
+use crate::{
+    consensus::{Digest, status::StatusReporter},
+    dkg,
+    epoch::SchemeProvider,
+    evidence,
+    peers::PeerActivity,
+    subblocks,
+};
+
+/// Answers consensus RPC requests from marshal's finalized archive and the
+/// state of the running actors.
+#[derive(Clone)]
+pub(crate) struct ConsensusRpc {
+    marshal: crate::alias::marshal::Mailbox,
+    dkg_manager: dkg::manager::Mailbox,
+    peer_activity: PeerActivity,
+    evidence: evidence::Mailbox,
+    subblocks: subblocks::Mailbox,
+    scheme_provider: SchemeProvider,
+    status: StatusReporter,
+    execution_node: TempoFullNode,
+    epoch_length: u64,
+
+    /// The latest snapshot exported by this node, served to peers.
+    exported_snapshot: Arc<Mutex<Option<ExportedSnapshot>>>,
+}
+
+/// A snapshot written by `admin_exportSnapshot`.
+#[derive(Clone)]
+struct ExportedSnapshot {
+    number: u64,
+    hash: B256,
+    path: PathBuf,
+}
+
+impl ConsensusRpc {
+    pub(crate) fn new(
+        marshal: crate::alias::marshal::Mailbox,
+        dkg_manager: dkg::manager::Mailbox,
+        peer_activity: PeerActivity,
+        evidence: evidence::Mailbox,
+        subblocks: subblocks::Mailbox,
+        scheme_provider: SchemeProvider,
+        status: StatusReporter,
+        execution_node: TempoFullNode,
+        epoch_length: u64,
+    ) -> Self {
+        Self {
+            marshal,
+            dkg_manager,
+            peer_activity,
+            evidence,
+            subblocks,
+            scheme_provider,
+            status,
+            execution_node,
+            epoch_length,
+            exported_snapshot: Default::default(),
+        }
+    }
+
+    /// Reads the group public key of `epoch` from its DKG outcome.
+    ///
+    /// The outcome of the ceremony determining the group of epoch `E` is written to the
+    /// header of the last block of epoch `E-1`; the outcome for epoch 0 is in the genesis header.
+    async fn group_public_key(&self, epoch: Epoch) -> eyre::Result<Option<Vec<u8>>> {
+        let extra_data = if epoch == 0 {
+            self.execution_node
+                .chain_spec()
+                .genesis()
+                .extra_data
+                .clone()
+        } else {
+            let boundary_height = utils::last_block_in_epoch(self.epoch_length, epoch - 1);
+            let Some(block) = self.marshal.clone().get_block(boundary_height).await else {
+                return Ok(None);
+            };
+            block.header().extra_data().clone()
+        };
+
+        // Pre-allegretto genesis blocks do not carry a DKG outcome.
+        let Ok(outcome) = PublicOutcome::decode(extra_data.as_ref()) else {
+            return Ok(None);
+        };
+        eyre::ensure!(
+            outcome.epoch == epoch,
+            "DKG outcome stored for epoch `{epoch}` is for epoch `{}`",
+            outcome.epoch,
+        );
+
+        Ok(Some(
+            poly::public::<MinSig>(&outcome.public).encode().to_vec(),
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl ConsensusStateProvider for ConsensusRpc {
+    async fn finalization(
+        &self,
+        block: BlockHashOrNumber,
+    ) -> eyre::Result<Option<ConsensusFinalization>> {
+        let mut marshal = self.marshal.clone();
+
+        let height = match block {
+            BlockHashOrNumber::Number(number) => number,
+            BlockHashOrNumber::Hash(hash) => match marshal.get_info(&Digest(hash)).await {
+                Some((height, _)) => height,
+                None => return Ok(None),
+            },
+        };
+
+        let Some(finalization) = marshal.get_finalization(height).await else {
+            return Ok(None);
+        };
+
+        // Marshal also indexes blocks that are not finalized; make sure the requested block is
+        // the one that was finalized at its height.
+        if let BlockHashOrNumber::Hash(hash) = block
+            && finalization.proposal.payload.0 != hash
+        {
+            return Ok(None);
+        }
+
+        let epoch = finalization.proposal.round.epoch();
+        let group_public_key = self
+            .group_public_key(epoch)
+            .await
+            .wrap_err_with(|| format!("failed reading group public key of epoch `{epoch}`"))?;
+
+        Ok(Some(ConsensusFinalization {
+            number: height,
+            hash: finalization.proposal.payload.0,
+            epoch,
+            view: finalization.proposal.round.view(),
+            certificate: finalization.encode().freeze().into(),
+            group_public_key: group_public_key.map(Into::into),
+        }))
+    }
+
+    async fn status(&self) -> eyre::Result<ConsensusStatus> {
+        let consensus = self.status.status();
+        let dkg = self
+            .dkg_manager
+            .get_status()
+            .await
+            .wrap_err("failed reading status of DKG manager")?;
+
+        let epoch = consensus.round.map(|round| round.epoch());
+        let scheme = epoch.and_then(|epoch| self.scheme_provider.scheme(epoch));
+
+        Ok(ConsensusStatus {
+            epoch,
+            view: consensus.round.map(|round| round.view()),
+            leader: consensus
+                .leader
+                .map(|leader| B256::from_slice(leader.as_ref())),
+            is_signer: scheme
+                .as_deref()
+                .is_some_and(|scheme| scheme.me().is_some()),
+            participants: scheme
+                .map(|scheme| {
+                    scheme
+                        .participants()
+                        .iter()
+                        .map(|participant| B256::from_slice(participant.as_ref()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            dkg: dkg.ceremony,
+            nullified_views: consensus.nullified_views,
+        })
+    }
+
+    async fn peers(&self) -> eyre::Result<Vec<ConsensusPeer>> {
+        let dkg = self
+            .dkg_manager
+            .get_status()
+            .await
+            .wrap_err("failed reading status of DKG manager")?;
+        Ok(dkg
+            .peers
+            .into_iter()
+            .map(|(public_key, address)| ConsensusPeer {
+                connected: self.peer_activity.is_connected(&public_key),
+                public_key: B256::from_slice(public_key.as_ref()),
+                address,
+            })
+            .collect())
+    }
+
+    async fn evidence(&self, from: u64, limit: usize) -> eyre::Result<Vec<ConsensusEvidence>> {
+        self.evidence
+            .get_evidence(from, limit)
+            .await
+            .wrap_err("failed reading recorded evidence")
+    }
+
+    async fn export_snapshot(&self, path: PathBuf) -> eyre::Result<SnapshotInfo> {
+        let provider = self.execution_node.provider.clone();
+        let finalized = provider
+            .finalized_block_number()
+            .wrap_err("failed reading finalized block number")?
+            .ok_or_eyre("no block was finalized yet")?;
+
+        // The snapshot is taken at the last finalized block concluding an epoch.
+        let mut epoch = utils::epoch(self.epoch_length, finalized);
+        if utils::last_block_in_epoch(self.epoch_length, epoch) > finalized {
+            epoch = epoch
+                .checked_sub(1)
+                .ok_or_eyre("no epoch was finalized yet")?;
+        }
+
+        // Every boundary up to the snapshot's is included so that the snapshot can be verified
+        // starting from genesis.
+        let mut marshal = self.marshal.clone();
+        let mut boundaries = Vec::with_capacity(epoch as usize + 1);
+        let mut last = None;
+        for epoch in 0..=epoch {
+            let height = utils::last_block_in_epoch(self.epoch_length, epoch);
+            let finalization = marshal
+                .get_finalization(height)
+                .await
+                .ok_or_else(|| eyre::eyre!("finalization of block `{height}` is not available"))?;
+            let block = marshal
+                .get_block(height)
+                .await
+                .ok_or_else(|| eyre::eyre!("block `{height}` is not available"))?;
+            boundaries.push(Boundary::new(
+                block.header(),
+                finalization.encode().freeze().into(),
+            ));
+            last = Some(block);
+        }
+        let block = last.expect("at least the boundary of epoch 0 is included");
+        let (number, hash, state_root) = (
+            block.header().number(),
+            block.block_hash(),
+            block.header().state_root(),
+        );
+
+        info!(number, %hash, epoch, path = %path.display(), "exporting state snapshot");
+        let header = SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            boundaries,
+        };
+        // The snapshot is written next to `path` and moved in place once complete, so that peers
+        // still downloading the previous snapshot keep reading from its file.
+        let mut partial = path.clone().into_os_string();
+        partial.push(".partial");
+        let partial = PathBuf::from(partial);
+        let accounts = tokio::task::spawn_blocking({
+            let path = partial.clone();
+            move || -> eyre::Result<usize> {
+                let database = provider
+                    .database_provider_ro()
+                    .wrap_err("failed opening database")?;
+                let history = provider
+                    .history_by_block_number(number)
+                    .wrap_err_with(|| format!("failed reading state at block `{number}`"))?;
+
+                let mut out = BufWriter::new(
+                    File::create(&path)
+                        .wrap_err_with(|| format!("failed creating `{}`", path.display()))?,
+                );
+                tempo_snapshot::write_header(&mut out, &header, state_root)?;
+                Ok(tempo_snapshot::export::write_state(
+                    &database,
+                    history.as_ref(),
+                    number,
+                    &mut out,
+                )?)
+            }
+        })
+        .await
+        .wrap_err("snapshot export task panicked")?
+        .wrap_err("failed writing snapshot")?;
+        {
+            let mut exported = self.exported_snapshot.lock();
+            std::fs::rename(&partial, &path)
+                .wrap_err_with(|| format!("failed moving snapshot to `{}`", path.display()))?;
+            *exported = Some(ExportedSnapshot {
+                number,
+                hash,
+                path: path.clone(),
+            });
+        }
+        info!(number, accounts, "exported state snapshot");
+
+        Ok(SnapshotInfo {
+            number,
+            hash,
+            epoch,
+            state_root,
+            accounts: accounts as u64,
+            path,
+        })
+    }
+
+    async fn subblock_transaction_status(
+        &self,
+        tx_hash: B256,
+    ) -> eyre::Result<Option<SubblockTransactionStatus>> {
+        self.subblocks
+            .get_transaction_status(tx_hash)
+            .await
+            .wrap_err("failed reading subblock transaction status")
+    }
+
+    async fn snapshot_chunk(
+        &self,
+        offset: u64,
+        limit: usize,
+    ) -> eyre::Result<Option<SnapshotChunk>> {
+        // The file is opened while holding the lock so that it belongs to the snapshot it is
+        // served as, even if a newer snapshot replaces it afterwards.
+        let (snapshot, mut file) = {
+            let exported = self.exported_snapshot.lock();
+            let Some(snapshot) = exported.clone() else {
+                return Ok(None);
+            };
+            let file = File::open(&snapshot.path)
+                .wrap_err_with(|| format!("failed opening `{}`", snapshot.path.display()))?;
+            (snapshot, file)
+        };
+
+        tokio::task::spawn_blocking(move || -> eyre::Result<Option<SnapshotChunk>> {
+            let size = file.metadata()?.len();
+            ensure!(
+                offset <= size,
+                "offset `{offset}` is past the end of the snapshot of `{size}` bytes",
+            );
+            file.seek(SeekFrom::Start(offset))?;
+            let mut data = Vec::with_capacity(limit.min((size - offset) as usize));
+            file.take(limit as u64).read_to_end(&mut data)?;
+            Ok(Some(SnapshotChunk {
+                number: snapshot.number,
+                hash: snapshot.hash,
+                size,
+                offset,
+                data: data.into(),
+            }))
+        })
+        .await
+        .wrap_err("snapshot read task panicked")?
+    }
+}
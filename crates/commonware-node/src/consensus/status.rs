@@ -0,0 +1,161 @@
+//! Tracks consensus activity for the `consensus_` and `admin_` RPC namespaces.
+
+use std::{collections::VecDeque, sync::Arc};
+
+use alloy_primitives::B256;
+use commonware_consensus::{
+    Reporter,
+    marshal::SchemeProvider as _,
+    simplex::{
+        select_leader,
+        signing_scheme::{Scheme as _, bls12381_threshold},
+        types::Activity,
+    },
+    types::Round,
+};
+use commonware_cryptography::{bls12381::primitives::variant::MinSig, ed25519::PublicKey};
+use parking_lot::Mutex;
+use tempo_node::rpc::NullifiedView;
+
+use crate::{
+    consensus::Digest,
+    epoch::{ConsensusScheme, SchemeProvider},
+};
+
+type Scheme = ConsensusScheme;
+type Certificate = bls12381_threshold::Signature<MinSig>;
+
+/// How many of the most recently nullified views are kept.
+const NULLIFIED_VIEWS_TO_TRACK: usize = 32;
+
+/// A snapshot of the consensus activity observed by a [`StatusReporter`].
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Status {
+    /// The latest round a certificate was observed for.
+    pub(crate) round: Option<Round>,
+
+    /// The leader of the view following `round`.
+    pub(crate) leader: Option<PublicKey>,
+
+    /// The most recently nullified views, oldest first.
+    pub(crate) nullified_views: Vec<NullifiedView>,
+}
+
+/// Reports notarizations, finalizations, and nullifications of the simplex
+/// engines to the RPC.
+#[derive(Clone)]
+pub(crate) struct StatusReporter {
+    scheme_provider: SchemeProvider,
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// The latest round a certificate was observed for and its certificate.
+    latest: Option<(Round, Certificate)>,
+    nullified_views: VecDeque<NullifiedView>,
+}
+
+impl StatusReporter {
+    pub(crate) fn new(scheme_provider: SchemeProvider) -> Self {
+        Self {
+            scheme_provider,
+            inner: Default::default(),
+        }
+    }
+
+    /// Returns a snapshot of the observed consensus activity.
+    pub(crate) fn status(&self) -> Status {
+        let inner = self.inner.lock();
+        let (round, leader) = match &inner.latest {
+            Some((round, certificate)) => (
+                Some(*round),
+                self.leader(
+                    Round::new(round.epoch(), round.view() + 1),
+                    Some((*round, certificate)),
+                ),
+            ),
+            None => (None, None),
+        };
+        Status {
+            round,
+            leader,
+            nullified_views: inner.nullified_views.iter().cloned().collect(),
+        }
+    }
+
+    /// Returns the leader of `round`, seeded by the certificate of the round
+    /// preceding it.
+    ///
+    /// Returns `None` if the scheme of the epoch is not known (anymore), or if
+    /// the certificate of the preceding round was not observed.
+    fn leader(&self, round: Round, previous: Option<(Round, &Certificate)>) -> Option<PublicKey> {
+        leader(&self.scheme_provider, round, previous)
+    }
+}
+
+/// Returns the leader of `round`, seeded by the certificate of the round
+/// preceding it.
+///
+/// Returns `None` if the scheme of the epoch is not known (anymore), or if
+/// the certificate of the preceding round is not given.
+pub(crate) fn leader(
+    scheme_provider: &SchemeProvider,
+    round: Round,
+    previous: Option<(Round, &Certificate)>,
+) -> Option<PublicKey> {
+    let scheme = scheme_provider.scheme(round.epoch())?;
+    let seed = if round.view() == 1 {
+        // First view does not have a seed.
+        None
+    } else {
+        let (previous, certificate) = previous?;
+        scheme.seed(previous, certificate)
+    };
+    let (leader, _) = select_leader::<Scheme, _>(scheme.participants().as_ref(), round, seed);
+    Some(leader)
+}
+
+impl Reporter for StatusReporter {
+    type Activity = Activity<Scheme, Digest>;
+
+    async fn report(&mut self, activity: Self::Activity) {
+        let (round, certificate, is_nullification) = match activity {
+            Activity::Notarization(n) => (n.proposal.round, n.certificate, false),
+            Activity::Finalization(n) => (n.proposal.round, n.certificate, false),
+            Activity::Nullification(n) => (n.round, n.certificate, true),
+            _ => return,
+        };
+
+        let mut inner = self.inner.lock();
+
+        if is_nullification {
+            // The leader of a view is only known if the certificate of the
+            // view before it was observed.
+            let previous = inner
+                .latest
+                .as_ref()
+                .filter(|(previous, _)| {
+                    previous.epoch() == round.epoch() && previous.view() + 1 == round.view()
+                })
+                .map(|(previous, certificate)| (*previous, certificate));
+            let leader = self.leader(round, previous);
+            if inner.nullified_views.len() == NULLIFIED_VIEWS_TO_TRACK {
+                inner.nullified_views.pop_front();
+            }
+            inner.nullified_views.push_back(NullifiedView {
+                epoch: round.epoch(),
+                view: round.view(),
+                leader: leader.map(|leader| B256::from_slice(leader.as_ref())),
+            });
+        }
+
+        if inner
+            .latest
+            .as_ref()
+            .is_none_or(|(latest, _)| *latest < round)
+        {
+            inner.latest = Some((round, certificate));
+        }
+    }
+}
@@ -0,0 +1,852 @@
+use crate::{
+    consensus::Digest,
+    epoch::{ConsensusScheme, SchemeProvider},
+};
+use alloy_consensus::{
+    BlockHeader as _,
+    transaction::{SignerRecoverable as _, TxHashRef as _},
+};
+use alloy_primitives::{B256, BlockHash, Bytes};
+use bytes::{Buf, BufMut};
+use commonware_codec::{
+    DecodeExt as _, Encode as _, EncodeSize, Error as CodecError, RangeCfg, Read, ReadExt as _,
+    Write,
+};
+use commonware_consensus::{
+    Reporter, marshal::SchemeProvider as _, simplex::types::Activity, types::Epoch,
+};
+use commonware_cryptography::{
+    bls12381::primitives::{poly::PartialSignature, variant::MinSig},
+    ed25519::PublicKey,
+};
+use commonware_p2p::{Receiver, Recipients, Sender};
+use commonware_utils::quorum;
+use eyre::{Context, OptionExt};
+use futures::{
+    StreamExt,
+    channel::{mpsc, oneshot},
+};
+use indexmap::IndexMap;
+use reth_primitives_traits::Recovered;
+use reth_provider::{BlockReader, BlockSource};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+use tempo_encryption::{DecryptionKey, recover_key, sign_target, verify_partial};
+use tempo_node::TempoFullNode;
+use tempo_primitives::{Block, TempoTxEnvelope};
+use tokio::time::MissedTickBehavior;
+use tracing::{Level, debug, instrument, warn};
+
+/// Maximum number of encrypted transactions tracked per block.
+const MAX_ENCRYPTED_TXS_PER_BLOCK: usize = 10_000;
+
+/// Maximum number of blocks for which partial signatures are buffered before the block itself
+/// was seen finalized. Used to prevent DOS attacks.
+///
+/// Once exceeded, the partial signatures of the oldest such block are dropped.
+const MAX_EARLY_BLOCKS: usize = 16;
+
+/// Maximum number of finalized blocks whose keys are waiting to be released because the
+/// execution layer does not have them yet.
+///
+/// Once exceeded, the oldest such block is dropped.
+const MAX_PENDING_BLOCKS: usize = 16;
+
+/// How often to look for finalized blocks the execution layer did not have yet.
+const PENDING_BLOCKS_RETRY_INTERVAL: Duration = Duration::from_millis(200);
+
+pub(crate) struct Config {
+    pub(crate) scheme_provider: SchemeProvider,
+    pub(crate) node: TempoFullNode,
+}
+
+/// Task releasing the decryption keys of encrypted transactions.
+///
+/// Once a block is finalized, the position of its encrypted transactions in the chain is fixed
+/// and their contents may be revealed. A notarized block is not enough: it may still be dropped
+/// in favor of another block at the same height, which would leave its encrypted transactions
+/// revealed without being executed. This actor then signs the decryption target of every
+/// encrypted transaction in the block with our share of the group key, and broadcasts these
+/// partial signatures to all other validators.
+///
+/// Partial signatures received from the network are verified against the public polynomial of
+/// the block's epoch. Once a threshold of them is collected for every target of a block, the
+/// decryption keys are recovered and handed to the proposer of the next block, which must
+/// include them.
+///
+/// Partial signatures are only sent once. Validators that miss them will not be able to
+/// recover the keys of the block, and can't propose its child. If the execution layer does not
+/// have a block yet when it is finalized, its keys are released once the block shows up.
+pub(crate) struct Actor {
+    /// Sender of messages to the service.
+    actions_tx: mpsc::UnboundedSender<Message>,
+    /// Receiver of events to the service.
+    actions_rx: mpsc::UnboundedReceiver<Message>,
+
+    /// Scheme provider to access the shares and public polynomials of each epoch.
+    scheme_provider: SchemeProvider,
+    /// Execution layer node.
+    node: TempoFullNode,
+
+    /// Decryption keys of finalized blocks, keyed by block hash.
+    blocks: HashMap<BlockHash, BlockKeys>,
+    /// Partial signatures received for blocks we have not yet seen finalized.
+    early: IndexMap<BlockHash, HashMap<PublicKey, Partials>>,
+    /// Requests for the keys of blocks we have not yet seen finalized.
+    waiting: HashMap<BlockHash, Vec<oneshot::Sender<Vec<DecryptionKey>>>>,
+    /// Finalized blocks the execution layer did not have yet.
+    pending: PendingBlocks,
+}
+
+impl Actor {
+    pub(crate) fn new(
+        Config {
+            scheme_provider,
+            node,
+        }: Config,
+    ) -> Self {
+        let (actions_tx, actions_rx) = mpsc::unbounded();
+        Self {
+            actions_tx,
+            actions_rx,
+            scheme_provider,
+            node,
+            blocks: HashMap::new(),
+            early: IndexMap::new(),
+            waiting: HashMap::new(),
+            pending: PendingBlocks::default(),
+        }
+    }
+
+    /// Returns a handle to the decryption service.
+    pub(crate) fn mailbox(&self) -> Mailbox {
+        Mailbox {
+            tx: self.actions_tx.clone(),
+        }
+    }
+
+    pub(crate) async fn run(
+        mut self,
+        (mut network_tx, mut network_rx): (
+            impl Sender<PublicKey = PublicKey>,
+            impl Receiver<PublicKey = PublicKey>,
+        ),
+    ) {
+        let mut retry_pending = tokio::time::interval(PENDING_BLOCKS_RETRY_INTERVAL);
+        retry_pending.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                biased;
+
+                // Handle messages from consensus engine and service handle.
+                Some(action) = self.actions_rx.next() => {
+                    self.on_new_message(action, &mut network_tx).await;
+                },
+                // Handle messages from the network.
+                Ok((sender, message)) = network_rx.recv() => {
+                    let _ = self.on_network_message(sender, message);
+                },
+                // Release the keys of finalized blocks the execution layer now has.
+                _ = retry_pending.tick(), if !self.pending.is_empty() => {
+                    self.on_retry_pending(&mut network_tx).await;
+                },
+            }
+        }
+    }
+
+    async fn on_new_message(
+        &mut self,
+        action: Message,
+        network_tx: &mut impl Sender<PublicKey = PublicKey>,
+    ) {
+        match action {
+            Message::GetKeys { parent, response } => {
+                let Some(block) = self.blocks.get_mut(&parent) else {
+                    // The parent is usually proposed on once it is notarized, before it is
+                    // finalized. Forget about requests that were given up on.
+                    self.waiting.retain(|_, waiters| {
+                        waiters.retain(|waiter| !waiter.is_canceled());
+                        !waiters.is_empty()
+                    });
+                    self.waiting.entry(parent).or_default().push(response);
+                    return;
+                };
+                block.wait(response);
+            }
+            Message::Consensus(activity) => self.on_consensus_event(*activity, network_tx).await,
+        }
+    }
+
+    /// Starts releasing the keys of finalized blocks and prunes the state of lower heights.
+    #[instrument(skip_all)]
+    async fn on_consensus_event(
+        &mut self,
+        event: Activity<ConsensusScheme, Digest>,
+        network_tx: &mut impl Sender<PublicKey = PublicKey>,
+    ) {
+        let Activity::Finalization(finalization) = event else {
+            return;
+        };
+        let (digest, epoch) = (
+            finalization.proposal.payload.0,
+            finalization.proposal.round.epoch(),
+        );
+
+        if !self.blocks.contains_key(&digest) {
+            let Some(block) = find_block(&self.node, digest) else {
+                debug!(
+                    %digest,
+                    "missing finalized block, releasing its decryption keys once it is available"
+                );
+                self.pending.push(digest, epoch);
+                return;
+            };
+            self.on_finalized_block(digest, epoch, block, network_tx)
+                .await;
+        }
+
+        self.prune(digest);
+    }
+
+    /// Releases the keys of pending finalized blocks that the execution layer now has.
+    async fn on_retry_pending(&mut self, network_tx: &mut impl Sender<PublicKey = PublicKey>) {
+        let node = &self.node;
+        let available = self
+            .pending
+            .take_available(|digest| find_block(node, digest));
+        for (digest, epoch, block) in available {
+            if self.blocks.contains_key(&digest) {
+                continue;
+            }
+            debug!(%digest, "finalized block became available, releasing its decryption keys");
+            self.on_finalized_block(digest, epoch, block, network_tx)
+                .await;
+            self.prune(digest);
+        }
+    }
+
+    /// Starts tracking the keys of the finalized `block` and releases our partial signatures.
+    async fn on_finalized_block(
+        &mut self,
+        digest: BlockHash,
+        epoch: Epoch,
+        block: Block,
+        network_tx: &mut impl Sender<PublicKey = PublicKey>,
+    ) {
+        let targets = match encrypted_transactions(&block.body.transactions) {
+            Ok(encrypted) => encrypted
+                .into_iter()
+                .map(|(target, _)| target)
+                .collect::<Vec<_>>(),
+            Err(error) => {
+                warn!(%error, %digest, "failed reading encrypted transactions of block");
+                return;
+            }
+        };
+
+        let mut keys = BlockKeys::new(block.header.number(), epoch, targets);
+        for waiter in self.waiting.remove(&digest).unwrap_or_default() {
+            keys.wait(waiter);
+        }
+        self.blocks.insert(digest, keys);
+        self.release_keys(digest, network_tx).await;
+
+        // Process partial signatures that arrived before the block was finalized.
+        for (sender, partials) in self.early.shift_remove(&digest).unwrap_or_default() {
+            let _ = self.on_partials(sender, partials);
+        }
+    }
+
+    /// Prunes the keys of blocks below the finalized block `digest`.
+    fn prune(&mut self, digest: BlockHash) {
+        if let Some(number) = self.blocks.get(&digest).map(|block| block.number) {
+            // Keys of the finalized block are still needed to propose its child.
+            self.blocks.retain(|_, block| block.number >= number);
+        }
+    }
+
+    /// Signs the targets of the block with our share and broadcasts the partial signatures.
+    async fn release_keys(
+        &mut self,
+        digest: BlockHash,
+        network_tx: &mut impl Sender<PublicKey = PublicKey>,
+    ) {
+        let Some(block) = self.blocks.get_mut(&digest) else {
+            return;
+        };
+        if block.targets.is_empty() {
+            return;
+        }
+        let Some(scheme) = self.scheme_provider.scheme(block.epoch) else {
+            debug!(epoch = block.epoch, "scheme not found for epoch");
+            return;
+        };
+        let Some(share) = scheme.share() else {
+            return;
+        };
+
+        let partials = block
+            .targets
+            .iter()
+            .map(|target| sign_target(share, *target))
+            .collect::<Vec<_>>();
+        block.insert(quorum(scheme.participants().len() as u32), partials.clone());
+
+        debug!(
+            %digest,
+            targets = block.targets.len(),
+            "broadcasting partial signatures over decryption targets"
+        );
+        let message = Partials {
+            epoch: block.epoch,
+            block: digest,
+            partials,
+        };
+        let _ = network_tx
+            .send(Recipients::All, message.encode().freeze(), true)
+            .await;
+    }
+
+    #[instrument(skip_all, err(level = Level::WARN), fields(sender = %sender, msg_bytes = message.len()))]
+    fn on_network_message(&mut self, sender: PublicKey, message: bytes::Bytes) -> eyre::Result<()> {
+        let partials = Partials::decode(message).wrap_err("failed to decode network message")?;
+
+        if !self.blocks.contains_key(&partials.block) {
+            if !self.early.contains_key(&partials.block) && self.early.len() >= MAX_EARLY_BLOCKS {
+                self.early.shift_remove_index(0);
+            }
+            self.early
+                .entry(partials.block)
+                .or_default()
+                .insert(sender, partials);
+            return Ok(());
+        }
+
+        self.on_partials(sender, partials)
+    }
+
+    /// Verifies partial signatures of `sender` and records them.
+    fn on_partials(&mut self, sender: PublicKey, partials: Partials) -> eyre::Result<()> {
+        let scheme = self
+            .scheme_provider
+            .scheme(partials.epoch)
+            .ok_or_eyre("scheme not found")?;
+        let block = self
+            .blocks
+            .get_mut(&partials.block)
+            .ok_or_eyre("unknown block")?;
+
+        eyre::ensure!(
+            block.epoch == partials.epoch,
+            "partial signatures for epoch {}, block is in epoch {}",
+            partials.epoch,
+            block.epoch,
+        );
+        eyre::ensure!(
+            block.targets.len() == partials.partials.len(),
+            "expected {} partial signatures, got {}",
+            block.targets.len(),
+            partials.partials.len(),
+        );
+
+        let index = scheme
+            .participants()
+            .iter()
+            .position(|participant| participant == &sender)
+            .ok_or_eyre("sender is not a validator")?;
+        let public = scheme
+            .polynomial()
+            .get(index)
+            .ok_or_eyre("missing public key of sender's share")?;
+
+        for (target, partial) in block.targets.iter().zip(&partials.partials) {
+            eyre::ensure!(
+                partial.index as usize == index,
+                "partial signature of another validator"
+            );
+            eyre::ensure!(
+                verify_partial(public, *target, partial),
+                "invalid partial signature"
+            );
+        }
+
+        block.insert(
+            quorum(scheme.participants().len() as u32),
+            partials.partials,
+        );
+
+        Ok(())
+    }
+}
+
+/// Returns the block `digest` if the execution layer has it.
+fn find_block(node: &TempoFullNode, digest: BlockHash) -> Option<Block> {
+    node.provider
+        .find_block_by_hash(digest, BlockSource::Any)
+        .ok()
+        .flatten()
+}
+
+/// Finalized blocks whose keys are not released yet, because the execution layer did not have
+/// them when they were finalized.
+#[derive(Default)]
+struct PendingBlocks {
+    blocks: IndexMap<BlockHash, Epoch>,
+}
+
+impl PendingBlocks {
+    fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Queues the finalized block `digest` of `epoch`, dropping the oldest block if too many are
+    /// queued.
+    fn push(&mut self, digest: BlockHash, epoch: Epoch) {
+        if !self.blocks.contains_key(&digest) && self.blocks.len() >= MAX_PENDING_BLOCKS {
+            self.blocks.shift_remove_index(0);
+        }
+        self.blocks.insert(digest, epoch);
+    }
+
+    /// Removes and returns the queued blocks that `find` finds, in the order they were
+    /// finalized.
+    fn take_available<B>(
+        &mut self,
+        mut find: impl FnMut(BlockHash) -> Option<B>,
+    ) -> Vec<(BlockHash, Epoch, B)> {
+        let mut available = Vec::new();
+        self.blocks.retain(|digest, epoch| match find(*digest) {
+            Some(block) => {
+                available.push((*digest, *epoch, block));
+                false
+            }
+            None => true,
+        });
+        available
+    }
+}
+
+/// Returns the decryption targets and ciphertexts of the encrypted transactions among
+/// `transactions`, in order.
+pub(crate) fn encrypted_transactions<'a>(
+    transactions: impl IntoIterator<Item = &'a TempoTxEnvelope>,
+) -> eyre::Result<Vec<(B256, &'a Bytes)>> {
+    transactions
+        .into_iter()
+        .filter_map(|tx| tx.as_encrypted())
+        .map(|tx| {
+            let sender = tx
+                .recover_signer()
+                .wrap_err("failed recovering sender of encrypted transaction")?;
+            Ok((tx.tx().decryption_target(sender), &tx.tx().ciphertext))
+        })
+        .collect()
+}
+
+/// Decrypts the `encrypted` transactions of a block with their decryption `keys`, in order.
+///
+/// Returns the transactions the next block must include. Ciphertexts that don't decrypt to a
+/// signed transaction, or that decrypt to a system or subblock transaction, are dropped.
+pub(crate) fn decrypt_transactions(
+    encrypted: &[(B256, &Bytes)],
+    keys: &[DecryptionKey],
+) -> Vec<Recovered<TempoTxEnvelope>> {
+    encrypted
+        .iter()
+        .zip(keys)
+        .filter_map(|((_, ciphertext), key)| {
+            let transaction = tempo_encryption::decrypt(key, ciphertext)
+                .inspect_err(|error| debug!(%error, "failed decrypting transaction"))
+                .ok()?;
+            if transaction.is_system_tx() || transaction.subblock_proposer().is_some() {
+                debug!(
+                    tx_hash = %transaction.tx_hash(),
+                    "dropping decrypted system or subblock transaction",
+                );
+                return None;
+            }
+            transaction.try_into_recovered().ok()
+        })
+        .collect()
+}
+
+/// Decryption keys of the encrypted transactions of a finalized block.
+struct BlockKeys {
+    /// Number of the block.
+    number: u64,
+    /// Epoch the block was finalized in.
+    epoch: Epoch,
+    /// Decryption targets of the encrypted transactions in the block, in order.
+    targets: Vec<B256>,
+    /// Partial signatures collected so far for each target, keyed by share index.
+    partials: Vec<BTreeMap<u32, PartialSignature<MinSig>>>,
+    /// Recovered decryption keys for each target.
+    keys: Vec<Option<DecryptionKey>>,
+    /// Requests waiting for all keys of the block to be recovered.
+    waiters: Vec<oneshot::Sender<Vec<DecryptionKey>>>,
+}
+
+impl BlockKeys {
+    fn new(number: u64, epoch: Epoch, targets: Vec<B256>) -> Self {
+        Self {
+            number,
+            epoch,
+            partials: vec![BTreeMap::new(); targets.len()],
+            keys: vec![None; targets.len()],
+            targets,
+            waiters: Vec::new(),
+        }
+    }
+
+    /// Returns all keys of the block if they were recovered.
+    fn keys(&self) -> Option<Vec<DecryptionKey>> {
+        self.keys.iter().cloned().collect()
+    }
+
+    /// Sends all keys of the block to `waiter` once they are recovered.
+    fn wait(&mut self, waiter: oneshot::Sender<Vec<DecryptionKey>>) {
+        if let Some(keys) = self.keys() {
+            let _ = waiter.send(keys);
+        } else {
+            self.waiters.push(waiter);
+        }
+    }
+
+    /// Records verified partial signatures of a single validator, one per target, and recovers
+    /// the keys of all targets that reached `threshold` partial signatures.
+    fn insert(&mut self, threshold: u32, partials: Vec<PartialSignature<MinSig>>) {
+        for ((collected, key), partial) in
+            self.partials.iter_mut().zip(&mut self.keys).zip(partials)
+        {
+            if key.is_some() {
+                continue;
+            }
+            collected.insert(partial.index, partial);
+            if collected.len() >= threshold as usize {
+                *key = recover_key(threshold, collected.values());
+                if key.is_some() {
+                    collected.clear();
+                }
+            }
+        }
+
+        if let Some(keys) = self.keys() {
+            for waiter in self.waiters.drain(..) {
+                let _ = waiter.send(keys.clone());
+            }
+        }
+    }
+}
+
+/// Actions processed by the decryption service.
+#[derive(Debug)]
+enum Message {
+    /// Returns the decryption keys of the encrypted transactions in a block, in order.
+    ///
+    /// The response is sent once the block is finalized and all its keys are recovered.
+    GetKeys {
+        /// Block to return the keys for.
+        parent: BlockHash,
+        /// Response channel.
+        response: oneshot::Sender<Vec<DecryptionKey>>,
+    },
+
+    /// Reports a new consensus event.
+    Consensus(Box<Activity<ConsensusScheme, Digest>>),
+}
+
+/// Network message carrying the partial signatures of a validator over all decryption targets
+/// of a block.
+#[derive(Debug)]
+struct Partials {
+    /// Epoch the block was finalized in.
+    epoch: Epoch,
+    /// Hash of the block.
+    block: BlockHash,
+    /// One partial signature per encrypted transaction in the block, in order.
+    partials: Vec<PartialSignature<MinSig>>,
+}
+
+impl Write for Partials {
+    fn write(&self, buf: &mut impl BufMut) {
+        self.epoch.write(buf);
+        self.block.0.write(buf);
+        self.partials.write(buf);
+    }
+}
+
+impl EncodeSize for Partials {
+    fn encode_size(&self) -> usize {
+        self.epoch.encode_size() + self.block.0.encode_size() + self.partials.encode_size()
+    }
+}
+
+impl Read for Partials {
+    type Cfg = ();
+
+    fn read_cfg(buf: &mut impl Buf, _cfg: &Self::Cfg) -> Result<Self, CodecError> {
+        Ok(Self {
+            epoch: Epoch::read(buf)?,
+            block: B256::from(<[u8; 32]>::read(buf)?),
+            partials: Vec::<PartialSignature<MinSig>>::read_cfg(
+                buf,
+                &(RangeCfg::from(..=MAX_ENCRYPTED_TXS_PER_BLOCK), ()),
+            )?,
+        })
+    }
+}
+
+/// Handle to the spawned decryption service.
+#[derive(Clone)]
+pub(crate) struct Mailbox {
+    tx: mpsc::UnboundedSender<Message>,
+}
+
+impl Mailbox {
+    /// Returns the decryption keys of the encrypted transactions in `parent`, in order.
+    ///
+    /// Resolves once `parent` is finalized and all its keys are recovered, or to `None` if the
+    /// service went away.
+    pub(crate) async fn get_keys(&self, parent: BlockHash) -> Option<Vec<DecryptionKey>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.tx.unbounded_send(Message::GetKeys {
+            parent,
+            response: tx,
+        });
+        rx.await.ok()
+    }
+}
+
+impl Reporter for Mailbox {
+    type Activity = Activity<ConsensusScheme, Digest>;
+
+    async fn report(&mut self, activity: Self::Activity) -> () {
+        let _ = self
+            .tx
+            .unbounded_send(Message::Consensus(Box::new(activity)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_consensus::{Signed, TxLegacy, transaction::TxHashRef as _};
+    use alloy_primitives::{B256, Bytes, Signature};
+    use commonware_codec::{DecodeExt as _, Encode as _};
+    use commonware_cryptography::bls12381::{
+        dkg::ops::generate_shares,
+        primitives::{group::Share, poly, variant::MinSig},
+    };
+    use commonware_utils::quorum;
+    use futures::channel::oneshot;
+    use rand::{SeedableRng as _, rngs::StdRng};
+    use tempo_encryption::{
+        DecryptionKey, GroupPublicKey, encrypt, recover_key, sign_target, verify_key,
+    };
+    use tempo_primitives::{
+        TempoTxEnvelope, TxEncrypted, transaction::envelope::TEMPO_SYSTEM_TX_SIGNATURE,
+    };
+
+    use super::{
+        BlockKeys, MAX_ENCRYPTED_TXS_PER_BLOCK, MAX_PENDING_BLOCKS, Partials, PendingBlocks,
+        decrypt_transactions, encrypted_transactions,
+    };
+
+    fn shares() -> (GroupPublicKey, Vec<Share>) {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (polynomial, shares) = generate_shares::<_, MinSig>(&mut rng, None, 4, quorum(4));
+        (*poly::public::<MinSig>(&polynomial), shares)
+    }
+
+    fn key(shares: &[Share], target: B256) -> DecryptionKey {
+        let partials = shares
+            .iter()
+            .map(|share| sign_target(share, target))
+            .collect::<Vec<_>>();
+        recover_key(quorum(shares.len() as u32), &partials).unwrap()
+    }
+
+    fn legacy(nonce: u64) -> TempoTxEnvelope {
+        TempoTxEnvelope::Legacy(Signed::new_unhashed(
+            TxLegacy {
+                chain_id: Some(1),
+                nonce,
+                gas_limit: 21_000,
+                ..Default::default()
+            },
+            Signature::test_signature(),
+        ))
+    }
+
+    #[test]
+    fn keys_are_recovered_once_threshold_is_reached() {
+        let (identity, shares) = shares();
+        let targets = vec![B256::repeat_byte(1), B256::repeat_byte(2)];
+        let threshold = quorum(shares.len() as u32);
+        let mut block = BlockKeys::new(1, 0, targets.clone());
+
+        let (tx, mut rx) = oneshot::channel();
+        block.wait(tx);
+
+        for share in &shares[..threshold as usize - 1] {
+            block.insert(
+                threshold,
+                targets
+                    .iter()
+                    .map(|target| sign_target(share, *target))
+                    .collect(),
+            );
+        }
+        assert!(block.keys().is_none());
+        assert_eq!(rx.try_recv().unwrap(), None);
+
+        let share = &shares[threshold as usize - 1];
+        block.insert(
+            threshold,
+            targets
+                .iter()
+                .map(|target| sign_target(share, *target))
+                .collect(),
+        );
+        let keys = rx.try_recv().unwrap().unwrap();
+        assert_eq!(Some(keys.clone()), block.keys());
+        for (target, key) in targets.iter().zip(&keys) {
+            assert!(verify_key(&identity, *target, key));
+        }
+
+        // Later requests are answered right away.
+        let (tx, mut rx) = oneshot::channel();
+        block.wait(tx);
+        assert_eq!(rx.try_recv().unwrap(), Some(keys));
+    }
+
+    #[test]
+    fn finalized_blocks_are_released_once_they_arrive() {
+        let (early, late) = (B256::repeat_byte(1), B256::repeat_byte(2));
+        let mut pending = PendingBlocks::default();
+        pending.push(early, 3);
+        pending.push(late, 4);
+
+        // Neither block is known yet when it is finalized.
+        assert!(pending.take_available(|_| None::<()>).is_empty());
+        assert!(!pending.is_empty());
+
+        // The later block arrives first, the other one is kept queued.
+        let available = pending.take_available(|digest| (digest == late).then_some(digest));
+        assert_eq!(available, vec![(late, 4, late)]);
+        assert!(!pending.is_empty());
+
+        let available = pending.take_available(Some);
+        assert_eq!(available, vec![(early, 3, early)]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn pending_blocks_are_bounded() {
+        let mut pending = PendingBlocks::default();
+        for i in 0..=MAX_PENDING_BLOCKS {
+            pending.push(B256::with_last_byte(i as u8), i as u64);
+        }
+
+        let available = pending.take_available(Some);
+        assert_eq!(available.len(), MAX_PENDING_BLOCKS);
+        assert_eq!(available[0].0, B256::with_last_byte(1));
+    }
+
+    #[test]
+    fn partials_roundtrip_and_are_bounded() {
+        let (_, shares) = shares();
+        let partial = sign_target(&shares[0], B256::repeat_byte(1));
+
+        let partials = Partials {
+            epoch: 3,
+            block: B256::repeat_byte(7),
+            partials: vec![partial.clone(); 2],
+        };
+        let decoded = Partials::decode(partials.encode()).unwrap();
+        assert_eq!(decoded.epoch, partials.epoch);
+        assert_eq!(decoded.block, partials.block);
+        assert_eq!(decoded.partials, partials.partials);
+
+        let oversized = Partials {
+            partials: vec![partial; MAX_ENCRYPTED_TXS_PER_BLOCK + 1],
+            ..partials
+        };
+        assert!(Partials::decode(oversized.encode()).is_err());
+    }
+
+    #[test]
+    fn encrypted_transactions_are_returned_in_order() {
+        let encrypted = (0..2)
+            .map(|nonce| {
+                TempoTxEnvelope::Encrypted(Signed::new_unhashed(
+                    TxEncrypted {
+                        chain_id: 1,
+                        nonce,
+                        ciphertext: Bytes::from(vec![nonce as u8; 8]),
+                        ..Default::default()
+                    },
+                    Signature::test_signature(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let transactions = [encrypted[0].clone(), legacy(0), encrypted[1].clone()];
+
+        let found = encrypted_transactions(&transactions).unwrap();
+
+        assert_eq!(found.len(), 2);
+        for ((target, ciphertext), tx) in found.iter().zip(&encrypted) {
+            let tx = tx.as_encrypted().unwrap();
+            assert_eq!(
+                *target,
+                tx.tx().decryption_target(tx.recover_signer().unwrap())
+            );
+            assert_eq!(*ciphertext, &tx.tx().ciphertext);
+        }
+    }
+
+    #[test]
+    fn only_includable_transactions_are_decrypted() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let (identity, shares) = shares();
+        let targets = (1..=4).map(B256::repeat_byte).collect::<Vec<_>>();
+
+        let system = TempoTxEnvelope::Legacy(Signed::new_unhashed(
+            TxLegacy::default(),
+            TEMPO_SYSTEM_TX_SIGNATURE,
+        ));
+        let ciphertexts = [
+            encrypt(&mut rng, &identity, targets[0], &legacy(0)),
+            // Not a ciphertext.
+            Bytes::from_static(&[1; 64]),
+            encrypt(&mut rng, &identity, targets[2], &system),
+            encrypt(&mut rng, &identity, targets[3], &legacy(1)),
+        ];
+        let encrypted = targets
+            .iter()
+            .copied()
+            .zip(&ciphertexts)
+            .collect::<Vec<_>>();
+        let keys = targets
+            .iter()
+            .map(|target| key(&shares, *target))
+            .collect::<Vec<_>>();
+
+        let decrypted = decrypt_transactions(&encrypted, &keys)
+            .into_iter()
+            .map(|tx| *tx.tx_hash())
+            .collect::<Vec<_>>();
+
+        assert_eq!(decrypted, vec![*legacy(0).tx_hash(), *legacy(1).tx_hash()]);
+
+        // Keys of other targets don't decrypt anything.
+        let mut wrong_keys = keys;
+        wrong_keys.swap(0, 3);
+        let decrypted = decrypt_transactions(&encrypted, &wrong_keys)
+            .into_iter()
+            .map(|tx| *tx.tx_hash())
+            .collect::<Vec<_>>();
+        assert!(decrypted.is_empty());
+    }
+}
@@ -11,15 +11,14 @@ use commonware_cryptography::{
         dkg::{self, Arbiter, Player, arbiter},
         primitives::{group, poly::Public, variant::MinSig},
     },
-    ed25519::{PrivateKey, PublicKey},
+    ed25519::PublicKey,
 };
 use commonware_p2p::{
     Receiver, Recipients, Sender,
     utils::mux::{MuxHandle, SubReceiver, SubSender},
 };
 use commonware_runtime::{Clock, Storage};
-use commonware_storage::metadata::Metadata;
-use commonware_utils::{max_faults, sequence::U64, set::Ordered, union};
+use commonware_utils::{max_faults, set::Ordered, union};
 use eyre::{WrapErr as _, bail, ensure};
 use futures::{FutureExt as _, lock::Mutex};
 use indexmap::IndexSet;
@@ -28,13 +27,14 @@ use rand_core::CryptoRngCore;
 use tracing::{Level, debug, error, info, instrument, warn};
 
 use tempo_dkg_onchain_artifacts::{Ack, IntermediateOutcome};
+use tempo_node::rpc::{DkgPhase, DkgStatus};
 
-use crate::{consensus::block::Block, dkg::HardforkRegime};
+use crate::{consensus::block::Block, dkg::HardforkRegime, signer::ConsensusSigner};
 
 mod payload;
 mod persisted;
 
-pub(super) use persisted::State;
+pub(super) use persisted::{State, Store};
 
 use payload::{Message, Payload, Share};
 use persisted::Dealing;
@@ -50,7 +50,7 @@ pub(super) struct Config {
     /// Prefix all signed messages to prevent replay attacks.
     pub(super) namespace: Vec<u8>,
 
-    pub(super) me: PrivateKey,
+    pub(super) me: ConsensusSigner,
 
     /// The previous public polynomial.
     pub(super) public: Public<MinSig>,
@@ -67,6 +67,10 @@ pub(super) struct Config {
 
     /// The players in the round.
     pub(super) players: Ordered<PublicKey>,
+
+    /// The number of players that must ack a dealing since Allegro-Moderato.
+    /// The shares of all other players are revealed.
+    pub(super) dealing_threshold: u32,
 }
 pub(super) struct Ceremony<TContext, TReceiver, TSender>
 where
@@ -99,7 +103,7 @@ where
     /// The local [Arbiter] for this round.
     arbiter: Arbiter<PublicKey, MinSig>,
 
-    ceremony_metadata: Arc<Mutex<Metadata<TContext, U64, State>>>,
+    ceremony_metadata: Arc<Mutex<Store<TContext>>>,
     receiver: SubReceiver<TReceiver>,
     sender: SubSender<TSender>,
     metrics: Metrics,
@@ -116,7 +120,7 @@ where
     pub(super) async fn init(
         context: &mut TContext,
         mux: &mut MuxHandle<TSender, TReceiver>,
-        ceremony_metadata: Arc<Mutex<Metadata<TContext, U64, State>>>,
+        ceremony_metadata: Arc<Mutex<Store<TContext>>>,
         config: Config,
         metrics: Metrics,
     ) -> eyre::Result<Self> {
@@ -160,7 +164,7 @@ where
             .lock()
             .await
             .get(&config.epoch.into())
-            .cloned();
+            .wrap_err("failed reading ceremony state from disk")?;
 
         if let Some(recovered) = recovered {
             info!("found a previous ceremony state written to disk; recovering it");
@@ -324,6 +328,27 @@ where
             if let Some(player_me) = &mut self.player_me
                 && player == &self.config.me.public_key()
             {
+                // TODO(janis): easy to mess up the fields because some of them
+                // are of the same type. Better pass in a struct or create a
+                // builder.
+                let ack = match Ack::try_new(
+                    &union(&self.config.namespace, ACK_NAMESPACE),
+                    |namespace, payload| self.config.me.try_sign(namespace, payload),
+                    self.config.me.public_key(),
+                    self.config.epoch,
+                    &self.config.me.public_key(),
+                    &dealer_me.commitment,
+                ) {
+                    Ok(ack) => ack,
+                    Err(error) => {
+                        // Acking is attempted again the next time shares are
+                        // distributed. If it never succeeds, the share dealt
+                        // to ourselves is revealed in the dealing outcome.
+                        warn!(%error, "failed signing ack for our own share; not acking it");
+                        continue;
+                    }
+                };
+
                 player_me
                     .share(
                         self.config.me.public_key(),
@@ -335,17 +360,6 @@ where
                         commitment",
                     );
 
-                // TODO(janis): easy to mess up the fields because some of them
-                // are of the same type. Better pass in a struct or create a
-                // builder.
-                let ack = Ack::new(
-                    &union(&self.config.namespace, ACK_NAMESPACE),
-                    self.config.me.clone(),
-                    self.config.me.public_key(),
-                    self.config.epoch,
-                    &self.config.me.public_key(),
-                    &dealer_me.commitment,
-                );
                 assert_eq!(
                     None,
                     dealer_me
@@ -547,14 +561,15 @@ where
             .await
             .expect("must always be able to persist tracked shares to disk");
 
-        let payload = Ack::new(
+        let payload = Ack::try_new(
             &union(&self.config.namespace, ACK_NAMESPACE),
-            self.config.me.clone(),
+            |namespace, payload| self.config.me.try_sign(namespace, payload),
             self.config.me.public_key(),
             self.epoch(),
             &peer,
             &commitment,
         )
+        .wrap_err("failed signing ack; not returning it to peer")?
         .into();
         self.sender
             .send(
@@ -620,8 +635,22 @@ where
             block_outcome.dealer(),
         );
 
+        if let HardforkRegime::PostAllegroModerato = hardfork_regime {
+            ensure!(
+                usize::from(block_outcome.n_players()) == self.players().len(),
+                "deal outcome in block was dealt to `{}` players, but the current \
+                dkg ceremony has `{}` players",
+                block_outcome.n_players(),
+                self.players().len(),
+            );
+        }
+
         // Verify the dealer's signature before considering processing the outcome.
         let is_verified = match hardfork_regime {
+            HardforkRegime::PostAllegroModerato => block_outcome.verify_with_threshold(
+                &union(&self.config.namespace, OUTCOME_NAMESPACE),
+                self.config.dealing_threshold,
+            ),
             HardforkRegime::PostAllegretto => {
                 block_outcome.verify(&union(&self.config.namespace, OUTCOME_NAMESPACE))
             }
@@ -738,32 +767,41 @@ where
             })
             .collect::<Vec<_>>();
 
+        let n_players = self.config.players.len() as u32;
+        let max_reveals = match hardfork_regime {
+            HardforkRegime::PostAllegroModerato => n_players - self.config.dealing_threshold,
+            HardforkRegime::PreAllegretto | HardforkRegime::PostAllegretto => max_faults(n_players),
+        };
         ensure!(
-            reveals.len() as u32 <= max_faults(self.config.players.len() as u32),
+            reveals.len() as u32 <= max_reveals,
             "too many reveals; skipping deal outcome construction",
         );
 
         let dealing_outcome = match hardfork_regime {
-            HardforkRegime::PostAllegretto => Some(IntermediateOutcome::new(
-                self.config
-                    .players
-                    .len()
-                    .try_into()
-                    .expect("we should never have more than u16::MAX validators/players"),
-                &self.config.me,
-                &union(&self.config.namespace, OUTCOME_NAMESPACE),
-                self.config.epoch,
-                dealer_me.commitment.clone(),
-                dealer_me.acks.values().cloned().collect(),
-                reveals,
-            )),
-            HardforkRegime::PreAllegretto => Some(IntermediateOutcome::new_pre_allegretto(
+            HardforkRegime::PostAllegretto | HardforkRegime::PostAllegroModerato => {
+                Some(IntermediateOutcome::try_new(
+                    self.config
+                        .players
+                        .len()
+                        .try_into()
+                        .expect("we should never have more than u16::MAX validators/players"),
+                    self.config.me.public_key(),
+                    |namespace, payload| self.config.me.try_sign(namespace, payload),
+                    &union(&self.config.namespace, OUTCOME_NAMESPACE),
+                    self.config.epoch,
+                    dealer_me.commitment.clone(),
+                    dealer_me.acks.values().cloned().collect(),
+                    reveals,
+                ))
+            }
+            HardforkRegime::PreAllegretto => Some(IntermediateOutcome::try_new_pre_allegretto(
                 self.config
                     .players
                     .len()
                     .try_into()
                     .expect("we should never have more than u16::MAX validators/players"),
-                &self.config.me,
+                self.config.me.public_key(),
+                |namespace, payload| self.config.me.try_sign(namespace, payload),
                 &union(&self.config.namespace, OUTCOME_NAMESPACE),
                 self.config.epoch,
                 dealer_me.commitment.clone(),
@@ -900,6 +938,22 @@ where
     pub(super) fn is_player(&self) -> bool {
         self.player_me.is_some()
     }
+
+    /// Returns the progress of the ceremony, assuming it is in `phase`.
+    pub(super) fn status(&self, phase: DkgPhase) -> DkgStatus {
+        let to_b256 = |key: &PublicKey| alloy_primitives::B256::from_slice(key.as_ref());
+        DkgStatus {
+            epoch: self.epoch(),
+            phase,
+            dealers: self.dealers().iter().map(to_b256).collect(),
+            players: self.players().iter().map(to_b256).collect(),
+            is_dealer: self.is_dealer(),
+            is_player: self.is_player(),
+            shares_received: self.metrics.shares_received.get() as u64,
+            acks_received: self.metrics.acks_received.get() as u64,
+            dealings_read: self.metrics.dealings_read.get() as u64,
+        }
+    }
 }
 
 /// Metadata associated with a [Dealer].
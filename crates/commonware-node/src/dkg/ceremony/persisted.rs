@@ -1,17 +1,324 @@
 //! Information about a ceremony that is persisted to disk.
+//!
+//! If enabled, the ceremony state is encrypted with XChaCha20-Poly1305 before
+//! it is written, since it contains the shares dealt to and received from
+//! other validators. The key is derived from the keystore passphrase with
+//! scrypt, under a random salt stored next to the states.
+//!
+//! The same [`Store`] also persists the epoch states and DKG outcomes of the
+//! DKG manager, which hold the share of this node for an epoch.
 
 use std::collections::BTreeMap;
 
 use bytes::Buf;
-use commonware_codec::{EncodeSize, RangeCfg, Read, Write, varint::UInt};
+use chacha20poly1305::{
+    Key, XChaCha20Poly1305, XNonce,
+    aead::{Aead as _, KeyInit as _, Payload},
+};
+use commonware_codec::{
+    DecodeExt as _, Encode as _, EncodeSize, FixedSize as _, RangeCfg, Read, ReadExt as _, Write,
+    varint::UInt,
+};
 use commonware_cryptography::{
     bls12381::primitives::{group, poly::Public, variant::MinSig},
     ed25519::PublicKey,
 };
-use commonware_utils::quorum;
+use commonware_runtime::{Clock, Metrics, Storage};
+use commonware_storage::metadata::Metadata;
+use commonware_utils::{quorum, sequence::U64};
+use eyre::{OptionExt as _, WrapErr as _, bail, eyre};
+use rand::{RngCore as _, SeedableRng as _, rngs::StdRng};
+use rand_core::CryptoRngCore;
+use tempo_commonware_node_config::{Passphrase, keystore::DEFAULT_SCRYPT_LOG_N};
+use zeroize::Zeroizing;
 
 use super::IntermediateOutcome;
 
+/// Prefix of an encrypted record.
+///
+/// A plaintext [`State`] starts with its number of players encoded as a
+/// varint `u16`, which is at most 3 bytes long and so can never start with 3
+/// bytes of `0xff`. The values of the other stores start with a `bool` or a
+/// varint epoch, neither of which starts with 4 bytes of `0xff` either.
+const ENCRYPTED_MAGIC: [u8; 4] = [0xff; 4];
+
+/// Prefix of the record holding the parameters of the state encryption key.
+const KDF_MAGIC: [u8; 4] = [0xff, 0xff, 0xff, 0xfe];
+
+/// The key of the record holding the parameters of the state encryption key.
+///
+/// Values are keyed by epoch or by a small constant, which never reach this
+/// value.
+const KDF_KEY: U64 = U64::new(u64::MAX);
+
+const NONCE_LENGTH: usize = 24;
+const SALT_LENGTH: usize = 32;
+
+/// Upper bound on the size of an encrypted state read back from disk.
+const MAX_CIPHERTEXT_SIZE: usize = 64 * 1024 * 1024;
+
+/// Values persisted to disk, by default ceremony states keyed by epoch.
+///
+/// Wraps the on-disk metadata so that values are transparently encrypted on
+/// write and decrypted on read. Values written by a node that did not encrypt
+/// them are read as-is and encrypted the next time they are written.
+pub(in crate::dkg) struct Store<TContext, V = State>
+where
+    TContext: Clock + Metrics + Storage,
+    V: Clone + Write + EncodeSize + Read<Cfg = ()>,
+{
+    metadata: Metadata<TContext, U64, Record<V>>,
+    cipher: Option<(XChaCha20Poly1305, StdRng)>,
+}
+
+impl<TContext, V> Store<TContext, V>
+where
+    TContext: Clock + Metrics + Storage,
+    V: Clone + Write + EncodeSize + Read<Cfg = ()>,
+{
+    /// Initializes the store in `partition`.
+    ///
+    /// If `passphrase` is set, states are encrypted under a key derived from
+    /// it. Nonces and the salt of a new store are drawn from `rng` so that
+    /// runs under the deterministic runtime stay reproducible.
+    pub(in crate::dkg) async fn init(
+        context: TContext,
+        partition: String,
+        passphrase: Option<&Passphrase>,
+        rng: &mut impl CryptoRngCore,
+    ) -> eyre::Result<Self> {
+        Self::init_with_cost(context, partition, passphrase, DEFAULT_SCRYPT_LOG_N, rng).await
+    }
+
+    /// Like [`Store::init`], but deriving the key of a new store with scrypt
+    /// cost parameter `log2(N)` set to `log_n`.
+    async fn init_with_cost(
+        context: TContext,
+        partition: String,
+        passphrase: Option<&Passphrase>,
+        log_n: u8,
+        rng: &mut impl CryptoRngCore,
+    ) -> eyre::Result<Self> {
+        let mut metadata = Metadata::init(
+            context,
+            commonware_storage::metadata::Config {
+                partition,
+                codec_config: (),
+            },
+        )
+        .await
+        .wrap_err("failed initializing metadata on disk")?;
+
+        let Some(passphrase) = passphrase else {
+            return Ok(Self {
+                metadata,
+                cipher: None,
+            });
+        };
+
+        let (log_n, salt) = match metadata.get(&KDF_KEY) {
+            Some(Record::Kdf { log_n, salt }) => (*log_n, *salt),
+            Some(_) => bail!("record holding the state key parameters is not a key record"),
+            None => {
+                let mut salt = [0u8; SALT_LENGTH];
+                rng.fill_bytes(&mut salt);
+                metadata
+                    .put_sync(KDF_KEY, Record::Kdf { log_n, salt })
+                    .await
+                    .wrap_err("failed persisting state key parameters")?;
+                (log_n, salt)
+            }
+        };
+        let key = passphrase
+            .derive_key(&salt, log_n)
+            .wrap_err("failed deriving state key from keystore passphrase")?;
+        let cipher = (
+            XChaCha20Poly1305::new(Key::from_slice(key.as_slice())),
+            StdRng::from_rng(rng).expect("seeding from a cryptographic rng never fails"),
+        );
+        Ok(Self {
+            metadata,
+            cipher: Some(cipher),
+        })
+    }
+
+    /// Returns the value at `key`, decrypting it if necessary.
+    ///
+    /// Fails if the value is encrypted but cannot be decrypted, for example
+    /// because the keystore passphrase changed.
+    pub(in crate::dkg) fn get(&self, key: &U64) -> eyre::Result<Option<V>> {
+        let Some(record) = self.metadata.get(key) else {
+            return Ok(None);
+        };
+        let (nonce, ciphertext) = match record {
+            Record::Plain(value) => return Ok(Some(value.clone())),
+            Record::Encrypted { nonce, ciphertext } => (nonce, ciphertext),
+            Record::Kdf { .. } => bail!("record is not a persisted state"),
+        };
+        let (cipher, _) = self.cipher.as_ref().ok_or_eyre(
+            "state on disk is encrypted, but state encryption is not enabled; \
+            a keystore passphrase must be provided",
+        )?;
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(
+                    XNonce::from_slice(nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: &key.encode(),
+                    },
+                )
+                .map_err(|_| {
+                    eyre!(
+                        "failed decrypting state; \
+                        was it written with a different keystore passphrase?"
+                    )
+                })?,
+        );
+        V::decode(plaintext.as_slice())
+            .map(Some)
+            .wrap_err("decrypted state is invalid")
+    }
+
+    /// Writes `value` at `key`. Takes effect on the next sync.
+    pub(in crate::dkg) fn put(&mut self, key: U64, value: V) {
+        let record = self.seal(&key, value);
+        self.metadata.put(key, record);
+    }
+
+    /// Writes `value` at `key` and syncs it to disk.
+    pub(in crate::dkg) async fn put_sync(
+        &mut self,
+        key: U64,
+        value: V,
+    ) -> Result<(), commonware_storage::metadata::Error> {
+        let record = self.seal(&key, value);
+        self.metadata.put_sync(key, record).await
+    }
+
+    /// Removes the value at `key`. Takes effect on the next sync.
+    pub(in crate::dkg) fn remove(&mut self, key: &U64) {
+        self.metadata.remove(key);
+    }
+
+    /// Removes the value at `key` and returns it. Takes effect on the next
+    /// sync.
+    pub(in crate::dkg) fn take(&mut self, key: &U64) -> eyre::Result<Option<V>> {
+        let value = self.get(key)?;
+        self.remove(key);
+        Ok(value)
+    }
+
+    pub(in crate::dkg) async fn sync(&mut self) -> Result<(), commonware_storage::metadata::Error> {
+        self.metadata.sync().await
+    }
+
+    fn seal(&mut self, key: &U64, value: V) -> Record<V> {
+        let Some((cipher, rng)) = self.cipher.as_mut() else {
+            return Record::Plain(value);
+        };
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rng.fill_bytes(&mut nonce);
+        let plaintext = Zeroizing::new(value.encode().to_vec());
+        let ciphertext = cipher
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &key.encode(),
+                },
+            )
+            .expect("encrypting a state of bounded size never fails");
+        Record::Encrypted { nonce, ciphertext }
+    }
+}
+
+impl<TContext, V> Store<TContext, V>
+where
+    TContext: Clock + Metrics + Storage,
+    V: Clone + Default + Write + EncodeSize + Read<Cfg = ()>,
+{
+    /// Updates the value at `key` with `f`, starting from the default value
+    /// if there is none, and syncs it to disk.
+    pub(in crate::dkg) async fn upsert_sync(
+        &mut self,
+        key: U64,
+        f: impl FnOnce(&mut V),
+    ) -> eyre::Result<()> {
+        let mut value = self.get(&key)?.unwrap_or_default();
+        f(&mut value);
+        self.put_sync(key, value)
+            .await
+            .wrap_err("failed writing state to disk")
+    }
+}
+
+/// A value as it is written to disk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Record<V> {
+    Plain(V),
+    Encrypted {
+        nonce: [u8; NONCE_LENGTH],
+        ciphertext: Vec<u8>,
+    },
+    /// The parameters the state encryption key is derived with.
+    Kdf {
+        log_n: u8,
+        salt: [u8; SALT_LENGTH],
+    },
+}
+
+impl<V: Write> Write for Record<V> {
+    fn write(&self, buf: &mut impl bytes::BufMut) {
+        match self {
+            Self::Plain(value) => value.write(buf),
+            Self::Encrypted { nonce, ciphertext } => {
+                ENCRYPTED_MAGIC.write(buf);
+                nonce.write(buf);
+                ciphertext.write(buf);
+            }
+            Self::Kdf { log_n, salt } => {
+                KDF_MAGIC.write(buf);
+                log_n.write(buf);
+                salt.write(buf);
+            }
+        }
+    }
+}
+
+impl<V: EncodeSize> EncodeSize for Record<V> {
+    fn encode_size(&self) -> usize {
+        match self {
+            Self::Plain(value) => value.encode_size(),
+            Self::Encrypted {
+                nonce: _,
+                ciphertext,
+            } => <[u8; 4]>::SIZE + <[u8; NONCE_LENGTH]>::SIZE + ciphertext.encode_size(),
+            Self::Kdf { .. } => <[u8; 4]>::SIZE + u8::SIZE + <[u8; SALT_LENGTH]>::SIZE,
+        }
+    }
+}
+
+impl<V: Read<Cfg = ()>> Read for Record<V> {
+    type Cfg = ();
+
+    fn read_cfg(buf: &mut impl Buf, _cfg: &Self::Cfg) -> Result<Self, commonware_codec::Error> {
+        if buf.chunk().starts_with(&KDF_MAGIC) {
+            buf.advance(KDF_MAGIC.len());
+            let log_n = u8::read(buf)?;
+            let salt = <[u8; SALT_LENGTH]>::read(buf)?;
+            return Ok(Self::Kdf { log_n, salt });
+        }
+        if !buf.chunk().starts_with(&ENCRYPTED_MAGIC) {
+            return V::read_cfg(buf, &()).map(Self::Plain);
+        }
+        buf.advance(ENCRYPTED_MAGIC.len());
+        let nonce = <[u8; NONCE_LENGTH]>::read(buf)?;
+        let ciphertext = Vec::<u8>::read_cfg(buf, &(RangeCfg::from(0..=MAX_CIPHERTEXT_SIZE), ()))?;
+        Ok(Self::Encrypted { nonce, ciphertext })
+    }
+}
+
 /// Information on a ceremony that is persisted to disk.
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
 pub(in crate::dkg) struct State {
@@ -124,15 +431,17 @@ mod tests {
 
     use crate::dkg::ceremony::{ACK_NAMESPACE, Ack, OUTCOME_NAMESPACE};
 
-    use super::{Dealing, State};
+    use super::{Dealing, KDF_KEY, Record, SALT_LENGTH, State, Store};
     use commonware_codec::{DecodeExt as _, Encode as _, Read as _};
     use commonware_cryptography::{
-        PrivateKeyExt as _, Signer,
+        PrivateKeyExt as _, Signer as _,
         bls12381::{dkg, primitives::variant::MinSig},
         ed25519::{PrivateKey, PublicKey},
     };
-    use commonware_utils::{quorum, set::Ordered, union};
+    use commonware_runtime::{Blob as _, Metrics as _, Runner as _, Storage as _, deterministic};
+    use commonware_utils::{quorum, sequence::U64, set::Ordered, union};
     use rand::{SeedableRng as _, rngs::StdRng};
+    use tempo_commonware_node_config::Passphrase;
     use tempo_dkg_onchain_artifacts::IntermediateOutcome;
 
     fn four_private_keys() -> Ordered<PrivateKey> {
@@ -264,4 +573,178 @@ mod tests {
 
         assert_eq!(State::decode(&mut bytes.as_ref()).unwrap(), state);
     }
+
+    #[test]
+    fn plaintext_state_is_read_as_plain_record() {
+        let state = State {
+            num_players: u16::MAX,
+            dealing: Some(dealing(0)),
+            ..State::default()
+        };
+
+        assert_eq!(
+            Record::<State>::decode(state.encode().freeze()).unwrap(),
+            Record::Plain(state),
+        );
+    }
+
+    /// A scrypt cost low enough to keep the tests fast.
+    const TEST_SCRYPT_LOG_N: u8 = 4;
+
+    fn passphrase(passphrase: &str) -> Passphrase {
+        Passphrase::new(passphrase.to_string())
+    }
+
+    fn state() -> State {
+        State {
+            num_players: 4,
+            dealing: Some(dealing(0)),
+            ..State::default()
+        }
+    }
+
+    async fn init_store(
+        context: &mut deterministic::Context,
+        label: &str,
+        passphrase: Option<&Passphrase>,
+    ) -> eyre::Result<Store<deterministic::Context>> {
+        Store::init_with_cost(
+            context.with_label(label),
+            "ceremony".into(),
+            passphrase,
+            TEST_SCRYPT_LOG_N,
+            context,
+        )
+        .await
+    }
+
+    /// Returns the concatenated contents of all blobs in `partition`.
+    async fn disk_contents(context: &deterministic::Context, partition: &str) -> Vec<u8> {
+        let mut contents = Vec::new();
+        for name in context.scan(partition).await.unwrap() {
+            let (blob, len) = context.open(partition, &name).await.unwrap();
+            let bytes = blob.read_at(vec![0; len as usize], 0).await.unwrap();
+            contents.extend_from_slice(bytes.as_ref());
+        }
+        contents
+    }
+
+    #[test]
+    fn no_share_bytes_reach_disk_with_passphrase() {
+        deterministic::Runner::default().start(|mut context| async move {
+            let state = state();
+            let secret = state.dealing.as_ref().unwrap().shares[&four_public_keys()[0]]
+                .private
+                .encode();
+            let contains_secret = |contents: &[u8]| {
+                contents
+                    .windows(secret.len())
+                    .any(|window| window == secret.as_ref())
+            };
+
+            let mut plain = Store::init_with_cost(
+                context.with_label("plain"),
+                "plain".into(),
+                None,
+                TEST_SCRYPT_LOG_N,
+                &mut context,
+            )
+            .await
+            .unwrap();
+            plain.put_sync(U64::new(1), state.clone()).await.unwrap();
+            assert!(contains_secret(&disk_contents(&context, "plain").await));
+
+            let passphrase = passphrase("correct horse");
+            let mut store = init_store(&mut context, "store", Some(&passphrase))
+                .await
+                .unwrap();
+            store.put_sync(U64::new(1), state).await.unwrap();
+            let contents = disk_contents(&context, "ceremony").await;
+            assert!(!contents.is_empty());
+            assert!(!contains_secret(&contents));
+        });
+    }
+
+    #[test]
+    fn store_encrypts_state() {
+        deterministic::Runner::default().start(|mut context| async move {
+            let passphrase = passphrase("correct horse");
+            let mut store = init_store(&mut context, "store", Some(&passphrase))
+                .await
+                .unwrap();
+
+            store.put_sync(U64::new(1), state()).await.unwrap();
+
+            assert!(matches!(
+                store.metadata.get(&U64::new(1)),
+                Some(Record::Encrypted { .. }),
+            ));
+            assert_eq!(store.get(&U64::new(1)).unwrap(), Some(state()));
+        });
+    }
+
+    #[test]
+    fn reopened_store_decrypts_state_with_same_passphrase() {
+        deterministic::Runner::default().start(|mut context| async move {
+            let passphrase = passphrase("correct horse");
+            let mut store = init_store(&mut context, "store", Some(&passphrase))
+                .await
+                .unwrap();
+            store.put_sync(U64::new(1), state()).await.unwrap();
+            drop(store);
+
+            let store = init_store(&mut context, "reopened", Some(&passphrase))
+                .await
+                .unwrap();
+            assert_eq!(store.get(&U64::new(1)).unwrap(), Some(state()));
+        });
+    }
+
+    #[test]
+    fn reading_state_with_wrong_or_missing_passphrase_fails() {
+        deterministic::Runner::default().start(|mut context| async move {
+            let mut store = init_store(&mut context, "store", Some(&passphrase("correct horse")))
+                .await
+                .unwrap();
+            store.put_sync(U64::new(1), state()).await.unwrap();
+            drop(store);
+
+            let store = init_store(&mut context, "wrong", Some(&passphrase("battery staple")))
+                .await
+                .unwrap();
+            assert!(store.get(&U64::new(1)).is_err());
+            drop(store);
+
+            let store = init_store(&mut context, "unset", None).await.unwrap();
+            assert!(store.get(&U64::new(1)).is_err());
+        });
+    }
+
+    #[test]
+    fn store_with_excessive_scrypt_cost_on_disk_is_rejected() {
+        deterministic::Runner::default().start(|mut context| async move {
+            let passphrase = passphrase("correct horse");
+            let mut store = init_store(&mut context, "store", Some(&passphrase))
+                .await
+                .unwrap();
+            store
+                .metadata
+                .put_sync(
+                    KDF_KEY,
+                    Record::Kdf {
+                        log_n: 63,
+                        salt: [0; SALT_LENGTH],
+                    },
+                )
+                .await
+                .unwrap();
+            drop(store);
+
+            assert!(
+                init_store(&mut context, "reopened", Some(&passphrase))
+                    .await
+                    .is_err()
+            );
+        });
+    }
 }
@@ -22,12 +22,16 @@ use commonware_utils::{
     union,
 };
 
-use eyre::{OptionExt as _, eyre};
+use eyre::{OptionExt as _, WrapErr as _, eyre};
 use futures::{StreamExt as _, channel::mpsc, lock::Mutex};
 use prometheus_client::metrics::{counter::Counter, gauge::Gauge};
 use rand_core::CryptoRngCore;
 use tempo_chainspec::hardfork::TempoHardforks as _;
-use tempo_node::TempoFullNode;
+use tempo_dkg_onchain_artifacts::dealing_threshold;
+use tempo_node::{
+    TempoFullNode,
+    rpc::{DkgPhase, DkgStatus},
+};
 use tracing::{Span, error, info, instrument, warn};
 
 use crate::{
@@ -36,8 +40,8 @@ use crate::{
         ceremony,
         ceremony::{Ceremony, OUTCOME_NAMESPACE},
         manager::{
-            DecodedValidator,
-            ingress::{Finalize, GetIntermediateDealing, GetOutcome},
+            DecodedValidator, Status,
+            ingress::{Finalize, GetIntermediateDealing, GetOutcome, GetStatus},
             validators::{self, ValidatorState},
         },
     },
@@ -65,7 +69,7 @@ where
     /// predecessor (epochs i and i-1). This ceremony metadata is updated on
     /// the last height of en epoch (the height on which the ceremony for the
     /// next epoch will be started).
-    ceremony_metadata: Arc<Mutex<Metadata<ContextCell<TContext>, U64, ceremony::State>>>,
+    ceremony_metadata: Arc<Mutex<ceremony::Store<ContextCell<TContext>>>>,
 
     /// Persisted information on the current epoch for DKG ceremonies that were
     /// started after the allegretto hardfork.
@@ -88,6 +92,17 @@ where
     /// Handles to the metrics objects that the actor will update during its
     /// runtime.
     metrics: Metrics,
+
+    /// The height of the latest finalized block processed by the actor.
+    latest_finalized_height: Option<u64>,
+
+    /// Progress of the last ceremony at the moment it was finalized. Reported
+    /// in place of the running ceremony until the next one is started.
+    finalized_ceremony: Option<DkgStatus>,
+
+    /// The peers most recently registered on the p2p peer manager, together
+    /// with the epoch they were registered for.
+    registered_peers: Option<(Epoch, Vec<(PublicKey, SocketAddr)>)>,
 }
 
 impl<TContext, TPeerManager> Actor<TContext, TPeerManager>
@@ -103,23 +118,30 @@ where
         context: TContext,
         mailbox: mpsc::UnboundedReceiver<super::ingress::Message>,
     ) -> eyre::Result<Self> {
-        let context = ContextCell::new(context);
+        let mut context = ContextCell::new(context);
 
-        let ceremony_metadata = Metadata::init(
+        let ceremony_metadata = ceremony::Store::init(
             context.with_label("ceremony_metadata"),
-            commonware_storage::metadata::Config {
-                partition: format!("{}_ceremony", config.partition_prefix),
-                codec_config: (),
-            },
+            format!("{}_ceremony", config.partition_prefix),
+            config.state_passphrase.as_ref(),
+            &mut context,
         )
         .await
-        .expect("must be able to initialize metadata on disk to function");
+        .wrap_err("failed initializing ceremony state store")?;
 
-        let post_allegretto_metadatas =
-            post_allegretto::Metadatas::init(&context, &config.partition_prefix).await;
+        let post_allegretto_metadatas = post_allegretto::Metadatas::init(
+            &mut context,
+            &config.partition_prefix,
+            config.state_passphrase.as_ref(),
+        )
+        .await?;
 
-        let pre_allegretto_metadatas =
-            pre_allegretto::Metadatas::init(&context, &config.partition_prefix).await;
+        let pre_allegretto_metadatas = pre_allegretto::Metadatas::init(
+            &mut context,
+            &config.partition_prefix,
+            config.state_passphrase.as_ref(),
+        )
+        .await?;
 
         let validators_metadata = Metadata::init(
             context.with_label("validators__metadata"),
@@ -138,6 +160,7 @@ where
         let pre_allegretto_ceremonies = Counter::default();
         let post_allegretto_ceremonies = Counter::default();
         let failed_allegretto_transitions = Counter::default();
+        let key_rotations = Counter::default();
 
         context.register(
             "syncing_players",
@@ -168,6 +191,12 @@ where
             failed_allegretto_transitions.clone(),
         );
 
+        context.register(
+            "key_rotations",
+            "how many validators rotated their key into the players of a ceremony",
+            key_rotations.clone(),
+        );
+
         let ceremony = ceremony::Metrics::register(&context);
 
         let metrics = Metrics {
@@ -176,6 +205,7 @@ where
             pre_allegretto_ceremonies,
             post_allegretto_ceremonies,
             failed_allegretto_transitions,
+            key_rotations,
             ceremony,
         };
 
@@ -188,6 +218,9 @@ where
             pre_allegretto_metadatas,
             validators_metadata,
             metrics,
+            latest_finalized_height: None,
+            finalized_ceremony: None,
+            registered_peers: None,
         })
     }
 
@@ -243,6 +276,10 @@ where
                     let _: Result<_, _> =
                         self.handle_get_outcome(cause, get_ceremony_outcome).await;
                 }
+                super::Command::GetStatus(get_status) => {
+                    let _: Result<_, _> =
+                        self.handle_get_status(cause, get_status, ceremony.as_ref());
+                }
 
                 // Verifies some DKG dealing based on the current state the DKG manager
                 // is in. This is a request when verifying proposals. It relies on the
@@ -255,14 +292,24 @@ where
                 // In other words: no dealing will ever have to be verified if it is
                 // for another epoch than the currently latest one.
                 super::Command::VerifyDealing(verify_dealing) => {
-                    let outcome = if self
-                        .post_allegretto_metadatas
-                        .current_epoch_state()
-                        .is_some()
+                    let outcome = if let Some(epoch_state) =
+                        self.post_allegretto_metadatas.current_epoch_state()
                     {
-                        verify_dealing
-                            .dealing
-                            .verify(&union(&self.config.namespace, OUTCOME_NAMESPACE))
+                        let spec = self.config.execution_node.chain_spec();
+                        let namespace = union(&self.config.namespace, OUTCOME_NAMESPACE);
+                        if spec.is_allegro_moderato_active_at_timestamp(verify_dealing.timestamp) {
+                            let n_players = epoch_state.player_pubkeys().len();
+                            usize::from(verify_dealing.dealing.n_players()) == n_players
+                                && verify_dealing.dealing.verify_with_threshold(
+                                    &namespace,
+                                    dealing_threshold(
+                                        n_players as u32,
+                                        spec.info.dkg_threshold_bps(),
+                                    ),
+                                )
+                        } else {
+                            verify_dealing.dealing.verify(&namespace)
+                        }
                     } else if self
                         .pre_allegretto_metadatas
                         .current_epoch_state()
@@ -358,6 +405,48 @@ where
             .map_err(|_| eyre!("failed returning outcome because requester went away"))
     }
 
+    #[instrument(
+        parent = &cause,
+        skip_all,
+        err,
+    )]
+    fn handle_get_status<TReceiver, TSender>(
+        &self,
+        cause: Span,
+        GetStatus { response }: GetStatus,
+        ceremony: Option<&Ceremony<ContextCell<TContext>, TReceiver, TSender>>,
+    ) -> eyre::Result<()>
+    where
+        TReceiver: Receiver<PublicKey = PublicKey>,
+        TSender: Sender<PublicKey = PublicKey>,
+    {
+        let ceremony = match ceremony {
+            Some(ceremony) => Some(ceremony.status(self.ceremony_phase())),
+            None => self.finalized_ceremony.clone(),
+        };
+        let peers = self
+            .registered_peers
+            .as_ref()
+            .map(|(_, peers)| peers.clone())
+            .unwrap_or_default();
+
+        response
+            .send(Status { ceremony, peers })
+            .map_err(|_| eyre!("failed returning status because requester went away"))
+    }
+
+    /// Returns the phase a running ceremony is in, derived from the height
+    /// of the next block to be finalized.
+    fn ceremony_phase(&self) -> DkgPhase {
+        let next_height = self.latest_finalized_height.map_or(0, |height| height + 1);
+        match epoch::relative_position(next_height, self.config.epoch_length) {
+            epoch::RelativePosition::FirstHalf => DkgPhase::DistributingShares,
+            epoch::RelativePosition::Middle | epoch::RelativePosition::SecondHalf => {
+                DkgPhase::CollectingDealings
+            }
+        }
+    }
+
     /// Handles a finalized block.
     ///
     /// Some block heights are special cased:
@@ -405,6 +494,11 @@ where
         TReceiver: Receiver<PublicKey = PublicKey>,
         TSender: Sender<PublicKey = PublicKey>,
     {
+        let height = block.height();
+        let ceremony_before = maybe_ceremony
+            .as_ref()
+            .map(|ceremony| ceremony.status(DkgPhase::Finalized));
+
         if self.is_running_post_allegretto(&block) {
             self.handle_finalized_post_allegretto(cause, *block, maybe_ceremony, ceremony_mux)
                 .await;
@@ -412,6 +506,11 @@ where
             self.handle_finalized_pre_allegretto(cause, *block, maybe_ceremony, ceremony_mux)
                 .await;
         }
+
+        self.latest_finalized_height = Some(height);
+        if maybe_ceremony.is_none() && ceremony_before.is_some() {
+            self.finalized_ceremony = ceremony_before;
+        }
         acknowledgment.acknowledge();
     }
 
@@ -558,6 +657,18 @@ where
             .update(epoch, peers_to_register.clone())
             .await;
 
+        if self
+            .registered_peers
+            .as_ref()
+            .is_none_or(|(latest, _)| *latest <= epoch)
+        {
+            let peers = peers_to_register
+                .iter_pairs()
+                .map(|(public_key, address)| (public_key.clone(), *address))
+                .collect();
+            self.registered_peers = Some((epoch, peers));
+        }
+
         info!(
             peers_registered = ?peers_to_register,
             "registered p2p peers by merging dealers, players, syncing players",
@@ -586,16 +697,11 @@ where
     ///
     /// Always prefers the post allegretto state, if it exists.
     fn previous_epoch_state(&self) -> Option<EpochState> {
-        if let Some(epoch_state) = self
-            .post_allegretto_metadatas
-            .previous_epoch_state()
-            .cloned()
-        {
+        if let Some(epoch_state) = self.post_allegretto_metadatas.previous_epoch_state() {
             Some(EpochState::PostModerato(epoch_state))
         } else {
             self.pre_allegretto_metadatas
                 .previous_epoch_state()
-                .cloned()
                 .map(EpochState::PreModerato)
         }
     }
@@ -609,15 +715,9 @@ where
     /// Panics if no epoch state exists, neither for the pre- nor post-allegretto
     /// regime. There must always be an epoch state.
     fn current_epoch_state(&self) -> EpochState {
-        if let Some(epoch_state) = self
-            .post_allegretto_metadatas
-            .current_epoch_state()
-            .cloned()
-        {
+        if let Some(epoch_state) = self.post_allegretto_metadatas.current_epoch_state() {
             EpochState::PostModerato(epoch_state)
-        } else if let Some(epoch_state) =
-            self.pre_allegretto_metadatas.current_epoch_state().cloned()
-        {
+        } else if let Some(epoch_state) = self.pre_allegretto_metadatas.current_epoch_state() {
             EpochState::PreModerato(epoch_state)
         } else {
             panic!("either pre- or post-allegretto current-epoch-state should exist")
@@ -667,6 +767,7 @@ struct Metrics {
     pre_allegretto_ceremonies: Counter,
     post_allegretto_ceremonies: Counter,
     failed_allegretto_transitions: Counter,
+    key_rotations: Counter,
     syncing_players: Gauge,
     ceremony: ceremony::Metrics,
 }
@@ -686,7 +787,8 @@ async fn read_validator_config_with_retry<C: commonware_runtime::Clock>(
     let retry_after = Duration::from_secs(1);
     loop {
         if let Ok(validators) =
-            validators::read_from_contract(attempts, node, epoch, epoch_length, expected_block_hash).await
+            validators::read_from_contract(attempts, node, epoch, epoch_length, expected_block_hash)
+                .await
         {
             break validators;
         }
@@ -11,20 +11,21 @@ use commonware_cryptography::{
 };
 use commonware_p2p::{Receiver, Sender, utils::mux::MuxHandle};
 use commonware_runtime::{Clock, ContextCell, Metrics, Spawner, Storage};
-use commonware_storage::metadata::Metadata;
 use commonware_utils::{
     sequence::U64,
     set::{Ordered, OrderedAssociated},
 };
-use eyre::{WrapErr as _, ensure};
+use eyre::{WrapErr as _, ensure, eyre};
 use rand_core::CryptoRngCore;
 use reth_ethereum::chainspec::EthChainSpec as _;
 use tempo_chainspec::hardfork::TempoHardforks as _;
-use tempo_dkg_onchain_artifacts::PublicOutcome;
+use tempo_commonware_node_config::Passphrase;
+use tempo_dkg_onchain_artifacts::{PublicOutcome, dealing_threshold};
+use tempo_snapshot::Checkpoint;
 use tracing::{Span, info, instrument, warn};
 
 use crate::{
-    consensus::block::Block,
+    consensus::{Digest, block::Block},
     dkg::{
         HardforkRegime,
         ceremony::{self, Ceremony},
@@ -41,6 +42,11 @@ const PREVIOUS_EPOCH_KEY: U64 = U64::new(1);
 
 const DKG_OUTCOME_KEY: U64 = U64::new(0);
 
+/// All epoch states and DKG outcomes on disk were read once when the stores
+/// were initialized.
+const STATE_READABLE: &str =
+    "epoch states and DKG outcomes on disk were checked to be readable on startup";
+
 impl<TContext, TPeerManager> super::Actor<TContext, TPeerManager>
 where
     TContext: Clock + CryptoRngCore + commonware_runtime::Metrics + Spawner + Storage,
@@ -51,6 +57,12 @@ where
 {
     #[instrument(skip_all, err)]
     pub(super) async fn post_allegretto_init(&mut self) -> eyre::Result<()> {
+        if !self.post_allegretto_metadatas.exists()
+            && let Some(checkpoint) = self.config.checkpoint.clone()
+        {
+            return self.post_allegretto_init_from_checkpoint(checkpoint).await;
+        }
+
         let spec = self.config.execution_node.chain_spec();
         if !self.post_allegretto_metadatas.exists() && spec.is_allegretto_active_at_timestamp(0) {
             info!(
@@ -146,6 +158,98 @@ where
         Ok(())
     }
 
+    /// Initializes the epoch state of a node started from a state snapshot.
+    ///
+    /// The node enters the epoch following the snapshot's boundary block as a
+    /// verifier. Participants and public polynomial are taken from the DKG
+    /// outcome in the boundary header, which was verified together with the
+    /// snapshot.
+    ///
+    /// The players of the epoch's ceremony are read from the contract at the
+    /// boundary. The other nodes read them one epoch earlier, so both agree as
+    /// long as the validator set did not change in the epoch concluded by the
+    /// boundary.
+    ///
+    /// The node only runs the post-allegretto DKG, so allegretto must have
+    /// been active for the whole epoch concluded by the boundary. Chains that
+    /// activated allegretto after genesis can start from snapshots taken from
+    /// the epoch after the activation on.
+    #[instrument(
+        skip_all,
+        fields(
+            checkpoint.height = checkpoint.height(),
+            checkpoint.hash = %checkpoint.hash(),
+            epoch = checkpoint.outcome.epoch,
+        ),
+        err,
+    )]
+    async fn post_allegretto_init_from_checkpoint(
+        &mut self,
+        checkpoint: Checkpoint,
+    ) -> eyre::Result<()> {
+        let spec = self.config.execution_node.chain_spec();
+        ensure!(
+            spec.is_allegretto_active_at_timestamp(
+                checkpoint.ceremony_start_timestamp(spec.genesis().timestamp)
+            ),
+            "starting from a snapshot requires the allegretto hardfork to be \
+            active during the whole epoch of the checkpoint; use a snapshot \
+            taken at a later epoch",
+        );
+        if self.config.initial_share.is_some() {
+            warn!(
+                "ignoring the configured signing share: it was dealt for \
+                genesis, but the node starts from a snapshot in a later epoch"
+            );
+        }
+
+        let validators = super::read_validator_config_with_retry(
+            &self.context,
+            &self.config.execution_node,
+            checkpoint.outcome.epoch,
+            self.config.epoch_length,
+            Some(checkpoint.hash()),
+        )
+        .await;
+        let validator_state =
+            ValidatorState::from_checkpoint(&checkpoint.outcome.participants, validators);
+
+        info!(
+            participants = ?checkpoint.outcome.participants,
+            players = ?validator_state.player_pubkeys(),
+            "using public polynomial and participants of snapshot checkpoint",
+        );
+
+        self.post_allegretto_metadatas
+            .epoch_metadata
+            .put_sync(
+                CURRENT_EPOCH_KEY,
+                EpochState {
+                    dkg_outcome: DkgOutcome {
+                        dkg_successful: true,
+                        epoch: checkpoint.outcome.epoch,
+                        participants: checkpoint.outcome.participants,
+                        public: checkpoint.outcome.public,
+                        share: None,
+                    },
+                    validator_state,
+                },
+            )
+            .await
+            .expect("persisting epoch state must always work");
+
+        // The boundary block is the "genesis" of the epoch's consensus engine,
+        // so it must be known to marshal before the epoch can be entered.
+        info!("waiting for marshal to fetch the boundary block of the checkpoint");
+        self.config
+            .marshal
+            .subscribe(None, Digest(checkpoint.hash()))
+            .await
+            .await
+            .map_err(|_| eyre!("marshal dropped channel before the boundary block was sent"))?;
+        Ok(())
+    }
+
     /// Handles a finalized block.
     ///
     /// Some block heights are special cased:
@@ -217,7 +321,8 @@ where
         if utils::is_last_block_in_epoch(self.config.epoch_length, block.height()).is_some() {
             // Pass the finalized block hash to ensure we read validators from the
             // correct canonical block and not an uncle block.
-            self.update_and_register_current_epoch_state(block.block_hash()).await;
+            self.update_and_register_current_epoch_state(block.block_hash())
+                .await;
 
             maybe_ceremony.replace(self.start_post_allegretto_ceremony(ceremony_mux).await);
             // Early return: start driving the ceremony on the first height of
@@ -249,6 +354,16 @@ where
                 returned to its Option?",
         );
 
+        let regime = if self
+            .config
+            .execution_node
+            .chain_spec()
+            .is_allegro_moderato_active_at_timestamp(block.timestamp())
+        {
+            HardforkRegime::PostAllegroModerato
+        } else {
+            HardforkRegime::PostAllegretto
+        };
         match epoch::relative_position(block.height(), self.config.epoch_length) {
             epoch::RelativePosition::FirstHalf => {
                 let _ = ceremony.distribute_shares().await;
@@ -256,14 +371,10 @@ where
             }
             epoch::RelativePosition::Middle => {
                 let _ = ceremony.process_messages().await;
-                let _ = ceremony
-                    .construct_intermediate_outcome(HardforkRegime::PostAllegretto)
-                    .await;
+                let _ = ceremony.construct_intermediate_outcome(regime).await;
             }
             epoch::RelativePosition::SecondHalf => {
-                let _ = ceremony
-                    .process_dealings_in_block(&block, HardforkRegime::PostAllegretto)
-                    .await;
+                let _ = ceremony.process_dealings_in_block(&block, regime).await;
             }
         }
 
@@ -433,6 +544,14 @@ where
             epoch: epoch_state.epoch(),
             dealers: epoch_state.dealer_pubkeys(),
             players: epoch_state.player_pubkeys(),
+            dealing_threshold: dealing_threshold(
+                epoch_state.player_pubkeys().len() as u32,
+                self.config
+                    .execution_node
+                    .chain_spec()
+                    .info
+                    .dkg_threshold_bps(),
+            ),
         };
         let ceremony = ceremony::Ceremony::init(
             &mut self.context,
@@ -445,7 +564,7 @@ where
         .expect("must always be able to initialize ceremony");
 
         info!(
-            us = %self.config.me,
+            us = %self.config.me.public_key(),
             n_dealers = ceremony.dealers().len(),
             dealers = ?ceremony.dealers(),
             n_players = ceremony.players().len(),
@@ -480,7 +599,8 @@ where
         let old_epoch_state = self
             .post_allegretto_metadatas
             .epoch_metadata
-            .remove(&CURRENT_EPOCH_KEY)
+            .take(&CURRENT_EPOCH_KEY)
+            .expect(STATE_READABLE)
             .expect("there must always exist an epoch state");
 
         // Remove it?
@@ -488,7 +608,7 @@ where
             .post_allegretto_metadatas
             .dkg_outcome_metadata
             .get(&DKG_OUTCOME_KEY)
-            .cloned()
+            .expect(STATE_READABLE)
             .expect(
                 "when updating the current epoch state, there must be a DKG \
                 outcome of some ceremony",
@@ -513,9 +633,26 @@ where
 
         let mut new_validator_state = old_epoch_state.validator_state.clone();
         if dkg_outcome.dkg_successful {
-            new_validator_state.push_on_success(syncing_players);
+            new_validator_state.push_on_success(syncing_players.clone());
         } else {
-            new_validator_state.push_on_failure(syncing_players);
+            new_validator_state.push_on_failure(syncing_players.clone());
+        }
+
+        // Validators whose next key took effect at this boundary take part in
+        // the next ceremony with their new key. Their old key remains a dealer
+        // and is retired once the ceremony succeeds.
+        for (old, new) in new_validator_state.rotate_player_keys(&syncing_players) {
+            if old == self.config.me.public_key() {
+                warn!(
+                    %old,
+                    %new,
+                    "the key of this node was rotated; restart the node with the new key to \
+                    receive its share in the next ceremony",
+                );
+            } else {
+                info!(%old, %new, "validator rotated its key; dealing to the new key");
+            }
+            self.metrics.key_rotations.inc();
         }
 
         self.post_allegretto_metadatas.epoch_metadata.put(
@@ -543,7 +680,8 @@ where
         let epoch_to_shutdown = if let Some(old_epoch_state) = self
             .post_allegretto_metadatas
             .epoch_metadata
-            .remove(&PREVIOUS_EPOCH_KEY)
+            .take(&PREVIOUS_EPOCH_KEY)
+            .expect(STATE_READABLE)
         {
             self.post_allegretto_metadatas
                 .epoch_metadata
@@ -581,70 +719,95 @@ where
 {
     /// Persisted information on the current epoch for DKG ceremonies that were
     /// started after the allegretto hardfork.
-    epoch_metadata: Metadata<TContext, U64, EpochState>,
+    ///
+    /// Encrypted if a keystore passphrase is set, since it holds the share of
+    /// this node.
+    epoch_metadata: ceremony::Store<TContext, EpochState>,
 
     /// The persisted DKG outcome. This is the result of latest DKG ceremony,
     /// constructed one height before the boundary height b (on b-1).
-    dkg_outcome_metadata: Metadata<TContext, U64, DkgOutcome>,
+    ///
+    /// Encrypted like the epoch state.
+    dkg_outcome_metadata: ceremony::Store<TContext, DkgOutcome>,
 }
 
 impl<TContext> Metadatas<TContext>
 where
     TContext: Clock + Metrics + Storage,
 {
-    pub(super) async fn init(context: &TContext, partition_prefix: &str) -> Self
+    /// Initializes the epoch states and DKG outcomes on disk.
+    ///
+    /// Fails if a persisted state cannot be read back, for example because it
+    /// was encrypted with a different keystore passphrase.
+    pub(super) async fn init(
+        context: &mut TContext,
+        partition_prefix: &str,
+        passphrase: Option<&Passphrase>,
+    ) -> eyre::Result<Self>
     where
-        TContext: Metrics,
+        TContext: CryptoRngCore,
     {
-        let epoch_metadata = Metadata::init(
+        let epoch_metadata = ceremony::Store::init(
             context.with_label("post_allegretto_epoch_metadata"),
-            commonware_storage::metadata::Config {
-                partition: format!("{partition_prefix}_post_allegretto_current_epoch"),
-                codec_config: (),
-            },
+            format!("{partition_prefix}_post_allegretto_current_epoch"),
+            passphrase,
+            context,
         )
         .await
-        .expect("must be able to initialize metadata on disk to function");
+        .wrap_err("failed initializing post-allegretto epoch state store")?;
+        for key in [CURRENT_EPOCH_KEY, PREVIOUS_EPOCH_KEY] {
+            epoch_metadata
+                .get(&key)
+                .wrap_err("failed reading post-allegretto epoch state")?;
+        }
 
-        let dkg_outcome_metadata = Metadata::init(
+        let dkg_outcome_metadata = ceremony::Store::init(
             context.with_label("dkg_outcome_metadata"),
-            commonware_storage::metadata::Config {
-                partition: format!("{partition_prefix}_next_dkg_outcome"),
-                codec_config: (),
-            },
+            format!("{partition_prefix}_next_dkg_outcome"),
+            passphrase,
+            context,
         )
         .await
-        .expect("must be able to initialize metadata on disk to function");
+        .wrap_err("failed initializing DKG outcome store")?;
+        dkg_outcome_metadata
+            .get(&DKG_OUTCOME_KEY)
+            .wrap_err("failed reading DKG outcome")?;
 
-        Self {
+        Ok(Self {
             epoch_metadata,
             dkg_outcome_metadata,
-        }
+        })
     }
 
-    pub(super) fn current_epoch_state(&self) -> Option<&EpochState> {
-        self.epoch_metadata.get(&CURRENT_EPOCH_KEY)
+    pub(super) fn current_epoch_state(&self) -> Option<EpochState> {
+        self.epoch_metadata
+            .get(&CURRENT_EPOCH_KEY)
+            .expect(STATE_READABLE)
     }
 
-    pub(super) fn previous_epoch_state(&self) -> Option<&EpochState> {
-        self.epoch_metadata.get(&PREVIOUS_EPOCH_KEY)
+    pub(super) fn previous_epoch_state(&self) -> Option<EpochState> {
+        self.epoch_metadata
+            .get(&PREVIOUS_EPOCH_KEY)
+            .expect(STATE_READABLE)
     }
 
     pub(super) fn dkg_outcome(&self) -> Option<PublicOutcome> {
-        if let Some(dkg_outcome) = self.dkg_outcome_metadata.get(&DKG_OUTCOME_KEY) {
+        if let Some(dkg_outcome) = self
+            .dkg_outcome_metadata
+            .get(&DKG_OUTCOME_KEY)
+            .expect(STATE_READABLE)
+        {
             Some(PublicOutcome {
                 epoch: dkg_outcome.epoch,
-                participants: dkg_outcome.participants.clone(),
-                public: dkg_outcome.public.clone(),
+                participants: dkg_outcome.participants,
+                public: dkg_outcome.public,
             })
         } else {
-            self.epoch_metadata
-                .get(&CURRENT_EPOCH_KEY)
-                .map(|epoch_state| PublicOutcome {
-                    epoch: epoch_state.dkg_outcome.epoch,
-                    participants: epoch_state.dkg_outcome.participants.clone(),
-                    public: epoch_state.dkg_outcome.public.clone(),
-                })
+            self.current_epoch_state().map(|epoch_state| PublicOutcome {
+                epoch: epoch_state.dkg_outcome.epoch,
+                participants: epoch_state.dkg_outcome.participants,
+                public: epoch_state.dkg_outcome.public,
+            })
         }
     }
 
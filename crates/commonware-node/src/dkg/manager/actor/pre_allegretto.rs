@@ -8,7 +8,6 @@ use commonware_cryptography::{
 };
 use commonware_p2p::{Receiver, Sender, utils::mux::MuxHandle};
 use commonware_runtime::{Clock, ContextCell, Metrics, Spawner, Storage};
-use commonware_storage::metadata::Metadata;
 use commonware_utils::{
     quorum,
     sequence::U64,
@@ -17,6 +16,7 @@ use commonware_utils::{
 use eyre::{OptionExt as _, WrapErr as _};
 use rand_core::CryptoRngCore;
 use tempo_chainspec::hardfork::TempoHardforks;
+use tempo_commonware_node_config::Passphrase;
 use tempo_dkg_onchain_artifacts::PublicOutcome;
 use tracing::{Span, info, instrument, warn};
 
@@ -33,6 +33,9 @@ use crate::{
 const CURRENT_EPOCH_KEY: U64 = U64::new(0);
 const PREVIOUS_EPOCH_KEY: U64 = U64::new(1);
 
+/// All epoch states on disk were read once when the store was initialized.
+const EPOCH_STATE_READABLE: &str = "epoch states on disk were checked to be readable on startup";
+
 impl<TContext, TPeerManager> super::Actor<TContext, TPeerManager>
 where
     TContext: Clock + CryptoRngCore + commonware_runtime::Metrics + Spawner + Storage,
@@ -69,8 +72,7 @@ where
 
             if self
                 .pre_allegretto_metadatas
-                .epoch_metadata
-                .get(&CURRENT_EPOCH_KEY)
+                .current_epoch_state()
                 .is_none()
             {
                 self.pre_allegretto_metadatas
@@ -94,8 +96,7 @@ where
             // Note that pre-allegretto the validator set never changes.
             let current_epoch = self
                 .pre_allegretto_metadatas
-                .epoch_metadata
-                .get(&CURRENT_EPOCH_KEY)
+                .current_epoch_state()
                 .expect("we ensured above that the epoch state is initialized")
                 .epoch();
             self.validators_metadata
@@ -230,7 +231,8 @@ where
             && let Some(old_epoch_state) = self
                 .pre_allegretto_metadatas
                 .epoch_metadata
-                .remove(&PREVIOUS_EPOCH_KEY)
+                .take(&PREVIOUS_EPOCH_KEY)
+                .expect(EPOCH_STATE_READABLE)
         {
             self.config
                 .epoch_manager
@@ -309,7 +311,8 @@ where
         let old_epoch_state = self
             .pre_allegretto_metadatas
             .epoch_metadata
-            .remove(&CURRENT_EPOCH_KEY)
+            .take(&CURRENT_EPOCH_KEY)
+            .expect(EPOCH_STATE_READABLE)
             .expect("there must always be a current epoch state");
 
         self.pre_allegretto_metadatas
@@ -351,8 +354,7 @@ where
     {
         let epoch_state = self
             .pre_allegretto_metadatas
-            .epoch_metadata
-            .get(&CURRENT_EPOCH_KEY)
+            .current_epoch_state()
             .expect("the epoch state must always during the lifetime of the actor");
         let config = ceremony::Config {
             namespace: self.config.namespace.clone(),
@@ -362,6 +364,7 @@ where
             epoch: epoch_state.epoch,
             dealers: epoch_state.participants.clone(),
             players: epoch_state.participants.clone(),
+            dealing_threshold: quorum(epoch_state.participants.len() as u32),
         };
 
         let ceremony = ceremony::Ceremony::init(
@@ -375,7 +378,7 @@ where
         .expect("must always be able to initialize ceremony");
 
         info!(
-            us = %self.config.me,
+            us = %self.config.me.public_key(),
             n_dealers = ceremony.dealers().len(),
             dealers = ?ceremony.dealers(),
             n_players = ceremony.players().len(),
@@ -397,16 +400,11 @@ where
         TReceiver: Receiver<PublicKey = PublicKey>,
         TSender: Sender<PublicKey = PublicKey>,
     {
-        let epoch_state = self
-            .pre_allegretto_metadatas
-            .epoch_metadata
-            .get(&CURRENT_EPOCH_KEY)
-            .cloned()
-            .expect(
-                "when transitioning from pre-allegretto static validator sets to \
+        let epoch_state = self.pre_allegretto_metadatas.current_epoch_state().expect(
+            "when transitioning from pre-allegretto static validator sets to \
                 post-allegretto dynamic validator sets the pre-allegretto epoch \
                 state must exist",
-            );
+        );
 
         self.transition_from_static_validator_sets(epoch_state, mux)
             .await
@@ -420,30 +418,45 @@ where
 {
     /// Persisted information on the current epoch for DKG ceremonies that were
     /// started after the allegretto hardfork.
-    epoch_metadata: Metadata<TContext, U64, EpochState>,
+    ///
+    /// Encrypted if a keystore passphrase is set, since it holds the share of
+    /// this node.
+    epoch_metadata: ceremony::Store<TContext, EpochState>,
 }
 
 impl<TContext> Metadatas<TContext>
 where
     TContext: Clock + Metrics + Storage,
 {
-    pub(super) async fn init(context: &TContext, partition_prefix: &str) -> Self
+    /// Initializes the epoch states on disk.
+    ///
+    /// Fails if a persisted epoch state cannot be read back, for example
+    /// because it was encrypted with a different keystore passphrase.
+    pub(super) async fn init(
+        context: &mut TContext,
+        partition_prefix: &str,
+        passphrase: Option<&Passphrase>,
+    ) -> eyre::Result<Self>
     where
-        TContext: Metrics,
+        TContext: CryptoRngCore,
     {
-        let epoch_metadata = Metadata::init(
+        let epoch_metadata = ceremony::Store::init(
             context.with_label("post_allegretto_epoch_metadata"),
-            commonware_storage::metadata::Config {
-                // XXX: the prefix of this partition must stay fixed to be
-                // backward compatible with the pre-allegretto hardfork.
-                partition: format!("{partition_prefix}_current_epoch"),
-                codec_config: (),
-            },
+            // XXX: the prefix of this partition must stay fixed to be
+            // backward compatible with the pre-allegretto hardfork.
+            format!("{partition_prefix}_current_epoch"),
+            passphrase,
+            context,
         )
         .await
-        .expect("must be able to initialize metadata on disk to function");
+        .wrap_err("failed initializing pre-allegretto epoch state store")?;
+        for key in [CURRENT_EPOCH_KEY, PREVIOUS_EPOCH_KEY] {
+            epoch_metadata
+                .get(&key)
+                .wrap_err("failed reading pre-allegretto epoch state")?;
+        }
 
-        Self { epoch_metadata }
+        Ok(Self { epoch_metadata })
     }
 
     pub(super) fn dkg_outcome(&self) -> Option<PublicOutcome> {
@@ -455,12 +468,16 @@ where
         })
     }
 
-    pub(super) fn previous_epoch_state(&self) -> Option<&EpochState> {
-        self.epoch_metadata.get(&PREVIOUS_EPOCH_KEY)
+    pub(super) fn previous_epoch_state(&self) -> Option<EpochState> {
+        self.epoch_metadata
+            .get(&PREVIOUS_EPOCH_KEY)
+            .expect(EPOCH_STATE_READABLE)
     }
 
-    pub(super) fn current_epoch_state(&self) -> Option<&EpochState> {
-        self.epoch_metadata.get(&CURRENT_EPOCH_KEY)
+    pub(super) fn current_epoch_state(&self) -> Option<EpochState> {
+        self.epoch_metadata
+            .get(&CURRENT_EPOCH_KEY)
+            .expect(EPOCH_STATE_READABLE)
     }
 
     /// Removes all pre-allegretto state from disk.
@@ -468,7 +485,10 @@ where
     /// Returns the current epoch state on the left-hand side, if it exists, and
     /// the previous epoch state on the right.
     async fn delete_current_epoch_state(&mut self) -> Option<EpochState> {
-        let current_state = self.epoch_metadata.remove(&CURRENT_EPOCH_KEY);
+        let current_state = self
+            .epoch_metadata
+            .take(&CURRENT_EPOCH_KEY)
+            .expect(EPOCH_STATE_READABLE);
         self.epoch_metadata
             .sync()
             .await
@@ -477,7 +497,10 @@ where
     }
 
     pub(super) async fn delete_previous_epoch_state(&mut self) -> Option<EpochState> {
-        let previous_state = self.epoch_metadata.remove(&PREVIOUS_EPOCH_KEY);
+        let previous_state = self
+            .epoch_metadata
+            .take(&PREVIOUS_EPOCH_KEY)
+            .expect(EPOCH_STATE_READABLE);
         self.epoch_metadata
             .sync()
             .await
@@ -1,8 +1,12 @@
+use std::net::SocketAddr;
+
 use commonware_consensus::{Reporter, marshal::Update, types::Epoch};
+use commonware_cryptography::ed25519::PublicKey;
 use commonware_utils::acknowledgement::Exact;
 use eyre::WrapErr as _;
 use futures::channel::{mpsc, oneshot};
 use tempo_dkg_onchain_artifacts::{IntermediateOutcome, PublicOutcome};
+use tempo_node::rpc::DkgStatus;
 use tracing::{Span, warn};
 
 use crate::consensus::block::Block;
@@ -41,6 +45,17 @@ impl Mailbox {
             .wrap_err("actor dropped channel before responding with ceremony deal outcome")
     }
 
+    /// Returns the progress of the running ceremony and the peers registered
+    /// for the latest epoch.
+    pub(crate) async fn get_status(&self) -> eyre::Result<Status> {
+        let (response, rx) = oneshot::channel();
+        self.inner
+            .unbounded_send(Message::in_current_span(GetStatus { response }))
+            .wrap_err("failed sending message to actor")?;
+        rx.await
+            .wrap_err("actor dropped channel before responding with its status")
+    }
+
     /// Verifies the `dealing` based on the current status of the DKG actor.
     ///
     /// This method is intended to be called by the application when verifying
@@ -52,14 +67,20 @@ impl Mailbox {
     ///    to be verified except for the last height.
     /// 2. DKG dealings are only written into a block up to and excluding the
     ///    last height of an epoch.
+    ///
+    /// `timestamp` is the timestamp of the proposed block, which determines
+    /// whether the dealing is checked against the ceremony's players and
+    /// dealing threshold (since Allegro-Moderato).
     pub(crate) async fn verify_intermediate_dealings(
         &self,
         dealing: IntermediateOutcome,
+        timestamp: u64,
     ) -> eyre::Result<bool> {
         let (response, rx) = oneshot::channel();
         self.inner
             .unbounded_send(Message::in_current_span(VerifyDealing {
                 dealing: dealing.into(),
+                timestamp,
                 response,
             }))
             .wrap_err("failed sending message to actor")?;
@@ -86,6 +107,7 @@ pub(super) enum Command {
     Finalize(Finalize),
     GetIntermediateDealing(GetIntermediateDealing),
     GetOutcome(GetOutcome),
+    GetStatus(GetStatus),
     VerifyDealing(VerifyDealing),
 }
 
@@ -113,6 +135,12 @@ impl From<GetOutcome> for Command {
     }
 }
 
+impl From<GetStatus> for Command {
+    fn from(value: GetStatus) -> Self {
+        Self::GetStatus(value)
+    }
+}
+
 pub(super) struct Finalize {
     pub(super) block: Box<Block>,
     pub(super) acknowledgment: Exact,
@@ -127,8 +155,23 @@ pub(super) struct GetOutcome {
     pub(super) response: oneshot::Sender<PublicOutcome>,
 }
 
+pub(super) struct GetStatus {
+    pub(super) response: oneshot::Sender<Status>,
+}
+
+/// A snapshot of the DKG manager's state.
+pub(crate) struct Status {
+    /// Progress of the running ceremony, or of the last ceremony if it was
+    /// already finalized. `None` if no ceremony was observed yet.
+    pub(crate) ceremony: Option<DkgStatus>,
+
+    /// The peers registered on the p2p peer manager for the latest epoch.
+    pub(crate) peers: Vec<(PublicKey, SocketAddr)>,
+}
+
 pub(super) struct VerifyDealing {
     pub(super) dealing: Box<IntermediateOutcome>,
+    pub(super) timestamp: u64,
     pub(super) response: oneshot::Sender<bool>,
 }
 
@@ -1,28 +1,27 @@
 use std::net::SocketAddr;
 
-use commonware_cryptography::{
-    bls12381::primitives::group::Share,
-    ed25519::{PrivateKey, PublicKey},
-};
+use commonware_cryptography::{bls12381::primitives::group::Share, ed25519::PublicKey};
 use commonware_runtime::{Clock, Metrics, Spawner, Storage};
 use commonware_utils::set::OrderedAssociated;
 use eyre::WrapErr as _;
 use futures::channel::mpsc;
 use rand_core::CryptoRngCore;
+use tempo_commonware_node_config::Passphrase;
 use tempo_node::TempoFullNode;
+use tempo_snapshot::Checkpoint;
 
 mod actor;
 mod ingress;
 mod validators;
 
 pub(crate) use actor::Actor;
-pub(crate) use ingress::Mailbox;
+pub(crate) use ingress::{Mailbox, Status};
 
 use validators::DecodedValidator;
 
 use ingress::{Command, Message};
 
-use crate::epoch;
+use crate::{epoch, signer::ConsensusSigner};
 
 pub(crate) async fn init<TContext, TPeerManager>(
     context: TContext,
@@ -51,7 +50,12 @@ pub(crate) struct Config<TPeerManager> {
     /// a dkg ceremony.
     pub(crate) namespace: Vec<u8>,
 
-    pub(crate) me: PrivateKey,
+    pub(crate) me: ConsensusSigner,
+
+    /// The passphrase to derive the key encrypting the ceremony states, epoch
+    /// states and DKG outcomes persisted to disk from. They hold the shares of
+    /// this node and are written in plain if unset.
+    pub(crate) state_passphrase: Option<Passphrase>,
 
     /// The number of heights per epoch.
     pub(crate) epoch_length: u64,
@@ -78,6 +82,11 @@ pub(crate) struct Config<TPeerManager> {
     /// This node's initial share of the bls12381 private key.
     pub(crate) initial_share: Option<Share>,
 
+    /// The finalized epoch boundary a state snapshot was imported at. If set
+    /// and no epoch state exists yet, the node starts in the epoch following
+    /// the boundary instead of at genesis.
+    pub(crate) checkpoint: Option<Checkpoint>,
+
     /// The peer manager on which the dkg actor will register new peers for a
     /// given epoch after reading them from the smart contract.
     pub(crate) peer_manager: TPeerManager,
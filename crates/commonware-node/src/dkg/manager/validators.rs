@@ -119,9 +119,20 @@ pub(super) async fn read_from_contract(
         let ctx = evm.ctx_mut();
         StorageCtx::enter_evm(&mut ctx.journaled_state, &ctx.block, &ctx.cfg, || {
             let validator_config = ValidatorConfig::new();
-            validator_config
+            let mut validators = validator_config
                 .get_validators()
-                .wrap_err("failed to query contract for validator config")
+                .wrap_err("failed to query contract for validator config")?;
+            // A pending next key takes effect at the first epoch boundary it
+            // is read at.
+            for validator in &mut validators {
+                let next = validator_config
+                    .get_next_public_key(validator.validatorAddress)
+                    .wrap_err("failed to query contract for next public key")?;
+                if !next.is_zero() {
+                    validator.publicKey = next;
+                }
+            }
+            Ok::<_, eyre::Report>(validators)
         })
     }?;
 
@@ -182,21 +193,42 @@ impl ValidatorState {
     ) -> Self {
         let validators = validators
             .iter_pairs()
-            .map(|(key, addr)| {
-                let key = key.clone();
-                let validator = DecodedValidator {
-                    public_key: key.clone(),
-                    inbound: *addr,
-                    outbound: SocketAddr::from(([0, 0, 0, 0], 0)),
-                    index: 0,
-                    address: Address::ZERO,
-                };
-                (key, validator)
-            })
+            .map(|(key, addr)| (key.clone(), DecodedValidator::unknown(key.clone(), *addr)))
             .collect();
         Self::new(validators)
     }
 
+    /// Returns the validator state of a node started from a snapshot taken at
+    /// the boundary of epoch `E`.
+    ///
+    /// `participants` hold the shares of epoch `E+1` and become its dealers.
+    /// `on_chain` is read from the contract at the boundary and is taken for
+    /// both the players and the syncing players, because the contract state
+    /// at the boundary of `E-1` is not part of the snapshot. Participants not
+    /// found in `on_chain` keep an unknown contract state.
+    pub(super) fn from_checkpoint(
+        participants: &Ordered<PublicKey>,
+        on_chain: OrderedAssociated<PublicKey, DecodedValidator>,
+    ) -> Self {
+        let dealers = participants
+            .iter()
+            .map(|key| {
+                let validator = on_chain
+                    .iter_pairs()
+                    .find_map(|(other, validator)| (other == key).then(|| validator.clone()))
+                    .unwrap_or_else(|| {
+                        DecodedValidator::unknown(key.clone(), SocketAddr::from(([0, 0, 0, 0], 0)))
+                    });
+                (key.clone(), validator)
+            })
+            .collect();
+        Self {
+            dealers,
+            players: on_chain.clone(),
+            syncing_players: on_chain,
+        }
+    }
+
     pub(super) fn dealers(&self) -> &OrderedAssociated<PublicKey, DecodedValidator> {
         &self.dealers
     }
@@ -241,6 +273,55 @@ impl ValidatorState {
             .collect()
     }
 
+    /// Replaces the keys of players that rotated their key on chain.
+    ///
+    /// A validator rotates its key by registering a next public key on its
+    /// contract entry, which `on_chain` carries in place of the current key
+    /// (see [`read_from_contract`]). Contract entries are recognized by their
+    /// `index`, which is stable across updates. If a player's entry now
+    /// carries a different key, the player is replaced by the new key so that
+    /// the new key is dealt a share in the next ceremony.
+    ///
+    /// The old key remains a dealer of that ceremony so that the share
+    /// indices of the dealers stay the same, but its dealing is not required:
+    /// the validator can switch its node over to the new key right away. The
+    /// old key is retired once the ceremony succeeds.
+    ///
+    /// Players that were not read from the contract (those without an
+    /// `address`) are never replaced.
+    ///
+    /// Returns the rotated keys as pairs of `(old, new)`.
+    pub(super) fn rotate_player_keys(
+        &mut self,
+        on_chain: &OrderedAssociated<PublicKey, DecodedValidator>,
+    ) -> Vec<(PublicKey, PublicKey)> {
+        let by_index = on_chain
+            .iter_pairs()
+            .map(|(_, validator)| (validator.index, validator))
+            .collect::<HashMap<_, _>>();
+
+        let mut rotations = Vec::new();
+        let players = self
+            .players
+            .iter_pairs()
+            .map(|(key, player)| {
+                if let Some(&next) = by_index.get(&player.index)
+                    && player.address != Address::ZERO
+                    && next.public_key != *key
+                    && on_chain.keys().position(key).is_none()
+                    && self.players.keys().position(&next.public_key).is_none()
+                {
+                    rotations.push((key.clone(), next.public_key.clone()));
+                    (next.public_key.clone(), next.clone())
+                } else {
+                    (key.clone(), player.clone())
+                }
+            })
+            .collect();
+        self.players = players;
+        rotations
+    }
+
     /// Pushes `syncing_players` into the participants queue.
     ///
     /// This method is called on successful DKG ceremonies: the current players
@@ -332,9 +413,9 @@ pub(super) struct DecodedValidator {
     /// all p2p communication is symmetric (outbound and inbound) via the
     /// `inboundAddress` field.
     pub(super) outbound: SocketAddr,
-    /// The `index` field of the contract. Not used by consensus and just here
-    /// for debugging purposes to identify the contract entry. Emitted in
-    /// tracing events.
+    /// The `index` field of the contract. Identifies the contract entry
+    /// across updates, which is used to recognize validators rotating their
+    /// key. Emitted in tracing events.
     pub(super) index: u64,
     /// The `address` field of the contract. Not used by consensus and just here
     /// for debugging purposes to identify the contract entry. Emitted in
@@ -343,6 +424,17 @@ pub(super) struct DecodedValidator {
 }
 
 impl DecodedValidator {
+    /// Returns a validator with only public key and inbound address set.
+    fn unknown(public_key: PublicKey, inbound: SocketAddr) -> Self {
+        Self {
+            public_key,
+            inbound,
+            outbound: SocketAddr::from(([0, 0, 0, 0], 0)),
+            index: 0,
+            address: Address::ZERO,
+        }
+    }
+
     /// Attempts to decode a single validator from the values read in the smart contract.
     ///
     /// This function does not perform hostname lookup on either of the addresses.
@@ -460,10 +552,17 @@ fn last_height_before_epoch(epoch: Epoch, epoch_length: u64) -> u64 {
 
 #[cfg(test)]
 mod tests {
+    use std::net::SocketAddr;
+
+    use alloy_primitives::Address;
     use commonware_codec::{DecodeExt as _, Encode as _};
-    use commonware_cryptography::{PrivateKeyExt, Signer, ed25519::PrivateKey};
+    use commonware_cryptography::{
+        PrivateKeyExt as _, Signer as _,
+        ed25519::{PrivateKey, PublicKey},
+    };
+    use commonware_utils::set::OrderedAssociated;
 
-    use crate::dkg::manager::DecodedValidator;
+    use super::{DecodedValidator, ValidatorState};
 
     #[test]
     fn roundtrip_decoded_validator() {
@@ -480,4 +579,70 @@ mod tests {
             DecodedValidator::decode(&mut decoded_validator.encode().freeze()).unwrap()
         );
     }
+
+    fn validator(seed: u64, index: u64) -> DecodedValidator {
+        DecodedValidator {
+            public_key: PrivateKey::from_seed(seed).public_key(),
+            inbound: ([127, 0, 0, 1], index as u16 + 1).into(),
+            outbound: ([127, 0, 0, 1], index as u16 + 1).into(),
+            index,
+            address: Address::with_last_byte(index as u8 + 1),
+        }
+    }
+
+    fn validators(
+        validators: impl IntoIterator<Item = DecodedValidator>,
+    ) -> OrderedAssociated<PublicKey, DecodedValidator> {
+        validators
+            .into_iter()
+            .map(|validator| (validator.public_key.clone(), validator))
+            .collect()
+    }
+
+    #[test]
+    fn rotated_key_replaces_player_and_retires_after_success() {
+        let initial = validators([validator(0, 0), validator(1, 1), validator(2, 2)]);
+        let mut state = ValidatorState::new(initial.clone());
+
+        // Validator 2 registers the key of seed 3 as its next key.
+        let on_chain = validators([validator(0, 0), validator(1, 1), validator(3, 2)]);
+        state.push_on_success(on_chain.clone());
+        let rotations = state.rotate_player_keys(&on_chain);
+
+        let old = validator(2, 2).public_key;
+        let new = validator(3, 2).public_key;
+        assert_eq!(rotations, vec![(old.clone(), new.clone())]);
+        assert!(state.dealer_pubkeys().position(&old).is_some());
+        assert!(state.player_pubkeys().position(&old).is_none());
+        assert!(state.player_pubkeys().position(&new).is_some());
+
+        state.push_on_success(on_chain);
+        assert!(state.dealer_pubkeys().position(&old).is_none());
+        assert!(
+            state
+                .resolve_addresses_and_merge_peers()
+                .keys()
+                .position(&old)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn players_without_contract_state_are_not_rotated() {
+        let mut state = ValidatorState::with_unknown_contract_state(
+            initial_peers([0, 1, 2]).into_iter().collect(),
+        );
+        let on_chain = validators([validator(0, 0), validator(1, 1), validator(3, 2)]);
+        assert!(state.rotate_player_keys(&on_chain).is_empty());
+    }
+
+    fn initial_peers(seeds: impl IntoIterator<Item = u64>) -> Vec<(PublicKey, SocketAddr)> {
+        seeds
+            .into_iter()
+            .map(|seed| {
+                let validator = validator(seed, seed);
+                (validator.public_key, validator.inbound)
+            })
+            .collect()
+    }
 }
@@ -5,4 +5,7 @@ pub(crate) mod manager;
 enum HardforkRegime {
     PreAllegretto,
     PostAllegretto,
+    /// Dealings are checked against the players of the ceremony and must be
+    /// acked by the chain's governed dealing threshold.
+    PostAllegroModerato,
 }
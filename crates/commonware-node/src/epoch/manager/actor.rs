@@ -50,11 +50,11 @@ use bytes::Bytes;
 use commonware_codec::{DecodeExt as _, Encode as _, varint::UInt};
 use commonware_consensus::{
     Reporters,
-    simplex::{self, signing_scheme::bls12381_threshold::Scheme, types::Voter},
+    simplex::{self, signing_scheme::Scheme as _, types::Voter},
     types::Epoch,
     utils,
 };
-use commonware_cryptography::{bls12381::primitives::variant::MinSig, ed25519::PublicKey};
+use commonware_cryptography::ed25519::PublicKey;
 use commonware_macros::select;
 use commonware_p2p::{
     Blocker, Receiver, Recipients, Sender,
@@ -71,7 +71,10 @@ use tracing::{Level, Span, error, error_span, info, instrument, warn, warn_span}
 
 use crate::{
     consensus::Digest,
-    epoch::manager::ingress::{Enter, Exit},
+    epoch::{
+        ConsensusScheme,
+        manager::ingress::{Enter, Exit},
+    },
 };
 
 use super::ingress::Message;
@@ -326,19 +329,23 @@ where
 
         let n_participants = participants.len();
         // Register the new signing scheme with the scheme provider.
-        let scheme = if let Some(share) = share {
-            info!("we have a share for this epoch, participating as a signer",);
-            Scheme::new(participants, &public, share)
+        let scheme = ConsensusScheme::new(
+            participants,
+            &public,
+            share,
+            self.config.remote_signer.clone(),
+        );
+        if scheme.me().is_some() {
+            info!("we have a share for this epoch, participating as a signer");
         } else {
-            info!("we don't have a share for this epoch, participating as a verifier",);
-            Scheme::verifier(participants, &public)
-        };
+            info!("we don't have a share for this epoch, participating as a verifier");
+        }
         assert!(
             self.config.scheme_provider.register(epoch, scheme.clone()),
             "a scheme must never be registered twice",
         );
 
-        let is_signer = matches!(scheme, Scheme::Signer { .. });
+        let is_signer = scheme.me().is_some();
 
         let engine = simplex::Engine::new(
             self.context.with_label("consensus_engine"),
@@ -348,8 +355,17 @@ where
                 automaton: self.config.application.clone(),
                 relay: self.config.application.clone(),
                 reporter: Reporters::from((
-                    self.config.subblocks.clone(),
-                    self.config.marshal.clone(),
+                    Reporters::from((
+                        Reporters::from((
+                            self.config.subblocks.clone(),
+                            self.config.decryption.clone(),
+                        )),
+                        self.config.marshal.clone(),
+                    )),
+                    Reporters::from((
+                        Reporters::from((self.config.status.clone(), self.config.evidence.clone())),
+                        self.config.prebuild.clone(),
+                    )),
                 )),
                 partition: format!(
                     "{partition_prefix}_consensus_epoch_{epoch}",
@@ -501,7 +517,7 @@ where
                     available locally; cannot serve request"
                 )
             })?;
-        let message = Voter::<Scheme<PublicKey, MinSig>, Digest>::Finalization(cert);
+        let message = Voter::<ConsensusScheme, Digest>::Finalization(cert);
         recovered_global_sender
             .send(
                 requested_epoch,
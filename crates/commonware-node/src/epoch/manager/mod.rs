@@ -4,15 +4,22 @@ pub(super) mod ingress;
 use std::time::Duration;
 
 pub(crate) use actor::Actor;
-use commonware_cryptography::{bls12381::primitives::variant::MinSig, ed25519::PublicKey};
+use commonware_cryptography::ed25519::PublicKey;
 pub(crate) use ingress::Mailbox;
 
-use commonware_consensus::{marshal, simplex::signing_scheme::bls12381_threshold::Scheme};
+use commonware_consensus::marshal;
 use commonware_p2p::Blocker;
 use commonware_runtime::{Clock, Metrics, Network, Spawner, Storage, buffer::PoolRef};
 use rand::{CryptoRng, Rng};
 
-use crate::{consensus::block::Block, epoch::scheme_provider::SchemeProvider, subblocks};
+use crate::{
+    consensus::{block::Block, prebuild::PrebuildReporter, status::StatusReporter},
+    decryption,
+    epoch::{ConsensusScheme, scheme_provider::SchemeProvider},
+    evidence,
+    signer::RemoteSigner,
+    subblocks,
+};
 
 pub(crate) struct Config<TBlocker> {
     pub(crate) application: crate::consensus::application::Mailbox,
@@ -23,8 +30,14 @@ pub(crate) struct Config<TBlocker> {
     pub(crate) time_to_propose: Duration,
     pub(crate) mailbox_size: usize,
     pub(crate) subblocks: subblocks::Mailbox,
-    pub(crate) marshal: marshal::Mailbox<Scheme<PublicKey, MinSig>, Block>,
+    pub(crate) decryption: decryption::Mailbox,
+    pub(crate) marshal: marshal::Mailbox<ConsensusScheme, Block>,
     pub(crate) scheme_provider: SchemeProvider,
+    /// The remote signer to sign votes with or to have record votes, if any.
+    pub(crate) remote_signer: Option<RemoteSigner>,
+    pub(crate) status: StatusReporter,
+    pub(crate) evidence: evidence::Mailbox,
+    pub(crate) prebuild: PrebuildReporter,
     pub(crate) time_to_collect_notarizations: Duration,
     pub(crate) time_to_retry_nullify_broadcast: Duration,
     pub(crate) partition_prefix: String,
@@ -7,10 +7,12 @@
 //! Note that either way, 3 blocks per epoch is a highly unreasonable number.
 
 pub(crate) mod manager;
+mod scheme;
 mod scheme_provider;
 
 use commonware_consensus::types::Epoch;
 pub(crate) use manager::ingress::{Enter, Exit};
+pub(crate) use scheme::ConsensusScheme;
 pub(crate) use scheme_provider::SchemeProvider;
 
 /// The relative position of in an epoch.
@@ -0,0 +1,403 @@
+//! The signing scheme of an epoch.
+
+use std::fmt;
+
+use commonware_codec::{DecodeExt as _, Encode as _, Read};
+use commonware_consensus::{
+    simplex::{
+        signing_scheme::{Scheme, Vote, VoteContext, VoteVerification, bls12381_threshold},
+        types::Proposal,
+    },
+    types::Round,
+};
+use commonware_cryptography::{
+    Digest,
+    bls12381::primitives::{
+        group::Share,
+        poly::Public,
+        variant::{MinSig, Variant},
+    },
+    ed25519::PublicKey,
+    sha256,
+};
+use commonware_utils::set::Ordered;
+use rand::{CryptoRng, Rng};
+use tracing::warn;
+
+use crate::signer::RemoteSigner;
+
+type Threshold = bls12381_threshold::Scheme<PublicKey, MinSig>;
+
+/// The bls12-381 threshold signing scheme of an epoch.
+///
+/// Votes are signed with the epoch's share if the node holds it, or by the
+/// remote signer if it holds the share instead. If a remote signer is
+/// configured, votes the node signs itself are recorded by the remote signer
+/// first, so that its slashing protection applies to every vote.
+///
+/// Everything but signing votes is delegated to the wrapped threshold scheme.
+#[derive(Clone)]
+pub(crate) struct ConsensusScheme {
+    inner: Threshold,
+    remote: Option<RemoteSigner>,
+
+    /// The index of the share held by the remote signer, if it is a share of
+    /// this epoch and the node does not hold one itself.
+    remote_share: Option<u32>,
+}
+
+impl ConsensusScheme {
+    /// Creates the scheme of an epoch with group polynomial `public`.
+    ///
+    /// The node signs as a validator if it holds `share` or if `remote` holds
+    /// a share of `public`. Otherwise it only verifies.
+    pub(crate) fn new(
+        participants: Ordered<PublicKey>,
+        public: &Public<MinSig>,
+        share: Option<Share>,
+        remote: Option<RemoteSigner>,
+    ) -> Self {
+        if let Some(share) = share {
+            return Self {
+                inner: Threshold::new(participants, public, share),
+                remote,
+                remote_share: None,
+            };
+        }
+        let remote_share = remote
+            .as_ref()
+            .and_then(RemoteSigner::share)
+            .filter(|share| {
+                (share.index as usize) < participants.len()
+                    && public.evaluate(share.index).value == share.value
+            })
+            .map(|share| share.index);
+        Self {
+            inner: Threshold::verifier(participants, public),
+            remote,
+            remote_share,
+        }
+    }
+
+    /// Returns the public key of the epoch's threshold signing key.
+    pub(crate) fn identity(&self) -> &<MinSig as Variant>::Public {
+        self.inner.identity()
+    }
+
+    /// Returns the epoch's share if it is held by the node.
+    pub(crate) fn share(&self) -> Option<&Share> {
+        self.inner.share()
+    }
+
+    /// Has the remote signer sign the vote of `context`.
+    fn sign_remotely<D: Digest>(
+        &self,
+        remote: &RemoteSigner,
+        index: u32,
+        namespace: &[u8],
+        context: VoteContext<'_, D>,
+    ) -> Option<Vote<Self>> {
+        let request = remote_vote(&context)?;
+        let (vote, seed) = match remote.sign_vote(namespace, &request) {
+            Ok(signatures) => signatures,
+            Err(error) => {
+                warn!(
+                    vote = ?request,
+                    error = %eyre::Report::new(error),
+                    "remote signer did not sign vote",
+                );
+                return None;
+            }
+        };
+        let vote = Vote {
+            signer: index,
+            signature: bls12381_threshold::Signature {
+                vote_signature: vote.value,
+                seed_signature: seed.value,
+            },
+        };
+        if !self.verify_vote(namespace, context, &vote) {
+            warn!(vote = ?request, "remote signer returned an invalid vote signature");
+            return None;
+        }
+        Some(vote)
+    }
+}
+
+impl fmt::Debug for ConsensusScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsensusScheme")
+            .field("inner", &self.inner)
+            .field("remote", &self.remote.is_some())
+            .field("remote_share", &self.remote_share)
+            .finish()
+    }
+}
+
+impl From<Threshold> for ConsensusScheme {
+    fn from(inner: Threshold) -> Self {
+        Self {
+            inner,
+            remote: None,
+            remote_share: None,
+        }
+    }
+}
+
+impl Scheme for ConsensusScheme {
+    type PublicKey = PublicKey;
+    type Signature = <Threshold as Scheme>::Signature;
+    type Certificate = <Threshold as Scheme>::Certificate;
+    type Seed = <Threshold as Scheme>::Seed;
+
+    fn me(&self) -> Option<u32> {
+        self.remote_share.or_else(|| self.inner.me())
+    }
+
+    fn participants(&self) -> &Ordered<Self::PublicKey> {
+        self.inner.participants()
+    }
+
+    fn sign_vote<D: Digest>(
+        &self,
+        namespace: &[u8],
+        context: VoteContext<'_, D>,
+    ) -> Option<Vote<Self>> {
+        if let (Some(remote), Some(index)) = (&self.remote, self.remote_share) {
+            return self.sign_remotely(remote, index, namespace, context);
+        }
+        if let Some(remote) = &self.remote
+            && self.inner.me().is_some()
+        {
+            let request = remote_vote(&context)?;
+            if let Err(error) = remote.record_vote(&request) {
+                warn!(
+                    vote = ?request,
+                    error = %eyre::Report::new(error),
+                    "remote signer did not record vote; not signing it",
+                );
+                return None;
+            }
+        }
+        self.inner.sign_vote(namespace, context).map(|vote| Vote {
+            signer: vote.signer,
+            signature: vote.signature,
+        })
+    }
+
+    fn verify_vote<D: Digest>(
+        &self,
+        namespace: &[u8],
+        context: VoteContext<'_, D>,
+        vote: &Vote<Self>,
+    ) -> bool {
+        self.inner.verify_vote(
+            namespace,
+            context,
+            &Vote {
+                signer: vote.signer,
+                signature: vote.signature.clone(),
+            },
+        )
+    }
+
+    fn verify_votes<R, D, I>(
+        &self,
+        rng: &mut R,
+        namespace: &[u8],
+        context: VoteContext<'_, D>,
+        votes: I,
+    ) -> VoteVerification<Self>
+    where
+        R: Rng + CryptoRng,
+        D: Digest,
+        I: IntoIterator<Item = Vote<Self>>,
+    {
+        let verification = self.inner.verify_votes(
+            rng,
+            namespace,
+            context,
+            votes.into_iter().map(|vote| Vote {
+                signer: vote.signer,
+                signature: vote.signature,
+            }),
+        );
+        VoteVerification::new(
+            verification
+                .verified
+                .into_iter()
+                .map(|vote| Vote {
+                    signer: vote.signer,
+                    signature: vote.signature,
+                })
+                .collect(),
+            verification.invalid_signers,
+        )
+    }
+
+    fn assemble_certificate<I>(&self, votes: I) -> Option<Self::Certificate>
+    where
+        I: IntoIterator<Item = Vote<Self>>,
+    {
+        self.inner
+            .assemble_certificate(votes.into_iter().map(|vote| Vote {
+                signer: vote.signer,
+                signature: vote.signature,
+            }))
+    }
+
+    fn verify_certificate<R: Rng + CryptoRng, D: Digest>(
+        &self,
+        rng: &mut R,
+        namespace: &[u8],
+        context: VoteContext<'_, D>,
+        certificate: &Self::Certificate,
+    ) -> bool {
+        self.inner
+            .verify_certificate(rng, namespace, context, certificate)
+    }
+
+    fn verify_certificates<'a, R, D, I>(
+        &self,
+        rng: &mut R,
+        namespace: &[u8],
+        certificates: I,
+    ) -> bool
+    where
+        R: Rng + CryptoRng,
+        D: Digest,
+        I: Iterator<Item = (VoteContext<'a, D>, &'a Self::Certificate)>,
+    {
+        self.inner.verify_certificates(rng, namespace, certificates)
+    }
+
+    fn seed(&self, round: Round, certificate: &Self::Certificate) -> Option<Self::Seed> {
+        self.inner.seed(round, certificate)
+    }
+
+    fn is_attributable(&self) -> bool {
+        self.inner.is_attributable()
+    }
+
+    fn certificate_codec_config(&self) -> <Self::Certificate as Read>::Cfg {
+        self.inner.certificate_codec_config()
+    }
+
+    fn certificate_codec_config_unbounded() -> <Self::Certificate as Read>::Cfg {
+        Threshold::certificate_codec_config_unbounded()
+    }
+}
+
+/// Returns the vote of `context` as sent to the remote signer.
+///
+/// Consensus digests are 32 bytes, the same as sha256 digests the remote
+/// signer records proposals with, and are encoded the same.
+fn remote_vote<D: Digest>(context: &VoteContext<'_, D>) -> Option<tempo_remote_signer::Vote> {
+    let proposal =
+        |proposal: &Proposal<D>| Proposal::<sha256::Digest>::decode(proposal.encode()).ok();
+    match context {
+        VoteContext::Notarize { proposal: notarize } => {
+            proposal(notarize).map(tempo_remote_signer::Vote::Notarize)
+        }
+        VoteContext::Nullify { round } => Some(tempo_remote_signer::Vote::Nullify(*round)),
+        VoteContext::Finalize { proposal: finalize } => {
+            proposal(finalize).map(tempo_remote_signer::Vote::Finalize)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use commonware_consensus::{
+        simplex::{
+            signing_scheme::{Scheme as _, VoteContext},
+            types::Proposal,
+        },
+        types::Round,
+    };
+    use commonware_cryptography::{
+        PrivateKeyExt as _, Signer as _,
+        bls12381::{dkg::ops::generate_shares, primitives::variant::MinSig},
+        ed25519::{PrivateKey, PublicKey},
+    };
+    use commonware_utils::{quorum, set::Ordered};
+    use rand::{SeedableRng as _, rngs::StdRng};
+
+    use super::{ConsensusScheme, remote_vote};
+    use crate::consensus::Digest;
+
+    fn participants() -> Ordered<PublicKey> {
+        (0..4)
+            .map(|seed| PrivateKey::from_seed(seed).public_key())
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn proposal() -> Proposal<Digest> {
+        Proposal::new(
+            Round::new(2, 5),
+            4,
+            Digest(alloy_primitives::B256::repeat_byte(7)),
+        )
+    }
+
+    #[test]
+    fn scheme_with_local_share_signs_votes() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (public, shares) = generate_shares::<_, MinSig>(&mut rng, None, 4, quorum(4));
+        let scheme = ConsensusScheme::new(participants(), &public, Some(shares[1].clone()), None);
+        let proposal = proposal();
+
+        assert_eq!(scheme.me(), Some(1));
+        let vote = scheme
+            .sign_vote(
+                b"test",
+                VoteContext::Notarize {
+                    proposal: &proposal,
+                },
+            )
+            .unwrap();
+        assert!(scheme.verify_vote(
+            b"test",
+            VoteContext::Notarize {
+                proposal: &proposal,
+            },
+            &vote,
+        ));
+    }
+
+    #[test]
+    fn scheme_without_share_only_verifies() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (public, _) = generate_shares::<_, MinSig>(&mut rng, None, 4, quorum(4));
+        let scheme = ConsensusScheme::new(participants(), &public, None, None);
+
+        assert_eq!(scheme.me(), None);
+        assert!(
+            scheme
+                .sign_vote(
+                    b"test",
+                    VoteContext::<Digest>::Nullify {
+                        round: Round::new(2, 5),
+                    },
+                )
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn votes_are_sent_to_remote_signer_with_same_encoding() {
+        let proposal = proposal();
+        let Some(tempo_remote_signer::Vote::Finalize(remote)) =
+            remote_vote(&VoteContext::Finalize {
+                proposal: &proposal,
+            })
+        else {
+            panic!("finalize votes must be sent as finalize votes");
+        };
+
+        assert_eq!(remote.round, proposal.round);
+        assert_eq!(remote.parent, proposal.parent);
+        assert_eq!(remote.payload.as_ref(), proposal.payload.as_ref());
+    }
+}
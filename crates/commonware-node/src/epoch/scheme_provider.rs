@@ -5,15 +5,13 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use commonware_consensus::{
-    marshal, simplex::signing_scheme::bls12381_threshold::Scheme, types::Epoch,
-};
-use commonware_cryptography::{bls12381::primitives::variant::MinSig, ed25519::PublicKey};
+use commonware_consensus::{marshal, types::Epoch};
+
+use super::ConsensusScheme;
 
 #[derive(Clone)]
-#[expect(clippy::type_complexity)]
 pub(crate) struct SchemeProvider {
-    inner: Arc<Mutex<HashMap<Epoch, Arc<Scheme<PublicKey, MinSig>>>>>,
+    inner: Arc<Mutex<HashMap<Epoch, Arc<ConsensusScheme>>>>,
 }
 
 impl SchemeProvider {
@@ -23,7 +21,7 @@ impl SchemeProvider {
         }
     }
 
-    pub(crate) fn register(&self, epoch: Epoch, scheme: Scheme<PublicKey, MinSig>) -> bool {
+    pub(crate) fn register(&self, epoch: Epoch, scheme: ConsensusScheme) -> bool {
         self.inner
             .lock()
             .unwrap()
@@ -37,7 +35,7 @@ impl SchemeProvider {
 }
 
 impl marshal::SchemeProvider for SchemeProvider {
-    type Scheme = Scheme<PublicKey, MinSig>;
+    type Scheme = ConsensusScheme;
 
     fn scheme(&self, epoch: Epoch) -> Option<Arc<Self::Scheme>> {
         self.inner.lock().unwrap().get(&epoch).cloned()
@@ -0,0 +1,537 @@
+use std::collections::HashSet;
+
+use alloy_primitives::B256;
+use bytes::Bytes;
+use commonware_codec::{
+    Encode as _, EncodeSize, RangeCfg, Read, ReadExt as _, Write, varint::UInt,
+};
+use commonware_consensus::{
+    Epochable as _, Viewable as _,
+    marshal::SchemeProvider as _,
+    simplex::{
+        signing_scheme::Scheme as _,
+        types::{Activity, Attributable as _},
+    },
+    types::{Epoch, View},
+};
+use commonware_cryptography::ed25519::PublicKey;
+use commonware_runtime::{Clock, ContextCell, Handle, Metrics, Spawner, Storage, spawn_cell};
+use commonware_storage::metadata::Metadata;
+use commonware_utils::sequence::U64;
+use eyre::eyre;
+use futures::{StreamExt as _, channel::mpsc};
+use prometheus_client::metrics::{counter::Counter, family::Family};
+use tempo_node::rpc::{ConsensusEvidence, EvidenceKind};
+use tracing::{Span, info, instrument, warn};
+
+use super::ingress::{GetEvidence, Message, Record};
+
+/// Upper bound on the size of an encoded activity.
+///
+/// Conflicts hold two votes of a few hundred bytes. Larger activities are not
+/// recorded and not read back from disk.
+const MAX_ACTIVITY_SIZE: usize = 4 * 1024;
+
+/// How many records are kept. Once exceeded, the oldest record is dropped.
+///
+/// All records are held in memory and rewritten on every sync, so this bounds
+/// both to `MAX_RECORDS * MAX_ACTIVITY_SIZE`.
+const MAX_RECORDS: u64 = 1_000;
+
+pub(crate) struct Actor<TContext>
+where
+    TContext: Clock + Metrics + Storage,
+{
+    /// The actor configuration passed in when constructing the actor.
+    config: super::Config,
+
+    /// The runtime context passed in when constructing the actor.
+    context: ContextCell<TContext>,
+
+    /// The channel over which the actor will receive messages.
+    mailbox: mpsc::UnboundedReceiver<Message>,
+
+    /// The latest [`MAX_RECORDS`] records. A record with index `i` is stored
+    /// at key `i % MAX_RECORDS`.
+    metadata: Metadata<ContextCell<TContext>, U64, Evidence>,
+
+    /// The index of the next record.
+    next: u64,
+
+    /// The conflicts already recorded. The consensus engines report the same
+    /// conflict again after replaying their journals on restart.
+    recorded: HashSet<(EvidenceKind, Epoch, View, PublicKey)>,
+
+    /// The number of conflicts recorded per offending validator and kind.
+    evidence_recorded: Family<Vec<(String, String)>, Counter>,
+}
+
+impl<TContext> Actor<TContext>
+where
+    TContext: Clock + Metrics + Spawner + Storage,
+{
+    pub(super) async fn new(
+        config: super::Config,
+        context: TContext,
+        mailbox: mpsc::UnboundedReceiver<Message>,
+    ) -> Self {
+        let context = ContextCell::new(context);
+
+        let metadata = Metadata::init(
+            context.with_label("metadata"),
+            commonware_storage::metadata::Config {
+                partition: format!("{}_records", config.partition_prefix),
+                codec_config: (),
+            },
+        )
+        .await
+        .expect("must be able to initialize metadata on disk to function");
+
+        let evidence_recorded = Family::default();
+        context.register(
+            "evidence_recorded",
+            "the number of conflicting messages recorded per offending validator",
+            evidence_recorded.clone(),
+        );
+
+        let mut this = Self {
+            config,
+            context,
+            mailbox,
+            metadata,
+            next: 0,
+            recorded: HashSet::new(),
+            evidence_recorded,
+        };
+
+        let mut records = 0;
+        for slot in 0..MAX_RECORDS {
+            let Some(evidence) = this.metadata.get(&slot.into()) else {
+                continue;
+            };
+            this.evidence_recorded
+                .get_or_create(&evidence.labels())
+                .inc();
+            this.recorded.insert(evidence.key());
+            this.next = this.next.max(evidence.index + 1);
+            records += 1;
+        }
+        info!(
+            records,
+            next = this.next,
+            "loaded byzantine evidence from disk"
+        );
+
+        this
+    }
+
+    pub(crate) fn start(mut self) -> Handle<()> {
+        spawn_cell!(self.context, self.run().await)
+    }
+
+    async fn run(mut self) {
+        while let Some(message) = self.mailbox.next().await {
+            let cause = message.cause;
+            match message.command {
+                super::ingress::Command::GetEvidence(get_evidence) => {
+                    let _: Result<_, _> = self.handle_get_evidence(cause, get_evidence);
+                }
+                super::ingress::Command::Record(record) => {
+                    self.handle_record(cause, record).await;
+                }
+            }
+        }
+    }
+
+    #[instrument(parent = &cause, skip_all, err)]
+    fn handle_get_evidence(
+        &self,
+        cause: Span,
+        GetEvidence {
+            from,
+            limit,
+            response,
+        }: GetEvidence,
+    ) -> eyre::Result<()> {
+        response
+            .send(self.page(from, limit))
+            .map_err(|_| eyre!("failed returning evidence because requester went away"))
+    }
+
+    /// Returns up to `limit` records starting at index `from`, oldest first.
+    fn page(&self, from: u64, limit: usize) -> Vec<ConsensusEvidence> {
+        let oldest = self.next.saturating_sub(MAX_RECORDS);
+        (from.max(oldest)..self.next)
+            .filter_map(|index| self.metadata.get(&(index % MAX_RECORDS).into()))
+            .map(Evidence::to_rpc)
+            .take(limit)
+            .collect()
+    }
+
+    /// Persists a conflict reported by a consensus engine.
+    #[instrument(
+        parent = &cause,
+        skip_all,
+        fields(
+            activity.epoch = activity.epoch(),
+            activity.view = activity.view(),
+        ),
+    )]
+    async fn handle_record(&mut self, cause: Span, Record { activity }: Record) {
+        let (kind, signer) = match activity.as_ref() {
+            Activity::ConflictingNotarize(conflict) => {
+                (EvidenceKind::ConflictingNotarize, conflict.signer())
+            }
+            Activity::ConflictingFinalize(conflict) => {
+                (EvidenceKind::ConflictingFinalize, conflict.signer())
+            }
+            Activity::NullifyFinalize(conflict) => {
+                (EvidenceKind::NullifyFinalize, conflict.signer())
+            }
+            _ => return,
+        };
+
+        let epoch = activity.epoch();
+        let Some(scheme) = self.config.scheme_provider.scheme(epoch) else {
+            warn!("scheme of epoch no longer known; cannot attribute conflict to a validator");
+            return;
+        };
+        let Some(validator) = scheme.participants().as_ref().get(signer as usize).cloned() else {
+            warn!(
+                signer,
+                "signer of conflicting messages is not a participant of the epoch"
+            );
+            return;
+        };
+
+        let evidence = Evidence {
+            index: self.next,
+            kind,
+            epoch,
+            view: activity.view(),
+            validator,
+            activity: activity.encode().freeze(),
+        };
+        self.store(evidence).await;
+    }
+
+    /// Persists `evidence` unless the same conflict was already recorded.
+    ///
+    /// Returns whether the evidence was stored.
+    async fn store(&mut self, evidence: Evidence) -> bool {
+        if evidence.activity.len() > MAX_ACTIVITY_SIZE {
+            warn!(
+                %evidence.validator,
+                ?evidence.kind,
+                size = evidence.activity.len(),
+                "conflicting messages exceed the maximum size; not recording evidence",
+            );
+            return false;
+        }
+        if !self.recorded.insert(evidence.key()) {
+            return false;
+        }
+
+        warn!(
+            %evidence.validator,
+            ?evidence.kind,
+            "validator signed conflicting messages; recording evidence",
+        );
+
+        self.evidence_recorded
+            .get_or_create(&evidence.labels())
+            .inc();
+
+        let slot = U64::from(evidence.index % MAX_RECORDS);
+        if let Some(evicted) = self.metadata.remove(&slot) {
+            self.recorded.remove(&evicted.key());
+        }
+        self.next = evidence.index + 1;
+        self.metadata.put(slot, evidence);
+        self.metadata
+            .sync()
+            .await
+            .expect("must be able to persist evidence to disk");
+        true
+    }
+}
+
+/// A conflict signed by a validator, as persisted on disk.
+#[derive(Clone, Debug)]
+struct Evidence {
+    /// The order in which the evidence was recorded.
+    index: u64,
+    kind: EvidenceKind,
+    epoch: Epoch,
+    view: View,
+    validator: PublicKey,
+
+    /// The encoded simplex activity holding the conflicting messages.
+    activity: Bytes,
+}
+
+impl Evidence {
+    fn key(&self) -> (EvidenceKind, Epoch, View, PublicKey) {
+        (self.kind, self.epoch, self.view, self.validator.clone())
+    }
+
+    fn labels(&self) -> Vec<(String, String)> {
+        vec![
+            ("validator".to_string(), self.validator.to_string()),
+            ("kind".to_string(), kind_label(self.kind).to_string()),
+        ]
+    }
+
+    fn to_rpc(&self) -> ConsensusEvidence {
+        ConsensusEvidence {
+            index: self.index,
+            kind: self.kind,
+            epoch: self.epoch,
+            view: self.view,
+            validator: B256::from_slice(self.validator.as_ref()),
+            evidence: self.activity.clone().into(),
+        }
+    }
+}
+
+fn kind_label(kind: EvidenceKind) -> &'static str {
+    match kind {
+        EvidenceKind::ConflictingNotarize => "conflicting_notarize",
+        EvidenceKind::ConflictingFinalize => "conflicting_finalize",
+        EvidenceKind::NullifyFinalize => "nullify_finalize",
+    }
+}
+
+fn kind_to_u8(kind: EvidenceKind) -> u8 {
+    match kind {
+        EvidenceKind::ConflictingNotarize => 0,
+        EvidenceKind::ConflictingFinalize => 1,
+        EvidenceKind::NullifyFinalize => 2,
+    }
+}
+
+fn kind_from_u8(tag: u8) -> Result<EvidenceKind, commonware_codec::Error> {
+    match tag {
+        0 => Ok(EvidenceKind::ConflictingNotarize),
+        1 => Ok(EvidenceKind::ConflictingFinalize),
+        2 => Ok(EvidenceKind::NullifyFinalize),
+        other => Err(commonware_codec::Error::InvalidEnum(other)),
+    }
+}
+
+impl Write for Evidence {
+    fn write(&self, buf: &mut impl bytes::BufMut) {
+        UInt(self.index).write(buf);
+        kind_to_u8(self.kind).write(buf);
+        UInt(self.epoch).write(buf);
+        UInt(self.view).write(buf);
+        self.validator.write(buf);
+        self.activity.write(buf);
+    }
+}
+
+impl EncodeSize for Evidence {
+    fn encode_size(&self) -> usize {
+        UInt(self.index).encode_size()
+            + kind_to_u8(self.kind).encode_size()
+            + UInt(self.epoch).encode_size()
+            + UInt(self.view).encode_size()
+            + self.validator.encode_size()
+            + self.activity.encode_size()
+    }
+}
+
+impl Read for Evidence {
+    type Cfg = ();
+
+    fn read_cfg(
+        buf: &mut impl bytes::Buf,
+        _cfg: &Self::Cfg,
+    ) -> Result<Self, commonware_codec::Error> {
+        let index = UInt::read(buf)?.into();
+        let kind = kind_from_u8(u8::read(buf)?)?;
+        let epoch = UInt::read(buf)?.into();
+        let view = UInt::read(buf)?.into();
+        let validator = PublicKey::read(buf)?;
+        let activity = Bytes::read_cfg(buf, &RangeCfg::from(0..=MAX_ACTIVITY_SIZE))?;
+        Ok(Self {
+            index,
+            kind,
+            epoch,
+            view,
+            validator,
+            activity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use commonware_codec::{DecodeExt as _, Encode as _};
+    use commonware_consensus::{
+        Reporter as _,
+        simplex::types::{ConflictingNotarize, Notarize, Proposal},
+        types::Round,
+    };
+    use commonware_cryptography::{
+        PrivateKeyExt as _, Signer as _,
+        bls12381::{dkg::ops::generate_shares, primitives::variant::MinSig},
+        ed25519::PrivateKey,
+    };
+    use commonware_runtime::{Runner as _, deterministic};
+    use commonware_utils::{quorum, set::Ordered};
+    use rand::{SeedableRng as _, rngs::StdRng};
+
+    use super::*;
+    use crate::{
+        consensus::Digest,
+        epoch::{ConsensusScheme, SchemeProvider},
+        evidence::Mailbox,
+    };
+
+    fn evidence(index: u64, view: View) -> Evidence {
+        Evidence {
+            index,
+            kind: EvidenceKind::ConflictingNotarize,
+            epoch: 0,
+            view,
+            validator: PrivateKey::from_seed(0).public_key(),
+            activity: Bytes::from_static(&[1, 2, 3, 4]),
+        }
+    }
+
+    /// Starts an actor on the `evidence` partition. Actors started with the
+    /// same context share their records.
+    async fn actor(context: &deterministic::Context, label: &str) -> Actor<deterministic::Context> {
+        let (_, rx) = mpsc::unbounded();
+        let config = crate::evidence::Config {
+            partition_prefix: "evidence".into(),
+            scheme_provider: SchemeProvider::new(),
+        };
+        Actor::new(config, context.with_label(label), rx).await
+    }
+
+    #[test]
+    fn roundtrip_evidence() {
+        let evidence = Evidence {
+            index: 7,
+            kind: EvidenceKind::NullifyFinalize,
+            epoch: 3,
+            view: 1_000,
+            validator: PrivateKey::from_seed(42).public_key(),
+            activity: Bytes::from_static(&[1, 2, 3, 4]),
+        };
+        let decoded = Evidence::decode(evidence.encode()).unwrap();
+        assert_eq!(decoded.index, evidence.index);
+        assert_eq!(decoded.key(), evidence.key());
+        assert_eq!(decoded.activity, evidence.activity);
+    }
+
+    #[test]
+    fn stores_paginates_and_reloads_evidence() {
+        deterministic::Runner::default().start(|context| async move {
+            let mut actor = actor(&context, "first").await;
+            for view in 0..3 {
+                assert!(actor.store(evidence(actor.next, view)).await);
+            }
+
+            // The same conflict is only recorded once
+            assert!(!actor.store(evidence(actor.next, 0)).await);
+
+            // Oversized activities are not recorded
+            let mut oversized = evidence(actor.next, 3);
+            oversized.activity = vec![0; MAX_ACTIVITY_SIZE + 1].into();
+            assert!(!actor.store(oversized).await);
+
+            let indices = |page: Vec<ConsensusEvidence>| {
+                page.iter()
+                    .map(|evidence| evidence.index)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(indices(actor.page(0, 10)), vec![0, 1, 2]);
+            assert_eq!(indices(actor.page(1, 1)), vec![1]);
+            assert_eq!(indices(actor.page(3, 10)), Vec::<u64>::new());
+
+            // Records, their order, and the known conflicts survive a restart
+            drop(actor);
+            let mut actor = actor(&context, "second").await;
+            assert_eq!(actor.next, 3);
+            assert_eq!(indices(actor.page(0, 10)), vec![0, 1, 2]);
+            assert!(!actor.store(evidence(actor.next, 2)).await);
+        });
+    }
+
+    #[test]
+    fn drops_the_oldest_evidence_beyond_capacity() {
+        deterministic::Runner::default().start(|context| async move {
+            let mut actor = actor(&context, "first").await;
+            for view in 0..MAX_RECORDS + 2 {
+                assert!(actor.store(evidence(actor.next, view)).await);
+            }
+
+            let page = actor.page(0, usize::MAX);
+            assert_eq!(page.len() as u64, MAX_RECORDS);
+            assert_eq!(page.first().unwrap().index, 2);
+            assert_eq!(page.last().unwrap().index, MAX_RECORDS + 1);
+            assert_eq!(actor.recorded.len() as u64, MAX_RECORDS);
+
+            drop(actor);
+            let actor = actor(&context, "second").await;
+            assert_eq!(actor.next, MAX_RECORDS + 2);
+            assert_eq!(actor.page(0, 1)[0].index, 2);
+        });
+    }
+
+    #[test]
+    fn records_conflicts_reported_by_consensus() {
+        deterministic::Runner::default().start(|context| async move {
+            let mut rng = StdRng::seed_from_u64(0);
+            let participants = (0..4)
+                .map(|i| PrivateKey::from_seed(i).public_key())
+                .collect::<Ordered<_>>();
+            let (public, shares) = generate_shares::<_, MinSig>(&mut rng, None, 4, quorum(4));
+            let scheme = || {
+                ConsensusScheme::new(participants.clone(), &public, Some(shares[1].clone()), None)
+            };
+            let scheme_provider = SchemeProvider::new();
+            scheme_provider.register(0, scheme());
+
+            let (tx, rx) = mpsc::unbounded();
+            let config = crate::evidence::Config {
+                partition_prefix: "evidence".into(),
+                scheme_provider,
+            };
+            let handle = Actor::new(config, context.clone(), rx).await.start();
+            let mut mailbox = Mailbox { inner: tx };
+
+            let notarize = |payload: u8| {
+                let proposal =
+                    Proposal::new(Round::new(0, 5), 4, Digest(B256::repeat_byte(payload)));
+                Notarize::sign(&scheme(), crate::config::NAMESPACE, proposal).unwrap()
+            };
+            let conflict = ConflictingNotarize::new(notarize(1), notarize(2));
+
+            // Activities that are not conflicts are ignored
+            mailbox.report(Activity::Notarize(notarize(1))).await;
+            mailbox
+                .report(Activity::ConflictingNotarize(conflict.clone()))
+                .await;
+            // Reported again when the engine replays its journal
+            mailbox
+                .report(Activity::ConflictingNotarize(conflict))
+                .await;
+
+            let recorded = mailbox.get_evidence(0, 10).await.unwrap();
+            assert_eq!(recorded.len(), 1);
+            assert_eq!(recorded[0].index, 0);
+            assert_eq!(recorded[0].kind, EvidenceKind::ConflictingNotarize);
+            assert_eq!((recorded[0].epoch, recorded[0].view), (0, 5));
+            assert_eq!(
+                recorded[0].validator,
+                B256::from_slice(participants.as_ref()[1].as_ref())
+            );
+
+            handle.abort();
+        });
+    }
+}
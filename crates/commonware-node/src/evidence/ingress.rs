@@ -0,0 +1,98 @@
+use commonware_consensus::{Reporter, simplex::types::Activity};
+use eyre::WrapErr as _;
+use futures::channel::{mpsc, oneshot};
+use tempo_node::rpc::ConsensusEvidence;
+use tracing::{Span, warn};
+
+use crate::{consensus::Digest, epoch::ConsensusScheme};
+
+#[derive(Clone, Debug)]
+pub(crate) struct Mailbox {
+    pub(super) inner: mpsc::UnboundedSender<Message>,
+}
+
+impl Mailbox {
+    /// Returns up to `limit` records of evidence starting at index `from`,
+    /// oldest first.
+    pub(crate) async fn get_evidence(
+        &self,
+        from: u64,
+        limit: usize,
+    ) -> eyre::Result<Vec<ConsensusEvidence>> {
+        let (response, rx) = oneshot::channel();
+        self.inner
+            .unbounded_send(Message::in_current_span(GetEvidence {
+                from,
+                limit,
+                response,
+            }))
+            .wrap_err("failed sending message to actor")?;
+        rx.await
+            .wrap_err("actor dropped channel before responding with evidence")
+    }
+}
+
+pub(super) struct Message {
+    pub(super) cause: Span,
+    pub(super) command: Command,
+}
+
+impl Message {
+    fn in_current_span(cmd: impl Into<Command>) -> Self {
+        Self {
+            cause: Span::current(),
+            command: cmd.into(),
+        }
+    }
+}
+
+pub(super) enum Command {
+    GetEvidence(GetEvidence),
+    Record(Record),
+}
+
+impl From<GetEvidence> for Command {
+    fn from(value: GetEvidence) -> Self {
+        Self::GetEvidence(value)
+    }
+}
+
+impl From<Record> for Command {
+    fn from(value: Record) -> Self {
+        Self::Record(value)
+    }
+}
+
+pub(super) struct GetEvidence {
+    pub(super) from: u64,
+    pub(super) limit: usize,
+    pub(super) response: oneshot::Sender<Vec<ConsensusEvidence>>,
+}
+
+pub(super) struct Record {
+    pub(super) activity: Box<Activity<ConsensusScheme, Digest>>,
+}
+
+impl Reporter for Mailbox {
+    type Activity = Activity<ConsensusScheme, Digest>;
+
+    async fn report(&mut self, activity: Self::Activity) {
+        if !matches!(
+            activity,
+            Activity::ConflictingNotarize(_)
+                | Activity::ConflictingFinalize(_)
+                | Activity::NullifyFinalize(_)
+        ) {
+            return;
+        }
+        if let Err(error) = self
+            .inner
+            .unbounded_send(Message::in_current_span(Record {
+                activity: Box::new(activity),
+            }))
+            .wrap_err("evidence actor no longer running")
+        {
+            warn!(%error, "failed to report byzantine evidence");
+        }
+    }
+}
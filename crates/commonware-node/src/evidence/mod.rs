@@ -0,0 +1,41 @@
+//! Records evidence of Byzantine behavior reported by the consensus engines.
+//!
+//! Threshold-simplex reports validators that sign conflicting messages in the
+//! same view: two notarize votes for different proposals, two finalize votes
+//! for different proposals, or a nullify and a finalize vote. The evidence
+//! actor persists each of these together with the offending validator's
+//! public key, exports a per-validator metric, and serves the evidence to the
+//! `consensus_getEvidence` RPC. Only the most recent records are kept.
+//!
+//! Every record contains both signed messages, so it can be verified by
+//! anyone knowing the public polynomial of the epoch.
+
+mod actor;
+mod ingress;
+
+pub(crate) use actor::Actor;
+pub(crate) use ingress::Mailbox;
+
+use commonware_runtime::{Clock, Metrics, Spawner, Storage};
+use futures::channel::mpsc;
+
+use crate::epoch::SchemeProvider;
+
+pub(crate) struct Config {
+    /// The partition prefix to use when persisting evidence.
+    pub(crate) partition_prefix: String,
+
+    /// Used to map the signer index of a conflicting vote to the validator's
+    /// public key.
+    pub(crate) scheme_provider: SchemeProvider,
+}
+
+pub(crate) async fn init<TContext>(context: TContext, config: Config) -> (Actor<TContext>, Mailbox)
+where
+    TContext: Clock + Metrics + Spawner + Storage,
+{
+    let (tx, rx) = mpsc::unbounded();
+    let actor = Actor::new(config, context, rx).await;
+    let mailbox = Mailbox { inner: tx };
+    (actor, mailbox)
+}
@@ -7,26 +7,34 @@ pub(crate) mod alias;
 mod args;
 pub(crate) mod config;
 pub mod consensus;
+pub(crate) mod decryption;
 pub(crate) mod dkg;
 pub(crate) mod epoch;
+pub(crate) mod evidence;
 pub mod metrics;
+pub(crate) mod peers;
 
+pub mod signer;
 pub(crate) mod subblocks;
 
 use std::net::SocketAddr;
 
-use commonware_cryptography::ed25519::{PrivateKey, PublicKey};
+use commonware_cryptography::{Signer as _, ed25519::PublicKey};
 use commonware_p2p::authenticated::lookup;
 use commonware_runtime::Metrics as _;
-use eyre::{OptionExt, WrapErr as _, eyre};
-use tempo_commonware_node_config::SigningShare;
-use tempo_node::TempoFullNode;
-
-use crate::config::{
-    BOUNDARY_CERT_CHANNEL_IDENT, BOUNDARY_CERT_LIMIT, BROADCASTER_CHANNEL_IDENT, BROADCASTER_LIMIT,
-    DKG_CHANNEL_IDENT, DKG_LIMIT, MARSHAL_CHANNEL_IDENT, MARSHAL_LIMIT, PEERSETS_TO_TRACK,
-    PENDING_CHANNEL_IDENT, PENDING_LIMIT, RECOVERED_CHANNEL_IDENT, RECOVERED_LIMIT,
-    RESOLVER_CHANNEL_IDENT, RESOLVER_LIMIT, SUBBLOCKS_CHANNEL_IDENT, SUBBLOCKS_LIMIT,
+use eyre::{OptionExt, WrapErr as _, ensure, eyre};
+use tempo_node::{PrebuildHandle, TempoFullNode, rpc::ConsensusHandle};
+use tempo_snapshot::Checkpoint;
+
+use crate::{
+    config::{
+        BOUNDARY_CERT_CHANNEL_IDENT, BOUNDARY_CERT_LIMIT, BROADCASTER_CHANNEL_IDENT,
+        BROADCASTER_LIMIT, DECRYPTION_CHANNEL_IDENT, DECRYPTION_LIMIT, DKG_CHANNEL_IDENT,
+        DKG_LIMIT, MARSHAL_CHANNEL_IDENT, MARSHAL_LIMIT, PEERSETS_TO_TRACK, PENDING_CHANNEL_IDENT,
+        PENDING_LIMIT, RECOVERED_CHANNEL_IDENT, RECOVERED_LIMIT, RESOLVER_CHANNEL_IDENT,
+        RESOLVER_LIMIT, SUBBLOCKS_CHANNEL_IDENT, SUBBLOCKS_LIMIT,
+    },
+    signer::RemoteSigner,
 };
 
 pub use args::Args;
@@ -35,28 +43,38 @@ pub async fn run_consensus_stack(
     context: &commonware_runtime::tokio::Context,
     config: Args,
     execution_node: TempoFullNode,
+    consensus_handle: ConsensusHandle,
+    prebuild: PrebuildHandle,
+    checkpoint: Option<Checkpoint>,
 ) -> eyre::Result<()> {
     let share = config
-        .signing_share
-        .as_ref()
-        .map(|share| {
-            SigningShare::read_from_file(share).wrap_err_with(|| {
-                format!(
-                    "failed reading private bls12-381 key share from file `{}`",
-                    share.display()
-                )
-            })
-        })
-        .transpose()?
+        .signing_share()?
         .map(|signing_share| signing_share.into_inner());
 
-    let signing_key = config
-        .signing_key()?
-        .ok_or_eyre("required option `consensus.signing-key` not set")?;
+    let signer = if let Some(remote_signer) = config.remote_signer()? {
+        let remote_signer =
+            RemoteSigner::connect(context.with_label("remote_signer"), remote_signer).await?;
+        let signer = ConsensusSigner::Remote(remote_signer);
+        let expected = config
+            .public_key()?
+            .ok_or_eyre("required option `consensus.validator-public-key` not set")?;
+        ensure!(
+            signer.public_key() == expected,
+            "remote signer holds signing key `{}`, but `{expected}` was expected",
+            signer.public_key(),
+        );
+        signer
+    } else {
+        config
+            .signing_key()?
+            .ok_or_eyre("required option `consensus.signing-key` not set")?
+            .into_inner()
+            .into()
+    };
 
     let (mut network, oracle) = instantiate_network(
         context,
-        signing_key.clone().into_inner(),
+        signer.clone(),
         config.listen_address,
         config.mailbox_size,
         config.max_message_size_bytes,
@@ -82,6 +100,7 @@ pub async fn run_consensus_stack(
         message_backlog,
     );
     let subblocks = network.register(SUBBLOCKS_CHANNEL_IDENT, SUBBLOCKS_LIMIT, message_backlog);
+    let decryption = network.register(DECRYPTION_CHANNEL_IDENT, DECRYPTION_LIMIT, message_backlog);
 
     let fee_recipient = config
         .fee_recipient
@@ -93,12 +112,15 @@ pub async fn run_consensus_stack(
         fee_recipient,
 
         execution_node: Some(execution_node),
+        prebuild,
         blocker: oracle.clone(),
         peer_manager: oracle.clone(),
         // TODO: Set this through config?
         partition_prefix: "engine".into(),
-        signer: signing_key.into_inner(),
+        signer,
         share,
+        keystore_passphrase: config.keystore_passphrase()?,
+        checkpoint,
         mailbox_size: config.mailbox_size,
         deque_size: config.deque_size,
 
@@ -132,11 +154,14 @@ pub async fn run_consensus_stack(
             "failed converting argument subblock-broadcast-interval to regular \
             duration; was it negative or chosen too large",
         )?,
+        subblock_partners: config.subblock_partners.into_iter().collect(),
     }
     .try_init()
     .await
     .wrap_err("failed initializing consensus engine")?;
 
+    consensus_engine.install_rpc(&consensus_handle);
+
     let (network, consensus_engine) = (
         network.start(),
         consensus_engine.start(
@@ -148,6 +173,7 @@ pub async fn run_consensus_stack(
             dkg,
             boundary_certificates,
             subblocks,
+            decryption,
         ),
     );
 
@@ -168,23 +194,24 @@ pub async fn run_consensus_stack(
 
 async fn instantiate_network(
     context: &commonware_runtime::tokio::Context,
-    signing_key: PrivateKey,
+    signer: ConsensusSigner,
     listen_addr: SocketAddr,
     mailbox_size: usize,
     max_message_size: usize,
     allow_unregistered_handshakes: bool,
 ) -> eyre::Result<(
-    lookup::Network<commonware_runtime::tokio::Context, PrivateKey>,
+    lookup::Network<commonware_runtime::tokio::Context, ConsensusSigner>,
     lookup::Oracle<PublicKey>,
 )> {
-    // TODO: Find out why `union_unique` should be used at all. This is the only place
-    // where `NAMESPACE` is used at all. We follow alto's example for now.
-    let p2p_namespace = commonware_utils::union_unique(crate::config::NAMESPACE, b"_P2P");
+    // TODO: Find out why `union_unique` should be used at all. We follow alto's
+    // example for now. The remote signer derives the same namespace to only sign
+    // handshakes under it.
+    let p2p_namespace = tempo_remote_signer::p2p_namespace(crate::config::NAMESPACE);
     let p2p_cfg = lookup::Config {
         mailbox_size,
         tracked_peer_sets: PEERSETS_TO_TRACK,
         attempt_unregistered_handshakes: allow_unregistered_handshakes,
-        ..lookup::Config::local(signing_key, &p2p_namespace, listen_addr, max_message_size)
+        ..lookup::Config::local(signer, &p2p_namespace, listen_addr, max_message_size)
     };
 
     Ok(lookup::Network::new(context.with_label("network"), p2p_cfg))
@@ -0,0 +1,127 @@
+//! Observes which p2p peers are actually reachable.
+//!
+//! Peers registered with the p2p network are only dialed; whether a connection was established
+//! is not exposed by the network. Instead, every message received on a consensus channel is
+//! recorded as a sign of life of its sender.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use commonware_cryptography::ed25519::PublicKey;
+use commonware_p2p::{Message, Receiver};
+use parking_lot::Mutex;
+
+/// Peers that sent a message within this duration are considered connected.
+///
+/// Active validators send votes every view, so this is a multiple of the expected view time.
+const CONNECTED_WITHIN: Duration = Duration::from_secs(30);
+
+/// The time the latest message of every peer was received.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PeerActivity(Arc<Mutex<HashMap<PublicKey, Instant>>>);
+
+impl PeerActivity {
+    /// Wraps the receiver of `channel` so that the senders of all messages received through it
+    /// are recorded.
+    pub(crate) fn track<S, R>(&self, (sender, receiver): (S, R)) -> (S, TrackedReceiver<R>)
+    where
+        R: Receiver<PublicKey = PublicKey>,
+    {
+        let receiver = TrackedReceiver {
+            inner: receiver,
+            activity: self.clone(),
+        };
+        (sender, receiver)
+    }
+
+    /// Returns whether `peer` sent a message recently.
+    pub(crate) fn is_connected(&self, peer: &PublicKey) -> bool {
+        self.0
+            .lock()
+            .get(peer)
+            .is_some_and(|seen| seen.elapsed() <= CONNECTED_WITHIN)
+    }
+
+    fn record(&self, peer: &PublicKey) {
+        self.0.lock().insert(peer.clone(), Instant::now());
+    }
+}
+
+/// A [`Receiver`] recording the activity of the peers it receives messages from.
+#[derive(Debug)]
+pub(crate) struct TrackedReceiver<R> {
+    inner: R,
+    activity: PeerActivity,
+}
+
+impl<R> Receiver for TrackedReceiver<R>
+where
+    R: Receiver<PublicKey = PublicKey>,
+{
+    type Error = R::Error;
+    type PublicKey = PublicKey;
+
+    async fn recv(&mut self) -> Result<Message<PublicKey>, Self::Error> {
+        let (peer, message) = self.inner.recv().await?;
+        self.activity.record(&peer);
+        Ok((peer, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use commonware_cryptography::{PrivateKeyExt as _, Signer as _, ed25519::PrivateKey};
+    use std::collections::VecDeque;
+
+    #[derive(Debug)]
+    struct MockReceiver(VecDeque<Message<PublicKey>>);
+
+    impl Receiver for MockReceiver {
+        type Error = std::io::Error;
+        type PublicKey = PublicKey;
+
+        async fn recv(&mut self) -> Result<Message<PublicKey>, Self::Error> {
+            self.0
+                .pop_front()
+                .ok_or_else(|| std::io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    #[tokio::test]
+    async fn records_senders_of_received_messages() {
+        let alice = PrivateKey::from_seed(0).public_key();
+        let bob = PrivateKey::from_seed(1).public_key();
+
+        let activity = PeerActivity::default();
+        let ((), mut receiver) = activity.track((
+            (),
+            MockReceiver([(alice.clone(), Bytes::from_static(b"vote"))].into()),
+        ));
+        assert!(!activity.is_connected(&alice));
+
+        let (sender, message) = receiver.recv().await.unwrap();
+        assert_eq!(sender, alice);
+        assert_eq!(message, Bytes::from_static(b"vote"));
+        assert!(activity.is_connected(&alice));
+        assert!(!activity.is_connected(&bob));
+
+        // Errors of the wrapped receiver are passed through
+        assert!(receiver.recv().await.is_err());
+    }
+
+    #[test]
+    fn forgets_peers_that_went_silent() {
+        let alice = PrivateKey::from_seed(0).public_key();
+        let activity = PeerActivity::default();
+        activity
+            .0
+            .lock()
+            .insert(alice.clone(), Instant::now() - CONNECTED_WITHIN * 2);
+        assert!(!activity.is_connected(&alice));
+    }
+}
@@ -0,0 +1,211 @@
+//! The keys the node signs with as a validator.
+//!
+//! The ed25519 key is either held by the node itself or by a remote signer
+//! (see [`tempo_remote_signer`]). It is used for the p2p handshake, to sign
+//! subblocks, and to sign DKG acks and dealings.
+//!
+//! The bls12-381 share of an epoch is signed with by the simplex signing
+//! scheme (see [`crate::epoch::ConsensusScheme`]). If the remote signer holds
+//! the share of an epoch, votes are signed by it; otherwise the node signs
+//! them, but has the remote signer record them first so that it never signs
+//! conflicting votes either way. Subblocks, DKG acks and dealing outcomes
+//! are skipped if the remote signer cannot be reached, while the p2p
+//! handshake waits for it to come back. Dealing a reshare during DKG ceremonies
+//! requires the share in full, so a node whose share is held by a remote
+//! signer takes part in ceremonies as a player only. The shares it receives
+//! are held by the node.
+
+use std::{fmt, future::Future, sync::Arc, time::Duration};
+
+use commonware_cryptography::{
+    Signer,
+    bls12381::primitives::{
+        poly::{Eval, PartialSignature},
+        variant::{MinSig, Variant},
+    },
+    ed25519::{PrivateKey, PublicKey, Signature},
+};
+use eyre::WrapErr as _;
+use tempo_remote_signer::{Client, ClientConfig, RemoteSignerError, Vote};
+use tracing::warn;
+
+/// How often to ask the remote signer before giving up on a request.
+const REMOTE_SIGNER_ATTEMPTS: usize = 3;
+
+/// How long to wait before asking the remote signer again after it failed.
+const REMOTE_SIGNER_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long to wait at most between two signing attempts through
+/// [`Signer::sign`], which backs off exponentially from
+/// [`REMOTE_SIGNER_RETRY_INTERVAL`].
+const REMOTE_SIGNER_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Signs with the validator's ed25519 key.
+#[derive(Clone)]
+pub enum ConsensusSigner {
+    /// The key is held by the node.
+    Local(PrivateKey),
+
+    /// The key is held by a remote signer.
+    Remote(RemoteSigner),
+}
+
+impl ConsensusSigner {
+    /// Signs `message`, failing instead of panicking if the remote signer
+    /// holding the key cannot be reached.
+    pub(crate) fn try_sign(
+        &self,
+        namespace: Option<&[u8]>,
+        message: &[u8],
+    ) -> eyre::Result<Signature> {
+        match self {
+            Self::Local(key) => Ok(key.sign(namespace, message)),
+            Self::Remote(remote) => remote
+                .sign(namespace, message)
+                .wrap_err("remote signer failed to sign"),
+        }
+    }
+
+    /// Returns the remote signer holding the key, if any.
+    pub(crate) fn remote(&self) -> Option<&RemoteSigner> {
+        match self {
+            Self::Local(_) => None,
+            Self::Remote(remote) => Some(remote),
+        }
+    }
+}
+
+impl From<PrivateKey> for ConsensusSigner {
+    fn from(value: PrivateKey) -> Self {
+        Self::Local(value)
+    }
+}
+
+impl fmt::Debug for ConsensusSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local(_) => f.debug_tuple("Local").field(&self.public_key()).finish(),
+            Self::Remote(_) => f.debug_tuple("Remote").field(&self.public_key()).finish(),
+        }
+    }
+}
+
+impl Signer for ConsensusSigner {
+    type Signature = Signature;
+    type PublicKey = PublicKey;
+
+    fn public_key(&self) -> Self::PublicKey {
+        match self {
+            Self::Local(key) => key.public_key(),
+            Self::Remote(remote) => remote.client.public_key().clone(),
+        }
+    }
+
+    /// Blocks until the remote signer holding the key, if any, responds,
+    /// backing off exponentially between attempts.
+    ///
+    /// The p2p handshake signs through this trait and cannot proceed without
+    /// a signature; use [`ConsensusSigner::try_sign`] where a failure can be
+    /// handled.
+    fn sign(&self, namespace: Option<&[u8]>, message: &[u8]) -> Self::Signature {
+        let mut retry_after = REMOTE_SIGNER_RETRY_INTERVAL;
+        loop {
+            match self.try_sign(namespace, message) {
+                Ok(signature) => return signature,
+                Err(error) => {
+                    warn!(
+                        %error,
+                        retry_after = %tempo_telemetry_util::display_duration(retry_after),
+                        "failed signing with the validator's key; will retry",
+                    );
+                    tokio::task::block_in_place(|| std::thread::sleep(retry_after));
+                    retry_after = (retry_after * 2).min(REMOTE_SIGNER_MAX_BACKOFF);
+                }
+            }
+        }
+    }
+}
+
+/// A connection to a remote signer holding the validator's keys.
+#[derive(Clone)]
+pub struct RemoteSigner {
+    client: Arc<Client<commonware_runtime::tokio::Context>>,
+}
+
+impl RemoteSigner {
+    /// Connects to the remote signer configured in `config`.
+    pub async fn connect(
+        context: commonware_runtime::tokio::Context,
+        config: ClientConfig,
+    ) -> eyre::Result<Self> {
+        let client = Client::connect(context, config)
+            .await
+            .wrap_err("failed connecting to remote signer")?;
+        Ok(Self {
+            client: Arc::new(client),
+        })
+    }
+
+    /// Returns the index and public key of the share held by the remote
+    /// signer, if any.
+    pub(crate) fn share(&self) -> Option<&Eval<<MinSig as Variant>::Public>> {
+        self.client.share()
+    }
+
+    /// Asks the remote signer for a signature, blocking the current thread.
+    pub(crate) fn sign(
+        &self,
+        namespace: Option<&[u8]>,
+        message: &[u8],
+    ) -> Result<Signature, RemoteSignerError> {
+        self.block_on(|| self.client.sign(namespace, message))
+    }
+
+    /// Asks the remote signer for partial signatures over `vote` and the seed
+    /// of its round, blocking the current thread.
+    pub(crate) fn sign_vote(
+        &self,
+        namespace: &[u8],
+        vote: &Vote,
+    ) -> Result<(PartialSignature<MinSig>, PartialSignature<MinSig>), RemoteSignerError> {
+        self.block_on(|| self.client.sign_vote(namespace, vote.clone()))
+    }
+
+    /// Asks the remote signer to record `vote` before the node signs it with
+    /// a share it holds itself, blocking the current thread.
+    pub(crate) fn record_vote(&self, vote: &Vote) -> Result<(), RemoteSignerError> {
+        self.block_on(|| self.client.record_vote(vote.clone()))
+    }
+
+    /// Sends the request made by `request` to the remote signer, blocking the
+    /// current thread until it responds.
+    ///
+    /// Requests that failed are sent again up to [`REMOTE_SIGNER_ATTEMPTS`]
+    /// times. Refusals are returned immediately.
+    fn block_on<T, F>(&self, request: impl Fn() -> F) -> Result<T, RemoteSignerError>
+    where
+        F: Future<Output = Result<T, RemoteSignerError>>,
+    {
+        tokio::task::block_in_place(|| {
+            let runtime = tokio::runtime::Handle::current();
+            let mut attempt = 1;
+            loop {
+                match runtime.block_on(request()) {
+                    Err(error)
+                        if attempt < REMOTE_SIGNER_ATTEMPTS
+                            && !matches!(error, RemoteSignerError::Refused(_)) =>
+                    {
+                        warn!(
+                            attempt,
+                            error = %eyre::Report::new(error),
+                            "request to remote signer failed; retrying",
+                        );
+                        attempt += 1;
+                        std::thread::sleep(REMOTE_SIGNER_RETRY_INTERVAL);
+                    }
+                    result => return result,
+                }
+            }
+        })
+    }
+}
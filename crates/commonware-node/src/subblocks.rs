@@ -1,4 +1,8 @@
-use crate::{consensus::Digest, epoch::SchemeProvider};
+use crate::{
+    consensus::Digest,
+    epoch::{ConsensusScheme, SchemeProvider},
+    signer::ConsensusSigner,
+};
 use alloy_consensus::{BlockHeader, Transaction, transaction::TxHashRef};
 use alloy_primitives::{Address, B256, BlockHash, Bytes, TxHash};
 use alloy_rlp::Decodable;
@@ -8,10 +12,7 @@ use commonware_consensus::{
     marshal::SchemeProvider as _,
     simplex::{
         select_leader,
-        signing_scheme::{
-            Scheme as _,
-            bls12381_threshold::{self, Scheme},
-        },
+        signing_scheme::{Scheme as _, bls12381_threshold},
         types::Activity,
     },
     types::Round,
@@ -20,14 +21,18 @@ use commonware_consensus::{
 use commonware_cryptography::{
     Signer, Verifier,
     bls12381::primitives::variant::MinSig,
-    ed25519::{PrivateKey, PublicKey, Signature},
+    ed25519::{PublicKey, Signature},
 };
 use commonware_p2p::{Receiver, Recipients, Sender};
 use commonware_runtime::{Handle, Metrics, Pacer, Spawner};
 use eyre::{Context, OptionExt};
-use futures::{FutureExt as _, StreamExt, channel::mpsc};
+use futures::{
+    FutureExt as _, StreamExt,
+    channel::{mpsc, oneshot},
+};
 use indexmap::IndexMap;
 use parking_lot::Mutex;
+use prometheus_client::metrics::{counter::Counter, family::Family};
 use reth_consensus_common::validation::MAX_RLP_BLOCK_SIZE;
 use reth_evm::{Evm, revm::database::State};
 use reth_node_builder::ConfigureEvm;
@@ -37,13 +42,23 @@ use reth_provider::{
 };
 use reth_revm::database::StateProviderDatabase;
 use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::Read as _,
     pin::Pin,
     sync::{Arc, mpsc::RecvError},
     time::{Duration, Instant},
 };
-use tempo_node::{TempoFullNode, consensus::TEMPO_SHARED_GAS_DIVISOR, evm::evm::TempoEvm};
+use tempo_chainspec::hardfork::TempoHardforks as _;
+use tempo_node::{
+    TempoFullNode,
+    consensus::TEMPO_SHARED_GAS_DIVISOR,
+    evm::evm::TempoEvm,
+    rpc::{SubblockTransactionStage, SubblockTransactionStatus},
+};
+use tempo_payload_types::SubblockDropReason;
 use tempo_primitives::{
-    RecoveredSubBlock, SignedSubBlock, SubBlock, SubBlockVersion, TempoTxEnvelope,
+    Block, RecoveredSubBlock, SignedSubBlock, SubBlock, SubBlockMetadata, SubBlockVersion,
+    TempoTxEnvelope,
 };
 use tokio::sync::broadcast;
 use tracing::{Instrument, Level, Span, debug, instrument, warn};
@@ -54,12 +69,34 @@ use tracing::{Instrument, Level, Span, debug, instrument, warn};
 /// because they become invalid once their nonce is used.
 const MAX_SUBBLOCK_TXS: usize = 100_000;
 
+/// Maximum number of subblock transactions whose progress is tracked for RPC.
+///
+/// The oldest transactions are forgotten first.
+const MAX_TRACKED_TXS: usize = 100_000;
+
+/// Tag prefixing compressed subblocks sent over the network.
+///
+/// Never the first byte of an RLP list, so uncompressed subblocks are told apart. Compressed
+/// subblocks are always longer than an acknowledgement because the parent hash does not compress.
+const COMPRESSED_SUBBLOCK_TAG: u8 = 0x02;
+
+/// zstd level used to compress subblocks.
+const SUBBLOCK_COMPRESSION_LEVEL: i32 = 3;
+
+/// Maximum size of a decompressed subblock.
+///
+/// The transactions of a subblock are bounded by `MAX_RLP_BLOCK_SIZE / TEMPO_SHARED_GAS_DIVISOR`
+/// when there is a single validator; the rest leaves room for the per-transaction fee recipients.
+const MAX_DECOMPRESSED_SUBBLOCK_SIZE: usize =
+    2 * MAX_RLP_BLOCK_SIZE / TEMPO_SHARED_GAS_DIVISOR as usize;
+
 pub(crate) struct Config<TContext> {
     pub(crate) context: TContext,
-    pub(crate) signer: PrivateKey,
+    pub(crate) signer: ConsensusSigner,
     pub(crate) scheme_provider: SchemeProvider,
     pub(crate) node: TempoFullNode,
     pub(crate) fee_recipient: Address,
+    pub(crate) partners: HashMap<Address, Address>,
     pub(crate) time_to_build_subblock: Duration,
     pub(crate) subblock_broadcast_interval: Duration,
     pub(crate) epoch_length: u64,
@@ -90,11 +127,14 @@ pub(crate) struct Actor<TContext> {
     /// Commonware runtime context.
     context: TContext,
     /// ed25519 private key used for consensus.
-    signer: PrivateKey,
+    signer: ConsensusSigner,
     /// Execution layer node.
     node: TempoFullNode,
     /// Fee recipient address to set for subblocks.
     fee_recipient: Address,
+    /// Fee recipients of the partners whose transactions are included in our subblocks,
+    /// keyed by transaction sender.
+    partners: Arc<HashMap<Address, Address>>,
     /// Timeout for building a subblock.
     time_to_build_subblock: Duration,
     /// How often to broadcast subblocks to the current proposer.
@@ -109,6 +149,16 @@ pub(crate) struct Actor<TContext> {
     subblocks: IndexMap<B256, RecoveredSubBlock>,
     /// Subblock candidate transactions.
     subblock_transactions: Arc<Mutex<IndexMap<TxHash, Arc<Recovered<TempoTxEnvelope>>>>>,
+
+    /// Progress of the subblock transactions seen by this node.
+    tracked_transactions: TrackedTransactions,
+    /// Subblocks reported as dropped by the payload builder, keyed by validator and parent.
+    ///
+    /// The payload is rebuilt several times for the same parent, so the same subblock is
+    /// usually reported more than once.
+    dropped_subblocks: HashSet<(B256, BlockHash)>,
+    /// Subblock metrics labelled by the validator that built the subblock.
+    metrics: ValidatorMetrics,
 }
 
 impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
@@ -119,12 +169,14 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
             scheme_provider,
             node,
             fee_recipient,
+            partners,
             time_to_build_subblock,
             subblock_broadcast_interval,
             epoch_length,
         }: Config<TContext>,
     ) -> Self {
         let (actions_tx, actions_rx) = mpsc::unbounded();
+        let metrics = ValidatorMetrics::register(&context);
         Self {
             our_subblock: PendingSubblock::None,
             subblock_transactions_rx: node.add_ons_handle.eth_api().subblock_transactions_rx(),
@@ -135,12 +187,16 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
             signer,
             node,
             fee_recipient,
+            partners: Arc::new(partners),
             time_to_build_subblock,
             subblock_broadcast_interval,
             epoch_length,
             consensus_tip: None,
             subblocks: Default::default(),
             subblock_transactions: Default::default(),
+            tracked_transactions: Default::default(),
+            dropped_subblocks: Default::default(),
+            metrics,
         }
     }
 
@@ -220,11 +276,20 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
             }
             Message::Consensus(activity) => self.on_consensus_event(*activity),
             Message::ValidatedSubblock(subblock) => self.on_validated_subblock(subblock),
+            Message::DroppedSubblock {
+                validator,
+                parent,
+                transactions,
+                reason,
+            } => self.on_dropped_subblock(validator, parent, transactions, reason),
+            Message::TransactionStatus { tx_hash, response } => {
+                let _ = response.send(self.tracked_transactions.get(&tx_hash).cloned());
+            }
         }
     }
 
     #[instrument(skip_all, fields(transaction.tx_hash = %transaction.tx_hash()))]
-    fn on_new_subblock_transaction(&self, transaction: Recovered<TempoTxEnvelope>) {
+    fn on_new_subblock_transaction(&mut self, transaction: Recovered<TempoTxEnvelope>) {
         if !transaction
             .subblock_proposer()
             .is_some_and(|k| k.matches(self.signer.public_key()))
@@ -235,12 +300,17 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
         if txs.len() >= MAX_SUBBLOCK_TXS {
             return;
         }
-        txs.insert(*transaction.tx_hash(), Arc::new(transaction));
+        let tx_hash = *transaction.tx_hash();
+        txs.insert(tx_hash, Arc::new(transaction));
+        drop(txs);
+
+        self.tracked_transactions
+            .track(tx_hash, B256::from_slice(&self.signer.public_key()));
     }
 
     /// Tracking of the current sconsensus state by listening to notarizations and nullifications.
     #[instrument(skip_all, fields(event.epoch = event.epoch(), event.view = event.view()))]
-    fn on_consensus_event(&mut self, event: Activity<Scheme<PublicKey, MinSig>, Digest>) {
+    fn on_consensus_event(&mut self, event: Activity<ConsensusScheme, Digest>) {
         let (new_tip, new_round, new_cert) = match event {
             Activity::Notarization(n) => {
                 (Some(n.proposal.payload.0), n.proposal.round, n.certificate)
@@ -252,6 +322,7 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
             _ => return,
         };
 
+        let mut tip_changed = false;
         if let Some((round, tip, cert)) = &mut self.consensus_tip
             && *round <= new_round
         {
@@ -263,28 +334,31 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
             {
                 // Clear collected subblocks if we have a new tip.
                 self.subblocks.clear();
+                self.dropped_subblocks.clear();
                 *tip = new_tip;
+                tip_changed = true;
             }
         } else if self.consensus_tip.is_none()
             && let Some(new_tip) = new_tip
         {
             // Initialize consensus tip once we know the tip block hash.
             self.consensus_tip = Some((new_round, new_tip, new_cert));
+            tip_changed = true;
         }
 
-        let Some((round, tip, certificate)) = &self.consensus_tip else {
+        let Some((round, tip, certificate)) = self.consensus_tip.clone() else {
             return;
         };
 
-        let Ok(Some(header)) = self
-            .node
-            .provider
-            .find_block_by_hash(*tip, BlockSource::Any)
-        else {
+        let Ok(Some(header)) = self.node.provider.find_block_by_hash(tip, BlockSource::Any) else {
             debug!(?tip, "missing header for the tip block at {tip}");
             return;
         };
 
+        if tip_changed {
+            self.on_new_tip_block(tip, &header);
+        }
+
         let epoch_of_next_block = utils::epoch(self.epoch_length, header.number() + 1);
 
         // Can't proceed without knowing a validator set for the current epoch.
@@ -303,37 +377,63 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
             // First view does not have a seed.
             None
         } else {
-            scheme.seed(*round, certificate)
+            scheme.seed(round, &certificate)
         };
 
-        let (next_proposer, _) = select_leader::<Scheme<PublicKey, MinSig>, _>(
-            scheme.participants().as_ref(),
-            next_round,
-            seed,
-        );
+        let (next_proposer, _) =
+            select_leader::<ConsensusScheme, _>(scheme.participants().as_ref(), next_round, seed);
 
         debug!(?next_proposer, ?next_round, "determined next proposer");
 
         // Spawn new subblock building task if the current one is assuming different proposer or parent hash.
-        if self.our_subblock.parent_hash() != Some(*tip)
+        if self.our_subblock.parent_hash() != Some(tip)
             || self.our_subblock.target_proposer() != Some(&next_proposer)
         {
             debug!(%tip, %next_proposer, "building new subblock");
-            self.build_new_subblock(*tip, next_proposer, scheme);
+            self.build_new_subblock(tip, next_proposer, scheme);
         }
     }
 
+    /// Records which tracked transactions made it into the new tip `block`.
+    ///
+    /// Transactions whose subblock was built on the parent of `block` but are not in it
+    /// were left out by the proposer.
+    fn on_new_tip_block(&mut self, hash: BlockHash, block: &Block) {
+        let included_validators = block
+            .body
+            .transactions
+            .iter()
+            .rev()
+            .filter(|tx| tx.to() == Some(Address::ZERO))
+            .find_map(|tx| Vec::<SubBlockMetadata>::decode(&mut tx.input().as_ref()).ok())
+            .unwrap_or_default();
+        for metadata in included_validators {
+            self.metrics.included(metadata.validator).inc();
+        }
+
+        for tx in &block.body.transactions {
+            if let Some(status) = self.tracked_transactions.get_mut(tx.tx_hash()) {
+                status.stage = SubblockTransactionStage::Included;
+                status.block_number = Some(block.number());
+                status.block_hash = Some(hash);
+            }
+        }
+
+        self.tracked_transactions.not_included(block.parent_hash());
+    }
+
     fn build_new_subblock(
         &mut self,
         parent_hash: BlockHash,
         next_proposer: PublicKey,
-        scheme: Arc<Scheme<PublicKey, MinSig>>,
+        scheme: Arc<ConsensusScheme>,
     ) {
         let transactions = self.subblock_transactions.clone();
         let node = self.node.clone();
         let num_validators = scheme.participants().len();
         let signer = self.signer.clone();
         let fee_recipient = self.fee_recipient;
+        let partners = self.partners.clone();
         let timeout = self.time_to_build_subblock;
         let span = Span::current();
         let handle = self
@@ -348,6 +448,7 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
                     num_validators,
                     signer,
                     fee_recipient,
+                    partners,
                     timeout,
                 )
                 .instrument(span)
@@ -380,6 +481,7 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
                 {
                     debug!("received acknowledgement from the next proposer");
                     built.stop_broadcasting();
+                    self.tracked_transactions.reached_proposer(&built.subblock);
                 } else {
                     warn!(%ack, "received invalid acknowledgement");
                 }
@@ -444,16 +546,63 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
 
         debug!(subblock = ?subblock, "validated subblock");
 
+        // The sender rebroadcasts its subblock until it receives our acknowledgement.
+        if self
+            .subblocks
+            .get(&subblock.validator())
+            .is_none_or(|known| known.signature_hash() != subblock.signature_hash())
+        {
+            self.metrics.received(subblock.validator()).inc();
+        }
+
+        self.tracked_transactions.in_subblock(
+            &subblock,
+            B256::from_slice(&self.signer.public_key()),
+            true,
+        );
         self.subblocks.insert(subblock.validator(), subblock);
     }
 
+    #[instrument(skip_all, fields(%validator, %parent, ?reason))]
+    fn on_dropped_subblock(
+        &mut self,
+        validator: B256,
+        parent: BlockHash,
+        transactions: Vec<TxHash>,
+        reason: SubblockDropReason,
+    ) {
+        if !self.dropped_subblocks.insert((validator, parent)) {
+            return;
+        }
+
+        debug!("payload builder dropped subblock");
+
+        let stage = match reason {
+            SubblockDropReason::Expired => {
+                self.metrics.dropped_expired(validator).inc();
+                SubblockTransactionStage::DroppedExpired
+            }
+            SubblockDropReason::AfterInvalidSubblock => {
+                self.metrics.discarded_invalid(validator).inc();
+                SubblockTransactionStage::DiscardedInvalidSubblock
+            }
+        };
+        for tx_hash in transactions {
+            if let Some(status) = self.tracked_transactions.get_mut(&tx_hash)
+                && status.parent_hash == Some(parent)
+            {
+                status.stage = stage;
+            }
+        }
+    }
+
     #[instrument(skip_all)]
     async fn on_built_subblock(
         &mut self,
-        subblock: Result<RecoveredSubBlock, commonware_runtime::Error>,
+        subblock: Result<eyre::Result<RecoveredSubBlock>, commonware_runtime::Error>,
         next_proposer: PublicKey,
     ) {
-        let subblock = match subblock {
+        let subblock = match subblock.map_err(eyre::Report::new).and_then(|built| built) {
             Ok(subblock) => subblock,
             Err(error) => {
                 warn!(%error, "failed to build subblock");
@@ -465,6 +614,9 @@ impl<TContext: Spawner + Metrics + Pacer> Actor<TContext> {
             return;
         }
 
+        self.tracked_transactions
+            .in_subblock(&subblock, B256::from_slice(&next_proposer), false);
+
         self.our_subblock = PendingSubblock::Built(BuiltSubblock {
             subblock,
             proposer: next_proposer,
@@ -522,10 +674,216 @@ enum Message {
     },
 
     /// Reports a new consensus event.
-    Consensus(Box<Activity<Scheme<PublicKey, MinSig>, Digest>>),
+    Consensus(Box<Activity<ConsensusScheme, Digest>>),
 
     /// Reports a new validated subblock.
     ValidatedSubblock(RecoveredSubBlock),
+
+    /// Reports a subblock that the payload builder left out of a payload.
+    DroppedSubblock {
+        /// Validator that built the subblock.
+        validator: B256,
+        /// Parent block of the subblock.
+        parent: BlockHash,
+        /// Hashes of the subblock's transactions.
+        transactions: Vec<TxHash>,
+        /// Why the subblock was left out.
+        reason: SubblockDropReason,
+    },
+
+    /// Returns the progress of a tracked subblock transaction.
+    TransactionStatus {
+        /// Hash of the transaction.
+        tx_hash: TxHash,
+        /// Response channel.
+        response: oneshot::Sender<Option<SubblockTransactionStatus>>,
+    },
+}
+
+/// Progress of subblock transactions, bounded by [`MAX_TRACKED_TXS`].
+#[derive(Default)]
+struct TrackedTransactions {
+    /// Progress of every tracked transaction.
+    statuses: HashMap<TxHash, SubblockTransactionStatus>,
+    /// Tracked transactions in the order they were first tracked, oldest first.
+    order: VecDeque<TxHash>,
+    /// Transactions put in a subblock, keyed by the parent of the subblock.
+    ///
+    /// May hold transactions that moved on to another stage since, which are skipped when the
+    /// block on top of the parent is seen.
+    by_parent: HashMap<BlockHash, HashSet<TxHash>>,
+}
+
+impl TrackedTransactions {
+    fn get(&self, tx_hash: &TxHash) -> Option<&SubblockTransactionStatus> {
+        self.statuses.get(tx_hash)
+    }
+
+    fn get_mut(&mut self, tx_hash: &TxHash) -> Option<&mut SubblockTransactionStatus> {
+        self.statuses.get_mut(tx_hash)
+    }
+
+    /// Starts tracking `tx_hash` for the subblocks of `validator`, forgetting the oldest
+    /// transaction if at capacity.
+    fn track(&mut self, tx_hash: TxHash, validator: B256) -> &mut SubblockTransactionStatus {
+        if !self.statuses.contains_key(&tx_hash) {
+            if self.statuses.len() >= MAX_TRACKED_TXS
+                && let Some(oldest) = self.order.pop_front()
+                && let Some(status) = self.statuses.remove(&oldest)
+            {
+                self.forget_parent(&oldest, status.parent_hash);
+            }
+            self.order.push_back(tx_hash);
+        }
+        self.statuses
+            .entry(tx_hash)
+            .or_insert_with(|| SubblockTransactionStatus {
+                validator,
+                parent_hash: None,
+                proposer: None,
+                reached_proposer: false,
+                stage: SubblockTransactionStage::Pending,
+                block_number: None,
+                block_hash: None,
+            })
+    }
+
+    /// Removes `tx_hash` from the transactions put in a subblock on `parent`.
+    fn forget_parent(&mut self, tx_hash: &TxHash, parent: Option<BlockHash>) {
+        if let Some(parent) = parent
+            && let Some(transactions) = self.by_parent.get_mut(&parent)
+        {
+            transactions.remove(tx_hash);
+            if transactions.is_empty() {
+                self.by_parent.remove(&parent);
+            }
+        }
+    }
+
+    /// Records that the transactions of `subblock` were put in it for `proposer`.
+    fn in_subblock(&mut self, subblock: &RecoveredSubBlock, proposer: B256, reached: bool) {
+        for tx in &subblock.transactions {
+            self.put_in_subblock(
+                *tx.tx_hash(),
+                subblock.validator(),
+                subblock.parent_hash,
+                proposer,
+                reached,
+            );
+        }
+    }
+
+    /// Records that `tx_hash` was put in a subblock of `validator` on `parent` for `proposer`.
+    fn put_in_subblock(
+        &mut self,
+        tx_hash: TxHash,
+        validator: B256,
+        parent: BlockHash,
+        proposer: B256,
+        reached: bool,
+    ) {
+        let previous = self.track(tx_hash, validator).parent_hash;
+        if previous != Some(parent) {
+            self.forget_parent(&tx_hash, previous);
+        }
+        self.by_parent.entry(parent).or_default().insert(tx_hash);
+
+        let status = self
+            .statuses
+            .get_mut(&tx_hash)
+            .expect("transaction was just tracked");
+        status.parent_hash = Some(parent);
+        status.proposer = Some(proposer);
+        status.reached_proposer = reached;
+        status.stage = SubblockTransactionStage::InSubblock;
+    }
+
+    /// Records that `subblock` was acknowledged by its proposer.
+    fn reached_proposer(&mut self, subblock: &RecoveredSubBlock) {
+        for tx in &subblock.transactions {
+            if let Some(status) = self.statuses.get_mut(tx.tx_hash())
+                && status.parent_hash == Some(subblock.parent_hash)
+            {
+                status.reached_proposer = true;
+            }
+        }
+    }
+
+    /// Marks the transactions still waiting in a subblock on `parent` as not included.
+    fn not_included(&mut self, parent: BlockHash) {
+        for tx_hash in self.by_parent.remove(&parent).unwrap_or_default() {
+            if let Some(status) = self.statuses.get_mut(&tx_hash)
+                && status.stage == SubblockTransactionStage::InSubblock
+                && status.parent_hash == Some(parent)
+            {
+                status.stage = SubblockTransactionStage::NotIncluded;
+            }
+        }
+    }
+}
+
+/// Subblock counters labelled by the validator that built the subblock.
+struct ValidatorMetrics {
+    received: Family<Vec<(String, String)>, Counter>,
+    included: Family<Vec<(String, String)>, Counter>,
+    dropped_expired: Family<Vec<(String, String)>, Counter>,
+    discarded_invalid: Family<Vec<(String, String)>, Counter>,
+}
+
+impl ValidatorMetrics {
+    fn register(context: &impl Metrics) -> Self {
+        let this = Self {
+            received: Family::default(),
+            included: Family::default(),
+            dropped_expired: Family::default(),
+            discarded_invalid: Family::default(),
+        };
+        context.register(
+            "subblocks_received",
+            "the number of subblocks received and validated as proposer, per validator",
+            this.received.clone(),
+        );
+        context.register(
+            "subblocks_included",
+            "the number of subblocks included in notarized blocks, per validator",
+            this.included.clone(),
+        );
+        context.register(
+            "subblocks_dropped_expired",
+            "the number of subblocks left out of our payloads because of expired transactions, per validator",
+            this.dropped_expired.clone(),
+        );
+        context.register(
+            "subblocks_discarded_invalid",
+            "the number of subblocks left out of our payloads after an invalid subblock, per validator",
+            this.discarded_invalid.clone(),
+        );
+        this
+    }
+
+    fn received(&self, validator: B256) -> Counter {
+        self.received.get_or_create(&labels(validator)).clone()
+    }
+
+    fn included(&self, validator: B256) -> Counter {
+        self.included.get_or_create(&labels(validator)).clone()
+    }
+
+    fn dropped_expired(&self, validator: B256) -> Counter {
+        self.dropped_expired
+            .get_or_create(&labels(validator))
+            .clone()
+    }
+
+    fn discarded_invalid(&self, validator: B256) -> Counter {
+        self.discarded_invalid
+            .get_or_create(&labels(validator))
+            .clone()
+    }
+}
+
+fn labels(validator: B256) -> Vec<(String, String)> {
+    vec![("validator".to_string(), validator.to_string())]
 }
 
 /// The current state of our subblock.
@@ -572,7 +930,7 @@ impl PendingSubblock {
 /// Task for building a subblock.
 struct BuildSubblockTask {
     /// Handle to the spawned task.
-    handle: Handle<RecoveredSubBlock>,
+    handle: Handle<eyre::Result<RecoveredSubBlock>>,
     /// Parent hash subblock is being built on top of.
     parent_hash: BlockHash,
     /// Proposer we are going to send the subblock to.
@@ -607,8 +965,22 @@ enum SubblocksMessage {
 
 impl SubblocksMessage {
     /// Encodes the message into a [`bytes::Bytes`].
+    ///
+    /// [`SubBlockVersion::V2`] subblocks are compressed; proposers that accept them also
+    /// accept compressed messages.
     fn encode(self) -> bytes::Bytes {
         match self {
+            Self::Subblock(subblock) if subblock.version == SubBlockVersion::V2 => {
+                let compressed = zstd::encode_all(
+                    alloy_rlp::encode(&subblock).as_slice(),
+                    SUBBLOCK_COMPRESSION_LEVEL,
+                )
+                .expect("compressing an in-memory buffer must not fail");
+                let mut message = Vec::with_capacity(compressed.len() + 1);
+                message.push(COMPRESSED_SUBBLOCK_TAG);
+                message.extend_from_slice(&compressed);
+                message.into()
+            }
             Self::Subblock(subblock) => alloy_rlp::encode(&subblock).into(),
             Self::Ack(hash) => bytes::Bytes::copy_from_slice(hash.as_ref()),
         }
@@ -619,6 +991,15 @@ impl SubblocksMessage {
         if message.len() == 32 {
             let hash = B256::from_slice(&message);
             Ok(Self::Ack(hash))
+        } else if let Some((&COMPRESSED_SUBBLOCK_TAG, compressed)) = message.split_first() {
+            let subblock =
+                alloy_rlp::decode_exact::<SignedSubBlock>(decompress_subblock(compressed)?)?;
+            if subblock.version != SubBlockVersion::V2 {
+                return Err(alloy_rlp::Error::Custom(
+                    "only version 2 subblocks are compressed",
+                ));
+            }
+            Ok(Self::Subblock(subblock))
         } else {
             let subblock = SignedSubBlock::decode(&mut &*message)?;
             Ok(Self::Subblock(subblock))
@@ -626,6 +1007,23 @@ impl SubblocksMessage {
     }
 }
 
+/// Decompresses a subblock, failing if it exceeds [`MAX_DECOMPRESSED_SUBBLOCK_SIZE`].
+fn decompress_subblock(compressed: &[u8]) -> alloy_rlp::Result<Vec<u8>> {
+    const DECOMPRESSION_FAILED: alloy_rlp::Error =
+        alloy_rlp::Error::Custom("failed to decompress subblock");
+
+    let mut decompressed = Vec::new();
+    zstd::stream::read::Decoder::new(compressed)
+        .map_err(|_| DECOMPRESSION_FAILED)?
+        .take(MAX_DECOMPRESSED_SUBBLOCK_SIZE as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|_| DECOMPRESSION_FAILED)?;
+    if decompressed.len() > MAX_DECOMPRESSED_SUBBLOCK_SIZE {
+        return Err(alloy_rlp::Error::Custom("decompressed subblock too large"));
+    }
+    Ok(decompressed)
+}
+
 /// Handle to the spawned subblocks service.
 #[derive(Clone)]
 pub(crate) struct Mailbox {
@@ -644,10 +1042,41 @@ impl Mailbox {
         });
         rx.recv()
     }
+
+    /// Reports that the payload builder left `subblock` out of a payload for `reason`.
+    pub(crate) fn report_dropped_subblock(
+        &self,
+        subblock: &RecoveredSubBlock,
+        reason: SubblockDropReason,
+    ) {
+        let _ = self.tx.unbounded_send(Message::DroppedSubblock {
+            validator: subblock.validator(),
+            parent: subblock.parent_hash,
+            transactions: subblock
+                .transactions
+                .iter()
+                .map(|tx| *tx.tx_hash())
+                .collect(),
+            reason,
+        });
+    }
+
+    /// Returns the progress of the subblock transaction `tx_hash`, if it is tracked.
+    pub(crate) async fn get_transaction_status(
+        &self,
+        tx_hash: TxHash,
+    ) -> eyre::Result<Option<SubblockTransactionStatus>> {
+        let (response, rx) = oneshot::channel();
+        self.tx
+            .unbounded_send(Message::TransactionStatus { tx_hash, response })
+            .wrap_err("failed sending message to actor")?;
+        rx.await
+            .wrap_err("actor dropped channel before responding with transaction status")
+    }
 }
 
 impl Reporter for Mailbox {
-    type Activity = Activity<Scheme<PublicKey, MinSig>, Digest>;
+    type Activity = Activity<ConsensusScheme, Digest>;
 
     async fn report(&mut self, activity: Self::Activity) -> () {
         let _ = self
@@ -682,14 +1111,26 @@ async fn build_subblock(
     node: TempoFullNode,
     parent_hash: BlockHash,
     num_validators: usize,
-    signer: PrivateKey,
+    signer: ConsensusSigner,
     fee_recipient: Address,
+    partners: Arc<HashMap<Address, Address>>,
     timeout: Duration,
-) -> RecoveredSubBlock {
+) -> eyre::Result<RecoveredSubBlock> {
     let start = Instant::now();
 
-    let (transactions, senders) = match evm_at_block(&node, parent_hash) {
+    let (version, transactions, senders) = match evm_at_block(&node, parent_hash) {
         Ok(mut evm) => {
+            // The next block is never older than its parent, so it accepts version 2 subblocks
+            // if the parent does.
+            let version = if node
+                .chain_spec()
+                .is_allegro_moderato_active_at_timestamp(evm.block().timestamp.to())
+            {
+                SubBlockVersion::V2
+            } else {
+                SubBlockVersion::V1
+            };
+
             let mut selected_transactions = Vec::new();
             let mut senders = Vec::new();
             let mut gas_left =
@@ -716,33 +1157,45 @@ async fn build_subblock(
                 }
             }
 
-            (selected_transactions, senders)
+            (version, selected_transactions, senders)
         }
         Err(err) => {
             warn!(%err, "failed to build an evm at block, building an empty subblock");
 
-            Default::default()
+            (SubBlockVersion::V1, Vec::new(), Vec::new())
         }
     };
 
+    // Partners are credited the fees of their own transactions.
+    let transaction_fee_recipients = match version {
+        SubBlockVersion::V1 => Vec::new(),
+        SubBlockVersion::V2 => senders
+            .iter()
+            .map(|sender| partners.get(sender).copied().unwrap_or(fee_recipient))
+            .collect(),
+    };
+
     let subblock = SubBlock {
-        version: SubBlockVersion::V1,
+        version,
         fee_recipient,
         parent_hash,
         transactions,
+        transaction_fee_recipients,
     };
 
-    let signature = signer.sign(None, subblock.signature_hash().as_slice());
+    let signature = signer
+        .try_sign(None, subblock.signature_hash().as_slice())
+        .wrap_err("failed signing subblock")?;
     let signed_subblock = SignedSubBlock {
         inner: subblock,
         signature: Bytes::copy_from_slice(signature.as_ref()),
     };
 
-    RecoveredSubBlock::new_unchecked(
+    Ok(RecoveredSubBlock::new_unchecked(
         signed_subblock,
         senders,
         B256::from_slice(&signer.public_key()),
-    )
+    ))
 }
 
 /// Validates a subblock and reports it to the subblocks service.
@@ -785,6 +1238,14 @@ async fn validate_subblock(
 
     let mut evm = evm_at_block(&node, subblock.parent_hash)?;
 
+    eyre::ensure!(
+        subblock.version == SubBlockVersion::V1
+            || node
+                .chain_spec()
+                .is_allegro_moderato_active_at_timestamp(evm.block().timestamp.to()),
+        "subblock version 2 is not accepted on top of the parent block"
+    );
+
     let epoch = utils::epoch(epoch_length, evm.block().number.to::<u64>() + 1);
     let scheme = scheme_provider
         .scheme(epoch)
@@ -819,3 +1280,200 @@ async fn validate_subblock(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_consensus::{Signed, TxLegacy};
+    use alloy_primitives::{Address, B256, Bytes, Signature, TxHash};
+    use tempo_node::rpc::SubblockTransactionStage;
+    use tempo_primitives::{SignedSubBlock, SubBlock, SubBlockVersion, TempoTxEnvelope};
+
+    use super::{
+        COMPRESSED_SUBBLOCK_TAG, MAX_DECOMPRESSED_SUBBLOCK_SIZE, MAX_TRACKED_TXS,
+        SUBBLOCK_COMPRESSION_LEVEL, SubblocksMessage, TrackedTransactions, decompress_subblock,
+    };
+
+    const VALIDATOR: B256 = B256::repeat_byte(0xaa);
+    const PROPOSER: B256 = B256::repeat_byte(0xbb);
+
+    fn subblock(version: SubBlockVersion) -> SignedSubBlock {
+        let transactions = (0..8)
+            .map(|nonce| {
+                TempoTxEnvelope::Legacy(Signed::new_unhashed(
+                    TxLegacy {
+                        chain_id: Some(1),
+                        nonce,
+                        gas_limit: 21_000,
+                        ..Default::default()
+                    },
+                    Signature::test_signature(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let transaction_fee_recipients = match version {
+            SubBlockVersion::V1 => Vec::new(),
+            SubBlockVersion::V2 => vec![Address::repeat_byte(1); transactions.len()],
+        };
+        SignedSubBlock {
+            inner: SubBlock {
+                version,
+                parent_hash: B256::repeat_byte(7),
+                fee_recipient: Address::repeat_byte(1),
+                transactions,
+                transaction_fee_recipients,
+            },
+            signature: Bytes::from_static(&[9; 64]),
+        }
+    }
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut message = vec![COMPRESSED_SUBBLOCK_TAG];
+        message.extend(zstd::encode_all(data, SUBBLOCK_COMPRESSION_LEVEL).unwrap());
+        message
+    }
+
+    fn decode(message: Vec<u8>) -> alloy_rlp::Result<SubblocksMessage> {
+        SubblocksMessage::decode(message.into())
+    }
+
+    #[test]
+    fn only_version_2_subblocks_are_compressed() {
+        for (version, compressed) in [(SubBlockVersion::V1, false), (SubBlockVersion::V2, true)] {
+            let subblock = subblock(version);
+            let message = SubblocksMessage::Subblock(subblock.clone()).encode();
+
+            assert_eq!(message[0] == COMPRESSED_SUBBLOCK_TAG, compressed);
+            let Ok(SubblocksMessage::Subblock(decoded)) = SubblocksMessage::decode(message) else {
+                panic!("subblock must decode");
+            };
+            assert_eq!(decoded, subblock);
+        }
+
+        let hash = B256::repeat_byte(3);
+        let Ok(SubblocksMessage::Ack(decoded)) =
+            SubblocksMessage::decode(SubblocksMessage::Ack(hash).encode())
+        else {
+            panic!("acknowledgement must decode");
+        };
+        assert_eq!(decoded, hash);
+    }
+
+    #[test]
+    fn compressed_version_1_subblocks_are_rejected() {
+        let message = compress(&alloy_rlp::encode(subblock(SubBlockVersion::V1)));
+
+        assert!(decode(message).is_err());
+    }
+
+    #[test]
+    fn malformed_compressed_subblocks_are_rejected() {
+        // Not a zstd frame.
+        assert!(decode(vec![COMPRESSED_SUBBLOCK_TAG; 64]).is_err());
+
+        // Truncated zstd frame.
+        let mut message = compress(&alloy_rlp::encode(subblock(SubBlockVersion::V2)));
+        message.truncate(message.len() / 2);
+        assert!(decode(message).is_err());
+
+        // Valid zstd frame, but not a subblock.
+        assert!(decode(compress(&[0xab; 128])).is_err());
+
+        // Subblock followed by trailing bytes.
+        let mut encoded = alloy_rlp::encode(subblock(SubBlockVersion::V2));
+        encoded.extend_from_slice(&[0; 16]);
+        assert!(decode(compress(&encoded)).is_err());
+    }
+
+    #[test]
+    fn decompressed_size_is_bounded() {
+        let at_limit = vec![0; MAX_DECOMPRESSED_SUBBLOCK_SIZE];
+        assert_eq!(
+            decompress_subblock(&compress(&at_limit)[1..]).unwrap(),
+            at_limit
+        );
+
+        // A small message decompressing to a large buffer must be rejected without
+        // decompressing it entirely.
+        let bomb = compress(&vec![0; 8 * MAX_DECOMPRESSED_SUBBLOCK_SIZE]);
+        assert!(bomb.len() < MAX_DECOMPRESSED_SUBBLOCK_SIZE / 1000);
+        assert!(decompress_subblock(&bomb[1..]).is_err());
+        assert!(decode(bomb).is_err());
+
+        let over_limit = compress(&vec![0; MAX_DECOMPRESSED_SUBBLOCK_SIZE + 1]);
+        assert!(decompress_subblock(&over_limit[1..]).is_err());
+    }
+
+    fn tx_hash(index: usize) -> TxHash {
+        TxHash::left_padding_from(&(index as u64).to_be_bytes())
+    }
+
+    fn stage(tracked: &TrackedTransactions, index: usize) -> SubblockTransactionStage {
+        tracked.get(&tx_hash(index)).unwrap().stage
+    }
+
+    #[test]
+    fn transactions_left_out_of_block_on_parent_are_not_included() {
+        let mut tracked = TrackedTransactions::default();
+        let (parent, other) = (B256::repeat_byte(1), B256::repeat_byte(2));
+
+        tracked.track(tx_hash(0), VALIDATOR);
+        assert_eq!(stage(&tracked, 0), SubblockTransactionStage::Pending);
+
+        tracked.put_in_subblock(tx_hash(0), VALIDATOR, parent, PROPOSER, false);
+        tracked.put_in_subblock(tx_hash(1), VALIDATOR, parent, PROPOSER, true);
+        tracked.put_in_subblock(tx_hash(2), VALIDATOR, other, PROPOSER, false);
+        assert_eq!(stage(&tracked, 1), SubblockTransactionStage::InSubblock);
+        assert!(tracked.get(&tx_hash(1)).unwrap().reached_proposer);
+
+        // Included in the block on the parent.
+        tracked.get_mut(&tx_hash(1)).unwrap().stage = SubblockTransactionStage::Included;
+        tracked.not_included(parent);
+
+        assert_eq!(stage(&tracked, 0), SubblockTransactionStage::NotIncluded);
+        assert_eq!(stage(&tracked, 1), SubblockTransactionStage::Included);
+        assert_eq!(stage(&tracked, 2), SubblockTransactionStage::InSubblock);
+        assert!(!tracked.by_parent.contains_key(&parent));
+    }
+
+    #[test]
+    fn transactions_moved_to_subblock_on_new_parent_follow_it() {
+        let mut tracked = TrackedTransactions::default();
+        let (old, new) = (B256::repeat_byte(1), B256::repeat_byte(2));
+
+        tracked.put_in_subblock(tx_hash(0), VALIDATOR, old, PROPOSER, true);
+        tracked.put_in_subblock(tx_hash(0), VALIDATOR, new, PROPOSER, false);
+
+        assert!(!tracked.by_parent.contains_key(&old));
+        let status = tracked.get(&tx_hash(0)).unwrap();
+        assert_eq!(status.parent_hash, Some(new));
+        assert!(!status.reached_proposer);
+
+        tracked.not_included(old);
+        assert_eq!(stage(&tracked, 0), SubblockTransactionStage::InSubblock);
+        tracked.not_included(new);
+        assert_eq!(stage(&tracked, 0), SubblockTransactionStage::NotIncluded);
+    }
+
+    #[test]
+    fn oldest_transactions_are_forgotten_at_capacity() {
+        let mut tracked = TrackedTransactions::default();
+        let parent = B256::repeat_byte(1);
+
+        tracked.put_in_subblock(tx_hash(0), VALIDATOR, parent, PROPOSER, false);
+        for index in 1..MAX_TRACKED_TXS {
+            tracked.track(tx_hash(index), VALIDATOR);
+        }
+        // Tracking a known transaction again does not forget anything.
+        tracked.track(tx_hash(0), VALIDATOR);
+        assert!(tracked.get(&tx_hash(0)).is_some());
+
+        tracked.track(tx_hash(MAX_TRACKED_TXS), VALIDATOR);
+
+        assert!(tracked.get(&tx_hash(0)).is_none());
+        assert!(tracked.get(&tx_hash(1)).is_some());
+        assert!(tracked.get(&tx_hash(MAX_TRACKED_TXS)).is_some());
+        assert_eq!(tracked.statuses.len(), MAX_TRACKED_TXS);
+        assert_eq!(tracked.order.len(), MAX_TRACKED_TXS);
+        assert!(!tracked.by_parent.contains_key(&parent));
+    }
+}
@@ -12,7 +12,7 @@ use reth_consensus_common::validation::{
     validate_against_parent_gas_limit, validate_against_parent_hash_number,
 };
 use reth_ethereum_consensus::EthBeaconConsensus;
-use reth_primitives_traits::{RecoveredBlock, SealedBlock, SealedHeader};
+use reth_primitives_traits::{GotExpected, RecoveredBlock, SealedBlock, SealedHeader};
 use std::sync::Arc;
 use tempo_chainspec::{hardfork::TempoHardforks, spec::TempoChainSpec};
 use tempo_contracts::precompiles::{
@@ -88,6 +88,19 @@ impl HeaderValidator<TempoHeader> for TempoConsensus {
             ));
         }
 
+        // Validate that the lane fees are set exactly from Allegro-Moderato onwards
+        let is_allegro_moderato = self
+            .inner
+            .chain_spec()
+            .is_allegro_moderato_active_at_timestamp(header.timestamp());
+        if header.lane_fees.is_some() != is_allegro_moderato {
+            return Err(ConsensusError::Other(if is_allegro_moderato {
+                "Lane fees missing after Allegro-Moderato".to_string()
+            } else {
+                "Lane fees set before Allegro-Moderato".to_string()
+            }));
+        }
+
         Ok(())
     }
 
@@ -114,6 +127,20 @@ impl HeaderValidator<TempoHeader> for TempoConsensus {
             validate_against_parent_4844(header.header(), parent.header(), blob_params)?;
         }
 
+        if let Some(expected) = self
+            .inner
+            .chain_spec()
+            .next_block_payment_base_fee(parent.header(), header.timestamp())
+        {
+            let got = header
+                .lane_fees
+                .map(|fees| fees.payment_base_fee_per_gas)
+                .unwrap_or_default();
+            if got != expected {
+                return Err(ConsensusError::BaseFeeDiff(GotExpected { got, expected }));
+            }
+        }
+
         if header.timestamp_millis() <= parent.timestamp_millis() {
             return Err(ConsensusError::TimestampIsInPast {
                 parent_timestamp: parent.timestamp_millis(),
@@ -210,10 +237,80 @@ impl FullConsensus<TempoPrimitives> for TempoConsensus {
         block: &RecoveredBlock<Block>,
         result: &BlockExecutionResult<TempoReceipt>,
     ) -> Result<(), ConsensusError> {
-        FullConsensus::<TempoPrimitives>::validate_block_post_execution(&self.inner, block, result)
+        FullConsensus::<TempoPrimitives>::validate_block_post_execution(
+            &self.inner,
+            block,
+            result,
+        )?;
+
+        if let Some(lane_fees) = block.header().lane_fees {
+            let transactions = &block.body().transactions;
+            let general_gas_used = general_gas_used(transactions, &result.receipts);
+            if lane_fees.general_gas_used != general_gas_used {
+                return Err(ConsensusError::Other(format!(
+                    "General gas used mismatch: got {}, expected {general_gas_used}",
+                    lane_fees.general_gas_used
+                )));
+            }
+
+            let payment_gas_used = payment_gas_used(transactions, &result.receipts);
+            if lane_fees.payment_gas_used != payment_gas_used {
+                return Err(ConsensusError::Other(format!(
+                    "Payment gas used mismatch: got {}, expected {payment_gas_used}",
+                    lane_fees.payment_gas_used
+                )));
+            }
+        }
+
+        Ok(())
     }
 }
 
+/// Returns the gas used by the general lane of a block, i.e. by its non-payment transactions
+/// outside of subblocks.
+///
+/// `receipts` are the receipts of `transactions`, in the same order.
+pub fn general_gas_used<'a>(
+    transactions: impl IntoIterator<Item = &'a TempoTxEnvelope>,
+    receipts: &[TempoReceipt],
+) -> u64 {
+    lane_gas_used(transactions, receipts, |tx| !tx.is_payment())
+}
+
+/// Returns the gas used by the payment lane of a block, i.e. by its payment transactions outside
+/// of subblocks.
+///
+/// `receipts` are the receipts of `transactions`, in the same order.
+pub fn payment_gas_used<'a>(
+    transactions: impl IntoIterator<Item = &'a TempoTxEnvelope>,
+    receipts: &[TempoReceipt],
+) -> u64 {
+    lane_gas_used(transactions, receipts, TempoTxEnvelope::is_payment)
+}
+
+/// Returns the gas used by the transactions matching `in_lane`, excluding system transactions and
+/// subblock transactions, which don't belong to either lane.
+fn lane_gas_used<'a>(
+    transactions: impl IntoIterator<Item = &'a TempoTxEnvelope>,
+    receipts: &[TempoReceipt],
+    in_lane: impl Fn(&TempoTxEnvelope) -> bool,
+) -> u64 {
+    let mut cumulative_gas_used = 0;
+    transactions
+        .into_iter()
+        .zip(receipts)
+        .map(|(tx, receipt)| {
+            let gas_used = receipt.cumulative_gas_used - cumulative_gas_used;
+            cumulative_gas_used = receipt.cumulative_gas_used;
+            if tx.is_system_tx() || tx.subblock_proposer().is_some() || !in_lane(tx) {
+                0
+            } else {
+                gas_used
+            }
+        })
+        .sum()
+}
+
 /// Divisor for calculating non-payment gas limit.
 pub const TEMPO_GENERAL_GAS_DIVISOR: u64 = 2;
 
@@ -222,3 +319,139 @@ pub const TEMPO_SHARED_GAS_DIVISOR: u64 = 10;
 
 /// Maximum extra data size for Tempo blocks.
 pub const TEMPO_MAXIMUM_EXTRA_DATA_SIZE: usize = 10 * 1_024; // 10KiB
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{EMPTY_OMMER_ROOT_HASH, Header, Signed, TxLegacy};
+    use alloy_evm::revm::primitives::{B256, Signature, TxKind};
+    use tempo_chainspec::spec::{DEV, TEMPO_BASE_FEE};
+    use tempo_contracts::precompiles::PATH_USD_ADDRESS;
+    use tempo_primitives::{
+        LaneFees, TempoTxType, transaction::envelope::TEMPO_SYSTEM_TX_SIGNATURE,
+    };
+
+    const ALLEGRO_MODERATO_TIME: u64 = 1000;
+
+    /// Dev chain with Allegro-Moderato activated at [`ALLEGRO_MODERATO_TIME`].
+    fn consensus() -> TempoConsensus {
+        let mut genesis = DEV.genesis().clone();
+        genesis
+            .config
+            .extra_fields
+            .insert_value("allegroModeratoTime".to_string(), ALLEGRO_MODERATO_TIME)
+            .unwrap();
+        TempoConsensus::new(Arc::new(TempoChainSpec::from_genesis(genesis)))
+    }
+
+    fn header(number: u64, timestamp: u64, lane_fees: Option<LaneFees>) -> TempoHeader {
+        TempoHeader {
+            general_gas_limit: 225_000_000,
+            shared_gas_limit: 50_000_000,
+            timestamp_millis_part: 0,
+            inner: Header {
+                number,
+                timestamp,
+                gas_limit: 500_000_000,
+                base_fee_per_gas: Some(TEMPO_BASE_FEE),
+                ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                withdrawals_root: Some(B256::ZERO),
+                blob_gas_used: Some(0),
+                excess_blob_gas: Some(0),
+                parent_beacon_block_root: Some(B256::ZERO),
+                requests_hash: Some(B256::ZERO),
+                ..Default::default()
+            },
+            lane_fees,
+        }
+    }
+
+    fn lane_fees(payment_base_fee_per_gas: u64) -> Option<LaneFees> {
+        Some(LaneFees {
+            payment_base_fee_per_gas,
+            general_gas_used: 0,
+            payment_gas_used: 0,
+        })
+    }
+
+    #[test]
+    fn test_lane_fees_are_set_exactly_from_allegro_moderato() {
+        let consensus = consensus();
+        let validate = |timestamp, lane_fees| {
+            consensus.validate_header(&SealedHeader::seal_slow(header(1, timestamp, lane_fees)))
+        };
+
+        validate(ALLEGRO_MODERATO_TIME - 1, None).unwrap();
+        assert_eq!(
+            validate(ALLEGRO_MODERATO_TIME - 1, lane_fees(TEMPO_BASE_FEE)),
+            Err(ConsensusError::Other(
+                "Lane fees set before Allegro-Moderato".to_string()
+            ))
+        );
+
+        validate(ALLEGRO_MODERATO_TIME, lane_fees(TEMPO_BASE_FEE)).unwrap();
+        assert_eq!(
+            validate(ALLEGRO_MODERATO_TIME, None),
+            Err(ConsensusError::Other(
+                "Lane fees missing after Allegro-Moderato".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_payment_base_fee_is_validated_against_parent() {
+        let consensus = consensus();
+        let parent =
+            SealedHeader::seal_slow(header(1, ALLEGRO_MODERATO_TIME, lane_fees(TEMPO_BASE_FEE)));
+        let validate = |payment_base_fee_per_gas| {
+            let mut child = header(
+                2,
+                ALLEGRO_MODERATO_TIME + 1,
+                lane_fees(payment_base_fee_per_gas),
+            );
+            child.inner.parent_hash = parent.hash();
+            consensus.validate_header_against_parent(&SealedHeader::seal_slow(child), &parent)
+        };
+
+        // The parent used no gas, so the base fee stays at its floor
+        validate(TEMPO_BASE_FEE).unwrap();
+        assert_eq!(
+            validate(TEMPO_BASE_FEE + 1),
+            Err(ConsensusError::BaseFeeDiff(GotExpected {
+                got: TEMPO_BASE_FEE + 1,
+                expected: TEMPO_BASE_FEE,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_lane_gas_used() {
+        let tx = |to, signature| {
+            TempoTxEnvelope::Legacy(Signed::new_unhashed(
+                TxLegacy {
+                    to: TxKind::Call(to),
+                    ..Default::default()
+                },
+                signature,
+            ))
+        };
+        let receipt = |cumulative_gas_used| TempoReceipt {
+            tx_type: TempoTxType::Legacy,
+            success: true,
+            cumulative_gas_used,
+            logs: vec![],
+        };
+
+        let transactions = [
+            tx(PATH_USD_ADDRESS, Signature::test_signature()),
+            tx(STABLECOIN_EXCHANGE_ADDRESS, Signature::test_signature()),
+            tx(PATH_USD_ADDRESS, Signature::test_signature()),
+            tx(TIP_FEE_MANAGER_ADDRESS, TEMPO_SYSTEM_TX_SIGNATURE),
+        ];
+        let receipts = [receipt(100), receipt(1100), receipt(1300), receipt(1700)];
+
+        // The system transaction belongs to neither lane
+        assert_eq!(general_gas_used(&transactions, &receipts), 1000);
+        assert_eq!(payment_gas_used(&transactions, &receipts), 300);
+    }
+}
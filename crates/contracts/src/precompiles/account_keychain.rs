@@ -8,12 +8,18 @@ sol! {
     /// Account Keychain interface for managing authorized keys
     ///
     /// This precompile allows accounts to authorize secondary keys with:
-    /// - Different signature types (secp256k1, P256, WebAuthn)
+    /// - Different signature types (secp256k1, P256, WebAuthn, Ed25519)
     /// - Expiry times for key rotation
     /// - Per-token spending limits for security
     ///
     /// Only the main account key can authorize/revoke keys, while secondary keys
     /// can be used for regular transactions within their spending limits.
+    ///
+    /// Accounts can also register a threshold multisig config, allowing transactions
+    /// signed by M-of-N registered co-signers to act as the main account key.
+    ///
+    /// Guardians registered by an account can jointly recover it by proposing a new key,
+    /// which is authorized after a timelock during which the account can veto the recovery.
     #[derive(Debug, PartialEq, Eq)]
     #[sol(rpc)]
     interface IAccountKeychain {
@@ -21,6 +27,7 @@ sol! {
             Secp256k1,
             P256,
             WebAuthn,
+            Ed25519,
         }
 
         /// Token spending limit structure
@@ -37,6 +44,28 @@ sol! {
             bool enforceLimits;
             bool isRevoked;
         }
+
+        /// Threshold multisig configuration structure
+        struct MultisigConfig {
+            uint8 threshold;
+            address[] signers;
+        }
+
+        /// Guardian recovery configuration structure
+        struct GuardianConfig {
+            uint8 threshold;
+            address[] guardians;
+        }
+
+        /// Pending guardian recovery structure
+        struct PendingRecovery {
+            address keyId;
+            SignatureType signatureType;
+            uint64 expiry;
+            uint8 approvals;
+            uint64 executableAt;
+        }
+
         /// Emitted when a new key is authorized
         event KeyAuthorized(address indexed account, bytes32 indexed publicKey, uint8 signatureType, uint64 expiry);
 
@@ -46,9 +75,36 @@ sol! {
         /// Emitted when a spending limit is updated
         event SpendingLimitUpdated(address indexed account, bytes32 indexed publicKey, address indexed token, uint256 newLimit);
 
+        /// Emitted when a multisig config is registered or replaced
+        event MultisigConfigured(address indexed account, uint8 threshold, address[] signers);
+
+        /// Emitted when a multisig config is removed
+        event MultisigRemoved(address indexed account);
+
+        /// Emitted when a guardian config is registered or replaced
+        event GuardiansConfigured(address indexed account, uint8 threshold, address[] guardians);
+
+        /// Emitted when a guardian config is removed
+        event GuardiansRemoved(address indexed account);
+
+        /// Emitted when a guardian proposes or approves a recovery key
+        event RecoveryProposed(address indexed account, address indexed guardian, address indexed keyId, uint8 approvals);
+
+        /// Emitted when a recovery reaches the guardian threshold and its timelock starts
+        event RecoveryScheduled(address indexed account, address indexed keyId, uint64 executableAt);
+
+        /// Emitted when a guardian rejects the pending recovery of an account
+        event RecoveryRejected(address indexed account, address indexed guardian, address indexed keyId, uint8 rejections);
+
+        /// Emitted when a pending recovery is cancelled
+        event RecoveryCancelled(address indexed account, address indexed keyId);
+
+        /// Emitted when a recovery is executed and its key authorized
+        event RecoveryExecuted(address indexed account, address indexed keyId);
+
         /// Authorize a new key for the caller's account
         /// @param keyId The key identifier (address derived from public key)
-        /// @param signatureType 0: secp256k1, 1: P256, 2: WebAuthn
+        /// @param signatureType 0: secp256k1, 1: P256, 2: WebAuthn, 3: Ed25519
         /// @param expiry Block timestamp when the key expires (u64::MAX for never expires)
         /// @param enforceLimits Whether to enforce spending limits for this key
         /// @param limits Initial spending limits for tokens (only used if enforceLimits is true)
@@ -95,6 +151,60 @@ sol! {
         /// @return The keyId used in the current transaction
         function getTransactionKey() external view returns (address);
 
+        /// Register or replace the threshold multisig config for the caller's account
+        /// @param signers The co-signer addresses (derived from their public keys)
+        /// @param threshold Number of co-signer signatures required, at most `signers.length`
+        function setMultisig(address[] calldata signers, uint8 threshold) external;
+
+        /// Remove the threshold multisig config for the caller's account
+        function removeMultisig() external;
+
+        /// Get the threshold multisig config of an account
+        /// @param account The account address
+        /// @return The multisig config, with a zero threshold if none is registered
+        function getMultisig(address account) external view returns (MultisigConfig memory);
+
+        /// Register or replace the guardians of the caller's account
+        /// @param guardians The guardian addresses
+        /// @param threshold Number of guardian approvals required, at most `guardians.length`
+        function setGuardians(address[] calldata guardians, uint8 threshold) external;
+
+        /// Remove the guardians of the caller's account
+        function removeGuardians() external;
+
+        /// Get the guardian config of an account
+        /// @param account The account address
+        /// @return The guardian config, with a zero threshold if none is registered
+        function getGuardians(address account) external view returns (GuardianConfig memory);
+
+        /// Propose or approve a recovery key for an account, callable by its guardians
+        /// @param account The account to recover
+        /// @param keyId The key identifier to authorize once the recovery is executed
+        /// @param signatureType 0: secp256k1, 1: P256, 2: WebAuthn, 3: Ed25519
+        /// @param expiry Block timestamp when the recovered key expires
+        function proposeRecovery(
+            address account,
+            address keyId,
+            SignatureType signatureType,
+            uint64 expiry
+        ) external;
+
+        /// Cancel the pending recovery of the caller's account
+        function cancelRecovery() external;
+
+        /// Reject the pending recovery of an account, callable by its guardians
+        /// @param account The account whose pending recovery to reject
+        function rejectRecovery(address account) external;
+
+        /// Execute a recovery whose timelock has elapsed, authorizing its key
+        /// @param account The account to recover
+        function executeRecovery(address account) external;
+
+        /// Get the pending recovery of an account
+        /// @param account The account address
+        /// @return The pending recovery, with a zero keyId if none is pending
+        function getPendingRecovery(address account) external view returns (PendingRecovery memory);
+
         // Errors
         error UnauthorizedCaller();
         error KeyAlreadyExists();
@@ -105,6 +215,19 @@ sol! {
         error ZeroPublicKey();
         error ExpiryInPast();
         error KeyAlreadyRevoked();
+        error InvalidMultisigThreshold();
+        error InvalidMultisigSigner();
+        error MultisigNotConfigured();
+        error MultisigThresholdNotMet(uint8 threshold, uint8 approvals);
+        error InvalidGuardianThreshold();
+        error InvalidGuardian();
+        error GuardiansNotConfigured();
+        error NoPendingRecovery();
+        error RecoveryAlreadyApproved();
+        error RecoveryAlreadyRejected();
+        error RecoveryAlreadyScheduled();
+        error RecoveryAlreadyPending(uint64 replaceableAt);
+        error RecoveryNotReady(uint64 executableAt);
     }
 }
 
@@ -155,4 +278,78 @@ impl AccountKeychainError {
     pub const fn key_already_revoked() -> Self {
         Self::KeyAlreadyRevoked(IAccountKeychain::KeyAlreadyRevoked {})
     }
+
+    /// Creates an error for an invalid multisig threshold.
+    pub const fn invalid_multisig_threshold() -> Self {
+        Self::InvalidMultisigThreshold(IAccountKeychain::InvalidMultisigThreshold {})
+    }
+
+    /// Creates an error for an invalid multisig co-signer (zero, duplicate or unregistered).
+    pub const fn invalid_multisig_signer() -> Self {
+        Self::InvalidMultisigSigner(IAccountKeychain::InvalidMultisigSigner {})
+    }
+
+    /// Creates an error for an account without a multisig config.
+    pub const fn multisig_not_configured() -> Self {
+        Self::MultisigNotConfigured(IAccountKeychain::MultisigNotConfigured {})
+    }
+
+    /// Creates an error for a multisig signature that does not reach the threshold.
+    pub const fn multisig_threshold_not_met(threshold: u8, approvals: u8) -> Self {
+        Self::MultisigThresholdNotMet(IAccountKeychain::MultisigThresholdNotMet {
+            threshold,
+            approvals,
+        })
+    }
+
+    /// Creates an error for an invalid guardian threshold.
+    pub const fn invalid_guardian_threshold() -> Self {
+        Self::InvalidGuardianThreshold(IAccountKeychain::InvalidGuardianThreshold {})
+    }
+
+    /// Creates an error for an invalid guardian (zero, duplicate or unregistered).
+    pub const fn invalid_guardian() -> Self {
+        Self::InvalidGuardian(IAccountKeychain::InvalidGuardian {})
+    }
+
+    /// Creates an error for an account without guardians.
+    pub const fn guardians_not_configured() -> Self {
+        Self::GuardiansNotConfigured(IAccountKeychain::GuardiansNotConfigured {})
+    }
+
+    /// Creates an error for an account without a pending recovery.
+    pub const fn no_pending_recovery() -> Self {
+        Self::NoPendingRecovery(IAccountKeychain::NoPendingRecovery {})
+    }
+
+    /// Creates an error for a guardian approving the same recovery twice.
+    pub const fn recovery_already_approved() -> Self {
+        Self::RecoveryAlreadyApproved(IAccountKeychain::RecoveryAlreadyApproved {})
+    }
+
+    /// Creates an error for a guardian rejecting the same recovery twice.
+    pub const fn recovery_already_rejected() -> Self {
+        Self::RecoveryAlreadyRejected(IAccountKeychain::RecoveryAlreadyRejected {})
+    }
+
+    /// Creates an error for proposing a different key while a recovery is scheduled.
+    pub const fn recovery_already_scheduled() -> Self {
+        Self::RecoveryAlreadyScheduled(IAccountKeychain::RecoveryAlreadyScheduled {})
+    }
+
+    /// Creates an error for proposing a different key while a recovery is still collecting
+    /// approvals.
+    pub const fn recovery_already_pending(replaceable_at: u64) -> Self {
+        Self::RecoveryAlreadyPending(IAccountKeychain::RecoveryAlreadyPending {
+            replaceableAt: replaceable_at,
+        })
+    }
+
+    /// Creates an error for executing a recovery before its timelock has elapsed.
+    /// A zero `executable_at` means the recovery has not reached the guardian threshold yet.
+    pub const fn recovery_not_ready(executable_at: u64) -> Self {
+        Self::RecoveryNotReady(IAccountKeychain::RecoveryNotReady {
+            executableAt: executable_at,
+        })
+    }
 }
@@ -2,6 +2,7 @@ pub mod account_keychain;
 pub mod common_errors;
 pub mod nonce;
 pub mod path_usd;
+pub mod signature_verifier;
 pub mod stablecoin_exchange;
 pub mod tip20;
 pub mod tip20_factory;
@@ -9,6 +10,7 @@ pub mod tip20_rewards_registry;
 pub mod tip403_registry;
 pub mod tip_account_registrar;
 pub mod tip_fee_manager;
+pub mod transaction_context;
 pub mod validator_config;
 
 pub use account_keychain::*;
@@ -16,6 +18,7 @@ use alloy::primitives::{Address, address};
 pub use common_errors::*;
 pub use nonce::*;
 pub use path_usd::*;
+pub use signature_verifier::*;
 pub use stablecoin_exchange::*;
 pub use tip_account_registrar::*;
 pub use tip_fee_manager::*;
@@ -23,6 +26,7 @@ pub use tip20::*;
 pub use tip20_factory::*;
 pub use tip20_rewards_registry::*;
 pub use tip403_registry::*;
+pub use transaction_context::*;
 pub use validator_config::*;
 
 pub const TIP_FEE_MANAGER_ADDRESS: Address = address!("0xfeec000000000000000000000000000000000000");
@@ -45,3 +49,7 @@ pub const VALIDATOR_CONFIG_ADDRESS: Address =
     address!("0xCCCCCCCC00000000000000000000000000000000");
 pub const ACCOUNT_KEYCHAIN_ADDRESS: Address =
     address!("0xAAAAAAAA00000000000000000000000000000000");
+pub const SIGNATURE_VERIFIER_ADDRESS: Address =
+    address!("0x5160000000000000000000000000000000000000");
+pub const TRANSACTION_CONTEXT_ADDRESS: Address =
+    address!("0x7C00000000000000000000000000000000000000");
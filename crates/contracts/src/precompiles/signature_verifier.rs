@@ -0,0 +1,32 @@
+use alloy::sol;
+
+sol! {
+    /// Signature verifier interface exposing Tempo's native P256 and WebAuthn verification.
+    ///
+    /// Verification uses the exact same parsing and validation rules as Tempo transaction
+    /// signatures, so passkey wallets don't have to re-implement them in Solidity.
+    #[derive(Debug, PartialEq, Eq)]
+    #[sol(rpc, abi)]
+    interface ISignatureVerifier {
+        /// Verify a P256 signature over a digest
+        /// @param digest The signed digest
+        /// @param r The signature r value
+        /// @param s The signature s value
+        /// @param pubKeyX The public key x coordinate
+        /// @param pubKeyY The public key y coordinate
+        /// @param preHash Whether the signer sha256-hashed the digest before signing (Web Crypto)
+        /// @return valid True if the signature is valid
+        function verifyP256(bytes32 digest, bytes32 r, bytes32 s, bytes32 pubKeyX, bytes32 pubKeyY, bool preHash) external view returns (bool valid);
+
+        /// Verify a WebAuthn assertion
+        /// @param authenticatorData The authenticator data (at least 37 bytes, UP flag set, no AT flag, ED flag set if and only if extensions follow)
+        /// @param clientDataJSON The client data JSON (type "webauthn.get")
+        /// @param challenge The expected challenge, base64url encoded in clientDataJSON
+        /// @param r The signature r value
+        /// @param s The signature s value
+        /// @param pubKeyX The public key x coordinate
+        /// @param pubKeyY The public key y coordinate
+        /// @return valid True if the assertion is valid
+        function verifyWebAuthn(bytes calldata authenticatorData, bytes calldata clientDataJSON, bytes32 challenge, bytes32 r, bytes32 s, bytes32 pubKeyX, bytes32 pubKeyY) external view returns (bool valid);
+    }
+}
@@ -0,0 +1,55 @@
+use alloy::sol;
+
+sol! {
+    /// Read-only view of the context of the transaction being executed.
+    ///
+    /// The values are set by the protocol before the transaction executes and only live for the
+    /// duration of the transaction, so contracts can tailor their behavior to how they are being
+    /// paid for and authorized, e.g. apply stricter limits to calls signed by access keys.
+    #[derive(Debug, PartialEq, Eq)]
+    #[sol(rpc, abi)]
+    interface ITransactionContext {
+        enum SignatureType {
+            Secp256k1,
+            P256,
+            WebAuthn,
+            Ed25519,
+            Multisig,
+        }
+
+        /// Transaction context structure
+        struct Context {
+            address feeToken;
+            address feePayer;
+            address keyId;
+            SignatureType signatureType;
+            bool isAccessKey;
+            uint64 validAfter;
+            uint64 validBefore;
+            uint256 nonceKey;
+            uint64 callIndex;
+        }
+
+        /// Get the full context of the current transaction
+        /// @return context The transaction context
+        function getContext() external view returns (Context memory context);
+
+        /// Get the fee token the current transaction pays its fees in
+        /// @return token The resolved fee token
+        function feeToken() external view returns (address token);
+
+        /// Get the account paying the fees of the current transaction
+        /// @return payer The sender, or the sponsor of a sponsored transaction
+        function feePayer() external view returns (address payer);
+
+        /// Get the key that signed the current transaction
+        /// @return keyId The access key, or the sender if the transaction was signed by its root key
+        /// @return signatureType The signature type of the key
+        /// @return isAccessKey Whether the transaction was signed by a keychain access key
+        function signingKey() external view returns (address keyId, SignatureType signatureType, bool isAccessKey);
+
+        /// Get the index of the currently executing call within the transaction's batch of calls
+        /// @return index The call index, 0 for transactions without a batch
+        function callIndex() external view returns (uint64 index);
+    }
+}
@@ -37,12 +37,24 @@ sol! {
         function addValidator(address newValidatorAddress, bytes32 publicKey, bool active, string calldata inboundAddress, string calldata outboundAddress) external;
 
         /// Update validator information (only validator)
+        ///
+        /// Since Allegro-Moderato, a `publicKey` different from the current one does not replace
+        /// it but is registered as the validator's next public key, see `getNextPublicKey`.
         /// @param newValidatorAddress The new address for this validator
         /// @param publicKey The validator's new communication public publicKey
         /// @param inboundAddress The validator's inbound address `<hostname|ip>:<port>` for incoming connections
         /// @param outboundAddress The validator's outbound IP address `<ip>:<port>` for firewall whitelisting (IP only, no hostnames)
         function updateValidator(address newValidatorAddress, bytes32 publicKey, string calldata inboundAddress, string calldata outboundAddress) external;
 
+        /// Get the next public key of a validator (since Allegro-Moderato)
+        ///
+        /// A validator rotates its consensus key by registering the next key through
+        /// `updateValidator`. The next key takes effect at the following epoch boundary: it is
+        /// dealt a share in the DKG ceremony of that epoch in place of the current key.
+        /// @param validator The validator address
+        /// @return nextPublicKey The pending public key, or zero if there is none
+        function getNextPublicKey(address validator) external view returns (bytes32 nextPublicKey);
+
         /// Change validator active status (owner only)
         /// @param validator The validator address
         /// @param active Whether the validator should be active
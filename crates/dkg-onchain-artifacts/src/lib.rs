@@ -1,17 +1,36 @@
 //! Items that are written to chain.
 
+use std::convert::Infallible;
+
 use bytes::{Buf, BufMut};
 use commonware_codec::{
     EncodeSize, FixedSize as _, RangeCfg, Read, ReadExt as _, Write, varint::UInt,
 };
 use commonware_consensus::types::Epoch;
 use commonware_cryptography::{
-    Signer as _, Verifier as _,
+    Signer, Verifier as _,
     bls12381::primitives::{group, poly::Public, variant::MinSig},
-    ed25519::{PrivateKey, PublicKey, Signature},
+    ed25519::{PublicKey, Signature},
 };
 use commonware_utils::{quorum, set::Ordered};
 
+/// Returns the number of players whose acks each dealing of a ceremony with
+/// `n_players` players must carry.
+///
+/// `threshold_bps` is the chain's governed share of players in basis points.
+/// The result never drops below the `2f + 1` shares needed to recover the
+/// group key, which the bls12-381 DKG derives from the number of players, and
+/// never exceeds `n_players`.
+pub fn dealing_threshold(n_players: u32, threshold_bps: Option<u32>) -> u32 {
+    let governed = threshold_bps.map_or(0, |bps| {
+        (u64::from(n_players) * u64::from(bps))
+            .div_ceil(10_000)
+            .try_into()
+            .unwrap_or(u32::MAX)
+    });
+    governed.clamp(quorum(n_players), n_players)
+}
+
 /// A message from a player to a dealer, confirming the receipt of share.
 ///
 /// Contains the player's public key, as well as its signature over the
@@ -30,15 +49,37 @@ impl Ack {
     /// Create a new acknowledgment signed by `signer`.
     pub fn new(
         namespace: &[u8],
-        signer: PrivateKey,
+        signer: impl Signer<PublicKey = PublicKey, Signature = Signature>,
         player: PublicKey,
         epoch: Epoch,
         dealer: &PublicKey,
         commitment: &Public<MinSig>,
     ) -> Self {
+        Self::try_new(
+            namespace,
+            |namespace, payload| Ok::<_, Infallible>(signer.sign(namespace, payload)),
+            player,
+            epoch,
+            dealer,
+            commitment,
+        )
+        .unwrap_or_else(|never| match never {})
+    }
+
+    /// Create a new acknowledgment signed by `sign`, failing if it fails.
+    ///
+    /// `sign` is passed the namespace and the payload to sign.
+    pub fn try_new<E>(
+        namespace: &[u8],
+        sign: impl FnOnce(Option<&[u8]>, &[u8]) -> Result<Signature, E>,
+        player: PublicKey,
+        epoch: Epoch,
+        dealer: &PublicKey,
+        commitment: &Public<MinSig>,
+    ) -> Result<Self, E> {
         let payload = Self::construct_signature_payload(epoch, dealer, commitment);
-        let signature = signer.sign(Some(namespace), &payload);
-        Self { player, signature }
+        let signature = sign(Some(namespace), &payload)?;
+        Ok(Self { player, signature })
     }
 
     fn construct_signature_payload(
@@ -179,27 +220,55 @@ impl IntermediateOutcome {
     /// `(namespace, epoch, commitment, acks, reveals)` signed by the dealer.
     pub fn new(
         n_players: u16,
-        dealer_signer: &PrivateKey,
+        dealer_signer: &impl Signer<PublicKey = PublicKey, Signature = Signature>,
         namespace: &[u8],
         epoch: Epoch,
         commitment: Public<MinSig>,
         acks: Vec<Ack>,
         reveals: Vec<group::Share>,
     ) -> Self {
+        Self::try_new(
+            n_players,
+            dealer_signer.public_key(),
+            |namespace, payload| Ok::<_, Infallible>(dealer_signer.sign(namespace, payload)),
+            namespace,
+            epoch,
+            commitment,
+            acks,
+            reveals,
+        )
+        .unwrap_or_else(|never| match never {})
+    }
+
+    /// Like [`Self::new`], but signed by `sign` on behalf of `dealer`, failing
+    /// if it fails.
+    ///
+    /// `sign` is passed the namespace and the payload to sign.
+    #[expect(clippy::too_many_arguments)]
+    pub fn try_new<E>(
+        n_players: u16,
+        dealer: PublicKey,
+        sign: impl FnOnce(Option<&[u8]>, &[u8]) -> Result<Signature, E>,
+        namespace: &[u8],
+        epoch: Epoch,
+        commitment: Public<MinSig>,
+        acks: Vec<Ack>,
+        reveals: Vec<group::Share>,
+    ) -> Result<Self, E> {
         // Sign the resharing outcome
         let payload =
             Self::signature_payload_from_parts(n_players, epoch, &commitment, &acks, &reveals);
-        let dealer_signature = dealer_signer.sign(Some(namespace), payload.as_ref());
+        let dealer_signature = sign(Some(namespace), payload.as_ref())?;
 
-        Self {
+        Ok(Self {
             n_players,
-            dealer: dealer_signer.public_key(),
+            dealer,
             dealer_signature,
             epoch,
             commitment,
             acks,
             reveals,
-        }
+        })
     }
 
     /// Creates a new intermediate ceremony outcome.
@@ -217,27 +286,53 @@ impl IntermediateOutcome {
     /// `(namespace, epoch, commitment, acks, reveals)` signed by the dealer.
     pub fn new_pre_allegretto(
         n_players: u16,
-        dealer_signer: &PrivateKey,
+        dealer_signer: &impl Signer<PublicKey = PublicKey, Signature = Signature>,
         namespace: &[u8],
         epoch: Epoch,
         commitment: Public<MinSig>,
         acks: Vec<Ack>,
         reveals: Vec<group::Share>,
     ) -> Self {
+        Self::try_new_pre_allegretto(
+            n_players,
+            dealer_signer.public_key(),
+            |namespace, payload| Ok::<_, Infallible>(dealer_signer.sign(namespace, payload)),
+            namespace,
+            epoch,
+            commitment,
+            acks,
+            reveals,
+        )
+        .unwrap_or_else(|never| match never {})
+    }
+
+    /// Like [`Self::new_pre_allegretto`], but signed by `sign` on behalf of
+    /// `dealer`, failing if it fails.
+    #[expect(clippy::too_many_arguments)]
+    pub fn try_new_pre_allegretto<E>(
+        n_players: u16,
+        dealer: PublicKey,
+        sign: impl FnOnce(Option<&[u8]>, &[u8]) -> Result<Signature, E>,
+        namespace: &[u8],
+        epoch: Epoch,
+        commitment: Public<MinSig>,
+        acks: Vec<Ack>,
+        reveals: Vec<group::Share>,
+    ) -> Result<Self, E> {
         // Sign the resharing outcome
         let payload =
             Self::signature_payload_from_parts_pre_allegretto(epoch, &commitment, &acks, &reveals);
-        let dealer_signature = dealer_signer.sign(Some(namespace), payload.as_ref());
+        let dealer_signature = sign(Some(namespace), payload.as_ref())?;
 
-        Self {
+        Ok(Self {
             n_players,
-            dealer: dealer_signer.public_key(),
+            dealer,
             dealer_signature,
             epoch,
             commitment,
             acks,
             reveals,
-        }
+        })
     }
 
     /// Verifies the intermediate outcome's signature.
@@ -253,6 +348,48 @@ impl IntermediateOutcome {
             .verify(Some(namespace), &payload, &self.dealer_signature)
     }
 
+    /// Verifies the intermediate outcome's signature and shape.
+    ///
+    /// Besides the dealer's signature, this checks that the commitment has the
+    /// degree the DKG derives from `n_players`, that every player was either
+    /// acked or had its share revealed exactly once, and that at least
+    /// `threshold` players acked the dealing (see [`dealing_threshold`]).
+    /// Because these checks are relative to `n_players`, they hold regardless
+    /// of how much the set of players changed from the previous ceremony.
+    /// Callers must still check that `n_players` matches the players of the
+    /// ceremony.
+    pub fn verify_with_threshold(&self, namespace: &[u8], threshold: u32) -> bool {
+        let n_players = u32::from(self.n_players);
+        if self.commitment.required() != quorum(n_players)
+            || (self.acks.len() as u32) < threshold
+            || self.acks.len() + self.reveals.len() != self.n_players as usize
+        {
+            return false;
+        }
+
+        let mut players = self.acks.iter().map(Ack::player).collect::<Vec<_>>();
+        players.sort();
+        players.dedup();
+        if players.len() != self.acks.len() {
+            return false;
+        }
+
+        let mut revealed = self
+            .reveals
+            .iter()
+            .map(|share| share.index)
+            .collect::<Vec<_>>();
+        revealed.sort_unstable();
+        revealed.dedup();
+        if revealed.len() != self.reveals.len()
+            || revealed.last().is_some_and(|index| *index >= n_players)
+        {
+            return false;
+        }
+
+        self.verify(namespace)
+    }
+
     /// Verifies the intermediate outcome's signature.
     ///
     /// This method constructs a signature without the number players. This is
@@ -318,6 +455,11 @@ impl IntermediateOutcome {
         buf
     }
 
+    /// Returns the number of players of the ceremony the outcome was dealt in.
+    pub fn n_players(&self) -> u16 {
+        self.n_players
+    }
+
     pub fn acks(&self) -> &[Ack] {
         &self.acks
     }
@@ -410,7 +552,10 @@ mod tests {
     use commonware_codec::{DecodeExt as _, Encode as _};
     use commonware_cryptography::{
         PrivateKeyExt as _, Signer as _,
-        bls12381::{dkg, primitives::variant::MinSig},
+        bls12381::{
+            dkg,
+            primitives::{poly::Public, variant::MinSig},
+        },
         ed25519::{PrivateKey, PublicKey},
     };
     use commonware_utils::{set::Ordered, union};
@@ -419,7 +564,7 @@ mod tests {
     const ACK_NAMESPACE: &[u8] = b"_DKG_ACK";
     const OUTCOME_NAMESPACE: &[u8] = b"_DKG_OUTCOME";
 
-    use crate::{Ack, PublicOutcome};
+    use crate::{Ack, PublicOutcome, dealing_threshold};
 
     use super::IntermediateOutcome;
 
@@ -573,4 +718,145 @@ mod tests {
             public_outcome,
         );
     }
+
+    fn acks_from(players: std::ops::Range<usize>, commitment: &Public<MinSig>) -> Vec<Ack> {
+        players
+            .map(|i| {
+                Ack::new(
+                    &union(b"test", ACK_NAMESPACE),
+                    four_private_keys()[i].clone(),
+                    four_public_keys()[i].clone(),
+                    42,
+                    &four_public_keys()[0],
+                    commitment,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dealing_outcome_covering_all_players_verifies() {
+        let (_, commitment, shares) = dkg::Dealer::<_, MinSig>::new(
+            &mut StdRng::from_seed([0; 32]),
+            None,
+            four_public_keys(),
+        );
+        let dealing_outcome = IntermediateOutcome::new(
+            4,
+            &four_private_keys()[0],
+            &union(b"test", OUTCOME_NAMESPACE),
+            42,
+            commitment.clone(),
+            acks_from(0..3, &commitment),
+            vec![shares[3].clone()],
+        );
+        assert!(dealing_outcome.verify_with_threshold(
+            &union(b"test", OUTCOME_NAMESPACE),
+            dealing_threshold(4, None),
+        ));
+    }
+
+    #[test]
+    fn dealing_outcome_missing_a_player_is_rejected() {
+        let (_, commitment, _) = dkg::Dealer::<_, MinSig>::new(
+            &mut StdRng::from_seed([0; 32]),
+            None,
+            four_public_keys(),
+        );
+        let dealing_outcome = IntermediateOutcome::new(
+            4,
+            &four_private_keys()[0],
+            &union(b"test", OUTCOME_NAMESPACE),
+            42,
+            commitment.clone(),
+            acks_from(0..3, &commitment),
+            vec![],
+        );
+        assert!(!dealing_outcome.verify_with_threshold(
+            &union(b"test", OUTCOME_NAMESPACE),
+            dealing_threshold(4, None),
+        ));
+    }
+
+    #[test]
+    fn dealing_outcome_with_too_many_reveals_is_rejected() {
+        let (_, commitment, shares) = dkg::Dealer::<_, MinSig>::new(
+            &mut StdRng::from_seed([0; 32]),
+            None,
+            four_public_keys(),
+        );
+        let dealing_outcome = IntermediateOutcome::new(
+            4,
+            &four_private_keys()[0],
+            &union(b"test", OUTCOME_NAMESPACE),
+            42,
+            commitment.clone(),
+            acks_from(0..2, &commitment),
+            vec![shares[2].clone(), shares[3].clone()],
+        );
+        assert!(!dealing_outcome.verify_with_threshold(
+            &union(b"test", OUTCOME_NAMESPACE),
+            dealing_threshold(4, None),
+        ));
+    }
+
+    #[test]
+    fn dealing_outcome_for_a_different_number_of_players_is_rejected() {
+        // A commitment dealt to four players has the degree derived from four
+        // players, not from seven.
+        let (_, commitment, shares) = dkg::Dealer::<_, MinSig>::new(
+            &mut StdRng::from_seed([0; 32]),
+            None,
+            four_public_keys(),
+        );
+        let dealing_outcome = IntermediateOutcome::new(
+            7,
+            &four_private_keys()[0],
+            &union(b"test", OUTCOME_NAMESPACE),
+            42,
+            commitment.clone(),
+            acks_from(0..4, &commitment),
+            shares[..3].to_vec(),
+        );
+        assert!(!dealing_outcome.verify_with_threshold(
+            &union(b"test", OUTCOME_NAMESPACE),
+            dealing_threshold(7, None),
+        ));
+    }
+
+    #[test]
+    fn dealing_threshold_is_bounded_by_quorum_and_players() {
+        assert_eq!(dealing_threshold(4, None), 3);
+        assert_eq!(dealing_threshold(4, Some(5_000)), 3);
+        assert_eq!(dealing_threshold(4, Some(10_000)), 4);
+        assert_eq!(dealing_threshold(10, Some(8_000)), 8);
+        assert_eq!(dealing_threshold(10, Some(8_001)), 9);
+        assert_eq!(dealing_threshold(10, Some(u32::MAX)), 10);
+    }
+
+    #[test]
+    fn dealing_outcome_below_governed_threshold_is_rejected() {
+        let (_, commitment, shares) = dkg::Dealer::<_, MinSig>::new(
+            &mut StdRng::from_seed([0; 32]),
+            None,
+            four_public_keys(),
+        );
+        let dealing_outcome = IntermediateOutcome::new(
+            4,
+            &four_private_keys()[0],
+            &union(b"test", OUTCOME_NAMESPACE),
+            42,
+            commitment.clone(),
+            acks_from(0..3, &commitment),
+            vec![shares[3].clone()],
+        );
+
+        // The signature alone is still valid, but all four players must ack
+        // the dealing if governance requires all of them.
+        assert!(dealing_outcome.verify(&union(b"test", OUTCOME_NAMESPACE)));
+        assert!(!dealing_outcome.verify_with_threshold(
+            &union(b"test", OUTCOME_NAMESPACE),
+            dealing_threshold(4, Some(10_000)),
+        ));
+    }
 }
@@ -71,6 +71,8 @@ pub const TEST_MNEMONIC: &str = "test test test test test test test test test te
 #[derive(Default, Debug)]
 pub struct Builder {
     allegretto_time: Option<u64>,
+    allegro_moderato_time: Option<u64>,
+    dkg_threshold_bps: Option<u32>,
     epoch_length: Option<u64>,
     public_polynomial: Option<PublicPolynomial>,
     validators: Option<Peers>,
@@ -81,6 +83,8 @@ impl Builder {
     pub fn new() -> Self {
         Self {
             allegretto_time: None,
+            allegro_moderato_time: None,
+            dkg_threshold_bps: None,
             epoch_length: None,
             public_polynomial: None,
             validators: None,
@@ -95,6 +99,20 @@ impl Builder {
         }
     }
 
+    pub fn set_allegro_moderato_time(self, allegro_moderato_time: Option<u64>) -> Self {
+        Self {
+            allegro_moderato_time,
+            ..self
+        }
+    }
+
+    pub fn set_dkg_threshold_bps(self, dkg_threshold_bps: Option<u32>) -> Self {
+        Self {
+            dkg_threshold_bps,
+            ..self
+        }
+    }
+
     pub fn set_write_validators_into_genesis(self, write_validators_into_genesis: bool) -> Self {
         Self {
             write_validators_into_genesis,
@@ -133,6 +151,8 @@ impl Builder {
     pub fn launch(self) -> eyre::Result<ExecutionRuntime> {
         let Self {
             allegretto_time,
+            allegro_moderato_time,
+            dkg_threshold_bps,
             epoch_length,
             public_polynomial,
             validators,
@@ -160,6 +180,22 @@ impl Builder {
             .insert_value("validators".to_string(), validators.clone())
             .wrap_err("failed to insert validators into genesis")?;
 
+        if let Some(allegro_moderato_time) = allegro_moderato_time {
+            genesis
+                .config
+                .extra_fields
+                .insert_value("allegroModeratoTime".to_string(), allegro_moderato_time)
+                .wrap_err("failed to insert allegro-moderato timestamp into genesis")?;
+        }
+
+        if let Some(dkg_threshold_bps) = dkg_threshold_bps {
+            genesis
+                .config
+                .extra_fields
+                .insert_value("dkgThresholdBps".to_string(), dkg_threshold_bps)
+                .wrap_err("failed to insert dkg threshold into genesis")?;
+        }
+
         if let Some(allegretto_time) = allegretto_time {
             genesis
                 .config
@@ -436,6 +472,55 @@ impl ExecutionRuntime {
                                 .unwrap();
                             let _ = response.send(receipt);
                         }
+                        Message::UpdateValidator(update_validator) => {
+                            let UpdateValidator {
+                                http_url,
+                                public_key,
+                                next_public_key,
+                                addr,
+                                response,
+                            } = *update_validator;
+                            let validator_config = IValidatorConfig::new(
+                                VALIDATOR_CONFIG_ADDRESS,
+                                ProviderBuilder::new().connect_http(http_url.clone()),
+                            );
+                            let public_key: B256 = public_key.encode().as_ref().try_into().unwrap();
+                            let entry = validator_config
+                                .getValidators()
+                                .call()
+                                .await
+                                .unwrap()
+                                .into_iter()
+                                .find(|validator| validator.publicKey == public_key)
+                                .expect("the validator must have an entry in the contract");
+                            // Validators can only update their own entry, so
+                            // the call is signed with the validator's key.
+                            let index = (0..u8::MAX as u32)
+                                .find(|&index| validator(index) == entry.validatorAddress)
+                                .expect("validator entries are created from the test mnemonic");
+                            let wallet = MnemonicBuilder::from_phrase_nth(
+                                TEST_MNEMONIC,
+                                VALIDATOR_START_INDEX + index,
+                            );
+                            let provider =
+                                ProviderBuilder::new().wallet(wallet).connect_http(http_url);
+                            let validator_config =
+                                IValidatorConfig::new(VALIDATOR_CONFIG_ADDRESS, provider);
+                            let receipt = validator_config
+                                .updateValidator(
+                                    entry.validatorAddress,
+                                    next_public_key.encode().as_ref().try_into().unwrap(),
+                                    addr.to_string(),
+                                    addr.to_string(),
+                                )
+                                .send()
+                                .await
+                                .unwrap()
+                                .get_receipt()
+                                .await
+                                .unwrap();
+                            let _ = response.send(receipt);
+                        }
                         Message::SpawnNode {
                             name,
                             config,
@@ -526,6 +611,34 @@ impl ExecutionRuntime {
             .wrap_err("the execution runtime dropped the response channel before sending a receipt")
     }
 
+    /// Registers `next_public_key` and `addr` on the entry of the validator
+    /// with `public_key`, sending the call from the validator's own address.
+    ///
+    /// This is how a validator rotates its key.
+    pub async fn update_validator(
+        &self,
+        http_url: Url,
+        public_key: PublicKey,
+        next_public_key: PublicKey,
+        addr: SocketAddr,
+    ) -> eyre::Result<TransactionReceipt> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.to_runtime
+            .send(
+                UpdateValidator {
+                    http_url,
+                    public_key,
+                    next_public_key,
+                    addr,
+                    response: tx,
+                }
+                .into(),
+            )
+            .wrap_err("the execution runtime went away")?;
+        rx.await
+            .wrap_err("the execution runtime dropped the response channel before sending a receipt")
+    }
+
     pub async fn remove_validator(
         &self,
         http_url: Url,
@@ -748,6 +861,7 @@ pub async fn launch_execution_node<P: AsRef<Path>>(
 enum Message {
     AddValidator(Box<AddValidator>),
     ChangeValidatorStatus(Box<ChangeValidatorStatus>),
+    UpdateValidator(Box<UpdateValidator>),
     SpawnNode {
         name: String,
         config: ExecutionNodeConfig,
@@ -769,6 +883,12 @@ impl From<ChangeValidatorStatus> for Message {
     }
 }
 
+impl From<UpdateValidator> for Message {
+    fn from(value: UpdateValidator) -> Self {
+        Self::UpdateValidator(value.into())
+    }
+}
+
 #[derive(Debug)]
 struct AddValidator {
     /// URL of the node to send this to.
@@ -788,6 +908,17 @@ struct ChangeValidatorStatus {
     response: tokio::sync::oneshot::Sender<TransactionReceipt>,
 }
 
+#[derive(Debug)]
+struct UpdateValidator {
+    /// URL of the node to send this to.
+    http_url: Url,
+    /// The current public key of the validator, used to find its entry.
+    public_key: PublicKey,
+    next_public_key: PublicKey,
+    addr: SocketAddr,
+    response: tokio::sync::oneshot::Sender<TransactionReceipt>,
+}
+
 pub fn admin() -> Address {
     address(ADMIN_INDEX)
 }
@@ -75,6 +75,12 @@ pub struct Setup {
     /// Mutually exclusive with `allegretto_in_seconds`.
     pub allegretto_in_seconds: Option<u64>,
 
+    /// A specific value to set allegro_moderato_time to in chainspec.
+    pub allegro_moderato_time: Option<u64>,
+
+    /// A specific value to set dkg_threshold_bps to in chainspec.
+    pub dkg_threshold_bps: Option<u32>,
+
     /// Whether validators should be written into the genesis block.
     pub no_validators_in_genesis: bool,
 }
@@ -94,6 +100,8 @@ impl Setup {
             connect_execution_layer_nodes: false,
             allegretto_time: None,
             allegretto_in_seconds: None,
+            allegro_moderato_time: None,
+            dkg_threshold_bps: None,
             no_validators_in_genesis: false,
         }
     }
@@ -157,6 +165,22 @@ impl Setup {
         }
     }
 
+    /// Sets `allegro_moderato_time`.
+    pub fn allegro_moderato_time(self, allegro_moderato_time: u64) -> Self {
+        Self {
+            allegro_moderato_time: Some(allegro_moderato_time),
+            ..self
+        }
+    }
+
+    /// Sets `dkg_threshold_bps`.
+    pub fn dkg_threshold_bps(self, dkg_threshold_bps: u32) -> Self {
+        Self {
+            dkg_threshold_bps: Some(dkg_threshold_bps),
+            ..self
+        }
+    }
+
     pub fn no_validators_in_genesis(self) -> Self {
         Self {
             no_validators_in_genesis: true,
@@ -188,6 +212,8 @@ pub async fn setup_validators(
         epoch_length,
         allegretto_in_seconds,
         allegretto_time,
+        allegro_moderato_time,
+        dkg_threshold_bps,
         no_validators_in_genesis,
     }: Setup,
 ) -> (Vec<TestingNode>, ExecutionRuntime) {
@@ -246,6 +272,8 @@ pub async fn setup_validators(
         .with_public_polynomial(polynomial)
         .with_validators(peers)
         .set_allegretto_time(allegretto_time)
+        .set_allegro_moderato_time(allegro_moderato_time)
+        .set_dkg_threshold_bps(dkg_threshold_bps)
         .set_write_validators_into_genesis(!no_validators_in_genesis)
         .launch()
         .unwrap();
@@ -274,11 +302,14 @@ pub async fn setup_validators(
             context: context.with_label(&uid),
             fee_recipient: alloy_primitives::Address::ZERO,
             execution_node: None,
+            prebuild: Default::default(),
             blocker: oracle.control(private_key.public_key()),
             peer_manager: oracle.socket_manager(),
             partition_prefix: uid.clone(),
             share,
-            signer: private_key.clone(),
+            signer: private_key.clone().into(),
+            keystore_passphrase: None,
+            checkpoint: None,
             mailbox_size: 1024,
             deque_size: 10,
             time_to_propose: Duration::from_secs(2),
@@ -290,6 +321,7 @@ pub async fn setup_validators(
             new_payload_wait_time: Duration::from_millis(200),
             time_to_build_subblock: Duration::from_millis(100),
             subblock_broadcast_interval: Duration::from_millis(50),
+            subblock_partners: Default::default(),
         };
 
         nodes.push(TestingNode::new(
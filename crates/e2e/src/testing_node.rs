@@ -4,7 +4,9 @@ use crate::execution_runtime::{self, ExecutionNode, ExecutionNodeConfig, Executi
 use commonware_cryptography::ed25519::PublicKey;
 use commonware_p2p::simulated::{Control, Oracle, SocketManager};
 use commonware_runtime::{Handle, deterministic::Context};
+use reth_config::config::EtlConfig;
 use reth_db::{Database, DatabaseEnv, mdbx::DatabaseArguments, open_db_read_only};
+use reth_ethereum::chainspec::EthChainSpec as _;
 use reth_ethereum::{
     provider::{
         DatabaseProviderFactory, ProviderFactory,
@@ -13,9 +15,23 @@ use reth_ethereum::{
     storage::BlockNumReader,
 };
 use reth_node_builder::NodeTypesWithDBAdapter;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tempo_chainspec::spec::TempoChainSpec;
 use tempo_commonware_node::consensus;
-use tempo_node::node::TempoNode;
+use tempo_light_client::LightClient;
+use tempo_node::{
+    node::TempoNode,
+    rpc::{ConsensusHandle, SnapshotInfo},
+};
+use tempo_snapshot::{
+    Checkpoint,
+    import::{Imported, import_state},
+};
 use tracing::{debug, instrument};
 
 /// A testing node that can start and stop both consensus and execution layers.
@@ -30,6 +46,8 @@ pub struct TestingNode {
     consensus_config: consensus::Builder<Control<PublicKey>, Context, SocketManager<PublicKey>>,
     /// Running consensus handle (None if consensus is stopped)
     consensus_handle: Option<Handle<eyre::Result<()>>>,
+    /// Consensus state served to RPC, installed by the running consensus engine
+    consensus_rpc: ConsensusHandle,
     /// Path to the execution node's data directory
     execution_node_datadir: PathBuf,
     /// Running execution node (None if execution is stopped)
@@ -66,6 +84,7 @@ impl TestingNode {
             oracle,
             consensus_config,
             consensus_handle: None,
+            consensus_rpc: ConsensusHandle::default(),
             execution_node: None,
             execution_node_datadir,
             execution_runtime,
@@ -184,6 +203,8 @@ impl TestingNode {
             .try_init()
             .await
             .expect("must be able to start the engine");
+        self.consensus_rpc = ConsensusHandle::default();
+        engine.install_rpc(&self.consensus_rpc);
 
         let pending = self
             .oracle
@@ -233,6 +254,12 @@ impl TestingNode {
             .register(7)
             .await
             .unwrap();
+        let decryption = self
+            .oracle
+            .control(self.public_key.clone())
+            .register(8)
+            .await
+            .unwrap();
 
         let consensus_handle = engine.start(
             pending,
@@ -243,6 +270,7 @@ impl TestingNode {
             dkg,
             boundary_certs,
             subblocks,
+            decryption,
         );
 
         self.consensus_handle = Some(consensus_handle);
@@ -361,6 +389,79 @@ impl TestingNode {
             .expect("consensus is not running")
     }
 
+    /// Writes a state snapshot at the latest finalized epoch boundary to `path`, as
+    /// `admin_exportSnapshot` does.
+    ///
+    /// The snapshot is written on a separate thread running tokio, while the consensus engine
+    /// keeps serving the certificates of the snapshot.
+    ///
+    /// # Panics
+    /// Panics if consensus is not running.
+    pub async fn export_snapshot(&self, path: PathBuf) -> eyre::Result<SnapshotInfo> {
+        let provider = self
+            .consensus_rpc
+            .provider()
+            .expect("consensus is not running")
+            .clone();
+        let (tx, rx) = futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new()
+                .expect("must be able to create a runtime to export the snapshot");
+            let _ = tx.send(runtime.block_on(provider.export_snapshot(path)));
+        });
+        rx.await
+            .expect("snapshot export thread must send its result")
+    }
+
+    /// Imports the state snapshot at `path` into the node's new database and makes consensus
+    /// start from the snapshot's checkpoint, as `--snapshot.import` does.
+    ///
+    /// # Panics
+    /// Panics if the node was started before, or if the snapshot fails verification or import.
+    pub fn import_snapshot(&mut self, chain_spec: Arc<TempoChainSpec>, path: &Path) -> Checkpoint {
+        assert!(
+            self.execution_database.is_none(),
+            "snapshots can only be imported into new nodes"
+        );
+
+        let mut reader = BufReader::new(File::open(path).expect("failed to open snapshot"));
+        let mut light_client = LightClient::from_genesis(
+            chain_spec
+                .info
+                .epoch_length()
+                .expect("chainspec must define an epoch length"),
+            chain_spec.genesis_header(),
+        )
+        .expect("genesis must carry the initial DKG outcome");
+        let checkpoint = tempo_snapshot::read_header(&mut reader)
+            .expect("failed to read snapshot header")
+            .verify(&mut light_client)
+            .expect("snapshot must pass verification");
+
+        let database = Arc::new(
+            reth_db::init_db(
+                self.execution_node_datadir.join("db"),
+                DatabaseArguments::default(),
+            )
+            .expect("failed to init database")
+            .with_metrics(),
+        );
+        let factory = ProviderFactory::<NodeTypesWithDBAdapter<TempoNode, _>>::new(
+            database.clone(),
+            chain_spec,
+            StaticFileProvider::read_write(self.execution_node_datadir.join("static_files"))
+                .expect("failed to open static files"),
+        )
+        .expect("failed to create provider factory");
+        let imported = import_state(&factory, &checkpoint, reader, EtlConfig::default())
+            .expect("failed to import snapshot state");
+        assert_eq!(imported, Imported::Imported);
+
+        self.execution_database = Some(database);
+        self.consensus_config.checkpoint = Some(checkpoint.clone());
+        checkpoint
+    }
+
     /// Get a blockchain provider for the execution node.
     ///
     /// # Panics
@@ -14,6 +14,7 @@ use crate::{CONSENSUS_NODE_PREFIX, Setup, execution_runtime::validator, setup_va
 #[test_traced]
 fn after_hardfork_validator_is_added_to_a_set_of_one() {
     AssertValidatorIsAdded {
+        how_many_added: 1,
         how_many_initial: 1,
         epoch_length: 20,
         allegretto_at_genesis: false,
@@ -24,6 +25,7 @@ fn after_hardfork_validator_is_added_to_a_set_of_one() {
 #[test_traced]
 fn after_hardfork_validator_is_added_to_a_set_of_three() {
     AssertValidatorIsAdded {
+        how_many_added: 1,
         how_many_initial: 3,
         epoch_length: 30,
         allegretto_at_genesis: false,
@@ -34,6 +36,7 @@ fn after_hardfork_validator_is_added_to_a_set_of_three() {
 #[test_traced]
 fn after_hardfork_validator_is_removed_from_set_of_two() {
     AssertValidatorIsRemoved {
+        how_many_removed: 1,
         how_many_initial: 2,
         epoch_length: 20,
         allegretto_at_genesis: false,
@@ -44,6 +47,7 @@ fn after_hardfork_validator_is_removed_from_set_of_two() {
 #[test_traced]
 fn after_hardfork_validator_is_removed_from_set_of_four() {
     AssertValidatorIsRemoved {
+        how_many_removed: 1,
         how_many_initial: 4,
         epoch_length: 40,
         allegretto_at_genesis: false,
@@ -54,6 +58,7 @@ fn after_hardfork_validator_is_removed_from_set_of_four() {
 #[test_traced]
 fn with_allegretto_at_genesis_validator_is_added_to_a_set_of_one() {
     AssertValidatorIsAdded {
+        how_many_added: 1,
         how_many_initial: 1,
         epoch_length: 20,
         allegretto_at_genesis: true,
@@ -64,6 +69,7 @@ fn with_allegretto_at_genesis_validator_is_added_to_a_set_of_one() {
 #[test_traced]
 fn with_allegretto_at_genesis_validator_is_added_to_a_set_of_three() {
     AssertValidatorIsAdded {
+        how_many_added: 1,
         how_many_initial: 3,
         epoch_length: 30,
         allegretto_at_genesis: true,
@@ -74,6 +80,7 @@ fn with_allegretto_at_genesis_validator_is_added_to_a_set_of_three() {
 #[test_traced]
 fn with_allegretto_at_genesis_validator_is_removed_from_set_of_two() {
     AssertValidatorIsRemoved {
+        how_many_removed: 1,
         how_many_initial: 2,
         epoch_length: 20,
         allegretto_at_genesis: true,
@@ -84,6 +91,7 @@ fn with_allegretto_at_genesis_validator_is_removed_from_set_of_two() {
 #[test_traced]
 fn with_allegretto_at_genesis_validator_is_removed_from_set_of_four() {
     AssertValidatorIsRemoved {
+        how_many_removed: 1,
         how_many_initial: 4,
         epoch_length: 40,
         allegretto_at_genesis: true,
@@ -91,7 +99,41 @@ fn with_allegretto_at_genesis_validator_is_removed_from_set_of_four() {
     .run();
 }
 
+#[test_traced]
+fn with_allegretto_at_genesis_two_validators_are_added_to_a_set_of_two() {
+    AssertValidatorIsAdded {
+        how_many_added: 2,
+        how_many_initial: 2,
+        epoch_length: 30,
+        allegretto_at_genesis: true,
+    }
+    .run();
+}
+
+#[test_traced]
+fn with_allegretto_at_genesis_three_validators_are_added_to_a_set_of_one() {
+    AssertValidatorIsAdded {
+        how_many_added: 3,
+        how_many_initial: 1,
+        epoch_length: 30,
+        allegretto_at_genesis: true,
+    }
+    .run();
+}
+
+#[test_traced]
+fn with_allegretto_at_genesis_two_validators_are_removed_from_set_of_five() {
+    AssertValidatorIsRemoved {
+        how_many_removed: 2,
+        how_many_initial: 5,
+        epoch_length: 50,
+        allegretto_at_genesis: true,
+    }
+    .run();
+}
+
 struct AssertValidatorIsAdded {
+    how_many_added: u32,
     how_many_initial: u32,
     epoch_length: u64,
     allegretto_at_genesis: bool,
@@ -100,6 +142,7 @@ struct AssertValidatorIsAdded {
 impl AssertValidatorIsAdded {
     fn run(self) {
         let Self {
+            how_many_added,
             how_many_initial,
             epoch_length,
             allegretto_at_genesis,
@@ -108,7 +151,7 @@ impl AssertValidatorIsAdded {
 
         let setup = Setup::new()
             .how_many_signers(how_many_initial)
-            .how_many_verifiers(1)
+            .how_many_verifiers(how_many_added)
             .epoch_length(epoch_length);
 
         let setup = if allegretto_at_genesis {
@@ -121,22 +164,16 @@ impl AssertValidatorIsAdded {
         let executor = Runner::from(cfg);
 
         executor.start(|context| async move {
-            let (mut validators, execution_runtime) =
-                setup_validators(context.clone(), setup).await;
+            let (validators, execution_runtime) = setup_validators(context.clone(), setup).await;
 
-            let mut new_validator = {
-                let idx = validators
-                    .iter()
-                    .position(|node| node.consensus_config().share.is_none())
-                    .expect("at least one node must be a verifier, i.e. not have a share");
-                validators.remove(idx)
-            };
-
-            assert!(
-                validators
-                    .iter()
-                    .all(|node| node.consensus_config().share.is_some()),
-                "must have removed the one non-signer node; must be left with only signers",
+            let (mut new_validators, mut validators): (Vec<_>, Vec<_>) = validators
+                .into_iter()
+                .partition(|node| node.consensus_config().share.is_none());
+
+            assert_eq!(
+                new_validators.len() as u32,
+                how_many_added,
+                "must have split off all non-signer nodes; must be left with only signers",
             );
 
             join_all(validators.iter_mut().map(|v| v.start())).await;
@@ -200,34 +237,41 @@ impl AssertValidatorIsAdded {
                 }
             }
 
-            // Now add and start the new validator.
-            let receipt = execution_runtime
-                .add_validator(
-                    http_url.clone(),
-                    // XXX: The addValidator call above adding the initial set
-                    // adds validators 0..validators.len() (i.e. exclusive validators.len())
-                    validator(validators.len() as u32),
-                    new_validator.public_key().clone(),
-                    SocketAddr::from(([127, 0, 0, 1], (validators.len() + 1) as u16)),
-                )
-                .await
-                .unwrap();
+            // Now add and start the new validators. They are all added within
+            // the same epoch so that a single ceremony has to reshare to all
+            // of them at once.
+            for (i, new_validator) in new_validators.iter().enumerate() {
+                // XXX: The addValidator call above adding the initial set
+                // adds validators 0..validators.len() (i.e. exclusive validators.len())
+                let index = validators.len() + i;
+                let receipt = execution_runtime
+                    .add_validator(
+                        http_url.clone(),
+                        validator(index as u32),
+                        new_validator.public_key().clone(),
+                        SocketAddr::from(([127, 0, 0, 1], (index + 1) as u16)),
+                    )
+                    .await
+                    .unwrap();
+
+                tracing::debug!(
+                    block.number = receipt.block_number,
+                    "addValidator call returned receipt"
+                );
+            }
 
-            tracing::debug!(
-                block.number = receipt.block_number,
-                "addValidator call returned receipt"
-            );
+            join_all(new_validators.iter_mut().map(|v| v.start())).await;
+            tracing::info!("new validators were started");
 
-            let _new_validator = new_validator.start().await;
-            tracing::info!("new validator was started");
+            let how_many_after = how_many_initial + how_many_added;
 
             // First, all initial validator nodes must observe a ceremony with
-            // dealers = how_many_initial, players = how_many_initial + 1.
+            // dealers = how_many_initial, players = how_many_after.
             loop {
                 context.sleep(Duration::from_secs(1)).await;
 
                 let mut dealers_is_initial = 0;
-                let mut players_is_initial_plus_one = 0;
+                let mut players_is_after = 0;
 
                 let metrics = context.encode();
                 for line in metrics.lines() {
@@ -250,7 +294,7 @@ impl AssertValidatorIsAdded {
                             panic!(
                                 "observed dealers = {value} before observing \
                             dealers = {how_many_initial}, \
-                            players = {how_many_initial} +1",
+                            players = {how_many_after}",
                             );
                         }
                         dealers_is_initial += (value as u32 == how_many_initial) as u32;
@@ -258,24 +302,21 @@ impl AssertValidatorIsAdded {
 
                     if metric.ends_with("_dkg_manager_ceremony_players") {
                         let value = value.parse::<u64>().unwrap();
-                        players_is_initial_plus_one +=
-                            (value as u32 == how_many_initial + 1) as u32;
+                        players_is_after += (value as u32 == how_many_after) as u32;
                     }
                 }
-                if dealers_is_initial == how_many_initial
-                    && players_is_initial_plus_one == how_many_initial
-                {
+                if dealers_is_initial == how_many_initial && players_is_after == how_many_initial {
                     break;
                 }
             }
 
-            // Then, all how_many_initial + 1 nodes must observe an epoch with the
-            // same number of participants (= how_many_intial + 1).
+            // Then, all how_many_after nodes must observe an epoch with the
+            // same number of participants (= how_many_after).
             loop {
                 context.sleep(Duration::from_secs(1)).await;
 
                 let metrics = context.encode();
-                let mut participants_is_initial_plus_one = 0;
+                let mut participants_is_after = 0;
 
                 for line in metrics.lines() {
                     if !line.starts_with(CONSENSUS_NODE_PREFIX) {
@@ -287,11 +328,10 @@ impl AssertValidatorIsAdded {
 
                     if metric.ends_with("_epoch_manager_latest_participants") {
                         let value = value.parse::<u64>().unwrap();
-                        participants_is_initial_plus_one +=
-                            (value as u32 == how_many_initial + 1) as u32;
+                        participants_is_after += (value as u32 == how_many_after) as u32;
                     }
                 }
-                if participants_is_initial_plus_one == how_many_initial + 1 {
+                if participants_is_after == how_many_after {
                     break;
                 }
             }
@@ -300,6 +340,7 @@ impl AssertValidatorIsAdded {
 }
 
 struct AssertValidatorIsRemoved {
+    how_many_removed: u32,
     how_many_initial: u32,
     epoch_length: u64,
     allegretto_at_genesis: bool,
@@ -308,6 +349,7 @@ struct AssertValidatorIsRemoved {
 impl AssertValidatorIsRemoved {
     fn run(self) {
         let Self {
+            how_many_removed,
             how_many_initial,
             epoch_length,
             allegretto_at_genesis,
@@ -392,30 +434,34 @@ impl AssertValidatorIsRemoved {
                 }
             }
 
-            let receipt = execution_runtime
-                // XXX: The addValidator call above adding the initial set
-                // adds validators 0..validators.len(). So this is the last of
-                // the validators
-                .change_validator_status(http_url, validator(validators.len() as u32 - 1), false)
-                .await
-                .unwrap();
+            // XXX: The addValidator call above adding the initial set
+            // adds validators 0..validators.len(). So these are the last
+            // how_many_removed of the validators.
+            for index in how_many_initial - how_many_removed..how_many_initial {
+                let receipt = execution_runtime
+                    .change_validator_status(http_url.clone(), validator(index), false)
+                    .await
+                    .unwrap();
+
+                tracing::debug!(
+                    block.number = receipt.block_number,
+                    "chanegValidatorStatus call returned receipt"
+                );
+            }
 
-            tracing::debug!(
-                block.number = receipt.block_number,
-                "chanegValidatorStatus call returned receipt"
-            );
+            tracing::info!("validators were removed");
 
-            tracing::info!("validator was removed");
+            let how_many_after = how_many_initial - how_many_removed;
 
             // First, all initial validator nodes must observe a ceremony with
-            // dealers = how_many_initial, players = how_many_initial - 1,
-            // including the validator to be removed because it is part of the
+            // dealers = how_many_initial, players = how_many_after, including
+            // the validators to be removed because they are part of the
             // original dealer set.
             loop {
                 context.sleep(Duration::from_secs(1)).await;
 
                 let mut dealers_is_initial = 0;
-                let mut players_is_initial_minus_one = 0;
+                let mut players_is_after = 0;
 
                 let metrics = context.encode();
                 for line in metrics.lines() {
@@ -438,7 +484,7 @@ impl AssertValidatorIsRemoved {
                             panic!(
                                 "observed dealers = {value} before observing \
                             dealers = {how_many_initial}, \
-                            players = {how_many_initial} - 1",
+                            players = {how_many_after}",
                             );
                         }
                         dealers_is_initial += (value as u32 == how_many_initial) as u32;
@@ -446,25 +492,23 @@ impl AssertValidatorIsRemoved {
 
                     if metric.ends_with("_dkg_manager_ceremony_players") {
                         let value = value.parse::<u64>().unwrap();
-                        players_is_initial_minus_one +=
-                            (value as u32 == how_many_initial - 1) as u32;
+                        players_is_after += (value as u32 == how_many_after) as u32;
                     }
                 }
-                if dealers_is_initial == how_many_initial
-                    && players_is_initial_minus_one == how_many_initial
-                {
+                if dealers_is_initial == how_many_initial && players_is_after == how_many_initial {
                     break;
                 }
             }
 
             // Then, all how_many_initial nodes must observe an epoch with the
-            // same number of participants (= how_many_intial - 1). This even
-            // includes the validator to be removed, since it will still transition.
+            // same number of participants (= how_many_after). This even
+            // includes the validators to be removed, since they will still
+            // transition.
             loop {
                 context.sleep(Duration::from_secs(1)).await;
 
                 let metrics = context.encode();
-                let mut participants_is_initial_minus_one = 0;
+                let mut participants_is_after = 0;
 
                 for line in metrics.lines() {
                     if !line.starts_with(CONSENSUS_NODE_PREFIX) {
@@ -476,11 +520,10 @@ impl AssertValidatorIsRemoved {
 
                     if metric.ends_with("_epoch_manager_latest_participants") {
                         let value = value.parse::<u64>().unwrap();
-                        participants_is_initial_minus_one +=
-                            (value as u32 == how_many_initial - 1) as u32;
+                        participants_is_after += (value as u32 == how_many_after) as u32;
                     }
                 }
-                if participants_is_initial_minus_one == how_many_initial {
+                if participants_is_after == how_many_initial {
                     break;
                 }
             }
@@ -2,5 +2,6 @@
 
 mod dynamic;
 mod hardfork_transitions;
+mod rotation;
 mod share_loss;
 mod static_transitions;
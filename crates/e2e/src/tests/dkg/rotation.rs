@@ -0,0 +1,176 @@
+use std::{net::SocketAddr, time::Duration};
+
+use alloy::transports::http::reqwest::Url;
+use commonware_macros::test_traced;
+use commonware_runtime::{
+    Clock as _, Metrics as _, Runner as _,
+    deterministic::{Config, Context, Runner},
+};
+use futures::future::join_all;
+use tracing::info;
+
+use crate::{CONSENSUS_NODE_PREFIX, Setup, setup_validators};
+
+#[test_traced]
+fn validator_rotates_key_in_a_set_of_four() {
+    AssertValidatorRotatesKey {
+        how_many_initial: 4,
+        epoch_length: 40,
+    }
+    .run();
+}
+
+#[test_traced]
+fn validator_rotates_key_in_a_set_of_five() {
+    AssertValidatorRotatesKey {
+        how_many_initial: 5,
+        epoch_length: 50,
+    }
+    .run();
+}
+
+struct AssertValidatorRotatesKey {
+    how_many_initial: u32,
+    epoch_length: u64,
+}
+
+impl AssertValidatorRotatesKey {
+    fn run(self) {
+        let Self {
+            how_many_initial,
+            epoch_length,
+        } = self;
+        let _ = tempo_eyre::install();
+
+        let setup = Setup::new()
+            .how_many_signers(how_many_initial)
+            .how_many_verifiers(1)
+            .epoch_length(epoch_length)
+            .allegretto_time(0)
+            .allegro_moderato_time(0);
+
+        let cfg = Config::default().with_seed(setup.seed);
+        let executor = Runner::from(cfg);
+
+        executor.start(|context| async move {
+            let (mut validators, execution_runtime) =
+                setup_validators(context.clone(), setup).await;
+
+            // The node running with the key the validator rotates to.
+            let mut next_node = {
+                let idx = validators
+                    .iter()
+                    .position(|node| node.consensus_config().share.is_none())
+                    .expect("at least one node must be a verifier, i.e. not have a share");
+                validators.remove(idx)
+            };
+
+            join_all(validators.iter_mut().map(|v| v.start())).await;
+
+            let http_url = validators[0]
+                .execution()
+                .rpc_server_handle()
+                .http_url()
+                .unwrap()
+                .parse::<Url>()
+                .unwrap();
+
+            // Any of the initial validators can rotate its key.
+            let rotating = 0;
+
+            next_node.start().await;
+            let receipt = execution_runtime
+                .update_validator(
+                    http_url,
+                    validators[rotating].public_key().clone(),
+                    next_node.public_key().clone(),
+                    SocketAddr::from(([127, 0, 0, 1], (validators.len() + 1) as u16)),
+                )
+                .await
+                .unwrap();
+
+            tracing::debug!(
+                block.number = receipt.block_number,
+                "updateValidator call returned receipt"
+            );
+
+            // The next key takes effect at the following epoch boundary. From
+            // then on, the old key is not needed anymore: the node running
+            // with it is stopped as soon as the rotation was observed, and its
+            // missing dealing must not fail the ceremony.
+            wait_for(&context, |metrics| {
+                metric_values(metrics, "_dkg_manager_key_rotations_total")
+                    .filter(|&value| value > 0)
+                    .count()
+                    >= how_many_initial as usize
+            })
+            .await;
+            info!("stopping the node running with the retired key");
+            validators[rotating].stop().await;
+
+            // Then, the next key must be dealt a share and become a signer.
+            wait_for(&context, |metrics| {
+                assert!(
+                    metric_values(metrics, "_dkg_manager_ceremony_failures_total")
+                        .all(|value| value == 0),
+                    "ceremonies must not fail during a rotation",
+                );
+                metric_values(
+                    metrics,
+                    &format!("{}_epoch_manager_how_often_signer_total", next_node.uid()),
+                )
+                .any(|value| value > 0)
+            })
+            .await;
+
+            let stopped_at = processed_height(&context.encode(), next_node.uid());
+            loop {
+                context.sleep(Duration::from_secs(1)).await;
+                if processed_height(&context.encode(), next_node.uid()) >= stopped_at + epoch_length
+                {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+/// Polls the metrics every second until `done` returns true.
+async fn wait_for(context: &Context, mut done: impl FnMut(&str) -> bool) {
+    loop {
+        context.sleep(Duration::from_secs(1)).await;
+        if done(&context.encode()) {
+            break;
+        }
+    }
+}
+
+/// Returns the values of the consensus node metrics ending in `suffix`.
+fn metric_values<'a>(metrics: &'a str, suffix: &'a str) -> impl Iterator<Item = u64> + 'a {
+    metrics
+        .lines()
+        .filter(|line| line.starts_with(CONSENSUS_NODE_PREFIX))
+        .filter_map(move |line| {
+            let mut parts = line.split_whitespace();
+            let metric = parts.next()?;
+            let value = parts.next()?;
+            metric
+                .ends_with(suffix)
+                .then(|| value.parse::<u64>().unwrap())
+        })
+}
+
+fn processed_height(metrics: &str, uid: &str) -> u64 {
+    metrics
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let metric = parts.next()?;
+            let value = parts.next()?;
+            metric
+                .ends_with(&format!("{uid}_marshal_processed_height"))
+                .then(|| value.parse::<u64>().unwrap())
+        })
+        .max()
+        .unwrap_or_default()
+}
@@ -160,6 +160,39 @@ fn pre_allegretto_four_validators_can_transition_twice() {
     .run();
 }
 
+#[test_traced]
+fn genesis_allegro_moderato_four_validators_transition_with_all_players_acking() {
+    let _ = tempo_eyre::install();
+
+    // Every dealing must be acked by all four players, so no shares are
+    // revealed.
+    let setup = Setup::new()
+        .how_many_signers(4)
+        .epoch_length(20)
+        .allegretto_time(0)
+        .allegro_moderato_time(0)
+        .dkg_threshold_bps(10_000);
+
+    let mut epoch_reached = false;
+    let mut dkg_successful = false;
+    let _first = run(setup, move |metric, value| {
+        if metric.ends_with("_dkg_manager_ceremony_failures_total") {
+            let value = value.parse::<u64>().unwrap();
+            assert_eq!(0, value);
+        }
+        if metric.ends_with("_epoch_manager_latest_epoch") {
+            let value = value.parse::<u64>().unwrap();
+            epoch_reached |= value >= 2;
+        }
+        if metric.ends_with("_dkg_manager_ceremony_successes_total") {
+            let value = value.parse::<u64>().unwrap();
+            dkg_successful |= value >= 2;
+        }
+
+        epoch_reached && dkg_successful
+    });
+}
+
 struct AssertStaticTransitions {
     how_many: u32,
     epoch_length: u64,
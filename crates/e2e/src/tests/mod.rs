@@ -7,6 +7,7 @@ mod backfill;
 mod dkg;
 mod linkage;
 mod restart;
+mod snapshot;
 mod subblocks;
 
 #[test_traced]
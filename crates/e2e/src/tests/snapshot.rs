@@ -0,0 +1,73 @@
+//! Tests for starting a node from a state snapshot exported by another node.
+
+use std::time::Duration;
+
+use commonware_macros::test_traced;
+use commonware_runtime::{
+    Clock as _, Runner as _,
+    deterministic::{self, Runner},
+};
+use futures::future::join_all;
+use reth_ethereum::{chainspec::ChainSpecProvider as _, storage::BlockNumReader as _};
+use tracing::debug;
+
+use crate::{Setup, setup_validators};
+
+#[test_traced]
+fn verifier_starts_from_snapshot_of_the_network() {
+    let _ = tempo_eyre::install();
+
+    let epoch_length = 10;
+    let setup = Setup::new()
+        .how_many_signers(3)
+        .how_many_verifiers(1)
+        .epoch_length(epoch_length)
+        .allegretto_time(0);
+    let cfg = deterministic::Config::default().with_seed(setup.seed);
+    let executor = Runner::from(cfg);
+
+    executor.start(|context| async move {
+        let (mut nodes, _execution_runtime) = setup_validators(context.clone(), setup).await;
+        let verifier = nodes
+            .iter()
+            .position(|node| node.consensus_config().share.is_none())
+            .expect("setup must contain a verifier");
+        let mut verifier = nodes.remove(verifier);
+        join_all(nodes.iter_mut().map(|node| node.start())).await;
+
+        // Export a snapshot once the network has finalized the boundary of the second epoch.
+        let snapshot_dir = tempfile::tempdir().unwrap();
+        let path = snapshot_dir.path().join("snapshot.jsonl");
+        let info = loop {
+            match nodes[0].export_snapshot(path.clone()).await {
+                Ok(info) if info.epoch >= 1 => break info,
+                Ok(info) => debug!(epoch = info.epoch, "snapshot too early; retrying"),
+                Err(err) => debug!(%err, "no snapshot to export yet; retrying"),
+            }
+            context.sleep(Duration::from_secs(1)).await;
+        };
+        debug!(
+            number = info.number,
+            epoch = info.epoch,
+            "exported snapshot"
+        );
+
+        let chain_spec = nodes[0].execution_provider().chain_spec();
+        let checkpoint = verifier.import_snapshot(chain_spec, &path);
+        assert_eq!(checkpoint.height(), info.number);
+        assert_eq!(checkpoint.hash(), info.hash);
+
+        verifier.start().await;
+        assert!(
+            verifier.execution_provider().best_block_number().unwrap() >= info.number,
+            "node started from a snapshot must not start below the snapshot"
+        );
+
+        // The verifier must follow the network past the next epoch boundary, executing the
+        // blocks after the snapshot on top of the imported state.
+        let target = info.number + epoch_length + 1;
+        while verifier.execution_provider().best_block_number().unwrap() < target {
+            context.sleep(Duration::from_secs(1)).await;
+        }
+    });
+}
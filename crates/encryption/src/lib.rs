@@ -0,0 +1,259 @@
+//! Threshold encryption of Tempo transactions to the group public key of the validators.
+//!
+//! An encrypted transaction (see [`TxEncrypted`](tempo_primitives::TxEncrypted)) carries
+//! another, signed transaction encrypted with ChaCha20-Poly1305 under a fresh random key. That
+//! key is in turn timelock-encrypted to the group public key for the decryption target of the
+//! encrypted transaction: it can only be recovered with a threshold signature of the validators
+//! over the target. That signature is the decryption key of the transaction.
+//!
+//! Validators release their partial signatures over the targets of the encrypted transactions
+//! of a block once that block is finalized, so that the inner transactions can be included in
+//! the next block but their contents are not known before their position in the chain is fixed.
+
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+use alloy_eips::{Decodable2718 as _, Encodable2718 as _};
+use alloy_primitives::{B256, Bytes};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, Nonce,
+    aead::{Aead as _, KeyInit as _},
+};
+use commonware_codec::{DecodeExt as _, Encode as _, ReadExt as _};
+use commonware_cryptography::bls12381::{
+    primitives::{
+        group::Share,
+        ops,
+        poly::PartialSignature,
+        variant::{MinSig, Variant},
+    },
+    tle,
+};
+use rand::{CryptoRng, RngCore};
+use tempo_primitives::TempoTxEnvelope;
+
+/// The namespace decryption keys are signed under.
+///
+/// Distinct from the namespaces of consensus messages, so that a partial signature over a
+/// decryption target can never be mistaken for a vote, and vice versa.
+pub const DECRYPTION_NAMESPACE: &[u8] = b"_TEMPO_DECRYPTION";
+
+/// The group public key transactions are encrypted to.
+pub type GroupPublicKey = <MinSig as Variant>::Public;
+
+/// The key decrypting an encrypted transaction: a threshold signature over its decryption target.
+pub type DecryptionKey = <MinSig as Variant>::Signature;
+
+/// The symmetric keys are only ever used once, so the nonce can be fixed.
+const NONCE: [u8; 12] = [0; 12];
+
+/// Errors returned when decrypting an encrypted transaction.
+#[derive(Debug, thiserror::Error)]
+pub enum DecryptionError {
+    /// The ciphertext does not start with a timelock-encrypted key.
+    #[error("failed to decode timelock ciphertext: {0}")]
+    Ciphertext(#[source] commonware_codec::Error),
+
+    /// The decryption key is not a valid signature.
+    #[error("failed to decode decryption key: {0}")]
+    Key(#[source] commonware_codec::Error),
+
+    /// The decryption key does not unlock the timelock-encrypted key.
+    #[error("decryption key does not match the ciphertext")]
+    KeyMismatch,
+
+    /// The symmetric ciphertext failed to authenticate.
+    #[error("failed to decrypt transaction")]
+    Aead,
+
+    /// The plaintext is not a valid transaction.
+    #[error("failed to decode decrypted transaction: {0}")]
+    Transaction(#[source] alloy_eips::eip2718::Eip2718Error),
+
+    /// The plaintext is itself an encrypted transaction.
+    #[error("decrypted transaction is itself encrypted")]
+    Nested,
+}
+
+/// Encrypts `transaction` to `identity` for `target`.
+///
+/// `target` must be the decryption target of the [`TxEncrypted`](tempo_primitives::TxEncrypted)
+/// the ciphertext is put in, otherwise the released decryption key will not unlock it.
+pub fn encrypt<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    identity: &GroupPublicKey,
+    target: B256,
+    transaction: &TempoTxEnvelope,
+) -> Bytes {
+    let mut key = [0u8; 32];
+    rng.fill_bytes(&mut key);
+
+    let sealed_key = tle::encrypt::<_, MinSig>(
+        rng,
+        *identity,
+        (Some(DECRYPTION_NAMESPACE), target.as_slice()),
+        &tle::Block::new(key),
+    );
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key))
+        .encrypt(
+            Nonce::from_slice(&NONCE),
+            transaction.encoded_2718().as_slice(),
+        )
+        .expect("encrypting an in-memory buffer must not fail");
+
+    let mut out = sealed_key.encode().to_vec();
+    out.extend_from_slice(&ciphertext);
+    out.into()
+}
+
+/// Decrypts `ciphertext` with `key`.
+///
+/// Fails if the key does not belong to the ciphertext, or if the plaintext is not a
+/// transaction that can be included in a block.
+pub fn decrypt(key: &DecryptionKey, ciphertext: &[u8]) -> Result<TempoTxEnvelope, DecryptionError> {
+    let mut buf = ciphertext;
+    let sealed_key =
+        tle::Ciphertext::<MinSig>::read(&mut buf).map_err(DecryptionError::Ciphertext)?;
+    let symmetric_key = tle::decrypt(key, &sealed_key).ok_or(DecryptionError::KeyMismatch)?;
+
+    let plaintext = ChaCha20Poly1305::new(Key::from_slice(symmetric_key.as_ref()))
+        .decrypt(Nonce::from_slice(&NONCE), buf)
+        .map_err(|_| DecryptionError::Aead)?;
+
+    let transaction =
+        TempoTxEnvelope::decode_2718_exact(&plaintext).map_err(DecryptionError::Transaction)?;
+    if transaction.is_encrypted() {
+        return Err(DecryptionError::Nested);
+    }
+    Ok(transaction)
+}
+
+/// Signs `target` with `share`, producing this validator's share of the decryption key.
+pub fn sign_target(share: &Share, target: B256) -> PartialSignature<MinSig> {
+    ops::partial_sign_message::<MinSig>(share, Some(DECRYPTION_NAMESPACE), target.as_slice())
+}
+
+/// Verifies that `partial` is a share of the decryption key for `target`.
+///
+/// `public` is the public key of the share at `partial.index`.
+pub fn verify_partial(
+    public: &GroupPublicKey,
+    target: B256,
+    partial: &PartialSignature<MinSig>,
+) -> bool {
+    ops::verify_message::<MinSig>(
+        public,
+        Some(DECRYPTION_NAMESPACE),
+        target.as_slice(),
+        &partial.value,
+    )
+    .is_ok()
+}
+
+/// Recovers a decryption key from at least `threshold` verified partial signatures.
+pub fn recover_key<'a>(
+    threshold: u32,
+    partials: impl IntoIterator<Item = &'a PartialSignature<MinSig>>,
+) -> Option<DecryptionKey> {
+    ops::threshold_signature_recover::<MinSig, _>(threshold, partials).ok()
+}
+
+/// Verifies that `key` is the decryption key for `target`.
+pub fn verify_key(identity: &GroupPublicKey, target: B256, key: &DecryptionKey) -> bool {
+    ops::verify_message::<MinSig>(identity, Some(DECRYPTION_NAMESPACE), target.as_slice(), key)
+        .is_ok()
+}
+
+/// Encodes `key` as included in the decryption keys system transaction.
+pub fn encode_key(key: &DecryptionKey) -> Bytes {
+    key.encode().to_vec().into()
+}
+
+/// Decodes a key included in the decryption keys system transaction.
+pub fn decode_key(bytes: &[u8]) -> Result<DecryptionKey, DecryptionError> {
+    DecryptionKey::decode(bytes).map_err(DecryptionError::Key)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_consensus::{Signed, TxLegacy};
+    use alloy_primitives::{Address, Signature};
+    use commonware_cryptography::bls12381::{dkg::ops::generate_shares, primitives::poly};
+    use commonware_utils::quorum;
+    use rand::{SeedableRng as _, rngs::StdRng};
+    use tempo_primitives::TxEncrypted;
+
+    use super::*;
+
+    fn transaction() -> TempoTxEnvelope {
+        TempoTxEnvelope::Legacy(Signed::new_unhashed(
+            TxLegacy {
+                chain_id: Some(1),
+                nonce: 7,
+                gas_limit: 21_000,
+                ..Default::default()
+            },
+            Signature::test_signature(),
+        ))
+    }
+
+    fn encrypted(nonce: u64) -> TxEncrypted {
+        TxEncrypted {
+            chain_id: 1,
+            nonce,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn threshold_key_decrypts() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let n = 4;
+        let threshold = quorum(n);
+        let (polynomial, shares) = generate_shares::<_, MinSig>(&mut rng, None, n, threshold);
+        let identity = *poly::public::<MinSig>(&polynomial);
+
+        let target = encrypted(0).decryption_target(Address::repeat_byte(1));
+        let ciphertext = encrypt(&mut rng, &identity, target, &transaction());
+
+        let partials = shares
+            .iter()
+            .take(threshold as usize)
+            .map(|share| sign_target(share, target))
+            .collect::<Vec<_>>();
+        for partial in &partials {
+            let public = polynomial.evaluate(partial.index).value;
+            assert!(verify_partial(&public, target, partial));
+        }
+
+        let key = recover_key(threshold, &partials).unwrap();
+        assert!(verify_key(&identity, target, &key));
+        assert_eq!(decode_key(&encode_key(&key)).unwrap(), key);
+        assert_eq!(decrypt(&key, &ciphertext).unwrap(), transaction());
+    }
+
+    #[test]
+    fn key_for_other_target_fails() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let n = 4;
+        let threshold = quorum(n);
+        let (polynomial, shares) = generate_shares::<_, MinSig>(&mut rng, None, n, threshold);
+        let identity = *poly::public::<MinSig>(&polynomial);
+
+        let sender = Address::repeat_byte(1);
+        let target = encrypted(0).decryption_target(sender);
+        let other = encrypted(1).decryption_target(sender);
+        let ciphertext = encrypt(&mut rng, &identity, target, &transaction());
+
+        let partials = shares
+            .iter()
+            .map(|share| sign_target(share, other))
+            .collect::<Vec<_>>();
+        let key = recover_key(threshold, &partials).unwrap();
+        assert!(!verify_key(&identity, target, &key));
+        assert!(matches!(
+            decrypt(&key, &ciphertext),
+            Err(DecryptionError::KeyMismatch)
+        ));
+    }
+}
@@ -0,0 +1,209 @@
+//! Benchmarks sequential and parallel execution of blocks full of TIP-20 transfers.
+
+use alloy_consensus::{Header, Signed, TxLegacy};
+use alloy_evm::{
+    block::{BlockExecutor, BlockExecutorFactory},
+    eth::EthBlockExecutionCtx,
+};
+use alloy_primitives::{Address, B256, Signature, TxKind, U256};
+use alloy_sol_types::SolCall;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use reth_chainspec::EthChainSpec;
+use reth_errors::ProviderError;
+use reth_evm::ConfigureEvm;
+use reth_primitives_traits::Recovered;
+use reth_revm::State;
+use revm::{
+    DatabaseRef,
+    database::InMemoryDB,
+    state::{AccountInfo, Bytecode},
+};
+use std::{convert::Infallible, hint::black_box, sync::Arc};
+use tempo_chainspec::spec::{DEV, TEMPO_BASE_FEE};
+use tempo_evm::{ParallelExecution, TempoBlockExecutionCtx, TempoEvmConfig};
+use tempo_precompiles::{
+    DEFAULT_FEE_TOKEN_PRE_ALLEGRETTO, TIP_FEE_MANAGER_ADDRESS,
+    storage::StorageKey as _,
+    tip_fee_manager,
+    tip20::{self, ITIP20},
+};
+use tempo_primitives::{TempoHeader, TempoTxEnvelope};
+
+const TOKEN: Address = DEFAULT_FEE_TOKEN_PRE_ALLEGRETTO;
+const BENEFICIARY: Address = Address::repeat_byte(0xbe);
+
+#[derive(Debug)]
+struct SharedDb(InMemoryDB);
+
+impl DatabaseRef for SharedDb {
+    type Error = ProviderError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.0
+            .basic_ref(address)
+            .map_err(|err: Infallible| match err {})
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.0
+            .code_by_hash_ref(code_hash)
+            .map_err(|err: Infallible| match err {})
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.0
+            .storage_ref(address, index)
+            .map_err(|err: Infallible| match err {})
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.0
+            .block_hash_ref(number)
+            .map_err(|err: Infallible| match err {})
+    }
+}
+
+fn sender(index: usize) -> Address {
+    Address::left_padding_from(&(index as u64 + 1).to_be_bytes())
+}
+
+/// Dev genesis state with the beneficiary accepting fees in [`TOKEN`] and `senders` funded
+/// accounts.
+fn genesis_db(senders: usize) -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    for (address, account) in &DEV.genesis().alloc {
+        let mut info = AccountInfo::from_balance(account.balance);
+        info.nonce = account.nonce.unwrap_or_default();
+        if let Some(code) = &account.code {
+            info = info.with_code(Bytecode::new_raw(code.clone()));
+        }
+        db.insert_account_info(*address, info);
+
+        for (slot, value) in account.storage.iter().flatten() {
+            db.insert_account_storage(
+                *address,
+                U256::from_be_bytes(slot.0),
+                U256::from_be_bytes(value.0),
+            )
+            .unwrap();
+        }
+    }
+
+    db.insert_account_storage(
+        TIP_FEE_MANAGER_ADDRESS,
+        BENEFICIARY.mapping_slot(tip_fee_manager::slots::VALIDATOR_TOKENS),
+        U256::from_be_slice(TOKEN.as_slice()),
+    )
+    .unwrap();
+    for index in 0..senders {
+        db.insert_account_storage(
+            TOKEN,
+            sender(index).mapping_slot(tip20::slots::BALANCES),
+            U256::from(u64::MAX),
+        )
+        .unwrap();
+    }
+
+    db
+}
+
+fn transfers(count: usize) -> Vec<Recovered<TempoTxEnvelope>> {
+    (0..count)
+        .map(|index| {
+            let tx = TxLegacy {
+                chain_id: Some(DEV.chain().id()),
+                nonce: 0,
+                gas_price: TEMPO_BASE_FEE as u128,
+                gas_limit: 300_000,
+                to: TxKind::Call(TOKEN),
+                value: U256::ZERO,
+                input: ITIP20::transferCall {
+                    to: Address::repeat_byte(0x42),
+                    amount: U256::from(1_000),
+                }
+                .abi_encode()
+                .into(),
+            };
+
+            Recovered::new_unchecked(
+                TempoTxEnvelope::Legacy(Signed::new_unhashed(tx, Signature::test_signature())),
+                sender(index),
+            )
+        })
+        .collect()
+}
+
+fn execute_block(
+    config: &TempoEvmConfig,
+    db: &InMemoryDB,
+    transactions: &[Recovered<TempoTxEnvelope>],
+    parallel: bool,
+) {
+    let header = TempoHeader {
+        inner: Header {
+            number: 1,
+            timestamp: DEV.genesis().timestamp + 1,
+            gas_limit: 500_000_000,
+            base_fee_per_gas: Some(TEMPO_BASE_FEE),
+            beneficiary: BENEFICIARY,
+            ..Default::default()
+        },
+        general_gas_limit: 30_000_000,
+        shared_gas_limit: 50_000_000,
+        timestamp_millis_part: 0,
+        lane_fees: None,
+    };
+
+    let mut state = State::builder()
+        .with_database(db.clone())
+        .with_bundle_update()
+        .build();
+    let evm = config.evm_with_env(&mut state, config.evm_env(&header).unwrap());
+    let ctx = TempoBlockExecutionCtx {
+        inner: EthBlockExecutionCtx {
+            parent_hash: B256::ZERO,
+            parent_beacon_block_root: None,
+            ommers: &[],
+            withdrawals: None,
+            extra_data: Default::default(),
+        },
+        general_gas_limit: header.general_gas_limit,
+        shared_gas_limit: header.shared_gas_limit,
+        validator_set: None,
+        subblock_fee_recipients: Default::default(),
+        parallel: parallel.then(|| ParallelExecution {
+            state: Arc::new(SharedDb(db.clone())),
+            transactions: transactions.to_vec(),
+        }),
+        prebuilt: None,
+        decrypted_transactions: None,
+    };
+
+    let mut executor = config.create_executor(evm, ctx);
+    for tx in transactions {
+        black_box(executor.execute_transaction(tx.as_recovered_ref()).unwrap());
+    }
+}
+
+fn payment_lane(c: &mut Criterion) {
+    let config = TempoEvmConfig::new_with_default_factory(DEV.clone());
+    let mut group = c.benchmark_group("payment_lane");
+    group.sample_size(10);
+
+    for count in [256, 1024, 2048] {
+        let db = genesis_db(count);
+        let transactions = transfers(count);
+
+        group.bench_with_input(BenchmarkId::new("sequential", count), &count, |b, _| {
+            b.iter(|| execute_block(&config, &db, &transactions, false))
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", count), &count, |b, _| {
+            b.iter(|| execute_block(&config, &db, &transactions, true))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, payment_lane);
+criterion_main!(benches);
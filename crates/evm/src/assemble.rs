@@ -7,7 +7,7 @@ use reth_evm_ethereum::EthBlockAssembler;
 use reth_primitives_traits::SealedHeader;
 use std::sync::Arc;
 use tempo_chainspec::TempoChainSpec;
-use tempo_primitives::TempoHeader;
+use tempo_primitives::{LaneFees, TempoHeader};
 
 /// Assembler for Tempo blocks.
 #[derive(Debug, Clone)]
@@ -39,6 +39,9 @@ impl BlockAssembler<TempoEvmConfig> for TempoBlockAssembler {
                     shared_gas_limit,
                     validator_set: _,
                     subblock_fee_recipients: _,
+                    parallel: _,
+                    prebuilt: _,
+                    decrypted_transactions: _,
                 },
             parent,
             transactions,
@@ -52,6 +55,20 @@ impl BlockAssembler<TempoEvmConfig> for TempoBlockAssembler {
         let parent = SealedHeader::new_unhashed(parent.clone().into_header().inner);
 
         let timestamp_millis_part = evm_env.block_env.timestamp_millis_part;
+        let lane_fees = evm_env
+            .block_env
+            .payment_basefee
+            .map(|payment_base_fee_per_gas| LaneFees {
+                payment_base_fee_per_gas,
+                general_gas_used: tempo_consensus::general_gas_used(
+                    &transactions,
+                    &output.receipts,
+                ),
+                payment_gas_used: tempo_consensus::payment_gas_used(
+                    &transactions,
+                    &output.receipts,
+                ),
+            });
 
         // Delegate block building to the inner assembler
         let block = self.inner.assemble_block(BlockAssemblerInput::<
@@ -72,6 +89,7 @@ impl BlockAssembler<TempoEvmConfig> for TempoBlockAssembler {
             general_gas_limit,
             timestamp_millis_part,
             shared_gas_limit,
+            lane_fees,
         }))
     }
 }
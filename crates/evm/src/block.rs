@@ -1,7 +1,10 @@
-use crate::{TempoBlockExecutionCtx, evm::TempoEvm};
+use crate::{
+    ParallelExecution, PrebuiltExecution, TempoBlockExecutionCtx, evm::TempoEvm,
+    parallel::ParallelExecutor,
+};
 use alloy_consensus::{Transaction, transaction::TxHashRef};
 use alloy_evm::{
-    Database, Evm,
+    Database, Evm, EvmEnv, FromRecoveredTx,
     block::{
         BlockExecutionError, BlockExecutionResult, BlockExecutor, BlockValidationError,
         ExecutableTx, OnStateHook,
@@ -11,7 +14,7 @@ use alloy_evm::{
         receipt_builder::{ReceiptBuilder, ReceiptBuilderCtx},
     },
 };
-use alloy_primitives::{Address, B256, Bytes, U256};
+use alloy_primitives::{B256, Bytes, Log, U256};
 use alloy_rlp::Decodable;
 use alloy_sol_types::SolCall;
 use commonware_codec::DecodeExt;
@@ -19,29 +22,42 @@ use commonware_cryptography::{
     Verifier,
     ed25519::{PublicKey, Signature},
 };
-use reth_revm::{Inspector, State, context::result::ResultAndState};
+use reth_primitives_traits::Recovered;
+use reth_revm::{
+    Inspector, State,
+    context::result::{EVMError, ResultAndState},
+};
 use revm::{
     DatabaseCommit,
     context::ContextTr,
     state::{Account, Bytecode},
 };
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
 use tempo_chainspec::{TempoChainSpec, hardfork::TempoHardforks};
 use tempo_precompiles::{
     ACCOUNT_KEYCHAIN_ADDRESS, STABLECOIN_EXCHANGE_ADDRESS, TIP_FEE_MANAGER_ADDRESS,
-    TIP20_REWARDS_REGISTRY_ADDRESS, stablecoin_exchange::IStablecoinExchange,
-    tip_fee_manager::IFeeManager, tip20_rewards_registry::ITIP20RewardsRegistry,
+    TIP20_REWARDS_REGISTRY_ADDRESS, metrics::ExecutionContext,
+    stablecoin_exchange::IStablecoinExchange, tip_fee_manager::IFeeManager,
+    tip20_rewards_registry::ITIP20RewardsRegistry,
 };
 use tempo_primitives::{
-    SubBlock, SubBlockMetadata, TempoReceipt, TempoTxEnvelope, subblock::PartialValidatorKey,
+    ENCRYPTED_TX_ADDRESS, SubBlock, SubBlockFeeRecipients, SubBlockMetadata, SubBlockVersion,
+    TempoReceipt, TempoTxEnvelope, subblock::PartialValidatorKey,
+    transaction::encrypted::decode_decryption_keys,
 };
-use tempo_revm::{TempoHaltReason, evm::TempoContext};
+use tempo_revm::{TempoHaltReason, TempoTxEnv, evm::TempoContext};
 use tracing::trace;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum BlockSection {
-    /// Start of block system transactions (rewards registry).
-    StartOfBlock { seen_tip20_rewards_registry: bool },
+    /// Start of block system transactions (rewards registry, decryption keys).
+    StartOfBlock {
+        seen_tip20_rewards_registry: bool,
+        seen_decryption_keys: bool,
+    },
     /// Basic section of the block. Includes arbitrary transactions chosen by the proposer.
     ///
     /// Must use at most `non_shared_gas_left` gas.
@@ -101,11 +117,22 @@ pub(crate) struct TempoBlockExecutor<'a, DB: Database, I> {
     seen_subblocks: Vec<(PartialValidatorKey, Vec<TempoTxEnvelope>)>,
     validator_set: Option<Vec<B256>>,
     shared_gas_limit: u64,
-    subblock_fee_recipients: HashMap<PartialValidatorKey, Address>,
+    subblock_fee_recipients: HashMap<PartialValidatorKey, SubBlockFeeRecipients>,
 
     non_shared_gas_left: u64,
     non_payment_gas_left: u64,
     incentive_gas_used: u64,
+
+    /// Speculative executor of the payment transactions, if parallel execution is enabled.
+    parallel: Option<ParallelExecutor>,
+    /// Transactions executed ahead of the block, if it has the environment they were executed in.
+    prebuilt: Option<Arc<PrebuiltExecution>>,
+    /// Revert logs of the last speculatively executed transaction.
+    speculative_revert_logs: Vec<Log>,
+
+    /// Decrypted transactions of the parent block that were not yet included, if their
+    /// inclusion is enforced.
+    pending_decrypted: Option<VecDeque<Recovered<TempoTxEnvelope>>>,
 }
 
 impl<'a, DB, I> TempoBlockExecutor<'a, DB, I>
@@ -118,6 +145,17 @@ where
         ctx: TempoBlockExecutionCtx<'a>,
         chain_spec: &'a TempoChainSpec,
     ) -> Self {
+        let parallel = ctx
+            .parallel
+            .map(|parallel| ParallelExecutor::new(parallel, evm.block().beneficiary));
+        let prebuilt = ctx.prebuilt.filter(|prebuilt| {
+            let ctx = evm.ctx();
+            prebuilt.is_env(&EvmEnv {
+                cfg_env: ctx.cfg.clone(),
+                block_env: ctx.block.clone(),
+            })
+        });
+
         Self {
             incentive_gas_used: 0,
             validator_set: ctx.validator_set,
@@ -132,9 +170,181 @@ where
             ),
             section: BlockSection::StartOfBlock {
                 seen_tip20_rewards_registry: false,
+                seen_decryption_keys: false,
             },
             seen_subblocks: Vec::new(),
             subblock_fee_recipients: ctx.subblock_fee_recipients,
+            parallel,
+            prebuilt,
+            speculative_revert_logs: Vec::new(),
+            pending_decrypted: ctx.decrypted_transactions.map(VecDeque::from),
+        }
+    }
+
+    /// Returns the prebuilt or speculative result of the transaction if it is still valid on top
+    /// of the current state, starting a new speculation round if needed.
+    fn speculative_result(
+        &mut self,
+        tx: &TempoTxEnvelope,
+    ) -> Result<Option<ResultAndState<TempoHaltReason>>, BlockExecutionError> {
+        if self.parallel.is_none() && self.prebuilt.is_none() {
+            return Ok(None);
+        }
+
+        // Inspected executions must go through the inspector. Subblock transactions are executed
+        // with the fee recipient of their position, which is unknown ahead of the block.
+        if self.inner.evm.is_inspecting() || tx.is_system_tx() || tx.subblock_proposer().is_some() {
+            return Ok(None);
+        }
+
+        // Leave the block gas limit violations to the sequential execution.
+        let gas_used = self
+            .inner
+            .receipts
+            .last()
+            .map_or(0, |receipt| receipt.cumulative_gas_used);
+        if tx.gas_limit() > self.inner.evm.block().gas_limit - gas_used {
+            return Ok(None);
+        }
+
+        let tx_hash = *tx.tx_hash();
+        if let Some(prebuilt) = &self.prebuilt
+            && let Some(speculation) = prebuilt.get(&tx_hash)
+        {
+            let state = self.inner.evm.ctx_mut().db_mut();
+            match speculation
+                .clone()
+                .validate(state, prebuilt.fee_slots())
+                .map_err(BlockExecutionError::other)?
+            {
+                Some((output, revert_logs)) => {
+                    self.speculative_revert_logs = revert_logs;
+                    return Ok(Some(output));
+                }
+                None => {
+                    trace!(target: "tempo::block", %tx_hash, "Prebuilt execution conflicted");
+                }
+            }
+        }
+
+        let Some(parallel) = self.parallel.as_mut() else {
+            return Ok(None);
+        };
+        if !ParallelExecution::is_candidate(tx) {
+            return Ok(None);
+        }
+
+        let mut conflict = false;
+        loop {
+            if let Some(speculation) = parallel.take(&tx_hash) {
+                let state = self.inner.evm.ctx_mut().db_mut();
+                match speculation
+                    .validate(state, parallel.fee_slots())
+                    .map_err(BlockExecutionError::other)?
+                {
+                    Some((output, revert_logs)) => {
+                        self.speculative_revert_logs = revert_logs;
+                        return Ok(Some(output));
+                    }
+                    None => {
+                        trace!(target: "tempo::block", %tx_hash, "Speculative execution conflicted");
+                        conflict = true;
+                    }
+                }
+            }
+
+            if !parallel.should_speculate(&tx_hash, conflict) {
+                return Ok(None);
+            }
+
+            let ctx = self.inner.evm.ctx();
+            let env = EvmEnv {
+                cfg_env: ctx.cfg.clone(),
+                block_env: ctx.block.clone(),
+            };
+            parallel.speculate(env, &ctx.db().cache);
+            conflict = false;
+        }
+    }
+
+    /// Enforces the inclusion of the decrypted transactions of the parent block, given the
+    /// section `tx` is executed in.
+    ///
+    /// The decryption keys of the parent block must be released at the start of the block, and
+    /// the decrypted transactions must follow them in the order of their ciphertexts. Decrypted
+    /// transactions may only be left out if they don't fit into the block or are invalid on top
+    /// of the state at their position, so that proposers can't censor them after seeing their
+    /// contents.
+    fn validate_decrypted_tx(
+        &mut self,
+        tx: &TempoTxEnvelope,
+        next_section: BlockSection,
+    ) -> Result<(), BlockExecutionError> {
+        if self.pending_decrypted.is_none() {
+            return Ok(());
+        }
+
+        match (self.section, next_section) {
+            (_, BlockSection::StartOfBlock { .. }) => return Ok(()),
+            (
+                BlockSection::StartOfBlock {
+                    seen_decryption_keys: false,
+                    ..
+                },
+                _,
+            ) => {
+                return Err(BlockValidationError::msg(
+                    "decryption keys of the parent block were not released",
+                )
+                .into());
+            }
+            _ => {}
+        }
+
+        while let Some(decrypted) = self
+            .pending_decrypted
+            .as_mut()
+            .and_then(|pending| pending.pop_front())
+        {
+            if decrypted.tx_hash() == tx.tx_hash() {
+                return Ok(());
+            }
+
+            if !self.can_skip_decrypted_tx(&decrypted)? {
+                trace!(target: "tempo::block", tx_hash = ?*decrypted.tx_hash(), "Rejecting: decrypted transaction was left out");
+                return Err(BlockValidationError::msg(
+                    "decrypted transaction of the parent block was left out",
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether a decrypted transaction may be left out at the current position, because
+    /// it does not fit into the block or is invalid on top of the current state.
+    fn can_skip_decrypted_tx(
+        &mut self,
+        tx: &Recovered<TempoTxEnvelope>,
+    ) -> Result<bool, BlockExecutionError> {
+        if tx.gas_limit() > self.non_shared_gas_left
+            || (!tx.is_payment() && tx.gas_limit() > self.non_payment_gas_left)
+        {
+            return Ok(true);
+        }
+
+        let result = self
+            .inner
+            .evm
+            .transact_raw(TempoTxEnv::from_recovered_tx(tx.inner(), tx.signer()));
+        // Only the validity of the transaction matters, drop anything its execution left behind.
+        self.inner.evm.take_revert_logs();
+
+        match result {
+            Ok(_) => Ok(false),
+            Err(EVMError::Transaction(_)) => Ok(true),
+            Err(err) => Err(BlockExecutionError::other(err)),
         }
     }
 
@@ -148,6 +358,41 @@ where
         let block_number = block.number.to_be_bytes_vec();
         let to = tx.to().unwrap_or_default();
 
+        // Handle the start-of-block system transaction releasing the decryption keys of the
+        // encrypted transactions in the parent block
+        if to == ENCRYPTED_TX_ADDRESS {
+            if !self
+                .inner
+                .spec
+                .is_allegro_moderato_active_at_timestamp(block_timestamp.to::<u64>())
+            {
+                return Err(BlockValidationError::msg(
+                    "decryption keys system transaction before Allegro-Moderato",
+                ));
+            }
+
+            let BlockSection::StartOfBlock {
+                seen_tip20_rewards_registry,
+                seen_decryption_keys: false,
+            } = self.section
+            else {
+                return Err(BlockValidationError::msg(
+                    "decryption keys system transaction must be at start of block",
+                ));
+            };
+
+            if decode_decryption_keys(tx.input(), block.number.to::<u64>()).is_none() {
+                return Err(BlockValidationError::msg(
+                    "invalid decryption keys system transaction",
+                ));
+            }
+
+            return Ok(BlockSection::StartOfBlock {
+                seen_tip20_rewards_registry,
+                seen_decryption_keys: true,
+            });
+        }
+
         if !self
             .inner
             .spec
@@ -157,6 +402,7 @@ where
             // Only enforce this restriction when we haven't seen the rewards registry yet
             if let BlockSection::StartOfBlock {
                 seen_tip20_rewards_registry: false,
+                ..
             } = self.section
             {
                 if to != TIP20_REWARDS_REGISTRY_ADDRESS {
@@ -179,6 +425,7 @@ where
 
                 return Ok(BlockSection::StartOfBlock {
                     seen_tip20_rewards_registry: true,
+                    seen_decryption_keys: false,
                 });
             }
         }
@@ -289,6 +536,11 @@ where
             return Ok(());
         };
         let gas_per_subblock = self.shared_gas_limit / validator_set.len() as u64;
+        let block_timestamp = self.evm().block().timestamp.to::<u64>();
+        let v2_active = self
+            .inner
+            .spec
+            .is_allegro_moderato_active_at_timestamp(block_timestamp);
 
         let mut incentive_gas = 0;
         let mut seen = HashSet::new();
@@ -304,6 +556,12 @@ where
                 ));
             }
 
+            if metadata.version == SubBlockVersion::V2 && !v2_active {
+                return Err(BlockValidationError::msg(
+                    "subblock version 2 before Allegro-Moderato",
+                ));
+            }
+
             let transactions = if let Some((validator, txs)) =
                 self.seen_subblocks.get(next_non_empty)
                 && validator.matches(metadata.validator)
@@ -314,6 +572,14 @@ where
                 Vec::new()
             };
 
+            if metadata.version == SubBlockVersion::V2
+                && metadata.transaction_fee_recipients.len() != transactions.len()
+            {
+                return Err(BlockValidationError::msg(
+                    "subblock fee recipients do not match its transactions",
+                ));
+            }
+
             let reserved_gas = transactions.iter().map(|tx| tx.gas_limit()).sum::<u64>();
 
             let signature_hash = SubBlock {
@@ -321,6 +587,7 @@ where
                 fee_recipient: metadata.fee_recipient,
                 parent_hash: self.inner.ctx.parent_hash,
                 transactions: transactions.clone(),
+                transaction_fee_recipients: metadata.transaction_fee_recipients.clone(),
             }
             .signature_hash();
 
@@ -379,6 +646,7 @@ where
             match self.section {
                 BlockSection::StartOfBlock {
                     seen_tip20_rewards_registry,
+                    ..
                 } if !post_moderato && !seen_tip20_rewards_registry => {
                     Err(BlockValidationError::msg(
                         "TIP20 rewards registry system transaction was not seen",
@@ -410,6 +678,7 @@ where
             match self.section {
                 BlockSection::StartOfBlock {
                     seen_tip20_rewards_registry,
+                    ..
                 } if !post_moderato && !seen_tip20_rewards_registry => {
                     Err(BlockValidationError::msg(
                         "TIP20 rewards registry system transaction was not seen",
@@ -498,19 +767,38 @@ where
         &mut self,
         tx: impl ExecutableTx<Self>,
     ) -> Result<ResultAndState<TempoHaltReason>, BlockExecutionError> {
+        self.speculative_revert_logs.clear();
+        if let Some(output) = self.speculative_result(tx.tx())? {
+            return Ok(output);
+        }
+
         let beneficiary = self.evm_mut().ctx_mut().block.beneficiary;
         // If we are dealing with a subblock transaction, configure the fee recipient context.
         if self.evm().ctx().cfg.spec.is_allegretto()
             && let Some(validator) = tx.tx().subblock_proposer()
         {
-            let fee_recipient = *self
+            // Subblock transactions are contiguous, so the transaction's index in its subblock
+            // is the number of transactions already seen from the same validator.
+            let index = self
+                .seen_subblocks
+                .last()
+                .filter(|(proposer, _)| *proposer == validator)
+                .map_or(0, |(_, txs)| txs.len());
+            let fee_recipient = self
                 .subblock_fee_recipients
                 .get(&validator)
+                .and_then(|recipients| recipients.get(index))
                 .ok_or(BlockExecutionError::msg("invalid subblock transaction"))?;
 
             self.evm_mut().ctx_mut().block.beneficiary = fee_recipient;
         }
-        let result = self.inner.execute_transaction_without_commit(tx);
+        // Blocks executed while building a payload keep the context of the payload builder.
+        let result = match ExecutionContext::current() {
+            ExecutionContext::Other => {
+                ExecutionContext::Block.enter(|| self.inner.execute_transaction_without_commit(tx))
+            }
+            _ => self.inner.execute_transaction_without_commit(tx),
+        };
 
         self.evm_mut().ctx_mut().block.beneficiary = beneficiary;
 
@@ -524,12 +812,22 @@ where
     ) -> Result<u64, BlockExecutionError> {
         let next_section = self.validate_tx(tx.tx(), output.result.gas_used())?;
 
-        let gas_used = self.inner.commit_transaction(output, &tx)?;
-
         // TODO: remove once revm supports emitting logs for reverted transactions
         //
         // <https://github.com/tempoxyz/tempo/pull/729>
-        let logs = self.inner.evm.take_revert_logs();
+        //
+        // Taken before validating decrypted transactions, which may execute other transactions.
+        let mut logs = self.inner.evm.take_revert_logs();
+        logs.append(&mut self.speculative_revert_logs);
+
+        self.validate_decrypted_tx(tx.tx(), next_section)?;
+
+        let gas_used = self.inner.commit_transaction(output, &tx)?;
+
+        if let Some(parallel) = &mut self.parallel {
+            parallel.on_executed(*tx.tx().tx_hash());
+        }
+
         if !logs.is_empty() {
             self.inner
                 .receipts
@@ -592,6 +890,16 @@ where
                 BlockValidationError::msg("end-of-block system transactions not seen").into(),
             );
         }
+        if self
+            .pending_decrypted
+            .as_ref()
+            .is_some_and(|pending| !pending.is_empty())
+        {
+            return Err(BlockValidationError::msg(
+                "decrypted transactions of the parent block were not included",
+            )
+            .into());
+        }
         self.inner.finish()
     }
 
@@ -607,3 +915,133 @@ where
         self.inner.evm()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TempoEvmConfig,
+        test_utils::{execution_ctx, genesis_db, header, sender, transfer},
+    };
+    use alloy_consensus::{Signed, TxLegacy};
+    use alloy_primitives::{Address, TxKind};
+    use reth_chainspec::EthChainSpec;
+    use reth_evm::ConfigureEvm;
+    use tempo_chainspec::spec::DEV;
+    use tempo_primitives::transaction::{
+        encrypted::encode_decryption_keys,
+        envelope::{TEMPO_SYSTEM_TX_SENDER, TEMPO_SYSTEM_TX_SIGNATURE},
+    };
+
+    fn decryption_keys_tx() -> Recovered<TempoTxEnvelope> {
+        let tx = TxLegacy {
+            chain_id: Some(DEV.chain().id()),
+            nonce: 0,
+            gas_price: 0,
+            gas_limit: 0,
+            to: TxKind::Call(ENCRYPTED_TX_ADDRESS),
+            value: U256::ZERO,
+            input: encode_decryption_keys(&[Bytes::from_static(&[1; 48])], header().inner.number),
+        };
+
+        Recovered::new_unchecked(
+            TempoTxEnvelope::Legacy(Signed::new_unhashed(tx, TEMPO_SYSTEM_TX_SIGNATURE)),
+            TEMPO_SYSTEM_TX_SENDER,
+        )
+    }
+
+    /// Executes `transactions` in a block that must include `decrypted`, stopping at the first
+    /// failing transaction.
+    fn execute(
+        transactions: &[Recovered<TempoTxEnvelope>],
+        decrypted: Vec<Recovered<TempoTxEnvelope>>,
+    ) -> Result<(), BlockExecutionError> {
+        let db = genesis_db(4);
+        let config = TempoEvmConfig::new_with_default_factory(DEV.clone());
+        let header = header();
+
+        let mut state = State::builder().with_database(db.clone()).build();
+        let evm = config.evm_with_env(&mut state, config.evm_env(&header).unwrap());
+        let mut ctx = execution_ctx(&header, &db, &[], false);
+        ctx.decrypted_transactions = Some(decrypted);
+
+        let mut executor = TempoBlockExecutor::new(evm, ctx, config.chain_spec());
+        for tx in transactions {
+            executor.execute_transaction(tx.as_recovered_ref())?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn decrypted_transactions_follow_decryption_keys() {
+        let decrypted = vec![
+            transfer(sender(0), 0, Address::repeat_byte(0x42)),
+            transfer(sender(1), 0, Address::repeat_byte(0x42)),
+        ];
+        let mut transactions = vec![decryption_keys_tx()];
+        transactions.extend(decrypted.iter().cloned());
+        transactions.push(transfer(sender(2), 0, Address::repeat_byte(0x42)));
+
+        execute(&transactions, decrypted).unwrap();
+    }
+
+    #[test]
+    fn decryption_keys_of_parent_must_be_released() {
+        let transactions = [transfer(sender(0), 0, Address::repeat_byte(0x42))];
+
+        assert!(
+            execute(
+                &transactions,
+                vec![transfer(sender(1), 0, Address::repeat_byte(0x42))]
+            )
+            .is_err()
+        );
+        // Even if none of the encrypted transactions of the parent could be decrypted.
+        assert!(execute(&transactions, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn valid_decrypted_transactions_cant_be_left_out() {
+        let decrypted = vec![
+            transfer(sender(0), 0, Address::repeat_byte(0x42)),
+            transfer(sender(1), 0, Address::repeat_byte(0x42)),
+        ];
+
+        // Left out.
+        let transactions = [decryption_keys_tx(), decrypted[1].clone()];
+        assert!(execute(&transactions, decrypted.clone()).is_err());
+
+        // Reordered.
+        let transactions = [
+            decryption_keys_tx(),
+            decrypted[1].clone(),
+            decrypted[0].clone(),
+        ];
+        assert!(execute(&transactions, decrypted.clone()).is_err());
+
+        // Replaced by another transaction.
+        let transactions = [
+            decryption_keys_tx(),
+            transfer(sender(2), 0, Address::repeat_byte(0x42)),
+        ];
+        assert!(execute(&transactions, decrypted).is_err());
+    }
+
+    #[test]
+    fn invalid_decrypted_transactions_can_be_left_out() {
+        let decrypted = vec![
+            // Nonce too high.
+            transfer(sender(0), 1, Address::repeat_byte(0x42)),
+            transfer(sender(1), 0, Address::repeat_byte(0x42)),
+            // Sender can't pay for fees.
+            transfer(Address::repeat_byte(0x99), 0, Address::repeat_byte(0x42)),
+        ];
+        let transactions = [
+            decryption_keys_tx(),
+            decrypted[1].clone(),
+            transfer(sender(2), 0, Address::repeat_byte(0x42)),
+        ];
+
+        execute(&transactions, decrypted).unwrap();
+    }
+}
@@ -1,9 +1,12 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use alloy_evm::eth::EthBlockExecutionCtx;
-use alloy_primitives::{Address, B256};
+use alloy_primitives::B256;
 use reth_evm::NextBlockEnvAttributes;
-use tempo_primitives::subblock::PartialValidatorKey;
+use reth_primitives_traits::Recovered;
+use tempo_primitives::{SubBlockFeeRecipients, TempoTxEnvelope, subblock::PartialValidatorKey};
+
+use crate::parallel::{ParallelExecution, PrebuiltExecution};
 
 /// Execution context for Tempo block.
 #[derive(Debug, Clone, derive_more::Deref)]
@@ -22,10 +25,29 @@ pub struct TempoBlockExecutionCtx<'a> {
     /// When this is set to `None`, no validation of subblock signatures is performed.
     /// Make sure to always set this field when executing blocks from untrusted sources
     pub validator_set: Option<Vec<B256>>,
-    /// Mapping from a subblock validator public key to the fee recipient configured.
+    /// Mapping from a subblock validator public key to the fee recipients configured.
     ///
     /// Used to provide EVM with the fee recipient context when executing subblock transactions.
-    pub subblock_fee_recipients: HashMap<PartialValidatorKey, Address>,
+    pub subblock_fee_recipients: HashMap<PartialValidatorKey, SubBlockFeeRecipients>,
+    /// Context for the parallel execution of the payment transactions.
+    ///
+    /// When set to `None`, all transactions are executed sequentially.
+    pub parallel: Option<ParallelExecution>,
+    /// Transactions executed ahead of the block, e.g. by a payload prebuild.
+    ///
+    /// Their results are committed without executing them again if the block has the environment
+    /// they were executed in and the values they read are unchanged.
+    pub prebuilt: Option<Arc<PrebuiltExecution>>,
+    /// Transactions decrypted from the encrypted transactions of the parent block, in the order
+    /// of their ciphertexts.
+    ///
+    /// Only set for un-finalized blocks coming from consensus layer whose parent has encrypted
+    /// transactions. The block must then release the decryption keys of the parent, and include
+    /// the decrypted transactions right after them. A decrypted transaction may only be left out
+    /// if it does not fit into the block or is invalid on top of the state at its position.
+    ///
+    /// When this is set to `None`, the inclusion of decrypted transactions is not enforced.
+    pub decrypted_transactions: Option<Vec<Recovered<TempoTxEnvelope>>>,
 }
 
 /// Context required for next block environment.
@@ -40,8 +62,14 @@ pub struct TempoNextBlockEnvAttributes {
     pub shared_gas_limit: u64,
     /// Milliseconds portion of the timestamp.
     pub timestamp_millis_part: u64,
-    /// Mapping from a subblock validator public key to the fee recipient configured.
-    pub subblock_fee_recipients: HashMap<PartialValidatorKey, Address>,
+    /// Mapping from a subblock validator public key to the fee recipients configured.
+    pub subblock_fee_recipients: HashMap<PartialValidatorKey, SubBlockFeeRecipients>,
+    /// Payment transactions likely to be included in the block, in expected execution order.
+    ///
+    /// These are speculatively executed in parallel if parallel execution is enabled.
+    pub speculative_transactions: Vec<Recovered<TempoTxEnvelope>>,
+    /// Transactions executed ahead of the block by a payload prebuild, if any.
+    pub prebuilt: Option<Arc<PrebuiltExecution>>,
 }
 
 #[cfg(feature = "rpc")]
@@ -61,6 +89,8 @@ impl reth_rpc_eth_api::helpers::pending_block::BuildPendingEnv<tempo_primitives:
             shared_gas_limit,
             timestamp_millis_part: parent.timestamp_millis_part,
             subblock_fee_recipients: Default::default(),
+            speculative_transactions: Vec::new(),
+            prebuilt: None,
         }
     }
 }
@@ -94,6 +94,11 @@ impl<DB: Database, I> TempoEvm<DB, I> {
         }
     }
 
+    /// Returns whether the inspector is enabled.
+    pub const fn is_inspecting(&self) -> bool {
+        self.inspect
+    }
+
     /// Takes the inner EVM's revert logs.
     ///
     /// This is used as a work around to allow logs to be
@@ -184,10 +189,20 @@ where
             *gas_refunded = 0;
 
             Ok(result)
-        } else if self.inspect {
-            self.inner.inspect_tx(tx)
         } else {
-            self.inner.transact(tx)
+            // Payment transactions are priced by the base fee of the payment lane.
+            let basefee = self.block.inner.basefee;
+            self.block.inner.basefee = self.block.lane_basefee(tx.is_payment());
+
+            let result = if self.inspect {
+                self.inner.inspect_tx(tx)
+            } else {
+                self.inner.transact(tx)
+            };
+
+            self.block.inner.basefee = basefee;
+
+            result
         }
     }
 
@@ -234,8 +249,12 @@ where
 
 #[cfg(test)]
 mod tests {
+    use alloy_primitives::address;
     use reth_revm::context::BlockEnv;
-    use revm::{context::TxEnv, database::EmptyDB};
+    use revm::{
+        context::{TxEnv, result::InvalidTransaction},
+        database::EmptyDB,
+    };
 
     use super::*;
 
@@ -269,4 +288,47 @@ mod tests {
 
         assert!(result.result.is_success());
     }
+
+    #[test]
+    fn payment_transactions_use_payment_lane_base_fee() {
+        let mut evm = TempoEvm::new(
+            EmptyDB::default(),
+            EvmEnv {
+                block_env: TempoBlockEnv {
+                    inner: BlockEnv {
+                        basefee: 2,
+                        ..Default::default()
+                    },
+                    payment_basefee: Some(1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        let tx = |to: Address| TempoTxEnv {
+            inner: TxEnv {
+                gas_price: 1,
+                gas_limit: 100_000,
+                kind: TxKind::Call(to),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let is_underpriced = |result: Result<_, EVMError<_, TempoInvalidTransaction>>| {
+            matches!(
+                result,
+                Err(EVMError::Transaction(
+                    TempoInvalidTransaction::EthInvalidTransaction(
+                        InvalidTransaction::GasPriceLessThanBasefee
+                    )
+                ))
+            )
+        };
+
+        assert!(is_underpriced(evm.transact(tx(Address::ZERO))));
+        assert!(!is_underpriced(evm.transact(tx(address!(
+            "0x20C0000000000000000000000000000000000000"
+        )))));
+        assert_eq!(evm.block().basefee, 2);
+    }
 }
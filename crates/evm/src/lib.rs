@@ -5,7 +5,7 @@
 
 mod assemble;
 use alloy_consensus::{BlockHeader as _, Transaction, crypto::RecoveryError};
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 use alloy_rlp::Decodable;
 pub use assemble::TempoBlockAssembler;
 mod block;
@@ -14,6 +14,12 @@ pub use context::{TempoBlockExecutionCtx, TempoNextBlockEnvAttributes};
 mod error;
 pub use error::TempoEvmError;
 pub mod evm;
+pub mod parallel;
+pub use parallel::{
+    ParallelExecution, ParallelStateSource, PrebuiltExecution, SharedStateDatabase,
+};
+#[cfg(test)]
+mod test_utils;
 use std::{borrow::Cow, sync::Arc};
 
 use alloy_evm::{
@@ -23,19 +29,20 @@ use alloy_evm::{
     revm::{Inspector, database::State},
 };
 pub use evm::TempoEvmFactory;
+use rayon::prelude::*;
 use reth_chainspec::EthChainSpec;
 use reth_evm::{
     self, ConfigureEngineEvm, ConfigureEvm, EvmEnvFor, ExecutableTxIterator, ExecutionCtxFor,
     FromRecoveredTx, RecoveredTx, ToTxEnv,
 };
-use reth_primitives_traits::{SealedBlock, SealedHeader, SignedTransaction};
+use reth_primitives_traits::{Recovered, SealedBlock, SealedHeader, SignedTransaction};
 use tempo_payload_types::TempoExecutionData;
 use tempo_primitives::{
     Block, SubBlockMetadata, TempoHeader, TempoPrimitives, TempoReceipt, TempoTxEnvelope,
     subblock::PartialValidatorKey,
 };
 
-use crate::{block::TempoBlockExecutor, evm::TempoEvm};
+use crate::{block::TempoBlockExecutor, evm::TempoEvm, parallel::MIN_PARALLEL_TRANSACTIONS};
 use reth_evm_ethereum::EthEvmConfig;
 use tempo_chainspec::{TempoChainSpec, hardfork::TempoHardforks};
 use tempo_revm::{TempoTxEnv, evm::TempoContext};
@@ -50,6 +57,11 @@ pub struct TempoEvmConfig {
 
     /// Block assembler
     pub block_assembler: TempoBlockAssembler,
+
+    /// Source of the parent state for the parallel execution of payment transactions.
+    ///
+    /// Parallel execution is disabled when this is `None`.
+    pub parallel_state: Option<Arc<dyn ParallelStateSource>>,
 }
 
 impl TempoEvmConfig {
@@ -59,9 +71,22 @@ impl TempoEvmConfig {
         Self {
             inner,
             block_assembler: TempoBlockAssembler::new(chain_spec),
+            parallel_state: None,
         }
     }
 
+    /// Enables the parallel execution of payment transactions, reading the parent state of the
+    /// executed blocks from the given source.
+    pub fn with_parallel_execution(mut self, source: impl ParallelStateSource) -> Self {
+        self.parallel_state = Some(Arc::new(source));
+        self
+    }
+
+    /// Returns whether the parallel execution of payment transactions is enabled.
+    pub const fn is_parallel_execution_enabled(&self) -> bool {
+        self.parallel_state.is_some()
+    }
+
     /// Create a new [`TempoEvmConfig`] with the given chain spec and default EVM factory.
     pub fn new_with_default_factory(chain_spec: Arc<TempoChainSpec>) -> Self {
         Self::new(chain_spec, TempoEvmFactory::default())
@@ -76,6 +101,24 @@ impl TempoEvmConfig {
     pub const fn inner(&self) -> &EthEvmConfig<TempoChainSpec, TempoEvmFactory> {
         &self.inner
     }
+
+    /// Returns the parallel execution context for a block on top of the given parent, if there
+    /// are enough payment transactions to benefit from it.
+    fn parallel_execution(
+        &self,
+        parent_hash: B256,
+        transactions: Vec<Recovered<TempoTxEnvelope>>,
+    ) -> Option<ParallelExecution> {
+        let source = self.parallel_state.as_ref()?;
+        if transactions.len() < MIN_PARALLEL_TRANSACTIONS {
+            return None;
+        }
+
+        Some(ParallelExecution {
+            state: source.state_at(parent_hash)?,
+            transactions,
+        })
+    }
 }
 
 impl BlockExecutorFactory for TempoEvmConfig {
@@ -132,6 +175,7 @@ impl ConfigureEvm for TempoEvmConfig {
             block_env: TempoBlockEnv {
                 inner: block_env,
                 timestamp_millis_part: header.timestamp_millis_part,
+                payment_basefee: header.lane_fees.map(|fees| fees.payment_base_fee_per_gas),
             },
         })
     }
@@ -165,6 +209,9 @@ impl ConfigureEvm for TempoEvmConfig {
             block_env: TempoBlockEnv {
                 inner: block_env,
                 timestamp_millis_part: attributes.timestamp_millis_part,
+                payment_basefee: self
+                    .chain_spec()
+                    .next_block_payment_base_fee(parent, attributes.timestamp),
             },
         })
     }
@@ -186,11 +233,27 @@ impl ConfigureEvm for TempoEvmConfig {
             .map(|metadata| {
                 (
                     PartialValidatorKey::from_slice(&metadata.validator[..15]),
-                    metadata.fee_recipient,
+                    metadata.fee_recipients(),
                 )
             })
             .collect();
 
+        let parallel = if self.is_parallel_execution_enabled() {
+            let transactions = block
+                .body()
+                .transactions
+                .par_iter()
+                .filter(|tx| ParallelExecution::is_candidate(tx))
+                .filter_map(|tx| {
+                    let signer = tx.try_recover().ok()?;
+                    Some(Recovered::new_unchecked(tx.clone(), signer))
+                })
+                .collect();
+            self.parallel_execution(block.header().parent_hash(), transactions)
+        } else {
+            None
+        };
+
         Ok(TempoBlockExecutionCtx {
             inner: EthBlockExecutionCtx {
                 parent_hash: block.header().parent_hash(),
@@ -206,6 +269,10 @@ impl ConfigureEvm for TempoEvmConfig {
             // Not available when we only have a block body.
             validator_set: None,
             subblock_fee_recipients,
+            parallel,
+            prebuilt: None,
+            // Not available when we only have a block body.
+            decrypted_transactions: None,
         })
     }
 
@@ -214,6 +281,8 @@ impl ConfigureEvm for TempoEvmConfig {
         parent: &SealedHeader<TempoHeader>,
         attributes: Self::NextBlockEnvCtx,
     ) -> Result<TempoBlockExecutionCtx<'_>, Self::Error> {
+        let parallel = self.parallel_execution(parent.hash(), attributes.speculative_transactions);
+
         Ok(TempoBlockExecutionCtx {
             inner: EthBlockExecutionCtx {
                 parent_hash: parent.hash(),
@@ -228,6 +297,10 @@ impl ConfigureEvm for TempoEvmConfig {
             // Fine to not validate during block building.
             validator_set: None,
             subblock_fee_recipients: attributes.subblock_fee_recipients,
+            parallel,
+            prebuilt: attributes.prebuilt,
+            // Fine to not validate during block building.
+            decrypted_transactions: None,
         })
     }
 }
@@ -247,10 +320,12 @@ impl ConfigureEngineEvm<TempoExecutionData> for TempoEvmConfig {
         let TempoExecutionData {
             block,
             validator_set,
+            decrypted_transactions,
         } = payload;
         let mut context = self.context_for_block(block)?;
 
         context.validator_set = validator_set.clone();
+        context.decrypted_transactions = decrypted_transactions.clone();
 
         Ok(context)
     }
@@ -0,0 +1,884 @@
+//! Optimistic parallel execution of payment lane transactions.
+//!
+//! Payment transactions mostly touch a small set of per-account precompile storage slots
+//! (balances, nonce keys, rewards info), so most of them can be executed independently of each
+//! other. The block executor uses this module to speculatively execute the upcoming payment
+//! transactions of a block concurrently, each on top of a snapshot of the state taken when the
+//! speculation round started.
+//!
+//! Every speculative execution records the values it read. When the transaction is reached by the
+//! block executor, those values are compared to the current state: if they all match, the
+//! transaction would observe the exact same state when executed sequentially and its speculative
+//! result is committed as is. Otherwise the remaining transactions are speculatively executed again
+//! on top of the current state, up to [`MAX_SPECULATION_ROUNDS`] times, after which the executor
+//! falls back to sequential execution. This keeps the resulting state identical to sequential
+//! execution.
+//!
+//! Every transaction increments the fee manager's balance of its fee token and the fee manager's
+//! collected fees of the block beneficiary. These slots are treated as commutative accumulators:
+//! a speculative result which only increased them is rebased on top of their current value instead
+//! of being invalidated. This is only sound if the transaction can't observe these slots besides the
+//! fee collection, so transactions whose input references the fee manager are never rebased.
+//!
+//! Transactions executed ahead of a block, e.g. while pre-building a payload, are committed the
+//! same way through a [`PrebuiltExecution`], provided the block has the environment they were
+//! executed in.
+
+use crate::{TempoBlockEnv, evm::TempoEvm};
+use alloy_consensus::transaction::TxHashRef as _;
+use alloy_evm::{Evm, EvmEnv};
+use alloy_primitives::{Address, B256, Log, TxHash, U256};
+use rayon::prelude::*;
+use reth_errors::ProviderError;
+use reth_evm::FromRecoveredTx;
+use reth_primitives_traits::Recovered;
+use reth_revm::{State, database::StateProviderDatabase};
+use reth_storage_api::StateProviderFactory;
+use revm::{
+    Database, DatabaseCommit, DatabaseRef,
+    context::result::{EVMError, ExecutionResult, ResultAndState},
+    database::CacheState,
+    state::{AccountInfo, Bytecode},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
+use tempo_chainspec::hardfork::TempoHardfork;
+use tempo_precompiles::{
+    TIP_FEE_MANAGER_ADDRESS,
+    metrics::ExecutionContext,
+    storage::StorageKey as _,
+    tip_fee_manager,
+    tip20::{self, is_tip20_prefix},
+};
+use tempo_primitives::TempoTxEnvelope;
+use tempo_revm::{TempoHaltReason, TempoInvalidTransaction, TempoTxEnv};
+use tracing::trace;
+
+/// Minimum number of payment transactions for a block to be executed in parallel.
+pub const MIN_PARALLEL_TRANSACTIONS: usize = 16;
+
+/// Maximum number of speculation rounds per block.
+///
+/// The first round usually invalidates the transactions following the first writer of a
+/// once-per-block slot (e.g. the rewards accrual timestamp of a token), so a couple of rounds are
+/// needed even for blocks without any real conflicts.
+pub const MAX_SPECULATION_ROUNDS: usize = 4;
+
+/// Read-only view of the parent state shared by the speculative executions.
+pub type SharedStateDatabase = Arc<dyn DatabaseRef<Error = ProviderError> + Send + Sync>;
+
+/// Source of the parent state for parallel execution.
+pub trait ParallelStateSource: fmt::Debug + Send + Sync + 'static {
+    /// Returns the state at the given block hash, if it is available.
+    fn state_at(&self, block_hash: B256) -> Option<SharedStateDatabase>;
+}
+
+impl<P> ParallelStateSource for P
+where
+    P: StateProviderFactory + fmt::Debug + Send + Sync + 'static,
+{
+    fn state_at(&self, block_hash: B256) -> Option<SharedStateDatabase> {
+        let state = self.state_by_block_hash(block_hash).ok()?;
+        Some(Arc::new(StateProviderDatabase::new(state)))
+    }
+}
+
+/// Parallel execution context of a block.
+#[derive(Clone)]
+pub struct ParallelExecution {
+    /// State of the parent block.
+    pub state: SharedStateDatabase,
+    /// Payment transactions expected to be executed in the block, in execution order.
+    ///
+    /// Transactions which end up not being executed are ignored, and executed transactions
+    /// missing from this list are executed sequentially.
+    pub transactions: Vec<Recovered<TempoTxEnvelope>>,
+}
+
+impl ParallelExecution {
+    /// Returns whether the transaction can be executed speculatively.
+    ///
+    /// Only payment lane transactions are considered. Subblock transactions are excluded because
+    /// their fee recipient depends on their position in the subblock.
+    pub fn is_candidate(tx: &TempoTxEnvelope) -> bool {
+        tx.is_payment() && !tx.is_system_tx() && tx.subblock_proposer().is_none()
+    }
+}
+
+impl fmt::Debug for ParallelExecution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParallelExecution")
+            .field("transactions", &self.transactions.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Transactions executed ahead of a block, in order, on top of its parent state.
+///
+/// The block executor commits their results without executing them again if the block has the
+/// same environment and the values they read are unchanged when they are reached, see
+/// [`Speculation::validate`].
+pub struct PrebuiltExecution {
+    env: EvmEnv<TempoHardfork, TempoBlockEnv>,
+    results: HashMap<TxHash, Speculation>,
+    fee_slots: FeeSlots,
+}
+
+impl PrebuiltExecution {
+    /// Creates an empty prebuilt execution in the given block environment.
+    pub fn new(env: EvmEnv<TempoHardfork, TempoBlockEnv>) -> Self {
+        let fee_slots = FeeSlots::new(env.block_env.beneficiary);
+        Self {
+            env,
+            results: HashMap::new(),
+            fee_slots,
+        }
+    }
+
+    /// Returns the environment the transactions are executed in.
+    pub const fn env(&self) -> &EvmEnv<TempoHardfork, TempoBlockEnv> {
+        &self.env
+    }
+
+    /// Returns the number of executed transactions.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Returns true if no transaction was executed.
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Executes the transaction on top of `state` and commits it, recording the values it read.
+    pub fn execute<DB: Database>(
+        &mut self,
+        tx: &Recovered<TempoTxEnvelope>,
+        state: &mut State<DB>,
+    ) -> Result<ExecutionResult<TempoHaltReason>, EVMError<DB::Error, TempoInvalidTransaction>>
+    {
+        let mut evm = TempoEvm::new(RecordingDb::new(&mut *state), self.env.clone());
+        let output = evm.transact(TempoTxEnv::from_recovered_tx(tx.inner(), tx.signer()))?;
+        let revert_logs = evm.take_revert_logs();
+        let (db, _) = evm.finish();
+        let reads = db.reads;
+
+        state.commit(output.state.clone());
+        let result = output.result.clone();
+        self.results.insert(
+            *tx.tx_hash(),
+            Speculation {
+                output,
+                revert_logs,
+                reads,
+                // Only candidates are known to observe the fee slots through their input alone.
+                rebase_fees: ParallelExecution::is_candidate(tx.inner())
+                    && !observes_fee_slots(tx.inner()),
+            },
+        );
+        Ok(result)
+    }
+
+    /// Returns whether the block environment matches the one the transactions were executed in.
+    pub(crate) fn is_env(&self, env: &EvmEnv<TempoHardfork, TempoBlockEnv>) -> bool {
+        self.env.cfg_env.spec == env.cfg_env.spec
+            && self.env.cfg_env.chain_id == env.cfg_env.chain_id
+            && self.env.block_env == env.block_env
+    }
+
+    /// Returns the prebuilt result of the given transaction, if any.
+    pub(crate) fn get(&self, tx_hash: &TxHash) -> Option<&Speculation> {
+        self.results.get(tx_hash)
+    }
+
+    pub(crate) const fn fee_slots(&self) -> &FeeSlots {
+        &self.fee_slots
+    }
+}
+
+impl fmt::Debug for PrebuiltExecution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrebuiltExecution")
+            .field("transactions", &self.results.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Speculative executor for the payment transactions of a block.
+pub(crate) struct ParallelExecutor {
+    state: SharedStateDatabase,
+    /// Candidate transactions which were not executed yet.
+    pending: Vec<Recovered<TempoTxEnvelope>>,
+    /// Hashes of the transactions executed in the block.
+    executed: HashSet<TxHash>,
+    /// Speculative results of the latest round.
+    speculations: HashMap<TxHash, Speculation>,
+    /// Number of speculation rounds performed.
+    rounds: usize,
+    fee_slots: FeeSlots,
+}
+
+impl ParallelExecutor {
+    pub(crate) fn new(parallel: ParallelExecution, beneficiary: Address) -> Self {
+        Self {
+            state: parallel.state,
+            pending: parallel.transactions,
+            executed: HashSet::new(),
+            speculations: HashMap::new(),
+            rounds: 0,
+            fee_slots: FeeSlots::new(beneficiary),
+        }
+    }
+
+    /// Takes the speculative result of the given transaction, if any.
+    pub(crate) fn take(&mut self, tx_hash: &TxHash) -> Option<Speculation> {
+        self.speculations.remove(tx_hash)
+    }
+
+    /// Returns whether a new speculation round should be started to execute the given transaction.
+    ///
+    /// The first round is started lazily once the first candidate is reached, so that it runs on
+    /// top of the start-of-block system transactions. Later rounds are only started on conflicts.
+    pub(crate) fn should_speculate(&self, tx_hash: &TxHash, conflict: bool) -> bool {
+        (conflict || self.rounds == 0)
+            && self.rounds < MAX_SPECULATION_ROUNDS
+            && self.pending.iter().any(|tx| tx.tx_hash() == tx_hash)
+    }
+
+    /// Records the transaction as executed.
+    pub(crate) fn on_executed(&mut self, tx_hash: TxHash) {
+        self.executed.insert(tx_hash);
+    }
+
+    /// Speculatively executes the pending transactions on top of the given state.
+    ///
+    /// Only the first pending transaction of each sender is executed, the following ones would
+    /// read a stale nonce.
+    pub(crate) fn speculate(
+        &mut self,
+        env: EvmEnv<TempoHardfork, TempoBlockEnv>,
+        cache: &CacheState,
+    ) {
+        self.pending
+            .retain(|tx| !self.executed.contains(tx.tx_hash()));
+        self.rounds += 1;
+
+        let mut senders = HashSet::new();
+        let batch = self
+            .pending
+            .iter()
+            .filter(|tx| senders.insert(tx.signer()))
+            .collect::<Vec<_>>();
+
+        let state = &*self.state;
+        self.speculations = batch
+            .into_par_iter()
+            .filter_map(|tx| {
+                ExecutionContext::Speculative
+                    .enter(|| Speculation::execute(tx, env.clone(), cache, state))
+                    .map(|s| (*tx.tx_hash(), s))
+            })
+            .collect();
+
+        trace!(
+            target: "tempo::parallel",
+            round = self.rounds,
+            pending = self.pending.len(),
+            speculated = self.speculations.len(),
+            "Speculatively executed payment transactions"
+        );
+    }
+
+    pub(crate) const fn fee_slots(&self) -> &FeeSlots {
+        &self.fee_slots
+    }
+}
+
+/// Commutative fee accumulator slots of a block.
+pub(crate) struct FeeSlots {
+    /// Slot of the fee manager's balance in any TIP-20 token.
+    fee_manager_balance: U256,
+    /// Slot of the beneficiary's collected fees in the fee manager.
+    collected_fees: U256,
+}
+
+impl FeeSlots {
+    fn new(beneficiary: Address) -> Self {
+        Self {
+            fee_manager_balance: TIP_FEE_MANAGER_ADDRESS.mapping_slot(tip20::slots::BALANCES),
+            collected_fees: beneficiary.mapping_slot(tip_fee_manager::slots::COLLECTED_FEES),
+        }
+    }
+
+    fn contains(&self, address: Address, slot: U256) -> bool {
+        (is_tip20_prefix(address) && slot == self.fee_manager_balance)
+            || (address == TIP_FEE_MANAGER_ADDRESS && slot == self.collected_fees)
+    }
+}
+
+/// Returns whether the transaction may observe the fee accumulator slots outside of the fee
+/// collection.
+///
+/// Candidates only call TIP-20 tokens, which never call back into other contracts. They only
+/// access the balance or the storage of the fee manager if its address is part of the call input
+/// (e.g. `balanceOf(feeManager)`).
+fn observes_fee_slots(tx: &TempoTxEnvelope) -> bool {
+    tx.calls().any(|(_, input)| {
+        input
+            .windows(TIP_FEE_MANAGER_ADDRESS.len())
+            .any(|window| window == TIP_FEE_MANAGER_ADDRESS.as_slice())
+    })
+}
+
+/// Result of a speculative transaction execution.
+#[derive(Clone)]
+pub(crate) struct Speculation {
+    output: ResultAndState<TempoHaltReason>,
+    revert_logs: Vec<Log>,
+    reads: ReadSet,
+    /// Whether the fee accumulator slots can be rebased, see [`observes_fee_slots`].
+    rebase_fees: bool,
+}
+
+impl Speculation {
+    fn execute(
+        tx: &Recovered<TempoTxEnvelope>,
+        env: EvmEnv<TempoHardfork, TempoBlockEnv>,
+        cache: &CacheState,
+        state: &(dyn DatabaseRef<Error = ProviderError> + Send + Sync),
+    ) -> Option<Self> {
+        let mut evm = TempoEvm::new(SpeculativeDb::new(cache, state), env);
+
+        // Invalid transactions are left to the sequential execution which reports the error.
+        let output = evm
+            .transact(TempoTxEnv::from_recovered_tx(tx.inner(), tx.signer()))
+            .ok()?;
+        let revert_logs = evm.take_revert_logs();
+        let (db, _) = evm.finish();
+
+        Some(Self {
+            output,
+            revert_logs,
+            reads: db.reads,
+            rebase_fees: !observes_fee_slots(tx.inner()),
+        })
+    }
+
+    /// Validates the speculative result against the current state.
+    ///
+    /// Returns the result and the revert logs of the transaction if all of the values it read
+    /// are unchanged, rebasing the commutative fee slots on top of their current values.
+    pub(crate) fn validate<DB: Database>(
+        self,
+        state: &mut State<DB>,
+        fee_slots: &FeeSlots,
+    ) -> Result<Option<(ResultAndState<TempoHaltReason>, Vec<Log>)>, DB::Error> {
+        let Self {
+            mut output,
+            revert_logs,
+            reads,
+            rebase_fees,
+        } = self;
+
+        // Loading the accounts and slots through the state also populates its cache, which is
+        // required to commit the result.
+        for (address, info) in reads.accounts {
+            let current = state.basic(address)?;
+            if current
+                .as_ref()
+                .map(|info| (info.balance, info.nonce, info.code_hash))
+                != info.map(|info| (info.balance, info.nonce, info.code_hash))
+            {
+                return Ok(None);
+            }
+        }
+
+        for (address, slot, value) in reads.storage {
+            let current = state.storage(address, slot)?;
+            if current == value {
+                continue;
+            }
+
+            if !rebase_fees
+                || !fee_slots.contains(address, slot)
+                || value.is_zero()
+                || current.is_zero()
+            {
+                return Ok(None);
+            }
+
+            // Only rebase accumulators which were strictly increased by the transaction.
+            let Some(entry) = output
+                .state
+                .get_mut(&address)
+                .and_then(|account| account.storage.get_mut(&slot))
+                .filter(|entry| entry.present_value > value)
+            else {
+                return Ok(None);
+            };
+            let Some(present) = current.checked_add(entry.present_value - value) else {
+                return Ok(None);
+            };
+
+            entry.original_value = current;
+            entry.present_value = present;
+        }
+
+        Ok(Some((output, revert_logs)))
+    }
+}
+
+/// Values read by a speculative execution.
+#[derive(Clone, Default)]
+struct ReadSet {
+    accounts: Vec<(Address, Option<AccountInfo>)>,
+    storage: Vec<(Address, U256, U256)>,
+}
+
+/// Database of a speculative execution, reading the snapshot of the block state and recording
+/// every value read.
+///
+/// The snapshot consists of the block state cache, which contains everything changed by the
+/// already executed transactions, on top of the parent state.
+struct SpeculativeDb<'a> {
+    cache: &'a CacheState,
+    state: &'a (dyn DatabaseRef<Error = ProviderError> + Send + Sync),
+    reads: ReadSet,
+}
+
+impl<'a> SpeculativeDb<'a> {
+    fn new(
+        cache: &'a CacheState,
+        state: &'a (dyn DatabaseRef<Error = ProviderError> + Send + Sync),
+    ) -> Self {
+        Self {
+            cache,
+            state,
+            reads: ReadSet::default(),
+        }
+    }
+}
+
+impl fmt::Debug for SpeculativeDb<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpeculativeDb").finish_non_exhaustive()
+    }
+}
+
+impl Database for SpeculativeDb<'_> {
+    type Error = ProviderError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = match self.cache.accounts.get(&address) {
+            Some(account) => account.account_info(),
+            None => self.state.basic_ref(address)?,
+        };
+        self.reads.accounts.push((address, info.clone()));
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        match self.cache.contracts.get(&code_hash) {
+            Some(code) => Ok(code.clone()),
+            None => self.state.code_by_hash_ref(code_hash),
+        }
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = match self.cache.accounts.get(&address) {
+            Some(account) => match account.account.as_ref() {
+                Some(plain) => match plain.storage.get(&index) {
+                    Some(value) => *value,
+                    None if account.status.is_storage_known() => U256::ZERO,
+                    None => self.state.storage_ref(address, index)?,
+                },
+                None => U256::ZERO,
+            },
+            None => self.state.storage_ref(address, index)?,
+        };
+        self.reads.storage.push((address, index, value));
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.state.block_hash_ref(number)
+    }
+}
+
+/// Database recording every value read from the wrapped database.
+struct RecordingDb<DB> {
+    inner: DB,
+    reads: ReadSet,
+}
+
+impl<DB> RecordingDb<DB> {
+    fn new(inner: DB) -> Self {
+        Self {
+            inner,
+            reads: ReadSet::default(),
+        }
+    }
+}
+
+impl<DB> fmt::Debug for RecordingDb<DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordingDb").finish_non_exhaustive()
+    }
+}
+
+impl<DB: Database> Database for RecordingDb<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.inner.basic(address)?;
+        self.reads.accounts.push((address, info.clone()));
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.inner.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.inner.storage(address, index)?;
+        self.reads.storage.push((address, index, value));
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.inner.block_hash(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TempoBlockExecutionCtx, TempoEvmConfig,
+        block::TempoBlockExecutor,
+        test_utils::{
+            TOKEN, call, execution_ctx, fund, genesis_db, header, sender, set_validator_token,
+            transfer, transfer_token,
+        },
+    };
+    use alloy_evm::block::BlockExecutor;
+    use alloy_primitives::{Bytes, address};
+    use alloy_sol_types::SolCall;
+    use reth_evm::ConfigureEvm;
+    use revm::database::{InMemoryDB, states::bundle_state::BundleRetention};
+    use tempo_chainspec::spec::DEV;
+    use tempo_precompiles::{tip_fee_manager::IFeeManager, tip20::ITIP20};
+    use tempo_primitives::TempoReceipt;
+
+    /// Token with a fee AMM pool from [`TOKEN`] in the dev genesis.
+    const OTHER_TOKEN: Address = address!("0x20c0000000000000000000000000000000000002");
+
+    /// Executes the transactions and returns the receipts and the resulting state changes, along
+    /// with the number of speculation rounds.
+    fn execute(
+        db: &InMemoryDB,
+        transactions: &[Recovered<TempoTxEnvelope>],
+        parallel: bool,
+    ) -> (Vec<TempoReceipt>, revm::database::BundleState, usize) {
+        execute_with_ctx(
+            db,
+            transactions,
+            execution_ctx(&header(), db, transactions, parallel),
+        )
+    }
+
+    fn execute_with_ctx(
+        db: &InMemoryDB,
+        transactions: &[Recovered<TempoTxEnvelope>],
+        ctx: TempoBlockExecutionCtx<'static>,
+    ) -> (Vec<TempoReceipt>, revm::database::BundleState, usize) {
+        let config = TempoEvmConfig::new_with_default_factory(DEV.clone());
+        let header = header();
+
+        let mut state = State::builder()
+            .with_database(db.clone())
+            .with_bundle_update()
+            .build();
+        let evm = config.evm_with_env(&mut state, config.evm_env(&header).unwrap());
+
+        let mut executor = TempoBlockExecutor::new(evm, ctx, config.chain_spec());
+        for tx in transactions {
+            executor.execute_transaction(tx.as_recovered_ref()).unwrap();
+        }
+        let receipts = executor.inner.receipts.clone();
+        let rounds = executor
+            .parallel
+            .as_ref()
+            .map_or(0, |parallel| parallel.rounds);
+        drop(executor);
+
+        state.merge_transitions(BundleRetention::Reverts);
+        (receipts, state.take_bundle(), rounds)
+    }
+
+    /// Asserts that parallel and sequential execution of the transactions produce the same
+    /// receipts and state, and returns the receipts.
+    fn assert_equivalent(
+        db: &InMemoryDB,
+        transactions: &[Recovered<TempoTxEnvelope>],
+    ) -> Vec<TempoReceipt> {
+        let (sequential_receipts, sequential_state, _) = execute(db, transactions, false);
+        let (parallel_receipts, parallel_state, rounds) = execute(db, transactions, true);
+
+        assert!(rounds > 0);
+        assert_eq!(parallel_receipts, sequential_receipts);
+        assert_eq!(parallel_state, sequential_state);
+        sequential_receipts
+    }
+
+    #[test]
+    fn parallel_execution_matches_sequential_execution() {
+        let db = genesis_db(64);
+
+        let mut transactions = (0..64)
+            .map(|index| transfer(sender(index), 0, Address::repeat_byte(0x42)))
+            .collect::<Vec<_>>();
+        // Second transaction of the same sender.
+        transactions.push(transfer(sender(0), 1, Address::repeat_byte(0x42)));
+        // Transfer to a sender of a later transaction.
+        transactions.insert(10, transfer(sender(5), 1, sender(20)));
+
+        let receipts = assert_equivalent(&db, &transactions);
+        assert!(receipts.iter().all(|receipt| receipt.success));
+    }
+
+    #[test]
+    fn conflicting_transactions_match_sequential_execution() {
+        let db = genesis_db(32);
+
+        // Every sender forwards more than its initial balance, which only succeeds once the
+        // transfer of the previous sender was executed.
+        let mut transactions = vec![transfer_token(
+            TOKEN,
+            sender(0),
+            0,
+            sender(1),
+            U256::from(1_000_000_000),
+        )];
+        transactions.extend((1..32).map(|index| {
+            transfer_token(
+                TOKEN,
+                sender(index),
+                0,
+                sender(index + 1),
+                U256::from(u64::MAX) + U256::from(1),
+            )
+        }));
+
+        let receipts = assert_equivalent(&db, &transactions);
+        assert!(receipts.iter().all(|receipt| receipt.success));
+    }
+
+    #[test]
+    fn reverting_transactions_match_sequential_execution() {
+        let db = genesis_db(32);
+
+        let transactions = (0..32)
+            .map(|index| {
+                // Every fourth transfer exceeds the balance of its sender.
+                let amount = if index % 4 == 0 {
+                    U256::MAX
+                } else {
+                    U256::from(1_000)
+                };
+                transfer_token(TOKEN, sender(index), 0, sender((index + 1) % 32), amount)
+            })
+            .collect::<Vec<_>>();
+
+        let receipts = assert_equivalent(&db, &transactions);
+        for (index, receipt) in receipts.iter().enumerate() {
+            assert_eq!(receipt.success, index % 4 != 0);
+        }
+    }
+
+    #[test]
+    fn multiple_fee_tokens_match_sequential_execution() {
+        // Fees paid in [`TOKEN`] are swapped to the validator token through the fee AMM.
+        let mut db = genesis_db(33);
+        set_validator_token(&mut db, OTHER_TOKEN);
+        for index in 0..33 {
+            fund(&mut db, OTHER_TOKEN, sender(index));
+        }
+
+        let mut transactions = (0..32)
+            .map(|index| {
+                let token = if index % 2 == 0 { TOKEN } else { OTHER_TOKEN };
+                transfer_token(
+                    token,
+                    sender(index),
+                    0,
+                    Address::repeat_byte(0x42),
+                    U256::from(1_000),
+                )
+            })
+            .collect::<Vec<_>>();
+        // Non-payment transaction changing the fee token of a sender in the middle of the block,
+        // so that its following transfer of [`OTHER_TOKEN`] pays fees in [`TOKEN`].
+        transactions.insert(
+            16,
+            call(
+                sender(32),
+                0,
+                TIP_FEE_MANAGER_ADDRESS,
+                IFeeManager::setUserTokenCall { token: TOKEN }
+                    .abi_encode()
+                    .into(),
+            ),
+        );
+        transactions.push(transfer_token(
+            OTHER_TOKEN,
+            sender(32),
+            1,
+            sender(0),
+            U256::from(1_000),
+        ));
+
+        let receipts = assert_equivalent(&db, &transactions);
+        assert!(receipts.iter().all(|receipt| receipt.success));
+    }
+
+    #[test]
+    fn transactions_observing_fee_slots_are_not_rebased() {
+        let db = genesis_db(32);
+
+        let balance_of = ITIP20::balanceOfCall {
+            account: TIP_FEE_MANAGER_ADDRESS,
+        }
+        .abi_encode();
+        assert!(!observes_fee_slots(
+            transfer(sender(0), 0, sender(1)).inner()
+        ));
+        assert!(observes_fee_slots(
+            call(sender(0), 0, TOKEN, balance_of.clone().into()).inner()
+        ));
+
+        let transactions = (0..32)
+            .map(|index| match index % 3 {
+                0 => call(sender(index), 0, TOKEN, balance_of.clone().into()),
+                1 => transfer_token(
+                    TOKEN,
+                    sender(index),
+                    0,
+                    TIP_FEE_MANAGER_ADDRESS,
+                    U256::from(1_000),
+                ),
+                _ => transfer(sender(index), 0, sender(0)),
+            })
+            .collect::<Vec<_>>();
+
+        assert_equivalent(&db, &transactions);
+    }
+
+    /// Address of the log marking the results committed from a prebuilt execution.
+    const PREBUILT_MARKER: Address = Address::repeat_byte(0xaa);
+
+    /// Executes the transactions ahead of the block in the given environment, marking their
+    /// results so that committing them can be told apart from executing them again.
+    fn prebuild(
+        db: &InMemoryDB,
+        transactions: &[Recovered<TempoTxEnvelope>],
+        env: EvmEnv<TempoHardfork, TempoBlockEnv>,
+    ) -> PrebuiltExecution {
+        let mut state = State::builder()
+            .with_database(db.clone())
+            .with_bundle_update()
+            .build();
+        let mut prebuilt = PrebuiltExecution::new(env);
+        for tx in transactions {
+            prebuilt.execute(tx, &mut state).unwrap();
+        }
+        assert_eq!(prebuilt.len(), transactions.len());
+
+        for speculation in prebuilt.results.values_mut() {
+            speculation.revert_logs.push(Log::new_unchecked(
+                PREBUILT_MARKER,
+                Vec::new(),
+                Bytes::new(),
+            ));
+        }
+        prebuilt
+    }
+
+    /// Executes the transactions with the given prebuilt execution and returns whether each
+    /// result was committed from it, asserting that the receipts and state otherwise match
+    /// sequential execution.
+    fn execute_prebuilt(
+        db: &InMemoryDB,
+        transactions: &[Recovered<TempoTxEnvelope>],
+        prebuilt: PrebuiltExecution,
+    ) -> Vec<bool> {
+        let (sequential_receipts, sequential_state, _) = execute(db, transactions, false);
+
+        let mut ctx = execution_ctx(&header(), db, transactions, false);
+        ctx.prebuilt = Some(Arc::new(prebuilt));
+        let (mut receipts, state, _) = execute_with_ctx(db, transactions, ctx);
+
+        let reused = receipts
+            .iter_mut()
+            .map(|receipt| {
+                let len = receipt.logs.len();
+                receipt.logs.retain(|log| log.address != PREBUILT_MARKER);
+                receipt.logs.len() != len
+            })
+            .collect();
+        assert_eq!(receipts, sequential_receipts);
+        assert_eq!(state, sequential_state);
+        reused
+    }
+
+    #[test]
+    fn prebuilt_transactions_are_committed_without_execution() {
+        let db = genesis_db(32);
+        let config = TempoEvmConfig::new_with_default_factory(DEV.clone());
+        let env = config.evm_env(&header()).unwrap();
+
+        let prebuilt_transactions = (0..16)
+            .map(|index| transfer(sender(index), 0, sender(index + 1)))
+            .collect::<Vec<_>>();
+        let prebuilt = prebuild(&db, &prebuilt_transactions, env);
+
+        // The block executes another transaction before the prebuilt ones, changing the balance
+        // of a sender of a prebuilt transaction, and new transactions after them.
+        let mut transactions = vec![transfer_token(
+            TOKEN,
+            sender(20),
+            0,
+            sender(8),
+            U256::from(1_000),
+        )];
+        transactions.extend(prebuilt_transactions);
+        transactions.extend((16..20).map(|index| transfer(sender(index), 0, sender(0))));
+
+        let reused = execute_prebuilt(&db, &transactions, prebuilt);
+        // The prebuilt transactions which read the changed balance are executed again, and so are
+        // the transactions that were not prebuilt.
+        assert!(!reused[0]);
+        assert!(!reused[8] && !reused[9]);
+        assert!(reused[10..=16].iter().all(|reused| *reused));
+        assert!(reused[17..].iter().all(|reused| !reused));
+    }
+
+    #[test]
+    fn prebuilt_transactions_are_executed_in_another_environment() {
+        let db = genesis_db(16);
+        let config = TempoEvmConfig::new_with_default_factory(DEV.clone());
+        let mut env = config.evm_env(&header()).unwrap();
+        env.block_env.timestamp += U256::from(1);
+
+        let transactions = (0..16)
+            .map(|index| transfer(sender(index), 0, sender((index + 1) % 16)))
+            .collect::<Vec<_>>();
+        let prebuilt = prebuild(&db, &transactions, env);
+
+        let reused = execute_prebuilt(&db, &transactions, prebuilt);
+        assert!(reused.iter().all(|reused| !reused));
+    }
+}
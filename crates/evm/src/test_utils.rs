@@ -0,0 +1,204 @@
+//! Helpers for executing blocks in tests.
+
+use crate::{ParallelExecution, TempoBlockExecutionCtx};
+use alloy_consensus::{Header, Signed, TxLegacy};
+use alloy_evm::eth::EthBlockExecutionCtx;
+use alloy_primitives::{Address, B256, Bytes, Signature, TxKind, U256};
+use alloy_sol_types::SolCall;
+use reth_chainspec::EthChainSpec;
+use reth_errors::ProviderError;
+use reth_primitives_traits::Recovered;
+use revm::{
+    DatabaseRef,
+    database::InMemoryDB,
+    state::{AccountInfo, Bytecode},
+};
+use std::{convert::Infallible, sync::Arc};
+use tempo_chainspec::spec::{DEV, TEMPO_BASE_FEE};
+use tempo_precompiles::{
+    DEFAULT_FEE_TOKEN_PRE_ALLEGRETTO, TIP_FEE_MANAGER_ADDRESS,
+    storage::StorageKey as _,
+    tip_fee_manager,
+    tip20::{self, ITIP20},
+};
+use tempo_primitives::{TempoHeader, TempoTxEnvelope};
+
+pub(crate) const TOKEN: Address = DEFAULT_FEE_TOKEN_PRE_ALLEGRETTO;
+pub(crate) const BENEFICIARY: Address = Address::repeat_byte(0xbe);
+
+#[derive(Debug)]
+pub(crate) struct SharedDb(pub(crate) InMemoryDB);
+
+impl DatabaseRef for SharedDb {
+    type Error = ProviderError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.0
+            .basic_ref(address)
+            .map_err(|err: Infallible| match err {})
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.0
+            .code_by_hash_ref(code_hash)
+            .map_err(|err: Infallible| match err {})
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.0
+            .storage_ref(address, index)
+            .map_err(|err: Infallible| match err {})
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.0
+            .block_hash_ref(number)
+            .map_err(|err: Infallible| match err {})
+    }
+}
+
+pub(crate) fn sender(index: usize) -> Address {
+    Address::left_padding_from(&(index as u64 + 1).to_be_bytes())
+}
+
+/// Dev genesis state with the beneficiary accepting fees in [`TOKEN`] and `senders` funded
+/// accounts.
+pub(crate) fn genesis_db(senders: usize) -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    for (address, account) in &DEV.genesis().alloc {
+        let mut info = AccountInfo::from_balance(account.balance);
+        info.nonce = account.nonce.unwrap_or_default();
+        if let Some(code) = &account.code {
+            info = info.with_code(Bytecode::new_raw(code.clone()));
+        }
+        db.insert_account_info(*address, info);
+
+        for (slot, value) in account.storage.iter().flatten() {
+            db.insert_account_storage(
+                *address,
+                U256::from_be_bytes(slot.0),
+                U256::from_be_bytes(value.0),
+            )
+            .unwrap();
+        }
+    }
+
+    set_validator_token(&mut db, TOKEN);
+    for index in 0..senders {
+        fund(&mut db, TOKEN, sender(index));
+    }
+
+    db
+}
+
+/// Sets the token in which the beneficiary accepts fees.
+pub(crate) fn set_validator_token(db: &mut InMemoryDB, token: Address) {
+    db.insert_account_storage(
+        TIP_FEE_MANAGER_ADDRESS,
+        BENEFICIARY.mapping_slot(tip_fee_manager::slots::VALIDATOR_TOKENS),
+        U256::from_be_slice(token.as_slice()),
+    )
+    .unwrap();
+}
+
+/// Sets the balance of `account` in `token` to `u64::MAX`.
+pub(crate) fn fund(db: &mut InMemoryDB, token: Address, account: Address) {
+    db.insert_account_storage(
+        token,
+        account.mapping_slot(tip20::slots::BALANCES),
+        U256::from(u64::MAX),
+    )
+    .unwrap();
+}
+
+pub(crate) fn transfer(from: Address, nonce: u64, to: Address) -> Recovered<TempoTxEnvelope> {
+    transfer_token(TOKEN, from, nonce, to, U256::from(1_000))
+}
+
+pub(crate) fn transfer_token(
+    token: Address,
+    from: Address,
+    nonce: u64,
+    to: Address,
+    amount: U256,
+) -> Recovered<TempoTxEnvelope> {
+    call(
+        from,
+        nonce,
+        token,
+        ITIP20::transferCall { to, amount }.abi_encode().into(),
+    )
+}
+
+pub(crate) fn call(
+    from: Address,
+    nonce: u64,
+    to: Address,
+    input: Bytes,
+) -> Recovered<TempoTxEnvelope> {
+    let tx = TxLegacy {
+        chain_id: Some(DEV.chain().id()),
+        nonce,
+        gas_price: TEMPO_BASE_FEE as u128,
+        gas_limit: 300_000,
+        to: TxKind::Call(to),
+        value: U256::ZERO,
+        input,
+    };
+
+    Recovered::new_unchecked(
+        TempoTxEnvelope::Legacy(Signed::new_unhashed(tx, Signature::test_signature())),
+        from,
+    )
+}
+
+/// Header of the first block on top of the dev genesis.
+pub(crate) fn header() -> TempoHeader {
+    TempoHeader {
+        inner: Header {
+            number: 1,
+            timestamp: DEV.genesis().timestamp + 1,
+            gas_limit: 500_000_000,
+            base_fee_per_gas: Some(TEMPO_BASE_FEE),
+            beneficiary: BENEFICIARY,
+            ..Default::default()
+        },
+        general_gas_limit: 30_000_000,
+        shared_gas_limit: 50_000_000,
+        timestamp_millis_part: 0,
+        lane_fees: None,
+    }
+}
+
+/// Execution context of [`header`], with parallel execution of the candidates among
+/// `transactions` if `parallel` is set.
+pub(crate) fn execution_ctx(
+    header: &TempoHeader,
+    db: &InMemoryDB,
+    transactions: &[Recovered<TempoTxEnvelope>],
+    parallel: bool,
+) -> TempoBlockExecutionCtx<'static> {
+    TempoBlockExecutionCtx {
+        inner: EthBlockExecutionCtx {
+            parent_hash: B256::ZERO,
+            parent_beacon_block_root: None,
+            ommers: &[],
+            withdrawals: None,
+            extra_data: Default::default(),
+        },
+        general_gas_limit: header.general_gas_limit,
+        shared_gas_limit: header.shared_gas_limit,
+        validator_set: None,
+        subblock_fee_recipients: Default::default(),
+        parallel: parallel.then(|| ParallelExecution {
+            state: Arc::new(SharedDb(db.clone())),
+            transactions: transactions
+                .iter()
+                .filter(|tx| ParallelExecution::is_candidate(tx))
+                .cloned()
+                .collect(),
+        }),
+        prebuilt: None,
+        decrypted_transactions: None,
+    }
+}
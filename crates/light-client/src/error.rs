@@ -0,0 +1,55 @@
+//! Light client error types.
+
+use alloy_primitives::{Address, B256};
+use alloy_trie::proof::ProofVerificationError;
+use commonware_consensus::types::Epoch;
+
+/// Errors returned when verifying consensus artifacts or state proofs.
+#[derive(Debug, thiserror::Error)]
+pub enum LightClientError {
+    /// The finalization certificate could not be decoded.
+    #[error("failed to decode finalization certificate: {0}")]
+    CertificateDecoding(#[source] commonware_codec::Error),
+
+    /// The finalization certificate is for a different epoch than the one being tracked.
+    #[error(
+        "finalization certificate is for epoch `{got}`, but the client is at epoch `{expected}`"
+    )]
+    EpochMismatch { expected: Epoch, got: Epoch },
+
+    /// The threshold signature of the finalization certificate is invalid.
+    #[error("invalid threshold signature on finalization certificate")]
+    InvalidCertificate,
+
+    /// The header does not hash to the block finalized by the certificate.
+    #[error("header hash `{header}` does not match finalized block `{finalized}`")]
+    HeaderMismatch { header: B256, finalized: B256 },
+
+    /// The header height does not lie within the epoch of its finalization.
+    #[error("block `{number}` is not part of epoch `{epoch}`")]
+    HeaderOutsideEpoch { number: u64, epoch: Epoch },
+
+    /// The DKG outcome in a genesis or boundary header could not be decoded.
+    #[error("failed to decode DKG outcome from header extra data: {0}")]
+    OutcomeDecoding(#[source] commonware_codec::Error),
+
+    /// The DKG outcome in a boundary header is not for the following epoch.
+    #[error("boundary block carries DKG outcome for epoch `{got}`, expected epoch `{expected}`")]
+    UnexpectedOutcomeEpoch { expected: Epoch, got: Epoch },
+
+    /// No header has been verified yet, so there is no state root to verify proofs against.
+    #[error("no finalized header has been verified yet")]
+    NoFinalizedHeader,
+
+    /// The account proof is for a different address than requested.
+    #[error("proof is for account `{got}`, expected `{expected}`")]
+    ProofAddressMismatch { expected: Address, got: Address },
+
+    /// The proof does not contain the requested storage slot.
+    #[error("proof does not contain storage slot `{0}`")]
+    MissingStorageProof(B256),
+
+    /// A Merkle-Patricia proof failed to verify.
+    #[error("invalid merkle proof: {0}")]
+    InvalidProof(#[from] ProofVerificationError),
+}
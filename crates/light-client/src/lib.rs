@@ -0,0 +1,406 @@
+//! A light client verifying Tempo blocks and state by their threshold finalization certificates.
+//!
+//! Tempo blocks are finalized by threshold-simplex, producing a certificate carrying a single
+//! BLS12-381 threshold signature of the epoch's validator group. Starting from a trusted DKG
+//! outcome (usually the one in the genesis header), the [`LightClient`]:
+//!
+//! 1. verifies finalization certificates (as served by `consensus_getFinalization`) against the
+//!    group public key of the current epoch;
+//! 2. verifies block headers against the finalized block hash;
+//! 3. follows epoch transitions by reading the DKG outcome of the next epoch from the finalized
+//!    last block of each epoch;
+//! 4. verifies `eth_getProof` account and storage proofs, e.g. of TIP-20 balances, against the
+//!    state root of the latest finalized header.
+//!
+//! None of these steps require trusting the RPC provider serving the data.
+
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+mod error;
+pub mod proof;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+pub use error::LightClientError;
+
+use alloy_consensus::{BlockHeader as _, Sealable as _};
+use alloy_primitives::{Address, B256, U256};
+use alloy_rpc_types_eth::EIP1186AccountProofResponse;
+use commonware_codec::DecodeExt as _;
+use commonware_consensus::{
+    simplex::{signing_scheme::bls12381_threshold::Scheme, types::Finalization},
+    types::{Epoch, View},
+    utils,
+};
+use commonware_cryptography::{
+    bls12381::primitives::{
+        poly::{self, Public},
+        variant::{MinSig, Variant},
+    },
+    ed25519::PublicKey,
+    sha256,
+};
+use tempo_dkg_onchain_artifacts::PublicOutcome;
+use tempo_primitives::TempoHeader;
+
+pub use tempo_commonware_node_config::NAMESPACE;
+
+/// The BLS12-381 group public key of an epoch's validator set.
+pub type GroupPublicKey = <MinSig as Variant>::Public;
+
+/// The verification scheme of threshold-simplex finalization certificates.
+type CertificateScheme = Scheme<PublicKey, MinSig>;
+
+/// A threshold-simplex finalization certificate.
+///
+/// Block digests are 32-byte hashes on the wire, so certificates are decoded using the plain
+/// sha256 digest type instead of the node's block digest.
+type Certificate = Finalization<CertificateScheme, sha256::Digest>;
+
+/// A block that was proven final by a finalization certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalizedBlock {
+    /// Hash of the finalized block.
+    pub hash: B256,
+    /// Epoch the block was finalized in.
+    pub epoch: Epoch,
+    /// View the block was finalized in.
+    pub view: View,
+}
+
+/// A header that was proven final by a finalization certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalizedHeader {
+    /// The finalized header.
+    pub header: TempoHeader,
+    /// Hash of the header.
+    pub hash: B256,
+    /// Epoch the header was finalized in.
+    pub epoch: Epoch,
+    /// View the header was finalized in.
+    pub view: View,
+}
+
+/// Tracks the validator group of the current epoch and the latest finalized header.
+#[derive(Debug, Clone)]
+pub struct LightClient {
+    /// Number of blocks per epoch.
+    epoch_length: u64,
+    /// The epoch whose certificates are currently accepted.
+    epoch: Epoch,
+    /// Group public key of `epoch`.
+    group_public_key: GroupPublicKey,
+    /// Highest header verified so far.
+    latest: Option<FinalizedHeader>,
+}
+
+impl LightClient {
+    /// Creates a light client trusting the validator group determined by `outcome`.
+    ///
+    /// `outcome` is usually the DKG outcome in the genesis header; any later outcome obtained
+    /// from a trusted source can be used as a checkpoint.
+    pub fn new(epoch_length: u64, outcome: &PublicOutcome) -> Self {
+        Self::from_public_polynomial(epoch_length, outcome.epoch, &outcome.public)
+    }
+
+    /// Creates a light client trusting the validator group with public polynomial `public` in
+    /// `epoch`.
+    ///
+    /// Chains started before Allegretto define the initial public polynomial in the chainspec
+    /// instead of the genesis header.
+    pub fn from_public_polynomial(
+        epoch_length: u64,
+        epoch: Epoch,
+        public: &Public<MinSig>,
+    ) -> Self {
+        Self {
+            epoch_length,
+            epoch,
+            group_public_key: *poly::public::<MinSig>(public),
+            latest: None,
+        }
+    }
+
+    /// Creates a light client trusting the validator group of the DKG outcome in `genesis`.
+    pub fn from_genesis(
+        epoch_length: u64,
+        genesis: &TempoHeader,
+    ) -> Result<Self, LightClientError> {
+        let outcome = PublicOutcome::decode(genesis.extra_data().as_ref())
+            .map_err(LightClientError::OutcomeDecoding)?;
+        Ok(Self::new(epoch_length, &outcome))
+    }
+
+    /// Returns the epoch whose certificates are currently accepted.
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    /// Returns the height of the last block of the current epoch.
+    ///
+    /// Verifying this block's header transitions the client to the next epoch.
+    pub fn epoch_boundary(&self) -> u64 {
+        utils::last_block_in_epoch(self.epoch_length, self.epoch)
+    }
+
+    /// Returns the group public key of the current epoch.
+    pub fn group_public_key(&self) -> &GroupPublicKey {
+        &self.group_public_key
+    }
+
+    /// Returns the highest header verified so far.
+    pub fn latest(&self) -> Option<&FinalizedHeader> {
+        self.latest.as_ref()
+    }
+
+    /// Verifies an encoded finalization `certificate` against the current epoch's group.
+    pub fn verify_finalization(
+        &self,
+        certificate: &[u8],
+    ) -> Result<FinalizedBlock, LightClientError> {
+        let certificate =
+            Certificate::decode(certificate).map_err(LightClientError::CertificateDecoding)?;
+
+        let epoch = certificate.proposal.round.epoch();
+        if epoch != self.epoch {
+            return Err(LightClientError::EpochMismatch {
+                expected: self.epoch,
+                got: epoch,
+            });
+        }
+
+        let scheme = CertificateScheme::certificate_verifier(self.group_public_key);
+        if !certificate.verify(&mut rand::thread_rng(), &scheme, NAMESPACE) {
+            return Err(LightClientError::InvalidCertificate);
+        }
+
+        Ok(FinalizedBlock {
+            hash: B256::from_slice(certificate.proposal.payload.as_ref()),
+            epoch,
+            view: certificate.proposal.round.view(),
+        })
+    }
+
+    /// Verifies that `header` was finalized by `certificate`.
+    ///
+    /// If `header` is the last block of the current epoch, the client transitions to the next
+    /// epoch using the DKG outcome stored in the header's extra data. Certificates of the
+    /// current epoch are not accepted afterwards.
+    pub fn verify_header(
+        &mut self,
+        header: TempoHeader,
+        certificate: &[u8],
+    ) -> Result<FinalizedHeader, LightClientError> {
+        let finalized = self.verify_finalization(certificate)?;
+
+        let hash = header.hash_slow();
+        if hash != finalized.hash {
+            return Err(LightClientError::HeaderMismatch {
+                header: hash,
+                finalized: finalized.hash,
+            });
+        }
+
+        let number = header.number();
+        if utils::epoch(self.epoch_length, number) != finalized.epoch {
+            return Err(LightClientError::HeaderOutsideEpoch {
+                number,
+                epoch: finalized.epoch,
+            });
+        }
+
+        // The outcome of the ceremony determining the group of epoch `E+1` is written to the
+        // last block of epoch `E`.
+        if utils::is_last_block_in_epoch(self.epoch_length, number).is_some() {
+            let outcome = PublicOutcome::decode(header.extra_data().as_ref())
+                .map_err(LightClientError::OutcomeDecoding)?;
+            if outcome.epoch != self.epoch + 1 {
+                return Err(LightClientError::UnexpectedOutcomeEpoch {
+                    expected: self.epoch + 1,
+                    got: outcome.epoch,
+                });
+            }
+            self.epoch = outcome.epoch;
+            self.group_public_key = *poly::public::<MinSig>(&outcome.public);
+        }
+
+        let finalized = FinalizedHeader {
+            header,
+            hash,
+            epoch: finalized.epoch,
+            view: finalized.view,
+        };
+        if self
+            .latest
+            .as_ref()
+            .is_none_or(|latest| latest.header.number() < number)
+        {
+            self.latest = Some(finalized.clone());
+        }
+
+        Ok(finalized)
+    }
+
+    /// Verifies the TIP-20 balance of `holder` in `token` against the state root of the latest
+    /// finalized header.
+    ///
+    /// See [`proof::verify_tip20_balance`].
+    pub fn verify_tip20_balance(
+        &self,
+        token: Address,
+        holder: Address,
+        proof: &EIP1186AccountProofResponse,
+    ) -> Result<U256, LightClientError> {
+        let latest = self
+            .latest
+            .as_ref()
+            .ok_or(LightClientError::NoFinalizedHeader)?;
+        proof::verify_tip20_balance(latest.header.state_root(), token, holder, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestGroup as Group;
+    use alloy_primitives::Bytes;
+    use commonware_codec::Encode as _;
+
+    const EPOCH_LENGTH: u64 = 10;
+
+    fn header(number: u64, extra_data: Bytes) -> TempoHeader {
+        let mut header = TempoHeader::default();
+        header.inner.number = number;
+        header.inner.extra_data = extra_data;
+        header
+    }
+
+    #[test]
+    fn verify_finalization_accepts_certificates_of_the_current_group() {
+        let group = Group::new(0, 0);
+        let client = group.light_client(EPOCH_LENGTH);
+        let hash = B256::repeat_byte(1);
+
+        let finalized = client
+            .verify_finalization(&group.finalize(5, hash))
+            .unwrap();
+        assert_eq!(
+            finalized,
+            FinalizedBlock {
+                hash,
+                epoch: 0,
+                view: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn verify_finalization_rejects_invalid_certificates() {
+        let group = Group::new(0, 0);
+        let client = group.light_client(EPOCH_LENGTH);
+        let hash = B256::repeat_byte(1);
+
+        // Signed by a different group
+        let other = Group::new(1, 0);
+        assert!(matches!(
+            client.verify_finalization(&other.finalize(5, hash)),
+            Err(LightClientError::InvalidCertificate)
+        ));
+
+        // Signed under a different namespace
+        assert!(matches!(
+            client.verify_finalization(&group.finalize_in(b"OTHER", 5, hash)),
+            Err(LightClientError::InvalidCertificate)
+        ));
+
+        // Signed for a different epoch
+        let next = Group::new(0, 1);
+        assert!(matches!(
+            client.verify_finalization(&next.finalize(5, hash)),
+            Err(LightClientError::EpochMismatch {
+                expected: 0,
+                got: 1
+            })
+        ));
+
+        // Not a certificate at all
+        assert!(matches!(
+            client.verify_finalization(&[0u8; 16]),
+            Err(LightClientError::CertificateDecoding(_))
+        ));
+    }
+
+    #[test]
+    fn verify_header_tracks_the_latest_finalized_header() {
+        let group = Group::new(0, 0);
+        let mut client = group.light_client(EPOCH_LENGTH);
+
+        let second = header(2, Bytes::new());
+        let finalized = client
+            .verify_header(second.clone(), &group.finalize(2, second.hash_slow()))
+            .unwrap();
+        assert_eq!(finalized.header, second);
+        assert_eq!(client.latest(), Some(&finalized));
+
+        // Verifying an older header does not move the latest header back
+        let first = header(1, Bytes::new());
+        client
+            .verify_header(first.clone(), &group.finalize(1, first.hash_slow()))
+            .unwrap();
+        assert_eq!(client.latest().unwrap().header, second);
+
+        // The header must hash to the finalized block
+        assert!(matches!(
+            client.verify_header(header(3, Bytes::new()), &group.finalize(3, B256::ZERO)),
+            Err(LightClientError::HeaderMismatch { .. })
+        ));
+
+        // The header must be part of the epoch it was finalized in
+        let outside = header(EPOCH_LENGTH + 1, Bytes::new());
+        assert!(matches!(
+            client.verify_header(outside.clone(), &group.finalize(4, outside.hash_slow())),
+            Err(LightClientError::HeaderOutsideEpoch { .. })
+        ));
+    }
+
+    #[test]
+    fn verify_header_follows_epoch_transitions() {
+        let group = Group::new(0, 0);
+        let next = Group::new(1, 1);
+        let mut client = group.light_client(EPOCH_LENGTH);
+
+        // The boundary block must carry the outcome of the next epoch
+        let boundary = client.epoch_boundary();
+        let wrong = header(boundary, group.outcome().encode().to_vec().into());
+        assert!(matches!(
+            client.verify_header(wrong.clone(), &group.finalize(9, wrong.hash_slow())),
+            Err(LightClientError::UnexpectedOutcomeEpoch {
+                expected: 1,
+                got: 0
+            })
+        ));
+        assert_eq!(client.epoch(), 0);
+
+        let last = header(boundary, next.outcome().encode().to_vec().into());
+        client
+            .verify_header(last.clone(), &group.finalize(9, last.hash_slow()))
+            .unwrap();
+        assert_eq!(client.epoch(), 1);
+        assert_eq!(
+            client.group_public_key(),
+            poly::public::<MinSig>(next.public())
+        );
+
+        // Only certificates of the new group are accepted from now on
+        let first = header(boundary + 1, Bytes::new());
+        assert!(matches!(
+            client.verify_header(first.clone(), &group.finalize(10, first.hash_slow())),
+            Err(LightClientError::EpochMismatch { .. })
+        ));
+        client
+            .verify_header(first.clone(), &next.finalize(1, first.hash_slow()))
+            .unwrap();
+        assert_eq!(client.latest().unwrap().header, first);
+    }
+}
@@ -0,0 +1,214 @@
+//! Verification of `eth_getProof` account and storage proofs against a state root.
+
+use alloy_primitives::{Address, B256, U256, keccak256};
+use alloy_rpc_types_eth::{EIP1186AccountProofResponse, EIP1186StorageProof};
+use alloy_trie::{EMPTY_ROOT_HASH, KECCAK_EMPTY, Nibbles, TrieAccount, proof::verify_proof};
+use tempo_precompiles::{storage::StorageKey as _, tip20};
+
+use crate::LightClientError;
+
+/// Verifies the account part of `proof` against `state_root`.
+///
+/// Proofs of absence are accepted if the account fields in the response are those of an empty
+/// account.
+pub fn verify_account_proof(
+    state_root: B256,
+    proof: &EIP1186AccountProofResponse,
+) -> Result<(), LightClientError> {
+    let account = TrieAccount {
+        nonce: proof.nonce,
+        balance: proof.balance,
+        storage_root: proof.storage_hash,
+        code_hash: proof.code_hash,
+    };
+    let is_empty = account.nonce == 0
+        && account.balance.is_zero()
+        && account.storage_root == EMPTY_ROOT_HASH
+        && (account.code_hash == KECCAK_EMPTY || account.code_hash.is_zero());
+    let expected = (!is_empty).then(|| alloy_rlp::encode(account));
+
+    verify_proof(
+        state_root,
+        Nibbles::unpack(keccak256(proof.address)),
+        expected,
+        &proof.account_proof,
+    )?;
+    Ok(())
+}
+
+/// Verifies a single storage slot proof against the `storage_root` of its account.
+pub fn verify_storage_proof(
+    storage_root: B256,
+    proof: &EIP1186StorageProof,
+) -> Result<(), LightClientError> {
+    let expected = (!proof.value.is_zero()).then(|| alloy_rlp::encode(proof.value));
+
+    verify_proof(
+        storage_root,
+        Nibbles::unpack(keccak256(proof.key.as_b256())),
+        expected,
+        &proof.proof,
+    )?;
+    Ok(())
+}
+
+/// Returns the storage slot holding the TIP-20 balance of `holder`.
+pub fn tip20_balance_slot(holder: Address) -> B256 {
+    holder.mapping_slot(tip20::slots::BALANCES).into()
+}
+
+/// Verifies the TIP-20 balance of `holder` in `token` against `state_root`.
+///
+/// `proof` is the response of `eth_getProof(token, [tip20_balance_slot(holder)])`. Returns the
+/// proven balance.
+pub fn verify_tip20_balance(
+    state_root: B256,
+    token: Address,
+    holder: Address,
+    proof: &EIP1186AccountProofResponse,
+) -> Result<U256, LightClientError> {
+    if proof.address != token {
+        return Err(LightClientError::ProofAddressMismatch {
+            expected: token,
+            got: proof.address,
+        });
+    }
+
+    let slot = tip20_balance_slot(holder);
+    let storage_proof = proof
+        .storage_proof
+        .iter()
+        .find(|storage_proof| storage_proof.key.as_b256() == slot)
+        .ok_or(LightClientError::MissingStorageProof(slot))?;
+
+    verify_account_proof(state_root, proof)?;
+    verify_storage_proof(proof.storage_hash, storage_proof)?;
+
+    Ok(storage_proof.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Bytes, map::B256Map};
+    use alloy_rpc_types_eth::JsonStorageKey;
+    use alloy_trie::{HashBuilder, proof::ProofRetainer};
+
+    /// Builds a trie over `leaves` and returns its root and the proof of `target`.
+    fn build_proof(leaves: &B256Map<Vec<u8>>, target: B256) -> (B256, Vec<Bytes>) {
+        let target = Nibbles::unpack(target);
+        let mut hash_builder =
+            HashBuilder::default().with_proof_retainer(ProofRetainer::new(vec![target]));
+
+        let mut sorted = leaves
+            .iter()
+            .map(|(key, value)| (Nibbles::unpack(key), value))
+            .collect::<Vec<_>>();
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+        for (key, value) in sorted {
+            hash_builder.add_leaf(key, value);
+        }
+
+        let root = hash_builder.root();
+        let proof = hash_builder
+            .take_proof_nodes()
+            .matching_nodes_sorted(&target)
+            .into_iter()
+            .map(|(_, node)| node)
+            .collect();
+        (root, proof)
+    }
+
+    #[test]
+    fn test_verify_tip20_balance() {
+        let token = tip20::token_id_to_address(1);
+        let holder = Address::random();
+        let balance = U256::from(1_000_000u64);
+
+        let slot = tip20_balance_slot(holder);
+        let mut storage = B256Map::default();
+        storage.insert(keccak256(slot), alloy_rlp::encode(balance));
+        storage.insert(keccak256(B256::random()), alloy_rlp::encode(U256::from(7)));
+        let (storage_root, storage_proof) = build_proof(&storage, keccak256(slot));
+
+        let account = TrieAccount {
+            nonce: 0,
+            balance: U256::ZERO,
+            storage_root,
+            code_hash: keccak256([0xef]),
+        };
+        let mut accounts = B256Map::default();
+        accounts.insert(keccak256(token), alloy_rlp::encode(account));
+        accounts.insert(
+            keccak256(Address::random()),
+            alloy_rlp::encode(TrieAccount::default()),
+        );
+        let (state_root, account_proof) = build_proof(&accounts, keccak256(token));
+
+        let mut proof = EIP1186AccountProofResponse {
+            address: token,
+            balance: account.balance,
+            code_hash: account.code_hash,
+            nonce: account.nonce,
+            storage_hash: storage_root,
+            account_proof,
+            storage_proof: vec![EIP1186StorageProof {
+                key: JsonStorageKey::from(slot),
+                value: balance,
+                proof: storage_proof,
+            }],
+        };
+
+        assert_eq!(
+            verify_tip20_balance(state_root, token, holder, &proof).unwrap(),
+            balance
+        );
+
+        // Wrong holder
+        assert!(matches!(
+            verify_tip20_balance(state_root, token, Address::random(), &proof),
+            Err(LightClientError::MissingStorageProof(_))
+        ));
+
+        // Wrong state root
+        assert!(matches!(
+            verify_tip20_balance(B256::random(), token, holder, &proof),
+            Err(LightClientError::InvalidProof(_))
+        ));
+
+        // Inflated balance
+        proof.storage_proof[0].value = balance + U256::from(1);
+        assert!(matches!(
+            verify_tip20_balance(state_root, token, holder, &proof),
+            Err(LightClientError::InvalidProof(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_account_proof_of_absence() {
+        let mut accounts = B256Map::default();
+        accounts.insert(
+            keccak256(Address::random()),
+            alloy_rlp::encode(TrieAccount::default()),
+        );
+        let absent = Address::random();
+        let (state_root, account_proof) = build_proof(&accounts, keccak256(absent));
+
+        let proof = EIP1186AccountProofResponse {
+            address: absent,
+            balance: U256::ZERO,
+            code_hash: KECCAK_EMPTY,
+            nonce: 0,
+            storage_hash: EMPTY_ROOT_HASH,
+            account_proof,
+            storage_proof: vec![],
+        };
+        verify_account_proof(state_root, &proof).unwrap();
+
+        let proof = EIP1186AccountProofResponse {
+            balance: U256::from(1),
+            ..proof
+        };
+        assert!(verify_account_proof(state_root, &proof).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+//! Helpers to produce finalization certificates in tests.
+
+use crate::{CertificateScheme, LightClient, NAMESPACE};
+use alloy_primitives::B256;
+use commonware_codec::Encode as _;
+use commonware_consensus::{
+    simplex::types::{Finalization, Finalize, Proposal},
+    types::{Epoch, Round, View},
+};
+use commonware_cryptography::{
+    PrivateKeyExt as _, Signer as _,
+    bls12381::{
+        dkg::ops::generate_shares,
+        primitives::{poly::Public, variant::MinSig},
+    },
+    ed25519::{PrivateKey, PublicKey},
+    sha256,
+};
+use commonware_utils::{quorum, set::Ordered};
+use rand::{SeedableRng as _, rngs::StdRng};
+use tempo_dkg_onchain_artifacts::PublicOutcome;
+
+/// The signing schemes of a group of four validators in an epoch.
+///
+/// Groups created from the same seed share their keys.
+pub struct TestGroup {
+    epoch: Epoch,
+    participants: Ordered<PublicKey>,
+    public: Public<MinSig>,
+    schemes: Vec<CertificateScheme>,
+}
+
+impl TestGroup {
+    /// Deterministically creates the group of `epoch` from `seed`.
+    pub fn new(seed: u64, epoch: Epoch) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let n = 4;
+        let participants = (0..n)
+            .map(|i| PrivateKey::from_seed(seed * 100 + i as u64).public_key())
+            .collect::<Ordered<_>>();
+        let (public, shares) = generate_shares::<_, MinSig>(&mut rng, None, n, quorum(n));
+        let schemes = shares
+            .into_iter()
+            .map(|share| CertificateScheme::new(participants.clone(), &public, share))
+            .collect();
+        Self {
+            epoch,
+            participants,
+            public,
+            schemes,
+        }
+    }
+
+    /// The public polynomial of the group.
+    pub fn public(&self) -> &Public<MinSig> {
+        &self.public
+    }
+
+    /// A light client trusting this group.
+    pub fn light_client(&self, epoch_length: u64) -> LightClient {
+        LightClient::from_public_polynomial(epoch_length, self.epoch, &self.public)
+    }
+
+    /// The DKG outcome installing this group, as written to the last block of the previous epoch.
+    pub fn outcome(&self) -> PublicOutcome {
+        PublicOutcome {
+            epoch: self.epoch,
+            participants: self.participants.clone(),
+            public: self.public.clone(),
+        }
+    }
+
+    /// Returns the encoded certificate finalizing `hash` in `view` under `namespace`.
+    pub fn finalize_in(&self, namespace: &[u8], view: View, hash: B256) -> Vec<u8> {
+        let proposal = Proposal::new(
+            Round::new(self.epoch, view),
+            view.saturating_sub(1),
+            sha256::Digest::from(hash.0),
+        );
+        let finalizes = self
+            .schemes
+            .iter()
+            .map(|scheme| Finalize::sign(scheme, namespace, proposal.clone()).unwrap())
+            .collect::<Vec<_>>();
+        Finalization::from_finalizes(&self.schemes[0], &finalizes)
+            .unwrap()
+            .encode()
+            .to_vec()
+    }
+
+    /// Returns the encoded certificate finalizing `hash` in `view` under the node's namespace.
+    pub fn finalize(&self, view: View, hash: B256) -> Vec<u8> {
+        self.finalize_in(NAMESPACE, view, hash)
+    }
+}
@@ -27,6 +27,7 @@ impl PayloadValidator<TempoPayloadTypes> for TempoEngineValidator {
         let TempoExecutionData {
             block,
             validator_set: _,
+            decrypted_transactions: _,
         } = payload;
         Ok(Arc::unwrap_or_clone(block))
     }
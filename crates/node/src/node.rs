@@ -2,9 +2,10 @@ use crate::{
     TempoPayloadTypes,
     engine::TempoEngineValidator,
     rpc::{
-        TempoAdminApi, TempoAdminApiServer, TempoAmm, TempoAmmApiServer, TempoDex,
-        TempoDexApiServer, TempoEthApiBuilder, TempoEthExt, TempoEthExtApiServer, TempoPolicy,
-        TempoPolicyApiServer, TempoToken, TempoTokenApiServer,
+        ConsensusHandle, TempoAdminApi, TempoAdminApiServer, TempoAmm, TempoAmmApiServer,
+        TempoConsensusApi, TempoConsensusApiServer, TempoDebug, TempoDebugApiServer, TempoDex,
+        TempoDexApiServer, TempoEthApiBuilder, TempoEthExt, TempoEthExtApiServer, TempoEthFees,
+        TempoEthFeesApiServer, TempoPolicy, TempoPolicyApiServer, TempoToken, TempoTokenApiServer,
     },
 };
 use alloy_eips::{eip7840::BlobParams, merge::EPOCH_SLOTS};
@@ -41,12 +42,12 @@ use std::{default::Default, sync::Arc, time::SystemTime};
 use tempo_chainspec::spec::{TEMPO_BASE_FEE, TempoChainSpec};
 use tempo_consensus::TempoConsensus;
 use tempo_evm::{TempoEvmConfig, evm::TempoEvmFactory};
-use tempo_payload_builder::TempoPayloadBuilder;
+use tempo_payload_builder::{PrebuildHandle, TempoPayloadBuilder};
 use tempo_payload_types::TempoPayloadAttributes;
 use tempo_primitives::{TempoHeader, TempoPrimitives, TempoTxEnvelope, TempoTxType};
 use tempo_transaction_pool::{
-    AA2dPool, AA2dPoolConfig, TempoTransactionPool, amm::AmmLiquidityCache,
-    validator::TempoTransactionValidator,
+    AA2dPool, AA2dPoolConfig, PaymentLaneConfig, PaymentOrdering, TempoTransactionPool,
+    amm::AmmLiquidityCache, validator::TempoTransactionValidator,
 };
 
 /// Default maximum allowed `valid_after` offset for AA txs (1 hour).
@@ -59,6 +60,34 @@ pub struct TempoNodeArgs {
     /// Maximum allowed `valid_after` offset for AA txs.
     #[arg(long = "txpool.aa-valid-after-max-secs", default_value_t = DEFAULT_AA_VALID_AFTER_MAX_SECS)]
     pub aa_valid_after_max_secs: u64,
+
+    /// Ordering of payment transactions in built payloads: `tip` or `fifo`.
+    ///
+    /// `fifo` includes payments in the order they arrived in the pool, after all non-payment
+    /// transactions.
+    #[arg(
+        long = "builder.payment-ordering",
+        help_heading = "Builder",
+        default_value_t = PaymentOrdering::Tip
+    )]
+    pub payment_ordering: PaymentOrdering,
+
+    /// Maximum number of payment transactions per sender in a block when using `fifo` payment
+    /// ordering.
+    #[arg(long = "builder.max-payments-per-sender", help_heading = "Builder")]
+    pub max_payments_per_sender: Option<usize>,
+
+    /// Record call counts, gas used, reverts and latency of the precompiles per function
+    /// selector, labeled with what they were executed for (block execution, payload building,
+    /// speculative execution or anything else such as `eth_call`).
+    #[arg(long = "metrics.precompiles", help_heading = "Metrics")]
+    pub precompile_metrics: bool,
+
+    /// Speculatively execute the payment transactions of blocks in parallel.
+    ///
+    /// The resulting state is identical to sequential execution.
+    #[arg(long = "execution.parallel-payments", help_heading = "Execution")]
+    pub parallel_execution: bool,
 }
 
 impl TempoNodeArgs {
@@ -68,6 +97,25 @@ impl TempoNodeArgs {
             aa_valid_after_max_secs: self.aa_valid_after_max_secs,
         }
     }
+
+    /// Returns a [`TempoPayloadBuilderBuilder`] configured from these args.
+    pub fn payload_builder(&self) -> TempoPayloadBuilderBuilder {
+        TempoPayloadBuilderBuilder {
+            payment_lane: PaymentLaneConfig {
+                ordering: self.payment_ordering,
+                max_payments_per_sender: self.max_payments_per_sender,
+            },
+            prebuild: None,
+        }
+    }
+
+    /// Returns a [`TempoExecutorBuilder`] configured from these args.
+    pub fn executor_builder(&self) -> TempoExecutorBuilder {
+        TempoExecutorBuilder {
+            precompile_metrics: self.precompile_metrics,
+            parallel_execution: self.parallel_execution,
+        }
+    }
 }
 
 /// Type configuration for a regular Ethereum node.
@@ -76,8 +124,14 @@ impl TempoNodeArgs {
 pub struct TempoNode {
     /// Transaction pool builder.
     pool_builder: TempoPoolBuilder,
+    /// Payload builder builder.
+    payload_builder: TempoPayloadBuilderBuilder,
+    /// EVM and executor builder.
+    executor_builder: TempoExecutorBuilder,
     /// Validator public key for `admin_validatorKey` RPC method.
     validator_key: Option<B256>,
+    /// Consensus engine state for the `consensus_` RPC namespace.
+    consensus: ConsensusHandle,
 }
 
 impl TempoNode {
@@ -85,13 +139,31 @@ impl TempoNode {
     pub fn new(args: &TempoNodeArgs, validator_key: Option<B256>) -> Self {
         Self {
             pool_builder: args.pool_builder(),
+            payload_builder: args.payload_builder(),
+            executor_builder: args.executor_builder(),
             validator_key,
+            consensus: ConsensusHandle::default(),
         }
     }
 
+    /// Sets the handle through which the consensus engine serves the `consensus_` RPC namespace.
+    pub fn with_consensus_handle(mut self, consensus: ConsensusHandle) -> Self {
+        self.consensus = consensus;
+        self
+    }
+
+    /// Sets the handle through which the consensus engine announces the parents of upcoming
+    /// payloads, so that they can be pre-built.
+    pub fn with_prebuild_handle(mut self, prebuild: PrebuildHandle) -> Self {
+        self.payload_builder = self.payload_builder.with_prebuild_handle(prebuild);
+        self
+    }
+
     /// Returns a [`ComponentsBuilder`] configured for a regular Tempo node.
     pub fn components<Node>(
         pool_builder: TempoPoolBuilder,
+        payload_builder: TempoPayloadBuilderBuilder,
+        executor_builder: TempoExecutorBuilder,
     ) -> ComponentsBuilder<
         Node,
         TempoPoolBuilder,
@@ -106,8 +178,8 @@ impl TempoNode {
         ComponentsBuilder::default()
             .node_types::<Node>()
             .pool(pool_builder)
-            .executor(TempoExecutorBuilder::default())
-            .payload(BasicPayloadServiceBuilder::default())
+            .executor(executor_builder)
+            .payload(BasicPayloadServiceBuilder::new(payload_builder))
             .network(EthereumNetworkBuilder::default())
             .consensus(TempoConsensusBuilder::default())
     }
@@ -134,6 +206,7 @@ pub struct TempoAddOns<
 > {
     inner: RpcAddOns<N, EthB, PVB, NoopEngineApiBuilder, EVB, RpcMiddleware>,
     validator_key: Option<B256>,
+    consensus: ConsensusHandle,
 }
 
 impl<N, EthB> TempoAddOns<N, EthB>
@@ -142,10 +215,11 @@ where
     EthB: EthApiBuilder<N>,
 {
     /// Creates a new instance from the inner `RpcAddOns`.
-    pub fn new(validator_key: Option<B256>) -> Self {
+    pub fn new(validator_key: Option<B256>, consensus: ConsensusHandle) -> Self {
         Self {
             inner: Default::default(),
             validator_key,
+            consensus,
         }
     }
 }
@@ -176,15 +250,25 @@ where
                 let amm = TempoAmm::new(eth_api.clone());
                 let token = TempoToken::new(eth_api.clone());
                 let policy = TempoPolicy::new(eth_api.clone());
-                let eth_ext = TempoEthExt::new(eth_api);
-                let admin = TempoAdminApi::new(self.validator_key);
+                let eth_ext = TempoEthExt::new(eth_api.clone());
+                let eth_fees = TempoEthFees::new(eth_api.clone());
+                let debug = TempoDebug::new(eth_api, registry.debug_api());
+                let admin = TempoAdminApi::new(self.validator_key, self.consensus.clone());
+                let consensus = TempoConsensusApi::new(self.consensus);
 
                 modules.merge_configured(dex.into_rpc())?;
                 modules.merge_configured(amm.into_rpc())?;
                 modules.merge_configured(token.into_rpc())?;
                 modules.merge_configured(policy.into_rpc())?;
                 modules.merge_configured(eth_ext.into_rpc())?;
+                modules.merge_configured(consensus.into_rpc())?;
                 modules.merge_if_module_configured(RethRpcModule::Admin, admin.into_rpc())?;
+                // Replaces `debug_traceTransaction` to support the precompile tracer.
+                modules
+                    .add_or_replace_if_module_configured(RethRpcModule::Debug, debug.into_rpc())?;
+                // Replaces `eth_feeHistory` to report the payment lane.
+                modules
+                    .add_or_replace_if_module_configured(RethRpcModule::Eth, eth_fees.into_rpc())?;
                 modules.merge_if_module_configured(RethRpcModule::Eth, eth_config.into_rpc())?;
 
                 Ok(())
@@ -239,11 +323,15 @@ where
     type AddOns = TempoAddOns<NodeAdapter<N>>;
 
     fn components_builder(&self) -> Self::ComponentsBuilder {
-        Self::components(self.pool_builder)
+        Self::components(
+            self.pool_builder,
+            self.payload_builder.clone(),
+            self.executor_builder,
+        )
     }
 
     fn add_ons(&self) -> Self::AddOns {
-        TempoAddOns::new(self.validator_key)
+        TempoAddOns::new(self.validator_key, self.consensus.clone())
     }
 }
 
@@ -305,7 +393,12 @@ impl PayloadAttributesBuilder<TempoPayloadAttributes, TempoHeader>
 /// A regular ethereum evm and executor builder.
 #[derive(Debug, Default, Clone, Copy)]
 #[non_exhaustive]
-pub struct TempoExecutorBuilder;
+pub struct TempoExecutorBuilder {
+    /// Whether to record per-precompile call metrics.
+    pub precompile_metrics: bool,
+    /// Whether to speculatively execute payment transactions in parallel.
+    pub parallel_execution: bool,
+}
 
 impl<Node> ExecutorBuilder<Node> for TempoExecutorBuilder
 where
@@ -314,7 +407,14 @@ where
     type EVM = TempoEvmConfig;
 
     async fn build_evm(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::EVM> {
-        let evm_config = TempoEvmConfig::new(ctx.chain_spec(), TempoEvmFactory::default());
+        if self.precompile_metrics {
+            tempo_precompiles::metrics::enable();
+        }
+
+        let mut evm_config = TempoEvmConfig::new(ctx.chain_spec(), TempoEvmFactory::default());
+        if self.parallel_execution {
+            evm_config = evm_config.with_parallel_execution(ctx.provider().clone());
+        }
         Ok(evm_config)
     }
 }
@@ -422,6 +522,7 @@ where
             .with_additional_tasks(ctx.config().txpool.additional_validation_tasks)
             .with_custom_tx_type(TempoTxType::AA as u8)
             .with_custom_tx_type(TempoTxType::FeeToken as u8)
+            .with_custom_tx_type(TempoTxType::Encrypted as u8)
             .build_with_tasks(ctx.task_executor().clone(), blob_store.clone());
 
         if validator.validator().eip4844() {
@@ -488,7 +589,27 @@ where
 
 #[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub struct TempoPayloadBuilderBuilder;
+pub struct TempoPayloadBuilderBuilder {
+    /// Ordering of the payment lane.
+    pub payment_lane: PaymentLaneConfig,
+    /// Handle through which the parents of upcoming payloads are announced, if pre-building is
+    /// enabled.
+    pub prebuild: Option<PrebuildHandle>,
+}
+
+impl TempoPayloadBuilderBuilder {
+    /// Sets the ordering of payment transactions in built payloads.
+    pub const fn with_payment_lane(mut self, payment_lane: PaymentLaneConfig) -> Self {
+        self.payment_lane = payment_lane;
+        self
+    }
+
+    /// Enables pre-building of payloads on top of the parents announced through `prebuild`.
+    pub fn with_prebuild_handle(mut self, prebuild: PrebuildHandle) -> Self {
+        self.prebuild = Some(prebuild);
+        self
+    }
+}
 
 impl<Node> PayloadBuilderBuilder<Node, TempoTransactionPool<Node::Provider>, TempoEvmConfig>
     for TempoPayloadBuilderBuilder
@@ -503,10 +624,22 @@ where
         pool: TempoTransactionPool<Node::Provider>,
         evm_config: TempoEvmConfig,
     ) -> eyre::Result<Self::PayloadBuilder> {
-        Ok(TempoPayloadBuilder::new(
-            pool,
-            ctx.provider().clone(),
-            evm_config,
-        ))
+        let mut payload_builder =
+            TempoPayloadBuilder::new(pool, ctx.provider().clone(), evm_config)
+                .with_payment_lane(self.payment_lane);
+
+        if let Some(prebuild) = self.prebuild {
+            payload_builder = payload_builder.with_prebuild_handle(prebuild.clone());
+            let prebuilder = payload_builder.prebuilder();
+            ctx.task_executor()
+                .spawn_critical_blocking("payload prebuilder", async move { prebuilder.run() });
+            ctx.task_executor()
+                .spawn_with_graceful_shutdown_signal(|shutdown| async move {
+                    let _guard = shutdown.await;
+                    prebuild.shutdown();
+                });
+        }
+
+        Ok(payload_builder)
     }
 }
@@ -1,5 +1,10 @@
+use std::path::PathBuf;
+
 use alloy_primitives::B256;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_node_core::rpc::result::internal_rpc_err;
+
+use crate::rpc::consensus::{ConsensusHandle, ConsensusPeer, ConsensusStatus, SnapshotInfo};
 
 #[rpc(server, namespace = "admin")]
 pub trait TempoAdminApi {
@@ -11,18 +16,44 @@ pub trait TempoAdminApi {
     /// Returns `null` if the node is not configured as a validator.
     #[method(name = "validatorKey")]
     async fn validator_key(&self) -> RpcResult<Option<B256>>;
+
+    /// Returns a snapshot of the state of the consensus engine.
+    ///
+    /// Includes the current epoch, view and leader, the participants, the progress of the
+    /// running DKG ceremony, and recently nullified views.
+    #[method(name = "consensusStatus")]
+    async fn consensus_status(&self) -> RpcResult<ConsensusStatus>;
+
+    /// Returns the peers registered with the consensus p2p network for the latest epoch.
+    ///
+    /// Includes the addresses peers are dialed at and whether a message of the peer was
+    /// received recently, so it is only served in the `admin_` namespace.
+    #[method(name = "consensusPeers")]
+    async fn consensus_peers(&self) -> RpcResult<Vec<ConsensusPeer>>;
+
+    /// Writes a state snapshot at the latest finalized epoch boundary to `path` on this node.
+    ///
+    /// The snapshot holds the state after the last block of the epoch, including the storage
+    /// of all precompiles, together with the finalized boundary headers proving its state root.
+    /// New nodes start from it with `--snapshot.import`.
+    #[method(name = "exportSnapshot")]
+    async fn export_snapshot(&self, path: PathBuf) -> RpcResult<SnapshotInfo>;
 }
 
 /// Tempo-specific `admin_` namespace extensions.
 #[derive(Debug, Clone)]
 pub struct TempoAdminApi {
     validator_key: Option<B256>,
+    consensus: ConsensusHandle,
 }
 
 impl TempoAdminApi {
     /// Create a new admin API handler.
-    pub fn new(validator_key: Option<B256>) -> Self {
-        Self { validator_key }
+    pub fn new(validator_key: Option<B256>, consensus: ConsensusHandle) -> Self {
+        Self {
+            validator_key,
+            consensus,
+        }
     }
 }
 
@@ -31,4 +62,28 @@ impl TempoAdminApiServer for TempoAdminApi {
     async fn validator_key(&self) -> RpcResult<Option<B256>> {
         Ok(self.validator_key)
     }
+
+    async fn consensus_status(&self) -> RpcResult<ConsensusStatus> {
+        self.consensus
+            .require_provider()?
+            .status()
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    async fn consensus_peers(&self) -> RpcResult<Vec<ConsensusPeer>> {
+        self.consensus
+            .require_provider()?
+            .peers()
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    async fn export_snapshot(&self, path: PathBuf) -> RpcResult<SnapshotInfo> {
+        self.consensus
+            .require_provider()?
+            .export_snapshot(path)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
 }
@@ -0,0 +1,616 @@
+use std::{
+    fmt,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
+
+use alloy_eips::BlockHashOrNumber;
+use alloy_primitives::{B256, Bytes};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_node_core::rpc::result::internal_rpc_err;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of entries returned by `consensus_getEvidence`.
+pub const MAX_EVIDENCE_PAGE_SIZE: usize = 100;
+
+/// Maximum number of bytes returned by `consensus_getSnapshotChunk`.
+pub const MAX_SNAPSHOT_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+#[rpc(server, namespace = "consensus")]
+pub trait TempoConsensusApi {
+    /// Returns the finalization certificate of a block.
+    ///
+    /// The certificate is a threshold-simplex finalization carrying a BLS12-381 threshold
+    /// signature that can be verified against the group public key of the block's epoch.
+    ///
+    /// Returns `null` if the block is not (yet) finalized or not known to this node.
+    #[method(name = "getFinalization")]
+    async fn get_finalization(
+        &self,
+        block: BlockHashOrNumber,
+    ) -> RpcResult<Option<ConsensusFinalization>>;
+
+    /// Returns a snapshot of the state of the consensus engine.
+    ///
+    /// See [`ConsensusStatus`] for the reported fields. The addresses of peers are only served
+    /// by `admin_consensusPeers`.
+    #[method(name = "getStatus")]
+    async fn get_status(&self) -> RpcResult<ConsensusStatus>;
+
+    /// Returns the evidence of Byzantine behavior recorded by this node, oldest first.
+    ///
+    /// Returns up to `limit` entries (at most [`MAX_EVIDENCE_PAGE_SIZE`]) whose index is at
+    /// least `from`. The next page starts at the index following the last returned entry. Only
+    /// the most recent entries are kept by the node.
+    ///
+    /// Every entry carries the two conflicting messages signed by the offending validator,
+    /// which can be verified against the validator's key share of the epoch.
+    #[method(name = "getEvidence")]
+    async fn get_evidence(
+        &self,
+        from: Option<u64>,
+        limit: Option<u64>,
+    ) -> RpcResult<Vec<ConsensusEvidence>>;
+
+    /// Returns what happened to a transaction sent with a subblock nonce key.
+    ///
+    /// The validator whose subblock holds the transaction tracks it from the moment it is
+    /// received until the block built on top of its subblock's parent is notarized. Proposers
+    /// additionally track the transactions of the subblocks sent to them, and are the only
+    /// nodes that know why a subblock was left out of their block.
+    ///
+    /// Returns `null` if the transaction is not known to this node.
+    #[method(name = "getSubblockTransactionStatus")]
+    async fn get_subblock_transaction_status(
+        &self,
+        tx_hash: B256,
+    ) -> RpcResult<Option<SubblockTransactionStatus>>;
+
+    /// Returns up to [`MAX_SNAPSHOT_CHUNK_SIZE`] bytes of the latest state snapshot written by
+    /// this node with `admin_exportSnapshot`, starting at byte `offset`.
+    ///
+    /// New nodes download snapshots from their peers through this method with
+    /// `--snapshot.peers`. Snapshots are verified by the downloading node, so serving them
+    /// requires no trust. A download is complete once `offset + data.len()` reaches `size`, and
+    /// must be restarted if `hash` changes because the node exported a newer snapshot.
+    ///
+    /// Returns `null` if this node did not export a snapshot since it was started.
+    #[method(name = "getSnapshotChunk")]
+    async fn get_snapshot_chunk(&self, offset: u64) -> RpcResult<Option<SnapshotChunk>>;
+}
+
+/// A finalization certificate proving that a block is final.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsensusFinalization {
+    /// Number of the finalized block.
+    #[serde(with = "alloy_serde::quantity")]
+    pub number: u64,
+    /// Hash of the finalized block.
+    pub hash: B256,
+    /// Consensus epoch the block was finalized in.
+    #[serde(with = "alloy_serde::quantity")]
+    pub epoch: u64,
+    /// Consensus view the block was finalized in.
+    #[serde(with = "alloy_serde::quantity")]
+    pub view: u64,
+    /// The encoded threshold-simplex finalization certificate.
+    pub certificate: Bytes,
+    /// The encoded BLS12-381 group public key of the epoch, taken from the epoch's DKG outcome.
+    ///
+    /// `None` if the DKG outcome of the epoch is not available on chain.
+    pub group_public_key: Option<Bytes>,
+}
+
+/// A snapshot of the state of the consensus engine.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsensusStatus {
+    /// Latest epoch consensus activity was observed in.
+    #[serde(with = "alloy_serde::quantity::opt")]
+    pub epoch: Option<u64>,
+    /// Latest view consensus activity was observed in.
+    #[serde(with = "alloy_serde::quantity::opt")]
+    pub view: Option<u64>,
+    /// The ed25519 public key of the leader of the view following `view`.
+    pub leader: Option<B256>,
+    /// Whether this node holds a key share for `epoch` and takes part in signing.
+    pub is_signer: bool,
+    /// The ed25519 public keys of the validators participating in `epoch`.
+    pub participants: Vec<B256>,
+    /// Progress of the DKG ceremony determining the validators of the next epoch.
+    ///
+    /// `None` if no ceremony is running, e.g. while the node is still starting up.
+    pub dkg: Option<DkgStatus>,
+    /// The most recently nullified views, oldest first.
+    pub nullified_views: Vec<NullifiedView>,
+}
+
+/// A peer registered with the p2p network.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsensusPeer {
+    /// The peer's ed25519 public key.
+    pub public_key: B256,
+    /// The address the peer is dialed at.
+    pub address: SocketAddr,
+    /// Whether a message of the peer was received recently.
+    pub connected: bool,
+}
+
+/// A view that was nullified instead of producing a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NullifiedView {
+    /// Epoch of the nullified view.
+    #[serde(with = "alloy_serde::quantity")]
+    pub epoch: u64,
+    /// The nullified view.
+    #[serde(with = "alloy_serde::quantity")]
+    pub view: u64,
+    /// The leader that failed to get a block notarized in the view, if known.
+    ///
+    /// Leaders that repeatedly show up here are skipped by the skip timeout.
+    pub leader: Option<B256>,
+}
+
+/// Progress of a DKG ceremony.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DkgStatus {
+    /// Epoch the ceremony is running in. Its outcome determines the validators of the next epoch.
+    #[serde(with = "alloy_serde::quantity")]
+    pub epoch: u64,
+    /// The phase the ceremony is in.
+    pub phase: DkgPhase,
+    /// The ed25519 public keys of the dealers of the ceremony.
+    pub dealers: Vec<B256>,
+    /// The ed25519 public keys of the players of the ceremony.
+    pub players: Vec<B256>,
+    /// Whether this node is a dealer in the ceremony.
+    pub is_dealer: bool,
+    /// Whether this node is a player in the ceremony.
+    pub is_player: bool,
+    /// Number of shares this node received from dealers.
+    #[serde(with = "alloy_serde::quantity")]
+    pub shares_received: u64,
+    /// Number of acks this node received from players for its own dealing.
+    #[serde(with = "alloy_serde::quantity")]
+    pub acks_received: u64,
+    /// Number of dealings read from finalized blocks.
+    #[serde(with = "alloy_serde::quantity")]
+    pub dealings_read: u64,
+}
+
+/// Phase of a DKG ceremony.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DkgPhase {
+    /// First half of the epoch: dealers distribute shares to players and collect acks.
+    DistributingShares,
+    /// Second half of the epoch: dealings are written to and read from finalized blocks.
+    CollectingDealings,
+    /// The ceremony was finalized; its outcome will be written to the last block of the epoch.
+    Finalized,
+}
+
+/// Proof that a validator signed two conflicting consensus messages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsensusEvidence {
+    /// The order in which the evidence was recorded by this node.
+    #[serde(with = "alloy_serde::quantity")]
+    pub index: u64,
+    /// The kind of conflict.
+    pub kind: EvidenceKind,
+    /// Epoch the conflicting messages were signed in.
+    #[serde(with = "alloy_serde::quantity")]
+    pub epoch: u64,
+    /// View the conflicting messages were signed in.
+    #[serde(with = "alloy_serde::quantity")]
+    pub view: u64,
+    /// The ed25519 public key of the offending validator.
+    pub validator: B256,
+    /// The encoded threshold-simplex activity holding both signed messages.
+    pub evidence: Bytes,
+}
+
+/// Kind of conflicting consensus messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EvidenceKind {
+    /// The validator voted to notarize two different proposals in the same view.
+    ConflictingNotarize,
+    /// The validator voted to finalize two different proposals in the same view.
+    ConflictingFinalize,
+    /// The validator voted to both nullify and finalize the same view.
+    NullifyFinalize,
+}
+
+/// The progress of a subblock transaction as observed by this node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubblockTransactionStatus {
+    /// The ed25519 public key of the validator whose subblock holds the transaction.
+    pub validator: B256,
+    /// Parent of the latest subblock holding the transaction.
+    ///
+    /// `None` while the transaction has not been put in a subblock.
+    pub parent_hash: Option<B256>,
+    /// The ed25519 public key of the proposer the subblock was sent to.
+    pub proposer: Option<B256>,
+    /// Whether the subblock reached the proposer and was acknowledged by it.
+    pub reached_proposer: bool,
+    /// Where the transaction currently stands.
+    pub stage: SubblockTransactionStage,
+    /// Number of the block including the transaction.
+    #[serde(with = "alloy_serde::quantity::opt")]
+    pub block_number: Option<u64>,
+    /// Hash of the block including the transaction.
+    pub block_hash: Option<B256>,
+}
+
+/// Stage of a subblock transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubblockTransactionStage {
+    /// Waiting to be put in a subblock.
+    Pending,
+    /// Put in a subblock for the next proposer.
+    InSubblock,
+    /// Included in a block.
+    Included,
+    /// The proposer dropped the subblock because one of its transactions expired.
+    DroppedExpired,
+    /// The proposer discarded the subblock because it had seen an invalid subblock at this
+    /// height or above.
+    DiscardedInvalidSubblock,
+    /// The block built on the subblock's parent does not include the subblock.
+    NotIncluded,
+}
+
+/// A state snapshot written by this node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    /// Number of the block the snapshot was taken at, the last block of `epoch`.
+    #[serde(with = "alloy_serde::quantity")]
+    pub number: u64,
+    /// Hash of the block the snapshot was taken at.
+    pub hash: B256,
+    /// Consensus epoch the block concludes.
+    #[serde(with = "alloy_serde::quantity")]
+    pub epoch: u64,
+    /// State root of the block.
+    pub state_root: B256,
+    /// Number of accounts in the snapshot.
+    #[serde(with = "alloy_serde::quantity")]
+    pub accounts: u64,
+    /// Path of the snapshot file on this node.
+    pub path: PathBuf,
+}
+
+/// A part of a state snapshot served to peers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotChunk {
+    /// Number of the block the snapshot was taken at.
+    #[serde(with = "alloy_serde::quantity")]
+    pub number: u64,
+    /// Hash of the block the snapshot was taken at.
+    pub hash: B256,
+    /// Size of the whole snapshot in bytes.
+    #[serde(with = "alloy_serde::quantity")]
+    pub size: u64,
+    /// Position of `data` in the snapshot.
+    #[serde(with = "alloy_serde::quantity")]
+    pub offset: u64,
+    /// The bytes of the snapshot starting at `offset`.
+    pub data: Bytes,
+}
+
+/// Source of consensus data served by the `consensus_` namespace.
+#[async_trait::async_trait]
+pub trait ConsensusStateProvider: Send + Sync + 'static {
+    /// Returns the finalization certificate of `block`, if it is finalized.
+    async fn finalization(
+        &self,
+        block: BlockHashOrNumber,
+    ) -> eyre::Result<Option<ConsensusFinalization>>;
+
+    /// Returns a snapshot of the state of the consensus engine.
+    async fn status(&self) -> eyre::Result<ConsensusStatus>;
+
+    /// Returns the peers registered with the p2p network for the latest epoch.
+    async fn peers(&self) -> eyre::Result<Vec<ConsensusPeer>>;
+
+    /// Returns up to `limit` records of evidence of Byzantine behavior starting at index `from`,
+    /// oldest first.
+    async fn evidence(&self, from: u64, limit: usize) -> eyre::Result<Vec<ConsensusEvidence>>;
+
+    /// Writes a state snapshot at the latest finalized epoch boundary to `path`.
+    async fn export_snapshot(&self, path: PathBuf) -> eyre::Result<SnapshotInfo>;
+
+    /// Returns up to `limit` bytes of the latest exported snapshot starting at `offset`, if a
+    /// snapshot was exported.
+    async fn snapshot_chunk(
+        &self,
+        offset: u64,
+        limit: usize,
+    ) -> eyre::Result<Option<SnapshotChunk>>;
+
+    /// Returns the progress of the subblock transaction `tx_hash`, if it is tracked.
+    async fn subblock_transaction_status(
+        &self,
+        tx_hash: B256,
+    ) -> eyre::Result<Option<SubblockTransactionStatus>>;
+}
+
+/// Shared handle through which the consensus engine provides its state to RPC.
+///
+/// The RPC server is launched before the consensus engine, so the provider is installed
+/// once the engine has started. Nodes not running consensus (e.g. dev or follow mode)
+/// never install one.
+#[derive(Clone, Default)]
+pub struct ConsensusHandle(Arc<OnceLock<Arc<dyn ConsensusStateProvider>>>);
+
+impl ConsensusHandle {
+    /// Installs the consensus state provider. Returns `false` if one was already installed.
+    pub fn install(&self, provider: impl ConsensusStateProvider) -> bool {
+        self.0.set(Arc::new(provider)).is_ok()
+    }
+
+    /// Returns the installed consensus state provider, if any.
+    pub fn provider(&self) -> Option<&Arc<dyn ConsensusStateProvider>> {
+        self.0.get()
+    }
+
+    /// Returns the installed consensus state provider, or an RPC error if there is none.
+    pub(crate) fn require_provider(&self) -> RpcResult<&Arc<dyn ConsensusStateProvider>> {
+        self.provider()
+            .ok_or_else(|| internal_rpc_err("consensus engine is not running on this node"))
+    }
+}
+
+impl fmt::Debug for ConsensusHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsensusHandle")
+            .field("installed", &self.0.get().is_some())
+            .finish()
+    }
+}
+
+/// Tempo-specific `consensus_` namespace.
+#[derive(Debug, Clone)]
+pub struct TempoConsensusApi {
+    consensus: ConsensusHandle,
+}
+
+impl TempoConsensusApi {
+    /// Create a new consensus API handler.
+    pub fn new(consensus: ConsensusHandle) -> Self {
+        Self { consensus }
+    }
+}
+
+#[async_trait::async_trait]
+impl TempoConsensusApiServer for TempoConsensusApi {
+    async fn get_finalization(
+        &self,
+        block: BlockHashOrNumber,
+    ) -> RpcResult<Option<ConsensusFinalization>> {
+        self.consensus
+            .require_provider()?
+            .finalization(block)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    async fn get_status(&self) -> RpcResult<ConsensusStatus> {
+        self.consensus
+            .require_provider()?
+            .status()
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    async fn get_evidence(
+        &self,
+        from: Option<u64>,
+        limit: Option<u64>,
+    ) -> RpcResult<Vec<ConsensusEvidence>> {
+        let limit = limit.map_or(MAX_EVIDENCE_PAGE_SIZE, |limit| {
+            limit.min(MAX_EVIDENCE_PAGE_SIZE as u64) as usize
+        });
+        self.consensus
+            .require_provider()?
+            .evidence(from.unwrap_or_default(), limit)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    async fn get_subblock_transaction_status(
+        &self,
+        tx_hash: B256,
+    ) -> RpcResult<Option<SubblockTransactionStatus>> {
+        self.consensus
+            .require_provider()?
+            .subblock_transaction_status(tx_hash)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    async fn get_snapshot_chunk(&self, offset: u64) -> RpcResult<Option<SnapshotChunk>> {
+        self.consensus
+            .require_provider()?
+            .snapshot_chunk(offset, MAX_SNAPSHOT_CHUNK_SIZE)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finalization_serde() {
+        let finalization = ConsensusFinalization {
+            number: 1000,
+            hash: B256::repeat_byte(0x11),
+            epoch: 2,
+            view: 42,
+            certificate: Bytes::from_static(&[0xde, 0xad]),
+            group_public_key: None,
+        };
+
+        let json = serde_json::to_value(&finalization).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "number": "0x3e8",
+                "hash": B256::repeat_byte(0x11),
+                "epoch": "0x2",
+                "view": "0x2a",
+                "certificate": "0xdead",
+                "groupPublicKey": null,
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<ConsensusFinalization>(json).unwrap(),
+            finalization
+        );
+    }
+
+    #[test]
+    fn test_status_serde() {
+        let status = ConsensusStatus {
+            epoch: Some(3),
+            view: Some(17),
+            leader: Some(B256::repeat_byte(0x01)),
+            is_signer: true,
+            participants: vec![B256::repeat_byte(0x01), B256::repeat_byte(0x02)],
+            dkg: Some(DkgStatus {
+                epoch: 3,
+                phase: DkgPhase::CollectingDealings,
+                dealers: vec![B256::repeat_byte(0x01)],
+                players: vec![B256::repeat_byte(0x02)],
+                is_dealer: true,
+                is_player: false,
+                shares_received: 0,
+                acks_received: 1,
+                dealings_read: 1,
+            }),
+            nullified_views: vec![NullifiedView {
+                epoch: 3,
+                view: 16,
+                leader: None,
+            }],
+        };
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["epoch"], "0x3");
+        assert_eq!(json["isSigner"], true);
+        assert!(json.get("peers").is_none());
+        assert_eq!(json["dkg"]["phase"], "collectingDealings");
+        assert_eq!(json["dkg"]["acksReceived"], "0x1");
+        assert_eq!(json["nullifiedViews"][0]["view"], "0x10");
+        assert_eq!(
+            serde_json::from_value::<ConsensusStatus>(json).unwrap(),
+            status
+        );
+    }
+
+    #[test]
+    fn test_peer_serde() {
+        let peer = ConsensusPeer {
+            public_key: B256::repeat_byte(0x02),
+            address: "127.0.0.1:8000".parse().unwrap(),
+            connected: true,
+        };
+
+        let json = serde_json::to_value(&peer).unwrap();
+        assert_eq!(json["address"], "127.0.0.1:8000");
+        assert_eq!(json["connected"], true);
+        assert_eq!(serde_json::from_value::<ConsensusPeer>(json).unwrap(), peer);
+    }
+
+    #[test]
+    fn test_snapshot_info_serde() {
+        let info = SnapshotInfo {
+            number: 199,
+            hash: B256::repeat_byte(0x11),
+            epoch: 1,
+            state_root: B256::repeat_byte(0x22),
+            accounts: 42,
+            path: "/tmp/snapshot.jsonl".into(),
+        };
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["number"], "0xc7");
+        assert_eq!(
+            json["stateRoot"],
+            serde_json::json!(B256::repeat_byte(0x22))
+        );
+        assert_eq!(json["accounts"], "0x2a");
+        assert_eq!(json["path"], "/tmp/snapshot.jsonl");
+        assert_eq!(serde_json::from_value::<SnapshotInfo>(json).unwrap(), info);
+    }
+
+    #[test]
+    fn test_snapshot_chunk_serde() {
+        let chunk = SnapshotChunk {
+            number: 199,
+            hash: B256::repeat_byte(0x11),
+            size: 1024,
+            offset: 512,
+            data: Bytes::from_static(&[0xab, 0xcd]),
+        };
+
+        let json = serde_json::to_value(&chunk).unwrap();
+        assert_eq!(json["size"], "0x400");
+        assert_eq!(json["offset"], "0x200");
+        assert_eq!(json["data"], "0xabcd");
+        assert_eq!(
+            serde_json::from_value::<SnapshotChunk>(json).unwrap(),
+            chunk
+        );
+    }
+
+    #[test]
+    fn test_subblock_transaction_status_serde() {
+        let status = SubblockTransactionStatus {
+            validator: B256::repeat_byte(0x11),
+            parent_hash: Some(B256::repeat_byte(0x22)),
+            proposer: Some(B256::repeat_byte(0x33)),
+            reached_proposer: true,
+            stage: SubblockTransactionStage::DroppedExpired,
+            block_number: None,
+            block_hash: None,
+        };
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["reachedProposer"], true);
+        assert_eq!(json["stage"], "droppedExpired");
+        assert!(json["blockNumber"].is_null());
+        assert_eq!(
+            serde_json::from_value::<SubblockTransactionStatus>(json).unwrap(),
+            status
+        );
+
+        let included = SubblockTransactionStatus {
+            stage: SubblockTransactionStage::Included,
+            block_number: Some(100),
+            block_hash: Some(B256::repeat_byte(0x44)),
+            ..status
+        };
+        let json = serde_json::to_value(&included).unwrap();
+        assert_eq!(json["stage"], "included");
+        assert_eq!(json["blockNumber"], "0x64");
+        assert_eq!(
+            serde_json::from_value::<SubblockTransactionStatus>(json).unwrap(),
+            included
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use alloy_primitives::B256;
+use alloy_rpc_types_trace::geth::{GethDebugTracerType, GethDebugTracingOptions, GethTrace};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_node_core::rpc::result::internal_rpc_err;
+use reth_rpc::DebugApi;
+use reth_rpc_eth_api::helpers::TraceExt;
+use reth_rpc_eth_types::EthApiError;
+use tempo_precompiles::tracer::{PrecompileCallFrame, PrecompileInspector};
+
+#[rpc(server, namespace = "debug")]
+pub trait TempoDebugApi {
+    /// Re-executes a transaction and returns a trace of every call it made into a Tempo
+    /// precompile, in execution order.
+    ///
+    /// Unlike `callTracer`, which only sees the outer call, each frame carries the storage
+    /// accesses (decoded against the precompile's storage layout where possible), events and gas
+    /// deductions of the precompile. Precompiles calling into each other, such as the DEX
+    /// transferring TIP-20 tokens, show up as nested frames.
+    ///
+    /// Returns `null` if the transaction is not known to this node.
+    #[method(name = "tracePrecompiles")]
+    async fn trace_precompiles(&self, tx_hash: B256)
+    -> RpcResult<Option<Vec<PrecompileCallFrame>>>;
+
+    /// `debug_traceTransaction`, with support for the
+    /// [`tempoPrecompileTracer`](PrecompileInspector::TRACER_NAME) in the `tracer` option, which
+    /// returns the same frames as `debug_tracePrecompiles`.
+    ///
+    /// Every other tracer is handled by the standard implementation.
+    #[method(name = "traceTransaction")]
+    async fn trace_transaction(
+        &self,
+        tx_hash: B256,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<GethTrace>;
+}
+
+/// The JSON-RPC handlers for the Tempo extensions of the `debug_` namespace.
+#[derive(Debug, Clone)]
+pub struct TempoDebug<EthApi> {
+    eth_api: EthApi,
+    debug_api: DebugApi<EthApi>,
+}
+
+impl<EthApi> TempoDebug<EthApi> {
+    pub fn new(eth_api: EthApi, debug_api: DebugApi<EthApi>) -> Self {
+        Self { eth_api, debug_api }
+    }
+}
+
+impl<EthApi: TraceExt + 'static> TempoDebug<EthApi> {
+    async fn precompile_frames(
+        &self,
+        tx_hash: B256,
+    ) -> RpcResult<Option<Vec<PrecompileCallFrame>>> {
+        self.eth_api
+            .spawn_trace_transaction_in_block_with_inspector(
+                tx_hash,
+                PrecompileInspector::default(),
+                |_, inspector, _, _| Ok(inspector.into_frames()),
+            )
+            .await
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait::async_trait]
+impl<EthApi: TraceExt + 'static> TempoDebugApiServer for TempoDebug<EthApi> {
+    async fn trace_precompiles(
+        &self,
+        tx_hash: B256,
+    ) -> RpcResult<Option<Vec<PrecompileCallFrame>>> {
+        self.precompile_frames(tx_hash).await
+    }
+
+    async fn trace_transaction(
+        &self,
+        tx_hash: B256,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<GethTrace> {
+        let opts = opts.unwrap_or_default();
+        if let Some(GethDebugTracerType::JsTracer(tracer)) = &opts.tracer
+            && tracer == PrecompileInspector::TRACER_NAME
+        {
+            let frames = self
+                .precompile_frames(tx_hash)
+                .await?
+                .ok_or(EthApiError::TransactionNotFound)?;
+            let frames =
+                serde_json::to_value(frames).map_err(|err| internal_rpc_err(err.to_string()))?;
+            return Ok(GethTrace::JS(frames));
+        }
+
+        self.debug_api
+            .debug_trace_transaction(tx_hash, opts)
+            .await
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,135 @@
+use alloy_eips::BlockNumberOrTag;
+use alloy_primitives::U64;
+use alloy_rpc_types_eth::FeeHistory;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_chainspec::{ChainSpecProvider, EthChainSpec};
+use reth_node_core::rpc::result::internal_rpc_err;
+use reth_provider::HeaderProvider;
+use reth_rpc_eth_api::{RpcNodeCore, helpers::EthFees};
+use reth_rpc_eth_types::EthApiError;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tempo_chainspec::TempoChainSpec;
+use tempo_primitives::TempoHeader;
+
+/// [`FeeHistory`] of the general lane, along with that of the payment lane.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempoFeeHistory {
+    /// Fee history of the general lane, whose base fee is that of the Ethereum header.
+    #[serde(flatten)]
+    pub inner: FeeHistory,
+    /// Base fees per gas of the payment lane, including the one of the block after the newest
+    /// block, like [`FeeHistory::base_fee_per_gas`].
+    #[serde(with = "alloy_serde::quantity::vec")]
+    pub payment_base_fee_per_gas: Vec<u128>,
+    /// Ratios of the gas used by the payment lane to its gas limit.
+    pub payment_gas_used_ratio: Vec<f64>,
+}
+
+#[rpc(server, namespace = "eth")]
+pub trait TempoEthFeesApi {
+    /// `eth_feeHistory`, reporting the payment lane next to the general lane.
+    ///
+    /// The standard fields describe the general lane. Before Allegro-Moderato both lanes share
+    /// the base fee and the gas used ratio of the block.
+    #[method(name = "feeHistory")]
+    async fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumberOrTag,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> RpcResult<TempoFeeHistory>;
+}
+
+/// The JSON-RPC handlers for the Tempo overrides of the fee methods of the `eth_` namespace.
+#[derive(Debug, Clone)]
+pub struct TempoEthFees<EthApi> {
+    eth_api: EthApi,
+}
+
+impl<EthApi> TempoEthFees<EthApi> {
+    pub fn new(eth_api: EthApi) -> Self {
+        Self { eth_api }
+    }
+}
+
+#[async_trait::async_trait]
+impl<EthApi> TempoEthFeesApiServer for TempoEthFees<EthApi>
+where
+    EthApi: EthFees
+        + RpcNodeCore<
+            Provider: HeaderProvider<Header = TempoHeader>
+                          + ChainSpecProvider<ChainSpec = TempoChainSpec>,
+        >,
+{
+    async fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumberOrTag,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> RpcResult<TempoFeeHistory> {
+        let mut inner = EthFees::fee_history(
+            &self.eth_api,
+            block_count.to(),
+            newest_block,
+            reward_percentiles,
+        )
+        .await
+        .map_err(Into::into)?;
+
+        let provider = self.eth_api.provider();
+        let end = inner.oldest_block + inner.gas_used_ratio.len() as u64;
+        let headers = provider
+            .sealed_headers_range(inner.oldest_block..end)
+            .map_err(EthApiError::from)?;
+        if headers.len() != inner.gas_used_ratio.len() {
+            return Err(internal_rpc_err("fee history headers not found"));
+        }
+
+        let mut payment_base_fee_per_gas = Vec::with_capacity(headers.len() + 1);
+        let mut payment_gas_used_ratio = Vec::with_capacity(headers.len());
+        for (header, gas_used_ratio) in headers.iter().zip(&inner.gas_used_ratio) {
+            payment_base_fee_per_gas
+                .push(header.payment_base_fee_per_gas().unwrap_or_default() as u128);
+            payment_gas_used_ratio.push(match header.lane_fees {
+                Some(fees) if header.payment_gas_limit() > 0 => {
+                    fees.payment_gas_used as f64 / header.payment_gas_limit() as f64
+                }
+                Some(_) => 0.0,
+                None => *gas_used_ratio,
+            });
+        }
+
+        if let Some(newest) = headers.last() {
+            // The block after the newest one is either known, or built at the current time
+            let next_timestamp = match provider
+                .header_by_number(newest.inner.number + 1)
+                .map_err(EthApiError::from)?
+            {
+                Some(next) => next.inner.timestamp,
+                None => SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |now| now.as_secs())
+                    .max(newest.inner.timestamp),
+            };
+
+            let chain_spec = provider.chain_spec();
+            let base_fee = chain_spec.next_block_base_fee(newest.header(), next_timestamp);
+            if let (Some(base_fee), Some(last)) = (base_fee, inner.base_fee_per_gas.last_mut()) {
+                *last = base_fee as u128;
+            }
+            let payment_base_fee = chain_spec
+                .next_block_payment_base_fee(newest.header(), next_timestamp)
+                .or(base_fee)
+                .unwrap_or_default();
+            payment_base_fee_per_gas.push(payment_base_fee as u128);
+        }
+
+        Ok(TempoFeeHistory {
+            inner,
+            payment_base_fee_per_gas,
+            payment_gas_used_ratio,
+        })
+    }
+}
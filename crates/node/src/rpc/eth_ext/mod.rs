@@ -4,6 +4,9 @@ use reth_node_core::rpc::result::internal_rpc_err;
 use reth_rpc_eth_api::RpcNodeCore;
 use tempo_alloy::rpc::pagination::PaginationParams;
 
+pub mod fees;
+pub use fees::{TempoEthFees, TempoEthFeesApiServer, TempoFeeHistory};
+
 pub mod transactions;
 pub use transactions::TransactionsFilter;
 
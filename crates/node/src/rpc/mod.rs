@@ -1,5 +1,7 @@
 pub mod admin;
 pub mod amm;
+pub mod consensus;
+pub mod debug;
 pub mod dex;
 pub mod error;
 pub mod eth_ext;
@@ -10,8 +12,16 @@ pub use admin::{TempoAdminApi, TempoAdminApiServer};
 use alloy_primitives::{Address, B256};
 use alloy_rpc_types_eth::{Log, ReceiptWithBloom};
 pub use amm::{TempoAmm, TempoAmmApiServer};
+pub use consensus::{
+    ConsensusEvidence, ConsensusFinalization, ConsensusHandle, ConsensusPeer,
+    ConsensusStateProvider, ConsensusStatus, DkgPhase, DkgStatus, EvidenceKind,
+    MAX_EVIDENCE_PAGE_SIZE, MAX_SNAPSHOT_CHUNK_SIZE, NullifiedView, SnapshotChunk, SnapshotInfo,
+    SubblockTransactionStage, SubblockTransactionStatus, TempoConsensusApi,
+    TempoConsensusApiServer,
+};
+pub use debug::{TempoDebug, TempoDebugApiServer};
 pub use dex::{TempoDex, api::TempoDexApiServer};
-pub use eth_ext::{TempoEthExt, TempoEthExtApiServer};
+pub use eth_ext::{TempoEthExt, TempoEthExtApiServer, TempoEthFees, TempoEthFeesApiServer};
 use futures::{TryFutureExt, future::Either};
 pub use policy::{TempoPolicy, TempoPolicyApiServer};
 use reth_errors::RethError;
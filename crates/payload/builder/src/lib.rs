@@ -4,9 +4,14 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 mod metrics;
+mod prebuild;
 
-use crate::metrics::TempoPayloadBuilderMetrics;
-use alloy_consensus::{BlockHeader as _, Signed, Transaction, TxLegacy};
+pub use prebuild::{PrebuildHandle, Prebuilder};
+
+use crate::{metrics::TempoPayloadBuilderMetrics, prebuild::PrebuiltTransactions};
+use alloy_consensus::{
+    BlockHeader as _, Signed, Transaction, TxLegacy, transaction::TxHashRef as _,
+};
 use alloy_primitives::{Address, U256};
 use alloy_rlp::{Decodable, Encodable};
 use alloy_sol_types::SolCall;
@@ -32,8 +37,8 @@ use reth_revm::{
 };
 use reth_storage_api::StateProviderFactory;
 use reth_transaction_pool::{
-    BestTransactions, BestTransactionsAttributes, TransactionPool, ValidPoolTransaction,
-    error::InvalidPoolTransactionError,
+    BestTransactions, BestTransactionsAttributes, PoolTransaction, TransactionPool,
+    ValidPoolTransaction, error::InvalidPoolTransactionError,
 };
 use std::{
     sync::{
@@ -44,23 +49,27 @@ use std::{
 };
 use tempo_chainspec::TempoChainSpec;
 use tempo_consensus::{TEMPO_GENERAL_GAS_DIVISOR, TEMPO_SHARED_GAS_DIVISOR};
-use tempo_evm::{TempoEvmConfig, TempoNextBlockEnvAttributes, evm::TempoEvm};
-use tempo_payload_types::TempoPayloadBuilderAttributes;
+use tempo_evm::{TempoBlockEnv, TempoEvmConfig, TempoNextBlockEnvAttributes, evm::TempoEvm};
+use tempo_payload_types::{
+    DecryptedTransactions, SubblockDropReason, TempoPayloadBuilderAttributes,
+};
 use tempo_precompiles::{
     STABLECOIN_EXCHANGE_ADDRESS, TIP_FEE_MANAGER_ADDRESS, TIP20_REWARDS_REGISTRY_ADDRESS,
-    stablecoin_exchange::IStablecoinExchange, tip_fee_manager::IFeeManager,
-    tip20_rewards_registry::ITIP20RewardsRegistry,
+    metrics::ExecutionContext, stablecoin_exchange::IStablecoinExchange,
+    tip_fee_manager::IFeeManager, tip20_rewards_registry::ITIP20RewardsRegistry,
 };
 use tempo_primitives::{
-    RecoveredSubBlock, SubBlockMetadata, TempoHeader, TempoPrimitives, TempoTxEnvelope,
+    ENCRYPTED_TX_ADDRESS, RecoveredSubBlock, SubBlockMetadata, TempoHeader, TempoPrimitives,
+    TempoTxEnvelope,
     subblock::PartialValidatorKey,
     transaction::{
         calc_gas_balance_spending,
+        encrypted::encode_decryption_keys,
         envelope::{TEMPO_SYSTEM_TX_SENDER, TEMPO_SYSTEM_TX_SIGNATURE},
     },
 };
 use tempo_transaction_pool::{
-    TempoTransactionPool,
+    PaymentLaneConfig, TempoTransactionPool,
     transaction::{TempoPoolTransactionError, TempoPooledTransaction},
 };
 use tracing::{Level, debug, error, info, instrument, trace, warn};
@@ -71,6 +80,10 @@ pub struct TempoPayloadBuilder<Provider> {
     provider: Provider,
     evm_config: TempoEvmConfig,
     metrics: TempoPayloadBuilderMetrics,
+    /// Ordering of the payment lane.
+    payment_lane: PaymentLaneConfig,
+    /// Handle to the state read ahead of payload requests.
+    prebuild: PrebuildHandle,
     /// Height at which we've seen an invalid subblock.
     ///
     /// We pre-validate all of the subblock transactions when collecting subblocks, so this
@@ -94,27 +107,90 @@ impl<Provider> TempoPayloadBuilder<Provider> {
             provider,
             evm_config,
             metrics: TempoPayloadBuilderMetrics::default(),
+            payment_lane: PaymentLaneConfig::default(),
+            prebuild: PrebuildHandle::default(),
             highest_invalid_subblock: Default::default(),
         }
     }
+
+    /// Sets the ordering of payment transactions in built payloads.
+    pub fn with_payment_lane(mut self, payment_lane: PaymentLaneConfig) -> Self {
+        self.payment_lane = payment_lane;
+        self
+    }
+
+    /// Sets the handle through which the parents of upcoming payloads are announced.
+    ///
+    /// Payloads built on top of an announced parent start with the transactions executed by the
+    /// [`Prebuilder`] returned by [`Self::prebuilder`], from the state it read.
+    pub fn with_prebuild_handle(mut self, prebuild: PrebuildHandle) -> Self {
+        self.prebuild = prebuild;
+        self
+    }
+
+    /// Returns a [`Prebuilder`] executing pool transactions on top of the parents announced
+    /// through this builder's [`PrebuildHandle`].
+    pub fn prebuilder(&self) -> Prebuilder<Provider>
+    where
+        Provider: Clone,
+    {
+        Prebuilder::new(
+            self.prebuild.clone(),
+            self.pool.clone(),
+            self.provider.clone(),
+            self.evm_config.clone(),
+            self.payment_lane,
+            self.metrics.clone(),
+        )
+    }
 }
 
 impl<Provider: ChainSpecProvider> TempoPayloadBuilder<Provider> {
     /// Builds system transactions to execute at the start of the block.
     ///
     /// Returns a vector of system transactions that must be executed at the beginning of each block:
-    /// 1. TIP20 Rewards Registry finalizeStreams - finalizes expired reward streams
+    /// 1. TIP20 Rewards Registry finalizeStreams - finalizes expired reward streams (pre-moderato)
+    /// 2. Decryption keys - releases the keys of the encrypted transactions in the parent block
     fn build_start_block_txs(
         &self,
         evm: &TempoEvm<impl Database>,
+        decrypted: Option<&DecryptedTransactions>,
     ) -> Vec<Recovered<TempoTxEnvelope>> {
-        // No start of block system transactions after moderato.
-        if evm.ctx().cfg.spec.is_moderato() {
-            return vec![];
+        let chain_id = Some(self.provider.chain_spec().chain().id());
+        let mut txs = Vec::new();
+
+        if !evm.ctx().cfg.spec.is_moderato() {
+            txs.push(self.build_rewards_registry_tx(evm, chain_id));
         }
 
-        let chain_id = Some(self.provider.chain_spec().chain().id());
+        if let Some(decrypted) = decrypted.filter(|decrypted| !decrypted.keys.is_empty()) {
+            let decryption_keys_tx = Recovered::new_unchecked(
+                TempoTxEnvelope::Legacy(Signed::new_unhashed(
+                    TxLegacy {
+                        chain_id,
+                        nonce: 0,
+                        gas_price: 0,
+                        gas_limit: 0,
+                        to: ENCRYPTED_TX_ADDRESS.into(),
+                        value: U256::ZERO,
+                        input: encode_decryption_keys(&decrypted.keys, evm.block().number.to()),
+                    },
+                    TEMPO_SYSTEM_TX_SIGNATURE,
+                )),
+                TEMPO_SYSTEM_TX_SENDER,
+            );
+            txs.push(decryption_keys_tx);
+        }
 
+        txs
+    }
+
+    /// Builds the TIP20 Rewards Registry finalizeStreams system transaction.
+    fn build_rewards_registry_tx(
+        &self,
+        evm: &TempoEvm<impl Database>,
+        chain_id: Option<u64>,
+    ) -> Recovered<TempoTxEnvelope> {
         // Build rewards registry system transaction
         let rewards_registry_input = ITIP20RewardsRegistry::finalizeStreamsCall {}
             .abi_encode()
@@ -122,7 +198,7 @@ impl<Provider: ChainSpecProvider> TempoPayloadBuilder<Provider> {
             .chain(evm.block().number.to_be_bytes_vec())
             .collect();
 
-        let rewards_registry_tx = Recovered::new_unchecked(
+        Recovered::new_unchecked(
             TempoTxEnvelope::Legacy(Signed::new_unhashed(
                 TxLegacy {
                     chain_id,
@@ -136,9 +212,7 @@ impl<Provider: ChainSpecProvider> TempoPayloadBuilder<Provider> {
                 TEMPO_SYSTEM_TX_SIGNATURE,
             )),
             TEMPO_SYSTEM_TX_SENDER,
-        );
-
-        vec![rewards_registry_tx]
+        )
     }
 
     /// Builds system transactions to seal the block.
@@ -246,11 +320,16 @@ where
         &self,
         args: BuildArguments<Self::Attributes, Self::BuiltPayload>,
     ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
-        self.build_payload(
-            args,
-            |attributes| self.pool.best_transactions_with_attributes(attributes),
-            false,
-        )
+        ExecutionContext::Payload.enter(|| {
+            self.build_payload(
+                args,
+                |attributes| {
+                    self.payment_lane
+                        .apply(self.pool.best_transactions_with_attributes(attributes))
+                },
+                false,
+            )
+        })
     }
 
     fn on_missing_payload(
@@ -264,18 +343,21 @@ where
         &self,
         config: PayloadConfig<Self::Attributes, TempoHeader>,
     ) -> Result<Self::BuiltPayload, PayloadBuilderError> {
-        self.build_payload(
-            BuildArguments::new(
-                Default::default(),
-                config,
-                Default::default(),
-                Default::default(),
-            ),
-            |_| core::iter::empty(),
-            true,
-        )?
-        .into_payload()
-        .ok_or_else(|| PayloadBuilderError::MissingPayload)
+        ExecutionContext::Payload
+            .enter(|| {
+                self.build_payload(
+                    BuildArguments::new(
+                        Default::default(),
+                        config,
+                        Default::default(),
+                        Default::default(),
+                    ),
+                    |_| core::iter::empty(),
+                    true,
+                )
+            })?
+            .into_payload()
+            .ok_or_else(|| PayloadBuilderError::MissingPayload)
     }
 }
 
@@ -295,7 +377,7 @@ where
     fn build_payload<Txs>(
         &self,
         args: BuildArguments<TempoPayloadBuilderAttributes, EthBuiltPayload<TempoPrimitives>>,
-        best_txs: impl FnOnce(BestTransactionsAttributes) -> Txs,
+        best_txs: impl Fn(BestTransactionsAttributes) -> Txs,
         empty: bool,
     ) -> Result<BuildOutcome<EthBuiltPayload<TempoPrimitives>>, PayloadBuilderError>
     where
@@ -314,6 +396,34 @@ where
 
         let start = Instant::now();
 
+        // Start the first build on top of a parent with the transactions of its prebuild, from
+        // the state it read. Their results are committed without executing them again if the
+        // payload is built for the timestamp of the prebuild.
+        let mut prebuilt = None;
+        let mut prebuilt_transactions = Vec::new();
+        let mut prebuilt_execution = None;
+        if best_payload.is_none() && !empty {
+            if let Some(prebuild) = self.prebuild.take(parent_header.hash()) {
+                self.metrics.prebuild_hits.increment(1);
+                if prebuild.execution.env().block_env.timestamp_millis()
+                    != U256::from(attributes.timestamp_millis())
+                {
+                    debug!(
+                        prebuild_timestamp = %prebuild.execution.env().block_env.timestamp_millis(),
+                        timestamp = attributes.timestamp_millis(),
+                        "payload timestamp differs from prebuild; executing prebuilt transactions again"
+                    );
+                }
+                cached_reads.extend(prebuild.cached_reads);
+                prebuilt_transactions = prebuild.transactions;
+                prebuilt_execution = Some(Arc::new(prebuild.execution));
+                prebuilt = Some(true);
+            } else {
+                self.metrics.prebuild_misses.increment(1);
+                prebuilt = Some(false);
+            }
+        }
+
         let block_time_millis =
             (attributes.timestamp_millis() - parent_header.timestamp_millis()) as f64;
         self.metrics.block_time_millis.record(block_time_millis);
@@ -349,9 +459,13 @@ where
         //
         // Also don't include any subblocks if we've seen an invalid subblock
         // at this height or above.
-        let mut subblocks = if empty
-            || self.highest_invalid_subblock.load(Ordering::Relaxed) > parent_header.number()
-        {
+        let mut subblocks = if empty {
+            vec![]
+        } else if self.highest_invalid_subblock.load(Ordering::Relaxed) > parent_header.number() {
+            for subblock in attributes.subblocks() {
+                attributes
+                    .report_dropped_subblock(&subblock, SubblockDropReason::AfterInvalidSubblock);
+            }
             vec![]
         } else {
             attributes.subblocks()
@@ -370,6 +484,7 @@ where
                         .is_some_and(|valid| valid < attributes.timestamp())
                 })
             }) {
+                attributes.report_dropped_subblock(subblock, SubblockDropReason::Expired);
                 return false;
             }
 
@@ -384,11 +499,28 @@ where
             .map(|subblock| {
                 (
                     PartialValidatorKey::from_slice(&subblock.validator()[..15]),
-                    subblock.fee_recipient,
+                    subblock.fee_recipients(),
                 )
             })
             .collect();
 
+        // Speculatively execute the best payment transactions of the pool in parallel, if enabled.
+        let speculative_transactions = if empty || !self.evm_config.is_parallel_execution_enabled()
+        {
+            Vec::new()
+        } else {
+            let base_fee = chain_spec
+                .next_block_base_fee(parent_header.header(), attributes.timestamp())
+                .unwrap_or_default();
+            let payment_base_fee = chain_spec
+                .next_block_payment_base_fee(parent_header.header(), attributes.timestamp())
+                .unwrap_or(base_fee);
+            collect_speculative_transactions(
+                best_txs(BestTransactionsAttributes::new(payment_base_fee, None)),
+                non_shared_gas_limit,
+            )
+        };
+
         let mut builder = self
             .evm_config
             .builder_for_next_block(
@@ -408,6 +540,8 @@ where
                     shared_gas_limit,
                     timestamp_millis_part: attributes.timestamp_millis_part(),
                     subblock_fee_recipients,
+                    speculative_transactions,
+                    prebuilt: prebuilt_execution,
                 },
             )
             .map_err(PayloadBuilderError::other)?;
@@ -430,19 +564,27 @@ where
             .prepare_system_transactions_duration_seconds
             .record(prepare_system_txs_elapsed);
 
-        let base_fee = builder.evm_mut().block().basefee;
-        let mut best_txs = best_txs(BestTransactionsAttributes::new(
-            base_fee,
-            builder
-                .evm_mut()
-                .block()
-                .blob_gasprice()
-                .map(|gasprice| gasprice as u64),
-        ));
+        // Payment transactions are priced by the base fee of the payment lane, if it has its own.
+        let block_env = builder.evm_mut().block().clone();
+        let mut best_txs = PrebuiltTransactions::new(
+            prebuilt_transactions,
+            best_txs(BestTransactionsAttributes::new(
+                block_env.lane_basefee(true).min(block_env.basefee),
+                builder
+                    .evm_mut()
+                    .block()
+                    .blob_gasprice()
+                    .map(|gasprice| gasprice as u64),
+            )),
+        );
+
+        // Even empty payloads must release the decryption keys of the parent block and include
+        // its decrypted transactions.
+        let decrypted = attributes.decrypted_transactions();
 
-        // Execute start-of-block system transactions (rewards registry finalize)
+        // Execute start-of-block system transactions (rewards registry finalize, decryption keys)
         let start_block_txs_execution_start = Instant::now();
-        for tx in self.build_start_block_txs(builder.evm()) {
+        for tx in self.build_start_block_txs(builder.evm(), decrypted) {
             block_size_used += tx.inner().length();
 
             builder
@@ -455,6 +597,51 @@ where
             .record(start_block_txs_execution_elapsed);
 
         let execution_start = Instant::now();
+
+        // Execute the decrypted transactions right after the decryption keys, in the order of
+        // their ciphertexts. Only transactions that don't fit or are invalid on top of the current
+        // state may be skipped, as enforced by the block executor.
+        for tx in decrypted
+            .iter()
+            .flat_map(|decrypted| &decrypted.transactions)
+        {
+            let is_payment = tx.is_payment();
+            if cumulative_gas_used + tx.gas_limit() > non_shared_gas_limit
+                || (!is_payment && non_payment_gas_used + tx.gas_limit() > general_gas_limit)
+            {
+                trace!(tx_hash = %tx.tx_hash(), "skipping decrypted transaction exceeding gas limit");
+                continue;
+            }
+
+            // Decrypted transactions are smaller than the ciphertexts of the parent block, so
+            // they always fit into the block.
+            let tx_rlp_length = tx.inner().length();
+
+            let effective_gas_price =
+                tx.effective_gas_price(Some(block_env.lane_basefee(is_payment)));
+            let gas_used = match builder.execute_transaction(tx.clone()) {
+                Ok(gas_used) => gas_used,
+                Err(BlockExecutionError::Validation(BlockValidationError::InvalidTx {
+                    error,
+                    ..
+                })) => {
+                    trace!(%error, tx_hash = %tx.tx_hash(), "skipping invalid decrypted transaction");
+                    continue;
+                }
+                Err(err) => return Err(PayloadBuilderError::evm(err)),
+            };
+
+            if is_payment {
+                payment_transactions += 1;
+            }
+            total_fees += calc_gas_balance_spending(gas_used, effective_gas_price);
+            cumulative_gas_used += gas_used;
+            if !is_payment {
+                non_payment_gas_used += gas_used;
+            }
+            block_size_used += tx_rlp_length;
+        }
+
         while let Some(pool_tx) = best_txs.next() {
             // ensure we still have capacity for this transaction
             if cumulative_gas_used + pool_tx.gas_limit() > non_shared_gas_limit {
@@ -494,6 +681,11 @@ where
                 return Ok(BuildOutcome::Cancelled);
             }
 
+            if is_underpriced(&pool_tx.transaction, &block_env) {
+                best_txs.mark_invalid(&pool_tx, &InvalidPoolTransactionError::Underpriced);
+                continue;
+            }
+
             let is_payment = pool_tx.transaction.is_payment();
             if is_payment {
                 payment_transactions += 1;
@@ -513,7 +705,9 @@ where
                 continue;
             }
 
-            let effective_gas_price = pool_tx.transaction.effective_gas_price(Some(base_fee));
+            let effective_gas_price = pool_tx
+                .transaction
+                .effective_gas_price(Some(block_env.lane_basefee(is_payment)));
 
             let tx_debug_repr = tracing::enabled!(Level::TRACE)
                 .then(|| format!("{:?}", pool_tx.transaction))
@@ -658,6 +852,17 @@ where
 
         let elapsed = start.elapsed();
         self.metrics.payload_build_duration_seconds.record(elapsed);
+        match prebuilt {
+            Some(true) => self
+                .metrics
+                .prebuilt_payload_build_duration_seconds
+                .record(elapsed),
+            Some(false) => self
+                .metrics
+                .cold_payload_build_duration_seconds
+                .record(elapsed),
+            None => {}
+        }
         let gas_per_second = sealed_block.gas_used() as f64 / elapsed.as_secs_f64();
         self.metrics.gas_per_second.record(gas_per_second);
         self.metrics.gas_per_second_last.set(gas_per_second);
@@ -709,11 +914,39 @@ pub fn is_more_subblocks(
     subblocks.len() > best_metadata.len()
 }
 
+/// Collects the best payment transactions of the pool fitting in the given gas limit.
+///
+/// These are likely to be included in the payload and are speculatively executed in parallel.
+fn collect_speculative_transactions(
+    best_txs: impl Iterator<Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+    gas_limit: u64,
+) -> Vec<Recovered<TempoTxEnvelope>> {
+    let mut gas_used = 0;
+    best_txs
+        .filter(|tx| tx.transaction.is_payment())
+        .take_while(|tx| {
+            gas_used += tx.gas_limit();
+            gas_used <= gas_limit
+        })
+        .map(|tx| tx.transaction.clone_into_consensus())
+        .collect()
+}
+
+/// Returns whether `tx` pays less than the base fee of its lane.
+///
+/// The pool only filters by the lower of the two lane base fees, so the payload builder has to
+/// filter non-payment transactions by the general lane base fee itself.
+pub(crate) fn is_underpriced(tx: &TempoPooledTransaction, block_env: &TempoBlockEnv) -> bool {
+    tx.max_fee_per_gas() < block_env.lane_basefee(tx.is_payment()) as u128
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy_primitives::{Address, B256, Bytes};
+    use alloy_primitives::{Address, B256, Bytes, Signature, TxKind};
     use reth_payload_builder::PayloadId;
+    use reth_primitives_traits::Recovered;
+    use tempo_precompiles::PATH_USD_ADDRESS;
 
     #[test]
     fn test_extra_data_flow_in_attributes() {
@@ -736,4 +969,37 @@ mod tests {
 
         assert_eq!(injected_data, extra_data);
     }
+
+    fn pooled_tx(to: Address, gas_price: u128) -> TempoPooledTransaction {
+        let tx = TxLegacy {
+            gas_price,
+            gas_limit: 100_000,
+            to: TxKind::Call(to),
+            ..Default::default()
+        };
+        let envelope =
+            TempoTxEnvelope::Legacy(Signed::new_unhashed(tx, Signature::test_signature()));
+        TempoPooledTransaction::new(Recovered::new_unchecked(envelope, Address::repeat_byte(1)))
+    }
+
+    #[test]
+    fn test_transactions_are_priced_by_their_lane() {
+        let payment = |gas_price| pooled_tx(PATH_USD_ADDRESS, gas_price);
+        let general = |gas_price| pooled_tx(STABLECOIN_EXCHANGE_ADDRESS, gas_price);
+
+        let mut block_env = TempoBlockEnv::default();
+        block_env.basefee = 20;
+        block_env.payment_basefee = Some(10);
+
+        assert!(!is_underpriced(&payment(10), &block_env));
+        assert!(is_underpriced(&payment(9), &block_env));
+        // Paying the payment lane base fee is not enough for the general lane
+        assert!(is_underpriced(&general(10), &block_env));
+        assert!(!is_underpriced(&general(20), &block_env));
+
+        // Without a separate payment lane base fee, both lanes pay the block base fee
+        block_env.payment_basefee = None;
+        assert!(is_underpriced(&payment(10), &block_env));
+        assert!(!is_underpriced(&payment(20), &block_env));
+    }
 }
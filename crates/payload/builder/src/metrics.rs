@@ -1,5 +1,8 @@
 use metrics::Gauge;
-use reth_metrics::{Metrics, metrics::Histogram};
+use reth_metrics::{
+    Metrics,
+    metrics::{Counter, Histogram},
+};
 
 #[derive(Metrics, Clone)]
 #[metrics(scope = "tempo_payload_builder")]
@@ -38,4 +41,17 @@ pub(crate) struct TempoPayloadBuilderMetrics {
     pub(crate) gas_per_second: Histogram,
     /// Gas per second for the last payload calculated as gas_used / payload_build_duration.
     pub(crate) gas_per_second_last: Gauge,
+    /// Number of transactions executed by a prebuild before its payload was requested.
+    pub(crate) prebuild_transactions: Histogram,
+    /// Time it took to build the first payload on top of a parent from its prebuild in seconds.
+    pub(crate) prebuilt_payload_build_duration_seconds: Histogram,
+    /// Time it took to build the first payload on top of a parent without a prebuild in seconds.
+    ///
+    /// Compared to `prebuilt_payload_build_duration_seconds`, this gives how much earlier
+    /// prebuilt payloads are ready.
+    pub(crate) cold_payload_build_duration_seconds: Histogram,
+    /// Number of payloads built from prebuilt state.
+    pub(crate) prebuild_hits: Counter,
+    /// Number of payloads built without prebuilt state.
+    pub(crate) prebuild_misses: Counter,
 }
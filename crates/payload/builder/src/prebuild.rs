@@ -0,0 +1,651 @@
+//! Pre-building of payloads ahead of their request.
+//!
+//! Once the block the next payload will be built on is notarized, the consensus layer announces
+//! it through the [`PrebuildHandle`]. The [`Prebuilder`] then continuously executes the best pool
+//! transactions on top of the parent's state, appending new transactions as they arrive, until
+//! the payload is requested.
+//!
+//! The prebuild executes the transactions for the timestamp chosen when its parent is announced,
+//! which the proposer requests the payload with through [`PrebuildHandle::timestamp_millis`].
+//! The prebuilt transactions, their results and the state read along the way are handed over to
+//! the payload builder. The payload starts with the prebuilt transactions, in order, before any
+//! other pool transaction. Their results are committed as is if the payload has the block
+//! environment of the prebuild and the values they read are unchanged by the transactions at the
+//! start of the block, so that only the transactions arriving after the request are executed.
+//! Otherwise they are executed again, mostly hitting warm caches.
+
+use crate::{is_underpriced, metrics::TempoPayloadBuilderMetrics};
+use alloy_consensus::BlockHeader as _;
+use alloy_primitives::{Address, B256, TxHash, map::HashSet};
+use parking_lot::{Condvar, Mutex};
+use reth_chainspec::ChainSpecProvider;
+use reth_evm::{ConfigureEvm, NextBlockEnvAttributes};
+use reth_payload_builder::PayloadBuilderError;
+use reth_primitives_traits::transaction::error::InvalidTransactionError;
+use reth_revm::{State, cached::CachedReads, database::StateProviderDatabase};
+use reth_storage_api::{HeaderProvider, StateProviderFactory};
+use reth_transaction_pool::{
+    BestTransactions, BestTransactionsAttributes, PoolTransaction, TransactionPool,
+    ValidPoolTransaction, error::InvalidPoolTransactionError,
+};
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tempo_chainspec::TempoChainSpec;
+use tempo_consensus::{TEMPO_GENERAL_GAS_DIVISOR, TEMPO_SHARED_GAS_DIVISOR};
+use tempo_evm::{PrebuiltExecution, TempoEvmConfig, TempoNextBlockEnvAttributes};
+use tempo_precompiles::metrics::ExecutionContext;
+use tempo_primitives::TempoHeader;
+use tempo_transaction_pool::{
+    PaymentLaneConfig, TempoTransactionPool,
+    transaction::{TempoPoolTransactionError, TempoPooledTransaction},
+};
+use tracing::{debug, trace};
+
+/// Maximum time a prebuild keeps running without its payload being requested.
+const MAX_PREBUILD_DURATION: Duration = Duration::from_secs(4);
+
+/// How long a prebuild waits for new pool transactions once it executed all of them.
+const POOL_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How long a payload build waits for the running prebuild to hand over its reads.
+const HANDOVER_TIMEOUT: Duration = Duration::from_millis(20);
+
+/// Handle through which the parent of the next payload is announced ahead of its request.
+#[derive(Debug, Clone, Default)]
+pub struct PrebuildHandle {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<PrebuildState>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct PrebuildState {
+    /// The block the next payload is expected to be built on.
+    target: Option<Target>,
+    /// The result of the latest finished prebuild.
+    prebuilt: Option<Prebuilt>,
+    /// Whether the node is shutting down.
+    shutdown: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Target {
+    parent_hash: B256,
+    fee_recipient: Address,
+    /// Timestamp of the payload, in milliseconds.
+    timestamp_millis: u64,
+    announced_at: Instant,
+}
+
+/// The transactions executed by a prebuild and the state it read.
+#[derive(Debug)]
+pub(crate) struct Prebuilt {
+    /// The block the prebuild was executed on.
+    pub(crate) parent_hash: B256,
+    /// The executed transactions, in execution order.
+    pub(crate) transactions: Vec<Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+    /// The results of the executed transactions and the environment they were executed in.
+    pub(crate) execution: PrebuiltExecution,
+    /// Accounts, storage and code read during the prebuild.
+    pub(crate) cached_reads: CachedReads,
+}
+
+impl PrebuildHandle {
+    /// Starts pre-building the next payload on top of `parent_hash`, paying fees to
+    /// `fee_recipient`, for the current time.
+    ///
+    /// Supersedes any running prebuild on top of a different block.
+    pub fn prebuild(&self, parent_hash: B256, fee_recipient: Address) {
+        let mut state = self.shared.state.lock();
+        if state.shutdown
+            || state
+                .target
+                .is_some_and(|target| target.parent_hash == parent_hash)
+        {
+            return;
+        }
+        state.target = Some(Target {
+            parent_hash,
+            fee_recipient,
+            timestamp_millis: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|now| now.as_millis() as u64)
+                .unwrap_or_default(),
+            announced_at: Instant::now(),
+        });
+        self.shared.changed.notify_all();
+    }
+
+    /// Returns the timestamp in milliseconds the payload on top of `parent_hash` is prebuilt for,
+    /// if it is still being prebuilt.
+    ///
+    /// Prebuilt transactions are only committed without executing them again if the payload is
+    /// requested with this timestamp.
+    pub fn timestamp_millis(&self, parent_hash: B256) -> Option<u64> {
+        self.shared
+            .state
+            .lock()
+            .target
+            .filter(|target| {
+                target.parent_hash == parent_hash
+                    && target.announced_at.elapsed() < MAX_PREBUILD_DURATION
+            })
+            .map(|target| target.timestamp_millis)
+    }
+
+    /// Stops the prebuild on top of `parent_hash` and takes the state it read.
+    ///
+    /// Returns `None` if there was no prebuild on top of `parent_hash`, or if it did not hand over
+    /// its state in time.
+    pub(crate) fn take(&self, parent_hash: B256) -> Option<Prebuilt> {
+        let mut state = self.shared.state.lock();
+        if state
+            .target
+            .is_some_and(|target| target.parent_hash == parent_hash)
+        {
+            state.target = None;
+            self.shared.changed.notify_all();
+            self.shared.changed.wait_while_for(
+                &mut state,
+                |state| {
+                    state
+                        .prebuilt
+                        .as_ref()
+                        .is_none_or(|prebuilt| prebuilt.parent_hash != parent_hash)
+                },
+                HANDOVER_TIMEOUT,
+            );
+        }
+
+        state
+            .prebuilt
+            .take_if(|prebuilt| prebuilt.parent_hash == parent_hash)
+    }
+
+    /// Stops the running prebuild and the [`Prebuilder`].
+    pub fn shutdown(&self) {
+        let mut state = self.shared.state.lock();
+        state.shutdown = true;
+        state.target = None;
+        self.shared.changed.notify_all();
+    }
+
+    /// Returns true if the prebuild on top of `parent_hash` should keep running.
+    fn is_current(&self, parent_hash: B256) -> bool {
+        self.shared
+            .state
+            .lock()
+            .target
+            .is_some_and(|target| target.parent_hash == parent_hash)
+    }
+
+    /// Blocks until a prebuild on top of a block other than `last` is requested.
+    ///
+    /// Returns `None` once the node is shutting down.
+    fn wait_for_target(&self, last: Option<B256>) -> Option<Target> {
+        let mut state = self.shared.state.lock();
+        loop {
+            if state.shutdown {
+                return None;
+            }
+            if let Some(target) = state
+                .target
+                .filter(|target| Some(target.parent_hash) != last)
+            {
+                return Some(target);
+            }
+            self.shared.changed.wait(&mut state);
+        }
+    }
+
+    /// Blocks for at most `timeout` or until the prebuild on top of `parent_hash` is stopped.
+    fn wait_while_current(&self, parent_hash: B256, timeout: Duration) {
+        let mut state = self.shared.state.lock();
+        self.shared.changed.wait_while_for(
+            &mut state,
+            |state| {
+                state
+                    .target
+                    .is_some_and(|target| target.parent_hash == parent_hash)
+            },
+            timeout,
+        );
+    }
+
+    /// Stores the result of a finished prebuild.
+    fn finish(&self, prebuilt: Prebuilt) {
+        self.shared.state.lock().prebuilt = Some(prebuilt);
+        self.shared.changed.notify_all();
+    }
+}
+
+/// Executes pool transactions on top of the blocks announced through a [`PrebuildHandle`].
+#[derive(Debug)]
+pub struct Prebuilder<Provider> {
+    handle: PrebuildHandle,
+    pool: TempoTransactionPool<Provider>,
+    provider: Provider,
+    evm_config: TempoEvmConfig,
+    payment_lane: PaymentLaneConfig,
+    metrics: TempoPayloadBuilderMetrics,
+}
+
+impl<Provider> Prebuilder<Provider>
+where
+    Provider: StateProviderFactory
+        + ChainSpecProvider<ChainSpec = TempoChainSpec>
+        + HeaderProvider<Header = TempoHeader>
+        + 'static,
+{
+    pub(crate) fn new(
+        handle: PrebuildHandle,
+        pool: TempoTransactionPool<Provider>,
+        provider: Provider,
+        evm_config: TempoEvmConfig,
+        payment_lane: PaymentLaneConfig,
+        metrics: TempoPayloadBuilderMetrics,
+    ) -> Self {
+        Self {
+            handle,
+            pool,
+            provider,
+            evm_config,
+            payment_lane,
+            metrics,
+        }
+    }
+
+    /// Runs the prebuilder, blocking the current thread until [`PrebuildHandle::shutdown`] is
+    /// called.
+    pub fn run(self) {
+        let mut last = None;
+        while let Some(target) = self.handle.wait_for_target(last) {
+            last = Some(target.parent_hash);
+
+            match ExecutionContext::Payload.enter(|| self.prebuild(target)) {
+                Ok(prebuilt) => self.handle.finish(prebuilt),
+                Err(error) => {
+                    debug!(%error, parent_hash = %target.parent_hash, "failed prebuilding payload");
+                }
+            }
+        }
+    }
+
+    /// Executes pool transactions on top of the target block until it is superseded, its payload
+    /// is requested, or [`MAX_PREBUILD_DURATION`] passed.
+    fn prebuild(&self, target: Target) -> Result<Prebuilt, PayloadBuilderError> {
+        let started_at = Instant::now();
+        let Target {
+            parent_hash,
+            fee_recipient,
+            timestamp_millis,
+            ..
+        } = target;
+
+        let parent_header = self
+            .provider
+            .header(parent_hash)?
+            .ok_or(PayloadBuilderError::MissingParentHeader(parent_hash))?;
+        let state_provider = self.provider.state_by_block_hash(parent_hash)?;
+
+        let mut cached_reads = CachedReads::default();
+        let mut db = State::builder()
+            .with_database(cached_reads.as_db_mut(StateProviderDatabase::new(&state_provider)))
+            .with_bundle_update()
+            .build();
+
+        let timestamp_millis = timestamp_millis.max(parent_header.timestamp_millis() + 1);
+        let block_gas_limit = parent_header.gas_limit();
+        let shared_gas_limit = block_gas_limit / TEMPO_SHARED_GAS_DIVISOR;
+        let non_shared_gas_limit = block_gas_limit - shared_gas_limit;
+        let general_gas_limit = non_shared_gas_limit / TEMPO_GENERAL_GAS_DIVISOR;
+
+        let evm_env = self
+            .evm_config
+            .next_evm_env(
+                &parent_header,
+                &TempoNextBlockEnvAttributes {
+                    inner: NextBlockEnvAttributes {
+                        timestamp: timestamp_millis / 1000,
+                        suggested_fee_recipient: fee_recipient,
+                        prev_randao: B256::ZERO,
+                        gas_limit: block_gas_limit,
+                        parent_beacon_block_root: Some(B256::ZERO),
+                        withdrawals: Some(Default::default()),
+                        extra_data: Default::default(),
+                    },
+                    general_gas_limit,
+                    shared_gas_limit,
+                    timestamp_millis_part: timestamp_millis % 1000,
+                    subblock_fee_recipients: Default::default(),
+                    speculative_transactions: Vec::new(),
+                    prebuilt: None,
+                },
+            )
+            .map_err(PayloadBuilderError::other)?;
+        // Payment transactions are priced against the payment lane base fee, which may be lower.
+        let block_env = evm_env.block_env.clone();
+        let mut execution = PrebuiltExecution::new(evm_env);
+
+        let mut seen = HashSet::<B256>::default();
+        let mut transactions = Vec::new();
+        let mut gas_used = 0;
+        let mut non_payment_gas_used = 0;
+        'prebuild: while self.handle.is_current(parent_hash)
+            && started_at.elapsed() < MAX_PREBUILD_DURATION
+        {
+            let mut best_txs =
+                self.payment_lane
+                    .apply(self.pool.best_transactions_with_attributes(
+                        BestTransactionsAttributes::base_fee(
+                            block_env.lane_basefee(true).min(block_env.basefee),
+                        ),
+                    ));
+            let mut appended = false;
+
+            while let Some(pool_tx) = best_txs.next() {
+                if !self.handle.is_current(parent_hash) {
+                    break 'prebuild;
+                }
+                if !seen.insert(*pool_tx.hash()) {
+                    continue;
+                }
+                if gas_used + pool_tx.gas_limit() > non_shared_gas_limit {
+                    break 'prebuild;
+                }
+
+                // Apply the same per-lane limits as the payload builder, so that the prebuilt
+                // transactions are included in the payload.
+                let is_payment = pool_tx.transaction.is_payment();
+                if !is_payment && non_payment_gas_used + pool_tx.gas_limit() > general_gas_limit {
+                    best_txs.mark_invalid(
+                        &pool_tx,
+                        &InvalidPoolTransactionError::Other(Box::new(
+                            TempoPoolTransactionError::ExceedsNonPaymentLimit,
+                        )),
+                    );
+                    continue;
+                }
+                if is_underpriced(&pool_tx.transaction, &block_env) {
+                    best_txs.mark_invalid(&pool_tx, &InvalidPoolTransactionError::Underpriced);
+                    continue;
+                }
+
+                match execution.execute(&pool_tx.transaction.clone_into_consensus(), &mut db) {
+                    Ok(result) => {
+                        gas_used += result.gas_used();
+                        if !is_payment {
+                            non_payment_gas_used += result.gas_used();
+                        }
+                        transactions.push(pool_tx);
+                        appended = true;
+                    }
+                    Err(error) => {
+                        trace!(%error, tx_hash = %pool_tx.hash(), "skipping invalid transaction in prebuild");
+                        best_txs.mark_invalid(
+                            &pool_tx,
+                            &InvalidPoolTransactionError::Consensus(
+                                InvalidTransactionError::TxTypeNotSupported,
+                            ),
+                        );
+                    }
+                }
+            }
+
+            if !appended {
+                self.handle
+                    .wait_while_current(parent_hash, POOL_POLL_INTERVAL);
+            }
+        }
+
+        // Keep the reads until the payload is requested.
+        let remaining = MAX_PREBUILD_DURATION.saturating_sub(started_at.elapsed());
+        self.handle.wait_while_current(parent_hash, remaining);
+
+        drop(db);
+
+        self.metrics
+            .prebuild_transactions
+            .record(transactions.len() as f64);
+        debug!(
+            %parent_hash,
+            executed = transactions.len(),
+            gas_used,
+            elapsed = ?started_at.elapsed(),
+            "finished prebuilding payload"
+        );
+
+        Ok(Prebuilt {
+            parent_hash,
+            transactions,
+            execution,
+            cached_reads,
+        })
+    }
+}
+
+/// A [`BestTransactions`] iterator yielding the transactions of a prebuild, in order, before the
+/// transactions of the wrapped iterator.
+///
+/// Transactions already yielded from the prebuild are skipped in the wrapped iterator. Once a
+/// prebuilt transaction is marked invalid, the following prebuilt transactions of its sender are
+/// skipped.
+pub(crate) struct PrebuiltTransactions<I> {
+    prebuilt: VecDeque<Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+    best: I,
+    /// Hashes of the yielded prebuilt transactions.
+    yielded: HashSet<TxHash>,
+    /// Senders of the prebuilt transactions marked invalid.
+    invalid: HashSet<Address>,
+}
+
+impl<I> PrebuiltTransactions<I> {
+    pub(crate) fn new(
+        prebuilt: Vec<Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+        best: I,
+    ) -> Self {
+        Self {
+            prebuilt: prebuilt.into(),
+            best,
+            yielded: HashSet::default(),
+            invalid: HashSet::default(),
+        }
+    }
+}
+
+impl<I> Iterator for PrebuiltTransactions<I>
+where
+    I: Iterator<Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+{
+    type Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(tx) = self.prebuilt.pop_front() {
+            if self.invalid.contains(&tx.sender()) {
+                continue;
+            }
+            self.yielded.insert(*tx.hash());
+            return Some(tx);
+        }
+
+        self.best.find(|tx| !self.yielded.contains(tx.hash()))
+    }
+}
+
+impl<I> BestTransactions for PrebuiltTransactions<I>
+where
+    I: BestTransactions<Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+{
+    fn mark_invalid(&mut self, transaction: &Self::Item, kind: &InvalidPoolTransactionError) {
+        self.invalid.insert(transaction.sender());
+        self.best.mark_invalid(transaction, kind);
+    }
+
+    fn no_updates(&mut self) {
+        self.best.no_updates();
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.best.set_skip_blobs(skip_blobs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{Signed, TxLegacy};
+    use alloy_primitives::{Bytes, Signature, TxKind};
+    use reth_evm::EvmEnv;
+    use reth_primitives_traits::Recovered;
+    use reth_transaction_pool::{TransactionOrigin, identifier::TransactionId};
+    use tempo_primitives::TempoTxEnvelope;
+
+    fn pooled_tx(sender: Address, nonce: u64) -> Arc<ValidPoolTransaction<TempoPooledTransaction>> {
+        let tx = TxLegacy {
+            nonce,
+            gas_price: 1_000_000_000,
+            gas_limit: 100_000,
+            to: TxKind::Call(Address::repeat_byte(0x42)),
+            // Distinguishes the hashes of transactions of different senders.
+            input: Bytes::copy_from_slice(sender.as_slice()),
+            ..Default::default()
+        };
+        let envelope =
+            TempoTxEnvelope::Legacy(Signed::new_unhashed(tx, Signature::test_signature()));
+
+        Arc::new(ValidPoolTransaction {
+            transaction: TempoPooledTransaction::new(Recovered::new_unchecked(envelope, sender)),
+            transaction_id: TransactionId::new(0u64.into(), nonce),
+            propagate: true,
+            timestamp: Instant::now(),
+            origin: TransactionOrigin::External,
+            authority_ids: None,
+        })
+    }
+
+    /// Best transactions iterator recording the transactions marked invalid.
+    struct MockBest {
+        txs: std::vec::IntoIter<Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+        invalid: Vec<TxHash>,
+    }
+
+    impl Iterator for MockBest {
+        type Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.txs.next()
+        }
+    }
+
+    impl BestTransactions for MockBest {
+        fn mark_invalid(&mut self, transaction: &Self::Item, _kind: &InvalidPoolTransactionError) {
+            self.invalid.push(*transaction.hash());
+        }
+
+        fn no_updates(&mut self) {}
+
+        fn set_skip_blobs(&mut self, _skip_blobs: bool) {}
+    }
+
+    fn prebuilt(parent_hash: B256) -> Prebuilt {
+        Prebuilt {
+            parent_hash,
+            transactions: Vec::new(),
+            execution: PrebuiltExecution::new(EvmEnv::default()),
+            cached_reads: CachedReads::default(),
+        }
+    }
+
+    #[test]
+    fn take_stops_prebuild_and_returns_its_reads() {
+        let handle = PrebuildHandle::default();
+        let parent = B256::repeat_byte(1);
+        assert!(handle.take(parent).is_none());
+
+        handle.prebuild(parent, Address::ZERO);
+        assert!(handle.is_current(parent));
+        assert!(handle.timestamp_millis(parent).is_some());
+        assert!(handle.timestamp_millis(B256::repeat_byte(2)).is_none());
+        handle.finish(prebuilt(parent));
+
+        assert!(handle.take(B256::repeat_byte(2)).is_none());
+        assert_eq!(handle.take(parent).unwrap().parent_hash, parent);
+        assert!(!handle.is_current(parent));
+        assert!(handle.take(parent).is_none());
+    }
+
+    #[test]
+    fn prebuild_supersedes_previous_parent() {
+        let handle = PrebuildHandle::default();
+        let (first, second) = (B256::repeat_byte(1), B256::repeat_byte(2));
+
+        handle.prebuild(first, Address::ZERO);
+        assert_eq!(handle.wait_for_target(None).unwrap().parent_hash, first);
+
+        handle.prebuild(second, Address::ZERO);
+        assert!(!handle.is_current(first));
+        assert_eq!(
+            handle.wait_for_target(Some(first)).unwrap().parent_hash,
+            second
+        );
+    }
+
+    #[test]
+    fn shutdown_stops_prebuilder() {
+        let handle = PrebuildHandle::default();
+        let parent = B256::repeat_byte(1);
+        handle.prebuild(parent, Address::ZERO);
+
+        let waiter = {
+            let handle = handle.clone();
+            std::thread::spawn(move || handle.wait_for_target(Some(parent)))
+        };
+        handle.shutdown();
+
+        assert!(waiter.join().unwrap().is_none());
+        assert!(!handle.is_current(parent));
+        handle.prebuild(B256::repeat_byte(2), Address::ZERO);
+        assert!(handle.wait_for_target(None).is_none());
+    }
+
+    #[test]
+    fn prebuilt_transactions_come_first() {
+        let (alice, bob, carol) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+        let prebuilt = vec![pooled_tx(alice, 0), pooled_tx(bob, 0), pooled_tx(bob, 1)];
+        let best = MockBest {
+            txs: vec![
+                pooled_tx(carol, 0),
+                prebuilt[1].clone(),
+                prebuilt[0].clone(),
+                pooled_tx(alice, 1),
+            ]
+            .into_iter(),
+            invalid: Vec::new(),
+        };
+        let mut txs = PrebuiltTransactions::new(prebuilt, best);
+
+        assert_eq!(txs.next().unwrap().sender(), alice);
+        // Bob's remaining prebuilt transactions are skipped once one is invalid.
+        let invalid = txs.next().unwrap();
+        assert_eq!((invalid.sender(), invalid.nonce()), (bob, 0));
+        txs.mark_invalid(&invalid, &InvalidPoolTransactionError::Underpriced);
+        assert_eq!(txs.best.invalid, vec![*invalid.hash()]);
+
+        // Prebuilt transactions are not yielded twice.
+        assert_eq!(
+            txs.map(|tx| (tx.sender(), tx.nonce())).collect::<Vec<_>>(),
+            vec![(carol, 0), (alice, 1)]
+        );
+    }
+}
@@ -3,12 +3,13 @@ use alloy_rpc_types_engine::PayloadId;
 use alloy_rpc_types_eth::Withdrawals;
 use reth_ethereum_engine_primitives::{EthPayloadAttributes, EthPayloadBuilderAttributes};
 use reth_node_api::{PayloadAttributes, PayloadBuilderAttributes};
+use reth_primitives_traits::Recovered;
 use serde::{Deserialize, Serialize};
 use std::{
     convert::Infallible,
     sync::{Arc, atomic, atomic::Ordering},
 };
-use tempo_primitives::RecoveredSubBlock;
+use tempo_primitives::{RecoveredSubBlock, TempoTxEnvelope};
 
 /// A handle for a payload interrupt flag.
 ///
@@ -28,6 +29,28 @@ impl InterruptHandle {
     }
 }
 
+/// Decryption keys of the encrypted transactions in the parent block, and the transactions they
+/// decrypt to.
+#[derive(Debug, Clone, Default)]
+pub struct DecryptedTransactions {
+    /// One key per encrypted transaction in the parent block, in block order.
+    pub keys: Vec<Bytes>,
+    /// The transactions that were successfully decrypted, in the order of their ciphertexts.
+    pub transactions: Vec<Recovered<TempoTxEnvelope>>,
+}
+
+/// Why the payload builder left out a subblock it was given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubblockDropReason {
+    /// One of the subblock's transactions expired before the timestamp of the block.
+    Expired,
+    /// A subblock at this height or above was found invalid, so no subblocks are included.
+    AfterInvalidSubblock,
+}
+
+/// Callback invoked for each subblock the payload builder leaves out.
+type DroppedSubblockReporter = dyn Fn(&RecoveredSubBlock, SubblockDropReason) + Send + Sync;
+
 /// Container type for all components required to build a payload.
 ///
 /// The `TempoPayloadBuilderAttributes` has an additional feature of interrupting payload.
@@ -45,6 +68,11 @@ pub struct TempoPayloadBuilderAttributes {
     extra_data: Bytes,
     #[debug(skip)]
     subblocks: Arc<dyn Fn() -> Vec<RecoveredSubBlock> + Send + Sync + 'static>,
+    /// The decrypted transactions of the parent block to include at the start of the block.
+    decrypted_transactions: Option<Arc<DecryptedTransactions>>,
+    /// Called for each subblock the payload builder leaves out.
+    #[debug(skip)]
+    on_dropped_subblock: Option<Arc<DroppedSubblockReporter>>,
 }
 
 impl TempoPayloadBuilderAttributes {
@@ -72,9 +100,26 @@ impl TempoPayloadBuilderAttributes {
             timestamp_millis_part: millis,
             extra_data,
             subblocks: Arc::new(subblocks),
+            decrypted_transactions: None,
+            on_dropped_subblock: None,
         }
     }
 
+    /// Sets the decrypted transactions of the parent block to include at the start of the block.
+    pub fn with_decrypted_transactions(mut self, decrypted: DecryptedTransactions) -> Self {
+        self.decrypted_transactions = Some(Arc::new(decrypted));
+        self
+    }
+
+    /// Sets the callback invoked for each subblock the payload builder leaves out.
+    pub fn with_dropped_subblock_reporter(
+        mut self,
+        reporter: impl Fn(&RecoveredSubBlock, SubblockDropReason) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_dropped_subblock = Some(Arc::new(reporter));
+        self
+    }
+
     /// Returns the extra data to be included in the block header.
     pub fn extra_data(&self) -> &Bytes {
         &self.extra_data
@@ -108,6 +153,22 @@ impl TempoPayloadBuilderAttributes {
     pub fn subblocks(&self) -> Vec<RecoveredSubBlock> {
         (self.subblocks)()
     }
+
+    /// Returns the decrypted transactions of the parent block, if any.
+    pub fn decrypted_transactions(&self) -> Option<&DecryptedTransactions> {
+        self.decrypted_transactions.as_deref()
+    }
+
+    /// Reports that `subblock` was left out of the payload for `reason`.
+    pub fn report_dropped_subblock(
+        &self,
+        subblock: &RecoveredSubBlock,
+        reason: SubblockDropReason,
+    ) {
+        if let Some(reporter) = &self.on_dropped_subblock {
+            reporter(subblock, reason);
+        }
+    }
 }
 
 // Required by reth's e2e-test-utils for integration tests.
@@ -121,6 +182,8 @@ impl From<EthPayloadBuilderAttributes> for TempoPayloadBuilderAttributes {
             timestamp_millis_part: 0,
             extra_data: Bytes::default(),
             subblocks: Arc::new(Vec::new),
+            decrypted_transactions: None,
+            on_dropped_subblock: None,
         }
     }
 }
@@ -147,6 +210,8 @@ impl PayloadBuilderAttributes for TempoPayloadBuilderAttributes {
             timestamp_millis_part,
             extra_data: Bytes::default(),
             subblocks: Arc::new(Vec::new),
+            decrypted_transactions: None,
+            on_dropped_subblock: None,
         })
     }
 
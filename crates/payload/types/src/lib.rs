@@ -6,15 +6,18 @@
 mod attrs;
 
 use alloy_primitives::B256;
-pub use attrs::{InterruptHandle, TempoPayloadAttributes, TempoPayloadBuilderAttributes};
+pub use attrs::{
+    DecryptedTransactions, InterruptHandle, SubblockDropReason, TempoPayloadAttributes,
+    TempoPayloadBuilderAttributes,
+};
 use std::sync::Arc;
 
 use alloy_rpc_types_eth::Withdrawal;
 use reth_ethereum_engine_primitives::EthBuiltPayload;
 use reth_node_api::{ExecutionPayload, PayloadBuilderAttributes, PayloadTypes};
-use reth_primitives_traits::{AlloyBlockHeader as _, SealedBlock};
+use reth_primitives_traits::{AlloyBlockHeader as _, Recovered, SealedBlock};
 use serde::{Deserialize, Serialize};
-use tempo_primitives::{Block, TempoPrimitives};
+use tempo_primitives::{Block, TempoPrimitives, TempoTxEnvelope};
 
 /// Payload types for Tempo node.
 #[derive(Debug, Clone, Copy, Default)]
@@ -28,6 +31,11 @@ pub struct TempoExecutionData {
     pub block: Arc<SealedBlock<Block>>,
     /// Validator set active at the time this block was built.
     pub validator_set: Option<Vec<B256>>,
+    /// Transactions decrypted from the encrypted transactions of the parent block, that the
+    /// block must include.
+    ///
+    /// Only set for un-finalized blocks whose parent has encrypted transactions.
+    pub decrypted_transactions: Option<Vec<Recovered<TempoTxEnvelope>>>,
 }
 
 impl ExecutionPayload for TempoExecutionData {
@@ -79,6 +87,7 @@ impl PayloadTypes for TempoPayloadTypes {
         TempoExecutionData {
             block: Arc::new(block),
             validator_set: None,
+            decrypted_transactions: None,
         }
     }
 }
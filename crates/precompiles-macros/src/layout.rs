@@ -212,11 +212,17 @@ pub(crate) fn gen_contract_storage_impl(name: &Ident) -> proc_macro2::TokenStrea
 
 /// Generate the `slots` module with constants and collision checks
 ///
-/// Returns the slots module containing only constants and collision detection functions
+/// Returns the slots module containing the slot constants, the field name table and collision
+/// detection functions
 pub(crate) fn gen_slots_module(allocated_fields: &[LayoutField<'_>]) -> proc_macro2::TokenStream {
     // Generate constants and collision check functions
     let constants = packing::gen_constants_from_ir(allocated_fields, false);
     let collision_checks = gen_collision_checks(allocated_fields);
+    let fields = allocated_fields.iter().map(|field| {
+        let name = field.name.to_string();
+        let slot = PackingConstants::new(field.name).slot();
+        quote! { (#name, #slot) }
+    });
 
     quote! {
         pub mod slots {
@@ -224,6 +230,9 @@ pub(crate) fn gen_slots_module(allocated_fields: &[LayoutField<'_>]) -> proc_mac
 
             #constants
             #collision_checks
+
+            /// Field names with their base slots, used to label storage accesses in traces.
+            pub const FIELDS: &[(&str, ::alloy::primitives::U256)] = &[#(#fields),*];
         }
     }
 }
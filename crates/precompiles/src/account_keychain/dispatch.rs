@@ -58,6 +58,163 @@ impl Precompile for AccountKeychain {
                 })
             }
 
+            IAccountKeychain::setMultisigCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                mutate_void::<IAccountKeychain::setMultisigCall>(
+                    calldata,
+                    msg_sender,
+                    |sender, call| self.set_multisig(sender, call),
+                )
+            }
+
+            IAccountKeychain::removeMultisigCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                mutate_void::<IAccountKeychain::removeMultisigCall>(
+                    calldata,
+                    msg_sender,
+                    |sender, call| self.remove_multisig(sender, call),
+                )
+            }
+
+            IAccountKeychain::getMultisigCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                view::<IAccountKeychain::getMultisigCall>(calldata, |call| self.get_multisig(call))
+            }
+
+            IAccountKeychain::setGuardiansCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                mutate_void::<IAccountKeychain::setGuardiansCall>(
+                    calldata,
+                    msg_sender,
+                    |sender, call| self.set_guardians(sender, call),
+                )
+            }
+
+            IAccountKeychain::removeGuardiansCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                mutate_void::<IAccountKeychain::removeGuardiansCall>(
+                    calldata,
+                    msg_sender,
+                    |sender, call| self.remove_guardians(sender, call),
+                )
+            }
+
+            IAccountKeychain::proposeRecoveryCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                mutate_void::<IAccountKeychain::proposeRecoveryCall>(
+                    calldata,
+                    msg_sender,
+                    |sender, call| self.propose_recovery(sender, call),
+                )
+            }
+
+            IAccountKeychain::cancelRecoveryCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                mutate_void::<IAccountKeychain::cancelRecoveryCall>(
+                    calldata,
+                    msg_sender,
+                    |sender, call| self.cancel_recovery(sender, call),
+                )
+            }
+
+            IAccountKeychain::rejectRecoveryCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                mutate_void::<IAccountKeychain::rejectRecoveryCall>(
+                    calldata,
+                    msg_sender,
+                    |sender, call| self.reject_recovery(sender, call),
+                )
+            }
+
+            IAccountKeychain::executeRecoveryCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                mutate_void::<IAccountKeychain::executeRecoveryCall>(
+                    calldata,
+                    msg_sender,
+                    |sender, call| self.execute_recovery(sender, call),
+                )
+            }
+
+            IAccountKeychain::getGuardiansCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                view::<IAccountKeychain::getGuardiansCall>(calldata, |call| {
+                    self.get_guardians(call)
+                })
+            }
+
+            IAccountKeychain::getPendingRecoveryCall::SELECTOR => {
+                if !self.storage.spec().is_allegro_moderato() {
+                    return unknown_selector(
+                        selector,
+                        self.storage.gas_used(),
+                        self.storage.spec(),
+                    );
+                }
+                view::<IAccountKeychain::getPendingRecoveryCall>(calldata, |call| {
+                    self.get_pending_recovery(call)
+                })
+            }
+
             _ => unknown_selector(selector, self.storage.gas_used(), self.storage.spec()),
         };
 
@@ -4,8 +4,11 @@ use tempo_contracts::precompiles::{AccountKeychainError, AccountKeychainEvent};
 pub use tempo_contracts::precompiles::{
     IAccountKeychain,
     IAccountKeychain::{
-        KeyInfo, SignatureType, TokenLimit, authorizeKeyCall, getKeyCall, getRemainingLimitCall,
-        getTransactionKeyCall, revokeKeyCall, updateSpendingLimitCall,
+        GuardianConfig, KeyInfo, MultisigConfig, PendingRecovery, SignatureType, TokenLimit,
+        authorizeKeyCall, cancelRecoveryCall, executeRecoveryCall, getGuardiansCall, getKeyCall,
+        getMultisigCall, getPendingRecoveryCall, getRemainingLimitCall, getTransactionKeyCall,
+        proposeRecoveryCall, rejectRecoveryCall, removeGuardiansCall, removeMultisigCall,
+        revokeKeyCall, setGuardiansCall, setMultisigCall, updateSpendingLimitCall,
     },
 };
 
@@ -17,6 +20,25 @@ use crate::{
 use alloy::primitives::{Address, B256, U256};
 use tempo_precompiles_macros::{Storable, contract};
 
+/// Maximum number of co-signers in a multisig config.
+///
+/// Matches the maximum number of signatures a multisig transaction signature can carry.
+pub const MAX_MULTISIG_SIGNERS: usize = 16;
+
+/// Maximum number of guardians that can be registered for an account.
+pub const MAX_GUARDIANS: usize = 16;
+
+/// Delay in seconds between a recovery reaching the guardian threshold and becoming executable.
+///
+/// This gives the account time to veto a malicious recovery with any of its remaining keys.
+pub const RECOVERY_TIMELOCK: u64 = 2 * 24 * 60 * 60;
+
+/// Time in seconds during which a pending recovery that has not reached the guardian threshold
+/// cannot be replaced by a different proposal, unless a threshold of guardians rejects it.
+///
+/// Without it, any single guardian could keep resetting the approvals collected by the others.
+pub const RECOVERY_PROPOSAL_LOCK: u64 = 2 * 24 * 60 * 60;
+
 /// Key information stored in the precompile
 ///
 /// Storage layout (packed into single slot, right-aligned):
@@ -26,7 +48,7 @@ use tempo_precompiles_macros::{Storable, contract};
 /// - byte 10: is_revoked (bool)
 #[derive(Debug, Clone, Default, PartialEq, Eq, Storable)]
 pub struct AuthorizedKey {
-    /// Signature type: 0 = secp256k1, 1 = P256, 2 = WebAuthn
+    /// Signature type: 0 = secp256k1, 1 = P256, 2 = WebAuthn, 3 = Ed25519
     pub signature_type: u8,
     /// Block timestamp when key expires
     pub expiry: u64,
@@ -52,6 +74,27 @@ impl AuthorizedKey {
     }
 }
 
+/// Guardian recovery proposal stored in the precompile
+///
+/// At most one recovery is pending per account. A zero `key_id` means no recovery is pending.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Storable)]
+pub struct RecoveryProposal {
+    /// Key to authorize once the recovery is executed
+    pub key_id: Address,
+    /// Signature type: 0 = secp256k1, 1 = P256, 2 = WebAuthn, 3 = Ed25519
+    pub signature_type: u8,
+    /// Block timestamp when the recovered key expires
+    pub expiry: u64,
+    /// Number of guardians that approved this recovery
+    pub approvals: u8,
+    /// Number of guardians that rejected this recovery
+    pub rejections: u8,
+    /// Block timestamp from which the recovery can be executed (0 = threshold not reached)
+    pub executable_at: u64,
+    /// Block timestamp at which the recovery was first proposed
+    pub proposed_at: u64,
+}
+
 /// Account Keychain contract for managing authorized keys
 #[contract(addr = ACCOUNT_KEYCHAIN_ADDRESS)]
 pub struct AccountKeychain {
@@ -60,6 +103,26 @@ pub struct AccountKeychain {
     // spendingLimits[(account, keyId)][token] -> amount
     // Using a hash of account and keyId as the key to avoid triple nesting
     spending_limits: Mapping<B256, Mapping<Address, U256>>,
+    // multisigThresholds[account] -> threshold (0 = no multisig config)
+    multisig_thresholds: Mapping<Address, u8>,
+    // multisigSigners[account] -> registered co-signers
+    multisig_signers: Mapping<Address, Vec<Address>>,
+    // isMultisigSigner[account][signer] -> whether signer is a registered co-signer
+    is_multisig_signer: Mapping<Address, Mapping<Address, bool>>,
+    // guardianThresholds[account] -> threshold (0 = no guardians)
+    guardian_thresholds: Mapping<Address, u8>,
+    // guardians[account] -> registered guardians
+    guardians: Mapping<Address, Vec<Address>>,
+    // isGuardian[account][guardian] -> whether guardian is registered for the account
+    is_guardian: Mapping<Address, Mapping<Address, bool>>,
+    // pendingRecoveries[account] -> RecoveryProposal
+    pending_recoveries: Mapping<Address, RecoveryProposal>,
+    // recoveryNonces[account] -> incremented whenever a recovery proposal is replaced or cleared
+    recovery_nonces: Mapping<Address, u64>,
+    // recoveryApprovals[(account, nonce)][guardian] -> whether guardian approved the proposal
+    recovery_approvals: Mapping<B256, Mapping<Address, bool>>,
+    // recoveryRejections[(account, nonce)][guardian] -> whether guardian rejected the proposal
+    recovery_rejections: Mapping<B256, Mapping<Address, bool>>,
 
     // WARNING(rusowsky): transient storage slots must always be placed at the very end until the `contract`
     // macro is refactored and has 2 independent layouts (persistent and transient).
@@ -77,6 +140,15 @@ impl AccountKeychain {
         keccak256(data)
     }
 
+    /// Create a hash key for recovery approvals mapping from account and recovery nonce
+    fn recovery_approval_key(account: Address, nonce: u64) -> B256 {
+        use alloy::primitives::keccak256;
+        let mut data = [0u8; 28];
+        data[..20].copy_from_slice(account.as_slice());
+        data[20..].copy_from_slice(&nonce.to_be_bytes());
+        keccak256(data)
+    }
+
     /// Initializes the account keychain contract.
     pub fn initialize(&mut self) -> Result<()> {
         self.__initialize()
@@ -114,6 +186,7 @@ impl AccountKeychain {
             SignatureType::Secp256k1 => 0,
             SignatureType::P256 => 1,
             SignatureType::WebAuthn => 2,
+            SignatureType::Ed25519 if self.storage.spec().is_allegro_moderato() => 3,
             _ => return Err(AccountKeychainError::invalid_signature_type().into()),
         };
 
@@ -235,6 +308,548 @@ impl AccountKeychain {
         ))
     }
 
+    /// Register or replace the threshold multisig config for an account
+    ///
+    /// This can only be called by the account itself (using main key or its multisig).
+    /// Any previously registered co-signers are removed.
+    pub fn set_multisig(&mut self, msg_sender: Address, call: setMultisigCall) -> Result<()> {
+        let transaction_key = self.transaction_key.t_read()?;
+
+        if transaction_key != Address::ZERO {
+            return Err(AccountKeychainError::unauthorized_caller().into());
+        }
+
+        // Validate inputs
+        if call.signers.is_empty() || call.signers.len() > MAX_MULTISIG_SIGNERS {
+            return Err(AccountKeychainError::invalid_multisig_signer().into());
+        }
+
+        if call.threshold == 0 || call.threshold as usize > call.signers.len() {
+            return Err(AccountKeychainError::invalid_multisig_threshold().into());
+        }
+
+        for (i, signer) in call.signers.iter().enumerate() {
+            if signer.is_zero() || call.signers[..i].contains(signer) {
+                return Err(AccountKeychainError::invalid_multisig_signer().into());
+            }
+        }
+
+        // Clear the previous co-signers before registering the new ones
+        self.clear_multisig(msg_sender)?;
+
+        for signer in &call.signers {
+            self.is_multisig_signer
+                .at(msg_sender)
+                .at(*signer)
+                .write(true)?;
+        }
+        self.multisig_signers
+            .at(msg_sender)
+            .write(call.signers.clone())?;
+        self.multisig_thresholds
+            .at(msg_sender)
+            .write(call.threshold)?;
+
+        // Emit event
+        self.emit_event(AccountKeychainEvent::MultisigConfigured(
+            IAccountKeychain::MultisigConfigured {
+                account: msg_sender,
+                threshold: call.threshold,
+                signers: call.signers,
+            },
+        ))
+    }
+
+    /// Remove the threshold multisig config for an account
+    pub fn remove_multisig(
+        &mut self,
+        msg_sender: Address,
+        _call: removeMultisigCall,
+    ) -> Result<()> {
+        let transaction_key = self.transaction_key.t_read()?;
+
+        if transaction_key != Address::ZERO {
+            return Err(AccountKeychainError::unauthorized_caller().into());
+        }
+
+        if self.multisig_thresholds.at(msg_sender).read()? == 0 {
+            return Err(AccountKeychainError::multisig_not_configured().into());
+        }
+
+        self.clear_multisig(msg_sender)?;
+
+        // Emit event
+        self.emit_event(AccountKeychainEvent::MultisigRemoved(
+            IAccountKeychain::MultisigRemoved {
+                account: msg_sender,
+            },
+        ))
+    }
+
+    /// Get the threshold multisig config of an account
+    pub fn get_multisig(&self, call: getMultisigCall) -> Result<MultisigConfig> {
+        Ok(MultisigConfig {
+            threshold: self.multisig_thresholds.at(call.account).read()?,
+            signers: self.multisig_signers.at(call.account).read()?,
+        })
+    }
+
+    /// Validate that a set of recovered co-signers satisfies the account's multisig config
+    ///
+    /// Every co-signer must be registered for the account and their number must reach the
+    /// configured threshold. Duplicate co-signers are rejected when recovering the signature.
+    pub fn validate_multisig(&self, account: Address, signers: &[Address]) -> Result<()> {
+        let threshold = self.multisig_thresholds.at(account).read()?;
+        if threshold == 0 {
+            return Err(AccountKeychainError::multisig_not_configured().into());
+        }
+
+        for signer in signers {
+            if !self.is_multisig_signer.at(account).at(*signer).read()? {
+                return Err(AccountKeychainError::invalid_multisig_signer().into());
+            }
+        }
+
+        // Signature decoding caps the number of co-signers well below `u8::MAX`
+        let approvals = signers.len().min(u8::MAX as usize) as u8;
+        if approvals < threshold {
+            return Err(
+                AccountKeychainError::multisig_threshold_not_met(threshold, approvals).into(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Internal: Remove all co-signers and the threshold of an account's multisig config
+    fn clear_multisig(&mut self, account: Address) -> Result<()> {
+        for signer in self.multisig_signers.at(account).read()? {
+            self.is_multisig_signer.at(account).at(signer).delete()?;
+        }
+        self.multisig_signers.at(account).delete()?;
+        self.multisig_thresholds.at(account).delete()
+    }
+
+    /// Register or replace the guardians of an account
+    ///
+    /// This can only be called by the account itself (using main key or its multisig).
+    /// Any previously registered guardians are removed and a pending recovery is cancelled.
+    pub fn set_guardians(&mut self, msg_sender: Address, call: setGuardiansCall) -> Result<()> {
+        let transaction_key = self.transaction_key.t_read()?;
+
+        if transaction_key != Address::ZERO {
+            return Err(AccountKeychainError::unauthorized_caller().into());
+        }
+
+        // Validate inputs
+        if call.guardians.is_empty() || call.guardians.len() > MAX_GUARDIANS {
+            return Err(AccountKeychainError::invalid_guardian().into());
+        }
+
+        if call.threshold == 0 || call.threshold as usize > call.guardians.len() {
+            return Err(AccountKeychainError::invalid_guardian_threshold().into());
+        }
+
+        for (i, guardian) in call.guardians.iter().enumerate() {
+            if guardian.is_zero()
+                || *guardian == msg_sender
+                || call.guardians[..i].contains(guardian)
+            {
+                return Err(AccountKeychainError::invalid_guardian().into());
+            }
+        }
+
+        // Clear the previous guardians and their pending recovery before registering the new ones
+        self.clear_guardians(msg_sender)?;
+
+        for guardian in &call.guardians {
+            self.is_guardian.at(msg_sender).at(*guardian).write(true)?;
+        }
+        self.guardians
+            .at(msg_sender)
+            .write(call.guardians.clone())?;
+        self.guardian_thresholds
+            .at(msg_sender)
+            .write(call.threshold)?;
+
+        // Emit event
+        self.emit_event(AccountKeychainEvent::GuardiansConfigured(
+            IAccountKeychain::GuardiansConfigured {
+                account: msg_sender,
+                threshold: call.threshold,
+                guardians: call.guardians,
+            },
+        ))
+    }
+
+    /// Remove the guardians of an account, cancelling any pending recovery
+    pub fn remove_guardians(
+        &mut self,
+        msg_sender: Address,
+        _call: removeGuardiansCall,
+    ) -> Result<()> {
+        let transaction_key = self.transaction_key.t_read()?;
+
+        if transaction_key != Address::ZERO {
+            return Err(AccountKeychainError::unauthorized_caller().into());
+        }
+
+        if self.guardian_thresholds.at(msg_sender).read()? == 0 {
+            return Err(AccountKeychainError::guardians_not_configured().into());
+        }
+
+        self.clear_guardians(msg_sender)?;
+
+        // Emit event
+        self.emit_event(AccountKeychainEvent::GuardiansRemoved(
+            IAccountKeychain::GuardiansRemoved {
+                account: msg_sender,
+            },
+        ))
+    }
+
+    /// Get the guardian config of an account
+    pub fn get_guardians(&self, call: getGuardiansCall) -> Result<GuardianConfig> {
+        Ok(GuardianConfig {
+            threshold: self.guardian_thresholds.at(call.account).read()?,
+            guardians: self.guardians.at(call.account).read()?,
+        })
+    }
+
+    /// Propose or approve a recovery key for an account
+    ///
+    /// Can only be called by a guardian of the account. Proposing a key that differs from the
+    /// pending recovery is rejected until the pending recovery has been open for
+    /// [`RECOVERY_PROPOSAL_LOCK`] without reaching the threshold, or its key has expired. Only
+    /// then it is replaced and its approvals are reset. A threshold of guardians can cancel it
+    /// earlier with [`Self::reject_recovery`]. Once the threshold is reached, the recovery
+    /// becomes executable after [`RECOVERY_TIMELOCK`] and can no longer be replaced.
+    pub fn propose_recovery(
+        &mut self,
+        msg_sender: Address,
+        call: proposeRecoveryCall,
+    ) -> Result<()> {
+        let threshold = self.guardian_thresholds.at(call.account).read()?;
+        if threshold == 0 {
+            return Err(AccountKeychainError::guardians_not_configured().into());
+        }
+
+        if !self.is_guardian.at(call.account).at(msg_sender).read()? {
+            return Err(AccountKeychainError::unauthorized_caller().into());
+        }
+
+        // Validate the proposed key
+        if call.keyId == Address::ZERO {
+            return Err(AccountKeychainError::zero_public_key().into());
+        }
+
+        let signature_type = match call.signatureType {
+            SignatureType::Secp256k1 => 0,
+            SignatureType::P256 => 1,
+            SignatureType::WebAuthn => 2,
+            SignatureType::Ed25519 if self.storage.spec().is_allegro_moderato() => 3,
+            _ => return Err(AccountKeychainError::invalid_signature_type().into()),
+        };
+
+        let current_timestamp = self.storage.timestamp().saturating_to::<u64>();
+        if call.expiry <= current_timestamp {
+            return Err(AccountKeychainError::expiry_in_past().into());
+        }
+
+        self.ensure_key_unused(call.account, call.keyId)?;
+
+        let mut proposal = self.pending_recoveries.at(call.account).read()?;
+        let is_same_proposal = proposal.key_id == call.keyId
+            && proposal.signature_type == signature_type
+            && proposal.expiry == call.expiry;
+
+        if !is_same_proposal {
+            // A scheduled recovery can only be cancelled by the account
+            if proposal.executable_at != 0 {
+                return Err(AccountKeychainError::recovery_already_scheduled().into());
+            }
+
+            if proposal.key_id != Address::ZERO {
+                // Approvals cannot be reset by a single guardian while the recovery is still open
+                let replaceable_at = proposal.proposed_at.saturating_add(RECOVERY_PROPOSAL_LOCK);
+                if current_timestamp < replaceable_at && current_timestamp < proposal.expiry {
+                    return Err(
+                        AccountKeychainError::recovery_already_pending(replaceable_at).into(),
+                    );
+                }
+
+                // Replace the stale proposal, invalidating its approvals
+                self.bump_recovery_nonce(call.account)?;
+            }
+            proposal = RecoveryProposal {
+                key_id: call.keyId,
+                signature_type,
+                expiry: call.expiry,
+                proposed_at: current_timestamp,
+                ..Default::default()
+            };
+        }
+
+        let nonce = self.recovery_nonces.at(call.account).read()?;
+        let approval_key = Self::recovery_approval_key(call.account, nonce);
+        if self
+            .recovery_approvals
+            .at(approval_key)
+            .at(msg_sender)
+            .read()?
+        {
+            return Err(AccountKeychainError::recovery_already_approved().into());
+        }
+        self.recovery_approvals
+            .at(approval_key)
+            .at(msg_sender)
+            .write(true)?;
+
+        proposal.approvals += 1;
+        let scheduled = proposal.executable_at == 0 && proposal.approvals >= threshold;
+        if scheduled {
+            proposal.executable_at = current_timestamp.saturating_add(RECOVERY_TIMELOCK);
+        }
+        let (approvals, executable_at) = (proposal.approvals, proposal.executable_at);
+        self.pending_recoveries.at(call.account).write(proposal)?;
+
+        // Emit events
+        self.emit_event(AccountKeychainEvent::RecoveryProposed(
+            IAccountKeychain::RecoveryProposed {
+                account: call.account,
+                guardian: msg_sender,
+                keyId: call.keyId,
+                approvals,
+            },
+        ))?;
+
+        if scheduled {
+            self.emit_event(AccountKeychainEvent::RecoveryScheduled(
+                IAccountKeychain::RecoveryScheduled {
+                    account: call.account,
+                    keyId: call.keyId,
+                    executableAt: executable_at,
+                },
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Cancel the pending recovery of an account
+    ///
+    /// Unlike other admin operations, this can be called with any key of the account, so that a
+    /// user who still holds an access key can veto a recovery started by malicious guardians.
+    pub fn cancel_recovery(
+        &mut self,
+        msg_sender: Address,
+        _call: cancelRecoveryCall,
+    ) -> Result<()> {
+        let proposal = self.pending_recoveries.at(msg_sender).read()?;
+        if proposal.key_id == Address::ZERO {
+            return Err(AccountKeychainError::no_pending_recovery().into());
+        }
+
+        self.clear_pending_recovery(msg_sender)?;
+
+        // Emit event
+        self.emit_event(AccountKeychainEvent::RecoveryCancelled(
+            IAccountKeychain::RecoveryCancelled {
+                account: msg_sender,
+                keyId: proposal.key_id,
+            },
+        ))
+    }
+
+    /// Reject the pending recovery of an account
+    ///
+    /// Can only be called by a guardian of the account while the recovery has not reached the
+    /// threshold. Once a threshold of guardians rejected it, the recovery is cancelled, so that
+    /// a bogus proposal of a single guardian does not lock the others out for
+    /// [`RECOVERY_PROPOSAL_LOCK`].
+    pub fn reject_recovery(&mut self, msg_sender: Address, call: rejectRecoveryCall) -> Result<()> {
+        let threshold = self.guardian_thresholds.at(call.account).read()?;
+        if threshold == 0 {
+            return Err(AccountKeychainError::guardians_not_configured().into());
+        }
+
+        if !self.is_guardian.at(call.account).at(msg_sender).read()? {
+            return Err(AccountKeychainError::unauthorized_caller().into());
+        }
+
+        let mut proposal = self.pending_recoveries.at(call.account).read()?;
+        if proposal.key_id == Address::ZERO {
+            return Err(AccountKeychainError::no_pending_recovery().into());
+        }
+
+        // A scheduled recovery can only be cancelled by the account
+        if proposal.executable_at != 0 {
+            return Err(AccountKeychainError::recovery_already_scheduled().into());
+        }
+
+        let nonce = self.recovery_nonces.at(call.account).read()?;
+        let rejection_key = Self::recovery_approval_key(call.account, nonce);
+        if self
+            .recovery_rejections
+            .at(rejection_key)
+            .at(msg_sender)
+            .read()?
+        {
+            return Err(AccountKeychainError::recovery_already_rejected().into());
+        }
+        self.recovery_rejections
+            .at(rejection_key)
+            .at(msg_sender)
+            .write(true)?;
+
+        proposal.rejections += 1;
+        let (key_id, rejections) = (proposal.key_id, proposal.rejections);
+        let cancelled = rejections >= threshold;
+        if cancelled {
+            self.clear_pending_recovery(call.account)?;
+        } else {
+            self.pending_recoveries.at(call.account).write(proposal)?;
+        }
+
+        // Emit events
+        self.emit_event(AccountKeychainEvent::RecoveryRejected(
+            IAccountKeychain::RecoveryRejected {
+                account: call.account,
+                guardian: msg_sender,
+                keyId: key_id,
+                rejections,
+            },
+        ))?;
+
+        if cancelled {
+            self.emit_event(AccountKeychainEvent::RecoveryCancelled(
+                IAccountKeychain::RecoveryCancelled {
+                    account: call.account,
+                    keyId: key_id,
+                },
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Execute a recovery whose timelock has elapsed
+    ///
+    /// Can be called by anyone. The recovered key is authorized without spending limits.
+    pub fn execute_recovery(
+        &mut self,
+        _msg_sender: Address,
+        call: executeRecoveryCall,
+    ) -> Result<()> {
+        let proposal = self.pending_recoveries.at(call.account).read()?;
+        if proposal.key_id == Address::ZERO {
+            return Err(AccountKeychainError::no_pending_recovery().into());
+        }
+
+        let current_timestamp = self.storage.timestamp().saturating_to::<u64>();
+        if proposal.executable_at == 0 || current_timestamp < proposal.executable_at {
+            return Err(AccountKeychainError::recovery_not_ready(proposal.executable_at).into());
+        }
+
+        if current_timestamp >= proposal.expiry {
+            return Err(AccountKeychainError::key_expired().into());
+        }
+
+        // The key may have been authorized or revoked by the account since the proposal
+        self.ensure_key_unused(call.account, proposal.key_id)?;
+
+        self.clear_pending_recovery(call.account)?;
+
+        self.keys
+            .at(call.account)
+            .at(proposal.key_id)
+            .write(AuthorizedKey {
+                signature_type: proposal.signature_type,
+                expiry: proposal.expiry,
+                enforce_limits: false,
+                is_revoked: false,
+            })?;
+
+        // Emit events
+        self.emit_event(AccountKeychainEvent::KeyAuthorized(
+            IAccountKeychain::KeyAuthorized {
+                account: call.account,
+                publicKey: proposal.key_id.into_word(),
+                signatureType: proposal.signature_type,
+                expiry: proposal.expiry,
+            },
+        ))?;
+
+        self.emit_event(AccountKeychainEvent::RecoveryExecuted(
+            IAccountKeychain::RecoveryExecuted {
+                account: call.account,
+                keyId: proposal.key_id,
+            },
+        ))
+    }
+
+    /// Get the pending recovery of an account
+    pub fn get_pending_recovery(&self, call: getPendingRecoveryCall) -> Result<PendingRecovery> {
+        let proposal = self.pending_recoveries.at(call.account).read()?;
+
+        // Convert u8 signature_type to SignatureType enum
+        let signature_type = match proposal.signature_type {
+            1 => SignatureType::P256,
+            2 => SignatureType::WebAuthn,
+            3 => SignatureType::Ed25519,
+            _ => SignatureType::Secp256k1,
+        };
+
+        Ok(PendingRecovery {
+            keyId: proposal.key_id,
+            signatureType: signature_type,
+            expiry: proposal.expiry,
+            approvals: proposal.approvals,
+            executableAt: proposal.executable_at,
+        })
+    }
+
+    /// Internal: Ensure a key_id has never been authorized or revoked for an account
+    fn ensure_key_unused(&self, account: Address, key_id: Address) -> Result<()> {
+        let existing_key = self.keys.at(account).at(key_id).read()?;
+        if existing_key.expiry > 0 {
+            return Err(AccountKeychainError::key_already_exists().into());
+        }
+
+        if existing_key.is_revoked {
+            return Err(AccountKeychainError::key_already_revoked().into());
+        }
+
+        Ok(())
+    }
+
+    /// Internal: Invalidate all approvals of the current recovery proposal of an account
+    fn bump_recovery_nonce(&mut self, account: Address) -> Result<()> {
+        let nonce = self.recovery_nonces.at(account).read()?;
+        self.recovery_nonces.at(account).write(nonce + 1)
+    }
+
+    /// Internal: Remove the pending recovery of an account and invalidate its approvals
+    fn clear_pending_recovery(&mut self, account: Address) -> Result<()> {
+        self.bump_recovery_nonce(account)?;
+        self.pending_recoveries.at(account).delete()
+    }
+
+    /// Internal: Remove all guardians and the threshold of an account, and its pending recovery
+    fn clear_guardians(&mut self, account: Address) -> Result<()> {
+        for guardian in self.guardians.at(account).read()? {
+            self.is_guardian.at(account).at(guardian).delete()?;
+        }
+        self.guardians.at(account).delete()?;
+        self.guardian_thresholds.at(account).delete()?;
+
+        if self.pending_recoveries.at(account).read()?.key_id != Address::ZERO {
+            self.clear_pending_recovery(account)?;
+        }
+
+        Ok(())
+    }
+
     /// Get key information
     pub fn get_key(&self, call: getKeyCall) -> Result<KeyInfo> {
         let key = self.keys.at(call.account).at(call.keyId).read()?;
@@ -255,6 +870,7 @@ impl AccountKeychain {
             0 => SignatureType::Secp256k1,
             1 => SignatureType::P256,
             2 => SignatureType::WebAuthn,
+            3 => SignatureType::Ed25519,
             _ => SignatureType::Secp256k1, // Default fallback
         };
 
@@ -458,6 +1074,7 @@ mod tests {
         storage::{StorageCtx, hashmap::HashMapStorageProvider},
     };
     use alloy::primitives::{Address, U256};
+    use tempo_chainspec::hardfork::TempoHardfork;
     use tempo_contracts::precompiles::IAccountKeychain::SignatureType;
 
     // Helper function to assert unauthorized error
@@ -697,4 +1314,468 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_authorize_ed25519_key_requires_allegro_moderato() -> eyre::Result<()> {
+        let account = Address::random();
+        let key_id = Address::random();
+        let auth_call = authorizeKeyCall {
+            keyId: key_id,
+            signatureType: SignatureType::Ed25519,
+            expiry: u64::MAX,
+            enforceLimits: false,
+            limits: vec![],
+        };
+
+        // Rejected before Allegro-Moderato
+        let mut storage = HashMapStorageProvider::new(1).with_spec(TempoHardfork::Allegretto);
+        StorageCtx::enter(&mut storage, || {
+            let mut keychain = AccountKeychain::new();
+            keychain.initialize()?;
+            keychain.set_transaction_key(Address::ZERO)?;
+
+            match keychain
+                .authorize_key(account, auth_call.clone())
+                .unwrap_err()
+            {
+                TempoPrecompileError::AccountKeychainError(e) => assert!(
+                    matches!(e, AccountKeychainError::InvalidSignatureType(_)),
+                    "Expected InvalidSignatureType error, got: {e:?}"
+                ),
+                e => panic!("Expected AccountKeychainError, got: {e:?}"),
+            }
+
+            Ok::<_, eyre::Report>(())
+        })?;
+
+        // Accepted from Allegro-Moderato
+        let mut storage = HashMapStorageProvider::new(1).with_spec(TempoHardfork::AllegroModerato);
+        StorageCtx::enter(&mut storage, || {
+            let mut keychain = AccountKeychain::new();
+            keychain.initialize()?;
+            keychain.set_transaction_key(Address::ZERO)?;
+
+            keychain.authorize_key(account, auth_call)?;
+            let key_info = keychain.get_key(getKeyCall {
+                account,
+                keyId: key_id,
+            })?;
+            assert_eq!(key_info.signatureType, SignatureType::Ed25519);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_multisig_config_lifecycle() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        let account = Address::random();
+        let (signer_a, signer_b, signer_c) =
+            (Address::random(), Address::random(), Address::random());
+        StorageCtx::enter(&mut storage, || {
+            let mut keychain = AccountKeychain::new();
+            keychain.initialize()?;
+            keychain.set_transaction_key(Address::ZERO)?;
+
+            // No config registered yet
+            assert_eq!(
+                keychain
+                    .get_multisig(getMultisigCall { account })?
+                    .threshold,
+                0
+            );
+            assert!(keychain.validate_multisig(account, &[signer_a]).is_err());
+
+            // Register a 2-of-3 config
+            keychain.set_multisig(
+                account,
+                setMultisigCall {
+                    signers: vec![signer_a, signer_b, signer_c],
+                    threshold: 2,
+                },
+            )?;
+            let config = keychain.get_multisig(getMultisigCall { account })?;
+            assert_eq!(config.threshold, 2);
+            assert_eq!(config.signers, vec![signer_a, signer_b, signer_c]);
+
+            keychain.validate_multisig(account, &[signer_a, signer_c])?;
+            match keychain
+                .validate_multisig(account, &[signer_b])
+                .unwrap_err()
+            {
+                TempoPrecompileError::AccountKeychainError(e) => assert!(
+                    matches!(e, AccountKeychainError::MultisigThresholdNotMet(_)),
+                    "Expected MultisigThresholdNotMet error, got: {e:?}"
+                ),
+                e => panic!("Expected AccountKeychainError, got: {e:?}"),
+            }
+            assert!(
+                keychain
+                    .validate_multisig(account, &[signer_a, Address::random()])
+                    .is_err(),
+                "Unregistered co-signers should be rejected"
+            );
+
+            // Replacing the config removes previous co-signers
+            keychain.set_multisig(
+                account,
+                setMultisigCall {
+                    signers: vec![signer_b],
+                    threshold: 1,
+                },
+            )?;
+            keychain.validate_multisig(account, &[signer_b])?;
+            assert!(keychain.validate_multisig(account, &[signer_a]).is_err());
+
+            // Removing the config disables multisig signatures
+            keychain.remove_multisig(account, removeMultisigCall {})?;
+            let config = keychain.get_multisig(getMultisigCall { account })?;
+            assert_eq!(config.threshold, 0);
+            assert!(config.signers.is_empty());
+            assert!(keychain.validate_multisig(account, &[signer_b]).is_err());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_set_multisig_validation() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        let account = Address::random();
+        let signer = Address::random();
+        StorageCtx::enter(&mut storage, || {
+            let mut keychain = AccountKeychain::new();
+            keychain.initialize()?;
+            keychain.set_transaction_key(Address::ZERO)?;
+
+            let invalid_calls = [
+                // zero threshold
+                setMultisigCall {
+                    signers: vec![signer],
+                    threshold: 0,
+                },
+                // threshold above number of signers
+                setMultisigCall {
+                    signers: vec![signer],
+                    threshold: 2,
+                },
+                // duplicate signer
+                setMultisigCall {
+                    signers: vec![signer, signer],
+                    threshold: 1,
+                },
+                // zero signer
+                setMultisigCall {
+                    signers: vec![Address::ZERO],
+                    threshold: 1,
+                },
+                // too many signers
+                setMultisigCall {
+                    signers: (0..=MAX_MULTISIG_SIGNERS)
+                        .map(|_| Address::random())
+                        .collect(),
+                    threshold: 1,
+                },
+            ];
+            for call in invalid_calls {
+                assert!(keychain.set_multisig(account, call).is_err());
+            }
+
+            // Access keys cannot manage the multisig config
+            keychain.set_transaction_key(Address::random())?;
+            let result = keychain.set_multisig(
+                account,
+                setMultisigCall {
+                    signers: vec![signer],
+                    threshold: 1,
+                },
+            );
+            assert_unauthorized_error(result.unwrap_err());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_guardian_recovery_lifecycle() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        let account = Address::random();
+        let (guardian_a, guardian_b, guardian_c) =
+            (Address::random(), Address::random(), Address::random());
+        let recovery_key = Address::random();
+        StorageCtx::enter(&mut storage, || {
+            StorageCtx.set_timestamp(U256::from(1000));
+
+            let mut keychain = AccountKeychain::new();
+            keychain.initialize()?;
+            keychain.set_transaction_key(Address::ZERO)?;
+
+            keychain.set_guardians(
+                account,
+                setGuardiansCall {
+                    guardians: vec![guardian_a, guardian_b, guardian_c],
+                    threshold: 2,
+                },
+            )?;
+            let config = keychain.get_guardians(getGuardiansCall { account })?;
+            assert_eq!(config.threshold, 2);
+            assert_eq!(config.guardians, vec![guardian_a, guardian_b, guardian_c]);
+
+            let propose = proposeRecoveryCall {
+                account,
+                keyId: recovery_key,
+                signatureType: SignatureType::P256,
+                expiry: u64::MAX,
+            };
+
+            // Only guardians can propose a recovery
+            let result = keychain.propose_recovery(Address::random(), propose.clone());
+            assert_unauthorized_error(result.unwrap_err());
+
+            // First approval does not reach the threshold
+            keychain.propose_recovery(guardian_a, propose.clone())?;
+            assert!(
+                keychain
+                    .propose_recovery(guardian_a, propose.clone())
+                    .is_err(),
+                "Guardians cannot approve the same recovery twice"
+            );
+            let execute = executeRecoveryCall { account };
+            assert!(
+                keychain
+                    .execute_recovery(guardian_a, execute.clone())
+                    .is_err()
+            );
+
+            // Second approval schedules the recovery
+            keychain.propose_recovery(guardian_b, propose)?;
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.keyId, recovery_key);
+            assert_eq!(pending.approvals, 2);
+            assert_eq!(pending.executableAt, 1000 + RECOVERY_TIMELOCK);
+
+            // Cannot execute before the timelock has elapsed
+            match keychain
+                .execute_recovery(guardian_a, execute.clone())
+                .unwrap_err()
+            {
+                TempoPrecompileError::AccountKeychainError(e) => assert!(
+                    matches!(e, AccountKeychainError::RecoveryNotReady(_)),
+                    "Expected RecoveryNotReady error, got: {e:?}"
+                ),
+                e => panic!("Expected AccountKeychainError, got: {e:?}"),
+            }
+
+            // Anyone can execute once the timelock has elapsed
+            StorageCtx.set_timestamp(U256::from(1000 + RECOVERY_TIMELOCK));
+            keychain.execute_recovery(Address::random(), execute)?;
+
+            let key = keychain.get_key(getKeyCall {
+                account,
+                keyId: recovery_key,
+            })?;
+            assert_eq!(key.keyId, recovery_key);
+            assert_eq!(key.signatureType, SignatureType::P256);
+            assert!(!key.enforceLimits);
+
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.keyId, Address::ZERO);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_guardian_recovery_veto() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        let account = Address::random();
+        let (guardian_a, guardian_b) = (Address::random(), Address::random());
+        StorageCtx::enter(&mut storage, || {
+            StorageCtx.set_timestamp(U256::from(1000));
+
+            let mut keychain = AccountKeychain::new();
+            keychain.initialize()?;
+            keychain.set_transaction_key(Address::ZERO)?;
+
+            keychain.set_guardians(
+                account,
+                setGuardiansCall {
+                    guardians: vec![guardian_a, guardian_b],
+                    threshold: 2,
+                },
+            )?;
+
+            let propose = |key_id| proposeRecoveryCall {
+                account,
+                keyId: key_id,
+                signatureType: SignatureType::WebAuthn,
+                expiry: u64::MAX,
+            };
+
+            // A competing proposal cannot reset the approvals of an open recovery
+            let (key_a, key_b) = (Address::random(), Address::random());
+            keychain.propose_recovery(guardian_a, propose(key_a))?;
+            match keychain
+                .propose_recovery(guardian_b, propose(key_b))
+                .unwrap_err()
+            {
+                TempoPrecompileError::AccountKeychainError(e) => assert_eq!(
+                    e,
+                    AccountKeychainError::recovery_already_pending(1000 + RECOVERY_PROPOSAL_LOCK)
+                ),
+                e => panic!("Expected AccountKeychainError, got: {e:?}"),
+            }
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.keyId, key_a);
+            assert_eq!(pending.approvals, 1);
+
+            // A recovery that did not reach the threshold in time can be replaced, dropping its
+            // approvals
+            StorageCtx.set_timestamp(U256::from(1000 + RECOVERY_PROPOSAL_LOCK));
+            keychain.propose_recovery(guardian_b, propose(key_b))?;
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.keyId, key_b);
+            assert_eq!(pending.approvals, 1);
+
+            // Once scheduled, guardians can no longer replace the recovery
+            keychain.propose_recovery(guardian_a, propose(key_b))?;
+            assert!(
+                keychain
+                    .propose_recovery(guardian_a, propose(key_a))
+                    .is_err()
+            );
+
+            // The account can veto the recovery with any of its keys
+            keychain.set_transaction_key(Address::random())?;
+            keychain.cancel_recovery(account, cancelRecoveryCall {})?;
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.keyId, Address::ZERO);
+
+            StorageCtx.set_timestamp(U256::from(
+                1000 + RECOVERY_PROPOSAL_LOCK + RECOVERY_TIMELOCK,
+            ));
+            assert!(
+                keychain
+                    .execute_recovery(guardian_a, executeRecoveryCall { account })
+                    .is_err()
+            );
+
+            // Previous approvals do not carry over to a new proposal
+            keychain.propose_recovery(guardian_a, propose(key_b))?;
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.approvals, 1);
+            assert_eq!(pending.executableAt, 0);
+
+            // Access keys cannot manage guardians
+            let result = keychain.remove_guardians(account, removeGuardiansCall {});
+            assert_unauthorized_error(result.unwrap_err());
+
+            // Removing guardians cancels the pending recovery
+            keychain.set_transaction_key(Address::ZERO)?;
+            keychain.remove_guardians(account, removeGuardiansCall {})?;
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.keyId, Address::ZERO);
+            assert!(
+                keychain
+                    .propose_recovery(guardian_a, propose(key_b))
+                    .is_err()
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_guardian_quorum_rejects_recovery() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        let account = Address::random();
+        let (guardian_a, guardian_b, guardian_c) =
+            (Address::random(), Address::random(), Address::random());
+        StorageCtx::enter(&mut storage, || {
+            StorageCtx.set_timestamp(U256::from(1000));
+
+            let mut keychain = AccountKeychain::new();
+            keychain.initialize()?;
+            keychain.set_transaction_key(Address::ZERO)?;
+
+            keychain.set_guardians(
+                account,
+                setGuardiansCall {
+                    guardians: vec![guardian_a, guardian_b, guardian_c],
+                    threshold: 2,
+                },
+            )?;
+
+            let propose = |key_id| proposeRecoveryCall {
+                account,
+                keyId: key_id,
+                signatureType: SignatureType::P256,
+                expiry: u64::MAX,
+            };
+            let reject = rejectRecoveryCall { account };
+
+            // Nothing to reject without a pending recovery
+            match keychain
+                .reject_recovery(guardian_b, reject.clone())
+                .unwrap_err()
+            {
+                TempoPrecompileError::AccountKeychainError(e) => {
+                    assert_eq!(e, AccountKeychainError::no_pending_recovery())
+                }
+                e => panic!("Expected AccountKeychainError, got: {e:?}"),
+            }
+
+            // A single guardian opens a bogus recovery, locking out competing proposals
+            let (bogus_key, recovery_key) = (Address::random(), Address::random());
+            keychain.propose_recovery(guardian_a, propose(bogus_key))?;
+            assert!(
+                keychain
+                    .propose_recovery(guardian_b, propose(recovery_key))
+                    .is_err()
+            );
+
+            // Only guardians can reject, and only once
+            let result = keychain.reject_recovery(Address::random(), reject.clone());
+            assert_unauthorized_error(result.unwrap_err());
+            keychain.reject_recovery(guardian_b, reject.clone())?;
+            match keychain
+                .reject_recovery(guardian_b, reject.clone())
+                .unwrap_err()
+            {
+                TempoPrecompileError::AccountKeychainError(e) => {
+                    assert_eq!(e, AccountKeychainError::recovery_already_rejected())
+                }
+                e => panic!("Expected AccountKeychainError, got: {e:?}"),
+            }
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.keyId, bogus_key);
+
+            // A threshold of rejections cancels the recovery
+            keychain.reject_recovery(guardian_c, reject.clone())?;
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.keyId, Address::ZERO);
+
+            // The other guardians can propose a different key right away, without the approval of
+            // the cancelled recovery
+            keychain.propose_recovery(guardian_b, propose(recovery_key))?;
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.keyId, recovery_key);
+            assert_eq!(pending.approvals, 1);
+
+            // Rejections do not carry over to the new recovery, and a scheduled recovery can only
+            // be cancelled by the account
+            keychain.reject_recovery(guardian_c, reject.clone())?;
+            keychain.propose_recovery(guardian_a, propose(recovery_key))?;
+            let pending = keychain.get_pending_recovery(getPendingRecoveryCall { account })?;
+            assert_eq!(pending.executableAt, 1000 + RECOVERY_TIMELOCK);
+            match keychain.reject_recovery(guardian_a, reject).unwrap_err() {
+                TempoPrecompileError::AccountKeychainError(e) => {
+                    assert_eq!(e, AccountKeychainError::recovery_already_scheduled())
+                }
+                e => panic!("Expected AccountKeychainError, got: {e:?}"),
+            }
+
+            Ok(())
+        })
+    }
 }
@@ -199,11 +199,14 @@ impl<T> IntoPrecompileResult<T> for Result<T> {
                     TPErr::OutOfGas => {
                         return Err(PrecompileError::OutOfGas);
                     }
-                    TPErr::UnknownFunctionSelector(selector) => UnknownFunctionSelector {
-                        selector: selector.into(),
+                    TPErr::UnknownFunctionSelector(selector) => {
+                        crate::metrics::unknown_selector();
+                        UnknownFunctionSelector {
+                            selector: selector.into(),
+                        }
+                        .abi_encode()
+                        .into()
                     }
-                    .abi_encode()
-                    .into(),
                     TPErr::Fatal(msg) => {
                         return Err(PrecompileError::Fatal(msg));
                     }
@@ -242,11 +245,14 @@ impl<T> IntoPrecompileResult<T> for TempoPrecompileError {
             Self::OutOfGas => {
                 return Err(PrecompileError::OutOfGas);
             }
-            Self::UnknownFunctionSelector(selector) => UnknownFunctionSelector {
-                selector: selector.into(),
+            Self::UnknownFunctionSelector(selector) => {
+                crate::metrics::unknown_selector();
+                UnknownFunctionSelector {
+                    selector: selector.into(),
+                }
+                .abi_encode()
+                .into()
             }
-            .abi_encode()
-            .into(),
             Self::Fatal(msg) => {
                 return Err(PrecompileError::Fatal(msg));
             }
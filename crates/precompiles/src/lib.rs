@@ -5,11 +5,15 @@
 pub mod error;
 pub use error::{IntoPrecompileResult, Result};
 
+pub mod metrics;
+
 pub mod storage;
+pub mod tracer;
 
 pub mod account_keychain;
 pub mod nonce;
 pub mod path_usd;
+pub mod signature_verifier;
 pub mod stablecoin_exchange;
 pub mod tip20;
 pub mod tip20_factory;
@@ -17,6 +21,7 @@ pub mod tip20_rewards_registry;
 pub mod tip403_registry;
 pub mod tip_account_registrar;
 pub mod tip_fee_manager;
+pub mod transaction_context;
 pub mod validator_config;
 
 #[cfg(any(test, feature = "test-utils"))]
@@ -26,6 +31,7 @@ use crate::{
     account_keychain::AccountKeychain,
     nonce::NonceManager,
     path_usd::PathUSD,
+    signature_verifier::SignatureVerifier,
     stablecoin_exchange::StablecoinExchange,
     storage::StorageCtx,
     tip_account_registrar::TipAccountRegistrar,
@@ -34,6 +40,7 @@ use crate::{
     tip20_factory::TIP20Factory,
     tip20_rewards_registry::TIP20RewardsRegistry,
     tip403_registry::TIP403Registry,
+    transaction_context::TransactionContext,
     validator_config::ValidatorConfig,
 };
 use tempo_chainspec::hardfork::TempoHardfork;
@@ -53,9 +60,10 @@ use revm::{
 
 pub use tempo_contracts::precompiles::{
     ACCOUNT_KEYCHAIN_ADDRESS, DEFAULT_FEE_TOKEN_POST_ALLEGRETTO, DEFAULT_FEE_TOKEN_PRE_ALLEGRETTO,
-    NONCE_PRECOMPILE_ADDRESS, PATH_USD_ADDRESS, STABLECOIN_EXCHANGE_ADDRESS, TIP_ACCOUNT_REGISTRAR,
-    TIP_FEE_MANAGER_ADDRESS, TIP20_FACTORY_ADDRESS, TIP20_REWARDS_REGISTRY_ADDRESS,
-    TIP403_REGISTRY_ADDRESS, VALIDATOR_CONFIG_ADDRESS,
+    NONCE_PRECOMPILE_ADDRESS, PATH_USD_ADDRESS, SIGNATURE_VERIFIER_ADDRESS,
+    STABLECOIN_EXCHANGE_ADDRESS, TIP_ACCOUNT_REGISTRAR, TIP_FEE_MANAGER_ADDRESS,
+    TIP20_FACTORY_ADDRESS, TIP20_REWARDS_REGISTRY_ADDRESS, TIP403_REGISTRY_ADDRESS,
+    TRANSACTION_CONTEXT_ADDRESS, VALIDATOR_CONFIG_ADDRESS,
 };
 
 // Re-export storage layout helpers for read-only contexts (e.g., pool validation)
@@ -105,6 +113,12 @@ pub fn extend_tempo_precompiles(precompiles: &mut PrecompilesMap, cfg: &CfgEnv<T
         } else if *address == ACCOUNT_KEYCHAIN_ADDRESS && spec.is_allegretto() {
             // AccountKeychain is only available after Allegretto hardfork
             Some(AccountKeychainPrecompile::create(chain_id, spec))
+        } else if *address == SIGNATURE_VERIFIER_ADDRESS && spec.is_allegro_moderato() {
+            // SignatureVerifier is only available after Allegro-Moderato hardfork
+            Some(SignatureVerifierPrecompile::create(chain_id, spec))
+        } else if *address == TRANSACTION_CONTEXT_ADDRESS && spec.is_allegro_moderato() {
+            // TransactionContext is only available after Allegro-Moderato hardfork
+            Some(TransactionContextPrecompile::create(chain_id, spec))
         } else {
             None
         }
@@ -124,15 +138,27 @@ macro_rules! tempo_precompile {
                     DelegateCallNotAllowed {}.abi_encode().into(),
                 ));
             }
+            let calldata = $input.data;
+            let started = crate::metrics::start();
+            let caller = $input.caller;
+            let address = $input.target_address;
             let mut storage = crate::storage::evm::EvmPrecompileStorageProvider::new(
                 $input.internals,
                 $input.gas,
                 $chain_id,
                 $spec,
             );
-            crate::storage::StorageCtx::enter(&mut storage, || {
-                $impl.call($input.data, $input.caller)
-            })
+            let result = crate::storage::StorageCtx::enter_call(
+                &mut storage,
+                caller,
+                address,
+                calldata,
+                || $impl.call(calldata, caller),
+            );
+            if let Some(started) = started {
+                crate::metrics::record($id, calldata, &result, started.elapsed());
+            }
+            result
         })
     };
 }
@@ -219,6 +245,24 @@ impl AccountKeychainPrecompile {
     }
 }
 
+pub struct SignatureVerifierPrecompile;
+impl SignatureVerifierPrecompile {
+    pub fn create(chain_id: u64, spec: TempoHardfork) -> DynPrecompile {
+        tempo_precompile!("SignatureVerifier", chain_id, spec, |input| {
+            SignatureVerifier::new()
+        })
+    }
+}
+
+pub struct TransactionContextPrecompile;
+impl TransactionContextPrecompile {
+    pub fn create(chain_id: u64, spec: TempoHardfork) -> DynPrecompile {
+        tempo_precompile!("TransactionContext", chain_id, spec, |input| {
+            TransactionContext::new()
+        })
+    }
+}
+
 pub struct PathUSDPrecompile;
 impl PathUSDPrecompile {
     pub fn create(chain_id: u64, spec: TempoHardfork) -> DynPrecompile {
@@ -297,6 +341,7 @@ pub fn unknown_selector(selector: [u8; 4], gas: u64, spec: TempoHardfork) -> Pre
         error::TempoPrecompileError::UnknownFunctionSelector(selector)
             .into_precompile_result(gas, |_: ()| Bytes::new())
     } else {
+        metrics::unknown_selector();
         Err(PrecompileError::Other("Unknown function selector".into()))
     }
 }
@@ -0,0 +1,514 @@
+//! Per-precompile, per-selector call metrics.
+//!
+//! Recording is disabled by default and turned on with [`enable`]. While disabled, the only cost
+//! of a precompile call is a single relaxed atomic load.
+//!
+//! Precompiles are executed for many reasons besides executing blocks, e.g. `eth_call` or
+//! building payloads, so every metric is labeled with the [`ExecutionContext`] of the call.
+
+use alloy::primitives::hex;
+use metrics::{Counter, Histogram};
+use revm::precompile::{PrecompileError, PrecompileResult};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Precompile, selector and execution context of a call.
+type MetricsKey = (&'static str, Selector, ExecutionContext);
+
+thread_local! {
+    /// Metric handles, keyed by precompile, selector and execution context.
+    ///
+    /// Every thread caches its own handles, so recording a call never takes a lock. The recorder
+    /// hands out handles to the same series for the same labels on every thread.
+    static METRICS: RefCell<HashMap<MetricsKey, PrecompileMetrics>> =
+        RefCell::new(HashMap::new());
+
+    static CONTEXT: Cell<ExecutionContext> = const { Cell::new(ExecutionContext::Other) };
+
+    /// Whether the current call was dispatched to [`unknown_selector`](crate::unknown_selector).
+    static UNKNOWN_SELECTOR: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Enables recording of precompile call metrics.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Returns whether precompile call metrics are recorded.
+#[inline]
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// What precompiles are executed for on the current thread, the `context` label of all metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ExecutionContext {
+    /// Execution of a block by the block executor, e.g. when importing it.
+    Block,
+    /// Building or prebuilding a payload.
+    Payload,
+    /// Speculative execution of transactions ahead of the block executor.
+    Speculative,
+    /// Anything else, e.g. `eth_call`, gas estimation or tracing.
+    #[default]
+    Other,
+}
+
+impl ExecutionContext {
+    /// Returns the context of the current thread.
+    pub fn current() -> Self {
+        CONTEXT.get()
+    }
+
+    /// Runs `f` with this context on the current thread, restoring the previous one afterwards.
+    pub fn enter<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Restore(ExecutionContext);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CONTEXT.set(self.0);
+            }
+        }
+
+        let _restore = Restore(CONTEXT.replace(self));
+        f()
+    }
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Payload => "payload",
+            Self::Speculative => "speculative",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Starts recording a call, returning its start time if recording is enabled.
+pub(crate) fn start() -> Option<Instant> {
+    enabled().then(|| {
+        UNKNOWN_SELECTOR.set(false);
+        Instant::now()
+    })
+}
+
+/// Marks the current call as dispatched to a selector the precompile does not implement.
+pub(crate) fn unknown_selector() {
+    if enabled() {
+        UNKNOWN_SELECTOR.set(true);
+    }
+}
+
+/// The selector label of a call.
+///
+/// Calls to selectors a precompile does not implement share a single label, so that arbitrary
+/// calldata cannot blow up the number of series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Selector {
+    Known([u8; 4]),
+    Unknown,
+}
+
+impl Selector {
+    fn label(&self) -> String {
+        match self {
+            Self::Known(selector) => hex::encode_prefixed(selector),
+            Self::Unknown => "unknown".to_string(),
+        }
+    }
+}
+
+/// The `reason` label of a call that reverted or halted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RevertReason {
+    /// The selector of the ABI-encoded error the call reverted with.
+    Error([u8; 4]),
+    /// The call reverted without an ABI-encoded error.
+    None,
+    /// The call ran out of gas.
+    OutOfGas,
+    /// The call halted with another error.
+    Other,
+}
+
+impl RevertReason {
+    fn label(&self) -> String {
+        match self {
+            Self::Error(selector) => hex::encode_prefixed(selector),
+            Self::None => "none".to_string(),
+            Self::OutOfGas => "out_of_gas".to_string(),
+            Self::Other => "other".to_string(),
+        }
+    }
+}
+
+struct PrecompileMetrics {
+    /// Number of calls.
+    calls: Counter,
+    /// Number of calls that reverted.
+    reverts: Counter,
+    /// Number of calls that halted with an error, e.g. because they ran out of gas.
+    errors: Counter,
+    /// Gas used per call.
+    gas_used: Histogram,
+    /// Execution time per call in seconds.
+    duration_seconds: Histogram,
+    /// Number of calls that reverted or halted, by reason.
+    revert_reasons: HashMap<RevertReason, Counter>,
+}
+
+impl PrecompileMetrics {
+    fn new(precompile: &'static str, selector: Selector, context: ExecutionContext) -> Self {
+        let selector = selector.label();
+        let context = context.label();
+        Self {
+            calls: metrics::counter!(
+                "tempo_precompiles_calls",
+                "precompile" => precompile,
+                "selector" => selector.clone(),
+                "context" => context
+            ),
+            reverts: metrics::counter!(
+                "tempo_precompiles_reverts",
+                "precompile" => precompile,
+                "selector" => selector.clone(),
+                "context" => context
+            ),
+            errors: metrics::counter!(
+                "tempo_precompiles_errors",
+                "precompile" => precompile,
+                "selector" => selector.clone(),
+                "context" => context
+            ),
+            gas_used: metrics::histogram!(
+                "tempo_precompiles_gas_used",
+                "precompile" => precompile,
+                "selector" => selector.clone(),
+                "context" => context
+            ),
+            duration_seconds: metrics::histogram!(
+                "tempo_precompiles_duration_seconds",
+                "precompile" => precompile,
+                "selector" => selector,
+                "context" => context
+            ),
+            revert_reasons: HashMap::new(),
+        }
+    }
+
+    /// Returns the counter of calls that reverted or halted for `reason`.
+    fn revert_reason(
+        &mut self,
+        (precompile, selector, context): MetricsKey,
+        reason: RevertReason,
+    ) -> &Counter {
+        self.revert_reasons.entry(reason).or_insert_with(|| {
+            metrics::counter!(
+                "tempo_precompiles_revert_reasons",
+                "precompile" => precompile,
+                "selector" => selector.label(),
+                "context" => context.label(),
+                "reason" => reason.label()
+            )
+        })
+    }
+}
+
+/// Records a call into the precompile `precompile` with `calldata` that returned `result` after
+/// `elapsed`.
+pub(crate) fn record(
+    precompile: &'static str,
+    calldata: &[u8],
+    result: &PrecompileResult,
+    elapsed: Duration,
+) {
+    let unknown = UNKNOWN_SELECTOR.take();
+    let selector = match calldata.first_chunk::<4>() {
+        Some(selector) if !unknown => Selector::Known(*selector),
+        _ => Selector::Unknown,
+    };
+    let key = (precompile, selector, ExecutionContext::current());
+
+    METRICS.with_borrow_mut(|metrics| {
+        let metrics = metrics
+            .entry(key)
+            .or_insert_with(|| PrecompileMetrics::new(precompile, selector, key.2));
+        metrics.calls.increment(1);
+        metrics.duration_seconds.record(elapsed);
+
+        match result {
+            Ok(output) => {
+                metrics.gas_used.record(output.gas_used as f64);
+                if output.reverted {
+                    metrics.reverts.increment(1);
+                    // The revert reason is labeled with the selector of the ABI-encoded error.
+                    let reason = output
+                        .bytes
+                        .first_chunk::<4>()
+                        .map_or(RevertReason::None, |selector| {
+                            RevertReason::Error(*selector)
+                        });
+                    metrics.revert_reason(key, reason).increment(1);
+                }
+            }
+            Err(err) => {
+                metrics.errors.increment(1);
+                let reason = match err {
+                    PrecompileError::OutOfGas => RevertReason::OutOfGas,
+                    _ => RevertReason::Other,
+                };
+                metrics.revert_reason(key, reason).increment(1);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unknown_selector;
+    use alloy::primitives::Bytes;
+    use metrics::{
+        CounterFn, Gauge, GaugeFn, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString,
+        Unit,
+    };
+    use revm::precompile::PrecompileOutput;
+    use std::sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    };
+    use tempo_chainspec::hardfork::TempoHardfork;
+
+    /// Records counters and the number of histogram samples by name and labels.
+    #[derive(Default)]
+    struct TestRecorder {
+        metrics: Mutex<HashMap<String, Arc<TestMetric>>>,
+        /// Number of counters registered.
+        registered_counters: AtomicU64,
+    }
+
+    #[derive(Default)]
+    struct TestMetric(AtomicU64);
+
+    impl CounterFn for TestMetric {
+        fn increment(&self, value: u64) {
+            self.0.fetch_add(value, Ordering::Relaxed);
+        }
+
+        fn absolute(&self, value: u64) {
+            self.0.store(value, Ordering::Relaxed);
+        }
+    }
+
+    impl HistogramFn for TestMetric {
+        fn record(&self, _value: f64) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    impl GaugeFn for TestMetric {
+        fn increment(&self, _value: f64) {}
+
+        fn decrement(&self, _value: f64) {}
+
+        fn set(&self, _value: f64) {}
+    }
+
+    impl TestRecorder {
+        fn metric(&self, key: &Key) -> Arc<TestMetric> {
+            let key = format!(
+                "{}{{{}}}",
+                key.name(),
+                key.labels()
+                    .map(|label| format!("{}={}", label.key(), label.value()))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+            self.metrics.lock().unwrap().entry(key).or_default().clone()
+        }
+
+        fn get(&self, key: &str) -> u64 {
+            self.metrics
+                .lock()
+                .unwrap()
+                .get(key)
+                .map_or(0, |metric| metric.0.load(Ordering::Relaxed))
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            self.registered_counters.fetch_add(1, Ordering::Relaxed);
+            Counter::from_arc(self.metric(key))
+        }
+
+        fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::from_arc(self.metric(key))
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(self.metric(key))
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        // Metric handles are cached per thread, so the precompile name is unique to this test.
+        const PRECOMPILE: &str = "TestRecordPrecompile";
+        const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+        enable();
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            let call = |calldata: &[u8], result: PrecompileResult| {
+                start();
+                record(PRECOMPILE, calldata, &result, Duration::ZERO);
+            };
+
+            call(&TRANSFER, Ok(PrecompileOutput::new(100, Bytes::new())));
+            ExecutionContext::Block.enter(|| {
+                call(
+                    &TRANSFER,
+                    Ok(PrecompileOutput::new_reverted(
+                        100,
+                        Bytes::from_static(&[1, 2, 3, 4, 5]),
+                    )),
+                );
+                call(&TRANSFER, Err(PrecompileError::OutOfGas));
+            });
+
+            // Unknown selectors share a label, whether they revert or halt.
+            call(
+                &[0xde, 0xad, 0xbe, 0xef],
+                unknown_selector([0xde, 0xad, 0xbe, 0xef], 0, TempoHardfork::Moderato),
+            );
+            call(
+                &[0xde, 0xad, 0xbe, 0xee],
+                unknown_selector([0xde, 0xad, 0xbe, 0xee], 0, TempoHardfork::Adagio),
+            );
+            call(&[0x01], Ok(PrecompileOutput::new(0, Bytes::new())));
+
+            // A reverting call with a known selector is labeled with its selector.
+            call(
+                &TRANSFER,
+                Ok(PrecompileOutput::new_reverted(0, Bytes::new())),
+            );
+        });
+
+        let labels = |selector: &str, context: &str| {
+            format!("precompile={PRECOMPILE},selector={selector},context={context}")
+        };
+        let get = |name: &str, labels: &str| recorder.get(&format!("{name}{{{labels}}}"));
+
+        let transfer = labels("0xa9059cbb", "other");
+        assert_eq!(get("tempo_precompiles_calls", &transfer), 2);
+        assert_eq!(get("tempo_precompiles_reverts", &transfer), 1);
+        assert_eq!(get("tempo_precompiles_gas_used", &transfer), 2);
+        assert_eq!(
+            get(
+                "tempo_precompiles_revert_reasons",
+                &format!("{transfer},reason=none")
+            ),
+            1
+        );
+
+        let block = labels("0xa9059cbb", "block");
+        assert_eq!(get("tempo_precompiles_calls", &block), 2);
+        assert_eq!(get("tempo_precompiles_reverts", &block), 1);
+        assert_eq!(get("tempo_precompiles_errors", &block), 1);
+        assert_eq!(get("tempo_precompiles_duration_seconds", &block), 2);
+        assert_eq!(
+            get(
+                "tempo_precompiles_revert_reasons",
+                &format!("{block},reason=0x01020304")
+            ),
+            1
+        );
+        assert_eq!(
+            get(
+                "tempo_precompiles_revert_reasons",
+                &format!("{block},reason=out_of_gas")
+            ),
+            1
+        );
+
+        let unknown = labels("unknown", "other");
+        assert_eq!(get("tempo_precompiles_calls", &unknown), 3);
+        assert_eq!(get("tempo_precompiles_reverts", &unknown), 1);
+        assert_eq!(get("tempo_precompiles_errors", &unknown), 1);
+    }
+
+    #[test]
+    fn test_revert_reason_handles_are_cached() {
+        const PRECOMPILE: &str = "TestRevertReasonPrecompile";
+        const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+        enable();
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            let revert = || {
+                start();
+                record(
+                    PRECOMPILE,
+                    &TRANSFER,
+                    &Ok(PrecompileOutput::new_reverted(
+                        0,
+                        Bytes::from_static(&[1, 2, 3, 4]),
+                    )),
+                    Duration::ZERO,
+                );
+            };
+
+            revert();
+            let registered = recorder.registered_counters.load(Ordering::Relaxed);
+            revert();
+            revert();
+            assert_eq!(
+                recorder.registered_counters.load(Ordering::Relaxed),
+                registered
+            );
+        });
+
+        assert_eq!(
+            recorder.get(&format!(
+                "tempo_precompiles_revert_reasons{{precompile={PRECOMPILE},selector=0xa9059cbb,\
+                context=other,reason=0x01020304}}"
+            )),
+            3
+        );
+    }
+
+    #[test]
+    fn test_execution_context_is_restored() {
+        assert_eq!(ExecutionContext::current(), ExecutionContext::Other);
+        ExecutionContext::Payload.enter(|| {
+            assert_eq!(ExecutionContext::current(), ExecutionContext::Payload);
+            ExecutionContext::Speculative.enter(|| {
+                assert_eq!(ExecutionContext::current(), ExecutionContext::Speculative);
+            });
+            assert_eq!(ExecutionContext::current(), ExecutionContext::Payload);
+        });
+        assert_eq!(ExecutionContext::current(), ExecutionContext::Other);
+    }
+
+    #[test]
+    fn test_selector_labels() {
+        assert_eq!(
+            Selector::Known([0xa9, 0x05, 0x9c, 0xbb]).label(),
+            "0xa9059cbb"
+        );
+        assert_eq!(Selector::Unknown.label(), "unknown");
+    }
+}
@@ -215,7 +215,10 @@ impl Precompile for PathUSD {
                 })
             }
 
-            _ => Err(PrecompileError::Other("Unknown selector".into())),
+            _ => {
+                crate::metrics::unknown_selector();
+                Err(PrecompileError::Other("Unknown selector".into()))
+            }
         };
 
         result.map(|res| fill_precompile_output(res, self.token.storage()))
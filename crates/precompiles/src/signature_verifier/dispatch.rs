@@ -0,0 +1,94 @@
+use crate::{
+    Precompile, fill_precompile_output, input_cost, signature_verifier::SignatureVerifier,
+    unknown_selector, view,
+};
+use alloy::{primitives::Address, sol_types::SolCall};
+use revm::precompile::{PrecompileError, PrecompileResult};
+
+use super::ISignatureVerifier;
+
+impl Precompile for SignatureVerifier {
+    fn call(&mut self, calldata: &[u8], _msg_sender: Address) -> PrecompileResult {
+        self.storage
+            .deduct_gas(input_cost(calldata.len()))
+            .map_err(|_| PrecompileError::OutOfGas)?;
+
+        let selector: [u8; 4] = calldata
+            .get(..4)
+            .ok_or_else(|| {
+                PrecompileError::Other("Invalid input: missing function selector".into())
+            })?
+            .try_into()
+            .unwrap();
+
+        let result = match selector {
+            ISignatureVerifier::verifyP256Call::SELECTOR => {
+                view::<ISignatureVerifier::verifyP256Call>(calldata, |call| self.verify_p256(call))
+            }
+            ISignatureVerifier::verifyWebAuthnCall::SELECTOR => {
+                view::<ISignatureVerifier::verifyWebAuthnCall>(calldata, |call| {
+                    self.verify_webauthn(call)
+                })
+            }
+            _ => unknown_selector(selector, self.storage.gas_used(), self.storage.spec()),
+        };
+
+        result.map(|res| fill_precompile_output(res, &mut self.storage))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::{StorageCtx, hashmap::HashMapStorageProvider},
+        test_util::{assert_full_coverage, check_selector_coverage},
+    };
+    use alloy::primitives::B256;
+    use tempo_contracts::precompiles::ISignatureVerifier::ISignatureVerifierCalls;
+
+    #[test]
+    fn test_signature_verifier_selector_coverage() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        StorageCtx::enter(&mut storage, || {
+            let mut verifier = SignatureVerifier::new();
+
+            let unsupported = check_selector_coverage(
+                &mut verifier,
+                ISignatureVerifierCalls::SELECTORS,
+                "ISignatureVerifier",
+                ISignatureVerifierCalls::name_by_selector,
+            );
+
+            assert_full_coverage([unsupported]);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_verify_p256_invalid_signature_returns_false() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        StorageCtx::enter(&mut storage, || {
+            let mut verifier = SignatureVerifier::new();
+
+            let calldata = ISignatureVerifier::verifyP256Call {
+                digest: B256::ZERO,
+                r: B256::ZERO,
+                s: B256::ZERO,
+                pubKeyX: B256::ZERO,
+                pubKeyY: B256::ZERO,
+                preHash: false,
+            }
+            .abi_encode();
+            let output = verifier.call(&calldata, Address::ZERO)?;
+
+            assert!(!output.reverted);
+            assert!(!ISignatureVerifier::verifyP256Call::abi_decode_returns(
+                &output.bytes
+            )?);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,258 @@
+pub mod dispatch;
+
+pub use tempo_contracts::precompiles::ISignatureVerifier;
+use tempo_precompiles_macros::contract;
+
+use crate::{SIGNATURE_VERIFIER_ADDRESS, error::Result};
+use alloy::primitives::B256;
+use tempo_primitives::transaction::{
+    PrimitiveSignature,
+    tt_signature::{P256SignatureWithPreHash, WebAuthnSignature, verify_webauthn_assertion},
+};
+
+/// Gas cost of a P256 signature verification (EIP-7951)
+pub const P256_VERIFY_GAS: u64 = 6_900;
+
+/// Gas cost of an Ed25519 signature verification
+pub const ED25519_VERIFY_GAS: u64 = 4_500;
+
+/// Gas cost of an `ecrecover` (secp256k1) signature recovery
+pub const ECRECOVER_GAS: u64 = 3_000;
+
+/// Base gas cost of a SHA-256 hash, as charged by the SHA-256 precompile
+const SHA256_BASE_GAS: u64 = 60;
+
+/// Per-word gas cost of a SHA-256 hash, as charged by the SHA-256 precompile
+const SHA256_PER_WORD_GAS: u64 = 12;
+
+#[inline]
+fn sha256_gas(len: usize) -> u64 {
+    SHA256_BASE_GAS
+        + revm::interpreter::gas::cost_per_word(len, SHA256_PER_WORD_GAS).unwrap_or(u64::MAX)
+}
+
+/// Stateless precompile verifying P256 and WebAuthn signatures.
+///
+/// Verification applies the same validation rules as Tempo transaction signatures. Since
+/// authenticatorData is passed separately, WebAuthn assertions may additionally carry extensions.
+#[contract(addr = SIGNATURE_VERIFIER_ADDRESS)]
+pub struct SignatureVerifier {}
+
+impl SignatureVerifier {
+    /// Verifies a P256 signature over `digest`, sha256-hashing the digest first if `preHash` is set.
+    pub fn verify_p256(&mut self, call: ISignatureVerifier::verifyP256Call) -> Result<bool> {
+        let mut gas = P256_VERIFY_GAS;
+        if call.preHash {
+            gas += sha256_gas(B256::len_bytes());
+        }
+        self.storage.deduct_gas(gas)?;
+
+        let signature = PrimitiveSignature::P256(P256SignatureWithPreHash {
+            r: call.r,
+            s: call.s,
+            pub_key_x: call.pubKeyX,
+            pub_key_y: call.pubKeyY,
+            pre_hash: call.preHash,
+        });
+
+        Ok(signature.recover_signer(&call.digest).is_ok())
+    }
+
+    /// Verifies a WebAuthn assertion over `challenge`.
+    pub fn verify_webauthn(
+        &mut self,
+        call: ISignatureVerifier::verifyWebAuthnCall,
+    ) -> Result<bool> {
+        // messageHash = sha256(authenticatorData || sha256(clientDataJSON))
+        self.storage.deduct_gas(
+            P256_VERIFY_GAS
+                + sha256_gas(call.clientDataJSON.len())
+                + sha256_gas(call.authenticatorData.len() + B256::len_bytes()),
+        )?;
+
+        let signature = WebAuthnSignature {
+            r: call.r,
+            s: call.s,
+            pub_key_x: call.pubKeyX,
+            pub_key_y: call.pubKeyY,
+            webauthn_data: Default::default(),
+        };
+
+        Ok(verify_webauthn_assertion(
+            &call.authenticatorData,
+            &call.clientDataJSON,
+            &call.challenge,
+            &signature,
+        )
+        .is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{StorageCtx, hashmap::HashMapStorageProvider};
+    use alloy::primitives::Bytes;
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use p256::{
+        ecdsa::{SigningKey, signature::hazmat::PrehashSigner},
+        elliptic_curve::rand_core::OsRng,
+    };
+    use sha2::{Digest, Sha256};
+
+    struct P256Key {
+        signing_key: SigningKey,
+        pub_key_x: B256,
+        pub_key_y: B256,
+    }
+
+    impl P256Key {
+        fn random() -> Self {
+            let signing_key = SigningKey::random(&mut OsRng);
+            let point = signing_key.verifying_key().to_encoded_point(false);
+            Self {
+                pub_key_x: B256::from_slice(point.x().unwrap()),
+                pub_key_y: B256::from_slice(point.y().unwrap()),
+                signing_key,
+            }
+        }
+
+        fn sign(&self, hash: &[u8]) -> (B256, B256) {
+            let signature: p256::ecdsa::Signature = self.signing_key.sign_prehash(hash).unwrap();
+            let bytes = signature.to_bytes();
+            (
+                B256::from_slice(&bytes[..32]),
+                B256::from_slice(&bytes[32..]),
+            )
+        }
+    }
+
+    #[test]
+    fn test_verify_p256() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        StorageCtx::enter(&mut storage, || {
+            let mut verifier = SignatureVerifier::new();
+            let key = P256Key::random();
+            let digest = B256::random();
+
+            for pre_hash in [false, true] {
+                let (r, s) = if pre_hash {
+                    key.sign(&Sha256::digest(digest))
+                } else {
+                    key.sign(digest.as_slice())
+                };
+                let call = ISignatureVerifier::verifyP256Call {
+                    digest,
+                    r,
+                    s,
+                    pubKeyX: key.pub_key_x,
+                    pubKeyY: key.pub_key_y,
+                    preHash: pre_hash,
+                };
+
+                assert!(verifier.verify_p256(call.clone())?);
+                assert!(!verifier.verify_p256(ISignatureVerifier::verifyP256Call {
+                    digest: B256::random(),
+                    ..call.clone()
+                })?);
+                assert!(!verifier.verify_p256(ISignatureVerifier::verifyP256Call {
+                    preHash: !pre_hash,
+                    ..call
+                })?);
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_verify_webauthn() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        StorageCtx::enter(&mut storage, || {
+            let mut verifier = SignatureVerifier::new();
+            let key = P256Key::random();
+            let challenge = B256::random();
+
+            let mut authenticator_data = vec![0u8; 37];
+            authenticator_data[32] = 0x01; // UP flag
+            let client_data_json = format!(
+                "{{\"type\":\"webauthn.get\",\"challenge\":\"{}\",\"origin\":\"https://example.com\"}}",
+                URL_SAFE_NO_PAD.encode(challenge.as_slice())
+            );
+            let sign = |authenticator_data: &[u8]| {
+                let mut hasher = Sha256::new();
+                hasher.update(authenticator_data);
+                hasher.update(Sha256::digest(client_data_json.as_bytes()));
+                key.sign(&hasher.finalize())
+            };
+            let (r, s) = sign(&authenticator_data);
+
+            let call = ISignatureVerifier::verifyWebAuthnCall {
+                authenticatorData: Bytes::from(authenticator_data),
+                clientDataJSON: Bytes::from(client_data_json.into_bytes()),
+                challenge,
+                r,
+                s,
+                pubKeyX: key.pub_key_x,
+                pubKeyY: key.pub_key_y,
+            };
+            assert!(verifier.verify_webauthn(call.clone())?);
+
+            // Wrong challenge
+            assert!(
+                !verifier.verify_webauthn(ISignatureVerifier::verifyWebAuthnCall {
+                    challenge: B256::random(),
+                    ..call.clone()
+                })?
+            );
+
+            // Missing UP flag
+            let mut authenticator_data = call.authenticatorData.to_vec();
+            authenticator_data[32] = 0;
+            assert!(
+                !verifier.verify_webauthn(ISignatureVerifier::verifyWebAuthnCall {
+                    authenticatorData: authenticator_data.into(),
+                    ..call.clone()
+                })?
+            );
+
+            // authenticatorData with extensions requires the ED flag
+            let mut authenticator_data = call.authenticatorData.to_vec();
+            authenticator_data.push(0xa0); // empty CBOR map
+            let (r, s) = sign(&authenticator_data);
+            let extensions_call = ISignatureVerifier::verifyWebAuthnCall {
+                authenticatorData: authenticator_data.clone().into(),
+                r,
+                s,
+                ..call.clone()
+            };
+            assert!(!verifier.verify_webauthn(extensions_call)?);
+
+            authenticator_data[32] |= 0x80; // ED flag
+            let (r, s) = sign(&authenticator_data);
+            assert!(
+                verifier.verify_webauthn(ISignatureVerifier::verifyWebAuthnCall {
+                    authenticatorData: authenticator_data.into(),
+                    r,
+                    s,
+                    ..call.clone()
+                })?
+            );
+
+            // The ED flag without extension data is rejected
+            let mut authenticator_data = call.authenticatorData.to_vec();
+            authenticator_data[32] |= 0x80;
+            let (r, s) = sign(&authenticator_data);
+            assert!(
+                !verifier.verify_webauthn(ISignatureVerifier::verifyWebAuthnCall {
+                    authenticatorData: authenticator_data.into(),
+                    r,
+                    s,
+                    ..call
+                })?
+            );
+
+            Ok(())
+        })
+    }
+}
@@ -205,50 +205,44 @@ impl StablecoinExchange {
 
     /// Transfer tokens, accounting for pathUSD
     fn transfer(&mut self, token: Address, to: Address, amount: u128) -> Result<()> {
-        if token == PATH_USD_ADDRESS {
-            PathUSD::new().transfer(
-                self.address,
-                ITIP20::transferCall {
-                    to,
-                    amount: U256::from(amount),
-                },
-            )?;
-        } else {
-            TIP20Token::from_address(token)?.transfer(
-                self.address,
-                ITIP20::transferCall {
-                    to,
-                    amount: U256::from(amount),
-                },
-            )?;
-        }
+        let call = ITIP20::transferCall {
+            to,
+            amount: U256::from(amount),
+        };
+        self.storage.call(token, call, |call| {
+            if token == PATH_USD_ADDRESS {
+                PathUSD::new().transfer(self.address, call)
+            } else {
+                TIP20Token::from_address(token)?.transfer(self.address, call)
+            }
+        })?;
         Ok(())
     }
 
     /// Transfer tokens from user, accounting for pathUSD
     fn transfer_from(&mut self, token: Address, from: Address, amount: u128) -> Result<()> {
-        if token == PATH_USD_ADDRESS {
-            PathUSD::new().transfer_from(
-                self.address,
-                ITIP20::transferFromCall {
-                    from,
-                    to: self.address,
-                    amount: U256::from(amount),
-                },
-            )?;
-        } else {
-            TIP20Token::from_address(token)?.transfer_from(
-                self.address,
-                ITIP20::transferFromCall {
-                    from,
-                    to: self.address,
-                    amount: U256::from(amount),
-                },
-            )?;
-        }
+        let call = ITIP20::transferFromCall {
+            from,
+            to: self.address,
+            amount: U256::from(amount),
+        };
+        self.storage.call(token, call, |call| {
+            if token == PATH_USD_ADDRESS {
+                PathUSD::new().transfer_from(self.address, call)
+            } else {
+                TIP20Token::from_address(token)?.transfer_from(self.address, call)
+            }
+        })?;
         Ok(())
     }
 
+    /// Returns the quote token of `token`.
+    fn quote_token(&self, token: Address) -> Result<Address> {
+        self.storage.call(token, ITIP20::quoteTokenCall {}, |_| {
+            TIP20Token::from_address(token)?.quote_token()
+        })
+    }
+
     /// Decrement user's internal balance or transfer from external wallet
     fn decrement_balance_or_transfer_from(
         &mut self,
@@ -400,7 +394,7 @@ impl StablecoinExchange {
 
     /// Get price level information
     pub fn get_price_level(&self, base: Address, tick: i16, is_bid: bool) -> Result<TickLevel> {
-        let quote = TIP20Token::from_address(base)?.quote_token()?;
+        let quote = self.quote_token(base)?;
         let book_key = compute_book_key(base, quote);
         self.books
             .at(book_key)
@@ -446,7 +440,7 @@ impl StablecoinExchange {
             return Err(StablecoinExchangeError::invalid_base_token().into());
         }
 
-        let quote = TIP20Token::from_address(base)?.quote_token()?;
+        let quote = self.quote_token(base)?;
         validate_usd_currency(base, self.storage)?;
         validate_usd_currency(quote, self.storage)?;
 
@@ -493,7 +487,7 @@ impl StablecoinExchange {
         is_bid: bool,
         tick: i16,
     ) -> Result<u128> {
-        let quote_token = TIP20Token::from_address(token)?.quote_token()?;
+        let quote_token = self.quote_token(token)?;
 
         // Compute book_key from token pair
         let book_key = compute_book_key(token, quote_token);
@@ -576,7 +570,7 @@ impl StablecoinExchange {
         tick: i16,
         flip_tick: i16,
     ) -> Result<u128> {
-        let quote_token = TIP20Token::from_address(token)?.quote_token()?;
+        let quote_token = self.quote_token(token)?;
 
         // Compute book_key from token pair
         let book_key = compute_book_key(token, quote_token);
@@ -1481,8 +1475,8 @@ impl StablecoinExchange {
         }
 
         // Check if direct or reverse pair exists
-        let in_quote = TIP20Token::from_address(token_in)?.quote_token()?;
-        let out_quote = TIP20Token::from_address(token_out)?.quote_token()?;
+        let in_quote = self.quote_token(token_in)?;
+        let out_quote = self.quote_token(token_out)?;
 
         if in_quote == token_out || out_quote == token_in {
             return self.validate_and_build_route(&[token_in, token_out]);
@@ -1558,7 +1552,7 @@ impl StablecoinExchange {
         let mut path = vec![token];
 
         while token != PATH_USD_ADDRESS {
-            token = TIP20Token::from_address(token)?.quote_token()?;
+            token = self.quote_token(token)?;
             path.push(token);
         }
 
@@ -7,7 +7,11 @@ use revm::{
 };
 use tempo_chainspec::hardfork::TempoHardfork;
 
-use crate::{error::TempoPrecompileError, storage::PrecompileStorageProvider};
+use crate::{
+    error::TempoPrecompileError,
+    storage::PrecompileStorageProvider,
+    tracer::{self, StorageOp},
+};
 
 pub struct EvmPrecompileStorageProvider<'a> {
     internals: EvmInternals<'a>,
@@ -100,6 +104,15 @@ impl<'a> PrecompileStorageProvider for EvmPrecompileStorageProvider<'a> {
     ) -> Result<(), TempoPrecompileError> {
         self.ensure_loaded_account(address)?;
         let result = self.internals.sstore(address, key, value)?;
+        tracer::record(|recorder| {
+            recorder.storage(
+                address,
+                StorageOp::Sstore,
+                key,
+                value,
+                Some(result.data.present_value),
+            )
+        });
 
         self.deduct_gas(revm::interpreter::gas::sstore_cost(
             SpecId::AMSTERDAM,
@@ -126,6 +139,7 @@ impl<'a> PrecompileStorageProvider for EvmPrecompileStorageProvider<'a> {
         self.ensure_loaded_account(address)?;
         self.deduct_gas(revm::interpreter::gas::WARM_STORAGE_READ_COST)?;
 
+        tracer::record(|recorder| recorder.storage(address, StorageOp::Tstore, key, value, None));
         self.internals.tstore(address, key, value);
         Ok(())
     }
@@ -137,6 +151,7 @@ impl<'a> PrecompileStorageProvider for EvmPrecompileStorageProvider<'a> {
                 .unwrap_or(u64::MAX),
         )?;
 
+        tracer::record(|recorder| recorder.log(address, &event));
         self.internals.log(Log {
             address,
             data: event,
@@ -149,6 +164,7 @@ impl<'a> PrecompileStorageProvider for EvmPrecompileStorageProvider<'a> {
     fn sload(&mut self, address: Address, key: U256) -> Result<U256, TempoPrecompileError> {
         self.ensure_loaded_account(address)?;
         let val = self.internals.sload(address, key)?;
+        tracer::record(|recorder| recorder.storage(address, StorageOp::Sload, key, val.data, None));
 
         self.deduct_gas(revm::interpreter::gas::sload_cost(
             SpecId::AMSTERDAM,
@@ -163,7 +179,9 @@ impl<'a> PrecompileStorageProvider for EvmPrecompileStorageProvider<'a> {
         self.ensure_loaded_account(address)?;
         self.deduct_gas(revm::interpreter::gas::WARM_STORAGE_READ_COST)?;
 
-        Ok(self.internals.tload(address, key))
+        let value = self.internals.tload(address, key);
+        tracer::record(|recorder| recorder.storage(address, StorageOp::Tload, key, value, None));
+        Ok(value)
     }
 
     #[inline]
@@ -172,6 +190,7 @@ impl<'a> PrecompileStorageProvider for EvmPrecompileStorageProvider<'a> {
             .gas_remaining
             .checked_sub(gas)
             .ok_or(TempoPrecompileError::OutOfGas)?;
+        tracer::record(|recorder| recorder.gas(gas));
         Ok(())
     }
 
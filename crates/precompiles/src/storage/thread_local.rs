@@ -1,7 +1,11 @@
-use alloy::primitives::{Address, LogData, U256};
+use alloy::{
+    primitives::{Address, Bytes, LogData, U256},
+    sol_types::SolCall,
+};
 use alloy_evm::{Database, EvmInternals};
 use revm::{
     context::{Block, CfgEnv, JournalTr},
+    precompile::PrecompileResult,
     state::{AccountInfo, Bytecode},
 };
 use scoped_tls::scoped_thread_local;
@@ -12,6 +16,7 @@ use crate::{
     Precompile,
     error::{Result, TempoPrecompileError},
     storage::{PrecompileStorageProvider, evm::EvmPrecompileStorageProvider},
+    tracer,
 };
 
 scoped_thread_local!(static STORAGE: RefCell<&mut dyn PrecompileStorageProvider>);
@@ -54,6 +59,57 @@ impl StorageCtx {
         STORAGE.set(&cell, f)
     }
 
+    /// Entry point for a call from `caller` into the precompile at `address` through the EVM.
+    ///
+    /// Enters the storage context like [`Self::enter`] and reports the call to the active
+    /// [tracer](crate::tracer), if any.
+    pub fn enter_call<S>(
+        storage: &mut S,
+        caller: Address,
+        address: Address,
+        calldata: &[u8],
+        f: impl FnOnce() -> PrecompileResult,
+    ) -> PrecompileResult
+    where
+        S: PrecompileStorageProvider,
+    {
+        let frame = tracer::enter(Some(caller), address, || Bytes::copy_from_slice(calldata));
+        let result = Self::enter(storage, f);
+        if let Some(frame) = frame {
+            match &result {
+                Ok(output) => frame.exit(
+                    Some(output.bytes.clone()),
+                    output.reverted.then(|| "execution reverted".to_string()),
+                    Some(output.gas_used),
+                ),
+                Err(err) => frame.exit(None, Some(err.to_string()), None),
+            }
+        }
+        result
+    }
+
+    /// Calls `f` as a call into the precompile at `address` from the precompile currently
+    /// executing.
+    ///
+    /// Precompiles call each other directly in Rust instead of through the EVM, this reports the
+    /// call and its ABI-encoded input and output to the active [tracer](crate::tracer), if any.
+    pub fn call<C: SolCall>(
+        &self,
+        address: Address,
+        call: C,
+        f: impl FnOnce(C) -> Result<C::Return>,
+    ) -> Result<C::Return> {
+        let frame = tracer::enter(None, address, || call.abi_encode().into());
+        let result = f(call);
+        if let Some(frame) = frame {
+            match &result {
+                Ok(ret) => frame.exit(Some(C::abi_encode_returns(ret).into()), None, None),
+                Err(err) => frame.exit(None, Some(err.to_string()), None),
+            }
+        }
+        result
+    }
+
     /// Execute an infallible function with access to the current thread-local storage provider.
     ///
     /// # Panics
@@ -307,6 +307,8 @@ pub trait StorageKey {
         // Append slot in big-endian
         buf[padded_len..].copy_from_slice(&slot.to_be_bytes::<32>());
 
-        U256::from_be_bytes(keccak256(&buf).0)
+        let mapping_slot = U256::from_be_bytes(keccak256(&buf).0);
+        crate::tracer::record(|recorder| recorder.preimage(mapping_slot, slot, key_bytes));
+        mapping_slot
     }
 }
@@ -8,7 +8,7 @@ pub use tempo_contracts::precompiles::{
 };
 
 use crate::{
-    PATH_USD_ADDRESS, TIP_FEE_MANAGER_ADDRESS,
+    PATH_USD_ADDRESS, TIP_FEE_MANAGER_ADDRESS, TIP403_REGISTRY_ADDRESS,
     account_keychain::AccountKeychain,
     error::{Result, TempoPrecompileError},
     storage::{Handler, Mapping, StorageCtx},
@@ -389,8 +389,7 @@ impl TIP20Token {
         // Check if the `to` address is authorized to receive tokens
         if self.storage.spec().is_allegretto() {
             let transfer_policy_id = self.transfer_policy_id()?;
-            let registry = TIP403Registry::new();
-            if !registry.is_authorized(ITIP403Registry::isAuthorizedCall {
+            if !self.is_authorized(ITIP403Registry::isAuthorizedCall {
                 policyId: transfer_policy_id,
                 user: to,
             })? {
@@ -469,8 +468,7 @@ impl TIP20Token {
 
         // Check if the address is blocked from transferring
         let transfer_policy_id = self.transfer_policy_id()?;
-        let registry = TIP403Registry::new();
-        if registry.is_authorized(ITIP403Registry::isAuthorizedCall {
+        if self.is_authorized(ITIP403Registry::isAuthorizedCall {
             policyId: transfer_policy_id,
             user: call.from,
         })? {
@@ -764,19 +762,24 @@ impl TIP20Token {
         Ok(())
     }
 
+    /// Checks `call` against the TIP-403 registry.
+    fn is_authorized(&self, call: ITIP403Registry::isAuthorizedCall) -> Result<bool> {
+        self.storage.call(TIP403_REGISTRY_ADDRESS, call, |call| {
+            TIP403Registry::new().is_authorized(call)
+        })
+    }
+
     /// Checks if the transfer is authorized.
     pub fn is_transfer_authorized(&self, from: Address, to: Address) -> Result<bool> {
         let transfer_policy_id = self.transfer_policy_id()?;
-        let registry = TIP403Registry::new();
-
         // Check if 'from' address is authorized
-        let from_authorized = registry.is_authorized(ITIP403Registry::isAuthorizedCall {
+        let from_authorized = self.is_authorized(ITIP403Registry::isAuthorizedCall {
             policyId: transfer_policy_id,
             user: from,
         })?;
 
         // Check if 'to' address is authorized
-        let to_authorized = registry.is_authorized(ITIP403Registry::isAuthorizedCall {
+        let to_authorized = self.is_authorized(ITIP403Registry::isAuthorizedCall {
             policyId: transfer_policy_id,
             user: to,
         })?;
@@ -217,8 +217,8 @@ impl TipFeeManager {
             amount_in,
         )?;
 
-        TIP20Token::from_address(user_token)?.transfer(
-            self.address,
+        self.transfer_token(
+            user_token,
             ITIP20::transferCall {
                 to,
                 amount: amount_out,
@@ -546,16 +546,16 @@ impl TipFeeManager {
         self.pools.at(pool_id).write(pool)?;
 
         // Transfer tokens to user
-        let _ = TIP20Token::from_address(user_token)?.transfer(
-            self.address,
+        let _ = self.transfer_token(
+            user_token,
             ITIP20::transferCall {
                 to,
                 amount: amount_user_token,
             },
         )?;
 
-        let _ = TIP20Token::from_address(validator_token)?.transfer(
-            self.address,
+        let _ = self.transfer_token(
+            validator_token,
             ITIP20::transferCall {
                 to,
                 amount: amount_validator_token,
@@ -280,29 +280,31 @@ impl TipFeeManager {
             }
 
             let validator_token = self.get_validator_token(validator)?;
-            let mut token = TIP20Token::from_address(validator_token)?;
+            let token = TIP20Token::from_address(validator_token)?;
 
             // If FeeManager or validator are blacklisted, we are not transferring any fees
             if token.is_transfer_authorized(self.address, beneficiary)? {
                 // Bound fee transfer to contract balance
-                let balance = token.balance_of(ITIP20::balanceOfCall {
+                let call = ITIP20::balanceOfCall {
                     account: self.address,
-                })?;
+                };
+                let balance = self
+                    .storage
+                    .call(validator_token, call, |call| token.balance_of(call))?;
 
                 if !balance.is_zero() {
-                    token
-                        .transfer(
-                            self.address,
-                            ITIP20::transferCall {
-                                to: beneficiary,
-                                amount: collected_fees.min(balance),
-                            },
+                    self.transfer_token(
+                        validator_token,
+                        ITIP20::transferCall {
+                            to: beneficiary,
+                            amount: collected_fees.min(balance),
+                        },
+                    )
+                    .map_err(|_| {
+                        IFeeManager::IFeeManagerErrors::InsufficientFeeTokenBalance(
+                            IFeeManager::InsufficientFeeTokenBalance {},
                         )
-                        .map_err(|_| {
-                            IFeeManager::IFeeManagerErrors::InsufficientFeeTokenBalance(
-                                IFeeManager::InsufficientFeeTokenBalance {},
-                            )
-                        })?;
+                    })?;
                 }
             }
 
@@ -313,6 +315,13 @@ impl TipFeeManager {
         Ok(())
     }
 
+    /// Transfers `token` held by the fee manager.
+    fn transfer_token(&self, token: Address, call: ITIP20::transferCall) -> Result<bool> {
+        self.storage.call(token, call, |call| {
+            TIP20Token::from_address(token)?.transfer(self.address, call)
+        })
+    }
+
     /// Add a token to the tokens with fees array
     fn add_pair_to_fees_array(
         &mut self,
@@ -420,9 +429,11 @@ impl TipFeeManager {
             }
         }
 
-        let tip20_token = TIP20Token::from_address(token)?;
-        let token_balance = tip20_token.balance_of(ITIP20::balanceOfCall {
+        let balance_of = ITIP20::balanceOfCall {
             account: call.sender,
+        };
+        let token_balance = self.storage.call(token, balance_of, |call| {
+            TIP20Token::from_address(token)?.balance_of(call)
         })?;
 
         Ok(IFeeManager::getFeeTokenBalanceReturn {
@@ -0,0 +1,488 @@
+//! Tracing of the internals of Tempo precompiles.
+//!
+//! Precompiles run as native code, so an EVM inspector only observes a single opaque call into
+//! them. [`PrecompileInspector`] opens a recorder for every call into a Tempo precompile, which the
+//! [`EvmPrecompileStorageProvider`](crate::storage::evm::EvmPrecompileStorageProvider) reports
+//! storage accesses, events and gas deductions to. The recorded activity is returned as a
+//! geth-style [`PrecompileCallFrame`] tree.
+//!
+//! Frames are opened and closed by the call entry points of [`StorageCtx`]:
+//! [`StorageCtx::enter_call`] for calls through the EVM and [`StorageCtx::call`] for precompiles
+//! calling each other directly in Rust (e.g. the DEX calling `transferFrom` on a TIP-20 token).
+//! Both know the calldata and return data of the call. Internal operations without an ABI, such as
+//! collecting fees, are recorded in the frame that performs them.
+//!
+//! [`StorageCtx`]: crate::storage::StorageCtx
+//! [`StorageCtx::enter_call`]: crate::storage::StorageCtx::enter_call
+//! [`StorageCtx::call`]: crate::storage::StorageCtx::call
+
+use alloy::primitives::{Address, Bytes, LogData, U256, map::HashMap};
+use revm::{
+    Inspector,
+    context::ContextTr,
+    interpreter::{CallInputs, CallOutcome},
+};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+
+use crate::{
+    ACCOUNT_KEYCHAIN_ADDRESS, NONCE_PRECOMPILE_ADDRESS, SIGNATURE_VERIFIER_ADDRESS,
+    STABLECOIN_EXCHANGE_ADDRESS, TIP_ACCOUNT_REGISTRAR, TIP_FEE_MANAGER_ADDRESS,
+    TIP20_FACTORY_ADDRESS, TIP20_REWARDS_REGISTRY_ADDRESS, TIP403_REGISTRY_ADDRESS,
+    TRANSACTION_CONTEXT_ADDRESS, VALIDATOR_CONFIG_ADDRESS, account_keychain, nonce,
+    signature_verifier, stablecoin_exchange, tip_account_registrar, tip_fee_manager, tip20,
+    tip20_factory, tip20_rewards_registry, tip403_registry, transaction_context, validator_config,
+};
+
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+/// Reports to the active recorder of the current thread, if any.
+pub(crate) fn record(f: impl FnOnce(&mut Recorder)) {
+    RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+            f(recorder)
+        }
+    })
+}
+
+/// Opens a frame for a call into the precompile at `to` on the active recorder of the current
+/// thread, if any.
+///
+/// The caller defaults to the precompile of the enclosing frame. The frame is closed when the
+/// returned guard is dropped.
+pub(crate) fn enter(
+    from: Option<Address>,
+    to: Address,
+    input: impl FnOnce() -> Bytes,
+) -> Option<FrameGuard> {
+    RECORDER.with(|recorder| {
+        let mut recorder = recorder.borrow_mut();
+        let recorder = recorder.as_mut()?;
+        let from = from
+            .or_else(|| recorder.stack.last().map(|frame| frame.to))
+            .unwrap_or_default();
+        recorder.stack.push(PrecompileCallFrame {
+            from,
+            to,
+            input: Some(input()),
+            ..Default::default()
+        });
+        Some(FrameGuard {
+            depth: recorder.stack.len(),
+        })
+    })
+}
+
+/// An open frame of the active recorder, closed on drop.
+///
+/// Closing a frame also closes any frame above it that was left open, so the recorder stays
+/// consistent even if a call unwinds.
+#[derive(Debug)]
+#[must_use]
+pub(crate) struct FrameGuard {
+    /// Number of open frames including this one.
+    depth: usize,
+}
+
+impl FrameGuard {
+    /// Sets the outcome of the call and closes the frame.
+    ///
+    /// `gas_used` overrides the gas attributed to the frame by the deductions made during the call.
+    pub(crate) fn exit(self, output: Option<Bytes>, error: Option<String>, gas_used: Option<u64>) {
+        record(|recorder| {
+            if let Some(frame) = recorder.stack.get_mut(self.depth - 1) {
+                frame.output = output;
+                frame.error = error;
+                if let Some(gas_used) = gas_used {
+                    frame.gas_used = gas_used;
+                }
+            }
+        });
+    }
+}
+
+impl Drop for FrameGuard {
+    fn drop(&mut self) {
+        record(|recorder| recorder.close(self.depth));
+    }
+}
+
+/// Returns the field names of the storage layout of the precompile at `address`.
+///
+/// Returns `None` if `address` is not a Tempo precompile.
+pub fn storage_layout(address: Address) -> Option<&'static [(&'static str, U256)]> {
+    if tip20::is_tip20_prefix(address) {
+        return Some(tip20::slots::FIELDS);
+    }
+
+    let fields = match address {
+        TIP20_FACTORY_ADDRESS => tip20_factory::slots::FIELDS,
+        TIP20_REWARDS_REGISTRY_ADDRESS => tip20_rewards_registry::slots::FIELDS,
+        TIP403_REGISTRY_ADDRESS => tip403_registry::slots::FIELDS,
+        TIP_FEE_MANAGER_ADDRESS => tip_fee_manager::slots::FIELDS,
+        TIP_ACCOUNT_REGISTRAR => tip_account_registrar::slots::FIELDS,
+        STABLECOIN_EXCHANGE_ADDRESS => stablecoin_exchange::slots::FIELDS,
+        NONCE_PRECOMPILE_ADDRESS => nonce::slots::FIELDS,
+        VALIDATOR_CONFIG_ADDRESS => validator_config::slots::FIELDS,
+        ACCOUNT_KEYCHAIN_ADDRESS => account_keychain::slots::FIELDS,
+        SIGNATURE_VERIFIER_ADDRESS => signature_verifier::slots::FIELDS,
+        TRANSACTION_CONTEXT_ADDRESS => transaction_context::slots::FIELDS,
+        _ => return None,
+    };
+    Some(fields)
+}
+
+/// A call into a precompile, in the format of geth's `callTracer` extended with storage accesses.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrecompileCallFrame {
+    pub from: Address,
+    pub to: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<Bytes>,
+    /// Return data of the call, unset if the call halted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<Bytes>,
+    #[serde(with = "alloy::serde::quantity")]
+    pub gas_used: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<PrecompileCallFrame>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub storage: Vec<StorageAccess>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<PrecompileLog>,
+}
+
+/// Kind of a [`StorageAccess`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StorageOp {
+    Sload,
+    Sstore,
+    Tload,
+    Tstore,
+}
+
+/// A storage read or write performed by a precompile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageAccess {
+    /// The account whose storage was accessed, which differs from the frame's precompile for
+    /// internal operations such as fee collection.
+    pub address: Address,
+    pub op: StorageOp,
+    pub slot: U256,
+    /// The slot decoded against the `#[contract]` layout, e.g. `balances[0x…]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub value: U256,
+    /// Value of the slot before an `SSTORE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<U256>,
+}
+
+/// An event emitted by a precompile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrecompileLog {
+    pub address: Address,
+    pub topics: Vec<alloy::primitives::B256>,
+    pub data: Bytes,
+}
+
+/// Records the activity of the calls into precompiles made while it is active.
+#[derive(Debug, Default)]
+pub(crate) struct Recorder {
+    /// Open frames; the first one is the outermost call.
+    stack: Vec<PrecompileCallFrame>,
+    /// Closed outermost frames, in execution order.
+    frames: Vec<PrecompileCallFrame>,
+    /// Preimages of mapping slots computed during the call: `slot => (base slot, key)`.
+    preimages: HashMap<U256, (U256, Bytes)>,
+}
+
+impl Recorder {
+    /// Closes the frame at `depth` and every frame above it.
+    ///
+    /// The gas used by a frame is added to its parent.
+    fn close(&mut self, depth: usize) {
+        while self.stack.len() >= depth {
+            let Some(frame) = self.stack.pop() else {
+                return;
+            };
+            match self.stack.last_mut() {
+                Some(parent) => {
+                    parent.gas_used = parent.gas_used.saturating_add(frame.gas_used);
+                    parent.calls.push(frame);
+                }
+                None => self.frames.push(frame),
+            }
+        }
+    }
+
+    /// Records that `slot` is the mapping slot of `key` under `base`.
+    pub(crate) fn preimage(&mut self, slot: U256, base: U256, key: &[u8]) {
+        self.preimages
+            .entry(slot)
+            .or_insert_with(|| (base, Bytes::copy_from_slice(key)));
+    }
+
+    pub(crate) fn storage(
+        &mut self,
+        address: Address,
+        op: StorageOp,
+        slot: U256,
+        value: U256,
+        previous: Option<U256>,
+    ) {
+        let name = self.slot_name(address, slot);
+        let Some(frame) = self.stack.last_mut() else {
+            return;
+        };
+        frame.storage.push(StorageAccess {
+            address,
+            op,
+            slot,
+            name,
+            value,
+            previous,
+        });
+    }
+
+    pub(crate) fn log(&mut self, address: Address, log: &LogData) {
+        let Some(frame) = self.stack.last_mut() else {
+            return;
+        };
+        frame.logs.push(PrecompileLog {
+            address,
+            topics: log.topics().to_vec(),
+            data: log.data.clone(),
+        });
+    }
+
+    /// Attributes `gas` to the innermost frame.
+    pub(crate) fn gas(&mut self, gas: u64) {
+        if let Some(frame) = self.stack.last_mut() {
+            frame.gas_used = frame.gas_used.saturating_add(gas);
+        }
+    }
+
+    /// Decodes `slot` against the storage layout of `address`, following mapping preimages.
+    fn slot_name(&self, address: Address, slot: U256) -> Option<String> {
+        let fields = storage_layout(address)?;
+        let mut keys = Vec::new();
+        let mut slot = slot;
+        while let Some((base, key)) = self.preimages.get(&slot) {
+            keys.push(key);
+            slot = *base;
+        }
+
+        let names = fields
+            .iter()
+            .filter(|(_, field_slot)| *field_slot == slot)
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            return None;
+        }
+
+        let mut name = names.join("|");
+        for key in keys.into_iter().rev() {
+            name.push_str(&format!("[{key}]"));
+        }
+        Some(name)
+    }
+}
+
+/// An [`Inspector`] that traces the internals of calls into Tempo precompiles.
+///
+/// The recorder is installed for the duration of each call into a precompile and removed when
+/// the call ends or the inspector is dropped, whichever comes first.
+#[derive(Debug, Default)]
+pub struct PrecompileInspector {
+    /// Depth of the currently recorded call, if any.
+    recording: Option<usize>,
+    depth: usize,
+    frames: Vec<PrecompileCallFrame>,
+}
+
+impl PrecompileInspector {
+    /// Name of the tracer in the `tracer` option of `debug_traceTransaction` and friends.
+    pub const TRACER_NAME: &'static str = "tempoPrecompileTracer";
+
+    /// Returns the traced precompile calls, in execution order.
+    pub fn into_frames(mut self) -> Vec<PrecompileCallFrame> {
+        std::mem::take(&mut self.frames)
+    }
+
+    /// Removes the recorder of the current thread and keeps its frames.
+    fn stop_recording(&mut self) {
+        self.recording = None;
+        if let Some(recorder) = RECORDER.with(|recorder| recorder.borrow_mut().take()) {
+            self.frames.extend(recorder.frames);
+        }
+    }
+}
+
+impl<CTX: ContextTr> Inspector<CTX> for PrecompileInspector {
+    fn call(&mut self, _context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.depth += 1;
+        if self.recording.is_none() && storage_layout(inputs.bytecode_address).is_some() {
+            RECORDER.with(|recorder| *recorder.borrow_mut() = Some(Recorder::default()));
+            self.recording = Some(self.depth);
+        }
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, _outcome: &mut CallOutcome) {
+        if self.recording == Some(self.depth) {
+            self.stop_recording();
+        }
+        self.depth -= 1;
+    }
+}
+
+impl Drop for PrecompileInspector {
+    fn drop(&mut self) {
+        if self.recording.is_some() {
+            self.stop_recording();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::StorageKey;
+    use alloy::primitives::{address, uint};
+
+    fn recording<R>(f: impl FnOnce() -> R) -> (R, Vec<PrecompileCallFrame>) {
+        RECORDER.with(|recorder| *recorder.borrow_mut() = Some(Recorder::default()));
+        let result = f();
+        let recorder = RECORDER
+            .with(|recorder| recorder.borrow_mut().take())
+            .unwrap();
+        (result, recorder.frames)
+    }
+
+    fn sload(address: Address) {
+        record(|recorder| {
+            recorder.storage(address, StorageOp::Sload, U256::ZERO, U256::ZERO, None)
+        });
+    }
+
+    #[test]
+    fn test_nested_frames_follow_calls() {
+        let caller = address!("0x1111111111111111111111111111111111111111");
+        let token = address!("0x20C0000000000000000000000000000000000001");
+        let registry = TIP403_REGISTRY_ADDRESS;
+
+        let ((), frames) = recording(|| {
+            let root = enter(Some(caller), STABLECOIN_EXCHANGE_ADDRESS, || {
+                Bytes::from_static(&[1])
+            });
+            sload(STABLECOIN_EXCHANGE_ADDRESS);
+            {
+                let transfer = enter(None, token, || Bytes::from_static(&[2]));
+                sload(token);
+                {
+                    let authorized = enter(None, registry, || Bytes::from_static(&[3]));
+                    sload(registry);
+                    record(|recorder| recorder.gas(100));
+                    authorized
+                        .unwrap()
+                        .exit(Some(Bytes::from_static(&[4])), None, None);
+                }
+                // Storage of another account accessed without a call stays in the current frame.
+                sload(TIP_FEE_MANAGER_ADDRESS);
+                transfer
+                    .unwrap()
+                    .exit(None, Some("reverted".to_string()), None);
+            }
+            record(|recorder| recorder.log(STABLECOIN_EXCHANGE_ADDRESS, &LogData::default()));
+            root.unwrap()
+                .exit(Some(Bytes::from_static(&[5])), None, Some(1_000));
+        });
+
+        assert_eq!(frames.len(), 1);
+        let root = &frames[0];
+        assert_eq!(root.from, caller);
+        assert_eq!(root.to, STABLECOIN_EXCHANGE_ADDRESS);
+        assert_eq!(root.input, Some(Bytes::from_static(&[1])));
+        assert_eq!(root.output, Some(Bytes::from_static(&[5])));
+        assert_eq!(root.gas_used, 1_000);
+        assert_eq!(root.storage.len(), 1);
+        assert_eq!(root.logs.len(), 1);
+        assert_eq!(root.calls.len(), 1);
+
+        let transfer = &root.calls[0];
+        assert_eq!(transfer.from, STABLECOIN_EXCHANGE_ADDRESS);
+        assert_eq!(transfer.to, token);
+        assert_eq!(transfer.input, Some(Bytes::from_static(&[2])));
+        assert_eq!(transfer.output, None);
+        assert_eq!(transfer.error.as_deref(), Some("reverted"));
+        assert_eq!(transfer.gas_used, 100);
+        assert_eq!(
+            transfer
+                .storage
+                .iter()
+                .map(|access| access.address)
+                .collect::<Vec<_>>(),
+            [token, TIP_FEE_MANAGER_ADDRESS]
+        );
+
+        let authorized = &transfer.calls[0];
+        assert_eq!(authorized.from, token);
+        assert_eq!(authorized.to, registry);
+        assert_eq!(authorized.input, Some(Bytes::from_static(&[3])));
+        assert_eq!(authorized.output, Some(Bytes::from_static(&[4])));
+        assert_eq!(authorized.gas_used, 100);
+    }
+
+    #[test]
+    fn test_dropped_frames_are_closed() {
+        let token = address!("0x20C0000000000000000000000000000000000001");
+
+        let ((), frames) = recording(|| {
+            let root = enter(Some(Address::ZERO), TIP_FEE_MANAGER_ADDRESS, Bytes::new);
+            // A frame left open, e.g. by an early return, is closed together with its parent.
+            std::mem::forget(enter(None, token, Bytes::new));
+            sload(token);
+            drop(root);
+        });
+
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].calls.len(), 1);
+        assert_eq!(frames[0].calls[0].storage.len(), 1);
+    }
+
+    #[test]
+    fn test_nothing_is_recorded_without_recorder() {
+        assert!(enter(Some(Address::ZERO), TIP_FEE_MANAGER_ADDRESS, Bytes::new).is_none());
+        sload(TIP_FEE_MANAGER_ADDRESS);
+        assert!(RECORDER.with(|recorder| recorder.borrow().is_none()));
+    }
+
+    #[test]
+    fn test_slot_names_follow_mapping_preimages() {
+        let token = address!("0x20C0000000000000000000000000000000000001");
+        let owner = address!("0x1111111111111111111111111111111111111111");
+        let spender = address!("0x2222222222222222222222222222222222222222");
+        let mut recorder = Recorder::default();
+
+        let outer = owner.mapping_slot(tip20::slots::ALLOWANCES);
+        recorder.preimage(outer, tip20::slots::ALLOWANCES, owner.as_slice());
+        let inner = spender.mapping_slot(outer);
+        recorder.preimage(inner, outer, spender.as_slice());
+
+        assert_eq!(
+            recorder.slot_name(token, inner),
+            Some(format!("allowances[{owner}][{spender}]").to_lowercase())
+        );
+        assert_eq!(recorder.slot_name(token, uint!(12345_U256)), None);
+        assert_eq!(recorder.slot_name(Address::ZERO, inner), None);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    Precompile, fill_precompile_output, input_cost, transaction_context::TransactionContext,
+    unknown_selector, view,
+};
+use alloy::{primitives::Address, sol_types::SolCall};
+use revm::precompile::{PrecompileError, PrecompileResult};
+
+use super::ITransactionContext;
+
+impl Precompile for TransactionContext {
+    fn call(&mut self, calldata: &[u8], _msg_sender: Address) -> PrecompileResult {
+        self.storage
+            .deduct_gas(input_cost(calldata.len()))
+            .map_err(|_| PrecompileError::OutOfGas)?;
+
+        let selector: [u8; 4] = calldata
+            .get(..4)
+            .ok_or_else(|| {
+                PrecompileError::Other("Invalid input: missing function selector".into())
+            })?
+            .try_into()
+            .unwrap();
+
+        let result = match selector {
+            ITransactionContext::getContextCall::SELECTOR => {
+                view::<ITransactionContext::getContextCall>(calldata, |_| self.get_context())
+            }
+            ITransactionContext::feeTokenCall::SELECTOR => {
+                view::<ITransactionContext::feeTokenCall>(calldata, |_| self.fee_token())
+            }
+            ITransactionContext::feePayerCall::SELECTOR => {
+                view::<ITransactionContext::feePayerCall>(calldata, |_| self.fee_payer())
+            }
+            ITransactionContext::signingKeyCall::SELECTOR => {
+                view::<ITransactionContext::signingKeyCall>(calldata, |_| self.signing_key())
+            }
+            ITransactionContext::callIndexCall::SELECTOR => {
+                view::<ITransactionContext::callIndexCall>(calldata, |_| self.call_index())
+            }
+            _ => unknown_selector(selector, self.storage.gas_used(), self.storage.spec()),
+        };
+
+        result.map(|res| fill_precompile_output(res, &mut self.storage))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::{StorageCtx, hashmap::HashMapStorageProvider},
+        test_util::{assert_full_coverage, check_selector_coverage},
+        transaction_context::{Context, SignatureType},
+    };
+    use alloy::primitives::U256;
+    use tempo_contracts::precompiles::ITransactionContext::ITransactionContextCalls;
+
+    #[test]
+    fn test_transaction_context_selector_coverage() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        StorageCtx::enter(&mut storage, || {
+            let mut context = TransactionContext::new();
+
+            let unsupported = check_selector_coverage(
+                &mut context,
+                ITransactionContextCalls::SELECTORS,
+                "ITransactionContext",
+                ITransactionContextCalls::name_by_selector,
+            );
+
+            assert_full_coverage([unsupported]);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_signing_key_call() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        StorageCtx::enter(&mut storage, || {
+            let mut context = TransactionContext::new();
+            let key_id = Address::random();
+            context.set_context(Context {
+                feeToken: Address::random(),
+                feePayer: Address::random(),
+                keyId: key_id,
+                signatureType: SignatureType::P256,
+                isAccessKey: true,
+                validAfter: 0,
+                validBefore: 0,
+                nonceKey: U256::ZERO,
+                callIndex: 0,
+            })?;
+
+            let calldata = ITransactionContext::signingKeyCall {}.abi_encode();
+            let output = context.call(&calldata, Address::ZERO)?;
+
+            assert!(!output.reverted);
+            let decoded = ITransactionContext::signingKeyCall::abi_decode_returns(&output.bytes)?;
+            assert_eq!(decoded.keyId, key_id);
+            assert_eq!(decoded.signatureType, SignatureType::P256);
+            assert!(decoded.isAccessKey);
+
+            Ok(())
+        })
+    }
+}
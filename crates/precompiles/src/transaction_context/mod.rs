@@ -0,0 +1,139 @@
+pub mod dispatch;
+
+pub use tempo_contracts::precompiles::{
+    ITransactionContext,
+    ITransactionContext::{Context, SignatureType, signingKeyReturn},
+};
+use tempo_precompiles_macros::contract;
+
+use crate::{
+    TRANSACTION_CONTEXT_ADDRESS,
+    error::{Result, TempoPrecompileError},
+    storage::Handler,
+};
+use alloy::primitives::{Address, U256};
+
+/// Read-only view of the context of the transaction being executed.
+///
+/// All fields live in transient storage: they are written by the handler before the transaction
+/// executes and are cleared when it ends.
+#[contract(addr = TRANSACTION_CONTEXT_ADDRESS)]
+pub struct TransactionContext {
+    fee_token: Address,
+    fee_payer: Address,
+    key_id: Address,
+    signature_type: u8,
+    is_access_key: bool,
+    valid_after: u64,
+    valid_before: u64,
+    nonce_key: U256,
+    call_index: u64,
+}
+
+impl TransactionContext {
+    /// Returns the full context of the current transaction.
+    pub fn get_context(&self) -> Result<Context> {
+        let signing_key = self.signing_key()?;
+        Ok(Context {
+            feeToken: self.fee_token()?,
+            feePayer: self.fee_payer()?,
+            keyId: signing_key.keyId,
+            signatureType: signing_key.signatureType,
+            isAccessKey: signing_key.isAccessKey,
+            validAfter: self.valid_after.t_read()?,
+            validBefore: self.valid_before.t_read()?,
+            nonceKey: self.nonce_key.t_read()?,
+            callIndex: self.call_index()?,
+        })
+    }
+
+    /// Returns the fee token of the current transaction.
+    pub fn fee_token(&self) -> Result<Address> {
+        self.fee_token.t_read()
+    }
+
+    /// Returns the fee payer of the current transaction.
+    pub fn fee_payer(&self) -> Result<Address> {
+        self.fee_payer.t_read()
+    }
+
+    /// Returns the key that signed the current transaction.
+    pub fn signing_key(&self) -> Result<signingKeyReturn> {
+        let signature_type = SignatureType::try_from(self.signature_type.t_read()?)
+            .map_err(|_| TempoPrecompileError::Fatal("invalid signature type".to_string()))?;
+        Ok(signingKeyReturn {
+            keyId: self.key_id.t_read()?,
+            signatureType: signature_type,
+            isAccessKey: self.is_access_key.t_read()?,
+        })
+    }
+
+    /// Returns the index of the currently executing call within the transaction's batch.
+    pub fn call_index(&self) -> Result<u64> {
+        self.call_index.t_read()
+    }
+
+    /// Internal: Set the context of the transaction (called during transaction validation)
+    ///
+    /// This must be called by the handler BEFORE the transaction is executed.
+    pub fn set_context(&mut self, context: Context) -> Result<()> {
+        self.fee_token.t_write(context.feeToken)?;
+        self.fee_payer.t_write(context.feePayer)?;
+        self.key_id.t_write(context.keyId)?;
+        self.signature_type.t_write(context.signatureType.into())?;
+        self.is_access_key.t_write(context.isAccessKey)?;
+        self.valid_after.t_write(context.validAfter)?;
+        self.valid_before.t_write(context.validBefore)?;
+        self.nonce_key.t_write(context.nonceKey)?;
+        self.call_index.t_write(context.callIndex)
+    }
+
+    /// Internal: Set the index of the call about to be executed within the transaction's batch.
+    pub fn set_call_index(&mut self, index: u64) -> Result<()> {
+        self.call_index.t_write(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{StorageCtx, hashmap::HashMapStorageProvider};
+
+    #[test]
+    fn test_context_roundtrip() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1);
+        StorageCtx::enter(&mut storage, || {
+            let mut context = TransactionContext::new();
+            assert_eq!(context.fee_token()?, Address::ZERO);
+            assert_eq!(context.call_index()?, 0);
+
+            let expected = Context {
+                feeToken: Address::random(),
+                feePayer: Address::random(),
+                keyId: Address::random(),
+                signatureType: SignatureType::WebAuthn,
+                isAccessKey: true,
+                validAfter: 100,
+                validBefore: 200,
+                nonceKey: U256::from(7),
+                callIndex: 0,
+            };
+            context.set_context(expected.clone())?;
+            context.set_call_index(2)?;
+
+            assert_eq!(
+                context.get_context()?,
+                Context {
+                    callIndex: 2,
+                    ..expected
+                }
+            );
+            assert_eq!(
+                context.signing_key()?.signatureType,
+                SignatureType::WebAuthn
+            );
+
+            Ok(())
+        })
+    }
+}
@@ -1,5 +1,8 @@
 use super::{IValidatorConfig, ValidatorConfig};
-use crate::{Precompile, fill_precompile_output, input_cost, mutate_void, unknown_selector, view};
+use crate::{
+    Precompile, error::TempoPrecompileError, fill_precompile_output, input_cost, mutate_void,
+    unknown_selector, view,
+};
 use alloy::{primitives::Address, sol_types::SolCall};
 use revm::precompile::{PrecompileError, PrecompileResult};
 
@@ -25,6 +28,17 @@ impl Precompile for ValidatorConfig {
             IValidatorConfig::getValidatorsCall::SELECTOR => {
                 view::<IValidatorConfig::getValidatorsCall>(calldata, |_call| self.get_validators())
             }
+            IValidatorConfig::getNextPublicKeyCall::SELECTOR => {
+                view::<IValidatorConfig::getNextPublicKeyCall>(calldata, |call| {
+                    if self.storage.spec().is_allegro_moderato() {
+                        self.get_next_public_key(call.validator)
+                    } else {
+                        Err(TempoPrecompileError::UnknownFunctionSelector(
+                            IValidatorConfig::getNextPublicKeyCall::SELECTOR,
+                        ))
+                    }
+                })
+            }
 
             // Mutate functions
             IValidatorConfig::addValidatorCall::SELECTOR => {
@@ -198,9 +212,40 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_get_next_public_key_dispatch_requires_allegro_moderato() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1).with_spec(TempoHardfork::Allegretto);
+        let sender = Address::random();
+        StorageCtx::enter(&mut storage, || {
+            let mut validator_config = ValidatorConfig::new();
+            validator_config.initialize(Address::random())?;
+
+            let calldata =
+                IValidatorConfig::getNextPublicKeyCall { validator: sender }.abi_encode();
+            let result = validator_config.call(&calldata, sender)?;
+            assert!(result.reverted);
+            Ok::<_, eyre::Report>(())
+        })?;
+
+        storage.set_spec(TempoHardfork::AllegroModerato);
+        StorageCtx::enter(&mut storage, || {
+            let mut validator_config = ValidatorConfig::new();
+
+            let calldata =
+                IValidatorConfig::getNextPublicKeyCall { validator: sender }.abi_encode();
+            let result = validator_config.call(&calldata, sender)?;
+            assert!(!result.reverted);
+            assert_eq!(
+                FixedBytes::<32>::abi_decode(&result.bytes)?,
+                FixedBytes::ZERO
+            );
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_selector_coverage() -> eyre::Result<()> {
-        let mut storage = HashMapStorageProvider::new(1);
+        let mut storage = HashMapStorageProvider::new(1).with_spec(TempoHardfork::AllegroModerato);
         StorageCtx::enter(&mut storage, || {
             let mut validator_config = ValidatorConfig::new();
 
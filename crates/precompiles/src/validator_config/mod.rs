@@ -36,6 +36,10 @@ pub struct ValidatorConfig {
     validator_count: u64,
     validators_array: Vec<Address>,
     validators: Mapping<Address, Validator>,
+    /// The public keys validators registered to rotate to. Zero if there is
+    /// no pending key. Kept out of [`Validator`] so that reading a validator
+    /// entry costs the same as before Allegro-Moderato.
+    next_public_keys: Mapping<Address, B256>,
 }
 
 impl ValidatorConfig {
@@ -115,6 +119,11 @@ impl ValidatorConfig {
         Ok(validators)
     }
 
+    /// Get the public key a validator registered to rotate to (view function)
+    pub fn get_next_public_key(&self, validator: Address) -> Result<B256> {
+        self.next_public_keys.at(validator).read()
+    }
+
     /// Add a new validator (owner only)
     pub fn add_validator(
         &mut self,
@@ -216,8 +225,22 @@ impl ValidatorConfig {
             )
         })?;
 
+        // Since Allegro-Moderato, a new public key only takes effect at the
+        // next epoch boundary, so that the current key keeps its share until
+        // the next key was dealt one.
+        let public_key = if self.storage.spec().is_allegro_moderato() {
+            self.register_next_public_key(
+                sender,
+                call.newValidatorAddress,
+                old_validator.public_key,
+                call.publicKey,
+            )?
+        } else {
+            call.publicKey
+        };
+
         let updated_validator = Validator {
-            public_key: call.publicKey,
+            public_key,
             active: old_validator.active,
             index: old_validator.index,
             validator_address: call.newValidatorAddress,
@@ -230,6 +253,38 @@ impl ValidatorConfig {
             .write(updated_validator)
     }
 
+    /// Registers `public_key` as the next public key of the validator moving
+    /// from `sender` to `new_address` and returns its current public key.
+    ///
+    /// Passing the current key leaves a pending key untouched. Passing the
+    /// pending key again promotes it to the current key, which does not change
+    /// the key consensus uses for the validator.
+    fn register_next_public_key(
+        &mut self,
+        sender: Address,
+        new_address: Address,
+        current: B256,
+        public_key: B256,
+    ) -> Result<B256> {
+        let mut next = self.next_public_keys.at(sender).read()?;
+        if new_address != sender {
+            self.next_public_keys.at(sender).delete()?;
+        }
+
+        let current = if public_key == current {
+            current
+        } else if public_key == next {
+            next = B256::ZERO;
+            public_key
+        } else {
+            next = public_key;
+            current
+        };
+
+        self.next_public_keys.at(new_address).write(next)?;
+        Ok(current)
+    }
+
     /// Change validator active status (owner only)
     pub fn change_validator_status(
         &mut self,
@@ -267,6 +322,7 @@ mod tests {
     use crate::storage::{StorageCtx, hashmap::HashMapStorageProvider};
     use alloy::primitives::Address;
     use alloy_primitives::FixedBytes;
+    use tempo_chainspec::hardfork::TempoHardfork;
 
     #[test]
     fn test_owner_initialization_and_change() -> eyre::Result<()> {
@@ -714,6 +770,99 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_public_key_update_is_pending_since_allegro_moderato() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1).with_spec(TempoHardfork::AllegroModerato);
+        let owner = Address::random();
+        let validator1 = Address::random();
+        let validator2 = Address::random();
+        StorageCtx::enter(&mut storage, || {
+            let mut validator_config = ValidatorConfig::new();
+            validator_config.initialize(owner)?;
+
+            let current = FixedBytes::<32>::from([0x21; 32]);
+            let next = FixedBytes::<32>::from([0x22; 32]);
+            validator_config.add_validator(
+                owner,
+                IValidatorConfig::addValidatorCall {
+                    newValidatorAddress: validator1,
+                    publicKey: current,
+                    inboundAddress: "192.168.1.1:8000".to_string(),
+                    active: true,
+                    outboundAddress: "192.168.1.1:9000".to_string(),
+                },
+            )?;
+            let update = |address, public_key| IValidatorConfig::updateValidatorCall {
+                newValidatorAddress: address,
+                publicKey: public_key,
+                inboundAddress: "192.168.1.1:8000".to_string(),
+                outboundAddress: "192.168.1.1:9000".to_string(),
+            };
+
+            // A new key is registered as the next key; the current key stays.
+            validator_config.update_validator(validator1, update(validator1, next))?;
+            assert_eq!(validator_config.get_validators()?[0].publicKey, current);
+            assert_eq!(validator_config.get_next_public_key(validator1)?, next);
+
+            // Updating with the current key keeps the pending key, and the
+            // pending key moves along with the validator address.
+            validator_config.update_validator(validator1, update(validator2, current))?;
+            assert_eq!(validator_config.get_validators()?[0].publicKey, current);
+            assert_eq!(
+                validator_config.get_next_public_key(validator1)?,
+                B256::ZERO
+            );
+            assert_eq!(validator_config.get_next_public_key(validator2)?, next);
+
+            // Updating with the pending key promotes it.
+            validator_config.update_validator(validator2, update(validator2, next))?;
+            assert_eq!(validator_config.get_validators()?[0].publicKey, next);
+            assert_eq!(
+                validator_config.get_next_public_key(validator2)?,
+                B256::ZERO
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_public_key_update_is_immediate_before_allegro_moderato() -> eyre::Result<()> {
+        let mut storage = HashMapStorageProvider::new(1).with_spec(TempoHardfork::Allegretto);
+        let owner = Address::random();
+        let validator = Address::random();
+        StorageCtx::enter(&mut storage, || {
+            let mut validator_config = ValidatorConfig::new();
+            validator_config.initialize(owner)?;
+
+            let next = FixedBytes::<32>::from([0x22; 32]);
+            validator_config.add_validator(
+                owner,
+                IValidatorConfig::addValidatorCall {
+                    newValidatorAddress: validator,
+                    publicKey: FixedBytes::<32>::from([0x21; 32]),
+                    inboundAddress: "192.168.1.1:8000".to_string(),
+                    active: true,
+                    outboundAddress: "192.168.1.1:9000".to_string(),
+                },
+            )?;
+            validator_config.update_validator(
+                validator,
+                IValidatorConfig::updateValidatorCall {
+                    newValidatorAddress: validator,
+                    publicKey: next,
+                    inboundAddress: "192.168.1.1:8000".to_string(),
+                    outboundAddress: "192.168.1.1:9000".to_string(),
+                },
+            )?;
+
+            assert_eq!(validator_config.get_validators()?[0].publicKey, next);
+            assert_eq!(validator_config.get_next_public_key(validator)?, B256::ZERO);
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_ipv4_with_port_is_host_port() {
         ensure_address_is_ip_port("127.0.0.1:8000").unwrap();
@@ -5,10 +5,12 @@ use reth_primitives_traits::InMemorySize;
 
 /// Tempo block header.
 ///
-/// Encoded as `rlp([inner, general_gas_limit])` meaning that any new
-/// fields added to the inner header will only affect the first list element.
+/// Encoded as `rlp([general_gas_limit, shared_gas_limit, timestamp_millis_part, inner,
+/// lane_fees?])` meaning that any new fields added to the inner header will only affect the
+/// fourth list element. `lane_fees` is omitted from the encoding if not set, so headers from
+/// before Allegro-Moderato keep their hash.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, RlpEncodable, RlpDecodable)]
-#[cfg_attr(feature = "reth-codec", derive(reth_codecs::Compact))]
+#[rlp(trailing)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 #[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
@@ -32,6 +34,35 @@ pub struct TempoHeader {
     /// Inner Ethereum [`Header`].
     #[cfg_attr(feature = "serde", serde(flatten))]
     pub inner: Header,
+
+    /// Fee market state of the payment and general lanes.
+    ///
+    /// Set for all blocks starting with Allegro-Moderato.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub lane_fees: Option<LaneFees>,
+}
+
+/// Fee market state of the payment and general lanes of a block.
+///
+/// The base fee of the general lane is the `base_fee_per_gas` of the inner header, so that it is
+/// picked up by tooling unaware of lanes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "reth-codec", derive(reth_codecs::Compact))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+pub struct LaneFees {
+    /// Base fee per gas of the payment lane.
+    #[cfg_attr(feature = "serde", serde(with = "alloy_serde::quantity"))]
+    pub payment_base_fee_per_gas: u64,
+
+    /// Gas used by the general lane, i.e. by the non-payment transactions outside of subblocks.
+    #[cfg_attr(feature = "serde", serde(with = "alloy_serde::quantity"))]
+    pub general_gas_used: u64,
+
+    /// Gas used by the payment lane, i.e. by the payment transactions outside of subblocks.
+    #[cfg_attr(feature = "serde", serde(with = "alloy_serde::quantity"))]
+    pub payment_gas_used: u64,
 }
 
 impl TempoHeader {
@@ -42,6 +73,23 @@ impl TempoHeader {
             .saturating_mul(1000)
             .saturating_add(self.timestamp_millis_part)
     }
+
+    /// Returns the base fee per gas of the payment lane.
+    ///
+    /// Before Allegro-Moderato both lanes share the base fee of the inner header.
+    pub fn payment_base_fee_per_gas(&self) -> Option<u64> {
+        self.lane_fees
+            .map(|fees| fees.payment_base_fee_per_gas)
+            .or(self.inner.base_fee_per_gas)
+    }
+
+    /// Returns the gas left to the payment lane by the general lane and the subblocks.
+    pub fn payment_gas_limit(&self) -> u64 {
+        self.inner
+            .gas_limit
+            .saturating_sub(self.shared_gas_limit)
+            .saturating_sub(self.general_gas_limit)
+    }
 }
 
 impl AsRef<Self> for TempoHeader {
@@ -146,11 +194,17 @@ impl InMemorySize for TempoHeader {
             general_gas_limit,
             timestamp_millis_part,
             shared_gas_limit,
+            lane_fees,
         } = self;
         inner.size()
             + general_gas_limit.size()
             + timestamp_millis_part.size()
             + shared_gas_limit.size()
+            + lane_fees.map_or(0, |fees| {
+                fees.payment_base_fee_per_gas.size()
+                    + fees.general_gas_used.size()
+                    + fees.payment_gas_used.size()
+            })
     }
 }
 
@@ -184,6 +238,67 @@ impl reth_primitives_traits::header::HeaderMut for TempoHeader {
     }
 }
 
+/// [`TempoHeader`] in the field order of its database encoding.
+///
+/// Fields added to the header must precede `inner`, whose encoding is not self-delimiting, and be
+/// optional, so that previously stored headers still decode.
+#[cfg(feature = "reth-codec")]
+#[derive(reth_codecs::Compact)]
+struct CompactTempoHeader {
+    general_gas_limit: u64,
+    shared_gas_limit: u64,
+    timestamp_millis_part: u64,
+    lane_fees: Option<LaneFees>,
+    inner: Header,
+}
+
+#[cfg(feature = "reth-codec")]
+impl reth_codecs::Compact for TempoHeader {
+    fn to_compact<B>(&self, buf: &mut B) -> usize
+    where
+        B: alloy_primitives::bytes::BufMut + AsMut<[u8]>,
+    {
+        let Self {
+            general_gas_limit,
+            shared_gas_limit,
+            timestamp_millis_part,
+            inner,
+            lane_fees,
+        } = self.clone();
+        CompactTempoHeader {
+            general_gas_limit,
+            shared_gas_limit,
+            timestamp_millis_part,
+            lane_fees,
+            inner,
+        }
+        .to_compact(buf)
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let (
+            CompactTempoHeader {
+                general_gas_limit,
+                shared_gas_limit,
+                timestamp_millis_part,
+                lane_fees,
+                inner,
+            },
+            buf,
+        ) = CompactTempoHeader::from_compact(buf, len);
+        (
+            Self {
+                general_gas_limit,
+                shared_gas_limit,
+                timestamp_millis_part,
+                inner,
+                lane_fees,
+            },
+            buf,
+        )
+    }
+}
+
 #[cfg(feature = "reth-codec")]
 impl reth_db_api::table::Compress for TempoHeader {
     type Compressed = Vec<u8>;
@@ -200,3 +315,80 @@ impl reth_db_api::table::Decompress for TempoHeader {
         Ok(obj)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rlp::Decodable;
+
+    #[test]
+    fn test_lane_fees_are_omitted_if_unset() {
+        let header = TempoHeader {
+            general_gas_limit: 1,
+            shared_gas_limit: 2,
+            timestamp_millis_part: 3,
+            ..Default::default()
+        };
+        let encoded = alloy_rlp::encode(&header);
+
+        // The list only contains the fields preceding the lane fees
+        let mut buf = encoded.as_slice();
+        let list = alloy_rlp::Header::decode(&mut buf).unwrap();
+        let mut payload = &buf[..list.payload_length];
+        assert_eq!(u64::decode(&mut payload).unwrap(), 1);
+        assert_eq!(u64::decode(&mut payload).unwrap(), 2);
+        assert_eq!(u64::decode(&mut payload).unwrap(), 3);
+        assert_eq!(Header::decode(&mut payload).unwrap(), header.inner);
+        assert!(payload.is_empty());
+
+        let with_lane_fees = TempoHeader {
+            lane_fees: Some(LaneFees {
+                payment_base_fee_per_gas: 4,
+                general_gas_used: 5,
+                payment_gas_used: 6,
+            }),
+            ..header.clone()
+        };
+        let encoded = alloy_rlp::encode(&with_lane_fees);
+        assert_eq!(
+            TempoHeader::decode(&mut encoded.as_slice()).unwrap(),
+            with_lane_fees
+        );
+        assert_ne!(with_lane_fees.hash_slow(), header.hash_slow());
+    }
+
+    #[cfg(feature = "reth-codec")]
+    #[test]
+    fn test_compact_decodes_headers_stored_before_lane_fees() {
+        use reth_codecs::Compact;
+
+        // Stored before the lane fees were added: the bitflags of the three `u64` fields, the
+        // fields themselves and an inner header with all fields unset.
+        let mut blob = alloy_primitives::hex!("1101010203").to_vec();
+        blob.extend([0; 472]);
+
+        let (header, rest) = TempoHeader::from_compact(&blob, blob.len());
+        assert!(rest.is_empty());
+        assert_eq!(
+            header,
+            TempoHeader {
+                general_gas_limit: 1,
+                shared_gas_limit: 2,
+                timestamp_millis_part: 3,
+                inner: Header {
+                    ommers_hash: B256::ZERO,
+                    state_root: B256::ZERO,
+                    transactions_root: B256::ZERO,
+                    receipts_root: B256::ZERO,
+                    ..Default::default()
+                },
+                lane_fees: None,
+            }
+        );
+
+        // Headers without lane fees keep their encoding
+        let mut buf = Vec::new();
+        header.to_compact(&mut buf);
+        assert_eq!(buf, blob);
+    }
+}
@@ -10,18 +10,19 @@ use reth_primitives_traits::NodePrimitives;
 
 pub mod transaction;
 pub use transaction::{
-    AASigned, FEE_TOKEN_TX_TYPE_ID, MAX_WEBAUTHN_SIGNATURE_LENGTH, P256_SIGNATURE_LENGTH,
-    SECP256K1_SIGNATURE_LENGTH, SignatureType, TEMPO_GAS_PRICE_SCALING_FACTOR, TEMPO_TX_TYPE_ID,
-    TempoSignature, TempoTransaction, TempoTxEnvelope, TempoTxType, TxFeeToken,
-    derive_p256_address,
+    AASigned, ENCRYPTED_TX_ADDRESS, ENCRYPTED_TX_TYPE_ID, FEE_TOKEN_TX_TYPE_ID,
+    MAX_WEBAUTHN_SIGNATURE_LENGTH, P256_SIGNATURE_LENGTH, SECP256K1_SIGNATURE_LENGTH,
+    SignatureType, TEMPO_GAS_PRICE_SCALING_FACTOR, TEMPO_TX_TYPE_ID, TempoSignature,
+    TempoTransaction, TempoTxEnvelope, TempoTxType, TxEncrypted, TxFeeToken, derive_p256_address,
 };
 
 mod header;
-pub use header::TempoHeader;
+pub use header::{LaneFees, TempoHeader};
 
 pub mod subblock;
 pub use subblock::{
-    RecoveredSubBlock, SignedSubBlock, SubBlock, SubBlockMetadata, SubBlockVersion,
+    RecoveredSubBlock, SignedSubBlock, SubBlock, SubBlockFeeRecipients, SubBlockMetadata,
+    SubBlockVersion,
 };
 
 /// Tempo block.
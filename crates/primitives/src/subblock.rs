@@ -1,6 +1,6 @@
 use crate::TempoTxEnvelope;
 use alloy_primitives::{Address, B256, Bytes, U256, keccak256, wrap_fixed_bytes};
-use alloy_rlp::{BufMut, Decodable, Encodable, RlpDecodable, RlpEncodable};
+use alloy_rlp::{BufMut, Decodable, Encodable};
 use reth_primitives_traits::{Recovered, crypto::RecoveryError};
 
 /// Magic byte for the subblock signature hash.
@@ -32,6 +32,10 @@ impl PartialValidatorKey {
 pub enum SubBlockVersion {
     /// Subblock version 1.
     V1 = 1,
+    /// Subblock version 2, carrying a fee recipient per transaction.
+    ///
+    /// Only valid after Allegro-Moderato.
+    V2 = 2,
 }
 
 impl From<SubBlockVersion> for u8 {
@@ -46,6 +50,7 @@ impl TryFrom<u8> for SubBlockVersion {
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
             1 => Ok(Self::V1),
+            2 => Ok(Self::V2),
             _ => Err(value),
         }
     }
@@ -69,8 +74,26 @@ impl Decodable for SubBlockVersion {
     }
 }
 
+/// Fee recipients of the transactions of a subblock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubBlockFeeRecipients {
+    /// All transactions pay their fees to the same recipient.
+    Single(Address),
+    /// Every transaction pays its fees to its own recipient, in transaction order.
+    PerTransaction(Vec<Address>),
+}
+
+impl SubBlockFeeRecipients {
+    /// Returns the fee recipient of the transaction at `index` of the subblock.
+    pub fn get(&self, index: usize) -> Option<Address> {
+        match self {
+            Self::Single(recipient) => Some(*recipient),
+            Self::PerTransaction(recipients) => recipients.get(index).copied(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
 pub struct SubBlock {
     /// Version of the subblock.
     pub version: SubBlockVersion,
@@ -78,9 +101,16 @@ pub struct SubBlock {
     /// part of the block building on top of the specified parent.
     pub parent_hash: B256,
     /// Recipient of the fees for the subblock.
+    ///
+    /// For [`SubBlockVersion::V2`] this is the validator's own recipient; the fees of every
+    /// transaction go to its entry in [`SubBlock::transaction_fee_recipients`].
     pub fee_recipient: Address,
     /// Transactions included in the subblock.
     pub transactions: Vec<TempoTxEnvelope>,
+    /// Fee recipient of every transaction, in transaction order.
+    ///
+    /// Empty for [`SubBlockVersion::V1`], which is not encoded with this field.
+    pub transaction_fee_recipients: Vec<Address>,
 }
 
 impl SubBlock {
@@ -92,18 +122,37 @@ impl SubBlock {
         keccak256(&buf)
     }
 
+    /// Returns the fee recipients of the subblock's transactions.
+    pub fn fee_recipients(&self) -> SubBlockFeeRecipients {
+        match self.version {
+            SubBlockVersion::V1 => SubBlockFeeRecipients::Single(self.fee_recipient),
+            SubBlockVersion::V2 => {
+                SubBlockFeeRecipients::PerTransaction(self.transaction_fee_recipients.clone())
+            }
+        }
+    }
+
     fn rlp_encode_fields(&self, out: &mut dyn BufMut) {
         self.version.encode(out);
         self.parent_hash.encode(out);
         self.fee_recipient.encode(out);
         self.transactions.encode(out);
+        if self.version == SubBlockVersion::V2 {
+            self.transaction_fee_recipients.encode(out);
+        }
     }
 
     fn rlp_encoded_fields_length(&self) -> usize {
+        let fee_recipients_length = if self.version == SubBlockVersion::V2 {
+            self.transaction_fee_recipients.length()
+        } else {
+            0
+        };
         self.version.length()
             + self.parent_hash.length()
             + self.fee_recipient.length()
             + self.transactions.length()
+            + fee_recipients_length
     }
 
     fn rlp_header(&self) -> alloy_rlp::Header {
@@ -114,11 +163,21 @@ impl SubBlock {
     }
 
     fn rlp_decode_fields(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let version = Decodable::decode(buf)?;
+        let parent_hash = Decodable::decode(buf)?;
+        let fee_recipient = Decodable::decode(buf)?;
+        let transactions: Vec<TempoTxEnvelope> = Decodable::decode(buf)?;
+        let fee_recipients = match version {
+            SubBlockVersion::V1 => Vec::new(),
+            SubBlockVersion::V2 => decode_fee_recipients(buf, transactions.len())?,
+        };
+
         Ok(Self {
-            version: Decodable::decode(buf)?,
-            parent_hash: Decodable::decode(buf)?,
-            fee_recipient: Decodable::decode(buf)?,
-            transactions: Decodable::decode(buf)?,
+            version,
+            parent_hash,
+            fee_recipient,
+            transactions,
+            transaction_fee_recipients: fee_recipients,
         })
     }
 
@@ -135,6 +194,39 @@ impl Encodable for SubBlock {
     }
 }
 
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for SubBlock {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let version = u.arbitrary()?;
+        let transactions: Vec<TempoTxEnvelope> = u.arbitrary()?;
+        let fee_recipients = match version {
+            SubBlockVersion::V1 => Vec::new(),
+            SubBlockVersion::V2 => (0..transactions.len())
+                .map(|_| u.arbitrary())
+                .collect::<arbitrary::Result<_>>()?,
+        };
+
+        Ok(Self {
+            version,
+            parent_hash: u.arbitrary()?,
+            fee_recipient: u.arbitrary()?,
+            transactions,
+            transaction_fee_recipients: fee_recipients,
+        })
+    }
+}
+
+/// Decodes the per-transaction fee recipients of a V2 subblock with `transactions` transactions.
+fn decode_fee_recipients(buf: &mut &[u8], transactions: usize) -> alloy_rlp::Result<Vec<Address>> {
+    let fee_recipients: Vec<Address> = Decodable::decode(buf)?;
+    if fee_recipients.len() != transactions {
+        return Err(alloy_rlp::Error::Custom(
+            "subblock fee recipients do not match its transactions",
+        ));
+    }
+    Ok(fee_recipients)
+}
+
 /// A subblock with a signature.
 #[derive(Debug, Clone, derive_more::Deref, derive_more::DerefMut, PartialEq, Eq)]
 #[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
@@ -263,12 +355,13 @@ impl RecoveredSubBlock {
             fee_recipient: self.fee_recipient,
             version: self.version,
             signature: self.signature.clone(),
+            transaction_fee_recipients: self.inner.transaction_fee_recipients.clone(),
         }
     }
 }
 
 /// Metadata for an included subblock.
-#[derive(Debug, Clone, RlpEncodable, RlpDecodable)]
+#[derive(Debug, Clone)]
 pub struct SubBlockMetadata {
     /// Version of the subblock.
     pub version: SubBlockVersion,
@@ -278,4 +371,168 @@ pub struct SubBlockMetadata {
     pub fee_recipient: Address,
     /// Signature of the subblock.
     pub signature: Bytes,
+    /// Fee recipient of every transaction of the subblock, in transaction order.
+    ///
+    /// Empty for [`SubBlockVersion::V1`], which is not encoded with this field.
+    pub transaction_fee_recipients: Vec<Address>,
+}
+
+impl SubBlockMetadata {
+    /// Returns the fee recipients of the subblock's transactions.
+    pub fn fee_recipients(&self) -> SubBlockFeeRecipients {
+        match self.version {
+            SubBlockVersion::V1 => SubBlockFeeRecipients::Single(self.fee_recipient),
+            SubBlockVersion::V2 => {
+                SubBlockFeeRecipients::PerTransaction(self.transaction_fee_recipients.clone())
+            }
+        }
+    }
+
+    fn rlp_encoded_fields_length(&self) -> usize {
+        let fee_recipients_length = if self.version == SubBlockVersion::V2 {
+            self.transaction_fee_recipients.length()
+        } else {
+            0
+        };
+        self.version.length()
+            + self.validator.length()
+            + self.fee_recipient.length()
+            + self.signature.length()
+            + fee_recipients_length
+    }
+
+    fn rlp_header(&self) -> alloy_rlp::Header {
+        alloy_rlp::Header {
+            list: true,
+            payload_length: self.rlp_encoded_fields_length(),
+        }
+    }
+}
+
+impl Encodable for SubBlockMetadata {
+    fn encode(&self, out: &mut dyn BufMut) {
+        self.rlp_header().encode(out);
+        self.version.encode(out);
+        self.validator.encode(out);
+        self.fee_recipient.encode(out);
+        self.signature.encode(out);
+        if self.version == SubBlockVersion::V2 {
+            self.transaction_fee_recipients.encode(out);
+        }
+    }
+
+    fn length(&self) -> usize {
+        self.rlp_header().length_with_payload()
+    }
+}
+
+impl Decodable for SubBlockMetadata {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = alloy_rlp::Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+
+        let remaining = buf.len();
+
+        let version = Decodable::decode(buf)?;
+        let validator = Decodable::decode(buf)?;
+        let fee_recipient = Decodable::decode(buf)?;
+        let signature = Decodable::decode(buf)?;
+        // The number of transactions is only known once the subblock is matched against the
+        // block, so only the shape of the field is checked here.
+        let fee_recipients = match version {
+            SubBlockVersion::V1 => Vec::new(),
+            SubBlockVersion::V2 => Decodable::decode(buf)?,
+        };
+
+        if buf.len() + header.payload_length != remaining {
+            return Err(alloy_rlp::Error::UnexpectedLength);
+        }
+
+        Ok(Self {
+            version,
+            validator,
+            fee_recipient,
+            signature,
+            transaction_fee_recipients: fee_recipients,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(version: SubBlockVersion, fee_recipients: Vec<Address>) -> SubBlockMetadata {
+        SubBlockMetadata {
+            version,
+            validator: B256::repeat_byte(1),
+            fee_recipient: Address::repeat_byte(2),
+            signature: Bytes::from_static(&[3; 64]),
+            transaction_fee_recipients: fee_recipients,
+        }
+    }
+
+    #[test]
+    fn v1_metadata_encoding_is_unchanged() {
+        #[derive(alloy_rlp::RlpEncodable)]
+        struct V1Metadata {
+            version: SubBlockVersion,
+            validator: B256,
+            fee_recipient: Address,
+            signature: Bytes,
+        }
+
+        let metadata = metadata(SubBlockVersion::V1, Vec::new());
+        let encoded = alloy_rlp::encode(&metadata);
+        assert_eq!(
+            encoded,
+            alloy_rlp::encode(V1Metadata {
+                version: metadata.version,
+                validator: metadata.validator,
+                fee_recipient: metadata.fee_recipient,
+                signature: metadata.signature.clone(),
+            })
+        );
+        assert_eq!(
+            SubBlockMetadata::decode(&mut encoded.as_slice())
+                .unwrap()
+                .fee_recipients(),
+            SubBlockFeeRecipients::Single(metadata.fee_recipient)
+        );
+    }
+
+    #[test]
+    fn v2_metadata_roundtrip() {
+        let recipients = vec![Address::repeat_byte(4), Address::repeat_byte(5)];
+        let metadata = metadata(SubBlockVersion::V2, recipients.clone());
+        let encoded = alloy_rlp::encode(&metadata);
+        assert_eq!(encoded.len(), metadata.length());
+
+        let decoded = SubBlockMetadata::decode(&mut encoded.as_slice()).unwrap();
+        assert_eq!(
+            decoded.fee_recipients(),
+            SubBlockFeeRecipients::PerTransaction(recipients)
+        );
+        assert_eq!(decoded.fee_recipients().get(2), None);
+    }
+
+    #[test]
+    fn v2_subblock_requires_recipient_per_transaction() {
+        let subblock = SubBlock {
+            version: SubBlockVersion::V2,
+            parent_hash: B256::repeat_byte(1),
+            fee_recipient: Address::repeat_byte(2),
+            transactions: Vec::new(),
+            transaction_fee_recipients: vec![Address::repeat_byte(3)],
+        };
+        let signed = SignedSubBlock {
+            inner: subblock,
+            signature: Bytes::from_static(&[3; 64]),
+        };
+
+        let encoded = alloy_rlp::encode(&signed);
+        assert!(SignedSubBlock::decode(&mut encoded.as_slice()).is_err());
+    }
 }
@@ -0,0 +1,296 @@
+//! EIP-712 typed-data signing for [`TempoTransaction`] and [`KeyAuthorization`].
+//!
+//! Signatures flagged with [`SIGNATURE_TYPE_EIP712`](super::tt_signature::SIGNATURE_TYPE_EIP712)
+//! commit to the EIP-712 hash of the typed data defined here instead of the keccak of the RLP
+//! encoding, so that hardware and browser wallets can display what the user is signing.
+//!
+//! Fields that cannot be meaningfully displayed by wallets (access list, authorization list and
+//! the signed key authorization of a transaction) are committed to by the keccak of their RLP
+//! encoding.
+
+use super::{KeyAuthorization, TempoTransaction};
+use alloy_primitives::{Address, B256, U256, keccak256};
+use alloy_sol_types::{Eip712Domain, SolStruct};
+use std::borrow::Cow;
+
+/// Name of the EIP-712 signing domain
+pub const EIP712_DOMAIN_NAME: &str = "Tempo";
+
+/// Version of the EIP-712 signing domain
+pub const EIP712_DOMAIN_VERSION: &str = "1";
+
+mod typed {
+    alloy_sol_types::sol! {
+        /// EIP-712 representation of a [`TokenLimit`](crate::transaction::TokenLimit)
+        struct TokenLimit {
+            address token;
+            uint256 limit;
+        }
+
+        /// EIP-712 representation of a [`KeyAuthorization`](crate::transaction::KeyAuthorization)
+        ///
+        /// `expiry` is 0 and `hasExpiry` is false if the key never expires, `limits` is empty if
+        /// `enforceLimits` is false.
+        struct KeyAuthorization {
+            uint64 chainId;
+            uint8 keyType;
+            address keyId;
+            bool hasExpiry;
+            uint64 expiry;
+            bool enforceLimits;
+            TokenLimit[] limits;
+        }
+
+        /// EIP-712 representation of a [`Call`](crate::transaction::Call)
+        ///
+        /// `to` is the zero address for contract creations.
+        struct Call {
+            address to;
+            bool isCreate;
+            uint256 value;
+            bytes input;
+        }
+
+        /// EIP-712 representation of a [`TempoTransaction`](crate::transaction::TempoTransaction)
+        ///
+        /// Optional fields are 0 if absent. As with the RLP signature hash, `feeToken` is not
+        /// committed to by sponsored transactions.
+        struct TempoTransaction {
+            uint64 chainId;
+            uint128 maxPriorityFeePerGas;
+            uint128 maxFeePerGas;
+            uint64 gas;
+            Call[] calls;
+            bytes32 accessListHash;
+            uint256 nonceKey;
+            uint64 nonce;
+            uint64 validBefore;
+            uint64 validAfter;
+            address feeToken;
+            bool sponsored;
+            bytes32 authorizationListHash;
+            bytes32 keyAuthorizationHash;
+        }
+    }
+}
+
+/// Returns the EIP-712 signing domain for the given chain ID.
+pub fn eip712_domain(chain_id: u64) -> Eip712Domain {
+    Eip712Domain::new(
+        Some(Cow::Borrowed(EIP712_DOMAIN_NAME)),
+        Some(Cow::Borrowed(EIP712_DOMAIN_VERSION)),
+        Some(U256::from(chain_id)),
+        None,
+        None,
+    )
+}
+
+/// Computes the EIP-712 signing hash of a [`KeyAuthorization`].
+pub(crate) fn key_authorization_signing_hash(auth: &KeyAuthorization) -> B256 {
+    let typed = typed::KeyAuthorization {
+        chainId: auth.chain_id,
+        keyType: auth.key_type.into(),
+        keyId: auth.key_id,
+        hasExpiry: auth.expiry.is_some(),
+        expiry: auth.expiry.unwrap_or_default(),
+        enforceLimits: auth.limits.is_some(),
+        limits: auth
+            .limits
+            .iter()
+            .flatten()
+            .map(|limit| typed::TokenLimit {
+                token: limit.token,
+                limit: limit.limit,
+            })
+            .collect(),
+    };
+
+    typed.eip712_signing_hash(&eip712_domain(auth.chain_id))
+}
+
+/// Computes the EIP-712 signing hash of a [`TempoTransaction`].
+pub(crate) fn transaction_signing_hash(tx: &TempoTransaction) -> B256 {
+    let sponsored = tx.fee_payer_signature.is_some();
+
+    let typed = typed::TempoTransaction {
+        chainId: tx.chain_id,
+        maxPriorityFeePerGas: tx.max_priority_fee_per_gas,
+        maxFeePerGas: tx.max_fee_per_gas,
+        gas: tx.gas_limit,
+        calls: tx
+            .calls
+            .iter()
+            .map(|call| typed::Call {
+                to: call.to.to().copied().unwrap_or_default(),
+                isCreate: call.to.is_create(),
+                value: call.value,
+                input: call.input.clone(),
+            })
+            .collect(),
+        accessListHash: keccak256(alloy_rlp::encode(&tx.access_list)),
+        nonceKey: tx.nonce_key,
+        nonce: tx.nonce,
+        validBefore: tx.valid_before.unwrap_or_default(),
+        validAfter: tx.valid_after.unwrap_or_default(),
+        // Skip fee_token for sponsored transactions so the user doesn't commit to a fee token
+        feeToken: if sponsored {
+            Address::ZERO
+        } else {
+            tx.fee_token.unwrap_or_default()
+        },
+        sponsored,
+        authorizationListHash: keccak256(alloy_rlp::encode(&tx.tempo_authorization_list)),
+        keyAuthorizationHash: tx
+            .key_authorization
+            .as_ref()
+            .map(|auth| keccak256(alloy_rlp::encode(auth)))
+            .unwrap_or_default(),
+    };
+
+    typed.eip712_signing_hash(&eip712_domain(tx.chain_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{
+        AASigned, Call, Ed25519Signature, Eip712Signature, PrimitiveSignature, SignatureType,
+        TempoSignature, TokenLimit, key_authorization::SignedKeyAuthorization,
+        tt_signature::derive_ed25519_address,
+    };
+    use alloy_consensus::transaction::SignerRecoverable;
+    use alloy_primitives::{B512, Bytes, TxKind};
+    use alloy_rlp::{Decodable, Encodable};
+
+    #[test]
+    fn test_eip712_type_strings() {
+        assert_eq!(
+            typed::KeyAuthorization::eip712_encode_type(),
+            "KeyAuthorization(uint64 chainId,uint8 keyType,address keyId,bool hasExpiry,uint64 expiry,bool enforceLimits,TokenLimit[] limits)TokenLimit(address token,uint256 limit)"
+        );
+        assert_eq!(
+            typed::TempoTransaction::eip712_encode_type(),
+            "TempoTransaction(uint64 chainId,uint128 maxPriorityFeePerGas,uint128 maxFeePerGas,uint64 gas,Call[] calls,bytes32 accessListHash,uint256 nonceKey,uint64 nonce,uint64 validBefore,uint64 validAfter,address feeToken,bool sponsored,bytes32 authorizationListHash,bytes32 keyAuthorizationHash)Call(address to,bool isCreate,uint256 value,bytes input)"
+        );
+    }
+
+    #[test]
+    fn test_eip712_hash_differs_from_rlp_hash() {
+        let auth = KeyAuthorization {
+            chain_id: 1,
+            key_type: SignatureType::P256,
+            key_id: Address::random(),
+            expiry: Some(1000),
+            limits: Some(vec![TokenLimit {
+                token: Address::random(),
+                limit: U256::from(100),
+            }]),
+        };
+        assert_ne!(auth.eip712_signature_hash(), auth.signature_hash());
+
+        // A key that never expires is distinct from one with a zero expiry
+        let no_expiry = KeyAuthorization {
+            expiry: None,
+            ..auth.clone()
+        };
+        let zero_expiry = KeyAuthorization {
+            expiry: Some(0),
+            ..auth.clone()
+        };
+        assert_ne!(
+            no_expiry.eip712_signature_hash(),
+            zero_expiry.eip712_signature_hash()
+        );
+
+        // The domain binds the hash to the chain
+        let other_chain = KeyAuthorization {
+            chain_id: 2,
+            ..auth.clone()
+        };
+        assert_ne!(
+            auth.eip712_signature_hash(),
+            other_chain.eip712_signature_hash()
+        );
+
+        let tx = TempoTransaction {
+            chain_id: 1,
+            calls: vec![Call {
+                to: TxKind::Create,
+                value: U256::ZERO,
+                input: Bytes::from_static(&[0x60, 0x80]),
+            }],
+            ..Default::default()
+        };
+        assert_ne!(tx.eip712_signature_hash(), tx.signature_hash());
+
+        // Calling the zero address is distinct from a contract creation
+        let call_zero = TempoTransaction {
+            calls: vec![Call {
+                to: TxKind::Call(Address::ZERO),
+                ..tx.calls[0].clone()
+            }],
+            ..tx.clone()
+        };
+        assert_ne!(
+            tx.eip712_signature_hash(),
+            call_zero.eip712_signature_hash()
+        );
+    }
+
+    #[test]
+    fn test_eip712_signature_recovery() {
+        use commonware_cryptography::{PrivateKeyExt as _, Signer as _, ed25519};
+
+        let signing_key = ed25519::PrivateKey::from_seed(9);
+        let public_key = B256::from_slice(&signing_key.public_key());
+        let signer = derive_ed25519_address(&public_key);
+        let sign = |hash: B256| {
+            PrimitiveSignature::Ed25519(Ed25519Signature {
+                public_key,
+                signature: B512::from_slice(&signing_key.sign(None, hash.as_slice())),
+            })
+        };
+
+        let auth = KeyAuthorization {
+            chain_id: 1,
+            key_type: SignatureType::Secp256k1,
+            key_id: Address::random(),
+            expiry: None,
+            limits: None,
+        };
+        let signed_auth = auth
+            .clone()
+            .into_eip712_signed(sign(auth.eip712_signature_hash()));
+        assert_eq!(signed_auth.recover_signer().unwrap(), signer);
+
+        // The EIP-712 flag survives an RLP roundtrip
+        let mut buf = Vec::new();
+        signed_auth.encode(&mut buf);
+        assert_eq!(buf.len(), signed_auth.length());
+        let decoded = SignedKeyAuthorization::decode(&mut buf.as_slice()).unwrap();
+        assert_eq!(decoded, signed_auth);
+        assert_eq!(decoded.recover_signer().unwrap(), signer);
+
+        let tx = TempoTransaction {
+            chain_id: 1,
+            gas_limit: 100_000,
+            calls: vec![Call {
+                to: TxKind::Call(Address::random()),
+                value: U256::ZERO,
+                input: Bytes::new(),
+            }],
+            key_authorization: Some(signed_auth),
+            ..Default::default()
+        };
+        let signature = TempoSignature::Eip712(Eip712Signature::new(TempoSignature::Primitive(
+            sign(tx.eip712_signature_hash()),
+        )));
+        let signed = AASigned::new_unhashed(tx.clone(), signature);
+        assert!(signed.uses_eip712());
+        assert_eq!(signed.recover_signer().unwrap(), signer);
+
+        // Without the flag the signature is checked against the RLP hash and fails
+        let unflagged = AASigned::new_unhashed(tx, signed.signature().strip_eip712().clone());
+        assert!(unflagged.recover_signer().is_err());
+    }
+}
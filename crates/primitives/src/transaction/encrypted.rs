@@ -0,0 +1,383 @@
+use alloy_consensus::{
+    SignableTransaction, Signed, Transaction,
+    transaction::{RlpEcdsaDecodableTx, RlpEcdsaEncodableTx},
+};
+use alloy_eips::{Typed2718, eip2930::AccessList, eip7702::SignedAuthorization};
+use alloy_primitives::{
+    Address, B256, Bytes, ChainId, Signature, TxKind, U256, address, keccak256,
+};
+use alloy_rlp::{BufMut, Decodable, Encodable};
+use core::mem;
+
+/// Encrypted transaction type byte (0x79)
+pub const ENCRYPTED_TX_TYPE_ID: u8 = 0x79;
+
+/// Address encrypted transactions are sent to.
+///
+/// The address holds no code: executing an encrypted transaction only pays for its ciphertext and
+/// consumes the sender's nonce. The system transaction releasing the decryption keys of the
+/// encrypted transactions in the parent block is sent to this address too.
+pub const ENCRYPTED_TX_ADDRESS: Address = address!("0xEC70000000000000000000000000000000000000");
+
+/// Magic byte prefixed to the message a decryption key is a threshold signature over.
+const DECRYPTION_TARGET_MAGIC_BYTE: u8 = 0x79;
+
+/// A transaction carrying another, encrypted transaction.
+///
+/// The ciphertext is the signed inner transaction, encrypted to the group public key of the
+/// validators with the decryption target of this transaction (see [`TxEncrypted::decryption_target`]).
+/// Proposers order encrypted transactions without seeing their contents. Once the block including
+/// them is finalized, validators release their shares of the decryption keys, and the inner
+/// transactions are executed at the start of the following block, in the order their ciphertexts
+/// were committed to.
+///
+/// The encrypted transaction itself is a call to [`ENCRYPTED_TX_ADDRESS`] with the ciphertext as
+/// input. It pays for including the ciphertext and consumes the sender's nonce; the inner
+/// transaction pays for its own execution.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reth-codec", derive(reth_codecs::Compact))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[doc(alias = "EncryptedTransaction", alias = "TransactionEncrypted")]
+#[cfg_attr(test, reth_codecs::add_arbitrary_tests(compact, rlp))]
+pub struct TxEncrypted {
+    /// EIP-155: Simple replay attack protection
+    #[cfg_attr(feature = "serde", serde(with = "alloy_serde::quantity"))]
+    pub chain_id: ChainId,
+
+    /// A scalar value equal to the number of transactions sent by the sender
+    #[cfg_attr(feature = "serde", serde(with = "alloy_serde::quantity"))]
+    pub nonce: u64,
+
+    /// Max Priority fee per gas (EIP-1559)
+    #[cfg_attr(feature = "serde", serde(with = "alloy_serde::quantity"))]
+    pub max_priority_fee_per_gas: u128,
+
+    /// Max fee per gas (EIP-1559)
+    #[cfg_attr(feature = "serde", serde(with = "alloy_serde::quantity"))]
+    pub max_fee_per_gas: u128,
+
+    /// Gas limit
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "alloy_serde::quantity", rename = "gas", alias = "gasLimit")
+    )]
+    pub gas_limit: u64,
+
+    /// The encrypted inner transaction
+    // Note: This is at last position for the codecs derive
+    pub ciphertext: Bytes,
+}
+
+impl TxEncrypted {
+    /// Get the transaction type
+    #[doc(alias = "transaction_type")]
+    pub const fn tx_type() -> u8 {
+        ENCRYPTED_TX_TYPE_ID
+    }
+
+    /// Returns the message the decryption key of this transaction is a threshold signature over.
+    ///
+    /// The target only depends on the sender and nonce, so that the inner transaction can be
+    /// encrypted before the outer one is signed. Once the key is released, no other transaction
+    /// with the same target can be executed because the nonce is consumed.
+    pub fn decryption_target(&self, sender: Address) -> B256 {
+        let mut buf = Vec::with_capacity(1 + 8 + 20 + 8);
+        buf.put_u8(DECRYPTION_TARGET_MAGIC_BYTE);
+        buf.put_u64(self.chain_id);
+        buf.put_slice(sender.as_slice());
+        buf.put_u64(self.nonce);
+        keccak256(&buf)
+    }
+
+    /// Calculates a heuristic for the in-memory size of the transaction
+    #[inline]
+    pub fn size(&self) -> usize {
+        mem::size_of::<ChainId>() + // chain_id
+        mem::size_of::<u64>() + // nonce
+        mem::size_of::<u128>() + // max_priority_fee_per_gas
+        mem::size_of::<u128>() + // max_fee_per_gas
+        mem::size_of::<u64>() + // gas_limit
+        self.ciphertext.len() // ciphertext
+    }
+
+    /// Combines this transaction with `signature`, taking `self`. Returns [`Signed`].
+    pub fn into_signed(self, signature: Signature) -> Signed<Self> {
+        let tx_hash = self.tx_hash(&signature);
+        Signed::new_unchecked(self, signature, tx_hash)
+    }
+}
+
+impl RlpEcdsaEncodableTx for TxEncrypted {
+    /// Outputs the length of the transaction's fields, without a RLP header
+    fn rlp_encoded_fields_length(&self) -> usize {
+        self.chain_id.length()
+            + self.nonce.length()
+            + self.max_priority_fee_per_gas.length()
+            + self.max_fee_per_gas.length()
+            + self.gas_limit.length()
+            + self.ciphertext.length()
+    }
+
+    /// Encodes only the transaction's fields into the desired buffer, without a RLP header
+    fn rlp_encode_fields(&self, out: &mut dyn BufMut) {
+        self.chain_id.encode(out);
+        self.nonce.encode(out);
+        self.max_priority_fee_per_gas.encode(out);
+        self.max_fee_per_gas.encode(out);
+        self.gas_limit.encode(out);
+        self.ciphertext.encode(out);
+    }
+}
+
+impl RlpEcdsaDecodableTx for TxEncrypted {
+    const DEFAULT_TX_TYPE: u8 = ENCRYPTED_TX_TYPE_ID;
+
+    /// Decodes the inner TxEncrypted fields from RLP bytes
+    fn rlp_decode_fields(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        Ok(Self {
+            chain_id: Decodable::decode(buf)?,
+            nonce: Decodable::decode(buf)?,
+            max_priority_fee_per_gas: Decodable::decode(buf)?,
+            max_fee_per_gas: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            ciphertext: Decodable::decode(buf)?,
+        })
+    }
+}
+
+impl Transaction for TxEncrypted {
+    #[inline]
+    fn chain_id(&self) -> Option<ChainId> {
+        Some(self.chain_id)
+    }
+
+    #[inline]
+    fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    #[inline]
+    fn gas_limit(&self) -> u64 {
+        self.gas_limit
+    }
+
+    #[inline]
+    fn gas_price(&self) -> Option<u128> {
+        None
+    }
+
+    #[inline]
+    fn max_fee_per_gas(&self) -> u128 {
+        self.max_fee_per_gas
+    }
+
+    #[inline]
+    fn max_priority_fee_per_gas(&self) -> Option<u128> {
+        Some(self.max_priority_fee_per_gas)
+    }
+
+    #[inline]
+    fn max_fee_per_blob_gas(&self) -> Option<u128> {
+        None
+    }
+
+    #[inline]
+    fn priority_fee_or_price(&self) -> u128 {
+        self.max_priority_fee_per_gas
+    }
+
+    fn effective_gas_price(&self, base_fee: Option<u64>) -> u128 {
+        alloy_eips::eip1559::calc_effective_gas_price(
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+            base_fee,
+        )
+    }
+
+    #[inline]
+    fn is_dynamic_fee(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn kind(&self) -> TxKind {
+        TxKind::Call(ENCRYPTED_TX_ADDRESS)
+    }
+
+    #[inline]
+    fn is_create(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn value(&self) -> U256 {
+        U256::ZERO
+    }
+
+    #[inline]
+    fn input(&self) -> &Bytes {
+        &self.ciphertext
+    }
+
+    #[inline]
+    fn access_list(&self) -> Option<&AccessList> {
+        None
+    }
+
+    #[inline]
+    fn blob_versioned_hashes(&self) -> Option<&[B256]> {
+        None
+    }
+
+    #[inline]
+    fn authorization_list(&self) -> Option<&[SignedAuthorization]> {
+        None
+    }
+}
+
+impl Typed2718 for TxEncrypted {
+    fn ty(&self) -> u8 {
+        ENCRYPTED_TX_TYPE_ID
+    }
+}
+
+impl SignableTransaction<Signature> for TxEncrypted {
+    fn set_chain_id(&mut self, chain_id: ChainId) {
+        self.chain_id = chain_id;
+    }
+
+    fn encode_for_signing(&self, out: &mut dyn BufMut) {
+        out.put_u8(Self::tx_type());
+        self.rlp_header().encode(out);
+        self.rlp_encode_fields(out);
+    }
+
+    fn payload_len_for_signature(&self) -> usize {
+        1 + self.rlp_header().length_with_payload()
+    }
+}
+
+impl Encodable for TxEncrypted {
+    fn encode(&self, out: &mut dyn BufMut) {
+        self.rlp_encode(out);
+    }
+
+    fn length(&self) -> usize {
+        self.rlp_encoded_length()
+    }
+}
+
+impl Decodable for TxEncrypted {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        Self::rlp_decode(buf)
+    }
+}
+
+impl reth_primitives_traits::InMemorySize for TxEncrypted {
+    fn size(&self) -> usize {
+        Self::size(self)
+    }
+}
+
+#[cfg(feature = "serde-bincode-compat")]
+impl reth_primitives_traits::serde_bincode_compat::RlpBincode for TxEncrypted {}
+
+/// Encodes the input of the system transaction releasing the decryption keys of the encrypted
+/// transactions in the parent block, in the order of these transactions.
+pub fn encode_decryption_keys(keys: &[Bytes], block_number: u64) -> Bytes {
+    alloy_rlp::encode(keys)
+        .into_iter()
+        .chain(U256::from(block_number).to_be_bytes_vec())
+        .collect()
+}
+
+/// Decodes the decryption keys from the input of a decryption keys system transaction.
+///
+/// Returns `None` if the input is malformed or was not made for `block_number`.
+pub fn decode_decryption_keys(input: &[u8], block_number: u64) -> Option<Vec<Bytes>> {
+    let (mut keys, number) = input.split_at_checked(input.len().checked_sub(U256::BYTES)?)?;
+    if number != U256::from(block_number).to_be_bytes_vec() {
+        return None;
+    }
+    let decoded = Vec::<Bytes>::decode(&mut keys).ok()?;
+    (keys.is_empty() && !decoded.is_empty()).then_some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::transaction::TxHashRef as _;
+    use alloy_eips::{Decodable2718, Encodable2718};
+    use alloy_primitives::{address, bytes};
+
+    use crate::TempoTxEnvelope;
+
+    fn tx() -> TxEncrypted {
+        TxEncrypted {
+            chain_id: 1,
+            nonce: 7,
+            max_priority_fee_per_gas: 1_000_000_000,
+            max_fee_per_gas: 2_000_000_000,
+            gas_limit: 50_000,
+            ciphertext: bytes!("c0ffee"),
+        }
+    }
+
+    #[test]
+    fn encrypted_tx_calls_encrypted_tx_address_with_ciphertext() {
+        let tx = tx();
+        assert_eq!(tx.kind(), TxKind::Call(ENCRYPTED_TX_ADDRESS));
+        assert_eq!(tx.input(), &tx.ciphertext);
+        assert_eq!(tx.value(), U256::ZERO);
+    }
+
+    #[test]
+    fn envelope_roundtrip() {
+        let signed = tx().into_signed(Signature::test_signature());
+        let envelope = TempoTxEnvelope::Encrypted(signed);
+
+        let encoded = envelope.encoded_2718();
+        assert_eq!(encoded[0], ENCRYPTED_TX_TYPE_ID);
+
+        let decoded = TempoTxEnvelope::decode_2718(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded.tx_hash(), envelope.tx_hash());
+        assert!(!decoded.is_payment());
+    }
+
+    #[test]
+    fn decryption_target_depends_on_sender_and_nonce() {
+        let tx = tx();
+        let alice = address!("0x1111111111111111111111111111111111111111");
+        let bob = address!("0x2222222222222222222222222222222222222222");
+
+        assert_ne!(tx.decryption_target(alice), tx.decryption_target(bob));
+        assert_ne!(
+            tx.decryption_target(alice),
+            TxEncrypted { nonce: 8, ..tx() }.decryption_target(alice)
+        );
+        assert_eq!(
+            tx.decryption_target(alice),
+            TxEncrypted {
+                ciphertext: bytes!("beef"),
+                ..tx()
+            }
+            .decryption_target(alice)
+        );
+    }
+
+    #[test]
+    fn decryption_keys_roundtrip() {
+        let keys = vec![bytes!("01"), bytes!("0203")];
+        let input = encode_decryption_keys(&keys, 42);
+
+        assert_eq!(decode_decryption_keys(&input, 42), Some(keys));
+        assert_eq!(decode_decryption_keys(&input, 43), None);
+        assert_eq!(decode_decryption_keys(&input[..16], 42), None);
+        assert_eq!(
+            decode_decryption_keys(&encode_decryption_keys(&[], 42), 42),
+            None
+        );
+    }
+}
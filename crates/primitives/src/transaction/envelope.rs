@@ -1,4 +1,4 @@
-use super::{fee_token::TxFeeToken, tt_signed::AASigned};
+use super::{encrypted::TxEncrypted, fee_token::TxFeeToken, tt_signed::AASigned};
 use crate::{TempoTransaction, subblock::PartialValidatorKey};
 use alloy_consensus::{
     EthereumTxEnvelope, SignableTransaction, Signed, Transaction, TxEip1559, TxEip2930, TxEip7702,
@@ -28,6 +28,7 @@ pub const TEMPO_SYSTEM_TX_SENDER: Address = Address::ZERO;
 /// - EIP-1559 dynamic fee transactions
 /// - EIP-7702 authorization list transactions
 /// - Tempo fee token transactions (0x77)
+/// - Tempo encrypted transactions (0x79)
 #[derive(Clone, Debug, alloy_consensus::TransactionEnvelope)]
 #[envelope(
     tx_type_name = TempoTxType,
@@ -61,6 +62,10 @@ pub enum TempoTxEnvelope {
     /// Tempo fee token transaction (type 0x77)
     #[envelope(ty = 0x77)]
     FeeToken(Signed<TxFeeToken>),
+
+    /// Tempo encrypted transaction (type 0x79)
+    #[envelope(ty = 0x79)]
+    Encrypted(Signed<TxEncrypted>),
 }
 
 impl TryFrom<TxType> for TempoTxType {
@@ -92,6 +97,9 @@ impl TryFrom<TempoTxType> for TxType {
             TempoTxType::AA => {
                 return Err(UnsupportedTransactionType::new(TempoTxType::AA));
             }
+            TempoTxType::Encrypted => {
+                return Err(UnsupportedTransactionType::new(TempoTxType::Encrypted));
+            }
         })
     }
 }
@@ -138,6 +146,7 @@ impl TempoTxEnvelope {
             Self::Eip7702(_) => TempoTxType::Eip7702,
             Self::AA(_) => TempoTxType::AA,
             Self::FeeToken(_) => TempoTxType::FeeToken,
+            Self::Encrypted(_) => TempoTxType::Encrypted,
         }
     }
 
@@ -210,6 +219,8 @@ impl TempoTxEnvelope {
                     .to()
                     .is_some_and(|to| to.starts_with(&TIP20_PAYMENT_PREFIX))
             }),
+            // The contents of encrypted transactions are unknown until they are decrypted.
+            Self::Encrypted(_) => false,
         }
     }
 
@@ -232,6 +243,19 @@ impl TempoTxEnvelope {
         self.as_aa().map(|tx| tx.tx().nonce_key)
     }
 
+    /// Returns the [`TxEncrypted`] transaction if this is an encrypted transaction.
+    pub fn as_encrypted(&self) -> Option<&Signed<TxEncrypted>> {
+        match self {
+            Self::Encrypted(tx) => Some(tx),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this is an encrypted transaction
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, Self::Encrypted(_))
+    }
+
     /// Returns true if this is a Tempo transaction
     pub fn is_aa(&self) -> bool {
         matches!(self, Self::AA(_))
@@ -267,6 +291,9 @@ impl alloy_consensus::transaction::SignerRecoverable for TempoTxEnvelope {
                 alloy_consensus::transaction::SignerRecoverable::recover_signer(tx)
             }
             Self::AA(tx) => alloy_consensus::transaction::SignerRecoverable::recover_signer(tx),
+            Self::Encrypted(tx) => {
+                alloy_consensus::transaction::SignerRecoverable::recover_signer(tx)
+            }
         }
     }
 
@@ -293,6 +320,9 @@ impl alloy_consensus::transaction::SignerRecoverable for TempoTxEnvelope {
             Self::AA(tx) => {
                 alloy_consensus::transaction::SignerRecoverable::recover_signer_unchecked(tx)
             }
+            Self::Encrypted(tx) => {
+                alloy_consensus::transaction::SignerRecoverable::recover_signer_unchecked(tx)
+            }
         }
     }
 }
@@ -306,6 +336,7 @@ impl reth_primitives_traits::InMemorySize for TempoTxEnvelope {
             Self::Eip7702(tx) => reth_primitives_traits::InMemorySize::size(tx),
             Self::AA(tx) => reth_primitives_traits::InMemorySize::size(tx),
             Self::FeeToken(tx) => reth_primitives_traits::InMemorySize::size(tx),
+            Self::Encrypted(tx) => reth_primitives_traits::InMemorySize::size(tx),
         }
     }
 }
@@ -319,6 +350,7 @@ impl alloy_consensus::transaction::TxHashRef for TempoTxEnvelope {
             Self::Eip7702(tx) => tx.hash(),
             Self::AA(tx) => tx.hash(),
             Self::FeeToken(tx) => tx.hash(),
+            Self::Encrypted(tx) => tx.hash(),
         }
     }
 }
@@ -340,6 +372,7 @@ impl fmt::Display for TempoTxType {
             Self::Eip7702 => write!(f, "EIP-7702"),
             Self::AA => write!(f, "AA"),
             Self::FeeToken => write!(f, "FeeToken"),
+            Self::Encrypted => write!(f, "Encrypted"),
         }
     }
 }
@@ -391,6 +424,12 @@ impl From<Signed<TxFeeToken>> for TempoTxEnvelope {
     }
 }
 
+impl From<Signed<TxEncrypted>> for TempoTxEnvelope {
+    fn from(value: Signed<TxEncrypted>) -> Self {
+        Self::Encrypted(value)
+    }
+}
+
 impl From<AASigned> for TempoTxEnvelope {
     fn from(value: AASigned) -> Self {
         Self::AA(value)
@@ -407,6 +446,7 @@ impl TempoTypedTransaction {
             Self::Eip7702(tx) => tx.into_signed(sig).into(),
             Self::AA(tx) => tx.into_signed(sig.into()).into(),
             Self::FeeToken(tx) => tx.into_signed(sig).into(),
+            Self::Encrypted(tx) => tx.into_signed(sig).into(),
         }
     }
 
@@ -419,6 +459,7 @@ impl TempoTypedTransaction {
             Self::Eip7702(tx) => tx,
             Self::AA(tx) => tx,
             Self::FeeToken(tx) => tx,
+            Self::Encrypted(tx) => tx,
         }
     }
 }
@@ -448,6 +489,7 @@ impl From<TempoTxEnvelope> for TempoTypedTransaction {
             TempoTxEnvelope::Eip7702(tx) => Self::Eip7702(tx.into_parts().0),
             TempoTxEnvelope::FeeToken(tx) => Self::FeeToken(tx.into_parts().0),
             TempoTxEnvelope::AA(tx) => Self::AA(tx.into_parts().0),
+            TempoTxEnvelope::Encrypted(tx) => Self::Encrypted(tx.into_parts().0),
         }
     }
 }
@@ -458,6 +500,12 @@ impl From<TxFeeToken> for TempoTypedTransaction {
     }
 }
 
+impl From<TxEncrypted> for TempoTypedTransaction {
+    fn from(value: TxEncrypted) -> Self {
+        Self::Encrypted(value)
+    }
+}
+
 impl From<TempoTransaction> for TempoTypedTransaction {
     fn from(value: TempoTransaction) -> Self {
         Self::AA(value)
@@ -561,6 +609,11 @@ mod codec {
                     let tx = Signed::new_unhashed(tx, signature);
                     (Self::FeeToken(tx), buf)
                 }
+                TempoTxType::Encrypted => {
+                    let (tx, buf) = TxEncrypted::from_compact(buf, buf.len());
+                    let tx = Signed::new_unhashed(tx, signature);
+                    (Self::Encrypted(tx), buf)
+                }
             }
         }
     }
@@ -579,6 +632,7 @@ mod codec {
                     len
                 }
                 Self::FeeToken(tx) => tx.tx().to_compact(buf),
+                Self::Encrypted(tx) => tx.tx().to_compact(buf),
             };
         }
     }
@@ -595,6 +649,7 @@ mod codec {
                     &TEMPO_SYSTEM_TX_SIGNATURE
                 }
                 Self::FeeToken(tx) => tx.signature(),
+                Self::Encrypted(tx) => tx.signature(),
             }
         }
 
@@ -624,6 +679,10 @@ mod codec {
                     buf.put_u8(crate::transaction::FEE_TOKEN_TX_TYPE_ID);
                     COMPACT_EXTENDED_IDENTIFIER_FLAG
                 }
+                Self::Encrypted => {
+                    buf.put_u8(crate::transaction::ENCRYPTED_TX_TYPE_ID);
+                    COMPACT_EXTENDED_IDENTIFIER_FLAG
+                }
             }
         }
 
@@ -643,6 +702,7 @@ mod codec {
                             EIP7702_TX_TYPE_ID => Self::Eip7702,
                             crate::transaction::TEMPO_TX_TYPE_ID => Self::AA,
                             crate::transaction::FEE_TOKEN_TX_TYPE_ID => Self::FeeToken,
+                            crate::transaction::ENCRYPTED_TX_TYPE_ID => Self::Encrypted,
                             _ => panic!("Unsupported TxType identifier: {extended_identifier}"),
                         }
                     }
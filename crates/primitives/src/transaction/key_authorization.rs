@@ -1,8 +1,8 @@
-use super::SignatureType;
+use super::{SignatureType, eip712, tt_signature::SIGNATURE_TYPE_EIP712};
 use crate::transaction::PrimitiveSignature;
 use alloy_consensus::crypto::RecoveryError;
-use alloy_primitives::{Address, B256, U256, keccak256};
-use alloy_rlp::Encodable;
+use alloy_primitives::{Address, B256, Bytes, U256, keccak256};
+use alloy_rlp::{Decodable, Encodable};
 use core::mem;
 use reth_primitives_traits::InMemorySize;
 
@@ -42,7 +42,7 @@ pub struct KeyAuthorization {
     /// Chain ID for replay protection (0 = valid on any chain)
     pub chain_id: u64,
 
-    /// Type of key being authorized (Secp256k1, P256, or WebAuthn)
+    /// Type of key being authorized (Secp256k1, P256, WebAuthn, or Ed25519)
     pub key_type: SignatureType,
 
     /// Key identifier, is the address derived from the public key of the key type.
@@ -68,6 +68,13 @@ impl KeyAuthorization {
         keccak256(&buf)
     }
 
+    /// Computes the EIP-712 typed-data hash for this key authorization.
+    ///
+    /// See [`eip712`](super::eip712) for the typed-data schema.
+    pub fn eip712_signature_hash(&self) -> B256 {
+        eip712::key_authorization_signing_hash(self)
+    }
+
     /// Returns whether this key has unlimited spending (limits is None)
     pub fn has_unlimited_spending(&self) -> bool {
         self.limits.is_none()
@@ -83,6 +90,17 @@ impl KeyAuthorization {
         SignedKeyAuthorization {
             authorization: self,
             signature,
+            eip712: false,
+        }
+    }
+
+    /// Convert the key authorization into a [`SignedKeyAuthorization`] with a signature over
+    /// its [EIP-712 hash](Self::eip712_signature_hash).
+    pub fn into_eip712_signed(self, signature: PrimitiveSignature) -> SignedKeyAuthorization {
+        SignedKeyAuthorization {
+            authorization: self,
+            signature,
+            eip712: true,
         }
     }
 }
@@ -102,20 +120,13 @@ impl InMemorySize for KeyAuthorization {
 }
 
 /// Signed key authorization that can be attached to a transaction.
-#[derive(
-    Clone,
-    Debug,
-    PartialEq,
-    Eq,
-    Hash,
-    alloy_rlp::RlpEncodable,
-    alloy_rlp::RlpDecodable,
-    derive_more::Deref,
-)]
+///
+/// RLP encoding: `[authorization, signature]`, where the signature bytes are prefixed with
+/// [`SIGNATURE_TYPE_EIP712`] if the root key signed the EIP-712 hash of the authorization.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, derive_more::Deref)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 #[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
-#[rlp(trailing)]
 #[cfg_attr(test, reth_codecs::add_arbitrary_tests(compact, rlp))]
 pub struct SignedKeyAuthorization {
     /// Key authorization for provisioning access keys
@@ -125,13 +136,99 @@ pub struct SignedKeyAuthorization {
 
     /// Signature authorizing this key (signed by root key)
     pub signature: PrimitiveSignature,
+
+    /// Whether the signature is over the EIP-712 hash instead of the RLP hash
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "core::ops::Not::not")
+    )]
+    pub eip712: bool,
 }
 
 impl SignedKeyAuthorization {
+    /// Returns the hash that was signed by the root key.
+    pub fn signature_hash(&self) -> B256 {
+        if self.eip712 {
+            self.authorization.eip712_signature_hash()
+        } else {
+            self.authorization.signature_hash()
+        }
+    }
+
     /// Recover the signer of the [`KeyAuthorization`].
     pub fn recover_signer(&self) -> Result<Address, RecoveryError> {
-        self.signature
-            .recover_signer(&self.authorization.signature_hash())
+        self.signature.recover_signer(&self.signature_hash())
+    }
+
+    /// Returns the signature bytes, including the EIP-712 flag prefix if set.
+    fn signature_bytes(&self) -> Bytes {
+        let bytes = self.signature.to_bytes();
+        if !self.eip712 {
+            return bytes;
+        }
+
+        let mut prefixed = Vec::with_capacity(1 + bytes.len());
+        prefixed.push(SIGNATURE_TYPE_EIP712);
+        prefixed.extend_from_slice(&bytes);
+        prefixed.into()
+    }
+
+    fn rlp_payload_length(&self) -> usize {
+        self.authorization.length() + self.signature_bytes().length()
+    }
+}
+
+impl Encodable for SignedKeyAuthorization {
+    fn encode(&self, out: &mut dyn alloy_rlp::BufMut) {
+        alloy_rlp::Header {
+            list: true,
+            payload_length: self.rlp_payload_length(),
+        }
+        .encode(out);
+        self.authorization.encode(out);
+        self.signature_bytes().encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.rlp_payload_length();
+        alloy_rlp::length_of_length(payload_length) + payload_length
+    }
+}
+
+impl Decodable for SignedKeyAuthorization {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = alloy_rlp::Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+        let remaining = buf.len();
+        if header.payload_length > remaining {
+            return Err(alloy_rlp::Error::InputTooShort);
+        }
+
+        let authorization = KeyAuthorization::decode(buf)?;
+        let bytes = Bytes::decode(buf)?;
+
+        if buf.len() + header.payload_length != remaining {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: remaining - buf.len(),
+            });
+        }
+
+        // Same disambiguation as `TempoSignature`: secp256k1 signatures carry no type prefix
+        let eip712 = bytes.len() > 1
+            && bytes.len() != super::tempo_transaction::SECP256K1_SIGNATURE_LENGTH
+            && bytes[0] == SIGNATURE_TYPE_EIP712;
+        let signature_bytes = if eip712 { &bytes[1..] } else { &bytes[..] };
+        let signature =
+            PrimitiveSignature::from_bytes(signature_bytes).map_err(alloy_rlp::Error::Custom)?;
+
+        Ok(Self {
+            authorization,
+            signature,
+            eip712,
+        })
     }
 }
 
@@ -1,3 +1,5 @@
+pub mod eip712;
+pub mod encrypted;
 pub mod envelope;
 pub mod fee_token;
 pub mod key_authorization;
@@ -9,16 +11,18 @@ pub mod tt_signed;
 pub use tt_authorization::{MAGIC, RecoveredTempoAuthorization, TempoSignedAuthorization};
 // Re-export Authorization from alloy for convenience
 pub use tt_signature::{
-    KeychainSignature, PrimitiveSignature, TempoSignature, derive_p256_address,
+    Ed25519Signature, Eip712Signature, KeychainSignature, MultisigSignature, PrimitiveSignature,
+    TempoSignature, derive_ed25519_address, derive_p256_address,
 };
 
 pub use alloy_eips::eip7702::Authorization;
+pub use encrypted::{ENCRYPTED_TX_ADDRESS, ENCRYPTED_TX_TYPE_ID, TxEncrypted};
 pub use envelope::{TempoTxEnvelope, TempoTxType, TempoTypedTransaction};
 pub use fee_token::{FEE_TOKEN_TX_TYPE_ID, TxFeeToken};
 pub use key_authorization::{KeyAuthorization, SignedKeyAuthorization, TokenLimit};
 pub use tempo_transaction::{
-    Call, MAX_WEBAUTHN_SIGNATURE_LENGTH, P256_SIGNATURE_LENGTH, SECP256K1_SIGNATURE_LENGTH,
-    SignatureType, TEMPO_TX_TYPE_ID, TempoTransaction,
+    Call, ED25519_SIGNATURE_LENGTH, MAX_WEBAUTHN_SIGNATURE_LENGTH, P256_SIGNATURE_LENGTH,
+    SECP256K1_SIGNATURE_LENGTH, SignatureType, TEMPO_TX_TYPE_ID, TempoTransaction,
 };
 pub use tt_signed::AASigned;
 
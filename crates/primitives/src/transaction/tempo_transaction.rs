@@ -23,6 +23,7 @@ pub const FEE_PAYER_SIGNATURE_MAGIC_BYTE: u8 = 0x78;
 pub const SECP256K1_SIGNATURE_LENGTH: usize = 65;
 pub const P256_SIGNATURE_LENGTH: usize = 129;
 pub const MAX_WEBAUTHN_SIGNATURE_LENGTH: usize = 2048; // 2KB max
+pub const ED25519_SIGNATURE_LENGTH: usize = 96;
 
 /// Signature type enumeration
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -35,6 +36,11 @@ pub enum SignatureType {
     Secp256k1 = 0,
     P256 = 1,
     WebAuthn = 2,
+    Ed25519 = 3,
+    /// A threshold signature by the co-signers of a multisig account.
+    ///
+    /// Only describes transaction signatures, it is never a valid access key type.
+    Multisig = 4,
 }
 
 impl From<SignatureType> for u8 {
@@ -43,6 +49,8 @@ impl From<SignatureType> for u8 {
             SignatureType::Secp256k1 => 0,
             SignatureType::P256 => 1,
             SignatureType::WebAuthn => 2,
+            SignatureType::Ed25519 => 3,
+            SignatureType::Multisig => 4,
         }
     }
 }
@@ -64,6 +72,8 @@ impl alloy_rlp::Decodable for SignatureType {
             0 => Ok(Self::Secp256k1),
             1 => Ok(Self::P256),
             2 => Ok(Self::WebAuthn),
+            3 => Ok(Self::Ed25519),
+            4 => Ok(Self::Multisig),
             _ => Err(alloy_rlp::Error::Custom("Invalid signature type")),
         }
     }
@@ -294,6 +304,14 @@ impl TempoTransaction {
         keccak256(&buf)
     }
 
+    /// Calculate the EIP-712 typed-data signing hash for this transaction
+    ///
+    /// Signed by the sender in place of [`Self::signature_hash`] when the transaction signature
+    /// is an [`Eip712`](TempoSignature::Eip712) signature.
+    pub fn eip712_signature_hash(&self) -> B256 {
+        super::eip712::transaction_signing_hash(self)
+    }
+
     /// Calculate the fee payer signature hash
     /// This hash is signed by the fee payer to sponsor the transaction
     pub fn fee_payer_signature_hash(&self, sender: Address) -> B256 {
@@ -72,6 +72,11 @@ impl TempoSignedAuthorization {
     ///
     /// Implementers should check that the authority has no code.
     pub fn recover_authority(&self) -> Result<Address, alloy_consensus::crypto::RecoveryError> {
+        // EIP-712 signing is only defined for transactions and key authorizations
+        if self.signature.is_eip712() {
+            return Err(alloy_consensus::crypto::RecoveryError::new());
+        }
+
         let sig_hash = self.signature_hash();
         self.signature.recover_signer(&sig_hash)
     }
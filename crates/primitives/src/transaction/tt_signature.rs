@@ -1,8 +1,11 @@
 use super::tempo_transaction::{
-    MAX_WEBAUTHN_SIGNATURE_LENGTH, P256_SIGNATURE_LENGTH, SECP256K1_SIGNATURE_LENGTH, SignatureType,
+    ED25519_SIGNATURE_LENGTH, MAX_WEBAUTHN_SIGNATURE_LENGTH, P256_SIGNATURE_LENGTH,
+    SECP256K1_SIGNATURE_LENGTH, SignatureType,
 };
-use alloy_primitives::{Address, B256, Bytes, Signature, keccak256};
+use alloy_primitives::{Address, B256, B512, Bytes, Signature, keccak256};
 use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use commonware_codec::DecodeExt as _;
+use commonware_cryptography::{Verifier as _, ed25519};
 use p256::{
     EncodedPoint,
     ecdsa::{Signature as P256Signature, VerifyingKey, signature::hazmat::PrehashVerifier},
@@ -15,10 +18,22 @@ use std::sync::OnceLock;
 pub const SIGNATURE_TYPE_P256: u8 = 0x01;
 pub const SIGNATURE_TYPE_WEBAUTHN: u8 = 0x02;
 pub const SIGNATURE_TYPE_KEYCHAIN: u8 = 0x03;
+pub const SIGNATURE_TYPE_MULTISIG: u8 = 0x04;
+pub const SIGNATURE_TYPE_ED25519: u8 = 0x05;
+/// Flag prefix marking a signature over the EIP-712 typed-data hash instead of the RLP hash
+pub const SIGNATURE_TYPE_EIP712: u8 = 0x06;
+
+/// Maximum number of co-signer signatures carried by a [`MultisigSignature`].
+pub const MAX_MULTISIG_SIGNATURES: usize = 16;
 
 // Minimum authenticatorData is 37 bytes (32 rpIdHash + 1 flags + 4 signCount)
 const MIN_AUTH_DATA_LEN: usize = 37;
 
+// authenticatorData flags: user present, attested credential data included, extension data included
+const WEBAUTHN_FLAG_UP: u8 = 0x01;
+const WEBAUTHN_FLAG_AT: u8 = 0x40;
+const WEBAUTHN_FLAG_ED: u8 = 0x80;
+
 /// P256 signature with pre-hash flag
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -50,8 +65,21 @@ pub struct WebAuthnSignature {
     pub webauthn_data: Bytes,
 }
 
+/// Ed25519 signature with embedded public key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "reth-codec", derive(reth_codecs::Compact))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[cfg_attr(test, reth_codecs::add_arbitrary_tests(compact))]
+pub struct Ed25519Signature {
+    pub public_key: B256,
+    /// R || S (64 bytes)
+    pub signature: B512,
+}
+
 /// Primitive signature types that can be used standalone or within a Keychain signature.
-/// This enum contains only the base signature types: Secp256k1, P256, and WebAuthn.
+/// This enum contains only the base signature types: Secp256k1, P256, WebAuthn, and Ed25519.
 /// It does NOT support Keychain signatures to prevent recursion.
 ///
 /// Note: This enum uses custom RLP encoding via `to_bytes()` and does NOT derive Compact.
@@ -73,6 +101,9 @@ pub enum PrimitiveSignature {
 
     /// WebAuthn signature with variable-length authenticator data
     WebAuthn(WebAuthnSignature),
+
+    /// Ed25519 signature with embedded public key (96 bytes)
+    Ed25519(Ed25519Signature),
 }
 
 impl PrimitiveSignature {
@@ -127,6 +158,15 @@ impl PrimitiveSignature {
                     webauthn_data: Bytes::copy_from_slice(&sig_data[..len - 128]),
                 }))
             }
+            SIGNATURE_TYPE_ED25519 => {
+                if sig_data.len() != ED25519_SIGNATURE_LENGTH {
+                    return Err("Invalid Ed25519 signature length");
+                }
+                Ok(Self::Ed25519(Ed25519Signature {
+                    public_key: B256::from_slice(&sig_data[0..32]),
+                    signature: B512::from_slice(&sig_data[32..96]),
+                }))
+            }
 
             _ => Err("Unknown signature type identifier"),
         }
@@ -136,7 +176,7 @@ impl PrimitiveSignature {
     ///
     /// For backward compatibility:
     /// - Secp256k1: encoded WITHOUT type identifier (65 bytes)
-    /// - P256/WebAuthn: encoded WITH type identifier prefix
+    /// - P256/WebAuthn/Ed25519: encoded WITH type identifier prefix
     pub fn to_bytes(&self) -> Bytes {
         match self {
             Self::Secp256k1(sig) => {
@@ -170,6 +210,13 @@ impl PrimitiveSignature {
                 bytes.extend_from_slice(webauthn_sig.pub_key_y.as_slice());
                 Bytes::from(bytes)
             }
+            Self::Ed25519(ed25519_sig) => {
+                let mut bytes = Vec::with_capacity(1 + ED25519_SIGNATURE_LENGTH);
+                bytes.push(SIGNATURE_TYPE_ED25519);
+                bytes.extend_from_slice(ed25519_sig.public_key.as_slice());
+                bytes.extend_from_slice(ed25519_sig.signature.as_slice());
+                Bytes::from(bytes)
+            }
         }
     }
 
@@ -177,12 +224,13 @@ impl PrimitiveSignature {
     ///
     /// For backward compatibility:
     /// - Secp256k1: 65 bytes (no type identifier)
-    /// - P256/WebAuthn: includes 1-byte type identifier prefix
+    /// - P256/WebAuthn/Ed25519: includes 1-byte type identifier prefix
     pub fn encoded_length(&self) -> usize {
         match self {
             Self::Secp256k1(_) => SECP256K1_SIGNATURE_LENGTH,
             Self::P256(_) => 1 + P256_SIGNATURE_LENGTH,
             Self::WebAuthn(webauthn_sig) => 1 + webauthn_sig.webauthn_data.len() + 128,
+            Self::Ed25519(_) => 1 + ED25519_SIGNATURE_LENGTH,
         }
     }
 
@@ -192,6 +240,7 @@ impl PrimitiveSignature {
             Self::Secp256k1(_) => SignatureType::Secp256k1,
             Self::P256(_) => SignatureType::P256,
             Self::WebAuthn(_) => SignatureType::WebAuthn,
+            Self::Ed25519(_) => SignatureType::Ed25519,
         }
     }
 
@@ -201,15 +250,22 @@ impl PrimitiveSignature {
             Self::Secp256k1(_) => SECP256K1_SIGNATURE_LENGTH,
             Self::P256(_) => 1 + P256_SIGNATURE_LENGTH,
             Self::WebAuthn(webauthn_sig) => 1 + webauthn_sig.webauthn_data.len() + 128,
+            Self::Ed25519(_) => 1 + ED25519_SIGNATURE_LENGTH,
         }
     }
 
+    /// Returns true if this is an Ed25519 signature
+    pub fn is_ed25519(&self) -> bool {
+        matches!(self, Self::Ed25519(_))
+    }
+
     /// Recover the signer address from the signature
     ///
     /// This function verifies the signature and extracts the address based on signature type:
     /// - secp256k1: Uses standard ecrecover (signature verification + address recovery)
     /// - P256: Verifies P256 signature then derives address from public key
     /// - WebAuthn: Parses WebAuthn data, verifies P256 signature, derives address
+    /// - Ed25519: Verifies Ed25519 signature then derives address from public key
     pub fn recover_signer(
         &self,
         sig_hash: &B256,
@@ -267,6 +323,18 @@ impl PrimitiveSignature {
                     &webauthn_sig.pub_key_y,
                 ))
             }
+            Self::Ed25519(ed25519_sig) => {
+                // Verify Ed25519 signature cryptographically over the signature hash
+                verify_ed25519_signature_internal(
+                    &ed25519_sig.public_key,
+                    &ed25519_sig.signature,
+                    sig_hash,
+                )
+                .map_err(|_| alloy_consensus::crypto::RecoveryError::new())?;
+
+                // Derive and return address
+                Ok(derive_ed25519_address(&ed25519_sig.public_key))
+            }
         }
     }
 }
@@ -440,6 +508,196 @@ impl<'a> arbitrary::Arbitrary<'a> for KeychainSignature {
     }
 }
 
+/// Threshold multisig signature carrying signatures from several co-signers of an account
+///
+/// Format: 0x04 || account (20 bytes) || rlp([signature_0, ..., signature_n])
+///
+/// The account is the root account this transaction is being executed for.
+/// Each inner signature is a [`PrimitiveSignature`], so co-signers can mix secp256k1, P256 and
+/// WebAuthn keys. The handler validates that the recovered co-signers are registered for the
+/// account in the AccountKeychain precompile and that they meet the configured threshold.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(test, reth_codecs::add_arbitrary_tests(compact))]
+pub struct MultisigSignature {
+    /// Root account address that this transaction is being executed for
+    pub account: Address,
+    /// Signatures from the co-signers (Secp256k1, P256, or WebAuthn, but NOT Keychain or Multisig)
+    pub signatures: Vec<PrimitiveSignature>,
+    /// Cached co-signer addresses recovered from the inner signatures.
+    /// This is an implementation detail - use `signers()` to access.
+    /// Note: Excluded from PartialEq, Eq, Hash, and Compact as it's a cache.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_once_lock",
+            rename = "signers",
+            skip_deserializing,
+        )
+    )]
+    cached_signers: OnceLock<Vec<Address>>,
+}
+
+impl MultisigSignature {
+    /// Create a new MultisigSignature
+    pub fn new(account: Address, signatures: Vec<PrimitiveSignature>) -> Self {
+        Self {
+            account,
+            signatures,
+            cached_signers: OnceLock::new(),
+        }
+    }
+
+    /// Get the co-signer addresses of this multisig signature.
+    ///
+    /// Every inner signature is verified and its signer recovered on first access, the result is
+    /// cached for subsequent calls. Fails if any inner signature is invalid, if there are no
+    /// signatures or more than [`MAX_MULTISIG_SIGNATURES`], or if the same co-signer signed twice.
+    pub fn signers(
+        &self,
+        sig_hash: &B256,
+    ) -> Result<&[Address], alloy_consensus::crypto::RecoveryError> {
+        // Check if already cached
+        if let Some(cached) = self.cached_signers.get() {
+            return Ok(cached);
+        }
+
+        if self.signatures.is_empty() || self.signatures.len() > MAX_MULTISIG_SIGNATURES {
+            return Err(alloy_consensus::crypto::RecoveryError::new());
+        }
+
+        // Not cached - recover and cache
+        let mut signers = Vec::with_capacity(self.signatures.len());
+        for signature in &self.signatures {
+            let signer = signature.recover_signer(sig_hash)?;
+
+            // Each co-signer may only be counted once towards the threshold
+            if signers.contains(&signer) {
+                return Err(alloy_consensus::crypto::RecoveryError::new());
+            }
+            signers.push(signer);
+        }
+
+        Ok(self.cached_signers.get_or_init(|| signers))
+    }
+
+    /// Encode the inner signatures as an RLP list
+    fn encode_signatures(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(alloy_rlp::list_length::<_, PrimitiveSignature>(
+            &self.signatures,
+        ));
+        alloy_rlp::encode_list::<_, PrimitiveSignature>(&self.signatures, &mut out);
+        out
+    }
+}
+
+// Manual implementations of PartialEq, Eq, and Hash that exclude cached_signers
+// since it's just a cache and doesn't affect the logical equality of signatures
+impl PartialEq for MultisigSignature {
+    fn eq(&self, other: &Self) -> bool {
+        self.account == other.account && self.signatures == other.signatures
+    }
+}
+
+impl Eq for MultisigSignature {}
+
+impl core::hash::Hash for MultisigSignature {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.account.hash(state);
+        self.signatures.hash(state);
+    }
+}
+
+// Manual Compact implementation that excludes cached_signers (cache field)
+#[cfg(feature = "reth-codec")]
+impl reth_codecs::Compact for MultisigSignature {
+    fn to_compact<B>(&self, buf: &mut B) -> usize
+    where
+        B: alloy_rlp::BufMut + AsMut<[u8]>,
+    {
+        // Only encode account and signatures, skip cached_signers
+        let mut written = 0;
+        written += self.account.to_compact(buf);
+        written += Bytes::from(self.encode_signatures()).to_compact(buf);
+        written
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        // Decode account and signatures, initialize cached_signers as empty
+        let (account, rest) = Address::from_compact(buf, len);
+        let remaining_len = len - (buf.len() - rest.len());
+        let (signatures, rest) = Bytes::from_compact(rest, remaining_len);
+        let signatures: Vec<PrimitiveSignature> =
+            alloy_rlp::Decodable::decode(&mut signatures.as_ref())
+                .expect("Failed to decode MultisigSignature from compact encoding");
+
+        (
+            Self {
+                account,
+                signatures,
+                cached_signers: OnceLock::new(),
+            },
+            rest,
+        )
+    }
+}
+
+// Manual Arbitrary implementation that excludes cached_signers (cache field)
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for MultisigSignature {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.int_in_range(1..=MAX_MULTISIG_SIGNATURES)?;
+        Ok(Self {
+            account: u.arbitrary()?,
+            signatures: (0..len)
+                .map(|_| u.arbitrary())
+                .collect::<arbitrary::Result<_>>()?,
+            cached_signers: OnceLock::new(), // Always start with empty cache
+        })
+    }
+}
+
+/// Signature over the EIP-712 typed-data hash of a transaction
+///
+/// Format: 0x06 || inner_signature
+///
+/// The inner signature is a Primitive, Keychain or Multisig signature that signed
+/// [`TempoTransaction::eip712_signature_hash`](super::TempoTransaction::eip712_signature_hash)
+/// instead of the RLP based [`TempoTransaction::signature_hash`](super::TempoTransaction::signature_hash).
+/// This allows wallets to display the transaction contents to the user before signing.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Eip712Signature {
+    /// Signature over the EIP-712 hash
+    /// IMP: MUST NOT be another Eip712 signature (validated when decoding)
+    #[cfg_attr(feature = "serde", serde(rename = "eip712"))]
+    pub signature: Box<TempoSignature>,
+}
+
+impl Eip712Signature {
+    /// Create a new Eip712Signature
+    pub fn new(signature: TempoSignature) -> Self {
+        Self {
+            signature: Box::new(signature),
+        }
+    }
+}
+
+// Manual Arbitrary implementation that prevents nested EIP-712 signatures
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for Eip712Signature {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let signature = match u.int_in_range(0..=2)? {
+            0 => TempoSignature::Primitive(u.arbitrary()?),
+            1 => TempoSignature::Keychain(u.arbitrary()?),
+            _ => TempoSignature::Multisig(u.arbitrary()?),
+        };
+        Ok(Self::new(signature))
+    }
+}
+
 /// AA transaction signature supporting multiple signature schemes
 ///
 /// Note: Uses custom Compact implementation that delegates to `to_bytes()` / `from_bytes()`.
@@ -457,6 +715,16 @@ pub enum TempoSignature {
     /// IMP: The inner signature MUST NOT be another Keychain (validated at runtime)
     /// Note: Recursion is prevented by KeychainSignature's custom Arbitrary impl
     Keychain(KeychainSignature),
+
+    /// Threshold multisig signature - carries signatures from several co-signers of an account
+    /// Format: account (20 bytes) + RLP list of inner signatures
+    /// The threshold is checked against the config registered in the AccountKeychain precompile
+    Multisig(MultisigSignature),
+
+    /// Signature over the EIP-712 typed-data hash instead of the RLP signature hash
+    /// Format: inner signature (Primitive, Keychain or Multisig)
+    /// Note: Recursion is prevented by Eip712Signature's custom Arbitrary impl
+    Eip712(Eip712Signature),
 }
 
 impl TempoSignature {
@@ -470,6 +738,19 @@ impl TempoSignature {
             return Err("Signature data is empty");
         }
 
+        // Check if this is an EIP-712 signature (flag prefix 0x06)
+        if data.len() > 1
+            && data.len() != SECP256K1_SIGNATURE_LENGTH
+            && data[0] == SIGNATURE_TYPE_EIP712
+        {
+            let inner = Self::from_bytes(&data[1..])?;
+            if inner.is_eip712() {
+                return Err("Invalid EIP-712 signature: nested EIP-712 signature");
+            }
+
+            return Ok(Self::Eip712(Eip712Signature::new(inner)));
+        }
+
         // Check if this is a Keychain signature (type identifier 0x03)
         // We need to handle this specially before delegating to PrimitiveSignature
         if data.len() > 1
@@ -497,6 +778,34 @@ impl TempoSignature {
             }));
         }
 
+        // Check if this is a Multisig signature (type identifier 0x04)
+        if data.len() > 1
+            && data.len() != SECP256K1_SIGNATURE_LENGTH
+            && data[0] == SIGNATURE_TYPE_MULTISIG
+        {
+            let sig_data = &data[1..];
+
+            // Multisig format: account (20 bytes) || rlp([inner_signature, ...])
+            if sig_data.len() < 20 {
+                return Err("Invalid Multisig signature: too short for account");
+            }
+
+            let account = Address::from_slice(&sig_data[0..20]);
+            let mut list = &sig_data[20..];
+
+            // Inner signatures are decoded as PrimitiveSignature, which prevents nesting
+            let signatures: Vec<PrimitiveSignature> = alloy_rlp::Decodable::decode(&mut list)
+                .map_err(|_| "Invalid Multisig signature: malformed signature list")?;
+            if !list.is_empty() {
+                return Err("Invalid Multisig signature: trailing bytes after signature list");
+            }
+            if signatures.is_empty() || signatures.len() > MAX_MULTISIG_SIGNATURES {
+                return Err("Invalid Multisig signature: invalid number of signatures");
+            }
+
+            return Ok(Self::Multisig(MultisigSignature::new(account, signatures)));
+        }
+
         // For all non-Keychain signatures, delegate to PrimitiveSignature
         let primitive = PrimitiveSignature::from_bytes(data)?;
         Ok(Self::Primitive(primitive))
@@ -519,6 +828,23 @@ impl TempoSignature {
                 bytes.extend_from_slice(&inner_bytes);
                 Bytes::from(bytes)
             }
+            Self::Multisig(multisig_sig) => {
+                // Format: 0x04 | account (20 bytes) | rlp([inner_signature, ...])
+                let inner_bytes = multisig_sig.encode_signatures();
+                let mut bytes = Vec::with_capacity(1 + 20 + inner_bytes.len());
+                bytes.push(SIGNATURE_TYPE_MULTISIG);
+                bytes.extend_from_slice(multisig_sig.account.as_slice());
+                bytes.extend_from_slice(&inner_bytes);
+                Bytes::from(bytes)
+            }
+            Self::Eip712(eip712_sig) => {
+                // Format: 0x06 | inner_signature
+                let inner_bytes = eip712_sig.signature.to_bytes();
+                let mut bytes = Vec::with_capacity(1 + inner_bytes.len());
+                bytes.push(SIGNATURE_TYPE_EIP712);
+                bytes.extend_from_slice(&inner_bytes);
+                Bytes::from(bytes)
+            }
         }
     }
 
@@ -531,14 +857,23 @@ impl TempoSignature {
         match self {
             Self::Primitive(primitive_sig) => primitive_sig.encoded_length(),
             Self::Keychain(keychain_sig) => 1 + 20 + keychain_sig.signature.encoded_length(),
+            Self::Multisig(multisig_sig) => {
+                1 + 20 + alloy_rlp::list_length::<_, PrimitiveSignature>(&multisig_sig.signatures)
+            }
+            Self::Eip712(eip712_sig) => 1 + eip712_sig.signature.encoded_length(),
         }
     }
 
     /// Get signature type
+    ///
+    /// Multisig signatures are always [`SignatureType::Multisig`], whatever their co-signers use.
+    /// For EIP-712 signatures, this is the type of the inner signature.
     pub fn signature_type(&self) -> SignatureType {
         match self {
             Self::Primitive(primitive_sig) => primitive_sig.signature_type(),
             Self::Keychain(keychain_sig) => keychain_sig.signature.signature_type(),
+            Self::Multisig(_) => SignatureType::Multisig,
+            Self::Eip712(eip712_sig) => eip712_sig.signature.signature_type(),
         }
     }
 
@@ -547,6 +882,15 @@ impl TempoSignature {
         match self {
             Self::Primitive(primitive_sig) => primitive_sig.size(),
             Self::Keychain(keychain_sig) => 1 + 20 + keychain_sig.signature.size(),
+            Self::Multisig(multisig_sig) => {
+                1 + 20
+                    + multisig_sig
+                        .signatures
+                        .iter()
+                        .map(PrimitiveSignature::size)
+                        .sum::<usize>()
+            }
+            Self::Eip712(eip712_sig) => 1 + eip712_sig.signature.size(),
         }
     }
 
@@ -557,9 +901,16 @@ impl TempoSignature {
     /// - P256: Verifies P256 signature then derives address from public key
     /// - WebAuthn: Parses WebAuthn data, verifies P256 signature, derives address
     /// - Keychain: Validates inner signature and returns user_address
+    /// - Multisig: Validates all inner signatures and returns account
     ///
     /// For Keychain signatures, this performs full validation of the inner signature.
     /// The access key address is cached in the KeychainSignature for later use.
+    ///
+    /// For Multisig signatures, the co-signer addresses are cached in the MultisigSignature.
+    /// The threshold itself is validated against on-chain state by the handler.
+    ///
+    /// For EIP-712 signatures, `sig_hash` must be the EIP-712 typed-data hash, see
+    /// [`AASigned::signature_hash`](super::AASigned::signature_hash).
     pub fn recover_signer(
         &self,
         sig_hash: &B256,
@@ -573,21 +924,77 @@ impl TempoSignature {
                 // Return the user_address - the root account this transaction is for
                 Ok(keychain_sig.user_address)
             }
+            Self::Multisig(multisig_sig) => {
+                // Ensure validity of every co-signer signature and cache the signers
+                multisig_sig.signers(sig_hash)?;
+
+                // Return the account - the root account this transaction is for
+                Ok(multisig_sig.account)
+            }
+            Self::Eip712(eip712_sig) => eip712_sig.signature.recover_signer(sig_hash),
+        }
+    }
+
+    /// Check if this signature was made over the EIP-712 typed-data hash
+    pub fn is_eip712(&self) -> bool {
+        matches!(self, Self::Eip712(_))
+    }
+
+    /// Get the signature without its EIP-712 flag
+    ///
+    /// Returns the inner signature for EIP-712 signatures, and `self` otherwise.
+    pub fn strip_eip712(&self) -> &Self {
+        match self {
+            Self::Eip712(eip712_sig) => &eip712_sig.signature,
+            _ => self,
         }
     }
 
     /// Check if this is a Keychain signature
+    ///
+    /// Note: This is also true for Keychain signatures over the EIP-712 hash.
     pub fn is_keychain(&self) -> bool {
-        matches!(self, Self::Keychain(_))
+        matches!(self.strip_eip712(), Self::Keychain(_))
     }
 
     /// Get the Keychain signature if this is a Keychain signature
     pub fn as_keychain(&self) -> Option<&KeychainSignature> {
-        match self {
+        match self.strip_eip712() {
             Self::Keychain(keychain_sig) => Some(keychain_sig),
             _ => None,
         }
     }
+
+    /// Check if this is a Multisig signature
+    ///
+    /// Note: This is also true for Multisig signatures over the EIP-712 hash.
+    pub fn is_multisig(&self) -> bool {
+        matches!(self.strip_eip712(), Self::Multisig(_))
+    }
+
+    /// Get the Multisig signature if this is a Multisig signature
+    pub fn as_multisig(&self) -> Option<&MultisigSignature> {
+        match self.strip_eip712() {
+            Self::Multisig(multisig_sig) => Some(multisig_sig),
+            _ => None,
+        }
+    }
+
+    /// Check if any signature carried by this signature is an Ed25519 signature
+    ///
+    /// This includes the inner signature of Keychain signatures and every co-signer signature of
+    /// Multisig signatures.
+    pub fn uses_ed25519(&self) -> bool {
+        match self {
+            Self::Primitive(primitive_sig) => primitive_sig.is_ed25519(),
+            Self::Keychain(keychain_sig) => keychain_sig.signature.is_ed25519(),
+            Self::Multisig(multisig_sig) => multisig_sig
+                .signatures
+                .iter()
+                .any(PrimitiveSignature::is_ed25519),
+            Self::Eip712(eip712_sig) => eip712_sig.signature.uses_ed25519(),
+        }
+    }
 }
 
 impl Default for TempoSignature {
@@ -652,6 +1059,37 @@ pub fn derive_p256_address(pub_key_x: &B256, pub_key_y: &B256) -> Address {
     Address::from_slice(&hash[12..])
 }
 
+/// Derives an address from an Ed25519 public key
+///
+/// The address is the last 20 bytes of `keccak256(public_key)`.
+pub fn derive_ed25519_address(public_key: &B256) -> Address {
+    let hash = keccak256(public_key);
+
+    // Take last 20 bytes as address
+    Address::from_slice(&hash[12..])
+}
+
+/// Verifies an Ed25519 signature using the provided components
+///
+/// Uses the same ZIP-215 validation rules as the consensus keys of validators, which are
+/// deterministic across implementations and therefore safe to use in consensus-critical code.
+/// The message is verified as is, without a namespace.
+fn verify_ed25519_signature_internal(
+    public_key: &B256,
+    signature: &B512,
+    message: &B256,
+) -> Result<(), &'static str> {
+    let public_key = ed25519::PublicKey::decode(public_key.as_slice())
+        .map_err(|_| "Invalid Ed25519 public key")?;
+    let signature = ed25519::Signature::decode(signature.as_slice())
+        .map_err(|_| "Invalid Ed25519 signature")?;
+
+    if !public_key.verify(None, message.as_slice(), &signature) {
+        return Err("Ed25519 signature verification failed");
+    }
+    Ok(())
+}
+
 /// Verifies a P256 signature using the provided components
 ///
 /// This performs actual cryptographic verification of the P256 signature
@@ -691,6 +1129,10 @@ fn verify_p256_signature_internal(
 /// 2. Validates authenticatorData (min 37 bytes, UP flag set)
 /// 3. Validates clientDataJSON (type="webauthn.get", challenge matches tx_hash)
 /// 4. Computes message hash = sha256(authenticatorData || sha256(clientDataJSON))
+///
+/// The length of authenticatorData extensions can only be determined by parsing their CBOR
+/// encoding, so transaction signatures must not carry extensions and authenticatorData is always
+/// the first 37 bytes.
 fn verify_webauthn_data_internal(
     webauthn_data: &[u8],
     tx_hash: &B256,
@@ -700,32 +1142,65 @@ fn verify_webauthn_data_internal(
         return Err("WebAuthn data too short");
     }
 
+    // ED flag must NOT be set, as Tempo AA doesn't support extensions
+    // NOTE: If we ever want to support extensions, we will have to parse CBOR data
+    if webauthn_data[32] & WEBAUTHN_FLAG_ED != 0 {
+        return Err("ED flag must not be set, as Tempo doesn't support extensions");
+    }
+
+    let (authenticator_data, client_data_json) = webauthn_data.split_at(MIN_AUTH_DATA_LEN);
+    webauthn_message_hash(authenticator_data, client_data_json, tx_hash)
+}
+
+/// Verifies a WebAuthn assertion whose authenticatorData and clientDataJSON are given separately
+///
+/// Applies the same rules as WebAuthn transaction signatures, except that authenticatorData may
+/// carry extensions (ED flag set), as its length is known.
+pub fn verify_webauthn_assertion(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    challenge: &B256,
+    signature: &WebAuthnSignature,
+) -> Result<(), &'static str> {
+    let message_hash = webauthn_message_hash(authenticator_data, client_data_json, challenge)?;
+    verify_p256_signature_internal(
+        signature.r.as_slice(),
+        signature.s.as_slice(),
+        signature.pub_key_x.as_slice(),
+        signature.pub_key_y.as_slice(),
+        &message_hash,
+    )
+}
+
+/// Validates authenticatorData and clientDataJSON of a WebAuthn assertion for `challenge`,
+/// returning the message hash signed by the authenticator
+fn webauthn_message_hash(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    challenge: &B256,
+) -> Result<B256, &'static str> {
+    if authenticator_data.len() < MIN_AUTH_DATA_LEN {
+        return Err("authenticatorData too short");
+    }
+
     // Check flags (byte 32): UP (bit 0), AT (bit 6), ED (bit 7)
-    let flags = webauthn_data[32];
-    let (up_flag, at_flag, ed_flag) = (flags & 0x01, flags & 0x40, flags & 0x80);
+    let flags = authenticator_data[32];
 
     // UP flag MUST be set
-    if up_flag == 0 {
+    if flags & WEBAUTHN_FLAG_UP == 0 {
         return Err("User Presence (UP) flag not set in authenticatorData");
     }
 
     // AT flag must NOT be set for assertion signatures (`webauthn.get`)
-    if at_flag != 0 {
+    if flags & WEBAUTHN_FLAG_AT != 0 {
         return Err("AT flag must not be set for assertion signatures");
     }
 
-    // Determine authenticatorData length
-    let auth_data_len = if ed_flag == 0 {
-        // If ED flag is not set, exactly 37 bytes (no extensions)
-        MIN_AUTH_DATA_LEN
-    } else {
-        // ED flag must NOT be set, as Tempo AA doesn't support extensions
-        // NOTE: If we ever want to support extensions, we will have to parse CBOR data
-        return Err("ED flag must not be set, as Tempo doesn't support extensions");
-    };
-
-    let authenticator_data = &webauthn_data[..auth_data_len];
-    let client_data_json = &webauthn_data[auth_data_len..];
+    // Extension data follows the fixed fields if and only if the ED flag is set
+    let has_extensions = authenticator_data.len() > MIN_AUTH_DATA_LEN;
+    if has_extensions != (flags & WEBAUTHN_FLAG_ED != 0) {
+        return Err("ED flag does not match the authenticatorData length");
+    }
 
     // Validate clientDataJSON
     let json_str =
@@ -741,8 +1216,8 @@ fn verify_webauthn_data_internal(
         return Err("clientDataJSON missing required type field");
     }
 
-    // Verify challenge matches tx_hash (Base64URL encoded)
-    let challenge_b64url = URL_SAFE_NO_PAD.encode(tx_hash.as_slice());
+    // Verify challenge matches the signed hash (Base64URL encoded)
+    let challenge_b64url = URL_SAFE_NO_PAD.encode(challenge.as_slice());
     let challenge_property = format!("\"challenge\":\"{challenge_b64url}\"");
     if !json_str.contains(&challenge_property) {
         return Err("clientDataJSON challenge does not match transaction hash");
@@ -762,8 +1237,9 @@ fn verify_webauthn_data_internal(
 
 #[cfg(feature = "serde")]
 /// Helper function to serialize a [`OnceLock`] as an [`Option`] if it's initialized.
-fn serialize_once_lock<S>(value: &OnceLock<Address>, serializer: S) -> Result<S::Ok, S::Error>
+fn serialize_once_lock<T, S>(value: &OnceLock<T>, serializer: S) -> Result<S::Ok, S::Error>
 where
+    T: serde::Serialize,
     S: serde::Serializer,
 {
     serde::Serialize::serialize(&value.get(), serializer)
@@ -1227,5 +1703,202 @@ mod tests {
             verify_webauthn_data_internal(&data, &tx_hash).is_ok(),
             "Should accept valid webauthn data with only UP flag"
         );
+
+        // Extensions are accepted when authenticatorData is given separately
+        let data = build_webauthn_data(0x81, Some(&[0xa0]), &tx_hash);
+        let (authenticator_data, client_data_json) = data.split_at(MIN_AUTH_DATA_LEN + 1);
+        assert!(webauthn_message_hash(authenticator_data, client_data_json, &tx_hash).is_ok());
+
+        // The ED flag must match the presence of extension data
+        let err = webauthn_message_hash(
+            &authenticator_data[..MIN_AUTH_DATA_LEN],
+            client_data_json,
+            &tx_hash,
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("ED flag"),
+            "Should reject ED flag without extensions"
+        );
+        let data = build_webauthn_data(0x01, Some(&[0xa0]), &tx_hash);
+        let (authenticator_data, client_data_json) = data.split_at(MIN_AUTH_DATA_LEN + 1);
+        let err =
+            webauthn_message_hash(authenticator_data, client_data_json, &tx_hash).unwrap_err();
+        assert!(
+            err.contains("ED flag"),
+            "Should reject extensions without ED flag"
+        );
+    }
+
+    #[test]
+    fn test_ed25519_signature_roundtrip_and_recovery() {
+        use commonware_cryptography::{PrivateKeyExt as _, Signer as _};
+
+        let signing_key = ed25519::PrivateKey::from_seed(7);
+        let public_key = B256::from_slice(&signing_key.public_key());
+        let sig_hash = B256::repeat_byte(0x42);
+
+        let signature = PrimitiveSignature::Ed25519(Ed25519Signature {
+            public_key,
+            signature: B512::from_slice(&signing_key.sign(None, sig_hash.as_slice())),
+        });
+
+        // Encoding: type identifier + public key + signature
+        let bytes = signature.to_bytes();
+        assert_eq!(bytes.len(), 1 + ED25519_SIGNATURE_LENGTH);
+        assert_eq!(bytes[0], SIGNATURE_TYPE_ED25519);
+        assert_eq!(signature.encoded_length(), bytes.len());
+        assert_eq!(PrimitiveSignature::from_bytes(&bytes).unwrap(), signature);
+        assert_eq!(signature.signature_type(), SignatureType::Ed25519);
+
+        // Recovery derives the address from the public key
+        assert_eq!(
+            signature.recover_signer(&sig_hash).unwrap(),
+            derive_ed25519_address(&public_key)
+        );
+        assert!(
+            signature.recover_signer(&B256::ZERO).is_err(),
+            "Signature over a different hash should fail verification"
+        );
+
+        // Invalid lengths are rejected
+        assert!(PrimitiveSignature::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        // Ed25519 is detected inside Keychain signatures
+        let keychain =
+            TempoSignature::Keychain(KeychainSignature::new(Address::random(), signature));
+        assert!(keychain.uses_ed25519());
+        assert!(!TempoSignature::default().uses_ed25519());
+    }
+
+    #[test]
+    fn test_tempo_signature_eip712_roundtrip() {
+        let secp_sig = PrimitiveSignature::Secp256k1(Signature::test_signature());
+        let keychain =
+            TempoSignature::Keychain(KeychainSignature::new(Address::random(), secp_sig.clone()));
+
+        for inner in [TempoSignature::Primitive(secp_sig), keychain] {
+            let signature = TempoSignature::Eip712(Eip712Signature::new(inner.clone()));
+
+            // Encoding: flag prefix + inner signature
+            let bytes = signature.to_bytes();
+            assert_eq!(bytes[0], SIGNATURE_TYPE_EIP712);
+            assert_eq!(&bytes[1..], inner.to_bytes().as_ref());
+            assert_eq!(signature.encoded_length(), bytes.len());
+            assert_eq!(TempoSignature::from_bytes(&bytes).unwrap(), signature);
+
+            assert!(signature.is_eip712());
+            assert_eq!(signature.strip_eip712(), &inner);
+            assert_eq!(signature.is_keychain(), inner.is_keychain());
+            assert_eq!(signature.signature_type(), inner.signature_type());
+        }
+
+        // Nested EIP-712 flags are rejected
+        let nested = [SIGNATURE_TYPE_EIP712]
+            .into_iter()
+            .chain(
+                TempoSignature::Eip712(Eip712Signature::new(TempoSignature::default())).to_bytes(),
+            )
+            .collect::<Vec<_>>();
+        assert!(TempoSignature::from_bytes(&nested).is_err());
+    }
+
+    #[test]
+    fn test_tempo_signature_multisig_roundtrip() {
+        let secp_sig = PrimitiveSignature::Secp256k1(Signature::test_signature());
+        let p256_sig = PrimitiveSignature::P256(P256SignatureWithPreHash {
+            r: B256::from([1u8; 32]),
+            s: B256::from([2u8; 32]),
+            pub_key_x: B256::from([3u8; 32]),
+            pub_key_y: B256::from([4u8; 32]),
+            pre_hash: false,
+        });
+        let multisig = TempoSignature::Multisig(MultisigSignature::new(
+            Address::repeat_byte(0x42),
+            vec![secp_sig, p256_sig],
+        ));
+
+        let encoded = multisig.to_bytes();
+        assert_eq!(encoded[0], SIGNATURE_TYPE_MULTISIG);
+        assert_eq!(encoded.len(), multisig.encoded_length());
+
+        let decoded = TempoSignature::from_bytes(&encoded).unwrap();
+        assert_eq!(multisig, decoded);
+        assert!(decoded.is_multisig());
+        assert_eq!(
+            decoded.as_multisig().unwrap().account,
+            Address::repeat_byte(0x42)
+        );
+
+        // The signature type does not depend on the co-signers' signatures
+        assert_eq!(decoded.signature_type(), SignatureType::Multisig);
+    }
+
+    #[test]
+    fn test_tempo_signature_multisig_invalid_encoding() {
+        // Empty signature list
+        let mut empty = vec![SIGNATURE_TYPE_MULTISIG];
+        empty.extend_from_slice(Address::repeat_byte(0x42).as_slice());
+        empty.push(alloy_rlp::EMPTY_LIST_CODE);
+        assert!(TempoSignature::from_bytes(&empty).is_err());
+
+        // Too many signatures
+        let multisig = MultisigSignature::new(
+            Address::repeat_byte(0x42),
+            vec![PrimitiveSignature::default(); MAX_MULTISIG_SIGNATURES + 1],
+        );
+        let encoded = TempoSignature::Multisig(multisig).to_bytes();
+        assert!(TempoSignature::from_bytes(&encoded).is_err());
+
+        // Trailing bytes after the signature list
+        let multisig = MultisigSignature::new(
+            Address::repeat_byte(0x42),
+            vec![PrimitiveSignature::default()],
+        );
+        let mut encoded = TempoSignature::Multisig(multisig).to_bytes().to_vec();
+        encoded.push(0x00);
+        assert!(TempoSignature::from_bytes(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_multisig_signers_reject_duplicates() {
+        use p256::{
+            ecdsa::{SigningKey, signature::hazmat::PrehashSigner},
+            elliptic_curve::rand_core::OsRng,
+        };
+
+        let sig_hash = B256::from([0xAB; 32]);
+
+        let sign = |signing_key: &SigningKey| {
+            let signature: p256::ecdsa::Signature =
+                signing_key.sign_prehash(sig_hash.as_slice()).unwrap();
+            let sig_bytes = signature.to_bytes();
+            let encoded_point = signing_key.verifying_key().to_encoded_point(false);
+            PrimitiveSignature::P256(P256SignatureWithPreHash {
+                r: B256::from_slice(&sig_bytes[0..32]),
+                s: B256::from_slice(&sig_bytes[32..64]),
+                pub_key_x: B256::from_slice(encoded_point.x().unwrap()),
+                pub_key_y: B256::from_slice(encoded_point.y().unwrap()),
+                pre_hash: false,
+            })
+        };
+
+        let key_a = SigningKey::random(&mut OsRng);
+        let key_b = SigningKey::random(&mut OsRng);
+        let account = Address::repeat_byte(0x42);
+
+        // Two distinct co-signers recover in order and resolve to the account
+        let multisig = TempoSignature::Multisig(MultisigSignature::new(
+            account,
+            vec![sign(&key_a), sign(&key_b)],
+        ));
+        assert_eq!(multisig.recover_signer(&sig_hash).unwrap(), account);
+        let signers = multisig.as_multisig().unwrap().signers(&sig_hash).unwrap();
+        assert_eq!(signers.len(), 2);
+        assert_ne!(signers[0], signers[1]);
+
+        // The same co-signer must not be counted twice
+        let duplicate = MultisigSignature::new(account, vec![sign(&key_a), sign(&key_a)]);
+        assert!(duplicate.signers(&sig_hash).is_err());
     }
 }
@@ -1,5 +1,5 @@
 use super::{
-    tempo_transaction::{TEMPO_TX_TYPE_ID, TempoTransaction},
+    tempo_transaction::{SignatureType, TEMPO_TX_TYPE_ID, TempoTransaction},
     tt_signature::TempoSignature,
 };
 use alloy_consensus::{Transaction, transaction::TxHashRef};
@@ -93,8 +93,15 @@ impl AASigned {
     }
 
     /// Calculate the signing hash for the transaction.
+    ///
+    /// This is the EIP-712 typed-data hash if the signature is flagged as an EIP-712 signature,
+    /// and the RLP based signature hash otherwise.
     pub fn signature_hash(&self) -> B256 {
-        self.tx.signature_hash()
+        if self.signature.is_eip712() {
+            self.tx.eip712_signature_hash()
+        } else {
+            self.tx.signature_hash()
+        }
     }
 
     /// Returns the RLP header for the transaction and signature, encapsulating both
@@ -120,6 +127,33 @@ impl AASigned {
         self.signature.encode(out);
     }
 
+    /// Returns true if the transaction carries any Ed25519 signature or authorizes an Ed25519 key.
+    ///
+    /// Covers the transaction signature, the [`KeyAuthorization`](super::KeyAuthorization) and
+    /// the Tempo authorization list.
+    pub fn uses_ed25519(&self) -> bool {
+        self.signature.uses_ed25519()
+            || self.tx.key_authorization.as_ref().is_some_and(|auth| {
+                auth.key_type == SignatureType::Ed25519 || auth.signature.is_ed25519()
+            })
+            || self
+                .tx
+                .tempo_authorization_list
+                .iter()
+                .any(|auth| auth.signature().uses_ed25519())
+    }
+
+    /// Returns true if the transaction signature or the key authorization is signed over the
+    /// EIP-712 typed-data hash.
+    pub fn uses_eip712(&self) -> bool {
+        self.signature.is_eip712()
+            || self
+                .tx
+                .key_authorization
+                .as_ref()
+                .is_some_and(|auth| auth.eip712)
+    }
+
     /// Splits the transaction into parts.
     pub fn into_parts(self) -> (TempoTransaction, TempoSignature, B256) {
         let hash = *self.hash();
@@ -385,9 +419,16 @@ mod serde_impl {
         where
             S: Serializer,
         {
-            if let TempoSignature::Keychain(keychain_sig) = &self.signature {
-                // Initialize the `key_id` field for keychain signatures so that it's serialized.
-                let _ = keychain_sig.key_id(&self.signature_hash());
+            match self.signature.strip_eip712() {
+                TempoSignature::Keychain(keychain_sig) => {
+                    // Initialize the `key_id` field for keychain signatures so that it's serialized.
+                    let _ = keychain_sig.key_id(&self.signature_hash());
+                }
+                TempoSignature::Multisig(multisig_sig) => {
+                    // Initialize the `signers` field for multisig signatures so that it's serialized.
+                    let _ = multisig_sig.signers(&self.signature_hash());
+                }
+                TempoSignature::Primitive(_) | TempoSignature::Eip712(_) => {}
             }
             AASignedHelper {
                 tx: Cow::Borrowed(&self.tx),
@@ -0,0 +1,249 @@
+//! The node's end of the connection to a remote signer.
+
+use std::{net::SocketAddr, time::Duration};
+
+use bytes::Bytes;
+use commonware_codec::{DecodeExt as _, Encode as _};
+use commonware_cryptography::{
+    Verifier as _,
+    bls12381::primitives::{
+        poly::{Eval, PartialSignature},
+        variant::{MinSig, Variant},
+    },
+    ed25519::{PrivateKey, PublicKey, Signature},
+};
+use commonware_macros::select;
+use commonware_runtime::{Clock, Network, SinkOf, StreamOf};
+use futures::lock::Mutex;
+use rand_core::CryptoRngCore;
+use tracing::{info, instrument};
+
+use crate::{
+    RemoteSignerError,
+    protocol::{Request, Response, Vote, stream_config},
+};
+
+/// Configuration of a [`Client`].
+#[derive(Clone, Debug)]
+pub struct ClientConfig {
+    /// The address the remote signer is listening on.
+    pub address: SocketAddr,
+
+    /// The key the node authenticates itself with to the remote signer.
+    ///
+    /// This is not the validator's signing key, which is held by the remote
+    /// signer.
+    pub identity: PrivateKey,
+
+    /// The key the remote signer authenticates itself with.
+    pub signer_identity: PublicKey,
+
+    /// How long to wait for the remote signer to respond to a request.
+    pub timeout: Duration,
+}
+
+/// A connection to a remote signer.
+///
+/// Requests are sent one at a time. If a request fails, the connection is
+/// dropped and established again on the next request.
+pub struct Client<E>
+where
+    E: Network,
+{
+    context: E,
+    config: ClientConfig,
+    connection: Mutex<Option<Connection<E>>>,
+
+    /// The public key of the ed25519 signing key held by the remote signer.
+    public_key: PublicKey,
+
+    /// The index and public key of the share held by the remote signer, if
+    /// any.
+    share: Option<Eval<<MinSig as Variant>::Public>>,
+}
+
+impl<E> Client<E>
+where
+    E: Clock + CryptoRngCore + Network + Clone,
+{
+    /// Connects to the remote signer and asks it for the keys it holds.
+    #[instrument(skip_all, fields(address = %config.address), err)]
+    pub async fn connect(context: E, config: ClientConfig) -> Result<Self, RemoteSignerError> {
+        let mut connection = Connection::establish(&context, &config).await?;
+        let Response::Identity { public_key, share } = connection
+            .request(&context, config.timeout, &Request::Identity)
+            .await?
+        else {
+            return Err(RemoteSignerError::UnexpectedResponse);
+        };
+        info!(
+            %public_key,
+            share_index = share.as_ref().map(|share| share.index),
+            "connected to remote signer",
+        );
+
+        Ok(Self {
+            context,
+            config,
+            connection: Mutex::new(Some(connection)),
+            public_key,
+            share,
+        })
+    }
+
+    /// Returns the public key of the ed25519 signing key held by the remote
+    /// signer.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Returns the index and public key of the share held by the remote
+    /// signer, if any.
+    pub fn share(&self) -> Option<&Eval<<MinSig as Variant>::Public>> {
+        self.share.as_ref()
+    }
+
+    /// Asks the remote signer for an ed25519 signature over `message`.
+    ///
+    /// The signature is verified before it is returned.
+    pub async fn sign(
+        &self,
+        namespace: Option<&[u8]>,
+        message: &[u8],
+    ) -> Result<Signature, RemoteSignerError> {
+        let Response::Signature(signature) = self
+            .request(Request::Sign {
+                namespace: namespace.map(Bytes::copy_from_slice),
+                message: Bytes::copy_from_slice(message),
+            })
+            .await?
+        else {
+            return Err(RemoteSignerError::UnexpectedResponse);
+        };
+        if !self.public_key.verify(namespace, message, &signature) {
+            return Err(RemoteSignerError::InvalidSignature);
+        }
+        Ok(signature)
+    }
+
+    /// Asks the remote signer for partial signatures over `vote` and the seed
+    /// of its round, in that order.
+    pub async fn sign_vote(
+        &self,
+        namespace: &[u8],
+        vote: Vote,
+    ) -> Result<(PartialSignature<MinSig>, PartialSignature<MinSig>), RemoteSignerError> {
+        let Response::VoteSignature { vote, seed } = self
+            .request(Request::SignVote {
+                namespace: Bytes::copy_from_slice(namespace),
+                vote,
+            })
+            .await?
+        else {
+            return Err(RemoteSignerError::UnexpectedResponse);
+        };
+        Ok((vote, seed))
+    }
+
+    /// Asks the remote signer to record `vote`, which the node signs with a
+    /// share it holds itself.
+    ///
+    /// Fails with [`RemoteSignerError::Refused`] if the vote conflicts with a
+    /// vote signed before, in which case it must not be signed.
+    pub async fn record_vote(&self, vote: Vote) -> Result<(), RemoteSignerError> {
+        let Response::Recorded = self.request(Request::RecordVote { vote }).await? else {
+            return Err(RemoteSignerError::UnexpectedResponse);
+        };
+        Ok(())
+    }
+
+    async fn request(&self, request: Request) -> Result<Response, RemoteSignerError> {
+        let mut connection = self.connection.lock().await;
+        if connection.is_none() {
+            *connection = Some(Connection::establish(&self.context, &self.config).await?);
+        }
+        let response = connection
+            .as_mut()
+            .expect("connection was established above")
+            .request(&self.context, self.config.timeout, &request)
+            .await;
+        if response.is_err() {
+            *connection = None;
+        }
+        match response? {
+            Response::Refused(refusal) => Err(RemoteSignerError::Refused(refusal)),
+            response => Ok(response),
+        }
+    }
+}
+
+struct Connection<E>
+where
+    E: Network,
+{
+    sender: commonware_stream::Sender<SinkOf<E>>,
+    receiver: commonware_stream::Receiver<StreamOf<E>>,
+}
+
+impl<E> Connection<E>
+where
+    E: Clock + CryptoRngCore + Network + Clone,
+{
+    /// Dials the remote signer and completes the handshake with it, failing
+    /// with [`RemoteSignerError::Timeout`] if that takes longer than a
+    /// request may.
+    async fn establish(context: &E, config: &ClientConfig) -> Result<Self, RemoteSignerError> {
+        let connect = async {
+            let (sink, stream) = context
+                .dial(config.address)
+                .await
+                .map_err(RemoteSignerError::Connect)?;
+            let (sender, receiver) = commonware_stream::dial(
+                context.clone(),
+                stream_config(config.identity.clone()),
+                config.signer_identity.clone(),
+                stream,
+                sink,
+            )
+            .await
+            .map_err(RemoteSignerError::Handshake)?;
+            Ok(Self { sender, receiver })
+        };
+        select! {
+            connection = connect => {
+                connection
+            },
+            _ = context.sleep(config.timeout) => {
+                Err(RemoteSignerError::Timeout)
+            },
+        }
+    }
+
+    async fn request(
+        &mut self,
+        context: &E,
+        timeout: Duration,
+        request: &Request,
+    ) -> Result<Response, RemoteSignerError> {
+        let exchange = async {
+            self.sender
+                .send(&request.encode())
+                .await
+                .map_err(RemoteSignerError::Stream)?;
+            let response = self
+                .receiver
+                .recv()
+                .await
+                .map_err(RemoteSignerError::Stream)?;
+            Response::decode(response).map_err(RemoteSignerError::Decode)
+        };
+        select! {
+            response = exchange => {
+                response
+            },
+            _ = context.sleep(timeout) => {
+                Err(RemoteSignerError::Timeout)
+            },
+        }
+    }
+}
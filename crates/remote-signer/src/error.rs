@@ -0,0 +1,47 @@
+//! Remote signer error types.
+
+use crate::protocol::Refusal;
+
+/// Errors returned when talking to a remote signer.
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteSignerError {
+    /// The remote signer could not be reached.
+    #[error("failed to connect to remote signer")]
+    Connect(#[source] commonware_runtime::Error),
+
+    /// The encrypted connection to the remote signer could not be established.
+    #[error("failed to establish encrypted connection with remote signer")]
+    Handshake(#[source] commonware_stream::Error),
+
+    /// Sending a request or receiving a response failed.
+    #[error("failed to exchange messages with remote signer")]
+    Stream(#[source] commonware_stream::Error),
+
+    /// The remote signer did not respond in time.
+    #[error("remote signer did not respond in time")]
+    Timeout,
+
+    /// The response could not be decoded.
+    #[error("failed to decode response of remote signer")]
+    Decode(#[source] commonware_codec::Error),
+
+    /// The remote signer responded with a message not matching the request.
+    #[error("remote signer responded with an unexpected message")]
+    UnexpectedResponse,
+
+    /// The remote signer refused to sign.
+    #[error("remote signer refused to sign")]
+    Refused(#[source] Refusal),
+
+    /// The remote signer returned an invalid signature.
+    #[error("remote signer returned an invalid signature")]
+    InvalidSignature,
+
+    /// The listener of the remote signer could not be bound.
+    #[error("failed to bind listener")]
+    Bind(#[source] commonware_runtime::Error),
+
+    /// The slashing protection history could not be read or written.
+    #[error("failed to access slashing protection history on disk")]
+    Storage(#[source] commonware_storage::metadata::Error),
+}
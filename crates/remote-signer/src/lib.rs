@@ -0,0 +1,36 @@
+//! Signing with consensus keys held outside of the node.
+//!
+//! Validators may keep their ed25519 signing key and bls12-381 share in a
+//! separate, hardened process instead of in the node. The node then connects
+//! to this remote signer over TCP and asks it for signatures.
+//!
+//! The connection is a [`commonware_stream`] connection, the same as used
+//! between consensus peers: it is encrypted, and both ends authenticate by an
+//! ed25519 identity key. The signer only accepts connections from the
+//! identities it was configured with.
+//!
+//! Before signing a simplex vote with the share, the signer persists the vote
+//! and refuses to sign any vote conflicting with one it signed before (see
+//! [`Refusal`]). This protects the validator from being caught equivocating
+//! if, for example, two nodes are accidentally run against the same signer.
+//! Nodes signing votes with a share they hold themselves have the signer
+//! record their votes first, so that the same protection applies to them.
+//!
+//! The signer only signs with the ed25519 key under the namespaces the node
+//! signs under: the p2p handshake, DKG acks and outcomes, and subblocks.
+
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+mod client;
+mod error;
+mod protection;
+pub mod protocol;
+mod server;
+#[cfg(test)]
+mod tests;
+
+pub use client::{Client, ClientConfig};
+pub use error::RemoteSignerError;
+pub use protocol::{Refusal, Vote, p2p_namespace};
+pub use server::{ServerConfig, run};
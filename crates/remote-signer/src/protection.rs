@@ -0,0 +1,261 @@
+//! Protection against signing conflicting simplex votes.
+//!
+//! The signer remembers which votes it signed for the most recent views and
+//! refuses to sign any vote that would let another validator prove that this
+//! validator equivocated: two notarize or finalize votes for different
+//! proposals in the same view, or a nullify and a finalize vote in the same
+//! view. Signing the exact same vote again is allowed so that a restarted node
+//! can rebroadcast its votes.
+//!
+//! Votes older than the views remembered are refused altogether.
+
+use std::collections::BTreeMap;
+
+use bytes::{Buf, BufMut};
+use commonware_codec::{EncodeSize, Error, Read, ReadExt as _, Write, varint::UInt};
+use commonware_consensus::types::{Epoch, View};
+use commonware_cryptography::sha256::Digest;
+
+use crate::protocol::{Refusal, Vote};
+
+/// The number of views for which signed votes are remembered.
+pub(crate) const VIEWS_TO_TRACK: usize = 4096;
+
+/// The votes signed in the most recent views.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct History {
+    /// The newest view that was forgotten. Votes at or below it are refused.
+    forgotten: Option<(Epoch, View)>,
+
+    votes: BTreeMap<(Epoch, View), Signed>,
+}
+
+/// The votes signed in a single view.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Signed {
+    notarized: Option<Digest>,
+    nullified: bool,
+    finalized: Option<Digest>,
+}
+
+impl History {
+    /// Records `vote` as signed.
+    ///
+    /// Returns whether the history changed and so must be persisted before
+    /// the vote is signed, or the reason the vote must not be signed.
+    pub(crate) fn record(&mut self, vote: &Vote) -> Result<bool, Refusal> {
+        let key = (vote.epoch(), vote.view());
+        if self.forgotten.is_some_and(|forgotten| key <= forgotten) {
+            return Err(Refusal::Stale);
+        }
+
+        let signed = self.votes.entry(key).or_default();
+        let before = signed.clone();
+        match vote {
+            Vote::Notarize(proposal) => {
+                if signed
+                    .notarized
+                    .is_some_and(|notarized| notarized != proposal.payload)
+                {
+                    return Err(Refusal::Conflict);
+                }
+                signed.notarized = Some(proposal.payload);
+            }
+            Vote::Nullify(_) => {
+                if signed.finalized.is_some() {
+                    return Err(Refusal::Conflict);
+                }
+                signed.nullified = true;
+            }
+            Vote::Finalize(proposal) => {
+                if signed.nullified
+                    || signed
+                        .notarized
+                        .is_some_and(|notarized| notarized != proposal.payload)
+                    || signed
+                        .finalized
+                        .is_some_and(|finalized| finalized != proposal.payload)
+                {
+                    return Err(Refusal::Conflict);
+                }
+                signed.finalized = Some(proposal.payload);
+            }
+        }
+        let changed = *signed != before;
+
+        while self.votes.len() > VIEWS_TO_TRACK {
+            let (oldest, _) = self
+                .votes
+                .pop_first()
+                .expect("history holds more than zero votes");
+            self.forgotten = Some(oldest);
+        }
+        Ok(changed)
+    }
+}
+
+impl Write for History {
+    fn write(&self, buf: &mut impl BufMut) {
+        self.forgotten.is_some().write(buf);
+        if let Some((epoch, view)) = self.forgotten {
+            UInt(epoch).write(buf);
+            UInt(view).write(buf);
+        }
+        UInt(self.votes.len() as u64).write(buf);
+        for ((epoch, view), signed) in &self.votes {
+            UInt(*epoch).write(buf);
+            UInt(*view).write(buf);
+            signed.write(buf);
+        }
+    }
+}
+
+impl EncodeSize for History {
+    fn encode_size(&self) -> usize {
+        self.forgotten.is_some().encode_size()
+            + self.forgotten.map_or(0, |(epoch, view)| {
+                UInt(epoch).encode_size() + UInt(view).encode_size()
+            })
+            + UInt(self.votes.len() as u64).encode_size()
+            + self
+                .votes
+                .iter()
+                .map(|((epoch, view), signed)| {
+                    UInt(*epoch).encode_size() + UInt(*view).encode_size() + signed.encode_size()
+                })
+                .sum::<usize>()
+    }
+}
+
+impl Read for History {
+    type Cfg = ();
+
+    fn read_cfg(buf: &mut impl Buf, _cfg: &Self::Cfg) -> Result<Self, Error> {
+        let forgotten = if bool::read(buf)? {
+            Some((UInt::read(buf)?.into(), UInt::read(buf)?.into()))
+        } else {
+            None
+        };
+        let len: u64 = UInt::read(buf)?.into();
+        if len > VIEWS_TO_TRACK as u64 {
+            return Err(Error::Invalid("History", "too many votes"));
+        }
+        let mut votes = BTreeMap::new();
+        for _ in 0..len {
+            let epoch = UInt::read(buf)?.into();
+            let view = UInt::read(buf)?.into();
+            votes.insert((epoch, view), Signed::read(buf)?);
+        }
+        Ok(Self { forgotten, votes })
+    }
+}
+
+impl Write for Signed {
+    fn write(&self, buf: &mut impl BufMut) {
+        self.notarized.write(buf);
+        self.nullified.write(buf);
+        self.finalized.write(buf);
+    }
+}
+
+impl EncodeSize for Signed {
+    fn encode_size(&self) -> usize {
+        self.notarized.encode_size() + self.nullified.encode_size() + self.finalized.encode_size()
+    }
+}
+
+impl Read for Signed {
+    type Cfg = ();
+
+    fn read_cfg(buf: &mut impl Buf, _cfg: &Self::Cfg) -> Result<Self, Error> {
+        Ok(Self {
+            notarized: Option::<Digest>::read(buf)?,
+            nullified: bool::read(buf)?,
+            finalized: Option::<Digest>::read(buf)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use commonware_codec::{DecodeExt as _, Encode as _};
+    use commonware_consensus::{simplex::types::Proposal, types::Round};
+    use commonware_cryptography::{Hasher as _, Sha256, sha256::Digest};
+
+    use super::{History, VIEWS_TO_TRACK};
+    use crate::protocol::{Refusal, Vote};
+
+    fn digest(value: u8) -> Digest {
+        let mut hasher = Sha256::new();
+        hasher.update(&[value]);
+        hasher.finalize()
+    }
+
+    fn notarize(view: u64, payload: u8) -> Vote {
+        Vote::Notarize(Proposal::new(
+            Round::new(0, view),
+            view - 1,
+            digest(payload),
+        ))
+    }
+
+    fn finalize(view: u64, payload: u8) -> Vote {
+        Vote::Finalize(Proposal::new(
+            Round::new(0, view),
+            view - 1,
+            digest(payload),
+        ))
+    }
+
+    fn nullify(view: u64) -> Vote {
+        Vote::Nullify(Round::new(0, view))
+    }
+
+    #[test]
+    fn same_vote_can_be_signed_again() {
+        let mut history = History::default();
+        assert_eq!(history.record(&notarize(1, 1)), Ok(true));
+        assert_eq!(history.record(&notarize(1, 1)), Ok(false));
+        assert_eq!(history.record(&finalize(1, 1)), Ok(true));
+        assert_eq!(history.record(&finalize(1, 1)), Ok(false));
+    }
+
+    #[test]
+    fn conflicting_notarize_is_refused() {
+        let mut history = History::default();
+        history.record(&notarize(1, 1)).unwrap();
+        assert_eq!(history.record(&notarize(1, 2)), Err(Refusal::Conflict));
+        assert_eq!(history.record(&finalize(1, 2)), Err(Refusal::Conflict));
+        assert_eq!(history.record(&notarize(2, 2)), Ok(true));
+    }
+
+    #[test]
+    fn nullify_and_finalize_are_refused_in_the_same_view() {
+        let mut history = History::default();
+        history.record(&nullify(1)).unwrap();
+        assert_eq!(history.record(&finalize(1, 1)), Err(Refusal::Conflict));
+
+        history.record(&finalize(2, 1)).unwrap();
+        assert_eq!(history.record(&nullify(2)), Err(Refusal::Conflict));
+    }
+
+    #[test]
+    fn forgotten_views_are_refused() {
+        let mut history = History::default();
+        for view in 1..=VIEWS_TO_TRACK as u64 + 1 {
+            history.record(&nullify(view)).unwrap();
+        }
+        assert_eq!(history.record(&notarize(1, 1)), Err(Refusal::Stale));
+        assert_eq!(history.record(&notarize(2, 1)), Ok(true));
+    }
+
+    #[test]
+    fn roundtrip_history() {
+        let mut history = History::default();
+        history.record(&notarize(1, 1)).unwrap();
+        history.record(&finalize(1, 1)).unwrap();
+        history.record(&nullify(2)).unwrap();
+
+        assert_eq!(History::decode(history.encode()).unwrap(), history);
+    }
+}
@@ -0,0 +1,514 @@
+//! Messages exchanged between a node and its remote signer.
+//!
+//! Every request is answered by exactly one response. Messages are encoded
+//! with [`commonware_codec`] and sent over an authenticated and encrypted
+//! [`commonware_stream`] connection.
+
+use std::time::Duration;
+
+use bytes::{Buf, BufMut, Bytes};
+use commonware_codec::{Encode as _, EncodeSize, Error, RangeCfg, Read, ReadExt as _, Write};
+use commonware_consensus::{
+    simplex::types::Proposal,
+    types::{Epoch, Round, View},
+};
+use commonware_cryptography::{
+    bls12381::primitives::{
+        poly::{Eval, PartialSignature},
+        variant::{MinSig, Variant},
+    },
+    ed25519::{PrivateKey, PublicKey, Signature},
+    sha256::Digest,
+};
+use commonware_utils::{union, union_unique};
+
+/// The namespace of the stream connecting a node to its remote signer.
+pub const STREAM_NAMESPACE: &[u8] = b"TEMPO_REMOTE_SIGNER";
+
+/// The maximum size of a message sent over the stream.
+pub const MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Returns the configuration of the stream between a node and its remote
+/// signer, with this end authenticating as `identity`.
+pub(crate) fn stream_config(identity: PrivateKey) -> commonware_stream::Config<PrivateKey> {
+    commonware_stream::Config {
+        signing_key: identity,
+        namespace: STREAM_NAMESPACE.to_vec(),
+        max_message_size: MAX_MESSAGE_SIZE,
+        synchrony_bound: Duration::from_secs(5),
+        max_handshake_age: Duration::from_secs(10),
+        handshake_timeout: Duration::from_secs(10),
+    }
+}
+
+// The namespace suffixes threshold simplex appends to the consensus namespace
+// when signing votes.
+const NOTARIZE_SUFFIX: &[u8] = b"_NOTARIZE";
+const NULLIFY_SUFFIX: &[u8] = b"_NULLIFY";
+const FINALIZE_SUFFIX: &[u8] = b"_FINALIZE";
+const SEED_SUFFIX: &[u8] = b"_SEED";
+
+// The namespace suffixes the node appends to the consensus namespace when
+// signing with the ed25519 key.
+const P2P_SUFFIX: &[u8] = b"_P2P";
+const DKG_ACK_SUFFIX: &[u8] = b"_DKG_ACK";
+const DKG_OUTCOME_SUFFIX: &[u8] = b"_DKG_OUTCOME";
+
+/// The length of the signature hash of a subblock, which is signed without a
+/// namespace.
+const SUBBLOCK_SIGNATURE_HASH_LENGTH: usize = 32;
+
+/// Returns the namespace of the p2p network of the nodes signing under the
+/// consensus `namespace`.
+pub fn p2p_namespace(namespace: &[u8]) -> Vec<u8> {
+    union_unique(namespace, P2P_SUFFIX)
+}
+
+/// Returns whether the node signs messages under `signing_namespace` with the
+/// ed25519 key, given the consensus `namespace`.
+///
+/// These are the p2p handshake, DKG acks and outcomes, and subblocks. The
+/// remote signer refuses to sign anything else so that it cannot be used as a
+/// signing oracle for arbitrary messages.
+pub(crate) fn is_signed_by_node(
+    namespace: &[u8],
+    signing_namespace: Option<&[u8]>,
+    message: &[u8],
+) -> bool {
+    match signing_namespace {
+        Some(signing_namespace) => {
+            signing_namespace.starts_with(&p2p_namespace(namespace))
+                || signing_namespace == union(namespace, DKG_ACK_SUFFIX)
+                || signing_namespace == union(namespace, DKG_OUTCOME_SUFFIX)
+        }
+        None => message.len() == SUBBLOCK_SIGNATURE_HASH_LENGTH,
+    }
+}
+
+/// A request sent from a node to its remote signer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Request {
+    /// Asks for the keys held by the signer.
+    Identity,
+
+    /// Asks for an ed25519 signature over `message`.
+    Sign {
+        namespace: Option<Bytes>,
+        message: Bytes,
+    },
+
+    /// Asks for a partial signature over a simplex vote with the bls12-381
+    /// share.
+    SignVote { namespace: Bytes, vote: Vote },
+
+    /// Asks the signer to record a simplex vote the node signs with a share
+    /// it holds itself.
+    ///
+    /// The signer refuses to record the vote if it conflicts with a vote
+    /// recorded or signed before, in which case the node must not sign it.
+    RecordVote { vote: Vote },
+}
+
+/// A simplex vote to be signed with the share.
+///
+/// Votes are sent in structured form instead of as opaque bytes so that the
+/// signer can enforce that it never signs conflicting votes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Vote {
+    Notarize(Proposal<Digest>),
+    Nullify(Round),
+    Finalize(Proposal<Digest>),
+}
+
+impl Vote {
+    /// Returns the round the vote is cast in.
+    pub fn round(&self) -> Round {
+        match self {
+            Self::Notarize(proposal) | Self::Finalize(proposal) => proposal.round,
+            Self::Nullify(round) => *round,
+        }
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        self.round().epoch()
+    }
+
+    pub fn view(&self) -> View {
+        self.round().view()
+    }
+
+    /// Returns the namespace and message signed for this vote.
+    pub fn signing_payload(&self, namespace: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        match self {
+            Self::Notarize(proposal) => (
+                union(namespace, NOTARIZE_SUFFIX),
+                proposal.encode().to_vec(),
+            ),
+            Self::Nullify(round) => (union(namespace, NULLIFY_SUFFIX), round.encode().to_vec()),
+            Self::Finalize(proposal) => (
+                union(namespace, FINALIZE_SUFFIX),
+                proposal.encode().to_vec(),
+            ),
+        }
+    }
+
+    /// Returns the namespace and message signed for the seed of the vote's
+    /// round.
+    pub fn seed_payload(&self, namespace: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        (
+            union(namespace, SEED_SUFFIX),
+            self.round().encode().to_vec(),
+        )
+    }
+}
+
+/// A response sent from a remote signer to a node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Response {
+    /// The keys held by the signer.
+    Identity {
+        public_key: PublicKey,
+
+        /// The index and public key of the share held by the signer, if any.
+        share: Option<Eval<<MinSig as Variant>::Public>>,
+    },
+
+    /// An ed25519 signature.
+    Signature(Signature),
+
+    /// Partial signatures over a vote and the seed of its round.
+    VoteSignature {
+        vote: PartialSignature<MinSig>,
+        seed: PartialSignature<MinSig>,
+    },
+
+    /// The vote was recorded.
+    Recorded,
+
+    /// The signer refused to sign.
+    Refused(Refusal),
+}
+
+/// The reason a signer refused to sign.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Refusal {
+    /// Signing would conflict with a vote signed earlier.
+    #[error("signing the vote would conflict with an earlier vote")]
+    Conflict,
+
+    /// The vote is older than the oldest vote remembered by the signer.
+    #[error("the vote is older than the oldest vote remembered by the signer")]
+    Stale,
+
+    /// The signer does not hold a share.
+    #[error("the signer does not hold a share")]
+    NoShare,
+
+    /// The request could not be decoded.
+    #[error("the request could not be decoded")]
+    Malformed,
+
+    /// The node does not sign messages under the requested namespace.
+    #[error("the signer does not sign messages under the requested namespace")]
+    Forbidden,
+}
+
+impl Write for Request {
+    fn write(&self, buf: &mut impl BufMut) {
+        match self {
+            Self::Identity => 0u8.write(buf),
+            Self::Sign { namespace, message } => {
+                1u8.write(buf);
+                namespace.write(buf);
+                message.write(buf);
+            }
+            Self::SignVote { namespace, vote } => {
+                2u8.write(buf);
+                namespace.write(buf);
+                vote.write(buf);
+            }
+            Self::RecordVote { vote } => {
+                3u8.write(buf);
+                vote.write(buf);
+            }
+        }
+    }
+}
+
+impl EncodeSize for Request {
+    fn encode_size(&self) -> usize {
+        1 + match self {
+            Self::Identity => 0,
+            Self::Sign { namespace, message } => namespace.encode_size() + message.encode_size(),
+            Self::SignVote { namespace, vote } => namespace.encode_size() + vote.encode_size(),
+            Self::RecordVote { vote } => vote.encode_size(),
+        }
+    }
+}
+
+impl Read for Request {
+    type Cfg = ();
+
+    fn read_cfg(buf: &mut impl Buf, _cfg: &Self::Cfg) -> Result<Self, Error> {
+        let range = RangeCfg::from(0..=MAX_MESSAGE_SIZE);
+        match u8::read(buf)? {
+            0 => Ok(Self::Identity),
+            1 => Ok(Self::Sign {
+                namespace: Option::<Bytes>::read_cfg(buf, &range)?,
+                message: Bytes::read_cfg(buf, &range)?,
+            }),
+            2 => Ok(Self::SignVote {
+                namespace: Bytes::read_cfg(buf, &range)?,
+                vote: Vote::read(buf)?,
+            }),
+            3 => Ok(Self::RecordVote {
+                vote: Vote::read(buf)?,
+            }),
+            other => Err(Error::InvalidEnum(other)),
+        }
+    }
+}
+
+impl Write for Vote {
+    fn write(&self, buf: &mut impl BufMut) {
+        match self {
+            Self::Notarize(proposal) => {
+                0u8.write(buf);
+                proposal.write(buf);
+            }
+            Self::Nullify(round) => {
+                1u8.write(buf);
+                round.write(buf);
+            }
+            Self::Finalize(proposal) => {
+                2u8.write(buf);
+                proposal.write(buf);
+            }
+        }
+    }
+}
+
+impl EncodeSize for Vote {
+    fn encode_size(&self) -> usize {
+        1 + match self {
+            Self::Notarize(proposal) | Self::Finalize(proposal) => proposal.encode_size(),
+            Self::Nullify(round) => round.encode_size(),
+        }
+    }
+}
+
+impl Read for Vote {
+    type Cfg = ();
+
+    fn read_cfg(buf: &mut impl Buf, _cfg: &Self::Cfg) -> Result<Self, Error> {
+        match u8::read(buf)? {
+            0 => Ok(Self::Notarize(Proposal::read(buf)?)),
+            1 => Ok(Self::Nullify(Round::read(buf)?)),
+            2 => Ok(Self::Finalize(Proposal::read(buf)?)),
+            other => Err(Error::InvalidEnum(other)),
+        }
+    }
+}
+
+impl Write for Response {
+    fn write(&self, buf: &mut impl BufMut) {
+        match self {
+            Self::Identity { public_key, share } => {
+                0u8.write(buf);
+                public_key.write(buf);
+                share.write(buf);
+            }
+            Self::Signature(signature) => {
+                1u8.write(buf);
+                signature.write(buf);
+            }
+            Self::VoteSignature { vote, seed } => {
+                2u8.write(buf);
+                vote.write(buf);
+                seed.write(buf);
+            }
+            Self::Refused(refusal) => {
+                3u8.write(buf);
+                refusal.write(buf);
+            }
+            Self::Recorded => 4u8.write(buf),
+        }
+    }
+}
+
+impl EncodeSize for Response {
+    fn encode_size(&self) -> usize {
+        1 + match self {
+            Self::Identity { public_key, share } => public_key.encode_size() + share.encode_size(),
+            Self::Signature(signature) => signature.encode_size(),
+            Self::VoteSignature { vote, seed } => vote.encode_size() + seed.encode_size(),
+            Self::Refused(refusal) => refusal.encode_size(),
+            Self::Recorded => 0,
+        }
+    }
+}
+
+impl Read for Response {
+    type Cfg = ();
+
+    fn read_cfg(buf: &mut impl Buf, _cfg: &Self::Cfg) -> Result<Self, Error> {
+        match u8::read(buf)? {
+            0 => Ok(Self::Identity {
+                public_key: PublicKey::read(buf)?,
+                share: Option::<Eval<<MinSig as Variant>::Public>>::read(buf)?,
+            }),
+            1 => Ok(Self::Signature(Signature::read(buf)?)),
+            2 => Ok(Self::VoteSignature {
+                vote: PartialSignature::<MinSig>::read(buf)?,
+                seed: PartialSignature::<MinSig>::read(buf)?,
+            }),
+            3 => Ok(Self::Refused(Refusal::read(buf)?)),
+            4 => Ok(Self::Recorded),
+            other => Err(Error::InvalidEnum(other)),
+        }
+    }
+}
+
+impl Write for Refusal {
+    fn write(&self, buf: &mut impl BufMut) {
+        let tag: u8 = match self {
+            Self::Conflict => 0,
+            Self::Stale => 1,
+            Self::NoShare => 2,
+            Self::Malformed => 3,
+            Self::Forbidden => 4,
+        };
+        tag.write(buf);
+    }
+}
+
+impl EncodeSize for Refusal {
+    fn encode_size(&self) -> usize {
+        1
+    }
+}
+
+impl Read for Refusal {
+    type Cfg = ();
+
+    fn read_cfg(buf: &mut impl Buf, _cfg: &Self::Cfg) -> Result<Self, Error> {
+        match u8::read(buf)? {
+            0 => Ok(Self::Conflict),
+            1 => Ok(Self::Stale),
+            2 => Ok(Self::NoShare),
+            3 => Ok(Self::Malformed),
+            4 => Ok(Self::Forbidden),
+            other => Err(Error::InvalidEnum(other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use commonware_codec::{DecodeExt as _, Encode as _};
+    use commonware_consensus::{simplex::types::Proposal, types::Round};
+    use commonware_cryptography::{
+        Hasher as _, PrivateKeyExt as _, Sha256, Signer as _, ed25519::PrivateKey,
+    };
+
+    use commonware_utils::union;
+
+    use super::{Refusal, Request, Response, Vote, is_signed_by_node, p2p_namespace};
+
+    fn proposal(view: u64) -> Proposal<commonware_cryptography::sha256::Digest> {
+        let mut hasher = Sha256::new();
+        hasher.update(&view.to_be_bytes());
+        Proposal::new(Round::new(3, view), view - 1, hasher.finalize())
+    }
+
+    #[test]
+    fn roundtrip_requests() {
+        for request in [
+            Request::Identity,
+            Request::Sign {
+                namespace: Some(b"namespace".to_vec().into()),
+                message: b"message".to_vec().into(),
+            },
+            Request::Sign {
+                namespace: None,
+                message: b"message".to_vec().into(),
+            },
+            Request::SignVote {
+                namespace: b"namespace".to_vec().into(),
+                vote: Vote::Notarize(proposal(5)),
+            },
+            Request::SignVote {
+                namespace: b"namespace".to_vec().into(),
+                vote: Vote::Nullify(Round::new(3, 5)),
+            },
+            Request::SignVote {
+                namespace: b"namespace".to_vec().into(),
+                vote: Vote::Finalize(proposal(5)),
+            },
+            Request::RecordVote {
+                vote: Vote::Notarize(proposal(5)),
+            },
+        ] {
+            assert_eq!(Request::decode(request.encode()).unwrap(), request);
+        }
+    }
+
+    #[test]
+    fn roundtrip_responses() {
+        let key = PrivateKey::from_seed(0);
+        for response in [
+            Response::Identity {
+                public_key: key.public_key(),
+                share: None,
+            },
+            Response::Signature(key.sign(None, b"message")),
+            Response::Recorded,
+            Response::Refused(Refusal::Conflict),
+            Response::Refused(Refusal::Forbidden),
+        ] {
+            assert_eq!(Response::decode(response.encode()).unwrap(), response);
+        }
+    }
+
+    #[test]
+    fn node_signing_namespaces_are_allowed() {
+        let namespace = b"TEMPO";
+        assert!(is_signed_by_node(
+            namespace,
+            Some(&p2p_namespace(namespace)),
+            b"handshake",
+        ));
+        assert!(is_signed_by_node(
+            namespace,
+            Some(&union(namespace, b"_DKG_ACK")),
+            b"ack",
+        ));
+        assert!(is_signed_by_node(
+            namespace,
+            Some(&union(namespace, b"_DKG_OUTCOME")),
+            b"outcome",
+        ));
+        assert!(is_signed_by_node(namespace, None, &[0; 32]));
+    }
+
+    #[test]
+    fn other_signing_namespaces_are_forbidden() {
+        let namespace = b"TEMPO";
+        assert!(!is_signed_by_node(
+            namespace,
+            Some(b"OTHER_P2P"),
+            b"message"
+        ));
+        assert!(!is_signed_by_node(
+            namespace,
+            Some(&union(namespace, b"_NOTARIZE")),
+            b"message",
+        ));
+        assert!(!is_signed_by_node(
+            b"OTHER",
+            Some(&union(namespace, b"_DKG_ACK")),
+            b"ack",
+        ));
+        assert!(!is_signed_by_node(namespace, None, b"arbitrary message"));
+    }
+}
@@ -0,0 +1,275 @@
+//! The remote signer's end of the connection.
+
+use std::{collections::HashSet, net::SocketAddr, sync::Arc};
+
+use commonware_codec::{DecodeExt as _, Encode as _};
+use commonware_cryptography::{
+    Signer as _,
+    bls12381::primitives::{group::Share, ops::partial_sign_message, poly::Eval, variant::MinSig},
+    ed25519::{PrivateKey, PublicKey},
+};
+use commonware_runtime::{
+    Clock, Handle, Listener as _, Metrics, Network, SinkOf, Spawner, Storage, StreamOf,
+};
+use commonware_storage::metadata::Metadata;
+use commonware_utils::sequence::U64;
+use futures::lock::Mutex;
+use rand_core::CryptoRngCore;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    RemoteSignerError,
+    protection::History,
+    protocol::{Refusal, Request, Response, Vote, is_signed_by_node, stream_config},
+};
+
+/// The key the slashing protection history is stored under.
+const HISTORY_KEY: u64 = 0;
+
+/// Configuration of a remote signer.
+#[derive(Clone, Debug)]
+pub struct ServerConfig {
+    /// The address to listen on for nodes.
+    pub listen_address: SocketAddr,
+
+    /// The key the signer authenticates itself with to nodes.
+    pub identity: PrivateKey,
+
+    /// The keys of the nodes allowed to connect.
+    pub allowed_clients: Vec<PublicKey>,
+
+    /// The validator's ed25519 signing key.
+    pub signing_key: PrivateKey,
+
+    /// The validator's share of the bls12-381 threshold signing key, if any.
+    pub share: Option<Share>,
+
+    /// The consensus namespace of the network the validator takes part in.
+    ///
+    /// The signer only signs messages under the namespaces the node derives
+    /// from it.
+    pub namespace: Vec<u8>,
+
+    /// The storage partition to persist signed votes in.
+    pub partition: String,
+}
+
+/// Runs a remote signer until the runtime is shut down.
+///
+/// Returns an error if the slashing protection history cannot be read from
+/// disk or the listener cannot be bound.
+pub async fn run<E>(context: E, config: ServerConfig) -> Result<(), RemoteSignerError>
+where
+    E: Clock + CryptoRngCore + Metrics + Network + Spawner + Storage + Clone,
+{
+    let ServerConfig {
+        listen_address,
+        identity,
+        allowed_clients,
+        signing_key,
+        share,
+        namespace,
+        partition,
+    } = config;
+
+    let metadata = Metadata::init(
+        context.with_label("history"),
+        commonware_storage::metadata::Config {
+            partition,
+            codec_config: (),
+        },
+    )
+    .await
+    .map_err(RemoteSignerError::Storage)?;
+    let history = metadata
+        .get(&HISTORY_KEY.into())
+        .cloned()
+        .unwrap_or_default();
+
+    let keys = Arc::new(Mutex::new(Keys {
+        signing_key,
+        share,
+        namespace,
+        metadata,
+        history,
+    }));
+    let allowed_clients: Arc<HashSet<_>> = Arc::new(allowed_clients.into_iter().collect());
+
+    let mut listener = context
+        .bind(listen_address)
+        .await
+        .map_err(RemoteSignerError::Bind)?;
+    info!(
+        %listen_address,
+        identity = %identity.public_key(),
+        "remote signer listening for nodes",
+    );
+
+    loop {
+        let (address, sink, stream) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(error) => {
+                warn!(%error, "failed accepting connection");
+                continue;
+            }
+        };
+        let _: Handle<()> = context.with_label("connection").spawn({
+            let identity = identity.clone();
+            let allowed_clients = allowed_clients.clone();
+            let keys = keys.clone();
+            move |context| {
+                serve(
+                    context,
+                    address,
+                    identity,
+                    allowed_clients,
+                    keys,
+                    sink,
+                    stream,
+                )
+            }
+        });
+    }
+}
+
+/// Serves the requests of a single node.
+#[instrument(skip_all, fields(%address))]
+async fn serve<E>(
+    context: E,
+    address: SocketAddr,
+    identity: PrivateKey,
+    allowed_clients: Arc<HashSet<PublicKey>>,
+    keys: Arc<Mutex<Keys<E>>>,
+    sink: SinkOf<E>,
+    stream: StreamOf<E>,
+) where
+    E: Clock + CryptoRngCore + Metrics + Network + Storage + Clone,
+{
+    let bouncer = move |peer: PublicKey| {
+        let allowed = allowed_clients.contains(&peer);
+        async move { allowed }
+    };
+    let (peer, mut sender, mut receiver) =
+        match commonware_stream::listen(context, bouncer, stream_config(identity), stream, sink)
+            .await
+        {
+            Ok(connection) => connection,
+            Err(error) => {
+                warn!(%error, "handshake with node failed");
+                return;
+            }
+        };
+    info!(%peer, "node connected");
+
+    loop {
+        let request = match receiver.recv().await {
+            Ok(request) => request,
+            Err(error) => {
+                info!(%peer, %error, "connection to node closed");
+                return;
+            }
+        };
+        let response = match Request::decode(request) {
+            Ok(request) => keys.lock().await.handle(request).await,
+            Err(error) => {
+                warn!(%peer, %error, "failed decoding request");
+                Response::Refused(Refusal::Malformed)
+            }
+        };
+        if let Err(error) = sender.send(&response.encode()).await {
+            info!(%peer, %error, "failed sending response; closing connection");
+            return;
+        }
+    }
+}
+
+/// The keys held by the signer, together with the votes signed with the
+/// share.
+struct Keys<E>
+where
+    E: Clock + Metrics + Storage,
+{
+    signing_key: PrivateKey,
+    share: Option<Share>,
+    namespace: Vec<u8>,
+    metadata: Metadata<E, U64, History>,
+    history: History,
+}
+
+impl<E> Keys<E>
+where
+    E: Clock + Metrics + Storage,
+{
+    async fn handle(&mut self, request: Request) -> Response {
+        match request {
+            Request::Identity => Response::Identity {
+                public_key: self.signing_key.public_key(),
+                share: self.share.as_ref().map(|share| Eval {
+                    index: share.index,
+                    value: share.public::<MinSig>(),
+                }),
+            },
+            Request::Sign { namespace, message } => {
+                if !is_signed_by_node(&self.namespace, namespace.as_deref(), &message) {
+                    warn!(
+                        ?namespace,
+                        "refusing to sign message under foreign namespace"
+                    );
+                    return Response::Refused(Refusal::Forbidden);
+                }
+                Response::Signature(self.signing_key.sign(namespace.as_deref(), &message))
+            }
+            Request::SignVote { namespace, vote } => self.sign_vote(&namespace, vote).await,
+            Request::RecordVote { vote } => match self.record(&vote).await {
+                Ok(()) => Response::Recorded,
+                Err(refusal) => Response::Refused(refusal),
+            },
+        }
+    }
+
+    /// Signs `vote` if it does not conflict with a vote signed before.
+    ///
+    /// The vote is persisted before it is signed.
+    #[instrument(skip_all, fields(epoch = vote.epoch(), view = vote.view()))]
+    async fn sign_vote(&mut self, namespace: &[u8], vote: Vote) -> Response {
+        if namespace != self.namespace {
+            warn!("refusing to sign vote under foreign namespace");
+            return Response::Refused(Refusal::Forbidden);
+        }
+        if self.share.is_none() {
+            return Response::Refused(Refusal::NoShare);
+        }
+        if let Err(refusal) = self.record(&vote).await {
+            return Response::Refused(refusal);
+        }
+        let share = self.share.as_ref().expect("checked above");
+
+        let (vote_namespace, vote_message) = vote.signing_payload(namespace);
+        let (seed_namespace, seed_message) = vote.seed_payload(namespace);
+        Response::VoteSignature {
+            vote: partial_sign_message::<MinSig>(share, Some(&vote_namespace), &vote_message),
+            seed: partial_sign_message::<MinSig>(share, Some(&seed_namespace), &seed_message),
+        }
+    }
+
+    /// Records `vote` as signed if it does not conflict with a vote signed
+    /// before, persisting it if it was not recorded yet.
+    async fn record(&mut self, vote: &Vote) -> Result<(), Refusal> {
+        let mut history = self.history.clone();
+        match history.record(vote) {
+            Ok(true) => {
+                self.metadata
+                    .put_sync(HISTORY_KEY.into(), history.clone())
+                    .await
+                    .expect("must be able to persist signed votes before signing");
+                self.history = history;
+                Ok(())
+            }
+            Ok(false) => Ok(()),
+            Err(refusal) => {
+                warn!(?vote, %refusal, "refusing to sign vote");
+                Err(refusal)
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::{net::SocketAddr, time::Duration};
+
+use commonware_consensus::{simplex::types::Proposal, types::Round};
+use commonware_cryptography::{
+    Hasher as _, PrivateKeyExt as _, Sha256, Signer as _,
+    bls12381::{
+        dkg::ops::generate_shares,
+        primitives::{group::Share, ops::partial_verify_message, poly::Public, variant::MinSig},
+    },
+    ed25519::PrivateKey,
+    sha256::Digest,
+};
+use commonware_runtime::{
+    Clock as _, Handle, Listener as _, Metrics as _, Network as _, Runner as _, Spawner as _,
+    deterministic,
+};
+use commonware_utils::{quorum, union};
+
+use crate::{
+    Client, ClientConfig, Refusal, RemoteSignerError, ServerConfig, Vote, p2p_namespace, run,
+};
+
+const NAMESPACE: &[u8] = b"TEST";
+
+fn address(port: u16) -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], port))
+}
+
+fn signer_identity() -> PrivateKey {
+    PrivateKey::from_seed(0)
+}
+
+fn node_identity() -> PrivateKey {
+    PrivateKey::from_seed(1)
+}
+
+fn signing_key() -> PrivateKey {
+    PrivateKey::from_seed(2)
+}
+
+fn shares(context: &mut deterministic::Context) -> (Public<MinSig>, Vec<Share>) {
+    generate_shares::<_, MinSig>(context, None, 4, quorum(4))
+}
+
+fn digest(value: u8) -> Digest {
+    let mut hasher = Sha256::new();
+    hasher.update(&[value]);
+    hasher.finalize()
+}
+
+fn notarize(view: u64, payload: u8) -> Vote {
+    Vote::Notarize(Proposal::new(
+        Round::new(0, view),
+        view - 1,
+        digest(payload),
+    ))
+}
+
+/// Starts a remote signer holding `share` on `port` and waits for it to
+/// listen.
+async fn start_server(
+    context: &deterministic::Context,
+    label: &str,
+    port: u16,
+    share: Option<Share>,
+) -> Handle<Result<(), RemoteSignerError>> {
+    let config = ServerConfig {
+        listen_address: address(port),
+        identity: signer_identity(),
+        allowed_clients: vec![node_identity().public_key()],
+        signing_key: signing_key(),
+        share,
+        namespace: NAMESPACE.to_vec(),
+        partition: "remote_signer".into(),
+    };
+    let handle = context
+        .with_label(label)
+        .spawn(move |context| run(context, config));
+    context.sleep(Duration::from_millis(10)).await;
+    handle
+}
+
+async fn connect(
+    context: &deterministic::Context,
+    port: u16,
+    identity: PrivateKey,
+) -> Result<Client<deterministic::Context>, RemoteSignerError> {
+    Client::connect(
+        context.clone(),
+        ClientConfig {
+            address: address(port),
+            identity,
+            signer_identity: signer_identity().public_key(),
+            timeout: Duration::from_secs(1),
+        },
+    )
+    .await
+}
+
+#[test]
+fn client_learns_keys_held_by_signer() {
+    deterministic::Runner::default().start(|mut context| async move {
+        let (public, shares) = shares(&mut context);
+        let _server = start_server(&context, "server", 8100, Some(shares[1].clone())).await;
+
+        let client = connect(&context, 8100, node_identity()).await.unwrap();
+
+        assert_eq!(client.public_key(), &signing_key().public_key());
+        let share = client.share().unwrap();
+        assert_eq!(share.index, 1);
+        assert_eq!(share.value, public.evaluate(1).value);
+    });
+}
+
+#[test]
+fn clients_not_allowed_are_rejected() {
+    deterministic::Runner::default().start(|context| async move {
+        let _server = start_server(&context, "server", 8100, None).await;
+
+        assert!(matches!(
+            connect(&context, 8100, PrivateKey::from_seed(3)).await,
+            Err(RemoteSignerError::Handshake(_)),
+        ));
+    });
+}
+
+#[test]
+fn connecting_to_unresponsive_signer_times_out() {
+    deterministic::Runner::default().start(|context| async move {
+        // Accepts connections, but never completes the handshake.
+        let mut listener = context.bind(address(8100)).await.unwrap();
+        let _server = context.with_label("server").spawn(|_| async move {
+            let mut connections = Vec::new();
+            loop {
+                connections.push(listener.accept().await.unwrap());
+            }
+        });
+
+        let start = context.current();
+        assert!(matches!(
+            connect(&context, 8100, node_identity()).await,
+            Err(RemoteSignerError::Timeout),
+        ));
+        assert!(context.current().duration_since(start).unwrap() < Duration::from_secs(2));
+    });
+}
+
+#[test]
+fn signer_only_signs_under_node_namespaces() {
+    deterministic::Runner::default().start(|context| async move {
+        let _server = start_server(&context, "server", 8100, None).await;
+        let client = connect(&context, 8100, node_identity()).await.unwrap();
+
+        client
+            .sign(Some(&p2p_namespace(NAMESPACE)), b"handshake")
+            .await
+            .unwrap();
+        client
+            .sign(Some(&union(NAMESPACE, b"_DKG_ACK")), b"ack")
+            .await
+            .unwrap();
+        client.sign(None, &[1; 32]).await.unwrap();
+
+        assert!(matches!(
+            client.sign(Some(b"OTHER"), b"message").await,
+            Err(RemoteSignerError::Refused(Refusal::Forbidden)),
+        ));
+        assert!(matches!(
+            client.sign(None, b"arbitrary message").await,
+            Err(RemoteSignerError::Refused(Refusal::Forbidden)),
+        ));
+    });
+}
+
+#[test]
+fn signed_votes_verify_and_conflicts_are_refused() {
+    deterministic::Runner::default().start(|mut context| async move {
+        let (public, shares) = shares(&mut context);
+        let _server = start_server(&context, "server", 8100, Some(shares[1].clone())).await;
+        let client = connect(&context, 8100, node_identity()).await.unwrap();
+
+        let vote = notarize(5, 1);
+        let (signature, seed) = client.sign_vote(NAMESPACE, vote.clone()).await.unwrap();
+        let (vote_namespace, vote_message) = vote.signing_payload(NAMESPACE);
+        partial_verify_message::<MinSig>(&public, Some(&vote_namespace), &vote_message, &signature)
+            .unwrap();
+        let (seed_namespace, seed_message) = vote.seed_payload(NAMESPACE);
+        partial_verify_message::<MinSig>(&public, Some(&seed_namespace), &seed_message, &seed)
+            .unwrap();
+
+        // Signing the same vote again is allowed, but not a conflicting one.
+        client.sign_vote(NAMESPACE, vote).await.unwrap();
+        assert!(matches!(
+            client.sign_vote(NAMESPACE, notarize(5, 2)).await,
+            Err(RemoteSignerError::Refused(Refusal::Conflict)),
+        ));
+        assert!(matches!(
+            client.sign_vote(b"OTHER", notarize(6, 1)).await,
+            Err(RemoteSignerError::Refused(Refusal::Forbidden)),
+        ));
+    });
+}
+
+#[test]
+fn recorded_votes_are_protected_across_restarts() {
+    deterministic::Runner::default().start(|mut context| async move {
+        let (_, shares) = shares(&mut context);
+        let server = start_server(&context, "first", 8100, Some(shares[1].clone())).await;
+        let client = connect(&context, 8100, node_identity()).await.unwrap();
+
+        client.record_vote(notarize(5, 1)).await.unwrap();
+        assert!(matches!(
+            client.record_vote(notarize(5, 2)).await,
+            Err(RemoteSignerError::Refused(Refusal::Conflict)),
+        ));
+        drop(client);
+        server.abort();
+
+        let _server = start_server(&context, "second", 8101, Some(shares[1].clone())).await;
+        let client = connect(&context, 8101, node_identity()).await.unwrap();
+        assert!(matches!(
+            client.sign_vote(NAMESPACE, notarize(5, 2)).await,
+            Err(RemoteSignerError::Refused(Refusal::Conflict)),
+        ));
+        client.sign_vote(NAMESPACE, notarize(5, 1)).await.unwrap();
+    });
+}
+
+#[test]
+fn signer_without_share_refuses_votes() {
+    deterministic::Runner::default().start(|context| async move {
+        let _server = start_server(&context, "server", 8100, None).await;
+        let client = connect(&context, 8100, node_identity()).await.unwrap();
+
+        assert!(client.share().is_none());
+        assert!(matches!(
+            client.sign_vote(NAMESPACE, notarize(5, 1)).await,
+            Err(RemoteSignerError::Refused(Refusal::NoShare)),
+        ));
+    });
+}
@@ -6,7 +6,7 @@ use revm::{
 };
 
 /// Tempo block environment.
-#[derive(Debug, Clone, Default, derive_more::Deref, derive_more::DerefMut)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, derive_more::Deref, derive_more::DerefMut)]
 pub struct TempoBlockEnv {
     /// Inner [`BlockEnv`].
     #[deref]
@@ -15,6 +15,11 @@ pub struct TempoBlockEnv {
 
     /// Milliseconds portion of the timestamp.
     pub timestamp_millis_part: u64,
+
+    /// Base fee of the payment lane, if it is priced separately from the general lane.
+    ///
+    /// Payment transactions are executed with this base fee instead of [`BlockEnv::basefee`].
+    pub payment_basefee: Option<u64>,
 }
 
 impl TempoBlockEnv {
@@ -25,6 +30,14 @@ impl TempoBlockEnv {
             .saturating_mul(uint!(1000_U256))
             .saturating_add(U256::from(self.timestamp_millis_part))
     }
+
+    /// Returns the base fee of the lane of a transaction, given whether it is a payment.
+    pub fn lane_basefee(&self, is_payment: bool) -> u64 {
+        match self.payment_basefee {
+            Some(payment_basefee) if is_payment => payment_basefee,
+            _ => self.inner.basefee,
+        }
+    }
 }
 
 impl Block for TempoBlockEnv {
@@ -130,6 +130,36 @@ pub enum TempoInvalidTransaction {
     #[error("keychain operations are not supported in subblock transactions")]
     KeychainOpInSubblockTransaction,
 
+    /// Multisig signatures are only supported after Allegro-Moderato.
+    #[error("multisig signatures are only supported after Allegro-Moderato")]
+    MultisigBeforeAllegroModerato,
+
+    /// Multisig signatures are not supported in subblock transactions.
+    #[error("multisig signatures are not supported in subblock transactions")]
+    MultisigInSubblockTransaction,
+
+    /// Ed25519 signatures and keys are only supported after Allegro-Moderato.
+    #[error("Ed25519 signatures are only supported after Allegro-Moderato")]
+    Ed25519BeforeAllegroModerato,
+
+    /// EIP-712 signatures are only supported after Allegro-Moderato.
+    #[error("EIP-712 signatures are only supported after Allegro-Moderato")]
+    Eip712BeforeAllegroModerato,
+
+    /// Encrypted transactions are only supported after Allegro-Moderato.
+    #[error("encrypted transactions are only supported after Allegro-Moderato")]
+    EncryptedBeforeAllegroModerato,
+
+    /// Multisig authorization failed.
+    ///
+    /// This error occurs when the co-signers of a multisig signature are not registered for the
+    /// account in the AccountKeychain precompile or do not reach the configured threshold.
+    #[error("multisig authorization failed: {reason}")]
+    MultisigAuthorizationFailed {
+        /// Specific reason for failure.
+        reason: String,
+    },
+
     /// Fee payment error.
     #[error(transparent)]
     CollectFeePreTx(#[from] FeePaymentError),
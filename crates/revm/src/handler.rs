@@ -21,7 +21,8 @@ use revm::{
         Gas, InitialAndFloorGas,
         gas::{
             ACCESS_LIST_ADDRESS, ACCESS_LIST_STORAGE_KEY, CALLVALUE, COLD_ACCOUNT_ACCESS_COST,
-            CREATE, STANDARD_TOKEN_COST, calc_tx_floor_cost, get_tokens_in_calldata, initcode_cost,
+            COLD_SLOAD_COST, CREATE, STANDARD_TOKEN_COST, calc_tx_floor_cost,
+            get_tokens_in_calldata, initcode_cost,
         },
         interpreter::EthInterpreter,
     },
@@ -36,13 +37,15 @@ use tempo_precompiles::{
     account_keychain::{AccountKeychain, TokenLimit, authorizeKeyCall},
     error::TempoPrecompileError,
     nonce::{INonce::getNonceCall, NonceManager},
+    signature_verifier::{self, ECRECOVER_GAS},
     storage::StorageCtx,
     tip_fee_manager::TipFeeManager,
     tip20::{self, ITIP20::InsufficientBalance, TIP20Error, TIP20Token},
+    transaction_context::{self, TransactionContext},
 };
 use tempo_primitives::transaction::{
-    PrimitiveSignature, RecoveredTempoAuthorization, SignatureType, TempoSignature,
-    calc_gas_balance_spending,
+    ENCRYPTED_TX_TYPE_ID, PrimitiveSignature, RecoveredTempoAuthorization, SignatureType,
+    TempoSignature, calc_gas_balance_spending,
 };
 
 use crate::{
@@ -50,11 +53,24 @@ use crate::{
     common::TempoStateAccess,
     error::{FeePaymentError, TempoHaltReason},
     evm::TempoContext,
+    tx::TempoTxEnv,
 };
 
 /// Additional gas for P256 signature verification
 /// P256 precompile cost (6900 from EIP-7951) + 1100 for 129 bytes extra signature size - ecrecover savings (3000)
-const P256_VERIFY_GAS: u64 = 5_000;
+const P256_VERIFY_GAS: u64 = signature_verifier::P256_VERIFY_GAS + 1_100 - ECRECOVER_GAS;
+
+/// Additional gas for Ed25519 signature verification
+/// Ed25519 verification cost (4500) + 500 for 31 bytes extra signature size - ecrecover savings (3000)
+const ED25519_VERIFY_GAS: u64 = signature_verifier::ED25519_VERIFY_GAS + 500 - ECRECOVER_GAS;
+
+/// Gas for each additional co-signer of a multisig signature
+/// The base 21k only covers a single ecrecover, every further co-signer pays the ecrecover cost (3000)
+const MULTISIG_SIGNER_GAS: u64 = ECRECOVER_GAS;
+
+/// Gas for each account keychain slot read while validating a multisig signature
+/// Validation reads the account's threshold plus one membership slot per co-signer, all cold
+const MULTISIG_CONFIG_READ_GAS: u64 = COLD_SLOAD_COST;
 
 /// Hashed account code of default 7702 delegate deployment
 const DEFAULT_7702_DELEGATE_CODE_HASH: B256 =
@@ -66,6 +82,7 @@ const DEFAULT_7702_DELEGATE_CODE_HASH: B256 =
 /// - Secp256k1: 0 (already included in base 21k)
 /// - P256: 5000 gas
 /// - WebAuthn: 5000 gas + calldata cost for webauthn_data
+/// - Ed25519: 2000 gas
 #[inline]
 fn primitive_signature_verification_gas(signature: &PrimitiveSignature) -> u64 {
     match signature {
@@ -75,12 +92,17 @@ fn primitive_signature_verification_gas(signature: &PrimitiveSignature) -> u64 {
             let tokens = get_tokens_in_calldata(&webauthn_sig.webauthn_data, true);
             P256_VERIFY_GAS + tokens * STANDARD_TOKEN_COST
         }
+        PrimitiveSignature::Ed25519(_) => ED25519_VERIFY_GAS,
     }
 }
 
 /// Calculates the gas cost for verifying an AA signature.
 ///
 /// For Keychain signatures, unwraps to the inner primitive signature for gas calculation.
+/// For Multisig signatures, sums the gas of every co-signer signature plus
+/// [`MULTISIG_SIGNER_GAS`] for each co-signer beyond the first, and charges
+/// [`MULTISIG_CONFIG_READ_GAS`] for the threshold and each co-signer's membership read.
+/// For EIP-712 signatures, charges the gas of the inner signature.
 /// Returns the additional gas required beyond the base transaction cost.
 #[inline]
 fn tempo_signature_verification_gas(signature: &TempoSignature) -> u64 {
@@ -90,6 +112,20 @@ fn tempo_signature_verification_gas(signature: &TempoSignature) -> u64 {
             // Keychain wraps a primitive signature - calculate gas for the inner signature
             primitive_signature_verification_gas(&keychain_sig.signature)
         }
+        TempoSignature::Multisig(multisig_sig) => {
+            let additional_signers = multisig_sig.signatures.len().saturating_sub(1) as u64;
+            multisig_sig
+                .signatures
+                .iter()
+                .map(primitive_signature_verification_gas)
+                .sum::<u64>()
+                + additional_signers * MULTISIG_SIGNER_GAS
+                + (1 + multisig_sig.signatures.len() as u64) * MULTISIG_CONFIG_READ_GAS
+        }
+        TempoSignature::Eip712(eip712_sig) => {
+            // The EIP-712 flag only changes the signed hash - charge for the inner signature
+            tempo_signature_verification_gas(&eip712_sig.signature)
+        }
     }
 }
 
@@ -234,7 +270,22 @@ where
 
         let mut final_result = None;
 
-        for call in calls.iter() {
+        for (index, call) in calls.iter().enumerate() {
+            // Expose the index of this call to contracts through the transaction context
+            let (block, _, cfg, journal, _, _) = evm.ctx().all_mut();
+            if cfg.spec.is_allegro_moderato() {
+                StorageCtx::enter_precompile(
+                    journal,
+                    block,
+                    cfg,
+                    |mut context: TransactionContext| {
+                        context
+                            .set_call_index(index as u64)
+                            .map_err(|e| EVMError::Custom(e.to_string()))
+                    },
+                )?;
+            }
+
             // Update TxEnv to point to this specific call
             {
                 let tx = &mut evm.ctx().tx;
@@ -694,6 +745,14 @@ where
                     SignatureType::Secp256k1 => PrecompileSignatureType::Secp256k1,
                     SignatureType::P256 => PrecompileSignatureType::P256,
                     SignatureType::WebAuthn => PrecompileSignatureType::WebAuthn,
+                    SignatureType::Ed25519 => PrecompileSignatureType::Ed25519,
+                    SignatureType::Multisig => {
+                        return Err(EVMError::Transaction(
+                            TempoInvalidTransaction::AccessKeyAuthorizationFailed {
+                                reason: "Multisig is not a valid access key type".to_string(),
+                            },
+                        ));
+                    }
                 };
 
                 // Handle expiry: None means never expires (store as u64::MAX)
@@ -822,6 +881,59 @@ where
             })?;
         }
 
+        // For Multisig signatures, validate the co-signers against the account's multisig config
+        if let Some(tempo_tx_env) = tx.tempo_tx_env.as_ref()
+            && let Some(multisig_sig) = tempo_tx_env.signature.as_multisig()
+        {
+            // Sanity check: account should match tx.caller
+            if multisig_sig.account != tx.caller {
+                return Err(EVMError::Transaction(
+                    TempoInvalidTransaction::MultisigAuthorizationFailed {
+                        reason: format!(
+                            "Multisig account {} does not match transaction caller {}",
+                            multisig_sig.account, tx.caller
+                        ),
+                    },
+                ));
+            }
+
+            // Get the co-signers (recovered during Tx->TxEnv conversion and cached)
+            let signers = multisig_sig
+                .signers(&tempo_tx_env.signature_hash)
+                .map_err(|_| {
+                    EVMError::Transaction(TempoInvalidTransaction::MultisigAuthorizationFailed {
+                        reason: "Failed to recover co-signers from Multisig signature".to_string(),
+                    })
+                })?;
+
+            StorageCtx::enter_precompile(journal, block, cfg, |keychain: AccountKeychain| {
+                keychain
+                    .validate_multisig(multisig_sig.account, signers)
+                    .map_err(|err| match err {
+                        TempoPrecompileError::Fatal(err) => EVMError::Custom(err),
+                        err => TempoInvalidTransaction::MultisigAuthorizationFailed {
+                            reason: err.to_string(),
+                        }
+                        .into(),
+                    })
+            })?;
+        }
+
+        // Expose the fee and signing context to contracts through the transaction context precompile
+        if cfg.spec.is_allegro_moderato() {
+            let context = build_transaction_context(tx, self.fee_token, self.fee_payer)?;
+            StorageCtx::enter_precompile(
+                journal,
+                block,
+                cfg,
+                |mut precompile: TransactionContext| {
+                    precompile
+                        .set_context(context)
+                        .map_err(|e| EVMError::Custom(e.to_string()))
+                },
+            )?;
+        }
+
         if gas_balance_spending.is_zero() {
             return Ok(());
         }
@@ -962,6 +1074,11 @@ where
         let cfg = evm.ctx_ref().cfg();
         let tx = evm.ctx_ref().tx();
 
+        // Validate that encrypted transactions are only supported after Allegro-Moderato
+        if tx.tx_type() == ENCRYPTED_TX_TYPE_ID && !cfg.spec.is_allegro_moderato() {
+            return Err(TempoInvalidTransaction::EncryptedBeforeAllegroModerato.into());
+        }
+
         if let Some(aa_env) = tx.tempo_tx_env.as_ref() {
             let has_keychain_fields =
                 aa_env.key_authorization.is_some() || aa_env.signature.is_keychain();
@@ -971,7 +1088,41 @@ where
                 return Err(TempoInvalidTransaction::KeychainOpBeforeAllegretto.into());
             }
 
+            // Validate that multisig signatures are only supported after Allegro-Moderato
+            if aa_env.signature.is_multisig() && !cfg.spec.is_allegro_moderato() {
+                return Err(TempoInvalidTransaction::MultisigBeforeAllegroModerato.into());
+            }
+
+            // Validate that Ed25519 signatures and keys are only supported after Allegro-Moderato
+            if !cfg.spec.is_allegro_moderato() {
+                let uses_ed25519 = aa_env.signature.uses_ed25519()
+                    || aa_env.key_authorization.as_ref().is_some_and(|auth| {
+                        auth.key_type == SignatureType::Ed25519 || auth.signature.is_ed25519()
+                    })
+                    || aa_env
+                        .tempo_authorization_list
+                        .iter()
+                        .any(|auth| auth.signature().uses_ed25519());
+                if uses_ed25519 {
+                    return Err(TempoInvalidTransaction::Ed25519BeforeAllegroModerato.into());
+                }
+
+                // Validate that EIP-712 signatures are only supported after Allegro-Moderato
+                let uses_eip712 = aa_env.signature.is_eip712()
+                    || aa_env
+                        .key_authorization
+                        .as_ref()
+                        .is_some_and(|auth| auth.eip712);
+                if uses_eip712 {
+                    return Err(TempoInvalidTransaction::Eip712BeforeAllegroModerato.into());
+                }
+            }
+
             if aa_env.subblock_transaction {
+                if aa_env.signature.is_multisig() {
+                    return Err(TempoInvalidTransaction::MultisigInSubblockTransaction.into());
+                }
+
                 if !cfg.spec.is_allegretto() {
                     if tx.max_fee_per_gas() > 0 {
                         return Err(
@@ -1244,6 +1395,51 @@ where
     Ok(balance)
 }
 
+/// Builds the context exposed to the contracts executed by `tx` through the transaction context
+/// precompile.
+///
+/// The signing key is the access key for keychain signatures, and the caller otherwise.
+fn build_transaction_context(
+    tx: &TempoTxEnv,
+    fee_token: Address,
+    fee_payer: Address,
+) -> Result<transaction_context::Context, TempoInvalidTransaction> {
+    let mut context = transaction_context::Context {
+        feeToken: fee_token,
+        feePayer: fee_payer,
+        keyId: tx.caller(),
+        signatureType: transaction_context::SignatureType::Secp256k1,
+        isAccessKey: false,
+        validAfter: 0,
+        validBefore: 0,
+        nonceKey: U256::ZERO,
+        callIndex: 0,
+    };
+
+    if let Some(tempo_tx_env) = tx.tempo_tx_env.as_ref() {
+        context.signatureType = match tempo_tx_env.signature.signature_type() {
+            SignatureType::Secp256k1 => transaction_context::SignatureType::Secp256k1,
+            SignatureType::P256 => transaction_context::SignatureType::P256,
+            SignatureType::WebAuthn => transaction_context::SignatureType::WebAuthn,
+            SignatureType::Ed25519 => transaction_context::SignatureType::Ed25519,
+            SignatureType::Multisig => transaction_context::SignatureType::Multisig,
+        };
+        if let Some(keychain_sig) = tempo_tx_env.signature.as_keychain() {
+            context.keyId = keychain_sig
+                .key_id(&tempo_tx_env.signature_hash)
+                .map_err(|_| TempoInvalidTransaction::AccessKeyAuthorizationFailed {
+                    reason: "Failed to recover access key address from inner signature".to_string(),
+                })?;
+            context.isAccessKey = true;
+        }
+        context.validAfter = tempo_tx_env.valid_after.unwrap_or_default();
+        context.validBefore = tempo_tx_env.valid_before.unwrap_or_default();
+        context.nonceKey = tempo_tx_env.nonce_key;
+    }
+
+    Ok(context)
+}
+
 impl<DB, I> InspectorHandler for TempoEvmHandler<DB, I>
 where
     DB: alloy_evm::Database,
@@ -1609,6 +1805,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_aa_gas_multisig_signature() {
+        use alloy_primitives::B256;
+        use tempo_primitives::transaction::{
+            MultisigSignature, TempoSignature, tt_signature::P256SignatureWithPreHash,
+        };
+
+        let secp256k1 =
+            PrimitiveSignature::Secp256k1(alloy_primitives::Signature::test_signature());
+        let p256 = PrimitiveSignature::P256(P256SignatureWithPreHash {
+            r: B256::ZERO,
+            s: B256::ZERO,
+            pub_key_x: B256::ZERO,
+            pub_key_y: B256::ZERO,
+            pre_hash: false,
+        });
+
+        // A single co-signer costs its primitive signature plus the threshold and membership reads
+        let single = TempoSignature::Multisig(MultisigSignature::new(
+            Address::random(),
+            vec![p256.clone()],
+        ));
+        assert_eq!(
+            tempo_signature_verification_gas(&single),
+            P256_VERIFY_GAS + 2 * MULTISIG_CONFIG_READ_GAS
+        );
+
+        // Every additional co-signer pays an extra ecrecover and membership read on top of its
+        // signature cost
+        let mixed = TempoSignature::Multisig(MultisigSignature::new(
+            Address::random(),
+            vec![secp256k1.clone(), p256, secp256k1],
+        ));
+        assert_eq!(
+            tempo_signature_verification_gas(&mixed),
+            P256_VERIFY_GAS + 2 * MULTISIG_SIGNER_GAS + 4 * MULTISIG_CONFIG_READ_GAS
+        );
+    }
+
+    #[test]
+    fn test_aa_gas_ed25519_signature() {
+        use alloy_primitives::{B256, B512};
+        use tempo_primitives::transaction::{Ed25519Signature, KeychainSignature, TempoSignature};
+
+        let ed25519 = PrimitiveSignature::Ed25519(Ed25519Signature {
+            public_key: B256::ZERO,
+            signature: B512::ZERO,
+        });
+        assert_eq!(
+            tempo_signature_verification_gas(&TempoSignature::Primitive(ed25519.clone())),
+            ED25519_VERIFY_GAS
+        );
+
+        // Keychain signatures pay for the inner Ed25519 signature
+        let keychain = TempoSignature::Keychain(KeychainSignature::new(Address::random(), ed25519));
+        assert_eq!(
+            tempo_signature_verification_gas(&keychain),
+            ED25519_VERIFY_GAS
+        );
+    }
+
     #[test]
     fn test_aa_gas_create_call() {
         use crate::TempoBatchCallEnv;
@@ -1841,6 +2098,153 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_transaction_context() -> eyre::Result<()> {
+        use crate::TempoBatchCallEnv;
+        use tempo_primitives::transaction::{MultisigSignature, TempoSignature};
+
+        let fee_token = Address::random();
+        let fee_payer = Address::random();
+        let mut tx = TempoTxEnv::default();
+        tx.inner.caller = Address::random();
+
+        // Non-Tempo transactions are signed by the sender's secp256k1 key
+        let context = build_transaction_context(&tx, fee_token, fee_payer)?;
+        assert_eq!(
+            context,
+            transaction_context::Context {
+                feeToken: fee_token,
+                feePayer: fee_payer,
+                keyId: tx.caller(),
+                signatureType: transaction_context::SignatureType::Secp256k1,
+                isAccessKey: false,
+                validAfter: 0,
+                validBefore: 0,
+                nonceKey: U256::ZERO,
+                callIndex: 0,
+            }
+        );
+
+        tx.tempo_tx_env = Some(Box::new(TempoBatchCallEnv {
+            signature: TempoSignature::Multisig(MultisigSignature::new(
+                tx.caller(),
+                vec![PrimitiveSignature::Secp256k1(
+                    alloy_primitives::Signature::test_signature(),
+                )],
+            )),
+            valid_after: Some(10),
+            valid_before: Some(20),
+            nonce_key: U256::from(3),
+            ..Default::default()
+        }));
+        let context = build_transaction_context(&tx, fee_token, fee_payer)?;
+        assert_eq!(
+            context.signatureType,
+            transaction_context::SignatureType::Multisig
+        );
+        assert_eq!(context.keyId, tx.caller());
+        assert!(!context.isAccessKey);
+        assert_eq!(context.validAfter, 10);
+        assert_eq!(context.validBefore, 20);
+        assert_eq!(context.nonceKey, U256::from(3));
+
+        Ok(())
+    }
+
+    /// Records the transaction context seen by every top-level call of a transaction.
+    #[derive(Debug, Default)]
+    struct TransactionContextRecorder(Vec<transaction_context::Context>);
+
+    impl<DB: alloy_evm::Database> Inspector<TempoContext<DB>> for TransactionContextRecorder {
+        fn call(
+            &mut self,
+            context: &mut TempoContext<DB>,
+            _inputs: &mut revm::interpreter::CallInputs,
+        ) -> Option<revm::interpreter::CallOutcome> {
+            if context.journal_mut().depth() == 0 {
+                let (block, _, cfg, journal, _, _) = context.all_mut();
+                let tx_context = StorageCtx::enter_precompile(
+                    journal,
+                    block,
+                    cfg,
+                    |precompile: TransactionContext| precompile.get_context(),
+                )
+                .expect("transaction context is readable");
+                self.0.push(tx_context);
+            }
+            None
+        }
+    }
+
+    #[test]
+    fn test_transaction_context_is_set_for_every_call() -> eyre::Result<()> {
+        use crate::{TempoBatchCallEnv, TempoEvm};
+        use alloy_primitives::{B256, Bytes, TxKind};
+        use revm::{context::CfgEnv, inspector::InspectEvm};
+        use tempo_primitives::transaction::{
+            Call, TempoSignature, tt_signature::P256SignatureWithPreHash,
+        };
+
+        let mut cfg = CfgEnv::<TempoHardfork>::default();
+        cfg.spec = TempoHardfork::AllegroModerato;
+        let ctx = Context::mainnet()
+            .with_db(CacheDB::new(EmptyDB::default()))
+            .with_block(TempoBlockEnv::default())
+            .with_cfg(cfg)
+            .with_tx(TempoTxEnv::default());
+        let mut evm = TempoEvm::new(ctx, TransactionContextRecorder::default());
+
+        // A free transaction, so that no fee token balance is needed
+        let caller = Address::random();
+        let targets = [Address::random(), Address::random(), Address::random()];
+        let mut tx = TempoTxEnv::default();
+        tx.inner.caller = caller;
+        tx.inner.gas_limit = 1_000_000;
+        tx.inner.gas_price = 0;
+        tx.inner.kind = TxKind::Call(targets[0]);
+        tx.tempo_tx_env = Some(Box::new(TempoBatchCallEnv {
+            signature: TempoSignature::Primitive(PrimitiveSignature::P256(
+                P256SignatureWithPreHash {
+                    r: B256::ZERO,
+                    s: B256::ZERO,
+                    pub_key_x: B256::ZERO,
+                    pub_key_y: B256::ZERO,
+                    pre_hash: false,
+                },
+            )),
+            aa_calls: targets
+                .iter()
+                .map(|to| Call {
+                    to: TxKind::Call(*to),
+                    value: U256::ZERO,
+                    input: Bytes::new(),
+                })
+                .collect(),
+            valid_before: Some(100),
+            ..Default::default()
+        }));
+
+        let result = evm.inspect_one_tx(tx)?;
+        assert!(result.is_success());
+
+        let contexts = &evm.inner.inspector.0;
+        assert_eq!(contexts.len(), targets.len());
+        for (index, context) in contexts.iter().enumerate() {
+            assert_eq!(context.feePayer, caller);
+            assert_eq!(context.keyId, caller);
+            assert_eq!(
+                context.signatureType,
+                transaction_context::SignatureType::P256
+            );
+            assert!(!context.isAccessKey);
+            assert_eq!(context.validBefore, 100);
+            assert_eq!(context.callIndex, index as u64);
+        }
+        assert_ne!(contexts[0].feeToken, Address::ZERO);
+
+        Ok(())
+    }
+
     /// This test will start failing once we get the balance transfer enabled
     /// PR that introduced [`TempoInvalidTransaction::ValueTransferNotAllowed`] https://github.com/tempoxyz/tempo/pull/759
     #[test]
@@ -12,9 +12,10 @@ use revm::context::{
     },
 };
 use tempo_primitives::{
-    AASigned, TempoSignature, TempoTransaction, TempoTxEnvelope, TxFeeToken,
+    AASigned, TempoSignature, TempoTransaction, TempoTxEnvelope, TxEncrypted, TxFeeToken,
     transaction::{
-        Call, RecoveredTempoAuthorization, SignedKeyAuthorization, calc_gas_balance_spending,
+        Call, ENCRYPTED_TX_ADDRESS, RecoveredTempoAuthorization, SignedKeyAuthorization,
+        calc_gas_balance_spending, envelope::TIP20_PAYMENT_PREFIX,
     },
 };
 
@@ -93,6 +94,17 @@ impl TempoTxEnv {
             .is_some_and(|aa| aa.subblock_transaction)
     }
 
+    /// Returns true if the transaction is a payment, i.e. all of its top-level calls go to TIP-20
+    /// tokens.
+    ///
+    /// Mirrors [`TempoTxEnvelope::is_payment`].
+    pub fn is_payment(&self) -> bool {
+        self.calls().all(|(kind, _)| {
+            kind.to()
+                .is_some_and(|to| to.starts_with(&TIP20_PAYMENT_PREFIX))
+        })
+    }
+
     /// Returns the first top-level call in the transaction.
     pub fn first_call(&self) -> Option<(&TxKind, &[u8])> {
         if let Some(aa) = self.tempo_tx_env.as_ref() {
@@ -314,6 +326,11 @@ impl FromRecoveredTx<AASigned> for TempoTxEnv {
             let _ = keychain_sig.key_id(&aa_signed.signature_hash());
         }
 
+        // Same for the co-signers of Multisig signatures
+        if let Some(multisig_sig) = signature.as_multisig() {
+            let _ = multisig_sig.signers(&aa_signed.signature_hash());
+        }
+
         let TempoTransaction {
             chain_id,
             fee_token,
@@ -396,6 +413,32 @@ impl FromRecoveredTx<AASigned> for TempoTxEnv {
     }
 }
 
+impl FromRecoveredTx<TxEncrypted> for TempoTxEnv {
+    fn from_recovered_tx(tx: &TxEncrypted, caller: Address) -> Self {
+        let TxEncrypted {
+            chain_id,
+            nonce,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit,
+            ciphertext,
+        } = tx;
+        TxEnv {
+            tx_type: tx.ty(),
+            caller,
+            gas_limit: *gas_limit,
+            gas_price: *max_fee_per_gas,
+            kind: TxKind::Call(ENCRYPTED_TX_ADDRESS),
+            data: ciphertext.clone(),
+            nonce: *nonce,
+            chain_id: Some(*chain_id),
+            gas_priority_fee: Some(*max_priority_fee_per_gas),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 impl FromRecoveredTx<TempoTxEnvelope> for TempoTxEnv {
     fn from_recovered_tx(tx: &TempoTxEnvelope, sender: Address) -> Self {
         match tx {
@@ -411,6 +454,7 @@ impl FromRecoveredTx<TempoTxEnvelope> for TempoTxEnv {
             TempoTxEnvelope::Eip7702(tx) => TxEnv::from_recovered_tx(tx.tx(), sender).into(),
             TempoTxEnvelope::AA(tx) => Self::from_recovered_tx(tx, sender),
             TempoTxEnvelope::FeeToken(tx) => Self::from_recovered_tx(tx.tx(), sender),
+            TempoTxEnvelope::Encrypted(tx) => Self::from_recovered_tx(tx.tx(), sender),
         }
     }
 }
@@ -431,6 +475,12 @@ impl FromTxWithEncoded<TxFeeToken> for TempoTxEnv {
     }
 }
 
+impl FromTxWithEncoded<TxEncrypted> for TempoTxEnv {
+    fn from_encoded_tx(tx: &TxEncrypted, sender: Address, _encoded: Bytes) -> Self {
+        Self::from_recovered_tx(tx, sender)
+    }
+}
+
 impl FromTxWithEncoded<AASigned> for TempoTxEnv {
     fn from_encoded_tx(tx: &AASigned, sender: Address, _encoded: Bytes) -> Self {
         Self::from_recovered_tx(tx, sender)
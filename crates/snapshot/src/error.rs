@@ -0,0 +1,55 @@
+//! Snapshot error types.
+
+use tempo_light_client::LightClientError;
+
+/// Errors returned when reading, writing, or verifying a snapshot.
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    /// Reading or writing the snapshot failed.
+    #[error("failed reading or writing snapshot: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A line of the snapshot is not valid JSON of the expected shape.
+    #[error("failed to parse snapshot line: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The snapshot is empty.
+    #[error("snapshot is empty")]
+    Empty,
+
+    /// The snapshot was written in a format this node does not understand.
+    #[error("unsupported snapshot version `{0}`")]
+    UnsupportedVersion(u64),
+
+    /// The snapshot does not contain any finalized boundary block.
+    #[error("snapshot does not contain any epoch boundary")]
+    NoBoundaries,
+
+    /// A boundary header could not be RLP-decoded.
+    #[error("failed to decode boundary header: {0}")]
+    HeaderDecoding(#[source] alloy_rlp::Error),
+
+    /// A header in the snapshot is not the last block of the epoch being verified.
+    #[error("snapshot contains block `{number}`, expected epoch boundary `{expected}`")]
+    NotABoundary { number: u64, expected: u64 },
+
+    /// The DKG outcome in a boundary header could not be decoded.
+    #[error("failed to decode DKG outcome from boundary header: {0}")]
+    OutcomeDecoding(#[source] commonware_codec::Error),
+
+    /// A boundary header failed to verify against its finalization certificate.
+    #[error("failed to verify boundary header: {0}")]
+    Verification(#[from] LightClientError),
+
+    /// Reading the state to export from the database failed.
+    #[error("failed reading state from database: {0}")]
+    Database(#[from] reth_db_api::DatabaseError),
+
+    /// Reading the state to export from a state provider failed.
+    #[error("failed reading state: {0}")]
+    Provider(#[from] reth_storage_api::errors::ProviderError),
+
+    /// The database has not persisted the block the snapshot is taken at.
+    #[error("database has persisted up to block `{persisted}`, snapshot requested at `{block}`")]
+    BlockNotPersisted { block: u64, persisted: u64 },
+}
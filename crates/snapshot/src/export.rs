@@ -0,0 +1,315 @@
+//! Exports the state of the chain at a past block.
+//!
+//! reth keeps only the latest state in plain tables, together with change sets recording the
+//! value every account and storage slot had before each block changed it. The state at block
+//! `B` is therefore the latest state, except for accounts and slots changed after `B`, whose
+//! values are read from history.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+use alloy_genesis::GenesisAccount;
+use alloy_primitives::{Address, B256, U256};
+use reth_db_api::{
+    cursor::{DbCursorRO as _, DbDupCursorRO as _},
+    models::{AccountBeforeTx, BlockNumberAddress},
+    tables,
+    transaction::DbTx as _,
+};
+use reth_primitives_traits::{Account, StorageEntry};
+use reth_storage_api::{BlockNumReader, DBProvider, StateProvider, errors::ProviderResult};
+use serde::Serialize;
+
+use crate::SnapshotError;
+
+/// An account line of the state dump, in the format read by reth's `init-state`.
+#[derive(Serialize)]
+struct AccountLine {
+    address: Address,
+    #[serde(flatten)]
+    account: GenesisAccount,
+}
+
+/// Writes every account alive after `block` to `out`, one JSON line per account in address
+/// order.
+///
+/// `history` must be the state provider at `block`. Precompiles are written like any other
+/// account, including their storage. Returns the number of accounts written.
+pub fn write_state<P>(
+    provider: &P,
+    history: &dyn StateProvider,
+    block: u64,
+    out: &mut impl Write,
+) -> Result<usize, SnapshotError>
+where
+    P: DBProvider + BlockNumReader,
+{
+    let persisted = provider.last_block_number()?;
+    if persisted < block {
+        return Err(SnapshotError::BlockNotPersisted { block, persisted });
+    }
+
+    let (changed_accounts, mut changed_slots) = changed_after(provider, block)?;
+    let tx = provider.tx_ref();
+    let mut plain_accounts = tx.cursor_read::<tables::PlainAccountState>()?;
+    let mut plain_storage = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+
+    let mut write = |address: Address, account: Option<Account>| -> Result<usize, SnapshotError> {
+        // Accounts created after `block` did not exist at `block`.
+        let Some(account) = account else {
+            return Ok(0);
+        };
+
+        let mut storage = BTreeMap::new();
+        for entry in plain_storage.walk_dup(Some(address), None)? {
+            let (_, StorageEntry { key, value }) = entry?;
+            storage.insert(key, value);
+        }
+        for key in changed_slots.remove(&address).unwrap_or_default() {
+            storage.insert(key, history.storage(address, key)?.unwrap_or_default());
+        }
+        storage.retain(|_, value: &mut U256| !value.is_zero());
+
+        let code = match account.bytecode_hash {
+            Some(hash) => history
+                .bytecode_by_hash(&hash)?
+                .map(|code| code.original_bytes()),
+            None => None,
+        };
+
+        let line = AccountLine {
+            address,
+            account: GenesisAccount {
+                nonce: Some(account.nonce),
+                balance: account.balance,
+                code,
+                storage: (!storage.is_empty()).then(|| {
+                    storage
+                        .into_iter()
+                        .map(|(key, value)| (key, B256::from(value)))
+                        .collect()
+                }),
+                private_key: None,
+            },
+        };
+        serde_json::to_writer(&mut *out, &line)?;
+        out.write_all(b"\n")?;
+        Ok(1)
+    };
+
+    // Merge the latest accounts with those changed after `block`, in address order. Accounts
+    // destroyed after `block` are only found among the changed accounts.
+    let mut written = 0;
+    let mut changed = changed_accounts.into_iter().peekable();
+    for entry in plain_accounts.walk(None)? {
+        let (address, account) = entry?;
+        while let Some(destroyed) = changed.next_if(|changed| *changed < address) {
+            written += write(destroyed, history.basic_account(&destroyed)?)?;
+        }
+        let account = if changed.next_if_eq(&address).is_some() {
+            history.basic_account(&address)?
+        } else {
+            Some(account)
+        };
+        written += write(address, account)?;
+    }
+    for destroyed in changed {
+        written += write(destroyed, history.basic_account(&destroyed)?)?;
+    }
+
+    out.flush()?;
+    Ok(written)
+}
+
+/// Returns the accounts and storage slots changed by the blocks after `block`.
+fn changed_after<P: DBProvider>(
+    provider: &P,
+    block: u64,
+) -> ProviderResult<(BTreeSet<Address>, BTreeMap<Address, BTreeSet<B256>>)> {
+    let tx = provider.tx_ref();
+
+    let mut accounts = BTreeSet::new();
+    for entry in tx
+        .cursor_read::<tables::AccountChangeSets>()?
+        .walk_range(block + 1..)?
+    {
+        let (_, AccountBeforeTx { address, .. }) = entry?;
+        accounts.insert(address);
+    }
+
+    let mut slots = BTreeMap::<_, BTreeSet<_>>::new();
+    for entry in tx
+        .cursor_dup_read::<tables::StorageChangeSets>()?
+        .walk_range(BlockNumberAddress((block + 1, Address::ZERO))..)?
+    {
+        let (BlockNumberAddress((_, address)), StorageEntry { key, .. }) = entry?;
+        slots.entry(address).or_default().insert(key);
+    }
+
+    Ok((accounts, slots))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_genesis::GenesisAccount;
+    use alloy_primitives::{Address, B256, U256};
+    use reth_db_api::{
+        models::{AccountBeforeTx, BlockNumberAddress},
+        tables,
+        transaction::DbTxMut as _,
+    };
+    use reth_primitives_traits::{Account, StorageEntry};
+    use reth_provider::test_utils::{
+        ExtendedAccount, MockEthProvider, create_test_provider_factory,
+    };
+    use reth_storage_api::DBProvider as _;
+
+    use super::AccountLine;
+    use crate::SnapshotError;
+
+    fn account(nonce: u64, balance: u64) -> Account {
+        Account {
+            nonce,
+            balance: U256::from(balance),
+            bytecode_hash: None,
+        }
+    }
+
+    fn slot(value: u8) -> B256 {
+        B256::with_last_byte(value)
+    }
+
+    fn line(address: Address, nonce: u64, balance: u64, storage: &[(u8, u8)]) -> serde_json::Value {
+        serde_json::to_value(AccountLine {
+            address,
+            account: GenesisAccount {
+                nonce: Some(nonce),
+                balance: U256::from(balance),
+                code: None,
+                storage: (!storage.is_empty()).then(|| {
+                    storage
+                        .iter()
+                        .map(|(key, value)| (slot(*key), slot(*value)))
+                        .collect()
+                }),
+                private_key: None,
+            },
+        })
+        .unwrap()
+    }
+
+    /// Writes the latest state and the changes of blocks 1 and 2, and exports the state after
+    /// block 1, whose values of the accounts changed by block 2 are served by `history`.
+    #[test]
+    fn state_at_past_block_merges_latest_state_with_history() {
+        let unchanged = Address::with_last_byte(1);
+        let updated = Address::with_last_byte(2);
+        let created = Address::with_last_byte(3);
+        let destroyed = Address::with_last_byte(4);
+        let changed_before = Address::with_last_byte(5);
+
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        for number in 0..=2 {
+            tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8))
+                .unwrap();
+        }
+        for (address, account) in [
+            (unchanged, account(1, 10)),
+            (updated, account(2, 20)),
+            (created, account(1, 30)),
+            (changed_before, account(1, 50)),
+        ] {
+            tx.put::<tables::PlainAccountState>(address, account)
+                .unwrap();
+        }
+        for (key, value) in [(1, 5), (2, 7)] {
+            tx.put::<tables::PlainStorageState>(
+                unchanged,
+                StorageEntry {
+                    key: slot(key),
+                    value: U256::from(value),
+                },
+            )
+            .unwrap();
+        }
+        for (number, address) in [
+            (1, changed_before),
+            (2, updated),
+            (2, created),
+            (2, destroyed),
+        ] {
+            tx.put::<tables::AccountChangeSets>(
+                number,
+                AccountBeforeTx {
+                    address,
+                    info: None,
+                },
+            )
+            .unwrap();
+        }
+        // Block 2 set slot 2 of `unchanged` and cleared its slot 3.
+        for key in [2, 3] {
+            tx.put::<tables::StorageChangeSets>(
+                BlockNumberAddress((2, unchanged)),
+                StorageEntry {
+                    key: slot(key),
+                    value: U256::ZERO,
+                },
+            )
+            .unwrap();
+        }
+        provider.commit().unwrap();
+
+        let history: MockEthProvider = MockEthProvider::default();
+        history.add_account(
+            unchanged,
+            ExtendedAccount::new(1, U256::from(10)).extend_storage([(slot(3), U256::from(9))]),
+        );
+        history.add_account(updated, ExtendedAccount::new(1, U256::from(15)));
+        history.add_account(destroyed, ExtendedAccount::new(4, U256::from(40)));
+
+        let provider = factory.provider().unwrap();
+        let mut out = Vec::new();
+        let written = super::write_state(&provider, &history, 1, &mut out).unwrap();
+
+        let lines = std::str::from_utf8(&out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(written, lines.len());
+        assert_eq!(
+            lines,
+            vec![
+                line(unchanged, 1, 10, &[(1, 5), (3, 9)]),
+                line(updated, 1, 15, &[]),
+                line(destroyed, 4, 40, &[]),
+                line(changed_before, 1, 50, &[]),
+            ],
+        );
+    }
+
+    #[test]
+    fn state_past_persisted_block_is_rejected() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        provider
+            .tx_ref()
+            .put::<tables::CanonicalHeaders>(0, B256::ZERO)
+            .unwrap();
+        provider.commit().unwrap();
+
+        let history: MockEthProvider = MockEthProvider::default();
+        assert!(matches!(
+            super::write_state(&factory.provider().unwrap(), &history, 1, &mut Vec::new()),
+            Err(SnapshotError::BlockNotPersisted {
+                block: 1,
+                persisted: 0,
+            }),
+        ));
+    }
+}
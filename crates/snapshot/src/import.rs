@@ -0,0 +1,108 @@
+//! Imports the state of a verified snapshot into the database of a new node.
+//!
+//! The state is written with reth's `init-state` tooling, which recomputes the state root and
+//! rejects the snapshot if it does not match the checkpoint header. The blocks before the
+//! checkpoint are never executed, so the database holds placeholder headers for them.
+
+use std::{io::BufRead, sync::Arc};
+
+use eyre::WrapErr as _;
+use reth_cli_commands::init_state::without_evm::setup_without_evm;
+use reth_config::config::EtlConfig;
+use reth_db::DatabaseEnv;
+use reth_db_common::init::{init_from_state_dump, init_genesis};
+use reth_node_builder::NodeTypesWithDBAdapter;
+use reth_primitives_traits::SealedHeader;
+use reth_provider::{DatabaseProviderFactory as _, ProviderFactory};
+use reth_storage_api::BlockNumReader as _;
+use tempo_node::node::TempoNode;
+use tempo_primitives::{Header, TempoHeader};
+use tracing::{info, warn};
+
+use crate::Checkpoint;
+
+/// Provider factory of the database a snapshot is imported into.
+pub type TempoProviderFactory =
+    ProviderFactory<NodeTypesWithDBAdapter<TempoNode, Arc<DatabaseEnv>>>;
+
+/// What [`import_state`] did with a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Imported {
+    /// The state of the snapshot was written to the database.
+    Imported,
+    /// The database already holds the checkpoint as its head, e.g. because the node was
+    /// restarted after importing the snapshot.
+    AlreadyImported,
+    /// The database already holds other blocks, so the snapshot was ignored. Snapshots are only
+    /// imported into new nodes.
+    DatabaseNotEmpty { best: u64 },
+}
+
+/// Writes the state read from `reader` into the database of `factory`, with the block of
+/// `checkpoint` as its head.
+///
+/// `reader` must be positioned at the state root line, as left by [`crate::read_header`].
+/// Fails without committing the state if it does not hash to the state root of the
+/// checkpoint.
+pub fn import_state(
+    factory: &TempoProviderFactory,
+    checkpoint: &Checkpoint,
+    reader: impl BufRead,
+    etl_config: EtlConfig,
+) -> eyre::Result<Imported> {
+    // Like reth's `init-state`, write the genesis first so that the node recognizes its chain on
+    // startup. The genesis of a database the snapshot was imported into before is left as is.
+    init_genesis(factory).wrap_err("failed initializing genesis")?;
+
+    let number = checkpoint.height();
+    let best = factory
+        .best_block_number()
+        .wrap_err("failed reading local head")?;
+    if best == number {
+        info!(
+            number,
+            "state of snapshot was imported before; not importing it again"
+        );
+        return Ok(Imported::AlreadyImported);
+    }
+    if best != 0 {
+        warn!(
+            best,
+            "database already holds blocks; ignoring snapshot, which is only imported into new nodes"
+        );
+        return Ok(Imported::DatabaseNotEmpty { best });
+    }
+
+    info!(number, "importing snapshot state; this may take a while");
+    let provider_rw = factory
+        .database_provider_rw()
+        .wrap_err("failed opening database for writing")?;
+
+    setup_without_evm(
+        &provider_rw,
+        SealedHeader::new(checkpoint.finalized.header.clone(), checkpoint.hash()),
+        |number| TempoHeader {
+            inner: Header {
+                number,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .wrap_err("failed writing checkpoint header")?;
+    // Static files written past the database checkpoints are unwound on startup, so committing
+    // them first is safe even if the import below fails.
+    factory
+        .static_file_provider()
+        .commit()
+        .wrap_err("failed committing static files")?;
+
+    let state_root = init_from_state_dump(reader, &provider_rw, etl_config)
+        .wrap_err("failed importing snapshot state")?;
+    provider_rw
+        .commit()
+        .wrap_err("failed committing snapshot state")?;
+
+    info!(number, %state_root, "imported snapshot state");
+    Ok(Imported::Imported)
+}
@@ -0,0 +1,395 @@
+//! State snapshots of Tempo taken at finalized epoch boundaries.
+//!
+//! A snapshot lets a new node start from the state at the last block of an epoch instead of
+//! executing the whole chain. It is a file of JSON lines:
+//!
+//! 1. a [`SnapshotHeader`] carrying, for every epoch up to and including the snapshot's epoch,
+//!    the RLP-encoded last block header of the epoch and its finalization certificate;
+//! 2. the state root of the last of these headers, as `{"root": "0x.."}`;
+//! 3. one line per account holding its balance, nonce, code, and storage, as produced by
+//!    [`export::write_state`].
+//!
+//! Lines 2 and 3 follow the state dump format read by reth's `init-state` command, so the state
+//! is imported with reth's tooling by [`import::import_state`], which recomputes the state root and rejects the dump if it
+//! does not match the header. Precompiles keep their state in the storage of their account, so
+//! it is part of the dump like that of any other account.
+//!
+//! Snapshots are not trusted. [`SnapshotHeader::verify`] walks the epoch boundaries starting
+//! from the validator group in the genesis header: each boundary header must be finalized by the
+//! group of its epoch, and carries the DKG outcome determining the group of the next epoch. This
+//! proves the last header final without trusting whoever served the snapshot.
+
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+mod error;
+pub mod export;
+pub mod import;
+
+pub use error::SnapshotError;
+
+use std::io::{BufRead, Write};
+
+use alloy_consensus::BlockHeader as _;
+use alloy_primitives::{B256, Bytes};
+use alloy_rlp::{Decodable as _, Encodable as _};
+use commonware_codec::DecodeExt as _;
+use serde::{Deserialize, Serialize};
+use tempo_dkg_onchain_artifacts::PublicOutcome;
+use tempo_light_client::{FinalizedHeader, LightClient};
+use tempo_primitives::TempoHeader;
+
+/// Version of the snapshot format written by this crate.
+pub const SNAPSHOT_VERSION: u64 = 1;
+
+/// The first line of a snapshot, proving its state root final.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotHeader {
+    /// Version of the snapshot format.
+    pub version: u64,
+    /// The last block of every epoch up to and including the snapshot's epoch, oldest first.
+    ///
+    /// The state in the snapshot is the state after the last of these blocks.
+    pub boundaries: Vec<Boundary>,
+}
+
+/// The last block of an epoch together with its finalization certificate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Boundary {
+    /// The RLP-encoded block header.
+    pub header: Bytes,
+    /// The encoded threshold-simplex finalization certificate of the block.
+    pub certificate: Bytes,
+}
+
+impl Boundary {
+    /// Creates a boundary from a header and its encoded finalization certificate.
+    pub fn new(header: &TempoHeader, certificate: Bytes) -> Self {
+        let mut encoded = Vec::with_capacity(header.length());
+        header.encode(&mut encoded);
+        Self {
+            header: encoded.into(),
+            certificate,
+        }
+    }
+
+    /// Decodes the block header.
+    pub fn decode_header(&self) -> Result<TempoHeader, SnapshotError> {
+        TempoHeader::decode(&mut self.header.as_ref()).map_err(SnapshotError::HeaderDecoding)
+    }
+}
+
+/// The finalized block a snapshot was taken at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// The last block of the snapshot's epoch, proven final.
+    pub finalized: FinalizedHeader,
+    /// The encoded finalization certificate of the block.
+    pub certificate: Bytes,
+    /// The DKG outcome in the block's header, determining the validators of the next epoch.
+    pub outcome: PublicOutcome,
+    /// Timestamp of the last block of the previous epoch, or `None` if the checkpoint is in the
+    /// first epoch.
+    pub previous_boundary_timestamp: Option<u64>,
+}
+
+impl Checkpoint {
+    /// Returns the height of the checkpoint block.
+    pub fn height(&self) -> u64 {
+        self.finalized.header.number()
+    }
+
+    /// Returns the hash of the checkpoint block.
+    pub fn hash(&self) -> B256 {
+        self.finalized.hash
+    }
+
+    /// Returns the timestamp of the block after which the DKG ceremony concluded by the
+    /// checkpoint started: the last block of the previous epoch, or genesis in the first epoch.
+    ///
+    /// A node started from the checkpoint runs the post-allegretto DKG from the next epoch on,
+    /// which only matches the other validators if allegretto was active at this timestamp.
+    pub fn ceremony_start_timestamp(&self, genesis_timestamp: u64) -> u64 {
+        self.previous_boundary_timestamp
+            .unwrap_or(genesis_timestamp)
+    }
+}
+
+impl SnapshotHeader {
+    /// Verifies that the boundaries are finalized, starting from the validator group trusted by
+    /// `light_client`.
+    ///
+    /// `light_client` must be at epoch 0, i.e. created from the genesis of the chain. On
+    /// success, it has transitioned to the epoch following the snapshot.
+    pub fn verify(&self, light_client: &mut LightClient) -> Result<Checkpoint, SnapshotError> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(self.version));
+        }
+
+        let mut checkpoint: Option<Checkpoint> = None;
+        for boundary in &self.boundaries {
+            let header = boundary.decode_header()?;
+            let expected = light_client.epoch_boundary();
+            if header.number() != expected {
+                return Err(SnapshotError::NotABoundary {
+                    number: header.number(),
+                    expected,
+                });
+            }
+
+            let outcome = PublicOutcome::decode(header.extra_data().as_ref())
+                .map_err(SnapshotError::OutcomeDecoding)?;
+            let finalized = light_client.verify_header(header, &boundary.certificate)?;
+            checkpoint = Some(Checkpoint {
+                finalized,
+                certificate: boundary.certificate.clone(),
+                outcome,
+                previous_boundary_timestamp: checkpoint
+                    .map(|previous| previous.finalized.header.timestamp()),
+            });
+        }
+        checkpoint.ok_or(SnapshotError::NoBoundaries)
+    }
+}
+
+/// The state root line following the [`SnapshotHeader`], as read by reth's `init-state`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct StateRoot {
+    root: B256,
+}
+
+/// Writes the header and the state root lines of a snapshot to `out`.
+///
+/// The accounts are written afterwards using [`export::write_state`].
+pub fn write_header(
+    out: &mut impl Write,
+    header: &SnapshotHeader,
+    state_root: B256,
+) -> Result<(), SnapshotError> {
+    serde_json::to_writer(&mut *out, header)?;
+    out.write_all(b"\n")?;
+    serde_json::to_writer(&mut *out, &StateRoot { root: state_root })?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Reads the [`SnapshotHeader`] from the first line of a snapshot.
+///
+/// Afterwards, `reader` is positioned at the state root line, which is where reth's state dump
+/// reader expects to start.
+pub fn read_header(reader: &mut impl BufRead) -> Result<SnapshotHeader, SnapshotError> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(SnapshotError::Empty);
+    }
+    Ok(serde_json::from_str(&line)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead as _, BufReader};
+
+    use alloy_consensus::{BlockHeader as _, Header};
+    use alloy_primitives::{B256, Bytes};
+    use commonware_codec::Encode as _;
+    use commonware_cryptography::{
+        PrivateKeyExt as _, Signer as _,
+        bls12381::{dkg, primitives::variant::MinSig},
+        ed25519::PrivateKey,
+    };
+    use commonware_utils::set::Ordered;
+    use rand::{SeedableRng as _, rngs::StdRng};
+    use tempo_dkg_onchain_artifacts::PublicOutcome;
+    use tempo_light_client::{LightClient, test_utils::TestGroup};
+    use tempo_primitives::TempoHeader;
+
+    use super::{Boundary, SNAPSHOT_VERSION, SnapshotError, SnapshotHeader};
+
+    const EPOCH_LENGTH: u64 = 10;
+
+    fn outcome(epoch: u64) -> PublicOutcome {
+        let participants = (0..4)
+            .map(|seed| PrivateKey::from_seed(seed).public_key())
+            .collect::<Ordered<_>>();
+        let (_, public, _) = dkg::Dealer::<_, MinSig>::new(
+            &mut StdRng::from_seed([epoch as u8; 32]),
+            None,
+            participants.clone(),
+        );
+        PublicOutcome {
+            epoch,
+            participants,
+            public,
+        }
+    }
+
+    fn header(number: u64) -> TempoHeader {
+        TempoHeader {
+            inner: Header {
+                number,
+                extra_data: outcome(1).encode().freeze().into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn header_and_state_root_roundtrip() {
+        let snapshot = SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            boundaries: vec![Boundary::new(
+                &header(EPOCH_LENGTH - 1),
+                Bytes::from_static(&[1, 2, 3]),
+            )],
+        };
+        let root = B256::repeat_byte(0x42);
+
+        let mut out = Vec::new();
+        super::write_header(&mut out, &snapshot, root).unwrap();
+
+        let mut reader = BufReader::new(out.as_slice());
+        assert_eq!(super::read_header(&mut reader).unwrap(), snapshot);
+        assert_eq!(
+            snapshot.boundaries[0].decode_header().unwrap(),
+            header(EPOCH_LENGTH - 1)
+        );
+
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&line).unwrap(),
+            serde_json::json!({ "root": root }),
+        );
+    }
+
+    /// Returns the boundary of the epoch before `next`'s, installing `next` and finalized by
+    /// `signer`.
+    fn boundary(next: &TestGroup, signer: &TestGroup) -> (TempoHeader, Boundary) {
+        let epoch = next.outcome().epoch - 1;
+        let header = TempoHeader {
+            inner: Header {
+                number: (epoch + 1) * EPOCH_LENGTH - 1,
+                timestamp: 1000 + epoch,
+                extra_data: next.outcome().encode().freeze().into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let certificate = signer.finalize(header.number(), header.hash_slow());
+        let boundary = Boundary::new(&header, certificate.into());
+        (header, boundary)
+    }
+
+    #[test]
+    fn chain_of_boundaries_is_verified_from_genesis_group() {
+        let groups = (0..3)
+            .map(|epoch| TestGroup::new(epoch, epoch))
+            .collect::<Vec<_>>();
+        let (first, first_boundary) = boundary(&groups[1], &groups[0]);
+        let (last, last_boundary) = boundary(&groups[2], &groups[1]);
+        let snapshot = SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            boundaries: vec![first_boundary, last_boundary],
+        };
+
+        let mut light_client = groups[0].light_client(EPOCH_LENGTH);
+        let checkpoint = snapshot.verify(&mut light_client).unwrap();
+
+        assert_eq!(checkpoint.height(), 2 * EPOCH_LENGTH - 1);
+        assert_eq!(checkpoint.hash(), last.hash_slow());
+        assert_eq!(checkpoint.finalized.epoch, 1);
+        assert_eq!(checkpoint.outcome, groups[2].outcome());
+        assert_eq!(
+            checkpoint.previous_boundary_timestamp,
+            Some(first.timestamp())
+        );
+        assert_eq!(checkpoint.ceremony_start_timestamp(0), first.timestamp());
+        assert_eq!(light_client.epoch(), 2);
+    }
+
+    #[test]
+    fn boundary_finalized_by_wrong_group_is_rejected() {
+        let groups = (0..3)
+            .map(|epoch| TestGroup::new(epoch, epoch))
+            .collect::<Vec<_>>();
+        let (_, first_boundary) = boundary(&groups[1], &groups[0]);
+        // The second boundary is finalized by the group of the first epoch, which no longer
+        // has authority over it.
+        let (_, last_boundary) = boundary(&groups[2], &TestGroup::new(0, 1));
+        let snapshot = SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            boundaries: vec![first_boundary, last_boundary],
+        };
+
+        assert!(matches!(
+            snapshot.verify(&mut groups[0].light_client(EPOCH_LENGTH)),
+            Err(SnapshotError::Verification(_)),
+        ));
+    }
+
+    #[test]
+    fn checkpoint_in_first_epoch_starts_ceremony_at_genesis() {
+        let groups = (0..2)
+            .map(|epoch| TestGroup::new(epoch, epoch))
+            .collect::<Vec<_>>();
+        let (_, boundary) = boundary(&groups[1], &groups[0]);
+        let snapshot = SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            boundaries: vec![boundary],
+        };
+
+        let checkpoint = snapshot
+            .verify(&mut groups[0].light_client(EPOCH_LENGTH))
+            .unwrap();
+        assert_eq!(checkpoint.previous_boundary_timestamp, None);
+        assert_eq!(checkpoint.ceremony_start_timestamp(42), 42);
+    }
+
+    #[test]
+    fn boundary_outside_epoch_end_is_rejected() {
+        let mut light_client = LightClient::new(EPOCH_LENGTH, &outcome(0));
+        let snapshot = SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            boundaries: vec![Boundary::new(
+                &header(EPOCH_LENGTH),
+                Bytes::from_static(&[1, 2, 3]),
+            )],
+        };
+        assert!(matches!(
+            snapshot.verify(&mut light_client),
+            Err(SnapshotError::NotABoundary {
+                number: EPOCH_LENGTH,
+                expected,
+            }) if expected == EPOCH_LENGTH - 1,
+        ));
+    }
+
+    #[test]
+    fn snapshot_without_boundaries_is_rejected() {
+        let mut light_client = LightClient::new(EPOCH_LENGTH, &outcome(0));
+        let snapshot = SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            boundaries: vec![],
+        };
+        assert!(matches!(
+            snapshot.verify(&mut light_client),
+            Err(SnapshotError::NoBoundaries),
+        ));
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let mut light_client = LightClient::new(EPOCH_LENGTH, &outcome(0));
+        let snapshot = SnapshotHeader {
+            version: SNAPSHOT_VERSION + 1,
+            boundaries: vec![],
+        };
+        assert!(matches!(
+            snapshot.verify(&mut light_client),
+            Err(SnapshotError::UnsupportedVersion(version)) if version == SNAPSHOT_VERSION + 1,
+        ));
+    }
+}
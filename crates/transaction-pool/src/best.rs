@@ -1,10 +1,19 @@
 //! An iterator over the best transactions in the tempo pool.
 
 use crate::transaction::TempoPooledTransaction;
+use alloy_primitives::{Address, U256, map::HashMap};
 use reth_transaction_pool::{
-    BestTransactions, CoinbaseTipOrdering, Priority, TransactionOrdering,
+    BestTransactions, CoinbaseTipOrdering, Priority, TransactionOrdering, ValidPoolTransaction,
     error::InvalidPoolTransactionError,
 };
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet, VecDeque},
+    fmt,
+    str::FromStr,
+    sync::Arc,
+    time::Instant,
+};
 
 /// An extension trait for [`BestTransactions`] that in addition to the transaction also yields the priority value.
 pub trait BestPriorityTransactions<T: TransactionOrdering>: BestTransactions {
@@ -137,9 +146,293 @@ where
     }
 }
 
+/// How payment transactions are ordered when building a payload.
+///
+/// This is a local block building policy and not validated by other nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PaymentOrdering {
+    /// Payments compete with all other transactions by coinbase tip.
+    #[default]
+    Tip,
+    /// Payments are included in the order they arrived in the pool, after all non-payment
+    /// transactions. See [`FifoPaymentTransactions`].
+    Fifo,
+}
+
+impl PaymentOrdering {
+    /// Returns the name of the ordering.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tip => "tip",
+            Self::Fifo => "fifo",
+        }
+    }
+}
+
+impl fmt::Display for PaymentOrdering {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PaymentOrdering {
+    type Err = UnknownPaymentOrdering;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tip" => Ok(Self::Tip),
+            "fifo" => Ok(Self::Fifo),
+            _ => Err(UnknownPaymentOrdering(s.to_string())),
+        }
+    }
+}
+
+/// Error returned when parsing an unknown [`PaymentOrdering`].
+#[derive(Debug, thiserror::Error)]
+#[error("unknown payment ordering `{0}`, expected `tip` or `fifo`")]
+pub struct UnknownPaymentOrdering(String);
+
+/// Configures the ordering of the payment lane in built payloads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PaymentLaneConfig {
+    /// How payment transactions are ordered.
+    pub ordering: PaymentOrdering,
+    /// Maximum number of payment transactions yielded per sender and payload.
+    ///
+    /// Only applies to [`PaymentOrdering::Fifo`].
+    pub max_payments_per_sender: Option<usize>,
+}
+
+impl PaymentLaneConfig {
+    /// Applies the configured ordering to the given best transactions iterator.
+    pub fn apply(
+        &self,
+        best: Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>>>,
+    ) -> Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>>> {
+        match self.ordering {
+            PaymentOrdering::Tip => best,
+            PaymentOrdering::Fifo => Box::new(FifoPaymentTransactions::new(
+                best,
+                self.max_payments_per_sender,
+            )),
+        }
+    }
+}
+
+/// Identifies a sequence of transactions that must be executed in nonce order: the sender and
+/// the nonce key of the transactions.
+type SequenceKey = (Address, U256);
+
+/// Default number of transactions [`FifoPaymentTransactions`] looks ahead in the wrapped
+/// iterator.
+pub const DEFAULT_FIFO_WINDOW: usize = 4096;
+
+/// Ordering key of the next transaction of a sequence.
+///
+/// Non-payment transactions are ranked above all payments and keep the order of the wrapped
+/// iterator. Payments are ranked by pool arrival time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rank {
+    Payment(Reverse<(Instant, usize)>),
+    NonPayment(Reverse<usize>),
+}
+
+/// A [`BestTransactions`] iterator that yields payment transactions first-come-first-served.
+///
+/// Transactions are lazily pulled from the wrapped iterator into a window of at most `window`
+/// transactions, grouped into their nonce sequences. Within the window, non-payment transactions
+/// are yielded first, in the order of the wrapped iterator, followed by payments in the order they
+/// arrived in the pool. Transactions of the same sequence are always yielded in nonce order.
+///
+/// Building a payload only pulls as many transactions as fit in the block plus the window, instead
+/// of the whole pool.
+///
+/// If `max_payments_per_sender` is set, all further transactions of a sender are skipped
+/// once it had that many payments yielded, so a single sender can't fill the payment lane.
+pub struct FifoPaymentTransactions<
+    I = Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>>>,
+> {
+    /// The wrapped iterator.
+    best: I,
+    /// Whether the wrapped iterator is exhausted.
+    exhausted: bool,
+    /// Maximum number of transactions pulled from the wrapped iterator but not yielded yet.
+    window: usize,
+    /// Number of transactions pulled from the wrapped iterator so far.
+    pulled: usize,
+    /// Number of transactions in `sequences`.
+    buffered: usize,
+    /// Remaining transactions of each sequence with their position in the wrapped iterator.
+    sequences:
+        HashMap<SequenceKey, VecDeque<(usize, Arc<ValidPoolTransaction<TempoPooledTransaction>>)>>,
+    /// Next transaction of each sequence.
+    heads: BinaryHeap<(Rank, SequenceKey)>,
+    /// Sequences whose remaining transactions are skipped.
+    skipped: HashSet<SequenceKey>,
+    /// Number of payments yielded per sender.
+    payments_per_sender: HashMap<Address, usize>,
+    max_payments_per_sender: Option<usize>,
+}
+
+impl<I> FifoPaymentTransactions<I>
+where
+    I: Iterator<Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+{
+    /// Creates a new iterator over the transactions of `best`.
+    pub fn new(best: I, max_payments_per_sender: Option<usize>) -> Self {
+        Self {
+            best,
+            exhausted: false,
+            window: DEFAULT_FIFO_WINDOW,
+            pulled: 0,
+            buffered: 0,
+            sequences: HashMap::default(),
+            heads: BinaryHeap::new(),
+            skipped: HashSet::new(),
+            payments_per_sender: HashMap::default(),
+            max_payments_per_sender,
+        }
+    }
+
+    /// Sets the number of transactions to look ahead in the wrapped iterator.
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Pulls transactions from the wrapped iterator until the window is full.
+    fn fill_window(&mut self) {
+        while !self.exhausted && self.buffered < self.window {
+            let Some(tx) = self.best.next() else {
+                self.exhausted = true;
+                break;
+            };
+            let position = self.pulled;
+            self.pulled += 1;
+
+            let key = Self::sequence_key(&tx);
+            if self.skipped.contains(&key) {
+                continue;
+            }
+
+            let sequence = self.sequences.entry(key).or_default();
+            if sequence.is_empty() {
+                self.heads.push((Self::rank(position, &tx), key));
+            }
+            sequence.push_back((position, tx));
+            self.buffered += 1;
+        }
+    }
+
+    /// Skips the remaining transactions of the sequence, including those not pulled yet.
+    fn skip_sequence(&mut self, key: SequenceKey) {
+        if let Some(sequence) = self.sequences.remove(&key) {
+            self.buffered -= sequence.len();
+        }
+        self.skipped.insert(key);
+    }
+}
+
+impl<I> FifoPaymentTransactions<I> {
+    fn sequence_key(tx: &ValidPoolTransaction<TempoPooledTransaction>) -> SequenceKey {
+        (tx.sender(), tx.transaction.nonce_key().unwrap_or_default())
+    }
+
+    fn rank(position: usize, tx: &ValidPoolTransaction<TempoPooledTransaction>) -> Rank {
+        if tx.transaction.is_payment() {
+            Rank::Payment(Reverse((tx.timestamp, position)))
+        } else {
+            Rank::NonPayment(Reverse(position))
+        }
+    }
+}
+
+impl<I> fmt::Debug for FifoPaymentTransactions<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FifoPaymentTransactions")
+            .field("window", &self.window)
+            .field("pulled", &self.pulled)
+            .field("buffered", &self.buffered)
+            .field("max_payments_per_sender", &self.max_payments_per_sender)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I> Iterator for FifoPaymentTransactions<I>
+where
+    I: Iterator<Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+{
+    type Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.fill_window();
+
+            let (_, key) = self.heads.pop()?;
+            // The sequence might have been skipped.
+            let Some(sequence) = self.sequences.get_mut(&key) else {
+                continue;
+            };
+            let Some((_, tx)) = sequence.pop_front() else {
+                self.sequences.remove(&key);
+                continue;
+            };
+            self.buffered -= 1;
+
+            let is_payment = tx.transaction.is_payment();
+            let is_capped = self.max_payments_per_sender.is_some_and(|max| {
+                self.payments_per_sender
+                    .get(&key.0)
+                    .is_some_and(|count| *count >= max)
+            });
+            if is_payment && is_capped {
+                // The remaining transactions of the sequence can't be executed without this one.
+                self.skip_sequence(key);
+                continue;
+            }
+
+            if let Some((position, next)) = sequence.front() {
+                self.heads.push((Self::rank(*position, next), key));
+            } else {
+                self.sequences.remove(&key);
+            }
+            if is_payment {
+                *self.payments_per_sender.entry(key.0).or_default() += 1;
+            }
+
+            return Some(tx);
+        }
+    }
+}
+
+impl<I> BestTransactions for FifoPaymentTransactions<I>
+where
+    I: BestTransactions<Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+{
+    fn mark_invalid(&mut self, transaction: &Self::Item, kind: &InvalidPoolTransactionError) {
+        self.best.mark_invalid(transaction, kind);
+        self.skip_sequence(Self::sequence_key(transaction));
+    }
+
+    fn no_updates(&mut self) {
+        self.best.no_updates();
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.best.set_skip_blobs(skip_blobs);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_consensus::{Signed, TxLegacy};
+    use alloy_primitives::{Signature, TxKind};
+    use reth_primitives_traits::Recovered;
+    use reth_transaction_pool::{TransactionOrigin, identifier::TransactionId};
+    use std::time::Duration;
+    use tempo_precompiles::DEFAULT_FEE_TOKEN_PRE_ALLEGRETTO;
+    use tempo_primitives::TempoTxEnvelope;
 
     /// A simple mock iterator for testing that yields items with priorities
     struct MockBestTransactions<T> {
@@ -271,4 +564,205 @@ mod tests {
         assert_eq!(merged.next(), Some("tx_d"));
         assert_eq!(merged.next(), None);
     }
+
+    /// Creates a pooled legacy transaction that arrived in the pool `arrival` after `start`.
+    fn pooled_tx(
+        sender: Address,
+        nonce: u64,
+        payment: bool,
+        start: Instant,
+        arrival: u64,
+    ) -> Arc<ValidPoolTransaction<TempoPooledTransaction>> {
+        let to = if payment {
+            DEFAULT_FEE_TOKEN_PRE_ALLEGRETTO
+        } else {
+            Address::repeat_byte(0x42)
+        };
+        let tx = TxLegacy {
+            nonce,
+            gas_price: 1_000_000_000,
+            gas_limit: 100_000,
+            to: TxKind::Call(to),
+            ..Default::default()
+        };
+        let envelope =
+            TempoTxEnvelope::Legacy(Signed::new_unhashed(tx, Signature::test_signature()));
+
+        Arc::new(ValidPoolTransaction {
+            transaction: TempoPooledTransaction::new(Recovered::new_unchecked(envelope, sender)),
+            transaction_id: TransactionId::new(0u64.into(), nonce),
+            propagate: true,
+            timestamp: start + Duration::from_millis(arrival),
+            origin: TransactionOrigin::External,
+            authority_ids: None,
+        })
+    }
+
+    fn yielded(
+        best: impl Iterator<Item = Arc<ValidPoolTransaction<TempoPooledTransaction>>>,
+    ) -> Vec<(Address, u64)> {
+        best.map(|tx| (tx.sender(), tx.nonce())).collect()
+    }
+
+    #[test]
+    fn test_fifo_orders_payments_by_arrival() {
+        let start = Instant::now();
+        let (alice, bob, carol) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+
+        // Tip ordering of the wrapped iterator: bob pays the highest tip but arrived last.
+        let best = vec![
+            pooled_tx(bob, 0, true, start, 30),
+            pooled_tx(carol, 0, false, start, 40),
+            pooled_tx(alice, 0, true, start, 10),
+            pooled_tx(carol, 1, true, start, 20),
+        ];
+
+        // Non-payments come first, carol's payment has to wait for its predecessor.
+        assert_eq!(
+            yielded(FifoPaymentTransactions::new(best.into_iter(), None)),
+            vec![(carol, 0), (alice, 0), (carol, 1), (bob, 0)]
+        );
+    }
+
+    #[test]
+    fn test_fifo_keeps_nonce_order() {
+        let start = Instant::now();
+        let alice = Address::repeat_byte(1);
+        let bob = Address::repeat_byte(2);
+
+        // Alice's second payment arrived before bob's, but after her first one.
+        let best = vec![
+            pooled_tx(alice, 0, true, start, 10),
+            pooled_tx(bob, 0, true, start, 5),
+            pooled_tx(alice, 1, true, start, 1),
+        ];
+
+        assert_eq!(
+            yielded(FifoPaymentTransactions::new(best.into_iter(), None)),
+            vec![(bob, 0), (alice, 0), (alice, 1)]
+        );
+    }
+
+    #[test]
+    fn test_fifo_caps_payments_per_sender() {
+        let start = Instant::now();
+        let alice = Address::repeat_byte(1);
+        let bob = Address::repeat_byte(2);
+
+        let best = (0..4)
+            .map(|nonce| pooled_tx(alice, nonce, true, start, nonce))
+            .chain([pooled_tx(bob, 0, true, start, 10)])
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            yielded(FifoPaymentTransactions::new(best.into_iter(), Some(2))),
+            vec![(alice, 0), (alice, 1), (bob, 0)]
+        );
+    }
+
+    #[test]
+    fn test_fifo_mark_invalid_skips_sequence() {
+        let start = Instant::now();
+        let alice = Address::repeat_byte(1);
+        let bob = Address::repeat_byte(2);
+
+        let best = MockBestTransactions::new(vec![
+            (pooled_tx(alice, 0, true, start, 0), 0),
+            (pooled_tx(alice, 1, true, start, 1), 0),
+            (pooled_tx(bob, 0, true, start, 2), 0),
+        ]);
+        let mut fifo = FifoPaymentTransactions::new(best, None);
+
+        let first = fifo.next().unwrap();
+        assert_eq!(first.sender(), alice);
+        fifo.mark_invalid(
+            &first,
+            &InvalidPoolTransactionError::ExceedsGasLimit(first.gas_limit(), 0),
+        );
+
+        assert_eq!(yielded(fifo), vec![(bob, 0)]);
+    }
+
+    #[test]
+    fn test_fifo_pulls_transactions_lazily() {
+        let start = Instant::now();
+        let pulled = std::cell::Cell::new(0);
+
+        let best = (0..100)
+            .map(|index| pooled_tx(Address::with_last_byte(index), 0, true, start, 0))
+            .inspect(|_| pulled.set(pulled.get() + 1));
+        let mut fifo = FifoPaymentTransactions::new(best, None).with_window(10);
+        assert_eq!(pulled.get(), 0);
+
+        // Only the yielded transactions and the remaining window are pulled.
+        for _ in 0..5 {
+            fifo.next().unwrap();
+        }
+        assert_eq!(pulled.get(), 5 + 9);
+        assert_eq!(fifo.count(), 95);
+    }
+
+    #[test]
+    fn test_fifo_orders_within_window() {
+        let start = Instant::now();
+        let (alice, bob, carol) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+
+        // Carol's payment arrived first but is out of the window until bob's is yielded.
+        let best = vec![
+            pooled_tx(alice, 0, true, start, 20),
+            pooled_tx(bob, 0, true, start, 10),
+            pooled_tx(carol, 0, true, start, 0),
+            pooled_tx(alice, 1, true, start, 30),
+        ];
+
+        assert_eq!(
+            yielded(FifoPaymentTransactions::new(best.into_iter(), None).with_window(2)),
+            vec![(bob, 0), (carol, 0), (alice, 0), (alice, 1)]
+        );
+    }
+
+    #[test]
+    fn test_fifo_skips_pending_transactions_of_invalid_sequence() {
+        let start = Instant::now();
+        let alice = Address::repeat_byte(1);
+        let bob = Address::repeat_byte(2);
+
+        // Alice's second transaction is only pulled after her first one was marked invalid.
+        let best = MockBestTransactions::new(vec![
+            (pooled_tx(alice, 0, true, start, 0), 0),
+            (pooled_tx(bob, 0, true, start, 1), 0),
+            (pooled_tx(alice, 1, true, start, 2), 0),
+        ]);
+        let mut fifo = FifoPaymentTransactions::new(best, None).with_window(1);
+
+        let first = fifo.next().unwrap();
+        assert_eq!(first.sender(), alice);
+        fifo.mark_invalid(
+            &first,
+            &InvalidPoolTransactionError::ExceedsGasLimit(first.gas_limit(), 0),
+        );
+
+        assert_eq!(yielded(fifo), vec![(bob, 0)]);
+    }
+
+    #[test]
+    fn test_payment_ordering_from_str() {
+        assert_eq!(
+            "tip".parse::<PaymentOrdering>().unwrap(),
+            PaymentOrdering::Tip
+        );
+        assert_eq!(
+            "fifo".parse::<PaymentOrdering>().unwrap(),
+            PaymentOrdering::Fifo
+        );
+        assert!("fair".parse::<PaymentOrdering>().is_err());
+    }
 }
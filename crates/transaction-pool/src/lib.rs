@@ -18,5 +18,6 @@ pub mod maintain;
 pub mod metrics;
 pub mod tt_2d_pool;
 
+pub use best::{PaymentLaneConfig, PaymentOrdering};
 pub use metrics::AA2dPoolMetrics;
 pub use tt_2d_pool::{AA2dPool, AA2dPoolConfig};
@@ -6,13 +6,13 @@ use crate::{
     amm::AmmLiquidityCache, best::MergeBestTransactions, transaction::TempoPooledTransaction,
     tt_2d_pool::AA2dPool, validator::TempoTransactionValidator,
 };
-use alloy_consensus::Transaction;
+use alloy_consensus::{BlockHeader, Transaction};
 use alloy_primitives::{Address, B256, map::HashMap};
 use parking_lot::RwLock;
-use reth_chainspec::ChainSpecProvider;
+use reth_chainspec::{ChainSpecProvider, EthChainSpec};
 use reth_eth_wire_types::HandleMempoolData;
 use reth_primitives_traits::Block;
-use reth_provider::{ChangedAccount, StateProviderFactory};
+use reth_provider::{ChangedAccount, HeaderProvider, StateProviderFactory};
 use reth_transaction_pool::{
     AddedTransactionOutcome, AllPoolTransactions, BestTransactions, BestTransactionsAttributes,
     BlockInfo, CanonicalStateUpdate, CoinbaseTipOrdering, GetPooledTransactionLimit,
@@ -25,8 +25,14 @@ use reth_transaction_pool::{
     identifier::TransactionId,
 };
 use revm::database::BundleAccount;
-use std::{collections::HashSet, sync::Arc, time::Instant};
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 use tempo_chainspec::TempoChainSpec;
+use tempo_primitives::TempoHeader;
+use tracing::warn;
 
 /// Tempo transaction pool that routes based on nonce_key
 pub struct TempoTransactionPool<Client> {
@@ -688,16 +694,50 @@ where
 
 impl<Client> TransactionPoolExt for TempoTransactionPool<Client>
 where
-    Client: StateProviderFactory + ChainSpecProvider<ChainSpec = TempoChainSpec> + 'static,
+    Client: StateProviderFactory
+        + HeaderProvider<Header = TempoHeader>
+        + ChainSpecProvider<ChainSpec = TempoChainSpec>
+        + 'static,
 {
     fn set_block_info(&self, info: BlockInfo) {
         self.protocol_pool.set_block_info(info)
     }
 
-    fn on_canonical_state_change<B>(&self, update: CanonicalStateUpdate<'_, B>)
+    fn on_canonical_state_change<B>(&self, mut update: CanonicalStateUpdate<'_, B>)
     where
         B: Block,
     {
+        // The pending base fee is that of the general lane. Lower it to the payment lane base fee
+        // if that is cheaper, so that payment transactions only paying for the payment lane are
+        // still pending.
+        //
+        // The update is generic over the block type, so the Tempo header of the tip is read back
+        // through the client.
+        match self.client().sealed_header_by_hash(update.new_tip.hash()) {
+            Ok(Some(tip)) => {
+                // The next block is built at the current time, but never before the tip
+                let next_timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |now| now.as_secs())
+                    .max(tip.timestamp());
+                let chain_spec = self.client().chain_spec();
+                if let Some(base_fee) = chain_spec.next_block_base_fee(tip.header(), next_timestamp)
+                {
+                    update.pending_block_base_fee = base_fee;
+                }
+                if let Some(payment_base_fee) =
+                    chain_spec.next_block_payment_base_fee(tip.header(), next_timestamp)
+                {
+                    update.pending_block_base_fee =
+                        update.pending_block_base_fee.min(payment_base_fee);
+                }
+            }
+            Ok(None) => {}
+            Err(err) => {
+                warn!(target: "txpool", ?err, "Failed to read the tip header");
+            }
+        }
+
         self.protocol_pool.on_canonical_state_change(update)
     }
 
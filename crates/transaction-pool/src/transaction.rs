@@ -174,6 +174,21 @@ pub enum TempoPoolTransactionError {
     )]
     Keychain(&'static str),
 
+    #[error("Multisig signature validation failed: {0}")]
+    Multisig(&'static str),
+
+    /// Thrown if a transaction carries an Ed25519 signature or key before Allegro-Moderato.
+    #[error("Ed25519 signatures are only supported after Allegro-Moderato")]
+    Ed25519BeforeAllegroModerato,
+
+    /// Thrown if a transaction carries an EIP-712 signature before Allegro-Moderato.
+    #[error("EIP-712 signatures are only supported after Allegro-Moderato")]
+    Eip712BeforeAllegroModerato,
+
+    /// Thrown if an encrypted transaction is added to the pool before Allegro-Moderato.
+    #[error("Encrypted transactions are only supported after Allegro-Moderato")]
+    EncryptedBeforeAllegroModerato,
+
     #[error(
         "Native transfers are not supported, if you were trying to transfer a stablecoin, please call TIP20::Transfer"
     )]
@@ -208,6 +223,10 @@ impl PoolTransactionError for TempoPoolTransactionError {
             | Self::InvalidValidBefore { .. }
             | Self::InvalidValidAfter { .. }
             | Self::Keychain(_)
+            | Self::Multisig(_)
+            | Self::Ed25519BeforeAllegroModerato
+            | Self::Eip712BeforeAllegroModerato
+            | Self::EncryptedBeforeAllegroModerato
             | Self::InsufficientLiquidity(_) => false,
             Self::NonZeroValue | Self::SubblockNonceKey => true,
         }
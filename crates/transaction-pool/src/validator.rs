@@ -166,6 +166,75 @@ where
         Ok(Ok(()))
     }
 
+    /// Check if a transaction's multisig signature satisfies the account's multisig config
+    ///
+    /// Mirrors the checks performed by the EVM handler: every co-signer must be registered for
+    /// the account in the AccountKeychain precompile and their number must reach the threshold.
+    fn validate_against_multisig(
+        &self,
+        transaction: &TempoPooledTransaction,
+        state_provider: &impl StateProvider,
+    ) -> Result<Result<(), &'static str>, ProviderError> {
+        let Some(tx) = transaction.inner().as_aa() else {
+            return Ok(Ok(()));
+        };
+
+        let Some(sig) = tx.signature().as_multisig() else {
+            return Ok(Ok(()));
+        };
+
+        let is_allegro_moderato = self
+            .inner
+            .chain_spec()
+            .is_allegro_moderato_active_at_timestamp(self.inner.fork_tracker().tip_timestamp());
+        if !is_allegro_moderato {
+            return Ok(Err(
+                "multisig signatures are only supported after Allegro-Moderato",
+            ));
+        }
+
+        // This should never fail because we set sender based on the sig.
+        if sig.account != transaction.sender() {
+            return Ok(Err("Multisig signature account does not match sender"));
+        }
+
+        // This should not happen because we validate the signature validity in `recover_signer`.
+        let Ok(signers) = sig.signers(&tx.signature_hash()) else {
+            return Ok(Err("Failed to recover co-signers from Multisig signature"));
+        };
+
+        let keychain = AccountKeychain::new();
+
+        let threshold_slot = keychain.multisig_thresholds.at(sig.account).slot();
+        let threshold: u8 = state_provider
+            .storage(ACCOUNT_KEYCHAIN_ADDRESS, threshold_slot.into())?
+            .unwrap_or_default()
+            .saturating_to();
+        if threshold == 0 {
+            return Ok(Err("account has no multisig config"));
+        }
+
+        for signer in signers {
+            let signer_slot = keychain
+                .is_multisig_signer
+                .at(sig.account)
+                .at(*signer)
+                .slot();
+            let is_signer = state_provider
+                .storage(ACCOUNT_KEYCHAIN_ADDRESS, signer_slot.into())?
+                .is_some_and(|value| !value.is_zero());
+            if !is_signer {
+                return Ok(Err("co-signer is not registered for the account"));
+            }
+        }
+
+        if signers.len() < threshold as usize {
+            return Ok(Err("not enough co-signers to reach the multisig threshold"));
+        }
+
+        Ok(Ok(()))
+    }
+
     /// Validates AA transaction time-bound conditionals
     fn ensure_valid_conditionals(
         &self,
@@ -217,6 +286,44 @@ where
             );
         }
 
+        // Reject Ed25519 and EIP-712 signatures until Allegro-Moderato is active
+        if let Some(tx) = transaction.inner().as_aa()
+            && !self
+                .inner
+                .chain_spec()
+                .is_allegro_moderato_active_at_timestamp(self.inner.fork_tracker().tip_timestamp())
+        {
+            let error = if tx.uses_ed25519() {
+                Some(TempoPoolTransactionError::Ed25519BeforeAllegroModerato)
+            } else if tx.uses_eip712() {
+                Some(TempoPoolTransactionError::Eip712BeforeAllegroModerato)
+            } else {
+                None
+            };
+
+            if let Some(error) = error {
+                return TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::other(error),
+                );
+            }
+        }
+
+        // Reject encrypted transactions until Allegro-Moderato is active
+        if transaction.inner().is_encrypted()
+            && !self
+                .inner
+                .chain_spec()
+                .is_allegro_moderato_active_at_timestamp(self.inner.fork_tracker().tip_timestamp())
+        {
+            return TransactionValidationOutcome::Invalid(
+                transaction,
+                InvalidPoolTransactionError::other(
+                    TempoPoolTransactionError::EncryptedBeforeAllegroModerato,
+                ),
+            );
+        }
+
         // Validate transactions that involve keychain keys
         match self.validate_against_keychain(&transaction, &state_provider) {
             Ok(Ok(())) => {}
@@ -231,6 +338,20 @@ where
             }
         }
 
+        // Validate transactions signed by a multisig
+        match self.validate_against_multisig(&transaction, &state_provider) {
+            Ok(Ok(())) => {}
+            Ok(Err(reason)) => {
+                return TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::other(TempoPoolTransactionError::Multisig(reason)),
+                );
+            }
+            Err(err) => {
+                return TransactionValidationOutcome::Error(*transaction.hash(), Box::new(err));
+            }
+        }
+
         // Balance transfer is not allowed as there is no balances in accounts yet.
         // Check added in https://github.com/tempoxyz/tempo/pull/759
         // AATx will aggregate all call values, so we dont need additional check for AA transactions.
@@ -542,6 +663,9 @@ mod tests {
                     }
                 }
                 TempoTxEnvelope::FeeToken(tx) => tx.tx_mut().value = value,
+                TempoTxEnvelope::Encrypted(_) => {
+                    panic!("encrypted transactions carry no value")
+                }
             }
         }
 
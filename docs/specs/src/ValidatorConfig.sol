@@ -21,6 +21,9 @@ contract ValidatorConfig is IValidatorConfig {
     /// @notice Mapping from validator address to validator info
     mapping(address => Validator) public validators;
 
+    /// @notice Mapping from validator address to its pending next public key
+    mapping(address => bytes32) internal nextPublicKeys;
+
     /// @notice Check if caller is the owner
     modifier onlyOwner() {
         if (msg.sender != owner) {
@@ -47,6 +50,11 @@ contract ValidatorConfig is IValidatorConfig {
         return result;
     }
 
+    /// @inheritdoc IValidatorConfig
+    function getNextPublicKey(address validator) external view returns (bytes32) {
+        return nextPublicKeys[validator];
+    }
+
     /// @inheritdoc IValidatorConfig
     function addValidator(
         address newValidatorAddress,
@@ -96,6 +104,16 @@ contract ValidatorConfig is IValidatorConfig {
         // Load old validator info
         Validator memory oldValidator = validators[msg.sender];
 
+        // Register a new public key as the next key; it takes effect at the next epoch boundary
+        bytes32 nextPublicKey = nextPublicKeys[msg.sender];
+        bytes32 currentPublicKey = oldValidator.publicKey;
+        if (publicKey == nextPublicKey) {
+            currentPublicKey = publicKey;
+            nextPublicKey = bytes32(0);
+        } else if (publicKey != currentPublicKey) {
+            nextPublicKey = publicKey;
+        }
+
         // Check if rotating to a new address
         if (newValidatorAddress != msg.sender) {
             // Check if new address already exists
@@ -108,6 +126,7 @@ contract ValidatorConfig is IValidatorConfig {
 
             // Clear the old validator
             delete validators[msg.sender];
+            delete nextPublicKeys[msg.sender];
         }
 
         // Validate addresses
@@ -115,8 +134,9 @@ contract ValidatorConfig is IValidatorConfig {
         _validateIpPort(outboundAddress, "outboundAddress");
 
         // Store updated validator
+        nextPublicKeys[newValidatorAddress] = nextPublicKey;
         validators[newValidatorAddress] = Validator({
-            publicKey: publicKey,
+            publicKey: currentPublicKey,
             active: oldValidator.active,
             index: oldValidator.index,
             validatorAddress: newValidatorAddress,
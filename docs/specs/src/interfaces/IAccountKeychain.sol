@@ -15,6 +15,11 @@ pragma solidity ^0.8.13;
  * This restriction is enforced by the protocol at transaction validation time.
  * Access Keys attempting to call these functions will fail with UnauthorizedCaller.
  *
+ * Accounts can register guardians that jointly recover the account by proposing a new
+ * Access Key. The key is authorized only after a 2 day timelock, during which any key of the
+ * account can veto the recovery with cancelRecovery. Before the recovery reaches the guardian
+ * threshold, `threshold` guardians can also cancel it with rejectRecovery.
+ *
  * This design is inspired by session key and access control patterns,
  * enshrined at the protocol level for better UX and reduced gas costs.
  */
@@ -28,7 +33,8 @@ interface IAccountKeychain {
     enum SignatureType {
         Secp256k1,
         P256,
-        WebAuthn
+        WebAuthn,
+        Ed25519
     }
 
     /// @notice Token spending limit structure
@@ -46,6 +52,27 @@ interface IAccountKeychain {
         bool isRevoked; // Whether this key has been revoked
     }
 
+    /// @notice Threshold multisig configuration structure
+    struct MultisigConfig {
+        uint8 threshold; // Number of co-signer signatures required (0 = not configured)
+        address[] signers; // Registered co-signer addresses
+    }
+
+    /// @notice Guardian recovery configuration structure
+    struct GuardianConfig {
+        uint8 threshold; // Number of guardian approvals required (0 = not configured)
+        address[] guardians; // Registered guardian addresses
+    }
+
+    /// @notice Pending guardian recovery structure
+    struct PendingRecovery {
+        address keyId; // Key authorized when the recovery is executed (address(0) = none pending)
+        SignatureType signatureType; // Signature type of the recovered key
+        uint64 expiry; // Unix timestamp when the recovered key expires
+        uint8 approvals; // Number of guardians that approved the recovery
+        uint64 executableAt; // Unix timestamp from which the recovery can be executed (0 = threshold not met)
+    }
+
     /*//////////////////////////////////////////////////////////////
                                 EVENTS
     //////////////////////////////////////////////////////////////*/
@@ -63,6 +90,37 @@ interface IAccountKeychain {
         address indexed account, bytes32 indexed publicKey, address indexed token, uint256 newLimit
     );
 
+    /// @notice Emitted when a multisig config is registered or replaced
+    event MultisigConfigured(address indexed account, uint8 threshold, address[] signers);
+
+    /// @notice Emitted when a multisig config is removed
+    event MultisigRemoved(address indexed account);
+
+    /// @notice Emitted when a guardian config is registered or replaced
+    event GuardiansConfigured(address indexed account, uint8 threshold, address[] guardians);
+
+    /// @notice Emitted when a guardian config is removed
+    event GuardiansRemoved(address indexed account);
+
+    /// @notice Emitted when a guardian proposes or approves a recovery key
+    event RecoveryProposed(
+        address indexed account, address indexed guardian, address indexed keyId, uint8 approvals
+    );
+
+    /// @notice Emitted when a recovery reaches the guardian threshold and its timelock starts
+    event RecoveryScheduled(address indexed account, address indexed keyId, uint64 executableAt);
+
+    /// @notice Emitted when a guardian rejects the pending recovery of an account
+    event RecoveryRejected(
+        address indexed account, address indexed guardian, address indexed keyId, uint8 rejections
+    );
+
+    /// @notice Emitted when a pending recovery is cancelled
+    event RecoveryCancelled(address indexed account, address indexed keyId);
+
+    /// @notice Emitted when a recovery is executed and its key authorized
+    event RecoveryExecuted(address indexed account, address indexed keyId);
+
     /*//////////////////////////////////////////////////////////////
                                 ERRORS
     //////////////////////////////////////////////////////////////*/
@@ -76,6 +134,19 @@ interface IAccountKeychain {
     error InvalidSignatureType();
     error ZeroPublicKey();
     error UnauthorizedCaller();
+    error InvalidMultisigThreshold();
+    error InvalidMultisigSigner();
+    error MultisigNotConfigured();
+    error MultisigThresholdNotMet(uint8 threshold, uint8 approvals);
+    error InvalidGuardianThreshold();
+    error InvalidGuardian();
+    error GuardiansNotConfigured();
+    error NoPendingRecovery();
+    error RecoveryAlreadyApproved();
+    error RecoveryAlreadyRejected();
+    error RecoveryAlreadyScheduled();
+    error RecoveryAlreadyPending(uint64 replaceableAt);
+    error RecoveryNotReady(uint64 executableAt);
 
     /*//////////////////////////////////////////////////////////////
                         MANAGEMENT FUNCTIONS
@@ -117,6 +188,78 @@ interface IAccountKeychain {
      */
     function updateSpendingLimit(address keyId, address token, uint256 newLimit) external;
 
+    /**
+     * @notice Register or replace the threshold multisig config for the caller's account
+     * @dev MUST only be called in transactions signed by the Root Key or by the account's multisig
+     *      Once registered, transactions carrying a Multisig signature with at least `threshold`
+     *      signatures from registered co-signers are executed on behalf of the account
+     * @param signers The co-signer addresses (at most 16, no duplicates, non-zero)
+     * @param threshold Number of co-signer signatures required (1 <= threshold <= signers.length)
+     */
+    function setMultisig(address[] calldata signers, uint8 threshold) external;
+
+    /**
+     * @notice Remove the threshold multisig config for the caller's account
+     * @dev MUST only be called in transactions signed by the Root Key or by the account's multisig
+     */
+    function removeMultisig() external;
+
+    /**
+     * @notice Register or replace the guardians of the caller's account
+     * @dev MUST only be called in transactions signed by the Root Key or by the account's multisig
+     *      Replacing the guardians cancels any pending recovery
+     * @param guardians The guardian addresses (at most 16, no duplicates, non-zero, not the account)
+     * @param threshold Number of guardian approvals required (1 <= threshold <= guardians.length)
+     */
+    function setGuardians(address[] calldata guardians, uint8 threshold) external;
+
+    /**
+     * @notice Remove the guardians of the caller's account, cancelling any pending recovery
+     * @dev MUST only be called in transactions signed by the Root Key or by the account's multisig
+     */
+    function removeGuardians() external;
+
+    /**
+     * @notice Propose or approve a recovery key for an account
+     * @dev MUST only be called by a guardian of the account. Proposing a different key is rejected
+     *      while the pending recovery is open: it can only be replaced, resetting its approvals,
+     *      once it went 2 days without reaching the threshold or its key expired, or once
+     *      `threshold` guardians rejected it with rejectRecovery.
+     *      Once `threshold` guardians approved, the recovery is executable after 2 days
+     * @param account The account to recover
+     * @param keyId The key ID to authorize once the recovery is executed
+     * @param signatureType Signature type of the key
+     * @param expiry Unix timestamp when the key expires
+     */
+    function proposeRecovery(
+        address account,
+        address keyId,
+        SignatureType signatureType,
+        uint64 expiry
+    ) external;
+
+    /**
+     * @notice Cancel the pending recovery of the caller's account
+     * @dev Can be called in transactions signed by any key of the account, including Access Keys
+     */
+    function cancelRecovery() external;
+
+    /**
+     * @notice Reject the pending recovery of an account
+     * @dev MUST only be called by a guardian of the account, before the recovery reached the
+     *      threshold. Once `threshold` guardians rejected it, the recovery is cancelled and a
+     *      different key can be proposed right away
+     * @param account The account whose pending recovery to reject
+     */
+    function rejectRecovery(address account) external;
+
+    /**
+     * @notice Execute a recovery whose timelock has elapsed
+     * @dev Can be called by anyone. The key is authorized without spending limits
+     * @param account The account to recover
+     */
+    function executeRecovery(address account) external;
+
     /*//////////////////////////////////////////////////////////////
                         VIEW FUNCTIONS
     //////////////////////////////////////////////////////////////*/
@@ -148,4 +291,25 @@ interface IAccountKeychain {
      */
     function getTransactionKey() external view returns (address);
 
+    /**
+     * @notice Get the threshold multisig config of an account
+     * @param account The account address
+     * @return The multisig config (threshold is 0 if none is registered)
+     */
+    function getMultisig(address account) external view returns (MultisigConfig memory);
+
+    /**
+     * @notice Get the guardian config of an account
+     * @param account The account address
+     * @return The guardian config (threshold is 0 if none is registered)
+     */
+    function getGuardians(address account) external view returns (GuardianConfig memory);
+
+    /**
+     * @notice Get the pending recovery of an account
+     * @param account The account address
+     * @return The pending recovery (keyId is address(0) if none is pending)
+     */
+    function getPendingRecovery(address account) external view returns (PendingRecovery memory);
+
 }
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.13;
+
+/// @title ISignatureVerifier - Signature Verifier Precompile Interface
+/// @notice Interface for verifying P256 and WebAuthn signatures from smart contracts
+/// @dev This precompile is deployed at 0x5160000000000000000000000000000000000000 from the
+///      Allegro-Moderato hardfork. It is stateless and applies the exact same validation rules
+///      as Tempo transaction signatures.
+interface ISignatureVerifier {
+
+    /// @notice Verify a P256 signature over a digest
+    /// @param digest The signed digest
+    /// @param r The signature r value
+    /// @param s The signature s value
+    /// @param pubKeyX The public key x coordinate
+    /// @param pubKeyY The public key y coordinate
+    /// @param preHash Whether the signer sha256-hashed the digest before signing (Web Crypto)
+    /// @return valid True if the signature is valid
+    function verifyP256(
+        bytes32 digest,
+        bytes32 r,
+        bytes32 s,
+        bytes32 pubKeyX,
+        bytes32 pubKeyY,
+        bool preHash
+    ) external view returns (bool valid);
+
+    /// @notice Verify a WebAuthn assertion
+    /// @dev The message hash is sha256(authenticatorData || sha256(clientDataJSON))
+    /// @param authenticatorData The authenticator data (at least 37 bytes, UP flag set, no AT flag, ED flag set if and only if extensions follow)
+    /// @param clientDataJSON The client data JSON (type "webauthn.get")
+    /// @param challenge The expected challenge, base64url encoded in clientDataJSON
+    /// @param r The signature r value
+    /// @param s The signature s value
+    /// @param pubKeyX The public key x coordinate
+    /// @param pubKeyY The public key y coordinate
+    /// @return valid True if the assertion is valid
+    function verifyWebAuthn(
+        bytes calldata authenticatorData,
+        bytes calldata clientDataJSON,
+        bytes32 challenge,
+        bytes32 r,
+        bytes32 s,
+        bytes32 pubKeyX,
+        bytes32 pubKeyY
+    ) external view returns (bool valid);
+
+}
@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.13;
+
+/// @title ITransactionContext - Transaction Context Precompile Interface
+/// @notice Interface for reading the fee and signing context of the transaction being executed
+/// @dev This precompile is deployed at 0x7C00000000000000000000000000000000000000 from the
+///      Allegro-Moderato hardfork. All values are set by the protocol before the transaction
+///      executes and only live in transient storage for the duration of the transaction.
+interface ITransactionContext {
+
+    enum SignatureType {
+        Secp256k1,
+        P256,
+        WebAuthn,
+        Ed25519,
+        Multisig
+    }
+
+    /// @notice Transaction context structure
+    /// @dev `validAfter` and `validBefore` are 0 if the transaction does not set them
+    struct Context {
+        address feeToken;
+        address feePayer;
+        address keyId;
+        SignatureType signatureType;
+        bool isAccessKey;
+        uint64 validAfter;
+        uint64 validBefore;
+        uint256 nonceKey;
+        uint64 callIndex;
+    }
+
+    /// @notice Get the full context of the current transaction
+    /// @return context The transaction context
+    function getContext() external view returns (Context memory context);
+
+    /// @notice Get the fee token the current transaction pays its fees in
+    /// @return token The resolved fee token
+    function feeToken() external view returns (address token);
+
+    /// @notice Get the account paying the fees of the current transaction
+    /// @return payer The sender, or the sponsor of a sponsored transaction
+    function feePayer() external view returns (address payer);
+
+    /// @notice Get the key that signed the current transaction
+    /// @return keyId The access key, or the sender if the transaction was signed by its root key
+    /// @return signatureType The signature type of the key
+    /// @return isAccessKey Whether the transaction was signed by a keychain access key
+    function signingKey()
+        external
+        view
+        returns (address keyId, SignatureType signatureType, bool isAccessKey);
+
+    /// @notice Get the index of the currently executing call within the transaction's batch
+    /// @return index The call index, 0 for transactions without a batch
+    function callIndex() external view returns (uint64 index);
+
+}
@@ -64,6 +64,9 @@ interface IValidatorConfig {
     ) external;
 
     /// @notice Update validator information (only validator)
+    /// @dev Post-Allegro-Moderato, a `publicKey` different from the current one is registered as
+    ///      the validator's next public key instead of replacing it. Passing the pending key
+    ///      again promotes it to the current key.
     /// @param newValidatorAddress The new address for this validator
     /// @param publicKey The validator's new communication public key
     /// @param inboundAddress The validator's inbound address `<hostname|ip>:<port>` for incoming connections
@@ -75,6 +78,13 @@ interface IValidatorConfig {
         string calldata outboundAddress
     ) external;
 
+    /// @notice Get the next public key of a validator (post-Allegro-Moderato)
+    /// @dev The next key takes effect at the following epoch boundary, where it is dealt a share
+    ///      in the DKG ceremony in place of the current key.
+    /// @param validator The validator address
+    /// @return nextPublicKey The pending public key, or zero if there is none
+    function getNextPublicKey(address validator) external view returns (bytes32 nextPublicKey);
+
     /// @notice Change validator active status (owner only)
     /// @param validator The validator address
     /// @param active Whether the validator should be active
@@ -168,7 +168,12 @@ contract ValidatorConfigTest is BaseTest {
         IValidatorConfig.Validator[] memory validators = validatorConfig.getValidators();
         assertEq(validators.length, 1, "Should still have 1 validator");
         assertEq(validators[0].validatorAddress, validator1);
-        assertEq(validators[0].publicKey, publicKey2, "Public key should be updated");
+        assertEq(validators[0].publicKey, publicKey1, "Public key should not change yet");
+        assertEq(
+            validatorConfig.getNextPublicKey(validator1),
+            publicKey2,
+            "New public key should be registered as the next key"
+        );
         assertEq(validators[0].inboundAddress, inboundAddr2, "Inbound address should be updated");
         assertEq(validators[0].outboundAddress, outboundAddr2, "Outbound address should be updated");
         assertTrue(validators[0].active, "Active status should remain unchanged");
@@ -185,8 +190,28 @@ contract ValidatorConfigTest is BaseTest {
         IValidatorConfig.Validator[] memory validators = validatorConfig.getValidators();
         assertEq(validators.length, 1, "Should still have 1 validator");
         assertEq(validators[0].validatorAddress, validator2, "Validator address should be rotated");
-        assertEq(validators[0].publicKey, publicKey2);
+        assertEq(validators[0].publicKey, publicKey1);
         assertEq(validators[0].index, 0, "Index should remain 0");
+        assertEq(validatorConfig.getNextPublicKey(validator1), bytes32(0));
+        assertEq(validatorConfig.getNextPublicKey(validator2), publicKey2);
+    }
+
+    function test_UpdateValidator_PromoteNextPublicKey() public {
+        validatorConfig.addValidator(validator1, publicKey1, true, inboundAddr1, outboundAddr1);
+
+        // Updating with the current key keeps a pending next key
+        vm.startPrank(validator1);
+        validatorConfig.updateValidator(validator1, publicKey2, inboundAddr1, outboundAddr1);
+        validatorConfig.updateValidator(validator1, publicKey1, inboundAddr2, outboundAddr2);
+        assertEq(validatorConfig.getNextPublicKey(validator1), publicKey2);
+
+        // Updating with the next key promotes it
+        validatorConfig.updateValidator(validator1, publicKey2, inboundAddr2, outboundAddr2);
+        vm.stopPrank();
+
+        IValidatorConfig.Validator[] memory validators = validatorConfig.getValidators();
+        assertEq(validators[0].publicKey, publicKey2);
+        assertEq(validatorConfig.getNextPublicKey(validator1), bytes32(0));
     }
 
     function test_UpdateValidator_RotateToExistingAddress() public {
@@ -425,8 +450,9 @@ contract ValidatorConfigTest is BaseTest {
 
         // First slot should now be validator3
         assertEq(validators[0].validatorAddress, validator3);
-        assertEq(validators[0].publicKey, publicKey3);
+        assertEq(validators[0].publicKey, publicKey1);
         assertEq(validators[0].index, 0);
+        assertEq(validatorConfig.getNextPublicKey(validator3), publicKey3);
 
         // Second slot should still be validator2
         assertEq(validators[1].validatorAddress, validator2);
@@ -486,7 +512,8 @@ contract ValidatorConfigTest is BaseTest {
 
         IValidatorConfig.Validator[] memory validators = validatorConfig.getValidators();
         assertEq(validators.length, 1);
-        assertEq(validators[0].publicKey, bytes32(uint256(0x5004)));
+        assertEq(validators[0].publicKey, publicKey1);
+        assertEq(validatorConfig.getNextPublicKey(validator1), bytes32(uint256(0x5004)));
         assertEq(validators[0].inboundAddress, "10.0.0.5:8000");
     }
 
@@ -0,0 +1,112 @@
+//! Commands to move consensus keys in and out of passphrase-protected keystores.
+use std::path::PathBuf;
+
+use eyre::WrapErr as _;
+use tempo_commonware_node_config::{Keystore, Passphrase, SigningKey, SigningShare};
+
+/// The kind of key stored in a file.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub(crate) enum Kind {
+    /// An ed25519 signing key.
+    SigningKey,
+    /// A bls12381 signing share.
+    SigningShare,
+}
+
+/// Encrypts a hex-encoded signing key or share into a keystore.
+#[derive(Debug, clap::Args)]
+pub(crate) struct EncryptKey {
+    /// The file holding the hex-encoded key.
+    #[arg(long, short, value_name = "FILE")]
+    input: PathBuf,
+    /// Destination of the keystore.
+    #[arg(long, short, value_name = "FILE")]
+    output: PathBuf,
+    /// The kind of key stored in the input file.
+    #[arg(long, value_enum)]
+    kind: Kind,
+    /// The file holding the passphrase to encrypt the key with.
+    #[arg(long, value_name = "FILE")]
+    passphrase_file: PathBuf,
+}
+
+impl EncryptKey {
+    pub(crate) fn run(self) -> eyre::Result<()> {
+        let Self {
+            input,
+            output,
+            kind,
+            passphrase_file,
+        } = self;
+        let passphrase = read_passphrase(&passphrase_file)?;
+        let keystore = match kind {
+            Kind::SigningKey => SigningKey::read_from_file(&input)
+                .wrap_err_with(|| format!("failed reading signing key from `{}`", input.display()))?
+                .to_keystore(&passphrase)
+                .wrap_err("failed encrypting signing key")?,
+            Kind::SigningShare => SigningShare::read_from_file(&input)
+                .wrap_err_with(|| {
+                    format!("failed reading signing share from `{}`", input.display())
+                })?
+                .to_keystore(&passphrase)
+                .wrap_err("failed encrypting signing share")?,
+        };
+        keystore
+            .write_to_file(&output)
+            .wrap_err_with(|| format!("failed writing keystore to `{}`", output.display()))?;
+        println!("wrote keystore to: {}", output.display());
+        Ok(())
+    }
+}
+
+/// Decrypts a keystore back into a hex-encoded signing key or share.
+#[derive(Debug, clap::Args)]
+pub(crate) struct DecryptKey {
+    /// The keystore file.
+    #[arg(long, short, value_name = "FILE")]
+    input: PathBuf,
+    /// Destination of the hex-encoded key.
+    #[arg(long, short, value_name = "FILE")]
+    output: PathBuf,
+    /// The kind of key stored in the keystore.
+    #[arg(long, value_enum)]
+    kind: Kind,
+    /// The file holding the passphrase the keystore is encrypted with.
+    #[arg(long, value_name = "FILE")]
+    passphrase_file: PathBuf,
+}
+
+impl DecryptKey {
+    pub(crate) fn run(self) -> eyre::Result<()> {
+        let Self {
+            input,
+            output,
+            kind,
+            passphrase_file,
+        } = self;
+        let passphrase = read_passphrase(&passphrase_file)?;
+        let keystore = Keystore::read_from_file(&input)
+            .wrap_err_with(|| format!("failed reading keystore from `{}`", input.display()))?;
+        match kind {
+            Kind::SigningKey => SigningKey::try_from_keystore(&keystore, &passphrase)
+                .wrap_err("failed decrypting signing key")?
+                .write_to_file(&output)
+                .wrap_err_with(|| {
+                    format!("failed writing signing key to `{}`", output.display())
+                })?,
+            Kind::SigningShare => SigningShare::try_from_keystore(&keystore, &passphrase)
+                .wrap_err("failed decrypting signing share")?
+                .write_to_file(&output)
+                .wrap_err_with(|| {
+                    format!("failed writing signing share to `{}`", output.display())
+                })?,
+        }
+        println!("wrote decrypted key to: {}", output.display());
+        Ok(())
+    }
+}
+
+pub(crate) fn read_passphrase(path: &std::path::Path) -> eyre::Result<Passphrase> {
+    Passphrase::read_from_file(path)
+        .wrap_err_with(|| format!("failed reading passphrase from `{}`", path.display()))
+}
@@ -2,8 +2,10 @@
 use std::{net::SocketAddr, path::PathBuf};
 
 use crate::{
-    generate_devnet::GenerateDevnet, generate_genesis::GenerateGenesis,
+    generate_devnet::GenerateDevnet,
+    generate_genesis::GenerateGenesis,
     generate_localnet::GenerateLocalnet,
+    keystore::{DecryptKey, EncryptKey},
 };
 
 use alloy::signers::{local::MnemonicBuilder, utils::secret_key_to_address};
@@ -18,6 +20,7 @@ mod generate_devnet;
 mod generate_genesis;
 mod generate_localnet;
 mod genesis_args;
+mod keystore;
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
@@ -34,6 +37,8 @@ async fn main() -> eyre::Result<()> {
             .wrap_err("failed to generate localnet configs"),
         Action::GenerateAddPeer(cfg) => generate_config_to_add_peer(cfg),
         Action::GenerateSigningKey(args) => args.run(),
+        Action::EncryptKey(args) => args.run().wrap_err("failed to encrypt key"),
+        Action::DecryptKey(args) => args.run().wrap_err("failed to decrypt key"),
     }
 }
 
@@ -58,6 +63,8 @@ enum Action {
     GenerateLocalnet(GenerateLocalnet),
     GenerateAddPeer(GenerateAddPeer),
     GenerateSigningKey(GenerateSigningKey),
+    EncryptKey(EncryptKey),
+    DecryptKey(DecryptKey),
 }
 
 /// Generates an ed25519 signing key pair to be used in consensus.
@@ -71,18 +78,35 @@ struct GenerateSigningKey {
     /// Use this only in environments that require reproducible keys.
     #[arg(long, value_name = "NUMBER")]
     seed: Option<u64>,
+    /// Optional file holding a passphrase. If set, the signing key is written
+    /// as an encrypted keystore instead of in plaintext.
+    #[arg(long, value_name = "FILE")]
+    passphrase_file: Option<PathBuf>,
 }
 
 impl GenerateSigningKey {
     fn run(self) -> eyre::Result<()> {
-        let Self { output, seed } = self;
+        let Self {
+            output,
+            seed,
+            passphrase_file,
+        } = self;
         let mut rng = rand::rngs::StdRng::seed_from_u64(seed.unwrap_or_else(rand::random::<u64>));
         let signing_key = PrivateKey::from_rng(&mut rng);
         let validating_key = signing_key.public_key();
         let signing_key = SigningKey::from(signing_key);
-        signing_key
-            .write_to_file(&output)
-            .wrap_err_with(|| format!("failed writing signing key to `{}`", output.display()))?;
+        if let Some(passphrase_file) = passphrase_file {
+            let passphrase = keystore::read_passphrase(&passphrase_file)?;
+            signing_key
+                .to_keystore(&passphrase)
+                .wrap_err("failed encrypting signing key")?
+                .write_to_file(&output)
+                .wrap_err_with(|| format!("failed writing keystore to `{}`", output.display()))?;
+        } else {
+            signing_key.write_to_file(&output).wrap_err_with(|| {
+                format!("failed writing signing key to `{}`", output.display())
+            })?;
+        }
         println!(
             "wrote signing key to: {}\nvalidating/public key: {validating_key}",
             output.display()